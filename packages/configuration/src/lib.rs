@@ -7,7 +7,6 @@
 pub mod v2_0_0;
 pub mod validator;
 
-use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
 use std::time::Duration;
@@ -38,14 +37,21 @@ pub const ENV_VAR_CONFIG_TOML_PATH: &str = "TORRUST_TRACKER_CONFIG_TOML_PATH";
 pub type Configuration = v2_0_0::Configuration;
 pub type Core = v2_0_0::core::Core;
 pub type HealthCheckApi = v2_0_0::health_check_api::HealthCheckApi;
+pub type FullScrape = v2_0_0::full_scrape::FullScrape;
+pub type GeoIp = v2_0_0::geoip::GeoIp;
 pub type HttpApi = v2_0_0::tracker_api::HttpApi;
 pub type HttpTracker = v2_0_0::http_tracker::HttpTracker;
+pub type MetricsHistory = v2_0_0::metrics_history::MetricsHistory;
 pub type UdpTracker = v2_0_0::udp_tracker::UdpTracker;
+pub type Webhooks = v2_0_0::webhooks::Webhooks;
+pub type WebhookTarget = v2_0_0::webhooks::WebhookTarget;
 pub type Database = v2_0_0::database::Database;
 pub type Driver = v2_0_0::database::Driver;
 pub type Threshold = v2_0_0::logging::Threshold;
 
-pub type AccessTokens = HashMap<String, String>;
+pub type AccessTokens = v2_0_0::tracker_api::AccessTokens;
+pub type AccessToken = v2_0_0::tracker_api::AccessToken;
+pub type Scope = v2_0_0::tracker_api::Scope;
 
 pub const LATEST_VERSION: &str = "2.0.0";
 
@@ -184,6 +190,299 @@ impl TrackerPolicy {
     }
 }
 
+/// Memory limits for the in-memory torrent repository.
+///
+/// All limits are disabled by default. When a limit is reached, the
+/// configured eviction policy decides whether the new torrent or peer is
+/// refused or whether an existing one is evicted to make room for it.
+///
+/// Limits are soft: under heavy concurrency the repository could temporarily
+/// hold a few more entries than the configured maximum.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct MemoryLimits {
+    /// Maximum number of torrents the tracker keeps in memory.
+    #[serde(default = "MemoryLimits::default_max_torrents")]
+    pub max_torrents: Option<usize>,
+
+    /// Maximum number of peers in a single torrent swarm.
+    #[serde(default = "MemoryLimits::default_max_peers_per_torrent")]
+    pub max_peers_per_torrent: Option<usize>,
+
+    /// Maximum number of peers for all torrents.
+    #[serde(default = "MemoryLimits::default_max_peers")]
+    pub max_peers: Option<usize>,
+
+    /// What to do with a new torrent when `max_torrents` has been reached.
+    #[serde(default = "MemoryLimits::default_torrent_eviction_policy")]
+    pub torrent_eviction_policy: TorrentEvictionPolicy,
+
+    /// What to do with a new peer when `max_peers_per_torrent` or
+    /// `max_peers` has been reached.
+    #[serde(default = "MemoryLimits::default_peer_eviction_policy")]
+    pub peer_eviction_policy: PeerEvictionPolicy,
+
+    /// Maximum number of peers with the same IP address in a single torrent
+    /// swarm.
+    ///
+    /// It prevents a single host from flooding a swarm with fake peers. IPv6
+    /// addresses in the same `ipv6_prefix_length` network count as the same
+    /// address.
+    #[serde(default = "MemoryLimits::default_max_peers_per_ip")]
+    pub max_peers_per_ip: Option<usize>,
+
+    /// Length (in bits) of the IPv6 prefix that identifies a single host for
+    /// the `max_peers_per_ip` limit.
+    #[serde(default = "MemoryLimits::default_ipv6_prefix_length")]
+    pub ipv6_prefix_length: u8,
+
+    /// What to do with a new peer when `max_peers_per_ip` has been reached.
+    #[serde(default = "MemoryLimits::default_peers_per_ip_policy")]
+    pub peers_per_ip_policy: PeersPerIpPolicy,
+}
+
+impl Default for MemoryLimits {
+    fn default() -> Self {
+        Self {
+            max_torrents: Self::default_max_torrents(),
+            max_peers_per_torrent: Self::default_max_peers_per_torrent(),
+            max_peers: Self::default_max_peers(),
+            torrent_eviction_policy: Self::default_torrent_eviction_policy(),
+            peer_eviction_policy: Self::default_peer_eviction_policy(),
+            max_peers_per_ip: Self::default_max_peers_per_ip(),
+            ipv6_prefix_length: Self::default_ipv6_prefix_length(),
+            peers_per_ip_policy: Self::default_peers_per_ip_policy(),
+        }
+    }
+}
+
+impl MemoryLimits {
+    #[allow(clippy::unnecessary_wraps)]
+    fn default_max_torrents() -> Option<usize> {
+        None
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn default_max_peers_per_torrent() -> Option<usize> {
+        None
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn default_max_peers() -> Option<usize> {
+        None
+    }
+
+    fn default_torrent_eviction_policy() -> TorrentEvictionPolicy {
+        TorrentEvictionPolicy::default()
+    }
+
+    fn default_peer_eviction_policy() -> PeerEvictionPolicy {
+        PeerEvictionPolicy::default()
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn default_max_peers_per_ip() -> Option<usize> {
+        None
+    }
+
+    fn default_ipv6_prefix_length() -> u8 {
+        64
+    }
+
+    fn default_peers_per_ip_policy() -> PeersPerIpPolicy {
+        PeersPerIpPolicy::default()
+    }
+}
+
+/// Policy applied when a new torrent would exceed the `max_torrents` limit.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TorrentEvictionPolicy {
+    /// The new torrent is not added to the repository.
+    #[default]
+    RefuseNew,
+
+    /// The torrent with the oldest peer activity (from a small sample of
+    /// torrents) is removed to make room for the new one.
+    EvictLeastRecentlyActive,
+}
+
+/// Policy applied when a new peer would exceed the `max_peers_per_torrent`
+/// or `max_peers` limits.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerEvictionPolicy {
+    /// The new peer is not added to the swarm.
+    #[default]
+    RefuseNew,
+
+    /// The peer in the same swarm that has not announced for the longest
+    /// time is removed to make room for the new one.
+    DropOldest,
+}
+
+/// Policy applied when a new peer would exceed the `max_peers_per_ip` limit.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PeersPerIpPolicy {
+    /// The announce request is rejected.
+    #[default]
+    Reject,
+
+    /// The peer with the same IP address that has not announced for the
+    /// longest time is removed to make room for the new one.
+    DropOldest,
+}
+
+/// Policy applied to the peer addresses that are not useful, or not safe, to
+/// share with every peer in the swarm.
+///
+/// The policy is applied to the peer address stored by the tracker, after
+/// replacing the loopback addresses with the `external_ip`.
+///
+/// - Bogon addresses are not routable on the Internet: unspecified,
+///   link-local, documentation, benchmarking, multicast and reserved ranges.
+/// - Private addresses are only reachable from the same network: RFC 1918,
+///   shared address space (RFC 6598), unique local (RFC 4193) and loopback
+///   ranges.
+/// - Privileged ports are the ports below 1024.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct PeerAddressPolicy {
+    /// Action for the peers with a bogon address.
+    #[serde(default = "PeerAddressPolicy::default_bogon_peers")]
+    pub bogon_peers: PeerAddressAction,
+
+    /// Action for the peers with a private address.
+    #[serde(default = "PeerAddressPolicy::default_private_peers")]
+    pub private_peers: PrivatePeerAddressAction,
+
+    /// Action for the peers announcing the port 0.
+    #[serde(default = "PeerAddressPolicy::default_port_zero_peers")]
+    pub port_zero_peers: PeerAddressAction,
+
+    /// Action for the peers announcing a privileged port.
+    #[serde(default = "PeerAddressPolicy::default_privileged_port_peers")]
+    pub privileged_port_peers: PeerAddressAction,
+}
+
+impl Default for PeerAddressPolicy {
+    fn default() -> Self {
+        Self {
+            bogon_peers: Self::default_bogon_peers(),
+            private_peers: Self::default_private_peers(),
+            port_zero_peers: Self::default_port_zero_peers(),
+            privileged_port_peers: Self::default_privileged_port_peers(),
+        }
+    }
+}
+
+impl PeerAddressPolicy {
+    fn default_bogon_peers() -> PeerAddressAction {
+        PeerAddressAction::Hide
+    }
+
+    fn default_private_peers() -> PrivatePeerAddressAction {
+        PrivatePeerAddressAction::SameNetwork
+    }
+
+    fn default_port_zero_peers() -> PeerAddressAction {
+        PeerAddressAction::Hide
+    }
+
+    fn default_privileged_port_peers() -> PeerAddressAction {
+        PeerAddressAction::Keep
+    }
+}
+
+/// What the tracker does with a peer whose address matches a rule of the
+/// [`PeerAddressPolicy`].
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerAddressAction {
+    /// The peer is shared with the other peers, like any other peer.
+    Keep,
+
+    /// The peer is added to the swarm, and counted in the swarm statistics,
+    /// but it's never returned to the other peers.
+    Hide,
+
+    /// The announce request is rejected.
+    Reject,
+}
+
+/// What the tracker does with a peer whose address is in a private range.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivatePeerAddressAction {
+    /// The peer is shared with the other peers, like any other peer.
+    Keep,
+
+    /// The peer is only returned to the peers in the same private range, for
+    /// example, a `192.168.1.10` peer is only returned to the peers in
+    /// `192.168.0.0/16`.
+    SameNetwork,
+
+    /// The peer is added to the swarm, and counted in the swarm statistics,
+    /// but it's never returned to the other peers.
+    Hide,
+
+    /// The announce request is rejected.
+    Reject,
+}
+
+/// Strategy to choose the peers returned in the announce responses, when
+/// the swarm has more peers than the response can hold.
+///
+/// ```toml
+/// [core.peer_selection]
+/// strategy = "locality"
+/// local_peers_percentage = 50
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct PeerSelection {
+    /// How the peers are chosen.
+    #[serde(default = "PeerSelection::default_strategy")]
+    pub strategy: PeerSelectionStrategy,
+
+    /// Percentage of the response filled with nearby peers when the
+    /// strategy is [`Locality`](PeerSelectionStrategy::Locality). From 0 to
+    /// 100.
+    #[serde(default = "PeerSelection::default_local_peers_percentage")]
+    pub local_peers_percentage: u8,
+}
+
+impl Default for PeerSelection {
+    fn default() -> Self {
+        Self {
+            strategy: Self::default_strategy(),
+            local_peers_percentage: Self::default_local_peers_percentage(),
+        }
+    }
+}
+
+impl PeerSelection {
+    fn default_strategy() -> PeerSelectionStrategy {
+        PeerSelectionStrategy::Ordered
+    }
+
+    fn default_local_peers_percentage() -> u8 {
+        50
+    }
+}
+
+/// How the peers returned in the announce responses are chosen.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerSelectionStrategy {
+    /// The first peers ordered by peer ID.
+    Ordered,
+
+    /// A part of the response is filled with peers near the client, and the
+    /// rest with random peers. The peers are near when they are in the same
+    /// `/24` IPv4 network, the same `/48` IPv6 network, or the same
+    /// autonomous system if the `GeoIP` database has the ASNs.
+    Locality,
+}
+
 /// Information required for loading config
 #[derive(Debug, Default, Clone)]
 pub struct Info {
@@ -225,6 +524,97 @@ impl Info {
     }
 }
 
+/// Adaptive announce interval.
+///
+/// When it's enabled, the interval of each announce response is computed
+/// from the current tracker load and the size of the swarm, within the
+/// `min_interval` and `max_interval` bounds:
+///
+/// - Swarms with fewer than `small_swarm_peers` peers get a shorter interval,
+///   down to `min_interval` for an empty swarm, because fresh peer lists
+///   matter most in tiny swarms.
+/// - The interval grows towards `max_interval` with the tracker load, which
+///   is the highest of the announce request rate (relative to
+///   `high_announce_rate`) and the fraction of the memory limits in use.
+///
+/// The [`AnnouncePolicy`] `interval` is the interval for an idle tracker and
+/// a swarm with at least `small_swarm_peers` peers. The `interval_min` sent to
+/// the clients is never longer than the computed interval.
+///
+/// ```toml
+/// [core.adaptive_interval]
+/// min_interval = 60
+/// max_interval = 1800
+/// small_swarm_peers = 10
+/// high_announce_rate = 1000
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct AdaptiveInterval {
+    /// Shortest interval in seconds sent to the clients.
+    #[serde(default = "AdaptiveInterval::default_min_interval")]
+    pub min_interval: u32,
+
+    /// Longest interval in seconds sent to the clients.
+    #[serde(default = "AdaptiveInterval::default_max_interval")]
+    pub max_interval: u32,
+
+    /// Swarms with fewer peers than this get a shorter interval.
+    #[serde(default = "AdaptiveInterval::default_small_swarm_peers")]
+    pub small_swarm_peers: u32,
+
+    /// Announce requests per second at which the tracker is considered fully
+    /// loaded and the interval reaches `max_interval`.
+    #[serde(default = "AdaptiveInterval::default_high_announce_rate")]
+    pub high_announce_rate: u64,
+}
+
+impl Default for AdaptiveInterval {
+    fn default() -> Self {
+        Self {
+            min_interval: Self::default_min_interval(),
+            max_interval: Self::default_max_interval(),
+            small_swarm_peers: Self::default_small_swarm_peers(),
+            high_announce_rate: Self::default_high_announce_rate(),
+        }
+    }
+}
+
+impl AdaptiveInterval {
+    fn default_min_interval() -> u32 {
+        60
+    }
+
+    fn default_max_interval() -> u32 {
+        1800
+    }
+
+    fn default_small_swarm_peers() -> u32 {
+        10
+    }
+
+    fn default_high_announce_rate() -> u64 {
+        1000
+    }
+}
+
+/// Policy applied to the announce requests a peer sends before the
+/// `interval_min` of the [`AnnouncePolicy`] has elapsed since its previous
+/// announce.
+///
+/// The `stopped` and `completed` events are always processed.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EarlyAnnouncePolicy {
+    /// The announce is processed like any other one. The `interval_min` is
+    /// only a recommendation for the clients.
+    #[default]
+    Allow,
+
+    /// The announce fails with an error telling the client when it can
+    /// announce again. The peer is not updated.
+    Reject,
+}
+
 /// Announce policy
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Constructor)]
 pub struct AnnouncePolicy {
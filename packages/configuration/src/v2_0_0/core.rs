@@ -1,14 +1,22 @@
 use derive_more::{Constructor, Display};
 use serde::{Deserialize, Serialize};
 
+use super::geoip::GeoIp;
 use super::network::Network;
 use crate::v2_0_0::database::Database;
 use crate::validator::{SemanticValidationError, Validator};
-use crate::{AnnouncePolicy, TrackerPolicy};
+use crate::{
+    AdaptiveInterval, AnnouncePolicy, EarlyAnnouncePolicy, MemoryLimits, PeerAddressPolicy, PeerSelection, TrackerPolicy,
+};
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct Core {
+    /// Adaptive announce interval. The interval of the announce policy is
+    /// sent to all the clients if it's not set.
+    #[serde(default = "Core::default_adaptive_interval")]
+    pub adaptive_interval: Option<AdaptiveInterval>,
+
     /// Announce policy configuration.
     #[serde(default = "Core::default_announce_policy")]
     pub announce_policy: AnnouncePolicy,
@@ -17,6 +25,16 @@ pub struct Core {
     #[serde(default = "Core::default_database")]
     pub database: Database,
 
+    /// What to do with the announce requests a peer sends before the
+    /// `interval_min` of the announce policy has elapsed.
+    #[serde(default = "Core::default_early_announce_policy")]
+    pub early_announce_policy: EarlyAnnouncePolicy,
+
+    /// `GeoIP` enrichment of the peers. The peers are not located if it's
+    /// not set.
+    #[serde(default = "Core::default_geoip")]
+    pub geoip: Option<GeoIp>,
+
     /// Interval in seconds that the cleanup job will run to remove inactive
     /// peers from the torrent peer list.
     #[serde(default = "Core::default_inactive_peer_cleanup_interval")]
@@ -26,10 +44,23 @@ pub struct Core {
     #[serde(default = "Core::default_listed")]
     pub listed: bool,
 
+    /// Memory limits for the in-memory torrent repository.
+    #[serde(default = "Core::default_memory_limits")]
+    pub memory_limits: MemoryLimits,
+
     /// Network configuration.
     #[serde(default = "Core::default_network")]
     pub net: Network,
 
+    /// Policy for the bogon and private peer addresses, and for the port 0
+    /// and privileged ports.
+    #[serde(default = "Core::default_peer_address_policy")]
+    pub peer_address_policy: PeerAddressPolicy,
+
+    /// How the peers returned in the announce responses are chosen.
+    #[serde(default = "Core::default_peer_selection")]
+    pub peer_selection: PeerSelection,
+
     /// When `true` clients require a key to connect and use the tracker.
     #[serde(default = "Core::default_private")]
     pub private: bool,
@@ -54,11 +85,17 @@ pub struct Core {
 impl Default for Core {
     fn default() -> Self {
         Self {
+            adaptive_interval: Self::default_adaptive_interval(),
             announce_policy: Self::default_announce_policy(),
             database: Self::default_database(),
+            early_announce_policy: Self::default_early_announce_policy(),
+            geoip: Self::default_geoip(),
             inactive_peer_cleanup_interval: Self::default_inactive_peer_cleanup_interval(),
             listed: Self::default_listed(),
+            memory_limits: Self::default_memory_limits(),
             net: Self::default_network(),
+            peer_address_policy: Self::default_peer_address_policy(),
+            peer_selection: Self::default_peer_selection(),
             private: Self::default_private(),
             private_mode: Self::default_private_mode(),
             tracker_policy: Self::default_tracker_policy(),
@@ -68,6 +105,10 @@ impl Default for Core {
 }
 
 impl Core {
+    fn default_adaptive_interval() -> Option<AdaptiveInterval> {
+        None
+    }
+
     fn default_announce_policy() -> AnnouncePolicy {
         AnnouncePolicy::default()
     }
@@ -76,6 +117,14 @@ impl Core {
         Database::default()
     }
 
+    fn default_early_announce_policy() -> EarlyAnnouncePolicy {
+        EarlyAnnouncePolicy::default()
+    }
+
+    fn default_geoip() -> Option<GeoIp> {
+        None
+    }
+
     fn default_inactive_peer_cleanup_interval() -> u64 {
         600
     }
@@ -84,10 +133,22 @@ impl Core {
         false
     }
 
+    fn default_memory_limits() -> MemoryLimits {
+        MemoryLimits::default()
+    }
+
     fn default_network() -> Network {
         Network::default()
     }
 
+    fn default_peer_address_policy() -> PeerAddressPolicy {
+        PeerAddressPolicy::default()
+    }
+
+    fn default_peer_selection() -> PeerSelection {
+        PeerSelection::default()
+    }
+
     fn default_private() -> bool {
         false
     }
@@ -139,6 +200,31 @@ impl Validator for Core {
             return Err(SemanticValidationError::UselessPrivateModeSection);
         }
 
+        if self.peer_selection.local_peers_percentage > 100 {
+            return Err(SemanticValidationError::InvalidLocalPeersPercentage {
+                percentage: self.peer_selection.local_peers_percentage,
+            });
+        }
+
+        if self.memory_limits.ipv6_prefix_length > 128 {
+            return Err(SemanticValidationError::InvalidIpv6PrefixLength {
+                length: self.memory_limits.ipv6_prefix_length,
+            });
+        }
+
+        if let Some(adaptive_interval) = &self.adaptive_interval {
+            if adaptive_interval.min_interval > adaptive_interval.max_interval {
+                return Err(SemanticValidationError::InvalidAdaptiveIntervalBounds {
+                    min_interval: adaptive_interval.min_interval,
+                    max_interval: adaptive_interval.max_interval,
+                });
+            }
+        }
+
+        if let Some(geoip) = &self.geoip {
+            geoip.validate()?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,115 @@
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::validator::{SemanticValidationError, Validator};
+
+/// The paths used by the HTTP tracker routes. The full scrape can't be served
+/// on any of them.
+const HTTP_TRACKER_ROUTES: [&str; 3] = ["/announce", "/scrape", "/health_check"];
+
+/// Configuration for the periodic full scrape dump.
+///
+/// When this section is present, the tracker regenerates the swarm metadata
+/// (seeders, completed and leechers) of all the torrents every `interval`
+/// seconds. The dump is written to the `path` directory as a gzipped bencoded
+/// file (`full_scrape.bencode.gz`) and a gzipped JSON file
+/// (`full_scrape.json.gz`).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct FullScrape {
+    /// The number of seconds between two dumps.
+    #[serde(default = "FullScrape::default_interval")]
+    pub interval: u64,
+
+    /// The directory where the dump files are written.
+    #[serde(default = "FullScrape::default_path")]
+    pub path: Utf8PathBuf,
+
+    /// The URL path where the HTTP trackers serve the latest dump, for example
+    /// `/full_scrape`. The dump is not served if it's not set.
+    #[serde(default = "FullScrape::default_http_path")]
+    pub http_path: Option<String>,
+}
+
+impl Default for FullScrape {
+    fn default() -> Self {
+        Self {
+            interval: Self::default_interval(),
+            path: Self::default_path(),
+            http_path: Self::default_http_path(),
+        }
+    }
+}
+
+impl FullScrape {
+    fn default_interval() -> u64 {
+        300
+    }
+
+    fn default_path() -> Utf8PathBuf {
+        Utf8PathBuf::from("./storage/tracker/lib/full_scrape")
+    }
+
+    fn default_http_path() -> Option<String> {
+        None
+    }
+}
+
+impl Validator for FullScrape {
+    fn validate(&self) -> Result<(), SemanticValidationError> {
+        if self.interval == 0 {
+            return Err(SemanticValidationError::InvalidFullScrapeInterval);
+        }
+
+        if let Some(http_path) = &self.http_path {
+            let is_tracker_route = HTTP_TRACKER_ROUTES
+                .iter()
+                .any(|route| http_path == route || http_path.starts_with(&format!("{route}/")));
+
+            if !http_path.starts_with('/') || http_path.len() == 1 || is_tracker_route {
+                return Err(SemanticValidationError::InvalidFullScrapeHttpPath {
+                    http_path: http_path.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v2_0_0::full_scrape::FullScrape;
+    use crate::validator::Validator;
+
+    #[test]
+    fn it_should_not_allow_a_zero_interval() {
+        let config = FullScrape {
+            interval: 0,
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn it_should_not_allow_serving_the_dump_on_a_tracker_route() {
+        for http_path in ["full_scrape", "/", "/announce", "/scrape/key"] {
+            let config = FullScrape {
+                http_path: Some(http_path.to_string()),
+                ..Default::default()
+            };
+
+            assert!(config.validate().is_err(), "{http_path} should not be valid");
+        }
+    }
+
+    #[test]
+    fn it_should_allow_serving_the_dump_on_any_other_route() {
+        let config = FullScrape {
+            http_path: Some("/full_scrape".to_string()),
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,117 @@
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::validator::{SemanticValidationError, Validator};
+
+/// Configuration for the `GeoIP` enrichment of the peers.
+///
+/// When this section is present, the tracker loads a local `MaxMind` DB
+/// (`MMDB`) file and looks up the country and the autonomous system (ASN) of
+/// the peer addresses. The number of peers by country and ASN is available in
+/// the stats API.
+///
+/// It works with the `GeoLite2` and `GeoIP2` databases, and with other
+/// providers using the same format. If the countries and the ASNs are in
+/// different files, like `GeoLite2-Country` and `GeoLite2-ASN`, set both
+/// paths.
+///
+/// ```toml
+/// [core.geoip]
+/// database_path = "./storage/tracker/lib/GeoLite2-Country.mmdb"
+/// asn_database_path = "./storage/tracker/lib/GeoLite2-ASN.mmdb"
+/// denied_countries = ["XX", "YY"]
+/// ```
+///
+/// The announce requests can be restricted by country:
+///
+/// - The peers in a `denied_countries` country are rejected.
+/// - If `allowed_countries` is not empty, only the peers in those countries
+///   are accepted. The peers whose country is not known are rejected too.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct GeoIp {
+    /// The `MMDB` file with the countries, the ASNs or both.
+    #[serde(default = "GeoIp::default_database_path")]
+    pub database_path: Utf8PathBuf,
+
+    /// An optional second `MMDB` file with the ASNs.
+    #[serde(default = "GeoIp::default_asn_database_path")]
+    pub asn_database_path: Option<Utf8PathBuf>,
+
+    /// The ISO 3166-1 alpha-2 codes of the countries whose peers are
+    /// accepted. All the countries are accepted if it's empty.
+    #[serde(default = "GeoIp::default_countries")]
+    pub allowed_countries: Vec<String>,
+
+    /// The ISO 3166-1 alpha-2 codes of the countries whose peers are
+    /// rejected.
+    #[serde(default = "GeoIp::default_countries")]
+    pub denied_countries: Vec<String>,
+}
+
+impl Default for GeoIp {
+    fn default() -> Self {
+        Self {
+            database_path: Self::default_database_path(),
+            asn_database_path: Self::default_asn_database_path(),
+            allowed_countries: Self::default_countries(),
+            denied_countries: Self::default_countries(),
+        }
+    }
+}
+
+impl GeoIp {
+    fn default_database_path() -> Utf8PathBuf {
+        Utf8PathBuf::from("./storage/tracker/lib/GeoLite2-Country.mmdb")
+    }
+
+    fn default_asn_database_path() -> Option<Utf8PathBuf> {
+        None
+    }
+
+    fn default_countries() -> Vec<String> {
+        vec![]
+    }
+}
+
+impl Validator for GeoIp {
+    fn validate(&self) -> Result<(), SemanticValidationError> {
+        for code in self.allowed_countries.iter().chain(&self.denied_countries) {
+            let is_country_code = code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase());
+
+            if !is_country_code {
+                return Err(SemanticValidationError::InvalidCountryCode { code: code.clone() });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v2_0_0::geoip::GeoIp;
+    use crate::validator::Validator;
+
+    #[test]
+    fn it_should_accept_iso_country_codes() {
+        let geoip = GeoIp {
+            allowed_countries: vec!["GB".to_string(), "SE".to_string()],
+            denied_countries: vec!["JP".to_string()],
+            ..GeoIp::default()
+        };
+
+        assert!(geoip.validate().is_ok());
+    }
+
+    #[test]
+    fn it_should_reject_invalid_country_codes() {
+        for code in ["gb", "GBR", ""] {
+            let geoip = GeoIp {
+                denied_countries: vec![code.to_string()],
+                ..GeoIp::default()
+            };
+
+            assert!(geoip.validate().is_err(), "{code:?} should not be valid");
+        }
+    }
+}
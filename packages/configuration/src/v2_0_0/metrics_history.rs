@@ -0,0 +1,127 @@
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::validator::{SemanticValidationError, Validator};
+
+/// Configuration for the historical metrics.
+///
+/// When this section is present, the tracker takes a sample of its metrics
+/// every `interval` seconds. Each sample contains the number of requests,
+/// announces, scrapes and errors since the previous sample, and the number
+/// of torrents, seeders and leechers at the time of the sample.
+///
+/// The latest `samples` are kept at full resolution. Older samples are
+/// downsampled to one sample every `downsampled_interval` seconds, and the
+/// latest `downsampled_samples` of them are kept. With the default values,
+/// there is one sample per minute for the last day and one sample per hour for
+/// the last 30 days.
+///
+/// The samples are saved to the `path` file after each sample, so they are
+/// not lost when the tracker restarts.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct MetricsHistory {
+    /// The number of seconds between two samples.
+    #[serde(default = "MetricsHistory::default_interval")]
+    pub interval: u64,
+
+    /// The number of samples kept at full resolution.
+    #[serde(default = "MetricsHistory::default_samples")]
+    pub samples: usize,
+
+    /// The number of seconds covered by each downsampled sample. It must be a
+    /// multiple of the `interval`.
+    #[serde(default = "MetricsHistory::default_downsampled_interval")]
+    pub downsampled_interval: u64,
+
+    /// The number of downsampled samples kept.
+    #[serde(default = "MetricsHistory::default_downsampled_samples")]
+    pub downsampled_samples: usize,
+
+    /// The file where the samples are saved.
+    #[serde(default = "MetricsHistory::default_path")]
+    pub path: Utf8PathBuf,
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self {
+            interval: Self::default_interval(),
+            samples: Self::default_samples(),
+            downsampled_interval: Self::default_downsampled_interval(),
+            downsampled_samples: Self::default_downsampled_samples(),
+            path: Self::default_path(),
+        }
+    }
+}
+
+impl MetricsHistory {
+    fn default_interval() -> u64 {
+        60
+    }
+
+    fn default_samples() -> usize {
+        1440
+    }
+
+    fn default_downsampled_interval() -> u64 {
+        3600
+    }
+
+    fn default_downsampled_samples() -> usize {
+        720
+    }
+
+    fn default_path() -> Utf8PathBuf {
+        Utf8PathBuf::from("./storage/tracker/lib/metrics_history.json")
+    }
+}
+
+impl Validator for MetricsHistory {
+    fn validate(&self) -> Result<(), SemanticValidationError> {
+        let is_valid = self.interval > 0
+            && self.samples > 0
+            && self.downsampled_samples > 0
+            && self.downsampled_interval > self.interval
+            && self.downsampled_interval % self.interval == 0;
+
+        if !is_valid {
+            return Err(SemanticValidationError::InvalidMetricsHistory);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v2_0_0::metrics_history::MetricsHistory;
+    use crate::validator::Validator;
+
+    #[test]
+    fn the_default_configuration_should_be_valid() {
+        assert!(MetricsHistory::default().validate().is_ok());
+    }
+
+    #[test]
+    fn it_should_not_allow_a_zero_interval() {
+        let config = MetricsHistory {
+            interval: 0,
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn the_downsampled_interval_should_be_a_greater_multiple_of_the_interval() {
+        for downsampled_interval in [60, 90] {
+            let config = MetricsHistory {
+                interval: 60,
+                downsampled_interval,
+                ..Default::default()
+            };
+
+            assert!(config.validate().is_err(), "{downsampled_interval} should not be valid");
+        }
+    }
+}
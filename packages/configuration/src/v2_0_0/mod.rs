@@ -44,6 +44,10 @@
 //! - [`HTTP Tracker configuration`](crate::v2_0_0::http_tracker::HttpTracker)
 //! - [`UDP Tracker configuration`](crate::v2_0_0::udp_tracker::UdpTracker)
 //! - [`Health Check API configuration`](crate::v2_0_0::health_check_api::HealthCheckApi)
+//! - [`Full scrape configuration`](crate::v2_0_0::full_scrape::FullScrape)
+//! - [`GeoIP configuration`](crate::v2_0_0::geoip::GeoIp)
+//! - [`Metrics history configuration`](crate::v2_0_0::metrics_history::MetricsHistory)
+//! - [`Webhooks configuration`](crate::v2_0_0::webhooks::Webhooks)
 //!
 //! ## Port binding
 //!
@@ -199,6 +203,7 @@
 //! threshold = "info"
 //!
 //! [core]
+//! early_announce_policy = "allow"
 //! inactive_peer_cleanup_interval = 600
 //! listed = false
 //! private = false
@@ -212,10 +217,26 @@
 //! driver = "sqlite3"
 //! path = "./storage/tracker/lib/database/sqlite3.db"
 //!
+//! [core.memory_limits]
+//! torrent_eviction_policy = "refuse_new"
+//! peer_eviction_policy = "refuse_new"
+//! ipv6_prefix_length = 64
+//! peers_per_ip_policy = "reject"
+//!
 //! [core.net]
 //! external_ip = "0.0.0.0"
 //! on_reverse_proxy = false
 //!
+//! [core.peer_address_policy]
+//! bogon_peers = "hide"
+//! private_peers = "same_network"
+//! port_zero_peers = "hide"
+//! privileged_port_peers = "keep"
+//!
+//! [core.peer_selection]
+//! strategy = "ordered"
+//! local_peers_percentage = 50
+//!
 //! [core.tracker_policy]
 //! max_peer_timeout = 900
 //! persistent_torrent_completed_stat = false
@@ -231,12 +252,16 @@
 //!```
 pub mod core;
 pub mod database;
+pub mod full_scrape;
+pub mod geoip;
 pub mod health_check_api;
 pub mod http_tracker;
 pub mod logging;
+pub mod metrics_history;
 pub mod network;
 pub mod tracker_api;
 pub mod udp_tracker;
+pub mod webhooks;
 
 use std::fs;
 use std::net::IpAddr;
@@ -247,10 +272,13 @@ use logging::Logging;
 use serde::{Deserialize, Serialize};
 
 use self::core::Core;
+use self::full_scrape::FullScrape;
 use self::health_check_api::HealthCheckApi;
 use self::http_tracker::HttpTracker;
+use self::metrics_history::MetricsHistory;
 use self::tracker_api::HttpApi;
 use self::udp_tracker::UdpTracker;
+use self::webhooks::Webhooks;
 use crate::validator::{SemanticValidationError, Validator};
 use crate::{Error, Info, Metadata, Version};
 
@@ -290,6 +318,18 @@ pub struct Configuration {
 
     /// The Health Check API configuration.
     pub health_check_api: HealthCheckApi,
+
+    /// The periodic full scrape dump configuration. There is no dump if it's
+    /// not set.
+    pub full_scrape: Option<FullScrape>,
+
+    /// The historical metrics configuration. The metrics history is not
+    /// recorded if it's not set.
+    pub metrics_history: Option<MetricsHistory>,
+
+    /// The webhook notifications configuration. No notifications are sent if
+    /// it's not set.
+    pub webhooks: Option<Webhooks>,
 }
 
 impl Configuration {
@@ -418,13 +458,35 @@ impl Configuration {
             api.mask_secrets();
         }
 
+        if let Some(ref mut webhooks) = self.webhooks {
+            webhooks.mask_secrets();
+        }
+
         self
     }
 }
 
 impl Validator for Configuration {
     fn validate(&self) -> Result<(), SemanticValidationError> {
-        self.core.validate()
+        self.core.validate()?;
+
+        if let Some(full_scrape) = &self.full_scrape {
+            full_scrape.validate()?;
+        }
+
+        if let Some(http_api) = &self.http_api {
+            http_api.validate()?;
+        }
+
+        if let Some(metrics_history) = &self.metrics_history {
+            metrics_history.validate()?;
+        }
+
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.validate()?;
+        }
+
+        Ok(())
     }
 }
 
@@ -433,6 +495,7 @@ mod tests {
 
     use std::net::{IpAddr, Ipv4Addr};
 
+    use crate::v2_0_0::tracker_api::AccessToken;
     use crate::v2_0_0::Configuration;
     use crate::Info;
 
@@ -447,6 +510,7 @@ mod tests {
                                 threshold = "info"
 
                                 [core]
+                                early_announce_policy = "allow"
                                 inactive_peer_cleanup_interval = 600
                                 listed = false
                                 private = false
@@ -460,10 +524,26 @@ mod tests {
                                 driver = "sqlite3"
                                 path = "./storage/tracker/lib/database/sqlite3.db"
 
+                                [core.memory_limits]
+                                torrent_eviction_policy = "refuse_new"
+                                peer_eviction_policy = "refuse_new"
+                                ipv6_prefix_length = 64
+                                peers_per_ip_policy = "reject"
+
                                 [core.net]
                                 external_ip = "0.0.0.0"
                                 on_reverse_proxy = false
 
+                                [core.peer_address_policy]
+                                bogon_peers = "hide"
+                                private_peers = "same_network"
+                                port_zero_peers = "hide"
+                                privileged_port_peers = "keep"
+
+                                [core.peer_selection]
+                                strategy = "ordered"
+                                local_peers_percentage = 50
+
                                 [core.tracker_policy]
                                 max_peer_timeout = 900
                                 persistent_torrent_completed_stat = false
@@ -663,7 +743,7 @@ mod tests {
 
             assert_eq!(
                 configuration.http_api.unwrap().access_tokens.get("admin"),
-                Some("NewToken".to_owned()).as_ref()
+                Some(AccessToken::from("NewToken")).as_ref()
             );
 
             Ok(())
@@ -1,12 +1,176 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
+use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use crate::validator::{SemanticValidationError, Validator};
 use crate::TslConfig;
 
-pub type AccessTokens = HashMap<String, String>;
+/// The prefix of the access tokens stored as a SHA-256 hash.
+pub const SHA256_TOKEN_PREFIX: &str = "sha256:";
+
+/// The prefix of the access tokens stored as an Argon2 hash.
+pub const ARGON2_TOKEN_PREFIX: &str = "$argon2";
+
+pub type AccessTokens = HashMap<String, AccessToken>;
+
+/// An access token for the HTTP API.
+///
+/// A token can be a plain string, which has all the permissions:
+///
+/// ```toml
+/// [http_api.access_tokens]
+/// admin = "MyAccessToken"
+/// ```
+///
+/// Or it can be restricted to some [`Scope`]s:
+///
+/// ```toml
+/// [http_api.access_tokens]
+/// monitoring = { token = "MyMonitoringToken", scopes = ["stats:read", "torrents:read"] }
+/// ```
+///
+/// Instead of the token itself, the configuration can contain a hash of the
+/// token:
+///
+/// - A SHA-256 hash with the `sha256:` prefix and the hex-encoded digest of
+///   the [`token_pepper`](HttpApi::token_pepper) followed by the token. For
+///   example: `sha256:9c5d...`.
+/// - An Argon2 hash in the PHC string format. For example:
+///   `$argon2id$v=19$m=19456,t=2,p=1$...`. Verifying Argon2 hashes is slow
+///   on purpose, so it's only recommended when there are a few tokens.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(untagged)]
+pub enum AccessToken {
+    /// A token with all the permissions.
+    Admin(String),
+    /// A token that is only allowed to use the endpoints in its scopes.
+    Scoped { token: String, scopes: Vec<Scope> },
+}
+
+impl AccessToken {
+    #[must_use]
+    pub fn token(&self) -> &str {
+        match self {
+            AccessToken::Admin(token) | AccessToken::Scoped { token, .. } => token,
+        }
+    }
+
+    /// Whether the configuration contains a hash of the token instead of the
+    /// token itself.
+    #[must_use]
+    pub fn is_hashed(&self) -> bool {
+        self.token().starts_with(SHA256_TOKEN_PREFIX) || self.token().starts_with(ARGON2_TOKEN_PREFIX)
+    }
+
+    #[must_use]
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        match self {
+            AccessToken::Admin(_) => true,
+            AccessToken::Scoped { scopes, .. } => scopes.contains(&scope),
+        }
+    }
+
+    fn mask(&mut self) {
+        match self {
+            AccessToken::Admin(token) | AccessToken::Scoped { token, .. } => *token = "***".to_string(),
+        }
+    }
+}
+
+impl From<&str> for AccessToken {
+    fn from(token: &str) -> Self {
+        AccessToken::Admin(token.to_string())
+    }
+}
+
+/// A permission an [`AccessToken`] can be granted.
+///
+/// Read scopes allow the `GET` endpoints of an API context and write scopes
+/// allow the rest of them.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Scope {
+    /// Get the tracker statistics and metrics.
+    #[serde(rename = "stats:read")]
+    StatsRead,
+    /// Get, list and export torrents.
+    #[serde(rename = "torrents:read")]
+    TorrentsRead,
+    /// Delete torrents and evict peers.
+    #[serde(rename = "torrents:write")]
+    TorrentsWrite,
+    /// Add and remove torrents from the whitelist, and reload it.
+    #[serde(rename = "whitelist:write")]
+    WhitelistWrite,
+    /// Generate, add and delete authentication keys, and reload them.
+    #[serde(rename = "keys:write")]
+    KeysWrite,
+    /// Reload the configuration.
+    #[serde(rename = "config:write")]
+    ConfigWrite,
+    /// List the running listeners.
+    #[serde(rename = "listeners:read")]
+    ListenersRead,
+    /// Start, stop and restart listeners.
+    #[serde(rename = "listeners:write")]
+    ListenersWrite,
+    /// Get the audit log of the administrative actions.
+    #[serde(rename = "audit:read")]
+    AuditRead,
+    /// Subscribe to the live event stream.
+    #[serde(rename = "events:read")]
+    EventsRead,
+    /// List the client policy rules.
+    #[serde(rename = "client-policy:read")]
+    ClientPolicyRead,
+    /// Add and remove client policy rules.
+    #[serde(rename = "client-policy:write")]
+    ClientPolicyWrite,
+}
+
+impl Scope {
+    /// Whether the scope allows changing the tracker state.
+    #[must_use]
+    pub fn is_write(self) -> bool {
+        match self {
+            Scope::StatsRead
+            | Scope::TorrentsRead
+            | Scope::ListenersRead
+            | Scope::AuditRead
+            | Scope::EventsRead
+            | Scope::ClientPolicyRead => false,
+            Scope::TorrentsWrite
+            | Scope::WhitelistWrite
+            | Scope::KeysWrite
+            | Scope::ConfigWrite
+            | Scope::ListenersWrite
+            | Scope::ClientPolicyWrite => true,
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = match self {
+            Scope::StatsRead => "stats:read",
+            Scope::TorrentsRead => "torrents:read",
+            Scope::TorrentsWrite => "torrents:write",
+            Scope::WhitelistWrite => "whitelist:write",
+            Scope::KeysWrite => "keys:write",
+            Scope::ConfigWrite => "config:write",
+            Scope::ListenersRead => "listeners:read",
+            Scope::ListenersWrite => "listeners:write",
+            Scope::AuditRead => "audit:read",
+            Scope::EventsRead => "events:read",
+            Scope::ClientPolicyRead => "client-policy:read",
+            Scope::ClientPolicyWrite => "client-policy:write",
+        };
+        write!(f, "{scope}")
+    }
+}
 
 /// Configuration for the HTTP API.
 #[serde_as]
@@ -25,10 +189,29 @@ pub struct HttpApi {
 
     /// Access tokens for the HTTP API. The key is a label identifying the
     /// token and the value is the token itself. The token is used to
-    /// authenticate the user. All tokens are valid for all endpoints and have
-    /// all permissions.
+    /// authenticate the user. Tokens have all permissions unless they are
+    /// restricted to some scopes (see [`AccessToken`]).
     #[serde(default = "HttpApi::default_access_tokens")]
     pub access_tokens: AccessTokens,
+
+    /// A secret prepended to the tokens before hashing them with SHA-256. It
+    /// should not be stored with the hashes. Only used by the tokens stored
+    /// as a SHA-256 hash.
+    #[serde(default = "HttpApi::default_token_pepper")]
+    pub token_pepper: Option<String>,
+
+    /// Whether the token can be sent in the `token` URL query param. It's a
+    /// legacy mode: the query param ends up in the logs of reverse proxies.
+    /// When disabled, the token can only be sent in the `Authorization`
+    /// header with the `Bearer` scheme.
+    #[serde(default = "HttpApi::default_token_query_param")]
+    pub token_query_param: bool,
+
+    /// The path of the audit log file. Every API call that changes the
+    /// tracker state is appended to the file as a JSON line. Default:
+    /// `./storage/tracker/lib/api_audit.jsonl`.
+    #[serde(default = "HttpApi::default_audit_log_path")]
+    pub audit_log_path: Utf8PathBuf,
 }
 
 impl Default for HttpApi {
@@ -37,6 +220,9 @@ impl Default for HttpApi {
             bind_address: Self::default_bind_address(),
             tsl_config: Self::default_tsl_config(),
             access_tokens: Self::default_access_tokens(),
+            token_pepper: Self::default_token_pepper(),
+            token_query_param: Self::default_token_query_param(),
+            audit_log_path: Self::default_audit_log_path(),
         }
     }
 }
@@ -55,20 +241,61 @@ impl HttpApi {
         [].iter().cloned().collect()
     }
 
+    fn default_token_pepper() -> Option<String> {
+        None
+    }
+
+    fn default_token_query_param() -> bool {
+        true
+    }
+
+    fn default_audit_log_path() -> Utf8PathBuf {
+        Utf8PathBuf::from("./storage/tracker/lib/api_audit.jsonl")
+    }
+
     pub fn add_token(&mut self, key: &str, token: &str) {
-        self.access_tokens.insert(key.to_string(), token.to_string());
+        self.access_tokens.insert(key.to_string(), token.into());
+    }
+
+    pub fn add_scoped_token(&mut self, key: &str, token: &str, scopes: &[Scope]) {
+        self.access_tokens.insert(
+            key.to_string(),
+            AccessToken::Scoped {
+                token: token.to_string(),
+                scopes: scopes.to_vec(),
+            },
+        );
     }
 
     pub fn mask_secrets(&mut self) {
         for token in self.access_tokens.values_mut() {
-            *token = "***".to_string();
+            token.mask();
+        }
+
+        if let Some(pepper) = &mut self.token_pepper {
+            *pepper = "***".to_string();
+        }
+    }
+}
+
+impl Validator for HttpApi {
+    fn validate(&self) -> Result<(), SemanticValidationError> {
+        for (label, access_token) in &self.access_tokens {
+            if let Some(digest) = access_token.token().strip_prefix(SHA256_TOKEN_PREFIX) {
+                if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(SemanticValidationError::InvalidAccessTokenHash { label: label.clone() });
+                }
+            }
         }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::v2_0_0::tracker_api::HttpApi;
+    use crate::v2_0_0::tracker_api::{AccessToken, HttpApi, Scope};
+    use crate::validator::Validator;
 
     #[test]
     fn default_http_api_configuration_should_not_contains_any_token() {
@@ -83,6 +310,60 @@ mod tests {
 
         configuration.add_token("admin", "MyAccessToken");
 
-        assert!(configuration.access_tokens.values().any(|t| t == "MyAccessToken"));
+        assert!(configuration.access_tokens.values().any(|t| t.token() == "MyAccessToken"));
+    }
+
+    #[test]
+    fn plain_tokens_should_have_all_the_scopes() {
+        let token = AccessToken::from("MyAccessToken");
+
+        assert!(token.has_scope(Scope::StatsRead));
+        assert!(token.has_scope(Scope::KeysWrite));
+    }
+
+    #[test]
+    fn scoped_tokens_should_only_have_their_scopes() {
+        let mut configuration = HttpApi::default();
+
+        configuration.add_scoped_token("monitoring", "MyMonitoringToken", &[Scope::StatsRead]);
+
+        let token = &configuration.access_tokens["monitoring"];
+
+        assert!(token.has_scope(Scope::StatsRead));
+        assert!(!token.has_scope(Scope::KeysWrite));
+    }
+
+    #[test]
+    fn tokens_should_be_deserialized_from_plain_strings_and_scoped_tables() {
+        let configuration: HttpApi = toml::from_str(
+            r#"
+            [access_tokens]
+            admin = "MyAccessToken"
+            monitoring = { token = "MyMonitoringToken", scopes = ["stats:read", "torrents:read"] }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(configuration.access_tokens["admin"], AccessToken::from("MyAccessToken"));
+        assert_eq!(
+            configuration.access_tokens["monitoring"],
+            AccessToken::Scoped {
+                token: "MyMonitoringToken".to_string(),
+                scopes: vec![Scope::StatsRead, Scope::TorrentsRead],
+            }
+        );
+    }
+
+    #[test]
+    fn sha256_token_hashes_should_be_64_hex_characters_long() {
+        let mut configuration = HttpApi::default();
+
+        configuration.add_token("admin", "sha256:9c5d");
+
+        assert!(configuration.validate().is_err());
+
+        configuration.add_token("admin", &format!("sha256:{}", "a".repeat(64)));
+
+        assert!(configuration.validate().is_ok());
     }
 }
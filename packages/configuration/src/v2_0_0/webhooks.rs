@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::validator::{SemanticValidationError, Validator};
+
+/// Configuration for the webhook notifications.
+///
+/// When this section is present, the tracker sends an HTTP `POST` request to
+/// each target URL when one of its events happens. The body is the event as
+/// JSON, for example:
+///
+/// ```json
+/// {"type":"torrent_created","info_hash":"9c38422213e30bff212b30c360d26f9a02136422"}
+/// ```
+///
+/// ```toml
+/// [webhooks]
+/// secret = "MyWebhookSecret"
+///
+/// [[webhooks.targets]]
+/// url = "https://indexer.example.com/tracker-events"
+/// events = ["torrent_created", "torrent_seeders_returned", "torrent_completed", "torrent_removed"]
+/// ```
+///
+/// The deliveries are queued and sent in the background, so they never delay
+/// the announce requests. When the queue is full, new deliveries are dropped.
+/// Failed deliveries are retried with an exponential backoff.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Webhooks {
+    /// The secret used to sign the requests. The `X-Torrust-Signature`
+    /// header contains `sha256=` followed by the hex-encoded HMAC-SHA256 of
+    /// the `X-Torrust-Timestamp` header, a dot and the body. The requests are
+    /// not signed if it's not set.
+    #[serde(default = "Webhooks::default_secret")]
+    pub secret: Option<String>,
+
+    /// The maximum number of deliveries waiting to be sent.
+    #[serde(default = "Webhooks::default_queue_capacity")]
+    pub queue_capacity: usize,
+
+    /// The number of times a delivery is tried before giving up.
+    #[serde(default = "Webhooks::default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// The URLs the events are sent to.
+    #[serde(default = "Webhooks::default_targets")]
+    pub targets: Vec<WebhookTarget>,
+}
+
+/// A URL the events are sent to.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct WebhookTarget {
+    /// The `http` or `https` URL.
+    pub url: String,
+
+    /// The types of the events sent to the URL, like in the `type` param of
+    /// the API event stream. All the events are sent if it's empty.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl Default for Webhooks {
+    fn default() -> Self {
+        Self {
+            secret: Self::default_secret(),
+            queue_capacity: Self::default_queue_capacity(),
+            max_attempts: Self::default_max_attempts(),
+            targets: Self::default_targets(),
+        }
+    }
+}
+
+impl Webhooks {
+    fn default_secret() -> Option<String> {
+        None
+    }
+
+    fn default_queue_capacity() -> usize {
+        1000
+    }
+
+    fn default_max_attempts() -> u32 {
+        5
+    }
+
+    fn default_targets() -> Vec<WebhookTarget> {
+        vec![]
+    }
+
+    pub fn mask_secrets(&mut self) {
+        if let Some(secret) = &mut self.secret {
+            *secret = "***".to_string();
+        }
+    }
+}
+
+impl Validator for Webhooks {
+    fn validate(&self) -> Result<(), SemanticValidationError> {
+        if self.queue_capacity == 0 || self.max_attempts == 0 {
+            return Err(SemanticValidationError::InvalidWebhooksQueue);
+        }
+
+        for target in &self.targets {
+            let is_http = Url::parse(&target.url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+
+            if !is_http {
+                return Err(SemanticValidationError::InvalidWebhookUrl { url: target.url.clone() });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v2_0_0::webhooks::{WebhookTarget, Webhooks};
+    use crate::validator::Validator;
+
+    fn target(url: &str) -> WebhookTarget {
+        WebhookTarget {
+            url: url.to_string(),
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn it_should_not_allow_an_empty_queue() {
+        let config = Webhooks {
+            queue_capacity: 0,
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn it_should_only_allow_http_urls() {
+        for url in ["indexer.example.com", "ftp://indexer.example.com"] {
+            let config = Webhooks {
+                targets: vec![target(url)],
+                ..Default::default()
+            };
+
+            assert!(config.validate().is_err(), "{url} should not be valid");
+        }
+
+        let config = Webhooks {
+            targets: vec![target("https://indexer.example.com/tracker-events")],
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn it_should_mask_the_secret() {
+        let mut config = Webhooks {
+            secret: Some("MyWebhookSecret".to_string()),
+            ..Default::default()
+        };
+
+        config.mask_secrets();
+
+        assert_eq!(config.secret, Some("***".to_string()));
+    }
+}
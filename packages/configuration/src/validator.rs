@@ -9,6 +9,36 @@ use thiserror::Error;
 pub enum SemanticValidationError {
     #[error("Private mode section in configuration can only be included when the tracker is running in private mode.")]
     UselessPrivateModeSection,
+
+    #[error("The full scrape interval must be greater than zero.")]
+    InvalidFullScrapeInterval,
+
+    #[error("The full scrape HTTP path must start with `/` and can't be an HTTP tracker route: {http_path}.")]
+    InvalidFullScrapeHttpPath { http_path: String },
+
+    #[error("The metrics history intervals and sizes must be greater than zero, and the downsampled interval must be a greater multiple of the interval.")]
+    InvalidMetricsHistory,
+
+    #[error("The webhooks queue capacity and the maximum number of attempts must be greater than zero.")]
+    InvalidWebhooksQueue,
+
+    #[error("The webhook URL must be an http or https URL: {url}.")]
+    InvalidWebhookUrl { url: String },
+
+    #[error("The country code must be an uppercase ISO 3166-1 alpha-2 code: {code}.")]
+    InvalidCountryCode { code: String },
+
+    #[error("The percentage of local peers must be between 0 and 100: {percentage}.")]
+    InvalidLocalPeersPercentage { percentage: u8 },
+
+    #[error("The IPv6 prefix length must be between 0 and 128: {length}.")]
+    InvalidIpv6PrefixLength { length: u8 },
+
+    #[error("The adaptive announce interval bounds are inverted: min {min_interval} > max {max_interval}.")]
+    InvalidAdaptiveIntervalBounds { min_interval: u32, max_interval: u32 },
+
+    #[error("The SHA-256 hash of the HTTP API access token \"{label}\" must be 64 hex characters long.")]
+    InvalidAccessTokenHash { label: String },
 }
 
 pub trait Validator {
@@ -63,6 +63,22 @@ impl From<bittorrent_tracker_core::error::WhitelistError> for Error {
     }
 }
 
+impl From<bittorrent_tracker_core::error::AnnounceError> for Error {
+    fn from(err: bittorrent_tracker_core::error::AnnounceError) -> Self {
+        Error {
+            failure_reason: format!("Tracker error: {err}"),
+        }
+    }
+}
+
+impl From<bittorrent_tracker_core::error::ClientPolicyError> for Error {
+    fn from(err: bittorrent_tracker_core::error::ClientPolicyError) -> Self {
+        Error {
+            failure_reason: format!("Tracker error: {err}"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
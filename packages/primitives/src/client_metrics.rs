@@ -0,0 +1,133 @@
+//! Number of peers by client software.
+//!
+//! `BitTorrent` clients identify themselves in their peer IDs. Most of them
+//! follow one of these conventions:
+//!
+//! - Azureus-style: `-TR4040-` followed by random bytes, where `TR` is the
+//!   client (Transmission) and `4040` the version (4.0.4).
+//! - Shadow-style: `S58B-----` followed by random bytes, where `S` is the
+//!   client (Shad0w) and `58B` the version (5.8.11).
+//!
+//! The peer IDs are parsed with the [`tdyne_peer_id_registry`] crate.
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+
+use aquatic_udp_protocol::PeerId;
+
+/// The client software of a peer, parsed from its peer ID.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Client {
+    /// The client family, for example, `Transmission`. It's
+    /// [`Client::UNKNOWN`] when the peer ID does not follow a known
+    /// convention.
+    pub name: String,
+    /// The client version, for example, `4.0.4`. It's `None` when the client
+    /// does not encode it in the peer ID or it can't be parsed.
+    pub version: Option<String>,
+}
+
+impl Client {
+    /// The name of the clients that can't be identified.
+    pub const UNKNOWN: &'static str = "Unknown";
+
+    #[must_use]
+    pub fn from_peer_id(peer_id: &PeerId) -> Self {
+        match tdyne_peer_id_registry::parse(tdyne_peer_id::PeerId::from(peer_id.0)) {
+            Ok(parsed) => Self {
+                name: parsed.client,
+                version: parsed.version.ok().flatten(),
+            },
+            Err(_) => Self {
+                name: Self::UNKNOWN.to_string(),
+                version: None,
+            },
+        }
+    }
+}
+
+/// Number of active peers by [`Client`].
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ClientsMetrics {
+    pub peers: BTreeMap<Client, u64>,
+}
+
+impl ClientsMetrics {
+    /// It counts a peer with the given peer ID.
+    pub fn count(&mut self, peer_id: &PeerId) {
+        *self.peers.entry(Client::from_peer_id(peer_id)).or_default() += 1;
+    }
+
+    /// It returns the clients sorted by the number of peers, the most used
+    /// first.
+    #[must_use]
+    pub fn most_used(&self) -> Vec<(&Client, u64)> {
+        let mut clients: Vec<(&Client, u64)> = self.peers.iter().map(|(client, peers)| (client, *peers)).collect();
+
+        clients.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        clients
+    }
+}
+
+impl AddAssign for ClientsMetrics {
+    fn add_assign(&mut self, rhs: Self) {
+        for (client, peers) in rhs.peers {
+            *self.peers.entry(client).or_default() += peers;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aquatic_udp_protocol::PeerId;
+
+    use super::{Client, ClientsMetrics};
+
+    fn client(name: &str, version: Option<&str>) -> Client {
+        Client {
+            name: name.to_string(),
+            version: version.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn it_should_parse_azureus_style_peer_ids() {
+        assert_eq!(
+            Client::from_peer_id(&PeerId(*b"-TR4040-xxxxxxxxxxxx")),
+            client("Transmission", Some("4.0.4"))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_shadow_style_peer_ids() {
+        assert_eq!(
+            Client::from_peer_id(&PeerId(*b"S58B-----xxxxxxxxxxx")),
+            client("Shad0w", Some("5.8.11"))
+        );
+    }
+
+    #[test]
+    fn it_should_not_identify_peer_ids_that_do_not_follow_a_known_convention() {
+        assert_eq!(
+            Client::from_peer_id(&PeerId(*b"\x01\x02\x03\x04xxxxxxxxxxxxxxxx")),
+            client(Client::UNKNOWN, None)
+        );
+    }
+
+    #[test]
+    fn it_should_count_the_peers_by_client_the_most_used_first() {
+        let mut metrics = ClientsMetrics::default();
+
+        metrics.count(&PeerId(*b"-TR4040-xxxxxxxxxxxx"));
+        metrics.count(&PeerId(*b"-qB4630-xxxxxxxxxxxx"));
+        metrics.count(&PeerId(*b"-qB4630-yyyyyyyyyyyy"));
+
+        assert_eq!(
+            metrics.most_used(),
+            vec![
+                (&client("qBittorrent", Some("4.6.3")), 2),
+                (&client("Transmission", Some("4.0.4")), 1)
+            ]
+        );
+    }
+}
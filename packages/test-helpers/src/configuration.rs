@@ -44,6 +44,7 @@ pub fn ephemeral() -> Configuration {
         ..Default::default()
     };
     http_api.add_token("admin", "MyAccessToken");
+    http_api.audit_log_path = ephemeral_audit_log().to_str().unwrap().to_string().into();
     config.http_api = Some(http_api);
 
     // Ephemeral socket address for Health Check API
@@ -77,6 +78,13 @@ pub fn ephemeral_sqlite_database() -> PathBuf {
     temp_directory.join(format!("data_{random_db_id}.db"))
 }
 
+#[must_use]
+pub fn ephemeral_audit_log() -> PathBuf {
+    let temp_directory = env::temp_dir();
+    let random_log_id = random::string(16);
+    temp_directory.join(format!("api_audit_{random_log_id}.jsonl"))
+}
+
 /// Ephemeral configuration with reverse proxy enabled.
 #[must_use]
 pub fn ephemeral_with_reverse_proxy() -> Configuration {
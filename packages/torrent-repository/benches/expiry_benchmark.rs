@@ -0,0 +1,211 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[path = "helpers/utils.rs"]
+mod utils;
+
+use aquatic_udp_protocol::PeerId;
+use bittorrent_primitives::info_hash::InfoHash;
+use criterion::{criterion_group, criterion_main, Criterion};
+use torrust_tracker_configuration::TrackerPolicy;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+use torrust_tracker_torrent_repository::expiry::EXPIRY_BATCH_SIZE;
+use torrust_tracker_torrent_repository::repository::Repository;
+use torrust_tracker_torrent_repository::TorrentsSkipMapMutexStd;
+
+use crate::utils::{generate_unique_info_hashes, DEFAULT_PEER};
+
+const TORRENTS: usize = 1_000;
+const PEERS_PER_TORRENT: usize = 100;
+
+/// Peers updated before this time are inactive.
+const CUTOFF: DurationSinceUnixEpoch = DurationSinceUnixEpoch::from_secs(200);
+
+// Build a repository where half of the peers of every torrent are inactive.
+fn populated_repository(info_hashes: &[InfoHash]) -> TorrentsSkipMapMutexStd {
+    let torrent_repository = TorrentsSkipMapMutexStd::default();
+
+    for info_hash in info_hashes {
+        for i in 0..PEERS_PER_TORRENT {
+            let mut peer = DEFAULT_PEER;
+
+            peer.peer_id = PeerId(peer_id_bytes(i));
+            peer.updated = if i % 2 == 0 {
+                DurationSinceUnixEpoch::from_secs(100 + i as u64)
+            } else {
+                DurationSinceUnixEpoch::from_secs(300 + i as u64)
+            };
+
+            torrent_repository.upsert_peer(info_hash, &peer);
+        }
+    }
+
+    torrent_repository
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn peer_id_bytes(i: usize) -> [u8; 20] {
+    let mut bytes = [0u8; 20];
+    bytes[0] = (i & 0xFF) as u8;
+    bytes[1] = ((i >> 8) & 0xFF) as u8;
+    bytes
+}
+
+// The time the repository is busy removing inactive peers in one go (full scan)
+// versus the time of one incremental batch. The batch is the longest pause
+// announces can observe with incremental expiry.
+fn remove_inactive_peers(c: &mut Criterion) {
+    let info_hashes = generate_unique_info_hashes(TORRENTS);
+
+    let mut group = c.benchmark_group("remove_inactive_peers");
+
+    group.sample_size(10);
+    group.warm_up_time(Duration::from_millis(500));
+    group.measurement_time(Duration::from_millis(1000));
+
+    group.bench_function("FullScan", |b| {
+        b.iter_custom(|iters| {
+            let mut elapsed = Duration::ZERO;
+
+            for _ in 0..iters {
+                let torrent_repository = populated_repository(&info_hashes);
+
+                let start = Instant::now();
+                torrent_repository.remove_inactive_peers(CUTOFF);
+                elapsed += start.elapsed();
+            }
+
+            elapsed
+        });
+    });
+
+    group.bench_function("IncrementalBatch", |b| {
+        b.iter_custom(|iters| {
+            let mut elapsed = Duration::ZERO;
+
+            for _ in 0..iters {
+                let torrent_repository = populated_repository(&info_hashes);
+
+                let start = Instant::now();
+                torrent_repository.remove_expired_peers(CUTOFF, &TrackerPolicy::default(), EXPIRY_BATCH_SIZE);
+                elapsed += start.elapsed();
+            }
+
+            elapsed
+        });
+    });
+
+    group.finish();
+}
+
+fn full_scan(torrent_repository: &TorrentsSkipMapMutexStd) {
+    torrent_repository.remove_inactive_peers(CUTOFF);
+}
+
+fn incremental(torrent_repository: &TorrentsSkipMapMutexStd) {
+    torrent_repository.remove_expired_peers(CUTOFF, &TrackerPolicy::default(), EXPIRY_BATCH_SIZE);
+}
+
+// The mean latency of announces while another thread keeps cleaning up the repository.
+fn announce_during_cleanup(c: &mut Criterion) {
+    let info_hashes = generate_unique_info_hashes(TORRENTS);
+
+    let mut group = c.benchmark_group("announce_during_cleanup");
+
+    group.sample_size(10);
+    group.warm_up_time(Duration::from_millis(500));
+    group.measurement_time(Duration::from_millis(1000));
+
+    group.bench_function("FullScan", |b| {
+        b.iter_custom(|iters| announce_while_cleaning(&info_hashes, iters, full_scan).iter().sum());
+    });
+
+    group.bench_function("Incremental", |b| {
+        b.iter_custom(|iters| announce_while_cleaning(&info_hashes, iters, incremental).iter().sum());
+    });
+
+    group.finish();
+}
+
+// The latency of the slowest announce while another thread keeps cleaning up
+// the repository. It is the pause a client observes when its announce waits
+// for the cleanup.
+fn slowest_announce_during_cleanup(c: &mut Criterion) {
+    let info_hashes = generate_unique_info_hashes(TORRENTS);
+
+    let mut group = c.benchmark_group("slowest_announce_during_cleanup");
+
+    group.sample_size(10);
+    group.warm_up_time(Duration::from_millis(500));
+    group.measurement_time(Duration::from_millis(1000));
+
+    group.bench_function("FullScan", |b| {
+        b.iter_custom(|iters| slowest(&announce_while_cleaning(&info_hashes, iters, full_scan), iters));
+    });
+
+    group.bench_function("Incremental", |b| {
+        b.iter_custom(|iters| slowest(&announce_while_cleaning(&info_hashes, iters, incremental), iters));
+    });
+
+    group.finish();
+}
+
+// Criterion divides the returned time by the iterations, so the slowest
+// latency is scaled up to be reported as the time per iteration.
+fn slowest(latencies: &[Duration], iters: u64) -> Duration {
+    let slowest = latencies.iter().max().copied().unwrap_or_default();
+
+    slowest * u32::try_from(iters).unwrap_or(u32::MAX)
+}
+
+// It returns the latency of each announce.
+fn announce_while_cleaning<F>(info_hashes: &[InfoHash], samples: u64, cleanup: F) -> Vec<Duration>
+where
+    F: Fn(&TorrentsSkipMapMutexStd) + Send + 'static,
+{
+    let torrent_repository = Arc::new(populated_repository(info_hashes));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let cleaner = {
+        let torrent_repository = torrent_repository.clone();
+        let stop = stop.clone();
+
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                cleanup(&torrent_repository);
+            }
+        })
+    };
+
+    let mut peer = DEFAULT_PEER;
+    peer.updated = DurationSinceUnixEpoch::from_secs(1000);
+
+    let mut latencies = Vec::with_capacity(samples.try_into().unwrap_or_default());
+
+    for (i, info_hash) in info_hashes
+        .iter()
+        .cycle()
+        .take(samples.try_into().unwrap_or(usize::MAX))
+        .enumerate()
+    {
+        peer.peer_id = PeerId(peer_id_bytes(i));
+
+        let start = Instant::now();
+        torrent_repository.upsert_peer(info_hash, &peer);
+        latencies.push(start.elapsed());
+    }
+
+    stop.store(true, Ordering::Relaxed);
+    cleaner.join().expect("the cleanup thread should finish");
+
+    latencies
+}
+
+criterion_group!(
+    benches,
+    remove_inactive_peers,
+    announce_during_cleanup,
+    slowest_announce_during_cleanup
+);
+criterion_main!(benches);
@@ -0,0 +1,179 @@
+//! It measures the heap memory used per peer by the peer lists and by the
+//! [`ExpiryWheel`], and the cost of getting the peers for an announce
+//! response.
+//!
+//! It compares the packed [`PeerList`] with the previous storage, a
+//! `BTreeMap<PeerId, Arc<Peer>>`, for swarms of IPv4 and IPv6 peers. The
+//! previous storage only clones the stored `Arc`s for a response, while the
+//! packed list allocates a new `Arc` for each returned peer.
+//!
+//! The expiry wheel keeps one key per peer on top of the peer list, so its
+//! memory has to be added to the memory of the peer list.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use aquatic_udp_protocol::PeerId;
+use bittorrent_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::peer::Peer;
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+use torrust_tracker_torrent_repository::entry::peer_list::PeerList;
+use torrust_tracker_torrent_repository::expiry::{ExpiryKey, ExpiryWheel};
+
+const PEERS: usize = 100_000;
+
+/// The number of peers in an announce response, the default `numwant`.
+const RESPONSE_PEERS: usize = 74;
+
+/// The number of announce responses measured.
+const RESPONSES: usize = 100_000;
+
+/// A global allocator that keeps track of the bytes currently allocated and
+/// of the number of allocations.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn main() {
+    let ipv4 = IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1));
+    let ipv6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+
+    for (family, ip) in [("IPv4", ipv4), ("IPv6", ipv6)] {
+        let peers = generate_peers(ip);
+
+        let arc_peer_list = || {
+            let mut peer_list = BTreeMap::new();
+            for peer in &peers {
+                peer_list.insert(peer.peer_id, Arc::new(*peer));
+            }
+            peer_list
+        };
+
+        let packed_peer_list = || {
+            let mut peer_list = PeerList::default();
+            for peer in &peers {
+                peer_list.upsert(*peer);
+            }
+            peer_list
+        };
+
+        let arc_peers = bytes_per_peer(arc_peer_list);
+        let packed_peers = bytes_per_peer(packed_peer_list);
+
+        println!("peer_list_memory/{family}/ArcPeer");
+        println!("                        bytes per peer: {arc_peers:.1}");
+        println!("peer_list_memory/{family}/PackedPeer");
+        println!("                        bytes per peer: {packed_peers:.1}");
+
+        let peer_list = arc_peer_list();
+        let (arc_allocations, arc_nanos) =
+            per_response(|| peer_list.values().take(RESPONSE_PEERS).cloned().collect::<Vec<Arc<Peer>>>());
+        drop(peer_list);
+
+        let peer_list = packed_peer_list();
+        let (packed_allocations, packed_nanos) = per_response(|| peer_list.get_all(Some(RESPONSE_PEERS)));
+        drop(peer_list);
+
+        println!("announce_response/{family}/ArcPeer");
+        println!("                        allocations per response: {arc_allocations:.1}");
+        println!("                        time per response: {arc_nanos:.0} ns");
+        println!("announce_response/{family}/PackedPeer");
+        println!("                        allocations per response: {packed_allocations:.1}");
+        println!("                        time per response: {packed_nanos:.0} ns");
+    }
+
+    let peers = generate_peers(ipv4);
+
+    // It includes the fixed cost of the empty slots.
+    let expiry_wheel = bytes_per_peer(|| {
+        let wheel = ExpiryWheel::default();
+        for peer in &peers {
+            let key = ExpiryKey {
+                info_hash: InfoHash::default(),
+                peer_id: peer.peer_id,
+            };
+            wheel.schedule(key, peer.updated, None);
+        }
+        wheel
+    });
+
+    println!("expiry_wheel_memory");
+    println!("                        bytes per peer: {expiry_wheel:.1}");
+}
+
+/// It returns the allocations and the nanoseconds it takes to get the peers
+/// of one announce response.
+#[allow(clippy::cast_precision_loss)]
+fn per_response<T, F>(get_peers: F) -> (f64, f64)
+where
+    F: Fn() -> T,
+{
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+
+    for _ in 0..RESPONSES {
+        black_box(get_peers());
+    }
+
+    let elapsed = start.elapsed();
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+
+    (
+        (after - before) as f64 / RESPONSES as f64,
+        elapsed.as_nanos() as f64 / RESPONSES as f64,
+    )
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn bytes_per_peer<T, F>(build: F) -> f64
+where
+    F: FnOnce() -> T,
+{
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let peer_list = build();
+    let after = ALLOCATED.load(Ordering::Relaxed);
+
+    drop(peer_list);
+
+    (after - before) as f64 / PEERS as f64
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn generate_peers(ip: IpAddr) -> Vec<Peer> {
+    (0..PEERS)
+        .map(|i| {
+            let mut peer = Peer::default();
+
+            let mut peer_id = [0u8; 20];
+            peer_id[..8].copy_from_slice(&(i as u64).to_be_bytes());
+
+            peer.peer_id = PeerId(peer_id);
+            peer.peer_addr = SocketAddr::new(ip, (i % 65_535) as u16 + 1);
+            peer.updated = DurationSinceUnixEpoch::new(1_669_397_478 + i as u64, 934);
+
+            peer
+        })
+        .collect()
+}
@@ -2,19 +2,27 @@ use std::fmt::Debug;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use aquatic_udp_protocol::PeerId;
 use torrust_tracker_configuration::TrackerPolicy;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
 
 use self::peer_list::PeerList;
+use crate::expiry::PeerExpiry;
+use crate::limits::{PeerChanges, PeerQuota};
 
 pub mod mutex_parking_lot;
 pub mod mutex_std;
 pub mod mutex_tokio;
+pub mod packed_peer;
 pub mod peer_list;
 pub mod rw_lock_parking_lot;
 pub mod single;
 
+/// A condition on the peers returned by
+/// [`get_peers_for_client_matching`](Entry::get_peers_for_client_matching).
+pub type PeerPredicate<'a> = dyn Fn(&peer::Peer) -> bool + Sync + 'a;
+
 pub trait Entry {
     /// It returns the swarm metadata (statistics) as a struct:
     ///
@@ -40,14 +48,65 @@ pub trait Entry {
     /// list of peers to that client peer.
     fn get_peers_for_client(&self, client: &SocketAddr, limit: Option<usize>) -> Vec<Arc<peer::Peer>>;
 
+    /// It returns the list of peers for a given peer client like
+    /// [`get_peers_for_client`](Entry::get_peers_for_client), but only the
+    /// peers that match the predicate.
+    ///
+    /// The limit is applied after filtering, so the result is not shorter
+    /// than the limit while there are matching peers in the swarm.
+    fn get_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        limit: Option<usize>,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>>;
+
+    /// It returns a uniform random sample of up to `amount` peers for a given
+    /// peer client, taken from the peers that match the predicate.
+    fn sample_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        amount: usize,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>>;
+
+    /// It returns the peer with the given ID, if it's in the swarm.
+    fn get_peer(&self, peer_id: &PeerId) -> Option<peer::Peer>;
+
     /// It updates a peer and returns true if the number of complete downloads have increased.
     ///
     /// The number of peers that have complete downloading is synchronously updated when peers are updated.
     /// That's the total torrent downloads counter.
     fn upsert_peer(&mut self, peer: &peer::Peer) -> bool;
 
+    /// It updates a peer like [`upsert_peer`](Entry::upsert_peer) but without
+    /// exceeding the given quota for new peers.
+    ///
+    /// Depending on the quota eviction policy, a new peer that does not fit
+    /// in the swarm is either refused or it replaces the oldest peer in the
+    /// swarm. Likewise, a new peer whose IP address already has the maximum
+    /// number of peers is either refused or it replaces the oldest peer with
+    /// the same IP address. It returns the changes in the swarm.
+    fn upsert_peer_within_quota(&mut self, peer: &peer::Peer, quota: &PeerQuota) -> PeerChanges;
+
+    /// It returns the last time a peer in the swarm was updated, or zero if
+    /// the swarm is empty.
+    fn get_last_activity(&self) -> DurationSinceUnixEpoch;
+
     /// It removes peer from the swarm that have not been updated for more than `current_cutoff` seconds
-    fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch);
+    ///
+    /// It returns the removed peers.
+    fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch) -> Vec<peer::Peer>;
+
+    /// It removes a single peer from the swarm if it has not been updated
+    /// after the `current_cutoff`.
+    fn remove_peer_if_inactive(&mut self, peer_id: &PeerId, current_cutoff: DurationSinceUnixEpoch) -> PeerExpiry;
+
+    /// It removes a single peer from the swarm, regardless of its activity.
+    /// It returns the removed peer, or `None` if it was not in the swarm.
+    ///
+    /// The number of completed downloads is not changed.
+    fn remove_peer(&mut self, peer_id: &PeerId) -> Option<peer::Peer>;
 }
 
 #[allow(clippy::module_name_repetitions)]
@@ -58,8 +117,25 @@ pub trait EntrySync {
     fn get_peers_len(&self) -> usize;
     fn get_peers(&self, limit: Option<usize>) -> Vec<Arc<peer::Peer>>;
     fn get_peers_for_client(&self, client: &SocketAddr, limit: Option<usize>) -> Vec<Arc<peer::Peer>>;
+    fn get_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        limit: Option<usize>,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>>;
+    fn sample_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        amount: usize,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>>;
+    fn get_peer(&self, peer_id: &PeerId) -> Option<peer::Peer>;
     fn upsert_peer(&self, peer: &peer::Peer) -> bool;
-    fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch);
+    fn upsert_peer_within_quota(&self, peer: &peer::Peer, quota: &PeerQuota) -> PeerChanges;
+    fn get_last_activity(&self) -> DurationSinceUnixEpoch;
+    fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) -> Vec<peer::Peer>;
+    fn remove_peer_if_inactive(&self, peer_id: &PeerId, current_cutoff: DurationSinceUnixEpoch) -> PeerExpiry;
+    fn remove_peer(&self, peer_id: &PeerId) -> Option<peer::Peer>;
 }
 
 #[allow(clippy::module_name_repetitions)]
@@ -74,8 +150,36 @@ pub trait EntryAsync {
         client: &SocketAddr,
         limit: Option<usize>,
     ) -> impl std::future::Future<Output = Vec<Arc<peer::Peer>>> + Send;
+    fn get_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        limit: Option<usize>,
+        predicate: &PeerPredicate<'_>,
+    ) -> impl std::future::Future<Output = Vec<Arc<peer::Peer>>> + Send;
+    fn sample_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        amount: usize,
+        predicate: &PeerPredicate<'_>,
+    ) -> impl std::future::Future<Output = Vec<Arc<peer::Peer>>> + Send;
+    fn get_peer(&self, peer_id: &PeerId) -> impl std::future::Future<Output = Option<peer::Peer>> + Send;
     fn upsert_peer(self, peer: &peer::Peer) -> impl std::future::Future<Output = bool> + Send;
-    fn remove_inactive_peers(self, current_cutoff: DurationSinceUnixEpoch) -> impl std::future::Future<Output = ()> + Send;
+    fn upsert_peer_within_quota(
+        self,
+        peer: &peer::Peer,
+        quota: &PeerQuota,
+    ) -> impl std::future::Future<Output = PeerChanges> + Send;
+    fn get_last_activity(&self) -> impl std::future::Future<Output = DurationSinceUnixEpoch> + Send;
+    fn remove_inactive_peers(
+        self,
+        current_cutoff: DurationSinceUnixEpoch,
+    ) -> impl std::future::Future<Output = Vec<peer::Peer>> + Send;
+    fn remove_peer_if_inactive(
+        self,
+        peer_id: &PeerId,
+        current_cutoff: DurationSinceUnixEpoch,
+    ) -> impl std::future::Future<Output = PeerExpiry> + Send;
+    fn remove_peer(self, peer_id: &PeerId) -> impl std::future::Future<Output = Option<peer::Peer>> + Send;
 }
 
 /// A data structure containing all the information about a torrent in the tracker.
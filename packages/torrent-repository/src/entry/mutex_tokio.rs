@@ -1,11 +1,14 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use aquatic_udp_protocol::PeerId;
 use torrust_tracker_configuration::TrackerPolicy;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
 
-use super::{Entry, EntryAsync};
+use super::{Entry, EntryAsync, PeerPredicate};
+use crate::expiry::PeerExpiry;
+use crate::limits::{PeerChanges, PeerQuota};
 use crate::{EntryMutexTokio, EntrySingle};
 
 impl EntryAsync for EntryMutexTokio {
@@ -33,12 +36,50 @@ impl EntryAsync for EntryMutexTokio {
         self.lock().await.get_peers_for_client(client, limit)
     }
 
+    async fn get_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        limit: Option<usize>,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        self.lock().await.get_peers_for_client_matching(client, limit, predicate)
+    }
+
+    async fn sample_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        amount: usize,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        self.lock().await.sample_peers_for_client_matching(client, amount, predicate)
+    }
+
+    async fn get_peer(&self, peer_id: &PeerId) -> Option<peer::Peer> {
+        self.lock().await.get_peer(peer_id)
+    }
+
     async fn upsert_peer(self, peer: &peer::Peer) -> bool {
         self.lock().await.upsert_peer(peer)
     }
 
-    async fn remove_inactive_peers(self, current_cutoff: DurationSinceUnixEpoch) {
-        self.lock().await.remove_inactive_peers(current_cutoff);
+    async fn upsert_peer_within_quota(self, peer: &peer::Peer, quota: &PeerQuota) -> PeerChanges {
+        self.lock().await.upsert_peer_within_quota(peer, quota)
+    }
+
+    async fn get_last_activity(&self) -> DurationSinceUnixEpoch {
+        self.lock().await.get_last_activity()
+    }
+
+    async fn remove_inactive_peers(self, current_cutoff: DurationSinceUnixEpoch) -> Vec<peer::Peer> {
+        self.lock().await.remove_inactive_peers(current_cutoff)
+    }
+
+    async fn remove_peer_if_inactive(self, peer_id: &PeerId, current_cutoff: DurationSinceUnixEpoch) -> PeerExpiry {
+        self.lock().await.remove_peer_if_inactive(peer_id, current_cutoff)
+    }
+
+    async fn remove_peer(self, peer_id: &PeerId) -> Option<peer::Peer> {
+        self.lock().await.remove_peer(peer_id)
     }
 }
 
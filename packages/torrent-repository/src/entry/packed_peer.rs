@@ -0,0 +1,204 @@
+//! A compact representation of a peer for the peer lists.
+//!
+//! A [`peer::Peer`] uses a full [`SocketAddr`] (big enough for IPv6 even for
+//! IPv4 peers) and a [`DurationSinceUnixEpoch`] for the last update time. The
+//! [`PackedPeer`] is generic over the IP address type, so IPv4 peers only
+//! use four bytes for the IP, and it stores the last update time relative to
+//! the swarm epoch (see [`PeerList`](super::peer_list::PeerList)).
+//!
+//! The peer ID is not stored in the record because it's the key in the peer
+//! list.
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes, PeerId};
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+
+/// A timestamp relative to an epoch (in seconds).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RelativeTime {
+    secs: u32,
+    nanos: u32,
+}
+
+impl RelativeTime {
+    /// It converts an absolute time into a time relative to the `epoch`.
+    ///
+    /// The `time` must not be before the `epoch`. Times more than `u32::MAX`
+    /// seconds (about 136 years) after the epoch are saturated.
+    #[must_use]
+    pub fn new(time: DurationSinceUnixEpoch, epoch: u64) -> Self {
+        Self {
+            secs: u32::try_from(time.as_secs().saturating_sub(epoch)).unwrap_or(u32::MAX),
+            nanos: time.subsec_nanos(),
+        }
+    }
+
+    /// It converts the relative time back into an absolute time.
+    #[must_use]
+    pub fn to_absolute(self, epoch: u64) -> DurationSinceUnixEpoch {
+        DurationSinceUnixEpoch::new(epoch + u64::from(self.secs), self.nanos)
+    }
+
+    /// It moves the relative time to an earlier epoch, `shift` seconds before
+    /// the current one.
+    #[must_use]
+    pub fn shifted(self, shift: u64) -> Self {
+        Self {
+            secs: u32::try_from(u64::from(self.secs) + shift).unwrap_or(u32::MAX),
+            nanos: self.nanos,
+        }
+    }
+}
+
+/// A peer record without the peer ID.
+///
+/// `A` is the IP address type: [`Ipv4Addr`] or [`Ipv6Addr`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PackedPeer<A> {
+    ip: A,
+    port: u16,
+    event: AnnounceEvent,
+    uploaded: NumberOfBytes,
+    downloaded: NumberOfBytes,
+    left: NumberOfBytes,
+    updated: RelativeTime,
+}
+
+impl<A> PackedPeer<A>
+where
+    A: Copy + Into<IpAddr>,
+{
+    #[must_use]
+    pub fn new(ip: A, peer: &peer::Peer, epoch: u64) -> Self {
+        Self {
+            ip,
+            port: peer.peer_addr.port(),
+            event: peer.event,
+            uploaded: peer.uploaded,
+            downloaded: peer.downloaded,
+            left: peer.left,
+            updated: RelativeTime::new(peer.updated, epoch),
+        }
+    }
+
+    /// It rebuilds the full peer.
+    #[must_use]
+    pub fn unpack(&self, peer_id: PeerId, epoch: u64) -> peer::Peer {
+        peer::Peer {
+            peer_id,
+            peer_addr: self.address(),
+            updated: self.updated(epoch),
+            uploaded: self.uploaded,
+            downloaded: self.downloaded,
+            left: self.left,
+            event: self.event,
+        }
+    }
+
+    #[must_use]
+    pub fn address(&self) -> SocketAddr {
+        SocketAddr::new(self.ip.into(), self.port)
+    }
+
+    #[must_use]
+    pub fn updated(&self, epoch: u64) -> DurationSinceUnixEpoch {
+        self.updated.to_absolute(epoch)
+    }
+
+    /// It moves the last update time to an earlier epoch, `shift` seconds
+    /// before the current one.
+    pub fn shift_epoch(&mut self, shift: u64) {
+        self.updated = self.updated.shifted(shift);
+    }
+
+    #[must_use]
+    pub fn is_seeder(&self) -> bool {
+        self.left.0.get() <= 0 && self.event != AnnounceEvent::Stopped
+    }
+
+    #[must_use]
+    pub fn event(&self) -> AnnounceEvent {
+        self.event
+    }
+}
+
+/// A packed peer with its IP family.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PackedPeerRecord {
+    V4(PackedPeer<Ipv4Addr>),
+    V6(PackedPeer<Ipv6Addr>),
+}
+
+impl PackedPeerRecord {
+    #[must_use]
+    pub fn new(peer: &peer::Peer, epoch: u64) -> Self {
+        match peer.peer_addr.ip() {
+            IpAddr::V4(ip) => Self::V4(PackedPeer::new(ip, peer, epoch)),
+            IpAddr::V6(ip) => Self::V6(PackedPeer::new(ip, peer, epoch)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod a_packed_peer {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+        use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+        use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+        use crate::entry::packed_peer::{PackedPeer, PackedPeerRecord};
+
+        #[test]
+        fn should_be_unpacked_into_the_original_ipv4_peer() {
+            let peer = PeerBuilder::default()
+                .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080))
+                .last_updated_on(DurationSinceUnixEpoch::new(1_669_397_478, 934))
+                .build();
+
+            let epoch = 1_669_397_000;
+
+            let PackedPeerRecord::V4(packed) = PackedPeerRecord::new(&peer, epoch) else {
+                panic!("it should pack an IPv4 peer");
+            };
+
+            assert_eq!(packed.unpack(peer.peer_id, epoch), peer);
+        }
+
+        #[test]
+        fn should_be_unpacked_into_the_original_ipv6_peer() {
+            let peer = PeerBuilder::default()
+                .with_peer_addr(&SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8080))
+                .build();
+
+            let epoch = peer.updated.as_secs();
+
+            let PackedPeerRecord::V6(packed) = PackedPeerRecord::new(&peer, epoch) else {
+                panic!("it should pack an IPv6 peer");
+            };
+
+            assert_eq!(packed.unpack(peer.peer_id, epoch), peer);
+        }
+
+        #[test]
+        fn should_keep_the_last_update_time_when_the_epoch_is_moved_back() {
+            let peer = PeerBuilder::default()
+                .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080))
+                .last_updated_on(DurationSinceUnixEpoch::new(1_669_397_478, 934))
+                .build();
+
+            let mut packed = PackedPeer::new(Ipv4Addr::new(126, 0, 0, 1), &peer, 1_669_397_000);
+
+            packed.shift_epoch(1000);
+
+            assert_eq!(packed.updated(1_669_396_000), peer.updated);
+        }
+
+        #[test]
+        fn should_use_less_memory_than_a_full_peer() {
+            assert!(size_of::<PackedPeer<Ipv4Addr>>() < size_of::<torrust_tracker_primitives::peer::Peer>() / 2);
+            assert!(size_of::<PackedPeer<Ipv6Addr>>() < size_of::<torrust_tracker_primitives::peer::Peer>());
+        }
+    }
+}
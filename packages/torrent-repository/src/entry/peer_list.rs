@@ -1,60 +1,216 @@
 //! A peer list.
-use std::net::SocketAddr;
+//!
+//! Peers are stored as [`PackedPeer`] records to reduce the memory used per
+//! peer. IPv4 and IPv6 peers are kept in separate maps so that IPv4 peers do
+//! not pay for the size of an IPv6 address, and the last update times are
+//! stored relative to the swarm `epoch`: the oldest last update time (in
+//! seconds) in the list.
+//!
+//! Peers are converted back to [`peer::Peer`] only when they leave the list
+//! (for example, to be included in an announce response). That costs one
+//! `Arc` allocation per returned peer, where the previous storage only
+//! cloned the stored `Arc`s. The `memory_benchmark` measures both sides of
+//! the trade-off: the memory used per peer and the time it takes to collect
+//! the peers of an announce response.
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::ops::{Bound, RangeInclusive};
 use std::sync::Arc;
 
 use aquatic_udp_protocol::PeerId;
+use rand::seq::IteratorRandom;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
 
+use super::packed_peer::{PackedPeer, PackedPeerRecord};
+use super::PeerPredicate;
+use crate::expiry::PeerExpiry;
+
 // code-review: the current implementation uses the peer Id as the ``BTreeMap``
 // key. That would allow adding two identical peers except for the Id.
 // For example, two peers with the same socket address but a different peer Id
 // would be allowed. That would lead to duplicated peers in the tracker responses.
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The peers of a swarm.
+///
+/// Two lists are equal when they contain the same peers. `PartialEq`, `Eq`,
+/// `Ord` and `Hash` are implemented by hand, over the peers, because the
+/// derived ones would be wrong: the same peers are stored with different
+/// relative update times depending on the `epoch`, and the `hosts` index only
+/// exists when the peers per host are limited.
+#[derive(Clone, Debug, Default)]
 pub struct PeerList {
-    peers: std::collections::BTreeMap<PeerId, Arc<peer::Peer>>,
+    ipv4: BTreeMap<PeerId, PackedPeer<Ipv4Addr>>,
+    ipv6: BTreeMap<PeerId, PackedPeer<Ipv6Addr>>,
+    /// The relative last update times of the peers are seconds after this epoch.
+    epoch: u64,
+    /// The peers by IP address. It's only built when the peers are counted
+    /// by IP address, to limit the peers per host.
+    hosts: Option<BTreeSet<(IpAddr, PeerId)>>,
 }
 
 impl PeerList {
     #[must_use]
     pub fn len(&self) -> usize {
-        self.peers.len()
+        self.ipv4.len() + self.ipv6.len()
     }
 
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.peers.is_empty()
+        self.ipv4.is_empty() && self.ipv6.is_empty()
+    }
+
+    /// It inserts or updates a peer and returns the previous version of the
+    /// peer, if there was one.
+    pub fn upsert(&mut self, peer: peer::Peer) -> Option<peer::Peer> {
+        self.make_epoch_fit(peer.updated);
+
+        let previous = self.remove(&peer.peer_id);
+
+        if let Some(hosts) = &mut self.hosts {
+            hosts.insert((peer.peer_addr.ip(), peer.peer_id));
+        }
+
+        match PackedPeerRecord::new(&peer, self.epoch) {
+            PackedPeerRecord::V4(packed) => drop(self.ipv4.insert(peer.peer_id, packed)),
+            PackedPeerRecord::V6(packed) => drop(self.ipv6.insert(peer.peer_id, packed)),
+        }
+
+        previous
+    }
+
+    pub fn remove(&mut self, key: &PeerId) -> Option<peer::Peer> {
+        let removed = match self.ipv4.remove(key) {
+            Some(packed) => Some(packed.unpack(*key, self.epoch)),
+            None => self.ipv6.remove(key).map(|packed| packed.unpack(*key, self.epoch)),
+        };
+
+        if let (Some(hosts), Some(peer)) = (&mut self.hosts, &removed) {
+            hosts.remove(&(peer.peer_addr.ip(), peer.peer_id));
+        }
+
+        removed
+    }
+
+    /// It removes the peers that have not been updated after the
+    /// `current_cutoff` and returns them.
+    pub fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch) -> Vec<peer::Peer> {
+        let epoch = self.epoch;
+        let hosts = &mut self.hosts;
+        let mut removed = vec![];
+
+        let mut is_active = |peer: peer::Peer| {
+            let is_active = peer.updated > current_cutoff;
+
+            if !is_active {
+                if let Some(hosts) = hosts.as_mut() {
+                    hosts.remove(&(peer.peer_addr.ip(), peer.peer_id));
+                }
+
+                removed.push(peer);
+            }
+
+            is_active
+        };
+
+        self.ipv4.retain(|id, peer| is_active(peer.unpack(*id, epoch)));
+        self.ipv6.retain(|id, peer| is_active(peer.unpack(*id, epoch)));
+
+        removed
+    }
+
+    /// It removes the peer if it has not been updated after the `current_cutoff`.
+    pub fn remove_if_inactive(&mut self, peer_id: &PeerId, current_cutoff: DurationSinceUnixEpoch) -> PeerExpiry {
+        match self.get_updated(peer_id) {
+            None => PeerExpiry::Missing,
+            Some(updated) if updated > current_cutoff => PeerExpiry::Active(updated),
+            Some(_) => match self.remove(peer_id) {
+                Some(removed) => PeerExpiry::Removed(removed),
+                None => PeerExpiry::Missing,
+            },
+        }
+    }
+
+    /// It removes the peer that has not been updated for the longest time.
+    pub fn remove_oldest(&mut self) -> Option<peer::Peer> {
+        let epoch = self.epoch;
+
+        let oldest_ipv4 = self.ipv4.iter().map(|(id, peer)| (peer.updated(epoch), *id)).min();
+        let oldest_ipv6 = self.ipv6.iter().map(|(id, peer)| (peer.updated(epoch), *id)).min();
+
+        let (_updated, oldest) = oldest_ipv4.into_iter().chain(oldest_ipv6).min()?;
+
+        self.remove(&oldest)
     }
 
-    pub fn upsert(&mut self, value: Arc<peer::Peer>) -> Option<Arc<peer::Peer>> {
-        self.peers.insert(value.peer_id, value)
+    /// It removes the peer that has not been updated for the longest time
+    /// among the peers whose IP address is in the range.
+    pub fn remove_oldest_with_ip_in(&mut self, ips: &RangeInclusive<IpAddr>) -> Option<peer::Peer> {
+        let ids: Vec<PeerId> = self.peers_with_ip_in(ips).collect();
+
+        let (_updated, oldest) = ids
+            .into_iter()
+            .filter_map(|id| self.get_updated(&id).map(|updated| (updated, id)))
+            .min()?;
+
+        self.remove(&oldest)
     }
 
-    pub fn remove(&mut self, key: &PeerId) -> Option<Arc<peer::Peer>> {
-        self.peers.remove(key)
+    /// The number of peers whose IP address is in the range.
+    ///
+    /// The first call builds an index of the peers by IP address, which is
+    /// kept up to date afterwards, so the cost only depends on the number of
+    /// peers in the range.
+    pub fn count_with_ip_in(&mut self, ips: &RangeInclusive<IpAddr>) -> usize {
+        self.peers_with_ip_in(ips).count()
+    }
+
+    /// The IDs of the peers whose IP address is in the range.
+    fn peers_with_ip_in(&mut self, ips: &RangeInclusive<IpAddr>) -> impl Iterator<Item = PeerId> + '_ {
+        let hosts = self.hosts.get_or_insert_with(|| {
+            self.ipv4
+                .iter()
+                .map(|(id, peer)| (peer.address().ip(), *id))
+                .chain(self.ipv6.iter().map(|(id, peer)| (peer.address().ip(), *id)))
+                .collect()
+        });
+
+        let first = Bound::Included((*ips.start(), PeerId([0; 20])));
+        let last = Bound::Included((*ips.end(), PeerId([u8::MAX; 20])));
+
+        hosts.range((first, last)).map(|(_ip, id)| *id)
     }
 
-    pub fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch) {
-        self.peers
-            .retain(|_, peer| peer::ReadInfo::get_updated(peer) > current_cutoff);
+    /// The last time any peer in the list was updated.
+    #[must_use]
+    pub fn last_updated(&self) -> Option<DurationSinceUnixEpoch> {
+        let epoch = self.epoch;
+
+        self.ipv4
+            .values()
+            .map(|peer| peer.updated(epoch))
+            .chain(self.ipv6.values().map(|peer| peer.updated(epoch)))
+            .max()
     }
 
     #[must_use]
-    pub fn get(&self, peer_id: &PeerId) -> Option<&Arc<peer::Peer>> {
-        self.peers.get(peer_id)
+    pub fn get(&self, peer_id: &PeerId) -> Option<peer::Peer> {
+        self.ipv4
+            .get(peer_id)
+            .map(|peer| peer.unpack(*peer_id, self.epoch))
+            .or_else(|| self.ipv6.get(peer_id).map(|peer| peer.unpack(*peer_id, self.epoch)))
     }
 
     #[must_use]
     pub fn get_all(&self, limit: Option<usize>) -> Vec<Arc<peer::Peer>> {
-        match limit {
-            Some(limit) => self.peers.values().take(limit).cloned().collect(),
-            None => self.peers.values().cloned().collect(),
-        }
+        self.collect(self.iter(), limit)
     }
 
     #[must_use]
     pub fn seeders_and_leechers(&self) -> (usize, usize) {
-        let seeders = self.peers.values().filter(|peer| peer.is_seeder()).count();
+        let seeders = self.ipv4.values().filter(|peer| peer.is_seeder()).count()
+            + self.ipv6.values().filter(|peer| peer.is_seeder()).count();
         let leechers = self.len() - seeders;
 
         (seeders, leechers)
@@ -62,23 +218,134 @@ impl PeerList {
 
     #[must_use]
     pub fn get_peers_excluding_addr(&self, peer_addr: &SocketAddr, limit: Option<usize>) -> Vec<Arc<peer::Peer>> {
-        match limit {
-            Some(limit) => self
-                .peers
-                .values()
-                // Take peers which are not the client peer
-                .filter(|peer| peer::ReadInfo::get_address(peer.as_ref()) != *peer_addr)
-                // Limit the number of peers on the result
-                .take(limit)
-                .cloned()
-                .collect(),
-            None => self
-                .peers
-                .values()
-                // Take peers which are not the client peer
-                .filter(|peer| peer::ReadInfo::get_address(peer.as_ref()) != *peer_addr)
-                .cloned()
-                .collect(),
+        // Take peers which are not the client peer
+        let peers = self.iter().filter(|peer| peer::ReadInfo::get_address(peer) != *peer_addr);
+
+        self.collect(peers, limit)
+    }
+
+    /// Like [`get_peers_excluding_addr`](Self::get_peers_excluding_addr), but
+    /// it only takes the peers that match the predicate.
+    #[must_use]
+    pub fn get_peers_excluding_addr_matching(
+        &self,
+        peer_addr: &SocketAddr,
+        limit: Option<usize>,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        let peers = self
+            .iter()
+            .filter(|peer| peer::ReadInfo::get_address(peer) != *peer_addr && predicate(peer));
+
+        self.collect(peers, limit)
+    }
+
+    /// Like [`get_peers_excluding_addr_matching`](Self::get_peers_excluding_addr_matching),
+    /// but it takes a uniform random sample of up to `amount` peers instead of
+    /// the first ones.
+    ///
+    /// The whole list is visited, but only the sampled peers are allocated.
+    /// The sample is not shuffled.
+    #[must_use]
+    pub fn sample_peers_excluding_addr_matching(
+        &self,
+        peer_addr: &SocketAddr,
+        amount: usize,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        let peers = self
+            .iter()
+            .filter(|peer| peer::ReadInfo::get_address(peer) != *peer_addr && predicate(peer));
+
+        peers
+            .choose_multiple(&mut rand::rng(), amount)
+            .into_iter()
+            .map(Arc::new)
+            .collect()
+    }
+
+    /// It collects up to `limit` peers into a vector allocated once.
+    fn collect(&self, peers: impl Iterator<Item = peer::Peer>, limit: Option<usize>) -> Vec<Arc<peer::Peer>> {
+        let limit = limit.unwrap_or(usize::MAX);
+
+        let mut collected = Vec::with_capacity(limit.min(self.len()));
+
+        collected.extend(peers.take(limit).map(Arc::new));
+
+        collected
+    }
+
+    /// It iterates over all the peers (IPv4 and IPv6) ordered by peer ID.
+    fn iter(&self) -> impl Iterator<Item = peer::Peer> + '_ {
+        let epoch = self.epoch;
+
+        let mut ipv4 = self.ipv4.iter().map(move |(id, peer)| peer.unpack(*id, epoch)).peekable();
+        let mut ipv6 = self.ipv6.iter().map(move |(id, peer)| peer.unpack(*id, epoch)).peekable();
+
+        std::iter::from_fn(move || match (ipv4.peek(), ipv6.peek()) {
+            (Some(v4), Some(v6)) if v6.peer_id < v4.peer_id => ipv6.next(),
+            (Some(_), _) => ipv4.next(),
+            (None, _) => ipv6.next(),
+        })
+    }
+
+    /// The last time the peer was updated, if it's in the list.
+    #[must_use]
+    pub fn get_updated(&self, peer_id: &PeerId) -> Option<DurationSinceUnixEpoch> {
+        self.ipv4
+            .get(peer_id)
+            .map(|peer| peer.updated(self.epoch))
+            .or_else(|| self.ipv6.get(peer_id).map(|peer| peer.updated(self.epoch)))
+    }
+
+    /// It moves the epoch back when a peer was updated before it, so that
+    /// all the relative times are positive.
+    fn make_epoch_fit(&mut self, updated: DurationSinceUnixEpoch) {
+        let updated = updated.as_secs();
+
+        if self.is_empty() {
+            self.epoch = updated;
+            return;
+        }
+
+        if updated < self.epoch {
+            let shift = self.epoch - updated;
+
+            self.ipv4.values_mut().for_each(|peer| peer.shift_epoch(shift));
+            self.ipv6.values_mut().for_each(|peer| peer.shift_epoch(shift));
+
+            self.epoch = updated;
+        }
+    }
+}
+
+impl PartialEq for PeerList {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for PeerList {}
+
+impl PartialOrd for PeerList {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PeerList {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl Hash for PeerList {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+
+        for peer in self.iter() {
+            peer.peer_id.hash(state);
+            peer.hash(state);
         }
     }
 }
@@ -87,7 +354,7 @@ impl PeerList {
 mod tests {
 
     mod it_should {
-        use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
         use std::sync::Arc;
 
         use aquatic_udp_protocol::PeerId;
@@ -116,7 +383,7 @@ mod tests {
 
             let peer = PeerBuilder::default().build();
 
-            assert_eq!(peer_list.upsert(peer.into()), None);
+            assert_eq!(peer_list.upsert(peer), None);
         }
 
         #[test]
@@ -125,9 +392,9 @@ mod tests {
 
             let peer = PeerBuilder::default().build();
 
-            peer_list.upsert(peer.into());
+            peer_list.upsert(peer);
 
-            assert_eq!(peer_list.upsert(peer.into()), Some(Arc::new(peer)));
+            assert_eq!(peer_list.upsert(peer), Some(peer));
         }
 
         #[test]
@@ -136,7 +403,7 @@ mod tests {
 
             let peer = PeerBuilder::default().build();
 
-            peer_list.upsert(peer.into());
+            peer_list.upsert(peer);
 
             assert_eq!(peer_list.get_all(None), [Arc::new(peer)]);
         }
@@ -147,9 +414,9 @@ mod tests {
 
             let peer = PeerBuilder::default().build();
 
-            peer_list.upsert(peer.into());
+            peer_list.upsert(peer);
 
-            assert_eq!(peer_list.get(&peer.peer_id), Some(Arc::new(peer)).as_ref());
+            assert_eq!(peer_list.get(&peer.peer_id), Some(peer));
         }
 
         #[test]
@@ -158,7 +425,7 @@ mod tests {
 
             let peer = PeerBuilder::default().build();
 
-            peer_list.upsert(peer.into());
+            peer_list.upsert(peer);
 
             assert_eq!(peer_list.len(), 1);
         }
@@ -169,7 +436,7 @@ mod tests {
 
             let peer = PeerBuilder::default().build();
 
-            peer_list.upsert(peer.into());
+            peer_list.upsert(peer);
 
             peer_list.remove(&peer.peer_id);
 
@@ -182,7 +449,7 @@ mod tests {
 
             let peer = PeerBuilder::default().build();
 
-            peer_list.upsert(peer.into());
+            peer_list.upsert(peer);
 
             peer_list.remove(&peer.peer_id);
 
@@ -197,17 +464,45 @@ mod tests {
                 .with_peer_id(&PeerId(*b"-qB00000000000000001"))
                 .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 6969))
                 .build();
-            peer_list.upsert(peer1.into());
+            peer_list.upsert(peer1);
 
             let peer2 = PeerBuilder::default()
                 .with_peer_id(&PeerId(*b"-qB00000000000000002"))
                 .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), 6969))
                 .build();
-            peer_list.upsert(peer2.into());
+            peer_list.upsert(peer2);
 
             assert_eq!(peer_list.get_peers_excluding_addr(&peer2.peer_addr, None), [Arc::new(peer1)]);
         }
 
+        #[test]
+        fn allow_sampling_the_peers_excluding_peers_with_a_given_address() {
+            let mut peer_list = PeerList::default();
+
+            for n in 0..10u8 {
+                let mut peer_id = *b"-qB00000000000000000";
+                peer_id[19] = n;
+
+                peer_list.upsert(
+                    PeerBuilder::default()
+                        .with_peer_id(&PeerId(peer_id))
+                        .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, n)), 6969))
+                        .build(),
+                );
+            }
+
+            let client = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 0)), 6969);
+
+            let sample = peer_list.sample_peers_excluding_addr_matching(&client, 4, &|_peer| true);
+
+            assert_eq!(sample.len(), 4);
+            assert!(sample.iter().all(|peer| peer.peer_addr != client));
+
+            let all = peer_list.sample_peers_excluding_addr_matching(&client, 20, &|_peer| true);
+
+            assert_eq!(all.len(), 9);
+        }
+
         #[test]
         fn return_the_number_of_seeders_in_the_list() {
             let mut peer_list = PeerList::default();
@@ -215,8 +510,8 @@ mod tests {
             let seeder = PeerBuilder::seeder().build();
             let leecher = PeerBuilder::leecher().build();
 
-            peer_list.upsert(seeder.into());
-            peer_list.upsert(leecher.into());
+            peer_list.upsert(seeder);
+            peer_list.upsert(leecher);
 
             let (seeders, _leechers) = peer_list.seeders_and_leechers();
 
@@ -230,8 +525,8 @@ mod tests {
             let seeder = PeerBuilder::seeder().build();
             let leecher = PeerBuilder::leecher().build();
 
-            peer_list.upsert(seeder.into());
-            peer_list.upsert(leecher.into());
+            peer_list.upsert(seeder);
+            peer_list.upsert(leecher);
 
             let (_seeders, leechers) = peer_list.seeders_and_leechers();
 
@@ -246,7 +541,7 @@ mod tests {
             // Insert the peer
             let last_update_time = DurationSinceUnixEpoch::new(1_669_397_478_934, 0);
             let peer = PeerBuilder::default().last_updated_on(last_update_time).build();
-            peer_list.upsert(peer.into());
+            peer_list.upsert(peer);
 
             // Remove peers not updated since one second after inserting the peer
             peer_list.remove_inactive_peers(last_update_time + one_second);
@@ -262,7 +557,7 @@ mod tests {
             // Insert the peer
             let last_update_time = DurationSinceUnixEpoch::new(1_669_397_478_934, 0);
             let peer = PeerBuilder::default().last_updated_on(last_update_time).build();
-            peer_list.upsert(peer.into());
+            peer_list.upsert(peer);
 
             // Remove peers not updated since one second before inserting the peer.
             peer_list.remove_inactive_peers(last_update_time - one_second);
@@ -275,11 +570,150 @@ mod tests {
             let mut peer_list = PeerList::default();
 
             let peer1 = PeerBuilder::default().with_peer_id(&PeerId(*b"-qB00000000000000001")).build();
-            peer_list.upsert(peer1.into());
+            peer_list.upsert(peer1);
 
             let peer2 = PeerBuilder::default().with_peer_id(&PeerId(*b"-qB00000000000000002")).build();
-            peer_list.upsert(peer2.into());
+            peer_list.upsert(peer2);
+
+            assert_eq!(peer_list.len(), 2);
+        }
+
+        #[test]
+        fn return_ipv4_and_ipv6_peers_ordered_by_id() {
+            let mut peer_list = PeerList::default();
+
+            let peer1 = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000001"))
+                .with_peer_addr(&SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 6969))
+                .build();
+            let peer2 = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000002"))
+                .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 6969))
+                .build();
+            let peer3 = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000003"))
+                .with_peer_addr(&SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 6970))
+                .build();
+
+            peer_list.upsert(peer3);
+            peer_list.upsert(peer2);
+            peer_list.upsert(peer1);
+
+            assert_eq!(peer_list.get_all(None), [Arc::new(peer1), Arc::new(peer2), Arc::new(peer3)]);
+        }
+
+        #[test]
+        fn allow_a_peer_to_change_its_ip_address_family() {
+            let mut peer_list = PeerList::default();
+
+            let mut peer = PeerBuilder::default()
+                .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 6969))
+                .build();
+            peer_list.upsert(peer);
+
+            peer.peer_addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 6969);
+            peer_list.upsert(peer);
+
+            assert_eq!(peer_list.get_all(None), [Arc::new(peer)]);
+        }
+
+        #[test]
+        fn keep_the_exact_last_update_time_of_the_peers_when_an_older_peer_is_inserted() {
+            let mut peer_list = PeerList::default();
+
+            let newer = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000001"))
+                .last_updated_on(DurationSinceUnixEpoch::new(1_669_397_478, 934))
+                .build();
+            let older = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000002"))
+                .last_updated_on(DurationSinceUnixEpoch::new(1_669_390_000, 1))
+                .build();
+
+            peer_list.upsert(newer);
+            peer_list.upsert(older);
+
+            assert_eq!(peer_list.get(&newer.peer_id), Some(newer));
+            assert_eq!(peer_list.get(&older.peer_id), Some(older));
+        }
+
+        #[test]
+        fn count_the_peers_with_an_ip_address_in_a_range() {
+            let mut peer_list = PeerList::default();
+
+            let ip = IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1));
+
+            for (n, peer_ip) in [ip, ip, IpAddr::V4(Ipv4Addr::new(126, 0, 0, 2))].into_iter().enumerate() {
+                let mut peer_id = *b"-qB00000000000000000";
+                peer_id[19] = b'0' + u8::try_from(n).unwrap();
+
+                peer_list.upsert(
+                    PeerBuilder::default()
+                        .with_peer_id(&PeerId(peer_id))
+                        .with_peer_addr(&SocketAddr::new(peer_ip, 6969))
+                        .build(),
+                );
+            }
+
+            assert_eq!(peer_list.count_with_ip_in(&(ip..=ip)), 2);
+
+            // The index of the peers by IP address is kept up to date.
+            peer_list.remove(&PeerId(*b"-qB00000000000000000"));
+
+            assert_eq!(peer_list.count_with_ip_in(&(ip..=ip)), 1);
+        }
+
+        #[test]
+        fn count_the_ipv6_peers_in_a_network() {
+            let mut peer_list = PeerList::default();
+
+            let network = "2001:db8:1:2::".parse::<IpAddr>().unwrap()..="2001:db8:1:2:ffff:ffff:ffff:ffff".parse().unwrap();
+
+            for (n, peer_ip) in ["2001:db8:1:2::1", "2001:db8:1:2:ffff::1", "2001:db8:1:3::1"]
+                .into_iter()
+                .enumerate()
+            {
+                let mut peer_id = *b"-qB00000000000000000";
+                peer_id[19] = b'0' + u8::try_from(n).unwrap();
+
+                peer_list.upsert(
+                    PeerBuilder::default()
+                        .with_peer_id(&PeerId(peer_id))
+                        .with_peer_addr(&SocketAddr::new(peer_ip.parse().unwrap(), 6969))
+                        .build(),
+                );
+            }
+
+            assert_eq!(peer_list.count_with_ip_in(&network), 2);
+        }
+
+        #[test]
+        fn remove_the_oldest_peer_with_an_ip_address_in_a_range() {
+            let mut peer_list = PeerList::default();
+
+            let ip = IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1));
+
+            let oldest = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000001"))
+                .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 2)), 6969))
+                .last_updated_on(DurationSinceUnixEpoch::new(1_669_390_000, 0))
+                .build();
+            let oldest_with_ip = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000002"))
+                .with_peer_addr(&SocketAddr::new(ip, 6969))
+                .last_updated_on(DurationSinceUnixEpoch::new(1_669_390_001, 0))
+                .build();
+            let newest_with_ip = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000003"))
+                .with_peer_addr(&SocketAddr::new(ip, 6970))
+                .last_updated_on(DurationSinceUnixEpoch::new(1_669_390_002, 0))
+                .build();
+
+            peer_list.upsert(oldest);
+            peer_list.upsert(oldest_with_ip);
+            peer_list.upsert(newest_with_ip);
 
+            assert_eq!(peer_list.remove_oldest_with_ip_in(&(ip..=ip)), Some(oldest_with_ip));
             assert_eq!(peer_list.len(), 2);
         }
     }
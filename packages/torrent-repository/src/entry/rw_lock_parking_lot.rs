@@ -1,11 +1,14 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use aquatic_udp_protocol::PeerId;
 use torrust_tracker_configuration::TrackerPolicy;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
 
-use super::{Entry, EntrySync};
+use super::{Entry, EntrySync, PeerPredicate};
+use crate::expiry::PeerExpiry;
+use crate::limits::{PeerChanges, PeerQuota};
 use crate::{EntryRwLockParkingLot, EntrySingle};
 
 impl EntrySync for EntryRwLockParkingLot {
@@ -33,12 +36,50 @@ impl EntrySync for EntryRwLockParkingLot {
         self.read().get_peers_for_client(client, limit)
     }
 
+    fn get_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        limit: Option<usize>,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        self.read().get_peers_for_client_matching(client, limit, predicate)
+    }
+
+    fn sample_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        amount: usize,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        self.read().sample_peers_for_client_matching(client, amount, predicate)
+    }
+
+    fn get_peer(&self, peer_id: &PeerId) -> Option<peer::Peer> {
+        self.read().get_peer(peer_id)
+    }
+
     fn upsert_peer(&self, peer: &peer::Peer) -> bool {
         self.write().upsert_peer(peer)
     }
 
-    fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
-        self.write().remove_inactive_peers(current_cutoff);
+    fn upsert_peer_within_quota(&self, peer: &peer::Peer, quota: &PeerQuota) -> PeerChanges {
+        self.write().upsert_peer_within_quota(peer, quota)
+    }
+
+    fn get_last_activity(&self) -> DurationSinceUnixEpoch {
+        self.read().get_last_activity()
+    }
+
+    fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) -> Vec<peer::Peer> {
+        self.write().remove_inactive_peers(current_cutoff)
+    }
+
+    fn remove_peer_if_inactive(&self, peer_id: &PeerId, current_cutoff: DurationSinceUnixEpoch) -> PeerExpiry {
+        self.write().remove_peer_if_inactive(peer_id, current_cutoff)
+    }
+
+    fn remove_peer(&self, peer_id: &PeerId) -> Option<peer::Peer> {
+        self.write().remove_peer(peer_id)
     }
 }
 
@@ -1,13 +1,15 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use aquatic_udp_protocol::AnnounceEvent;
-use torrust_tracker_configuration::TrackerPolicy;
+use aquatic_udp_protocol::{AnnounceEvent, PeerId};
+use torrust_tracker_configuration::{PeerEvictionPolicy, PeersPerIpPolicy, TrackerPolicy};
 use torrust_tracker_primitives::peer::{self};
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::DurationSinceUnixEpoch;
 
-use super::Entry;
+use super::{Entry, PeerPredicate};
+use crate::expiry::PeerExpiry;
+use crate::limits::{PeerChanges, PeerQuota};
 use crate::EntrySingle;
 
 impl Entry for EntrySingle {
@@ -50,15 +52,37 @@ impl Entry for EntrySingle {
         self.swarm.get_peers_excluding_addr(client, limit)
     }
 
+    fn get_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        limit: Option<usize>,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        self.swarm.get_peers_excluding_addr_matching(client, limit, predicate)
+    }
+
+    fn sample_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        amount: usize,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        self.swarm.sample_peers_excluding_addr_matching(client, amount, predicate)
+    }
+
+    fn get_peer(&self, peer_id: &PeerId) -> Option<peer::Peer> {
+        self.swarm.get(peer_id)
+    }
+
     fn upsert_peer(&mut self, peer: &peer::Peer) -> bool {
         let mut downloaded_stats_updated: bool = false;
 
         match peer::ReadInfo::get_event(peer) {
             AnnounceEvent::Stopped => {
-                drop(self.swarm.remove(&peer::ReadInfo::get_id(peer)));
+                self.swarm.remove(&peer::ReadInfo::get_id(peer));
             }
             AnnounceEvent::Completed => {
-                let previous = self.swarm.upsert(Arc::new(*peer));
+                let previous = self.swarm.upsert(*peer);
                 // Don't count if peer was not previously known and not already completed.
                 if previous.is_some_and(|p| p.event != AnnounceEvent::Completed) {
                     self.downloaded += 1;
@@ -66,14 +90,91 @@ impl Entry for EntrySingle {
                 }
             }
             _ => {
-                drop(self.swarm.upsert(Arc::new(*peer)));
+                self.swarm.upsert(*peer);
             }
         }
 
         downloaded_stats_updated
     }
 
-    fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch) {
-        self.swarm.remove_inactive_peers(current_cutoff);
+    fn upsert_peer_within_quota(&mut self, peer: &peer::Peer, quota: &PeerQuota) -> PeerChanges {
+        let mut changes = PeerChanges {
+            previous: self.swarm.get(&peer::ReadInfo::get_id(peer)),
+            ..Default::default()
+        };
+
+        let is_known = changes.previous.is_some();
+        let is_leaving = peer::ReadInfo::get_event(peer) == AnnounceEvent::Stopped;
+
+        if !is_known && !is_leaving {
+            if let Some(max_peers_per_ip) = quota.max_peers_per_ip {
+                let host = quota.host_addresses(&peer.peer_addr.ip());
+
+                if self.swarm.count_with_ip_in(&host) >= max_peers_per_ip {
+                    changes.peers_per_ip_exceeded = true;
+
+                    match quota.peers_per_ip_policy {
+                        PeersPerIpPolicy::Reject => {
+                            changes.refused = true;
+                            return changes;
+                        }
+                        PeersPerIpPolicy::DropOldest => {
+                            changes.evicted = self.swarm.remove_oldest_with_ip_in(&host);
+
+                            if changes.evicted.is_none() {
+                                // A zero limit leaves no room for any peer.
+                                changes.refused = true;
+                                return changes;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // A peer evicted for the same IP address has already made room for the new one.
+        if !is_known && !is_leaving && changes.evicted.is_none() && quota.is_exceeded_by_new_peer(self.swarm.len()) {
+            match quota.eviction_policy {
+                PeerEvictionPolicy::RefuseNew => {
+                    changes.refused = true;
+                    return changes;
+                }
+                PeerEvictionPolicy::DropOldest => {
+                    changes.evicted = self.swarm.remove_oldest();
+
+                    if changes.evicted.is_none() {
+                        // There is nobody in this swarm to make room for the new peer.
+                        changes.refused = true;
+                        return changes;
+                    }
+                }
+            }
+        }
+
+        changes.downloaded_increased = self.upsert_peer(peer);
+
+        match (is_known, is_leaving) {
+            (false, false) => changes.added = 1,
+            (true, true) => changes.removed = 1,
+            _ => {}
+        }
+
+        changes
+    }
+
+    fn get_last_activity(&self) -> DurationSinceUnixEpoch {
+        self.swarm.last_updated().unwrap_or_default()
+    }
+
+    fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch) -> Vec<peer::Peer> {
+        self.swarm.remove_inactive_peers(current_cutoff)
+    }
+
+    fn remove_peer_if_inactive(&mut self, peer_id: &PeerId, current_cutoff: DurationSinceUnixEpoch) -> PeerExpiry {
+        self.swarm.remove_if_inactive(peer_id, current_cutoff)
+    }
+
+    fn remove_peer(&mut self, peer_id: &PeerId) -> Option<peer::Peer> {
+        self.swarm.remove(peer_id)
     }
 }
@@ -0,0 +1,356 @@
+//! Incremental expiry of inactive peers.
+//!
+//! Removing inactive peers by walking all the torrents and all their peers
+//! locks every swarm at the same time, which produces latency spikes on
+//! announce requests when the tracker holds millions of peers.
+//!
+//! The [`ExpiryWheel`] is a timing wheel that indexes peers by the second
+//! they were last updated. Every announce adds an [`ExpiryKey`] to the slot
+//! for its second. The cleanup job only visits the slots whose second is
+//! already behind the cutoff time, and it does it in small batches, so each
+//! step only touches the swarms of the peers that have actually expired.
+//!
+//! The slots only contain the infohash and the peer ID of each peer. When a
+//! peer announces again, its key is moved from the slot for its previous
+//! update time, which the swarm returns when the peer is updated, to the slot
+//! for the new second. When a slot is visited, the key is still checked
+//! against the swarm and discarded if the peer has been updated since the
+//! key was added, or if the peer is gone.
+//!
+//! The `memory_benchmark` measures the memory used by the wheel per peer.
+//!
+//! Only the [`CrossbeamSkipList`](crate::repository::skip_map_mutex_std::CrossbeamSkipList)
+//! repositories, the ones used in production, index their peers in the wheel.
+//! The other repository implementations are kept for reference and
+//! benchmarking, and they only support removing the inactive peers with a
+//! full scan.
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use aquatic_udp_protocol::PeerId;
+use bittorrent_primitives::info_hash::InfoHash;
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+
+/// Number of one-second slots in the wheel.
+///
+/// Peers with a timeout longer than the wheel span are still expired
+/// correctly; they only stay in their slot for more than one turn.
+pub const WHEEL_SLOTS: usize = 4096;
+
+/// Default maximum number of keys inspected in one expiry batch.
+pub const EXPIRY_BATCH_SIZE: usize = 1024;
+
+/// A reference to a peer that might have expired.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExpiryKey {
+    pub info_hash: InfoHash,
+    pub peer_id: PeerId,
+}
+
+/// The result of trying to remove a peer that might have expired.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PeerExpiry {
+    /// The peer was inactive and it has been removed. It contains the
+    /// removed peer.
+    Removed(peer::Peer),
+    /// The peer is still active. It contains the last time it was updated.
+    Active(DurationSinceUnixEpoch),
+    /// The peer (or its torrent) is not in the repository anymore.
+    Missing,
+}
+
+/// The work done by one expiry batch.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ExpiredPeers {
+    /// Number of inactive peers removed.
+    pub peers: usize,
+    /// The torrents removed because they were left without peers.
+    pub torrents: Vec<InfoHash>,
+    /// `false` when the batch was full and there could be more expired
+    /// peers pending.
+    pub finished: bool,
+}
+
+/// A timing wheel with one slot per second.
+#[derive(Debug)]
+pub struct ExpiryWheel {
+    slots: Box<[Mutex<HashSet<ExpiryKey>>]>,
+    /// The next second to be visited.
+    cursor: Mutex<Option<u64>>,
+}
+
+impl Default for ExpiryWheel {
+    fn default() -> Self {
+        Self {
+            slots: (0..WHEEL_SLOTS).map(|_| Mutex::default()).collect(),
+            cursor: Mutex::default(),
+        }
+    }
+}
+
+impl ExpiryWheel {
+    /// It adds a peer to the slot for the second it was `updated`, removing
+    /// its key from the slot for the `previous` time it was updated, if the
+    /// peer was already in the swarm.
+    ///
+    /// # Panics
+    ///
+    /// It panics if a lock is poisoned.
+    pub fn schedule(&self, key: ExpiryKey, updated: DurationSinceUnixEpoch, previous: Option<DurationSinceUnixEpoch>) {
+        if let Some(previous) = previous.filter(|previous| slot_index(previous.as_secs()) != slot_index(updated.as_secs())) {
+            self.slot(previous.as_secs())
+                .lock()
+                .expect("it should lock the expiry slot")
+                .remove(&key);
+        }
+
+        self.slot(updated.as_secs())
+            .lock()
+            .expect("it should lock the expiry slot")
+            .insert(key);
+    }
+
+    /// It visits the slots whose second is behind the `current_cutoff`,
+    /// calling `expire` for each key until `max_keys` keys have been
+    /// inspected.
+    ///
+    /// It returns `true` if all the slots behind the cutoff have been
+    /// visited.
+    ///
+    /// # Panics
+    ///
+    /// It panics if a lock is poisoned.
+    pub fn expire<F>(&self, current_cutoff: DurationSinceUnixEpoch, max_keys: usize, mut expire: F) -> bool
+    where
+        F: FnMut(&ExpiryKey) -> PeerExpiry,
+    {
+        let mut cursor = self.cursor.lock().expect("it should lock the expiry cursor");
+
+        // Only whole seconds before the cutoff are visited. Peers updated
+        // during the cutoff second are expired on the next call.
+        let end = current_cutoff.as_secs();
+
+        // If the cursor is more than one turn behind, a single turn visits all the slots.
+        let mut second = cursor.unwrap_or_default().max(end.saturating_sub(WHEEL_SLOTS as u64));
+
+        let mut inspected = 0;
+
+        while second < end {
+            let keys = std::mem::take(&mut *self.slot(second).lock().expect("it should lock the expiry slot"));
+
+            let mut keys = keys.into_iter();
+            let mut pending = Vec::new();
+
+            while inspected < max_keys {
+                let Some(key) = keys.next() else {
+                    break;
+                };
+
+                inspected += 1;
+
+                match expire(&key) {
+                    PeerExpiry::Removed(_) | PeerExpiry::Missing => {}
+                    // The key belongs to a later turn of the wheel.
+                    PeerExpiry::Active(updated) if slot_index(updated.as_secs()) == slot_index(second) => pending.push(key),
+                    // The peer has announced again, and its key is in another slot.
+                    PeerExpiry::Active(_) => {}
+                }
+            }
+
+            let unvisited: Vec<ExpiryKey> = keys.collect();
+            let is_slot_done = unvisited.is_empty();
+
+            pending.extend(unvisited);
+
+            if !pending.is_empty() {
+                self.slot(second)
+                    .lock()
+                    .expect("it should lock the expiry slot")
+                    .extend(pending);
+            }
+
+            if !is_slot_done {
+                *cursor = Some(second);
+                return false;
+            }
+
+            second += 1;
+        }
+
+        *cursor = Some(second);
+
+        true
+    }
+
+    /// The total number of keys in the wheel, including the ones of peers
+    /// that are gone.
+    ///
+    /// # Panics
+    ///
+    /// It panics if a lock is poisoned.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|slot| slot.lock().expect("it should lock the expiry slot").len())
+            .sum()
+    }
+
+    /// It returns `true` if the wheel does not contain any key.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn slot(&self, second: u64) -> &Mutex<HashSet<ExpiryKey>> {
+        &self.slots[slot_index(second)]
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn slot_index(second: u64) -> usize {
+    (second % WHEEL_SLOTS as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod the_expiry_wheel {
+        use aquatic_udp_protocol::PeerId;
+        use bittorrent_primitives::info_hash::InfoHash;
+        use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+        use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+        use crate::expiry::{ExpiryKey, ExpiryWheel, PeerExpiry};
+
+        fn a_key(peer: u8) -> ExpiryKey {
+            ExpiryKey {
+                info_hash: InfoHash::from([0u8; 20]),
+                peer_id: PeerId([peer; 20]),
+            }
+        }
+
+        fn removed() -> PeerExpiry {
+            PeerExpiry::Removed(PeerBuilder::default().build())
+        }
+
+        fn secs(secs: u64) -> DurationSinceUnixEpoch {
+            DurationSinceUnixEpoch::from_secs(secs)
+        }
+
+        #[test]
+        fn should_only_visit_the_keys_updated_before_the_cutoff() {
+            let wheel = ExpiryWheel::default();
+
+            wheel.schedule(a_key(1), secs(1000), None);
+            wheel.schedule(a_key(2), secs(2000), None);
+
+            let mut visited = vec![];
+
+            let finished = wheel.expire(secs(1500), 10, |key| {
+                visited.push(*key);
+                removed()
+            });
+
+            assert!(finished);
+            assert_eq!(visited, vec![a_key(1)]);
+            assert_eq!(wheel.len(), 1);
+        }
+
+        #[test]
+        fn should_move_the_key_of_a_peer_that_announces_again() {
+            let wheel = ExpiryWheel::default();
+
+            wheel.schedule(a_key(1), secs(1000), None);
+            wheel.schedule(a_key(1), secs(1100), Some(secs(1000)));
+            wheel.schedule(a_key(1), secs(1100), Some(secs(1100)));
+            wheel.schedule(a_key(2), secs(1100), None);
+
+            assert_eq!(wheel.len(), 2);
+
+            let mut visited = vec![];
+
+            wheel.expire(secs(1050), 10, |key| {
+                visited.push(*key);
+                removed()
+            });
+
+            assert!(visited.is_empty());
+        }
+
+        #[test]
+        fn should_keep_the_keys_of_peers_that_are_still_active() {
+            let wheel = ExpiryWheel::default();
+
+            wheel.schedule(a_key(1), secs(1000), None);
+
+            wheel.expire(secs(1500), 10, |_key| PeerExpiry::Active(secs(1000 + 4096)));
+
+            assert_eq!(wheel.len(), 1);
+        }
+
+        #[test]
+        fn should_discard_the_keys_of_peers_that_have_announced_again() {
+            let wheel = ExpiryWheel::default();
+
+            wheel.schedule(a_key(1), secs(1000), None);
+
+            wheel.expire(secs(1500), 10, |_key| PeerExpiry::Active(secs(1400)));
+
+            assert!(wheel.is_empty());
+        }
+
+        #[test]
+        fn should_discard_the_keys_of_peers_that_are_gone() {
+            let wheel = ExpiryWheel::default();
+
+            wheel.schedule(a_key(1), secs(1000), None);
+
+            wheel.expire(secs(1500), 10, |_key| PeerExpiry::Missing);
+
+            assert!(wheel.is_empty());
+        }
+
+        #[test]
+        fn should_stop_when_the_batch_is_full_and_continue_on_the_next_call() {
+            let wheel = ExpiryWheel::default();
+
+            wheel.schedule(a_key(1), secs(1000), None);
+            wheel.schedule(a_key(2), secs(1000), None);
+            wheel.schedule(a_key(3), secs(1001), None);
+
+            let cutoff = secs(1500);
+            let mut visited = 0;
+
+            let finished = wheel.expire(cutoff, 1, |_key| {
+                visited += 1;
+                removed()
+            });
+
+            assert!(!finished);
+            assert_eq!(visited, 1);
+
+            let finished = wheel.expire(cutoff, 10, |_key| {
+                visited += 1;
+                removed()
+            });
+
+            assert!(finished);
+            assert_eq!(visited, 3);
+            assert!(wheel.is_empty());
+        }
+
+        #[test]
+        fn should_visit_all_the_slots_when_the_cutoff_is_more_than_one_turn_ahead() {
+            let wheel = ExpiryWheel::default();
+
+            wheel.schedule(a_key(1), secs(1000), None);
+            wheel.schedule(a_key(2), secs(5000), None);
+
+            let finished = wheel.expire(secs(1_000_000), 10, |_key| removed());
+
+            assert!(finished);
+            assert!(wheel.is_empty());
+        }
+    }
+}
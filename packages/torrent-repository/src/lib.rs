@@ -7,7 +7,10 @@ use repository::skip_map_mutex_std::CrossbeamSkipList;
 use torrust_tracker_clock::clock;
 
 pub mod entry;
+pub mod expiry;
+pub mod limits;
 pub mod repository;
+pub mod tally;
 
 // Repo Entries
 
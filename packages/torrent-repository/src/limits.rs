@@ -0,0 +1,508 @@
+//! Memory limits for the torrent repositories.
+//!
+//! The [`Limiter`] is shared by all the repository implementations. It keeps
+//! track of the total number of peers, decides whether a new torrent or peer
+//! fits in the repository and counts the torrents and peers that have been
+//! evicted or refused because of the configured [`MemoryLimits`].
+use std::net::{IpAddr, Ipv6Addr};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use bittorrent_primitives::info_hash::InfoHash;
+use torrust_tracker_configuration::{MemoryLimits, PeerEvictionPolicy, PeersPerIpPolicy, TorrentEvictionPolicy};
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+
+/// Number of torrents inspected to find the least recently active one when
+/// the [`TorrentEvictionPolicy::EvictLeastRecentlyActive`] policy is used.
+///
+/// Finding the exact least recently active torrent would require walking the
+/// whole repository, so it's approximated by sampling a few torrents.
+pub const EVICTION_SAMPLE_SIZE: usize = 16;
+
+/// Counters for the torrents and peers that did not fit in the repository.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct EvictionMetrics {
+    /// Total number of torrents removed to make room for new torrents.
+    pub torrents_evicted: u64,
+    /// Total number of new torrents that were not added because the
+    /// repository was full.
+    pub torrents_refused: u64,
+    /// Total number of peers removed to make room for new peers.
+    pub peers_evicted: u64,
+    /// Total number of new peers that were not added because the swarm or
+    /// the repository was full.
+    pub peers_refused: u64,
+    /// Total number of new peers whose IP address already had the maximum
+    /// number of peers in the swarm. They are not counted in
+    /// `peers_evicted` or `peers_refused`.
+    pub peers_per_ip_exceeded: u64,
+}
+
+/// What the repository has to do before inserting a new torrent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TorrentAdmission {
+    /// There is room for the new torrent.
+    Accept,
+    /// The new torrent must not be added.
+    Refuse,
+    /// One torrent has to be evicted before adding the new one.
+    EvictOne,
+}
+
+/// The room available in a swarm for a new peer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PeerQuota {
+    /// Maximum number of peers in the swarm.
+    pub max_swarm_peers: Option<usize>,
+    /// `true` when the repository already holds the maximum number of peers.
+    pub repository_full: bool,
+    /// What to do when there is no room for a new peer.
+    pub eviction_policy: PeerEvictionPolicy,
+    /// Maximum number of peers in the swarm with the same IP address.
+    pub max_peers_per_ip: Option<usize>,
+    /// Length of the IPv6 prefix that identifies a single host.
+    pub ipv6_prefix_length: u8,
+    /// What to do when the IP address of a new peer has no room left.
+    pub peers_per_ip_policy: PeersPerIpPolicy,
+}
+
+impl PeerQuota {
+    /// A quota without limits.
+    #[must_use]
+    pub fn unlimited() -> Self {
+        Self {
+            max_swarm_peers: None,
+            repository_full: false,
+            eviction_policy: PeerEvictionPolicy::default(),
+            max_peers_per_ip: None,
+            ipv6_prefix_length: 128,
+            peers_per_ip_policy: PeersPerIpPolicy::default(),
+        }
+    }
+
+    /// Returns `true` if a new peer does not fit in a swarm with
+    /// `swarm_len` peers.
+    #[must_use]
+    pub fn is_exceeded_by_new_peer(&self, swarm_len: usize) -> bool {
+        self.repository_full || self.max_swarm_peers.is_some_and(|max| swarm_len >= max)
+    }
+
+    /// The addresses that belong to the same host as `ip` for the
+    /// `max_peers_per_ip` limit.
+    ///
+    /// IPv4 addresses must be equal, while IPv6 addresses only have to share
+    /// the first `ipv6_prefix_length` bits.
+    #[must_use]
+    pub fn host_addresses(&self, ip: &IpAddr) -> RangeInclusive<IpAddr> {
+        match ip {
+            IpAddr::V4(_) => *ip..=*ip,
+            IpAddr::V6(ip) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.ipv6_prefix_length.min(128)))
+                    .unwrap_or(0);
+
+                let first = u128::from(*ip) & mask;
+                let last = first | !mask;
+
+                IpAddr::V6(Ipv6Addr::from(first))..=IpAddr::V6(Ipv6Addr::from(last))
+            }
+        }
+    }
+}
+
+/// Changes in a swarm after upserting a peer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct PeerChanges {
+    /// The number of peers added to the swarm.
+    pub added: usize,
+    /// The number of peers that left the swarm (`stopped` event).
+    pub removed: usize,
+    /// The peer evicted to make room for the new one.
+    pub evicted: Option<peer::Peer>,
+    /// `true` if the peer was not added because there was no room for it in
+    /// the swarm, the repository or its IP address.
+    pub refused: bool,
+    /// `true` if the IP address of the new peer already had the maximum
+    /// number of peers in the swarm.
+    pub peers_per_ip_exceeded: bool,
+    /// `true` if the number of completed downloads has increased.
+    pub downloaded_increased: bool,
+    /// The peer before this change, if it was already in the swarm.
+    pub previous: Option<peer::Peer>,
+    /// The torrent evicted to make room for the torrent of the new peer.
+    pub evicted_torrent: Option<InfoHash>,
+}
+
+impl PeerChanges {
+    /// The changes for a peer that was not added because there was no room
+    /// for its torrent.
+    #[must_use]
+    pub fn refused() -> Self {
+        Self {
+            refused: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// It enforces the [`MemoryLimits`] for a repository.
+#[derive(Debug, Default)]
+pub struct Limiter {
+    limits: MemoryLimits,
+    peers: AtomicUsize,
+    torrents_evicted: AtomicU64,
+    torrents_refused: AtomicU64,
+    peers_evicted: AtomicU64,
+    peers_refused: AtomicU64,
+    peers_per_ip_exceeded: AtomicU64,
+}
+
+impl Limiter {
+    #[must_use]
+    pub fn new(limits: MemoryLimits) -> Self {
+        Self {
+            limits,
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    pub fn limits(&self) -> &MemoryLimits {
+        &self.limits
+    }
+
+    /// It decides what to do with a new torrent when the repository already
+    /// contains `torrents` torrents.
+    ///
+    /// A new torrent is also refused when there is no room for its first
+    /// peer, so that full repositories do not fill up with empty torrents.
+    #[must_use]
+    pub fn torrent_admission(&self, torrents: usize) -> TorrentAdmission {
+        if self.peer_quota().is_exceeded_by_new_peer(0) {
+            return TorrentAdmission::Refuse;
+        }
+
+        match self.limits.max_torrents {
+            // A zero limit leaves no room for any torrent, not even by evicting one.
+            Some(0) => TorrentAdmission::Refuse,
+            Some(max_torrents) if torrents >= max_torrents => match self.limits.torrent_eviction_policy {
+                TorrentEvictionPolicy::RefuseNew => TorrentAdmission::Refuse,
+                TorrentEvictionPolicy::EvictLeastRecentlyActive => TorrentAdmission::EvictOne,
+            },
+            _ => TorrentAdmission::Accept,
+        }
+    }
+
+    /// The room currently available in a swarm for a new peer.
+    #[must_use]
+    pub fn peer_quota(&self) -> PeerQuota {
+        PeerQuota {
+            max_swarm_peers: self.limits.max_peers_per_torrent,
+            repository_full: self
+                .limits
+                .max_peers
+                .is_some_and(|max_peers| self.peers.load(Ordering::Relaxed) >= max_peers),
+            eviction_policy: self.limits.peer_eviction_policy,
+            max_peers_per_ip: self.limits.max_peers_per_ip,
+            ipv6_prefix_length: self.limits.ipv6_prefix_length,
+            peers_per_ip_policy: self.limits.peers_per_ip_policy,
+        }
+    }
+
+    /// It updates the peer counters after upserting a peer.
+    pub fn record_peer_changes(&self, changes: &PeerChanges) {
+        self.add_peers(changes.added);
+        self.sub_peers(changes.removed + usize::from(changes.evicted.is_some()));
+
+        // The peers evicted or refused for their IP address are only counted
+        // as exceeding the limit per IP address.
+        if changes.peers_per_ip_exceeded {
+            self.peers_per_ip_exceeded.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        if changes.evicted.is_some() {
+            self.peers_evicted.fetch_add(1, Ordering::Relaxed);
+        }
+
+        if changes.refused {
+            self.peers_refused.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// It records a torrent evicted to make room for a new one.
+    pub fn record_torrent_evicted(&self, peers: usize) {
+        self.torrents_evicted.fetch_add(1, Ordering::Relaxed);
+        self.sub_peers(peers);
+    }
+
+    /// It records a new torrent that did not fit in the repository.
+    pub fn record_torrent_refused(&self) {
+        self.torrents_refused.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// It records peers removed from the repository because they were
+    /// inactive.
+    pub fn record_peers_removed(&self, peers: usize) {
+        self.sub_peers(peers);
+    }
+
+    /// It records a torrent removed from the repository.
+    pub fn record_torrent_removed(&self, peers: usize) {
+        self.sub_peers(peers);
+    }
+
+    /// It overwrites the total number of peers with a freshly counted value.
+    ///
+    /// Repositories call it after walking all the torrents (for example, when
+    /// removing inactive peers) to correct any drift in the counter.
+    pub fn reset_peers(&self, peers: usize) {
+        self.peers.store(peers, Ordering::Relaxed);
+    }
+
+    /// The total number of peers in the repository.
+    #[must_use]
+    pub fn get_peers(&self) -> usize {
+        self.peers.load(Ordering::Relaxed)
+    }
+
+    /// The fraction, from `0.0` to `1.0`, of the most used memory limit that
+    /// is in use when the repository contains `torrents` torrents.
+    ///
+    /// It's `0.0` when there are no limits on the number of torrents or peers.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn memory_usage(&self, torrents: usize) -> f64 {
+        let usage = |used: usize, limit: Option<usize>| match limit {
+            Some(0) => 1.0,
+            Some(limit) => (used as f64 / limit as f64).min(1.0),
+            None => 0.0,
+        };
+
+        usage(torrents, self.limits.max_torrents).max(usage(self.get_peers(), self.limits.max_peers))
+    }
+
+    #[must_use]
+    pub fn get_metrics(&self) -> EvictionMetrics {
+        EvictionMetrics {
+            torrents_evicted: self.torrents_evicted.load(Ordering::Relaxed),
+            torrents_refused: self.torrents_refused.load(Ordering::Relaxed),
+            peers_evicted: self.peers_evicted.load(Ordering::Relaxed),
+            peers_refused: self.peers_refused.load(Ordering::Relaxed),
+            peers_per_ip_exceeded: self.peers_per_ip_exceeded.load(Ordering::Relaxed),
+        }
+    }
+
+    fn add_peers(&self, peers: usize) {
+        if peers > 0 {
+            self.peers.fetch_add(peers, Ordering::Relaxed);
+        }
+    }
+
+    fn sub_peers(&self, peers: usize) {
+        if peers > 0 {
+            let _ = self.peers.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                Some(current.saturating_sub(peers))
+            });
+        }
+    }
+}
+
+/// It returns the least recently active torrent from a sample of torrents
+/// with their last activity time.
+pub fn least_recently_active<I>(sample: I) -> Option<InfoHash>
+where
+    I: IntoIterator<Item = (InfoHash, DurationSinceUnixEpoch)>,
+{
+    sample
+        .into_iter()
+        .min_by_key(|(_info_hash, last_activity)| *last_activity)
+        .map(|(info_hash, _last_activity)| info_hash)
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod the_limiter {
+        use torrust_tracker_configuration::{MemoryLimits, PeerEvictionPolicy, TorrentEvictionPolicy};
+        use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+
+        use crate::limits::{Limiter, PeerChanges, TorrentAdmission};
+
+        #[test]
+        fn should_accept_any_torrent_when_there_are_no_limits() {
+            let limiter = Limiter::default();
+
+            assert_eq!(limiter.torrent_admission(usize::MAX), TorrentAdmission::Accept);
+        }
+
+        #[test]
+        fn should_refuse_new_torrents_when_the_repository_is_full() {
+            let limiter = Limiter::new(MemoryLimits {
+                max_torrents: Some(1),
+                ..Default::default()
+            });
+
+            assert_eq!(limiter.torrent_admission(0), TorrentAdmission::Accept);
+            assert_eq!(limiter.torrent_admission(1), TorrentAdmission::Refuse);
+        }
+
+        #[test]
+        fn should_ask_for_an_eviction_when_the_repository_is_full_and_the_policy_evicts_torrents() {
+            let limiter = Limiter::new(MemoryLimits {
+                max_torrents: Some(1),
+                torrent_eviction_policy: TorrentEvictionPolicy::EvictLeastRecentlyActive,
+                ..Default::default()
+            });
+
+            assert_eq!(limiter.torrent_admission(1), TorrentAdmission::EvictOne);
+        }
+
+        #[test]
+        fn should_report_the_repository_as_full_when_the_total_number_of_peers_reaches_the_limit() {
+            let limiter = Limiter::new(MemoryLimits {
+                max_peers: Some(2),
+                peer_eviction_policy: PeerEvictionPolicy::DropOldest,
+                ..Default::default()
+            });
+
+            limiter.record_peer_changes(&PeerChanges {
+                added: 1,
+                ..Default::default()
+            });
+
+            assert!(!limiter.peer_quota().repository_full);
+
+            limiter.record_peer_changes(&PeerChanges {
+                added: 1,
+                ..Default::default()
+            });
+
+            assert!(limiter.peer_quota().repository_full);
+            assert_eq!(limiter.peer_quota().eviction_policy, PeerEvictionPolicy::DropOldest);
+        }
+
+        #[test]
+        fn should_count_evicted_and_refused_peers_and_torrents() {
+            let limiter = Limiter::default();
+
+            limiter.record_peer_changes(&PeerChanges {
+                added: 3,
+                ..Default::default()
+            });
+            limiter.record_peer_changes(&PeerChanges {
+                added: 1,
+                evicted: Some(PeerBuilder::default().build()),
+                ..Default::default()
+            });
+            limiter.record_peer_changes(&PeerChanges {
+                refused: true,
+                ..Default::default()
+            });
+            limiter.record_peer_changes(&PeerChanges {
+                refused: true,
+                peers_per_ip_exceeded: true,
+                ..Default::default()
+            });
+            limiter.record_torrent_evicted(2);
+            limiter.record_torrent_refused();
+
+            let metrics = limiter.get_metrics();
+
+            assert_eq!(limiter.get_peers(), 1);
+            assert_eq!(metrics.peers_evicted, 1);
+            assert_eq!(metrics.peers_refused, 1);
+            assert_eq!(metrics.peers_per_ip_exceeded, 1);
+            assert_eq!(metrics.torrents_evicted, 1);
+            assert_eq!(metrics.torrents_refused, 1);
+        }
+
+        #[test]
+        fn should_not_report_any_memory_usage_when_there_are_no_limits() {
+            let limiter = Limiter::default();
+
+            limiter.record_peer_changes(&PeerChanges {
+                added: 10,
+                ..Default::default()
+            });
+
+            assert!(limiter.memory_usage(10).abs() < f64::EPSILON);
+        }
+
+        #[test]
+        fn should_report_the_usage_of_the_most_used_memory_limit() {
+            let limiter = Limiter::new(MemoryLimits {
+                max_torrents: Some(10),
+                max_peers: Some(100),
+                ..Default::default()
+            });
+
+            limiter.record_peer_changes(&PeerChanges {
+                added: 50,
+                ..Default::default()
+            });
+
+            assert!((limiter.memory_usage(2) - 0.5).abs() < f64::EPSILON);
+            assert!((limiter.memory_usage(8) - 0.8).abs() < f64::EPSILON);
+        }
+    }
+
+    mod the_peer_quota {
+        use std::net::IpAddr;
+
+        use crate::limits::PeerQuota;
+
+        fn ip(ip: &str) -> IpAddr {
+            ip.parse().unwrap()
+        }
+
+        #[test]
+        fn should_only_consider_equal_ipv4_addresses_as_the_same_host() {
+            let quota = PeerQuota::unlimited();
+
+            let host = quota.host_addresses(&ip("126.0.0.1"));
+
+            assert!(host.contains(&ip("126.0.0.1")));
+            assert!(!host.contains(&ip("126.0.0.2")));
+            assert!(!host.contains(&ip("::ffff:126.0.0.1")));
+        }
+
+        #[test]
+        fn should_consider_ipv6_addresses_with_the_same_prefix_as_the_same_host() {
+            let quota = PeerQuota {
+                ipv6_prefix_length: 64,
+                ..PeerQuota::unlimited()
+            };
+
+            let host = quota.host_addresses(&ip("2001:db8:1:2::1"));
+
+            assert!(host.contains(&ip("2001:db8:1:2:ffff::1")));
+            assert!(!host.contains(&ip("2001:db8:1:3::1")));
+        }
+    }
+
+    mod selecting_the_least_recently_active_torrent {
+        use bittorrent_primitives::info_hash::InfoHash;
+        use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+        use crate::limits::least_recently_active;
+
+        #[test]
+        fn should_return_the_torrent_with_the_oldest_activity() {
+            let recent = InfoHash::from([1u8; 20]);
+            let old = InfoHash::from([2u8; 20]);
+
+            let sample = vec![
+                (recent, DurationSinceUnixEpoch::from_secs(20)),
+                (old, DurationSinceUnixEpoch::from_secs(10)),
+            ];
+
+            assert_eq!(least_recently_active(sample), Some(old));
+        }
+
+        #[test]
+        fn should_return_none_for_an_empty_sample() {
+            assert_eq!(least_recently_active(vec![]), None);
+        }
+    }
+}
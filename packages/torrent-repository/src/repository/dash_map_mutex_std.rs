@@ -2,20 +2,105 @@ use std::sync::Arc;
 
 use bittorrent_primitives::info_hash::InfoHash;
 use dashmap::DashMap;
-use torrust_tracker_configuration::TrackerPolicy;
+use torrust_tracker_configuration::{MemoryLimits, TrackerPolicy};
 use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
 
-use super::Repository;
+use super::{Repository, TorrentRefused};
 use crate::entry::peer_list::PeerList;
 use crate::entry::{Entry, EntrySync};
+use crate::limits::{least_recently_active, EvictionMetrics, Limiter, PeerChanges, TorrentAdmission, EVICTION_SAMPLE_SIZE};
 use crate::{EntryMutexStd, EntrySingle};
 
 #[derive(Default, Debug)]
 pub struct XacrimonDashMap<T> {
     pub torrents: DashMap<InfoHash, T>,
+    pub(crate) limiter: Limiter,
+}
+
+impl<T> XacrimonDashMap<T> {
+    /// It creates an empty repository that enforces the given memory limits.
+    #[must_use]
+    pub fn new(limits: MemoryLimits) -> Self {
+        Self {
+            torrents: DashMap::new(),
+            limiter: Limiter::new(limits),
+        }
+    }
+}
+
+impl XacrimonDashMap<EntryMutexStd> {
+    /// It checks the memory limits before inserting a new torrent.
+    ///
+    /// If the repository is full and the policy allows it, the least recently
+    /// active torrent from a sample of torrents is evicted. It returns the
+    /// infohash of the evicted torrent, if any, or [`TorrentRefused`] when the
+    /// new torrent must not be inserted.
+    fn make_room_for_torrent(&self, info_hash: &InfoHash) -> Result<Option<InfoHash>, TorrentRefused> {
+        match self.limiter.torrent_admission(self.torrents.len()) {
+            TorrentAdmission::Accept => Ok(None),
+            TorrentAdmission::Refuse => {
+                self.limiter.record_torrent_refused();
+                Err(TorrentRefused)
+            }
+            TorrentAdmission::EvictOne => {
+                // The sample must be collected before removing the torrent
+                // because reading a shard keeps it locked.
+                let sample = self.eviction_sample(info_hash);
+
+                let evicted_info_hash = least_recently_active(sample);
+
+                if let Some((_, entry)) = evicted_info_hash.and_then(|evicted| self.torrents.remove(&evicted)) {
+                    self.limiter.record_torrent_evicted(entry.get_peers_len());
+                }
+
+                Ok(evicted_info_hash)
+            }
+        }
+    }
+
+    /// It returns the last activity of up to [`EVICTION_SAMPLE_SIZE`]
+    /// torrents, starting at a random position of a random shard.
+    ///
+    /// The position is taken from the infohash of the new torrent, which is
+    /// already random, so every torrent has the same chance of being sampled.
+    fn eviction_sample(&self, info_hash: &InfoHash) -> Vec<(InfoHash, DurationSinceUnixEpoch)> {
+        let seed = |bytes: &[u8]| usize::from_le_bytes(bytes.try_into().expect("it should be a usize"));
+        let shard_seed = seed(&info_hash.0[..size_of::<usize>()]);
+        let position_seed = seed(&info_hash.0[size_of::<usize>()..2 * size_of::<usize>()]);
+
+        let shards = self.torrents.shards();
+        let mut sample = Vec::with_capacity(EVICTION_SAMPLE_SIZE);
+
+        for shard_index in (0..shards.len()).map(|i| shard_seed.wrapping_add(i) % shards.len()) {
+            let shard = shards[shard_index].read();
+
+            if shard.is_empty() {
+                continue;
+            }
+
+            let position = position_seed % shard.len();
+
+            // SAFETY: the buckets are only read while the shard read lock is
+            // held, so the table can't be modified or freed meanwhile.
+            let torrents = unsafe { shard.iter().skip(position).chain(shard.iter().take(position)) };
+
+            for bucket in torrents.take(EVICTION_SAMPLE_SIZE - sample.len()) {
+                // SAFETY: see above.
+                let (info_hash, entry) = unsafe { bucket.as_ref() };
+
+                sample.push((*info_hash, entry.get().get_last_activity()));
+            }
+
+            if sample.len() == EVICTION_SAMPLE_SIZE {
+                break;
+            }
+        }
+
+        sample
+    }
 }
 
 impl Repository<EntryMutexStd> for XacrimonDashMap<EntryMutexStd>
@@ -23,15 +108,31 @@ where
     EntryMutexStd: EntrySync,
     EntrySingle: Entry,
 {
-    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
-        if let Some(entry) = self.torrents.get(info_hash) {
-            entry.upsert_peer(peer);
+    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
+        let peer_quota = self.limiter.peer_quota();
+
+        let mut evicted_torrent = None;
+
+        let mut changes = if let Some(entry) = self.torrents.get(info_hash) {
+            entry.upsert_peer_within_quota(peer, &peer_quota)
         } else {
+            match self.make_room_for_torrent(info_hash) {
+                Ok(evicted_info_hash) => evicted_torrent = evicted_info_hash,
+                Err(TorrentRefused) => return PeerChanges::refused(),
+            }
+
             let _unused = self.torrents.insert(*info_hash, Arc::default());
-            if let Some(entry) = self.torrents.get(info_hash) {
-                entry.upsert_peer(peer);
+            match self.torrents.get(info_hash) {
+                Some(entry) => entry.upsert_peer_within_quota(peer, &peer_quota),
+                None => return PeerChanges::refused(),
             }
-        }
+        };
+
+        self.limiter.record_peer_changes(&changes);
+
+        changes.evicted_torrent = evicted_torrent;
+
+        changes
     }
 
     fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
@@ -74,6 +175,44 @@ where
         }
     }
 
+    /// The dash map is not ordered, so it scans all the keys to find the next
+    /// ones after the cursor.
+    fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntryMutexStd)> {
+        let mut info_hashes: Vec<InfoHash> = self
+            .torrents
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|info_hash| after.map_or(true, |after| info_hash > after))
+            .collect();
+
+        info_hashes.sort_unstable();
+        info_hashes.truncate(limit);
+
+        info_hashes
+            .into_iter()
+            .filter_map(|info_hash| self.get(&info_hash).map(|entry| (info_hash, entry)))
+            .collect()
+    }
+
+    /// The dash map is not ordered, so it scans all the keys to find the next
+    /// ones after the cursor.
+    fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        let mut info_hashes: Vec<InfoHash> = self
+            .torrents
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|info_hash| after.map_or(true, |after| info_hash > after))
+            .collect();
+
+        info_hashes.sort_unstable();
+        info_hashes.truncate(limit);
+
+        info_hashes
+            .into_iter()
+            .filter_map(|info_hash| self.get_swarm_metadata(&info_hash).map(|stats| (info_hash, stats)))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         for (info_hash, completed) in persistent_torrents {
             if self.torrents.contains_key(info_hash) {
@@ -93,16 +232,31 @@ where
     }
 
     fn remove(&self, key: &InfoHash) -> Option<EntryMutexStd> {
-        self.torrents.remove(key).map(|(_key, value)| value.clone())
+        let maybe_entry = self.torrents.remove(key).map(|(_key, value)| value.clone());
+
+        if let Some(entry) = &maybe_entry {
+            self.limiter.record_torrent_removed(entry.get_peers_len());
+        }
+
+        maybe_entry
     }
 
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
+        let mut peers = 0;
+
         for entry in &self.torrents {
             entry.value().remove_inactive_peers(current_cutoff);
+            peers += entry.value().get_peers_len();
         }
+
+        self.limiter.reset_peers(peers);
     }
 
     fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
         self.torrents.retain(|_, entry| entry.meets_retaining_policy(policy));
     }
+
+    fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.limiter.get_metrics()
+    }
 }
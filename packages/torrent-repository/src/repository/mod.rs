@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
 use bittorrent_primitives::info_hash::InfoHash;
 use torrust_tracker_configuration::TrackerPolicy;
 use torrust_tracker_primitives::pagination::Pagination;
@@ -5,6 +8,10 @@ use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
 
+use crate::entry::Entry;
+use crate::limits::{least_recently_active, EvictionMetrics, Limiter, PeerChanges, TorrentAdmission, EVICTION_SAMPLE_SIZE};
+use crate::EntryMutexTokio;
+
 pub mod dash_map_mutex_std;
 pub mod rw_lock_std;
 pub mod rw_lock_std_mutex_std;
@@ -20,12 +27,19 @@ pub trait Repository<T>: Debug + Default + Sized + 'static {
     fn get(&self, key: &InfoHash) -> Option<T>;
     fn get_metrics(&self) -> TorrentsMetrics;
     fn get_paginated(&self, pagination: Option<&Pagination>) -> Vec<(InfoHash, T)>;
+    /// It returns up to `limit` torrent entries whose infohash comes after
+    /// `after`, in infohash order.
+    fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, T)>;
+    /// It returns the swarm metadata of up to `limit` torrents whose infohash
+    /// comes after `after`, in infohash order.
+    fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)>;
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents);
     fn remove(&self, key: &InfoHash) -> Option<T>;
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch);
     fn remove_peerless_torrents(&self, policy: &TrackerPolicy);
-    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer);
+    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges;
     fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata>;
+    fn get_eviction_metrics(&self) -> EvictionMetrics;
 }
 
 #[allow(clippy::module_name_repetitions)]
@@ -33,10 +47,82 @@ pub trait RepositoryAsync<T>: Debug + Default + Sized + 'static {
     fn get(&self, key: &InfoHash) -> impl std::future::Future<Output = Option<T>> + Send;
     fn get_metrics(&self) -> impl std::future::Future<Output = TorrentsMetrics> + Send;
     fn get_paginated(&self, pagination: Option<&Pagination>) -> impl std::future::Future<Output = Vec<(InfoHash, T)>> + Send;
+    /// It returns up to `limit` torrent entries whose infohash comes after
+    /// `after`, in infohash order.
+    fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> impl std::future::Future<Output = Vec<(InfoHash, T)>> + Send;
+    /// It returns the swarm metadata of up to `limit` torrents whose infohash
+    /// comes after `after`, in infohash order.
+    fn get_swarm_metadata_after(
+        &self,
+        after: Option<&InfoHash>,
+        limit: usize,
+    ) -> impl std::future::Future<Output = Vec<(InfoHash, SwarmMetadata)>> + Send;
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) -> impl std::future::Future<Output = ()> + Send;
     fn remove(&self, key: &InfoHash) -> impl std::future::Future<Output = Option<T>> + Send;
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) -> impl std::future::Future<Output = ()> + Send;
     fn remove_peerless_torrents(&self, policy: &TrackerPolicy) -> impl std::future::Future<Output = ()> + Send;
-    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> impl std::future::Future<Output = ()> + Send;
+    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> impl std::future::Future<Output = PeerChanges> + Send;
     fn get_swarm_metadata(&self, info_hash: &InfoHash) -> impl std::future::Future<Output = Option<SwarmMetadata>> + Send;
+    fn get_eviction_metrics(&self) -> EvictionMetrics;
+}
+
+/// It checks the memory limits before inserting a new torrent in a `BTreeMap`
+/// based repository.
+///
+/// If the repository is full and the policy allows it, the least recently
+/// active torrent from a sample of the torrents next to the new one is
+/// evicted and passed to `evicted`, which has to record it with
+/// [`Limiter::record_torrent_evicted`]. It returns the infohash of the
+/// evicted torrent, if any, or [`TorrentRefused`] when the new torrent must
+/// not be inserted.
+pub(crate) fn make_room_for_torrent<T>(
+    torrents: &mut BTreeMap<InfoHash, T>,
+    info_hash: &InfoHash,
+    limiter: &Limiter,
+    last_activity: impl Fn(&T) -> DurationSinceUnixEpoch,
+    evicted: impl FnOnce(T),
+) -> Result<Option<InfoHash>, TorrentRefused> {
+    match limiter.torrent_admission(torrents.len()) {
+        TorrentAdmission::Accept => Ok(None),
+        TorrentAdmission::Refuse => {
+            limiter.record_torrent_refused();
+            Err(TorrentRefused)
+        }
+        TorrentAdmission::EvictOne => {
+            let sample = torrents
+                .range(info_hash..)
+                .chain(torrents.iter())
+                .take(EVICTION_SAMPLE_SIZE)
+                .map(|(info_hash, entry)| (*info_hash, last_activity(entry)))
+                .collect::<Vec<_>>();
+
+            let evicted_info_hash = least_recently_active(sample);
+
+            if let Some(entry) = evicted_info_hash.and_then(|info_hash| torrents.remove(&info_hash)) {
+                evicted(entry);
+            }
+
+            Ok(evicted_info_hash)
+        }
+    }
+}
+
+/// The new torrent was refused because the repository is full.
+#[derive(Debug)]
+pub(crate) struct TorrentRefused;
+
+/// The range of the infohashes that come after the `after` cursor.
+pub(crate) fn infohashes_after(after: Option<&InfoHash>) -> (Bound<&InfoHash>, Bound<&InfoHash>) {
+    (after.map_or(Bound::Unbounded, Bound::Excluded), Bound::Unbounded)
+}
+
+/// The last activity of a torrent entry guarded by a Tokio mutex.
+///
+/// It's used to choose which torrent to evict while holding the repository
+/// lock, so it does not wait for the entry lock. A locked entry is being
+/// updated right now, so it's considered the most recently active one.
+pub(crate) fn last_activity_without_waiting(entry: &EntryMutexTokio) -> DurationSinceUnixEpoch {
+    entry
+        .try_lock()
+        .map_or(DurationSinceUnixEpoch::MAX, |entry| entry.get_last_activity())
 }
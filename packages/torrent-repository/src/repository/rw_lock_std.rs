@@ -1,21 +1,32 @@
 use bittorrent_primitives::info_hash::InfoHash;
-use torrust_tracker_configuration::TrackerPolicy;
+use torrust_tracker_configuration::{MemoryLimits, TrackerPolicy};
 use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
 
-use super::Repository;
+use super::{infohashes_after, make_room_for_torrent, Repository, TorrentRefused};
 use crate::entry::peer_list::PeerList;
 use crate::entry::Entry;
+use crate::limits::{EvictionMetrics, Limiter, PeerChanges};
 use crate::{EntrySingle, TorrentsRwLockStd};
 
 #[derive(Default, Debug)]
 pub struct RwLockStd<T> {
     pub(crate) torrents: std::sync::RwLock<std::collections::BTreeMap<InfoHash, T>>,
+    pub(crate) limiter: Limiter,
 }
 
 impl<T> RwLockStd<T> {
+    /// It creates an empty repository that enforces the given memory limits.
+    #[must_use]
+    pub fn new(limits: MemoryLimits) -> Self {
+        Self {
+            torrents: std::sync::RwLock::default(),
+            limiter: Limiter::new(limits),
+        }
+    }
+
     /// # Panics
     ///
     /// Panics if unable to get a lock.
@@ -46,12 +57,29 @@ impl Repository<EntrySingle> for TorrentsRwLockStd
 where
     EntrySingle: Entry,
 {
-    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
+        let mut evicted_torrent = None;
+
         let mut db = self.get_torrents_mut();
 
+        if !db.contains_key(info_hash) {
+            match make_room_for_torrent(&mut db, info_hash, &self.limiter, Entry::get_last_activity, |entry| {
+                self.limiter.record_torrent_evicted(Entry::get_peers_len(&entry))
+            }) {
+                Ok(evicted_info_hash) => evicted_torrent = evicted_info_hash,
+                Err(TorrentRefused) => return PeerChanges::refused(),
+            }
+        }
+
         let entry = db.entry(*info_hash).or_insert(EntrySingle::default());
 
-        entry.upsert_peer(peer);
+        let mut changes = entry.upsert_peer_within_quota(peer, &self.limiter.peer_quota());
+
+        self.limiter.record_peer_changes(&changes);
+
+        changes.evicted_torrent = evicted_torrent;
+
+        changes
     }
 
     fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
@@ -91,6 +119,22 @@ where
         }
     }
 
+    fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntrySingle)> {
+        self.get_torrents()
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|(info_hash, entry)| (*info_hash, entry.clone()))
+            .collect()
+    }
+
+    fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        self.get_torrents()
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|(info_hash, entry)| (*info_hash, entry.get_swarm_metadata()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         let mut torrents = self.get_torrents_mut();
 
@@ -111,16 +155,26 @@ where
 
     fn remove(&self, key: &InfoHash) -> Option<EntrySingle> {
         let mut db = self.get_torrents_mut();
-        db.remove(key)
+        let maybe_entry = db.remove(key);
+
+        if let Some(entry) = &maybe_entry {
+            self.limiter.record_torrent_removed(entry.get_peers_len());
+        }
+
+        maybe_entry
     }
 
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
         let mut db = self.get_torrents_mut();
         let entries = db.values_mut();
+        let mut peers = 0;
 
         for entry in entries {
             entry.remove_inactive_peers(current_cutoff);
+            peers += entry.get_peers_len();
         }
+
+        self.limiter.reset_peers(peers);
     }
 
     fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
@@ -128,4 +182,8 @@ where
 
         db.retain(|_, e| e.meets_retaining_policy(policy));
     }
+
+    fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.limiter.get_metrics()
+    }
 }
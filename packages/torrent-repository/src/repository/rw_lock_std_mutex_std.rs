@@ -7,9 +7,10 @@ use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
 
-use super::Repository;
+use super::{infohashes_after, make_room_for_torrent, Repository, TorrentRefused};
 use crate::entry::peer_list::PeerList;
 use crate::entry::{Entry, EntrySync};
+use crate::limits::{EvictionMetrics, PeerChanges};
 use crate::{EntryMutexStd, EntrySingle, TorrentsRwLockStdMutexStd};
 
 impl TorrentsRwLockStdMutexStd {
@@ -33,18 +34,36 @@ where
     EntryMutexStd: EntrySync,
     EntrySingle: Entry,
 {
-    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
+        let mut evicted_torrent = None;
+
         let maybe_entry = self.get_torrents().get(info_hash).cloned();
 
         let entry = if let Some(entry) = maybe_entry {
             entry
         } else {
             let mut db = self.get_torrents_mut();
+
+            if !db.contains_key(info_hash) {
+                match make_room_for_torrent(&mut db, info_hash, &self.limiter, EntrySync::get_last_activity, |entry| {
+                    self.limiter.record_torrent_evicted(EntrySync::get_peers_len(&entry))
+                }) {
+                    Ok(evicted_info_hash) => evicted_torrent = evicted_info_hash,
+                    Err(TorrentRefused) => return PeerChanges::refused(),
+                }
+            }
+
             let entry = db.entry(*info_hash).or_insert(Arc::default());
             entry.clone()
         };
 
-        entry.upsert_peer(peer);
+        let mut changes = entry.upsert_peer_within_quota(peer, &self.limiter.peer_quota());
+
+        self.limiter.record_peer_changes(&changes);
+
+        changes.evicted_torrent = evicted_torrent;
+
+        changes
     }
 
     fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
@@ -86,6 +105,22 @@ where
         }
     }
 
+    fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntryMutexStd)> {
+        self.get_torrents()
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|(info_hash, entry)| (*info_hash, entry.clone()))
+            .collect()
+    }
+
+    fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        self.get_torrents()
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|(info_hash, entry)| (*info_hash, entry.get_swarm_metadata()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         let mut torrents = self.get_torrents_mut();
 
@@ -109,16 +144,26 @@ where
 
     fn remove(&self, key: &InfoHash) -> Option<EntryMutexStd> {
         let mut db = self.get_torrents_mut();
-        db.remove(key)
+        let maybe_entry = db.remove(key);
+
+        if let Some(entry) = &maybe_entry {
+            self.limiter.record_torrent_removed(entry.get_peers_len());
+        }
+
+        maybe_entry
     }
 
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
         let db = self.get_torrents();
         let entries = db.values().cloned();
+        let mut peers = 0;
 
         for entry in entries {
             entry.remove_inactive_peers(current_cutoff);
+            peers += entry.get_peers_len();
         }
+
+        self.limiter.reset_peers(peers);
     }
 
     fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
@@ -126,4 +171,8 @@ where
 
         db.retain(|_, e| e.lock().expect("it should lock entry").meets_retaining_policy(policy));
     }
+
+    fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.limiter.get_metrics()
+    }
 }
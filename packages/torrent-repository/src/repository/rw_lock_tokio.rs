@@ -1,21 +1,32 @@
 use bittorrent_primitives::info_hash::InfoHash;
-use torrust_tracker_configuration::TrackerPolicy;
+use torrust_tracker_configuration::{MemoryLimits, TrackerPolicy};
 use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
 
-use super::RepositoryAsync;
+use super::{infohashes_after, make_room_for_torrent, RepositoryAsync, TorrentRefused};
 use crate::entry::peer_list::PeerList;
 use crate::entry::Entry;
+use crate::limits::{EvictionMetrics, Limiter, PeerChanges};
 use crate::{EntrySingle, TorrentsRwLockTokio};
 
 #[derive(Default, Debug)]
 pub struct RwLockTokio<T> {
     pub(crate) torrents: tokio::sync::RwLock<std::collections::BTreeMap<InfoHash, T>>,
+    pub(crate) limiter: Limiter,
 }
 
 impl<T> RwLockTokio<T> {
+    /// It creates an empty repository that enforces the given memory limits.
+    #[must_use]
+    pub fn new(limits: MemoryLimits) -> Self {
+        Self {
+            torrents: tokio::sync::RwLock::default(),
+            limiter: Limiter::new(limits),
+        }
+    }
+
     pub fn write(
         &self,
     ) -> impl std::future::Future<
@@ -47,12 +58,29 @@ impl RepositoryAsync<EntrySingle> for TorrentsRwLockTokio
 where
     EntrySingle: Entry,
 {
-    async fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+    async fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
+        let mut evicted_torrent = None;
+
         let mut db = self.get_torrents_mut().await;
 
+        if !db.contains_key(info_hash) {
+            match make_room_for_torrent(&mut db, info_hash, &self.limiter, Entry::get_last_activity, |entry| {
+                self.limiter.record_torrent_evicted(Entry::get_peers_len(&entry))
+            }) {
+                Ok(evicted_info_hash) => evicted_torrent = evicted_info_hash,
+                Err(TorrentRefused) => return PeerChanges::refused(),
+            }
+        }
+
         let entry = db.entry(*info_hash).or_insert(EntrySingle::default());
 
-        entry.upsert_peer(peer);
+        let mut changes = entry.upsert_peer_within_quota(peer, &self.limiter.peer_quota());
+
+        self.limiter.record_peer_changes(&changes);
+
+        changes.evicted_torrent = evicted_torrent;
+
+        changes
     }
 
     async fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
@@ -78,6 +106,24 @@ where
         }
     }
 
+    async fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntrySingle)> {
+        self.get_torrents()
+            .await
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|(info_hash, entry)| (*info_hash, entry.clone()))
+            .collect()
+    }
+
+    async fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        self.get_torrents()
+            .await
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|(info_hash, entry)| (*info_hash, entry.get_swarm_metadata()))
+            .collect()
+    }
+
     async fn get_metrics(&self) -> TorrentsMetrics {
         let mut metrics = TorrentsMetrics::default();
 
@@ -112,16 +158,26 @@ where
 
     async fn remove(&self, key: &InfoHash) -> Option<EntrySingle> {
         let mut db = self.get_torrents_mut().await;
-        db.remove(key)
+        let maybe_entry = db.remove(key);
+
+        if let Some(entry) = &maybe_entry {
+            self.limiter.record_torrent_removed(entry.get_peers_len());
+        }
+
+        maybe_entry
     }
 
     async fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
         let mut db = self.get_torrents_mut().await;
         let entries = db.values_mut();
+        let mut peers = 0;
 
         for entry in entries {
             entry.remove_inactive_peers(current_cutoff);
+            peers += entry.get_peers_len();
         }
+
+        self.limiter.reset_peers(peers);
     }
 
     async fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
@@ -129,4 +185,8 @@ where
 
         db.retain(|_, e| e.meets_retaining_policy(policy));
     }
+
+    fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.limiter.get_metrics()
+    }
 }
@@ -7,9 +7,10 @@ use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
 
-use super::RepositoryAsync;
+use super::{infohashes_after, last_activity_without_waiting, make_room_for_torrent, RepositoryAsync, TorrentRefused};
 use crate::entry::peer_list::PeerList;
 use crate::entry::{Entry, EntryAsync};
+use crate::limits::{EvictionMetrics, PeerChanges};
 use crate::{EntryMutexTokio, EntrySingle, TorrentsRwLockTokioMutexTokio};
 
 impl TorrentsRwLockTokioMutexTokio {
@@ -35,18 +36,43 @@ where
     EntryMutexTokio: EntryAsync,
     EntrySingle: Entry,
 {
-    async fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+    async fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
         let maybe_entry = self.get_torrents().await.get(info_hash).cloned();
 
+        let mut evicted = None;
+        let mut evicted_torrent = None;
+
         let entry = if let Some(entry) = maybe_entry {
             entry
         } else {
             let mut db = self.get_torrents_mut().await;
+
+            if !db.contains_key(info_hash) {
+                match make_room_for_torrent(&mut db, info_hash, &self.limiter, last_activity_without_waiting, |entry| {
+                    evicted = Some(entry)
+                }) {
+                    Ok(evicted_info_hash) => evicted_torrent = evicted_info_hash,
+                    Err(TorrentRefused) => return PeerChanges::refused(),
+                }
+            }
+
             let entry = db.entry(*info_hash).or_insert(Arc::default());
             entry.clone()
         };
 
-        entry.upsert_peer(peer).await;
+        // The peers of the evicted torrent are counted once nobody is updating
+        // it, without holding the repository lock.
+        if let Some(entry) = evicted {
+            self.limiter.record_torrent_evicted(entry.get_peers_len().await);
+        }
+
+        let mut changes = entry.upsert_peer_within_quota(peer, &self.limiter.peer_quota()).await;
+
+        self.limiter.record_peer_changes(&changes);
+
+        changes.evicted_torrent = evicted_torrent;
+
+        changes
     }
 
     async fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
@@ -75,6 +101,33 @@ where
         }
     }
 
+    async fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntryMutexTokio)> {
+        self.get_torrents()
+            .await
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|(info_hash, entry)| (*info_hash, entry.clone()))
+            .collect()
+    }
+
+    async fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        let entries: Vec<_> = self
+            .get_torrents()
+            .await
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|(info_hash, entry)| (*info_hash, entry.clone()))
+            .collect();
+
+        let mut torrents = Vec::with_capacity(entries.len());
+
+        for (info_hash, entry) in entries {
+            torrents.push((info_hash, entry.get_swarm_metadata().await));
+        }
+
+        torrents
+    }
+
     async fn get_metrics(&self) -> TorrentsMetrics {
         let mut metrics = TorrentsMetrics::default();
 
@@ -112,16 +165,26 @@ where
 
     async fn remove(&self, key: &InfoHash) -> Option<EntryMutexTokio> {
         let mut db = self.get_torrents_mut().await;
-        db.remove(key)
+        let maybe_entry = db.remove(key);
+
+        if let Some(entry) = &maybe_entry {
+            self.limiter.record_torrent_removed(entry.get_peers_len().await);
+        }
+
+        maybe_entry
     }
 
     async fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
         let db = self.get_torrents().await;
         let entries = db.values().cloned();
+        let mut peers = 0;
 
         for entry in entries {
-            entry.remove_inactive_peers(current_cutoff).await;
+            entry.clone().remove_inactive_peers(current_cutoff).await;
+            peers += entry.get_peers_len().await;
         }
+
+        self.limiter.reset_peers(peers);
     }
 
     async fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
@@ -139,4 +202,8 @@ where
             drop(db.remove(&remove));
         }
     }
+
+    fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.limiter.get_metrics()
+    }
 }
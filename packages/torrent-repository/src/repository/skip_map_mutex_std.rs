@@ -1,21 +1,234 @@
 use std::sync::Arc;
 
+use aquatic_udp_protocol::{AnnounceEvent, PeerId};
 use bittorrent_primitives::info_hash::InfoHash;
 use crossbeam_skiplist::SkipMap;
-use torrust_tracker_configuration::TrackerPolicy;
+use torrust_tracker_configuration::{MemoryLimits, TrackerPolicy};
 use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
 
-use super::Repository;
+use super::{infohashes_after, Repository, TorrentRefused};
 use crate::entry::peer_list::PeerList;
 use crate::entry::{Entry, EntrySync};
+use crate::expiry::{ExpiredPeers, ExpiryKey, ExpiryWheel, PeerExpiry};
+use crate::limits::{least_recently_active, EvictionMetrics, Limiter, PeerChanges, TorrentAdmission, EVICTION_SAMPLE_SIZE};
+use crate::tally::PeerTally;
 use crate::{EntryMutexParkingLot, EntryMutexStd, EntryRwLockParkingLot, EntrySingle};
 
 #[derive(Default, Debug)]
 pub struct CrossbeamSkipList<T> {
     pub torrents: SkipMap<InfoHash, T>,
+    pub(crate) limiter: Limiter,
+    pub(crate) expiry: ExpiryWheel,
+    pub(crate) tally: Option<Arc<dyn PeerTally>>,
+}
+
+impl<T> CrossbeamSkipList<T> {
+    /// It creates an empty repository that enforces the given memory limits.
+    #[must_use]
+    pub fn new(limits: MemoryLimits) -> Self {
+        Self {
+            torrents: SkipMap::new(),
+            limiter: Limiter::new(limits),
+            expiry: ExpiryWheel::default(),
+            tally: None,
+        }
+    }
+
+    /// It notifies the `tally` of all the peers added to and removed from
+    /// the repository from now on.
+    #[must_use]
+    pub fn with_tally(mut self, tally: Arc<dyn PeerTally>) -> Self {
+        self.tally = Some(tally);
+        self
+    }
+
+    /// The fraction, from `0.0` to `1.0`, of the most used memory limit that
+    /// is in use.
+    #[must_use]
+    pub fn get_memory_usage(&self) -> f64 {
+        self.limiter.memory_usage(self.torrents.len())
+    }
+}
+
+impl<T> CrossbeamSkipList<T>
+where
+    T: EntrySync + Send + 'static,
+{
+    /// It checks the memory limits before inserting a new torrent.
+    ///
+    /// If the repository is full and the policy allows it, the least recently
+    /// active torrent from a sample of the torrents next to the new one is
+    /// evicted. It returns the infohash of the evicted torrent, if any, or
+    /// [`TorrentRefused`] when the new torrent must not be inserted.
+    fn make_room_for_torrent(&self, info_hash: &InfoHash) -> Result<Option<InfoHash>, TorrentRefused> {
+        match self.limiter.torrent_admission(self.torrents.len()) {
+            TorrentAdmission::Accept => Ok(None),
+            TorrentAdmission::Refuse => {
+                self.limiter.record_torrent_refused();
+                Err(TorrentRefused)
+            }
+            TorrentAdmission::EvictOne => {
+                let sample = self
+                    .torrents
+                    .range(info_hash..)
+                    .chain(self.torrents.iter())
+                    .take(EVICTION_SAMPLE_SIZE)
+                    .map(|entry| (*entry.key(), entry.value().get_last_activity()))
+                    .collect::<Vec<_>>();
+
+                let evicted_info_hash = least_recently_active(sample);
+
+                if let Some(entry) = evicted_info_hash.and_then(|evicted| self.torrents.remove(&evicted)) {
+                    self.limiter.record_torrent_evicted(entry.value().get_peers_len());
+                    self.tally_removed_torrent(entry.value());
+                }
+
+                Ok(evicted_info_hash)
+            }
+        }
+    }
+
+    /// It notifies the tally of the peers added, updated or removed by an
+    /// upsert.
+    fn tally_peer_changes(&self, peer: &peer::Peer, changes: &PeerChanges) {
+        let Some(tally) = &self.tally else {
+            return;
+        };
+
+        if let Some(evicted) = &changes.evicted {
+            tally.remove(evicted);
+        }
+
+        if changes.refused {
+            return;
+        }
+
+        match (&changes.previous, peer::ReadInfo::get_event(peer) == AnnounceEvent::Stopped) {
+            (Some(previous), true) => tally.remove(previous),
+            (Some(previous), false) => tally.update(previous, peer),
+            (None, true) => {}
+            (None, false) => tally.add(peer),
+        }
+    }
+
+    /// It notifies the tally of the peers removed with their torrent.
+    fn tally_removed_torrent(&self, entry: &T) {
+        if let Some(tally) = &self.tally {
+            for peer in entry.get_peers(None) {
+                tally.remove(&peer);
+            }
+        }
+    }
+
+    /// It notifies the tally of removed peers.
+    fn tally_removed_peers<'a>(&self, peers: impl IntoIterator<Item = &'a peer::Peer>) {
+        if let Some(tally) = &self.tally {
+            for peer in peers {
+                tally.remove(peer);
+            }
+        }
+    }
+
+    /// It indexes an updated peer so that it can be expired incrementally.
+    fn schedule_expiry(&self, info_hash: &InfoHash, peer: &peer::Peer, changes: &PeerChanges) {
+        if changes.refused || peer::ReadInfo::get_event(peer) == AnnounceEvent::Stopped {
+            return;
+        }
+
+        self.expiry.schedule(
+            ExpiryKey {
+                info_hash: *info_hash,
+                peer_id: peer::ReadInfo::get_id(peer),
+            },
+            peer::ReadInfo::get_updated(peer),
+            changes.previous.map(|previous| previous.updated),
+        );
+    }
+
+    /// It removes the peers that have not been updated after the
+    /// `current_cutoff`, inspecting at most `max_peers` peers.
+    ///
+    /// Unlike [`remove_inactive_peers`](Repository::remove_inactive_peers)
+    /// it does not walk the whole repository. It only visits the swarms of
+    /// the peers that were last updated before the cutoff, so it can be
+    /// called often with a small batch size without blocking announces.
+    ///
+    /// Torrents left without peers are removed too when the `policy` does
+    /// not retain them.
+    pub fn remove_expired_peers(
+        &self,
+        current_cutoff: DurationSinceUnixEpoch,
+        policy: &TrackerPolicy,
+        max_peers: usize,
+    ) -> ExpiredPeers {
+        let mut expired = ExpiredPeers::default();
+
+        expired.finished = self.expiry.expire(current_cutoff, max_peers, |key| {
+            let Some(entry) = self.torrents.get(&key.info_hash) else {
+                return PeerExpiry::Missing;
+            };
+
+            let expiry = entry.value().remove_peer_if_inactive(&key.peer_id, current_cutoff);
+
+            if let PeerExpiry::Removed(peer) = &expiry {
+                expired.peers += 1;
+                self.limiter.record_peers_removed(1);
+                self.tally_removed_peers([peer]);
+
+                if !entry.value().meets_retaining_policy(policy) {
+                    entry.remove();
+                    expired.torrents.push(key.info_hash);
+                }
+            }
+
+            expiry
+        });
+
+        expired
+    }
+
+    /// It removes the torrents that do not meet the retaining `policy`, like
+    /// [`remove_peerless_torrents`](Repository::remove_peerless_torrents),
+    /// and returns their info hashes.
+    pub fn take_peerless_torrents(&self, policy: &TrackerPolicy) -> Vec<InfoHash> {
+        let mut removed = vec![];
+
+        for entry in &self.torrents {
+            if entry.value().meets_retaining_policy(policy) {
+                continue;
+            }
+
+            if entry.remove() {
+                removed.push(*entry.key());
+            }
+        }
+
+        removed
+    }
+
+    /// It removes a single peer from a torrent's swarm, regardless of its
+    /// activity. It returns the removed peer, or `None` if the torrent or the
+    /// peer do not exist.
+    ///
+    /// The torrent is removed too if it's left without peers and the `policy`
+    /// does not retain it.
+    pub fn remove_peer(&self, info_hash: &InfoHash, peer_id: &PeerId, policy: &TrackerPolicy) -> Option<peer::Peer> {
+        let entry = self.torrents.get(info_hash)?;
+
+        let removed = entry.value().remove_peer(peer_id)?;
+
+        self.limiter.record_peers_removed(1);
+        self.tally_removed_peers([&removed]);
+
+        if !entry.value().meets_retaining_policy(policy) {
+            entry.remove();
+        }
+
+        Some(removed)
+    }
 }
 
 impl Repository<EntryMutexStd> for CrossbeamSkipList<EntryMutexStd>
@@ -23,9 +236,29 @@ where
     EntryMutexStd: EntrySync,
     EntrySingle: Entry,
 {
-    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
+        let mut evicted_torrent = None;
+
+        if !self.torrents.contains_key(info_hash) {
+            match self.make_room_for_torrent(info_hash) {
+                Ok(evicted_info_hash) => evicted_torrent = evicted_info_hash,
+                Err(TorrentRefused) => return PeerChanges::refused(),
+            }
+        }
+
         let entry = self.torrents.get_or_insert(*info_hash, Arc::default());
-        entry.value().upsert_peer(peer);
+
+        let mut changes = entry.value().upsert_peer_within_quota(peer, &self.limiter.peer_quota());
+
+        self.limiter.record_peer_changes(&changes);
+
+        changes.evicted_torrent = evicted_torrent;
+
+        self.tally_peer_changes(peer, &changes);
+
+        self.schedule_expiry(info_hash, peer, &changes);
+
+        changes
     }
 
     fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
@@ -68,6 +301,22 @@ where
         }
     }
 
+    fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntryMutexStd)> {
+        self.torrents
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
+    fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        self.torrents
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|entry| (*entry.key(), entry.value().get_swarm_metadata()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         for (info_hash, completed) in persistent_torrents {
             if self.torrents.contains_key(info_hash) {
@@ -89,13 +338,26 @@ where
     }
 
     fn remove(&self, key: &InfoHash) -> Option<EntryMutexStd> {
-        self.torrents.remove(key).map(|entry| entry.value().clone())
+        let maybe_entry = self.torrents.remove(key).map(|entry| entry.value().clone());
+
+        if let Some(entry) = &maybe_entry {
+            self.limiter.record_torrent_removed(entry.get_peers_len());
+            self.tally_removed_torrent(entry);
+        }
+
+        maybe_entry
     }
 
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
+        let mut peers = 0;
+
         for entry in &self.torrents {
-            entry.value().remove_inactive_peers(current_cutoff);
+            let removed = entry.value().remove_inactive_peers(current_cutoff);
+            self.tally_removed_peers(&removed);
+            peers += entry.value().get_peers_len();
         }
+
+        self.limiter.reset_peers(peers);
     }
 
     fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
@@ -107,6 +369,10 @@ where
             entry.remove();
         }
     }
+
+    fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.limiter.get_metrics()
+    }
 }
 
 impl Repository<EntryRwLockParkingLot> for CrossbeamSkipList<EntryRwLockParkingLot>
@@ -114,9 +380,29 @@ where
     EntryRwLockParkingLot: EntrySync,
     EntrySingle: Entry,
 {
-    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
+        let mut evicted_torrent = None;
+
+        if !self.torrents.contains_key(info_hash) {
+            match self.make_room_for_torrent(info_hash) {
+                Ok(evicted_info_hash) => evicted_torrent = evicted_info_hash,
+                Err(TorrentRefused) => return PeerChanges::refused(),
+            }
+        }
+
         let entry = self.torrents.get_or_insert(*info_hash, Arc::default());
-        entry.value().upsert_peer(peer);
+
+        let mut changes = entry.value().upsert_peer_within_quota(peer, &self.limiter.peer_quota());
+
+        self.limiter.record_peer_changes(&changes);
+
+        changes.evicted_torrent = evicted_torrent;
+
+        self.tally_peer_changes(peer, &changes);
+
+        self.schedule_expiry(info_hash, peer, &changes);
+
+        changes
     }
 
     fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
@@ -159,6 +445,22 @@ where
         }
     }
 
+    fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntryRwLockParkingLot)> {
+        self.torrents
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
+    fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        self.torrents
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|entry| (*entry.key(), entry.value().get_swarm_metadata()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         for (info_hash, completed) in persistent_torrents {
             if self.torrents.contains_key(info_hash) {
@@ -180,13 +482,26 @@ where
     }
 
     fn remove(&self, key: &InfoHash) -> Option<EntryRwLockParkingLot> {
-        self.torrents.remove(key).map(|entry| entry.value().clone())
+        let maybe_entry = self.torrents.remove(key).map(|entry| entry.value().clone());
+
+        if let Some(entry) = &maybe_entry {
+            self.limiter.record_torrent_removed(entry.get_peers_len());
+            self.tally_removed_torrent(entry);
+        }
+
+        maybe_entry
     }
 
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
+        let mut peers = 0;
+
         for entry in &self.torrents {
-            entry.value().remove_inactive_peers(current_cutoff);
+            let removed = entry.value().remove_inactive_peers(current_cutoff);
+            self.tally_removed_peers(&removed);
+            peers += entry.value().get_peers_len();
         }
+
+        self.limiter.reset_peers(peers);
     }
 
     fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
@@ -198,6 +513,10 @@ where
             entry.remove();
         }
     }
+
+    fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.limiter.get_metrics()
+    }
 }
 
 impl Repository<EntryMutexParkingLot> for CrossbeamSkipList<EntryMutexParkingLot>
@@ -205,9 +524,29 @@ where
     EntryMutexParkingLot: EntrySync,
     EntrySingle: Entry,
 {
-    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+    fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
+        let mut evicted_torrent = None;
+
+        if !self.torrents.contains_key(info_hash) {
+            match self.make_room_for_torrent(info_hash) {
+                Ok(evicted_info_hash) => evicted_torrent = evicted_info_hash,
+                Err(TorrentRefused) => return PeerChanges::refused(),
+            }
+        }
+
         let entry = self.torrents.get_or_insert(*info_hash, Arc::default());
-        entry.value().upsert_peer(peer);
+
+        let mut changes = entry.value().upsert_peer_within_quota(peer, &self.limiter.peer_quota());
+
+        self.limiter.record_peer_changes(&changes);
+
+        changes.evicted_torrent = evicted_torrent;
+
+        self.tally_peer_changes(peer, &changes);
+
+        self.schedule_expiry(info_hash, peer, &changes);
+
+        changes
     }
 
     fn get_swarm_metadata(&self, info_hash: &InfoHash) -> Option<SwarmMetadata> {
@@ -250,6 +589,22 @@ where
         }
     }
 
+    fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntryMutexParkingLot)> {
+        self.torrents
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
+    fn get_swarm_metadata_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, SwarmMetadata)> {
+        self.torrents
+            .range(infohashes_after(after))
+            .take(limit)
+            .map(|entry| (*entry.key(), entry.value().get_swarm_metadata()))
+            .collect()
+    }
+
     fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         for (info_hash, completed) in persistent_torrents {
             if self.torrents.contains_key(info_hash) {
@@ -271,13 +626,26 @@ where
     }
 
     fn remove(&self, key: &InfoHash) -> Option<EntryMutexParkingLot> {
-        self.torrents.remove(key).map(|entry| entry.value().clone())
+        let maybe_entry = self.torrents.remove(key).map(|entry| entry.value().clone());
+
+        if let Some(entry) = &maybe_entry {
+            self.limiter.record_torrent_removed(entry.get_peers_len());
+            self.tally_removed_torrent(entry);
+        }
+
+        maybe_entry
     }
 
     fn remove_inactive_peers(&self, current_cutoff: DurationSinceUnixEpoch) {
+        let mut peers = 0;
+
         for entry in &self.torrents {
-            entry.value().remove_inactive_peers(current_cutoff);
+            let removed = entry.value().remove_inactive_peers(current_cutoff);
+            self.tally_removed_peers(&removed);
+            peers += entry.value().get_peers_len();
         }
+
+        self.limiter.reset_peers(peers);
     }
 
     fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
@@ -289,4 +657,8 @@ where
             entry.remove();
         }
     }
+
+    fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.limiter.get_metrics()
+    }
 }
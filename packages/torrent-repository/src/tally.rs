@@ -0,0 +1,34 @@
+//! Aggregate counts of the peers in a repository.
+//!
+//! Some statistics, like the number of peers by client software, would need
+//! to walk all the peers in the repository every time they are requested. A
+//! [`PeerTally`] keeps them up to date instead: the repository notifies it
+//! every time a peer is added to or removed from a swarm, so the statistics
+//! can be read without visiting the swarms.
+//!
+//! Only the [`CrossbeamSkipList`](crate::repository::skip_map_mutex_std::CrossbeamSkipList)
+//! repositories, the ones used in production, notify a tally.
+use std::fmt::Debug;
+
+use torrust_tracker_primitives::peer;
+
+/// It's notified of the peers added to and removed from the swarms.
+///
+/// The notifications for different swarms can arrive concurrently, so the
+/// implementations have to synchronize their counters.
+pub trait PeerTally: Debug + Send + Sync {
+    /// A new peer was added to a swarm.
+    fn add(&self, peer: &peer::Peer);
+
+    /// A peer was removed from a swarm, for whatever reason.
+    fn remove(&self, peer: &peer::Peer);
+
+    /// A peer already in a swarm was updated.
+    ///
+    /// By default, it's counted as removing the previous version of the peer
+    /// and adding the current one.
+    fn update(&self, previous: &peer::Peer, current: &peer::Peer) {
+        self.remove(previous);
+        self.add(current);
+    }
+}
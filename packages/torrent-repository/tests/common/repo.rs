@@ -1,9 +1,10 @@
 use bittorrent_primitives::info_hash::InfoHash;
-use torrust_tracker_configuration::TrackerPolicy;
+use torrust_tracker_configuration::{MemoryLimits, TrackerPolicy};
 use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
+use torrust_tracker_torrent_repository::limits::{EvictionMetrics, PeerChanges};
 use torrust_tracker_torrent_repository::repository::{Repository as _, RepositoryAsync as _};
 use torrust_tracker_torrent_repository::{
     EntrySingle, TorrentsDashMapMutexStd, TorrentsRwLockStd, TorrentsRwLockStdMutexStd, TorrentsRwLockStdMutexTokio,
@@ -26,7 +27,23 @@ pub(crate) enum Repo {
 }
 
 impl Repo {
-    pub(crate) async fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) {
+    /// It returns a new empty repository of the same kind with the given memory limits.
+    pub(crate) fn with_limits(&self, limits: MemoryLimits) -> Self {
+        match self {
+            Repo::RwLockStd(_) => Repo::RwLockStd(TorrentsRwLockStd::new(limits)),
+            Repo::RwLockStdMutexStd(_) => Repo::RwLockStdMutexStd(TorrentsRwLockStdMutexStd::new(limits)),
+            Repo::RwLockStdMutexTokio(_) => Repo::RwLockStdMutexTokio(TorrentsRwLockStdMutexTokio::new(limits)),
+            Repo::RwLockTokio(_) => Repo::RwLockTokio(TorrentsRwLockTokio::new(limits)),
+            Repo::RwLockTokioMutexStd(_) => Repo::RwLockTokioMutexStd(TorrentsRwLockTokioMutexStd::new(limits)),
+            Repo::RwLockTokioMutexTokio(_) => Repo::RwLockTokioMutexTokio(TorrentsRwLockTokioMutexTokio::new(limits)),
+            Repo::SkipMapMutexStd(_) => Repo::SkipMapMutexStd(TorrentsSkipMapMutexStd::new(limits)),
+            Repo::SkipMapMutexParkingLot(_) => Repo::SkipMapMutexParkingLot(TorrentsSkipMapMutexParkingLot::new(limits)),
+            Repo::SkipMapRwLockParkingLot(_) => Repo::SkipMapRwLockParkingLot(TorrentsSkipMapRwLockParkingLot::new(limits)),
+            Repo::DashMapMutexStd(_) => Repo::DashMapMutexStd(TorrentsDashMapMutexStd::new(limits)),
+        }
+    }
+
+    pub(crate) async fn upsert_peer(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
         match self {
             Repo::RwLockStd(repo) => repo.upsert_peer(info_hash, peer),
             Repo::RwLockStdMutexStd(repo) => repo.upsert_peer(info_hash, peer),
@@ -56,6 +73,25 @@ impl Repo {
         }
     }
 
+    pub(crate) async fn get_swarm_metadata_after(
+        &self,
+        after: Option<&InfoHash>,
+        limit: usize,
+    ) -> Vec<(InfoHash, SwarmMetadata)> {
+        match self {
+            Repo::RwLockStd(repo) => repo.get_swarm_metadata_after(after, limit),
+            Repo::RwLockStdMutexStd(repo) => repo.get_swarm_metadata_after(after, limit),
+            Repo::RwLockStdMutexTokio(repo) => repo.get_swarm_metadata_after(after, limit).await,
+            Repo::RwLockTokio(repo) => repo.get_swarm_metadata_after(after, limit).await,
+            Repo::RwLockTokioMutexStd(repo) => repo.get_swarm_metadata_after(after, limit).await,
+            Repo::RwLockTokioMutexTokio(repo) => repo.get_swarm_metadata_after(after, limit).await,
+            Repo::SkipMapMutexStd(repo) => repo.get_swarm_metadata_after(after, limit),
+            Repo::SkipMapMutexParkingLot(repo) => repo.get_swarm_metadata_after(after, limit),
+            Repo::SkipMapRwLockParkingLot(repo) => repo.get_swarm_metadata_after(after, limit),
+            Repo::DashMapMutexStd(repo) => repo.get_swarm_metadata_after(after, limit),
+        }
+    }
+
     pub(crate) async fn get(&self, key: &InfoHash) -> Option<EntrySingle> {
         match self {
             Repo::RwLockStd(repo) => repo.get(key),
@@ -140,6 +176,60 @@ impl Repo {
         }
     }
 
+    pub(crate) async fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntrySingle)> {
+        match self {
+            Repo::RwLockStd(repo) => repo.get_after(after, limit),
+            Repo::RwLockStdMutexStd(repo) => repo
+                .get_after(after, limit)
+                .iter()
+                .map(|(i, t)| (*i, t.lock().expect("it should get a lock").clone()))
+                .collect(),
+            Repo::RwLockStdMutexTokio(repo) => {
+                let mut v: Vec<(InfoHash, EntrySingle)> = vec![];
+
+                for (i, t) in repo.get_after(after, limit).await {
+                    v.push((i, t.lock().await.clone()));
+                }
+                v
+            }
+            Repo::RwLockTokio(repo) => repo.get_after(after, limit).await,
+            Repo::RwLockTokioMutexStd(repo) => repo
+                .get_after(after, limit)
+                .await
+                .iter()
+                .map(|(i, t)| (*i, t.lock().expect("it should get a lock").clone()))
+                .collect(),
+            Repo::RwLockTokioMutexTokio(repo) => {
+                let mut v: Vec<(InfoHash, EntrySingle)> = vec![];
+
+                for (i, t) in repo.get_after(after, limit).await {
+                    v.push((i, t.lock().await.clone()));
+                }
+                v
+            }
+            Repo::SkipMapMutexStd(repo) => repo
+                .get_after(after, limit)
+                .iter()
+                .map(|(i, t)| (*i, t.lock().expect("it should get a lock").clone()))
+                .collect(),
+            Repo::SkipMapMutexParkingLot(repo) => repo
+                .get_after(after, limit)
+                .iter()
+                .map(|(i, t)| (*i, t.lock().clone()))
+                .collect(),
+            Repo::SkipMapRwLockParkingLot(repo) => repo
+                .get_after(after, limit)
+                .iter()
+                .map(|(i, t)| (*i, t.read().clone()))
+                .collect(),
+            Repo::DashMapMutexStd(repo) => repo
+                .get_after(after, limit)
+                .iter()
+                .map(|(i, t)| (*i, t.lock().expect("it should get a lock").clone()))
+                .collect(),
+        }
+    }
+
     pub(crate) async fn import_persistent(&self, persistent_torrents: &PersistentTorrents) {
         match self {
             Repo::RwLockStd(repo) => repo.import_persistent(persistent_torrents),
@@ -200,6 +290,21 @@ impl Repo {
         }
     }
 
+    pub(crate) fn get_eviction_metrics(&self) -> EvictionMetrics {
+        match self {
+            Repo::RwLockStd(repo) => repo.get_eviction_metrics(),
+            Repo::RwLockStdMutexStd(repo) => repo.get_eviction_metrics(),
+            Repo::RwLockStdMutexTokio(repo) => repo.get_eviction_metrics(),
+            Repo::RwLockTokio(repo) => repo.get_eviction_metrics(),
+            Repo::RwLockTokioMutexStd(repo) => repo.get_eviction_metrics(),
+            Repo::RwLockTokioMutexTokio(repo) => repo.get_eviction_metrics(),
+            Repo::SkipMapMutexStd(repo) => repo.get_eviction_metrics(),
+            Repo::SkipMapMutexParkingLot(repo) => repo.get_eviction_metrics(),
+            Repo::SkipMapRwLockParkingLot(repo) => repo.get_eviction_metrics(),
+            Repo::DashMapMutexStd(repo) => repo.get_eviction_metrics(),
+        }
+    }
+
     pub(crate) async fn insert(&self, info_hash: &InfoHash, torrent: EntrySingle) -> Option<EntrySingle> {
         match self {
             Repo::RwLockStd(repo) => {
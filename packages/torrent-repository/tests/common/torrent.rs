@@ -1,10 +1,12 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use aquatic_udp_protocol::PeerId;
 use torrust_tracker_configuration::TrackerPolicy;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
-use torrust_tracker_torrent_repository::entry::{Entry as _, EntryAsync as _, EntrySync as _};
+use torrust_tracker_torrent_repository::entry::{Entry as _, EntryAsync as _, EntrySync as _, PeerPredicate};
+use torrust_tracker_torrent_repository::limits::{PeerChanges, PeerQuota};
 use torrust_tracker_torrent_repository::{
     EntryMutexParkingLot, EntryMutexStd, EntryMutexTokio, EntryRwLockParkingLot, EntrySingle,
 };
@@ -79,6 +81,31 @@ impl Torrent {
         }
     }
 
+    pub(crate) async fn get_peers_for_client_matching(
+        &self,
+        client: &SocketAddr,
+        limit: Option<usize>,
+        predicate: &PeerPredicate<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        match self {
+            Torrent::Single(entry) => entry.get_peers_for_client_matching(client, limit, predicate),
+            Torrent::MutexStd(entry) => entry.get_peers_for_client_matching(client, limit, predicate),
+            Torrent::MutexTokio(entry) => entry.clone().get_peers_for_client_matching(client, limit, predicate).await,
+            Torrent::MutexParkingLot(entry) => entry.get_peers_for_client_matching(client, limit, predicate),
+            Torrent::RwLockParkingLot(entry) => entry.get_peers_for_client_matching(client, limit, predicate),
+        }
+    }
+
+    pub(crate) async fn get_peer(&self, peer_id: &PeerId) -> Option<peer::Peer> {
+        match self {
+            Torrent::Single(entry) => entry.get_peer(peer_id),
+            Torrent::MutexStd(entry) => entry.get_peer(peer_id),
+            Torrent::MutexTokio(entry) => entry.clone().get_peer(peer_id).await,
+            Torrent::MutexParkingLot(entry) => entry.get_peer(peer_id),
+            Torrent::RwLockParkingLot(entry) => entry.get_peer(peer_id),
+        }
+    }
+
     pub(crate) async fn upsert_peer(&mut self, peer: &peer::Peer) -> bool {
         match self {
             Torrent::Single(entry) => entry.upsert_peer(peer),
@@ -89,7 +116,17 @@ impl Torrent {
         }
     }
 
-    pub(crate) async fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch) {
+    pub(crate) async fn upsert_peer_within_quota(&mut self, peer: &peer::Peer, quota: &PeerQuota) -> PeerChanges {
+        match self {
+            Torrent::Single(entry) => entry.upsert_peer_within_quota(peer, quota),
+            Torrent::MutexStd(entry) => entry.upsert_peer_within_quota(peer, quota),
+            Torrent::MutexTokio(entry) => entry.clone().upsert_peer_within_quota(peer, quota).await,
+            Torrent::MutexParkingLot(entry) => entry.upsert_peer_within_quota(peer, quota),
+            Torrent::RwLockParkingLot(entry) => entry.upsert_peer_within_quota(peer, quota),
+        }
+    }
+
+    pub(crate) async fn remove_inactive_peers(&mut self, current_cutoff: DurationSinceUnixEpoch) -> Vec<peer::Peer> {
         match self {
             Torrent::Single(entry) => entry.remove_inactive_peers(current_cutoff),
             Torrent::MutexStd(entry) => entry.remove_inactive_peers(current_cutoff),
@@ -98,4 +135,14 @@ impl Torrent {
             Torrent::RwLockParkingLot(entry) => entry.remove_inactive_peers(current_cutoff),
         }
     }
+
+    pub(crate) async fn remove_peer(&mut self, peer_id: &PeerId) -> Option<peer::Peer> {
+        match self {
+            Torrent::Single(entry) => entry.remove_peer(peer_id),
+            Torrent::MutexStd(entry) => entry.remove_peer(peer_id),
+            Torrent::MutexTokio(entry) => entry.clone().remove_peer(peer_id).await,
+            Torrent::MutexParkingLot(entry) => entry.remove_peer(peer_id),
+            Torrent::RwLockParkingLot(entry) => entry.remove_peer(peer_id),
+        }
+    }
 }
@@ -6,9 +6,10 @@ use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes};
 use rstest::{fixture, rstest};
 use torrust_tracker_clock::clock::stopped::Stopped as _;
 use torrust_tracker_clock::clock::{self, Time as _};
-use torrust_tracker_configuration::{TrackerPolicy, TORRENT_PEERS_LIMIT};
-use torrust_tracker_primitives::peer;
+use torrust_tracker_configuration::{PeerEvictionPolicy, TrackerPolicy, TORRENT_PEERS_LIMIT};
 use torrust_tracker_primitives::peer::Peer;
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+use torrust_tracker_torrent_repository::limits::{PeerChanges, PeerQuota};
 use torrust_tracker_torrent_repository::{
     EntryMutexParkingLot, EntryMutexStd, EntryMutexTokio, EntryRwLockParkingLot, EntrySingle,
 };
@@ -409,6 +410,63 @@ async fn it_should_limit_the_number_of_peers_returned(
     assert_eq!(peers.len(), 74);
 }
 
+#[rstest]
+#[case::empty(&Makes::Empty)]
+#[case::started(&Makes::Started)]
+#[case::completed(&Makes::Completed)]
+#[case::downloaded(&Makes::Downloaded)]
+#[case::three(&Makes::Three)]
+#[tokio::test]
+async fn it_should_limit_the_peers_returned_after_filtering_them_with_a_predicate(
+    #[values(single(), mutex_std(), mutex_tokio(), mutex_parking_lot(), rw_lock_parking_lot())] mut torrent: Torrent,
+    #[case] makes: &Makes,
+) {
+    make(&mut torrent, makes).await;
+
+    let client = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8081);
+    let matching_ip = IpAddr::V4(Ipv4Addr::new(126, 0, 0, 2));
+
+    // Only the peers with an even number match the predicate
+    for peer_number in 1..=20 {
+        let mut peer = a_started_peer(1);
+        peer.peer_id = *peer::Id::new(peer_number);
+        if peer_number % 2 == 0 {
+            peer.peer_addr = SocketAddr::new(matching_ip, 8080);
+        }
+        torrent.upsert_peer(&peer).await;
+    }
+
+    let peers = torrent
+        .get_peers_for_client_matching(&client, Some(5), &|peer| peer.peer_addr.ip() == matching_ip)
+        .await;
+
+    assert_eq!(peers.len(), 5);
+    assert!(peers.iter().all(|peer| peer.peer_addr.ip() == matching_ip));
+}
+
+#[rstest]
+#[case::empty(&Makes::Empty)]
+#[case::started(&Makes::Started)]
+#[case::completed(&Makes::Completed)]
+#[case::downloaded(&Makes::Downloaded)]
+#[case::three(&Makes::Three)]
+#[tokio::test]
+async fn it_should_get_a_peer_by_its_id(
+    #[values(single(), mutex_std(), mutex_tokio(), mutex_parking_lot(), rw_lock_parking_lot())] mut torrent: Torrent,
+    #[case] makes: &Makes,
+) {
+    make(&mut torrent, makes).await;
+
+    let mut peer = a_started_peer(1);
+    peer.peer_id = *peer::Id::new(100);
+
+    assert_eq!(torrent.get_peer(&peer.peer_id).await, None);
+
+    torrent.upsert_peer(&peer).await;
+
+    assert_eq!(torrent.get_peer(&peer.peer_id).await, Some(peer));
+}
+
 #[rstest]
 #[case::empty(&Makes::Empty)]
 #[case::started(&Makes::Started)]
@@ -441,3 +499,107 @@ async fn it_should_remove_inactive_peers_beyond_cutoff(
 
     assert_eq!(torrent.get_peers_len().await, peers.len());
 }
+
+#[rstest]
+#[case::started(&Makes::Started)]
+#[case::completed(&Makes::Completed)]
+#[case::downloaded(&Makes::Downloaded)]
+#[case::three(&Makes::Three)]
+#[tokio::test]
+async fn it_should_remove_a_single_peer_keeping_the_completed_counter(
+    #[values(single(), mutex_std(), mutex_tokio(), mutex_parking_lot(), rw_lock_parking_lot())] mut torrent: Torrent,
+    #[case] makes: &Makes,
+) {
+    let peers = make(&mut torrent, makes).await;
+
+    let downloaded = torrent.get_stats().await.downloaded;
+
+    let removed = torrent.remove_peer(&peers[0].peer_id).await;
+
+    assert_eq!(removed.map(|peer| peer.peer_id), Some(peers[0].peer_id));
+    assert_eq!(torrent.get_peers_len().await, peers.len() - 1);
+    assert_eq!(torrent.get_stats().await.downloaded, downloaded);
+
+    assert_eq!(torrent.remove_peer(&peers[0].peer_id).await, None);
+}
+
+#[rstest]
+#[case::empty(&Makes::Empty)]
+#[case::started(&Makes::Started)]
+#[case::completed(&Makes::Completed)]
+#[case::downloaded(&Makes::Downloaded)]
+#[case::three(&Makes::Three)]
+#[tokio::test]
+async fn it_should_refuse_a_new_peer_when_the_swarm_is_full(
+    #[values(single(), mutex_std(), mutex_tokio(), mutex_parking_lot(), rw_lock_parking_lot())] mut torrent: Torrent,
+    #[case] makes: &Makes,
+) {
+    let peers = make(&mut torrent, makes).await;
+
+    let quota = PeerQuota {
+        max_swarm_peers: Some(peers.len()),
+        ..PeerQuota::unlimited()
+    };
+
+    let changes = torrent.upsert_peer_within_quota(&a_started_peer(-1), &quota).await;
+
+    assert!(changes.refused);
+    assert_eq!(torrent.get_peers_len().await, peers.len());
+}
+
+#[rstest]
+#[case::started(&Makes::Started)]
+#[case::completed(&Makes::Completed)]
+#[case::downloaded(&Makes::Downloaded)]
+#[case::three(&Makes::Three)]
+#[tokio::test]
+async fn it_should_drop_the_oldest_peer_to_make_room_for_a_new_one_when_the_swarm_is_full(
+    #[values(single(), mutex_std(), mutex_tokio(), mutex_parking_lot(), rw_lock_parking_lot())] mut torrent: Torrent,
+    #[case] makes: &Makes,
+) {
+    let peers = make(&mut torrent, makes).await;
+
+    let mut oldest = a_started_peer(-1);
+    oldest.updated = DurationSinceUnixEpoch::ZERO;
+    torrent.upsert_peer(&oldest).await;
+
+    let quota = PeerQuota {
+        max_swarm_peers: Some(peers.len() + 1),
+        eviction_policy: PeerEvictionPolicy::DropOldest,
+        ..PeerQuota::unlimited()
+    };
+
+    let changes = torrent.upsert_peer_within_quota(&a_started_peer(-2), &quota).await;
+
+    assert_eq!(changes.evicted, Some(oldest));
+    assert_eq!(changes.added, 1);
+    assert_eq!(torrent.get_peers_len().await, peers.len() + 1);
+    assert!(!torrent.get_peers(None).await.contains(&oldest.into()));
+}
+
+#[rstest]
+#[case::started(&Makes::Started)]
+#[case::three(&Makes::Three)]
+#[tokio::test]
+async fn it_should_allow_updating_a_known_peer_when_the_swarm_is_full(
+    #[values(single(), mutex_std(), mutex_tokio(), mutex_parking_lot(), rw_lock_parking_lot())] mut torrent: Torrent,
+    #[case] makes: &Makes,
+) {
+    let peers = make(&mut torrent, makes).await;
+
+    let quota = PeerQuota {
+        max_swarm_peers: Some(peers.len()),
+        ..PeerQuota::unlimited()
+    };
+
+    let changes = torrent.upsert_peer_within_quota(&peers[0], &quota).await;
+
+    assert_eq!(
+        changes,
+        PeerChanges {
+            previous: Some(peers[0]),
+            ..PeerChanges::default()
+        }
+    );
+    assert_eq!(torrent.get_peers_len().await, peers.len());
+}
@@ -1,19 +1,22 @@
 use std::collections::{BTreeMap, HashSet};
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 use aquatic_udp_protocol::{AnnounceEvent, NumberOfBytes};
 use bittorrent_primitives::info_hash::InfoHash;
 use rstest::{fixture, rstest};
-use torrust_tracker_configuration::TrackerPolicy;
+use torrust_tracker_configuration::{MemoryLimits, PeerEvictionPolicy, PeersPerIpPolicy, TorrentEvictionPolicy, TrackerPolicy};
 use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
-use torrust_tracker_primitives::PersistentTorrents;
-use torrust_tracker_torrent_repository::entry::Entry as _;
+use torrust_tracker_primitives::{DurationSinceUnixEpoch, PersistentTorrents};
+use torrust_tracker_torrent_repository::entry::{Entry as _, EntrySync as _};
+use torrust_tracker_torrent_repository::expiry::EXPIRY_BATCH_SIZE;
 use torrust_tracker_torrent_repository::repository::dash_map_mutex_std::XacrimonDashMap;
 use torrust_tracker_torrent_repository::repository::rw_lock_std::RwLockStd;
 use torrust_tracker_torrent_repository::repository::rw_lock_tokio::RwLockTokio;
 use torrust_tracker_torrent_repository::repository::skip_map_mutex_std::CrossbeamSkipList;
-use torrust_tracker_torrent_repository::EntrySingle;
+use torrust_tracker_torrent_repository::repository::Repository as _;
+use torrust_tracker_torrent_repository::{EntrySingle, TorrentsSkipMapMutexStd};
 
 use crate::common::repo::Repo;
 use crate::common::torrent_peer_builder::{a_completed_peer, a_started_peer};
@@ -376,6 +379,108 @@ async fn it_should_get_paginated(
     }
 }
 
+#[rstest]
+#[case::empty(empty())]
+#[case::default(default())]
+#[case::started(started())]
+#[case::completed(completed())]
+#[case::downloaded(downloaded())]
+#[case::three(three())]
+#[case::out_of_order(many_out_of_order())]
+#[case::in_order(many_hashed_in_order())]
+#[tokio::test]
+async fn it_should_walk_through_all_the_entries_in_batches(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+    #[case] entries: Entries,
+) {
+    make(&repo, &entries).await;
+
+    let mut expected = repo.get_paginated(None).await;
+
+    expected.sort_by_key(|(info_hash, _)| *info_hash);
+
+    let mut walked = vec![];
+    let mut cursor = None;
+
+    loop {
+        let batch = repo.get_after(cursor.as_ref(), 2).await;
+
+        let Some((last, _)) = batch.last() else {
+            break;
+        };
+
+        cursor = Some(*last);
+        walked.extend(batch);
+    }
+
+    assert_eq!(walked, expected);
+}
+
+#[rstest]
+#[case::empty(empty())]
+#[case::default(default())]
+#[case::started(started())]
+#[case::completed(completed())]
+#[case::downloaded(downloaded())]
+#[case::three(three())]
+#[case::out_of_order(many_out_of_order())]
+#[case::in_order(many_hashed_in_order())]
+#[tokio::test]
+async fn it_should_walk_through_the_swarm_metadata_of_all_the_torrents_in_batches(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+    #[case] entries: Entries,
+) {
+    make(&repo, &entries).await;
+
+    let mut expected = vec![];
+
+    for (info_hash, _) in repo.get_paginated(None).await {
+        expected.push((info_hash, repo.get_swarm_metadata(&info_hash).await.unwrap()));
+    }
+
+    expected.sort_by_key(|(info_hash, _)| *info_hash);
+
+    let mut walked = vec![];
+    let mut cursor = None;
+
+    loop {
+        let batch = repo.get_swarm_metadata_after(cursor.as_ref(), 2).await;
+
+        let Some((last, _)) = batch.last() else {
+            break;
+        };
+
+        cursor = Some(*last);
+        walked.extend(batch);
+    }
+
+    assert_eq!(walked, expected);
+}
+
 #[rstest]
 #[case::empty(empty())]
 #[case::default(default())]
@@ -637,3 +742,311 @@ async fn it_should_remove_peerless_torrents(
         assert!(entry.meets_retaining_policy(&policy));
     }
 }
+
+fn an_info_hash(n: u8) -> InfoHash {
+    InfoHash::from([n; 20])
+}
+
+#[rstest]
+#[tokio::test]
+async fn it_should_refuse_new_torrents_when_the_repository_is_full(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+) {
+    let repo = repo.with_limits(MemoryLimits {
+        max_torrents: Some(1),
+        ..Default::default()
+    });
+
+    repo.upsert_peer(&an_info_hash(1), &a_started_peer(1)).await;
+    repo.upsert_peer(&an_info_hash(2), &a_started_peer(2)).await;
+
+    assert!(repo.get(&an_info_hash(1)).await.is_some());
+    assert!(repo.get(&an_info_hash(2)).await.is_none());
+    assert_eq!(repo.get_metrics().await.torrents, 1);
+    assert_eq!(repo.get_eviction_metrics().torrents_refused, 1);
+}
+
+#[rstest]
+#[tokio::test]
+async fn it_should_evict_the_least_recently_active_torrent_when_the_repository_is_full(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+) {
+    let repo = repo.with_limits(MemoryLimits {
+        max_torrents: Some(2),
+        torrent_eviction_policy: TorrentEvictionPolicy::EvictLeastRecentlyActive,
+        ..Default::default()
+    });
+
+    let mut recent = a_started_peer(1);
+    recent.updated = DurationSinceUnixEpoch::from_secs(200);
+    let mut old = a_started_peer(2);
+    old.updated = DurationSinceUnixEpoch::from_secs(100);
+
+    repo.upsert_peer(&an_info_hash(1), &recent).await;
+    repo.upsert_peer(&an_info_hash(2), &old).await;
+    repo.upsert_peer(&an_info_hash(3), &a_started_peer(3)).await;
+
+    assert!(repo.get(&an_info_hash(1)).await.is_some());
+    assert!(repo.get(&an_info_hash(2)).await.is_none());
+    assert!(repo.get(&an_info_hash(3)).await.is_some());
+    assert_eq!(repo.get_eviction_metrics().torrents_evicted, 1);
+}
+
+#[rstest]
+#[tokio::test]
+async fn it_should_free_the_peers_of_an_evicted_torrent(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+) {
+    let repo = repo.with_limits(MemoryLimits {
+        max_torrents: Some(1),
+        max_peers: Some(2),
+        torrent_eviction_policy: TorrentEvictionPolicy::EvictLeastRecentlyActive,
+        ..Default::default()
+    });
+
+    repo.upsert_peer(&an_info_hash(1), &a_started_peer(1)).await;
+    repo.upsert_peer(&an_info_hash(2), &a_started_peer(2)).await;
+
+    // The peer of the evicted torrent no longer counts for the `max_peers` limit.
+    repo.upsert_peer(&an_info_hash(2), &a_started_peer(3)).await;
+
+    assert!(repo.get(&an_info_hash(1)).await.is_none());
+    assert_eq!(repo.get_metrics().await.incomplete, 2);
+    assert_eq!(repo.get_eviction_metrics().torrents_evicted, 1);
+    assert_eq!(repo.get_eviction_metrics().peers_refused, 0);
+}
+
+#[rstest]
+#[case::refuse_new(PeerEvictionPolicy::RefuseNew)]
+#[case::drop_oldest(PeerEvictionPolicy::DropOldest)]
+#[tokio::test]
+async fn it_should_limit_the_number_of_peers_per_torrent(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+    #[case] policy: PeerEvictionPolicy,
+) {
+    let repo = repo.with_limits(MemoryLimits {
+        max_peers_per_torrent: Some(1),
+        peer_eviction_policy: policy,
+        ..Default::default()
+    });
+
+    repo.upsert_peer(&an_info_hash(1), &a_started_peer(1)).await;
+    repo.upsert_peer(&an_info_hash(1), &a_started_peer(2)).await;
+
+    let torrent = repo.get(&an_info_hash(1)).await.expect("it should keep the torrent");
+    let metrics = repo.get_eviction_metrics();
+
+    assert_eq!(torrent.get_peers_len(), 1);
+
+    match policy {
+        PeerEvictionPolicy::RefuseNew => {
+            assert!(torrent.get_peers(None).contains(&a_started_peer(1).into()));
+            assert_eq!(metrics.peers_refused, 1);
+        }
+        PeerEvictionPolicy::DropOldest => {
+            assert!(torrent.get_peers(None).contains(&a_started_peer(2).into()));
+            assert_eq!(metrics.peers_evicted, 1);
+        }
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn it_should_refuse_new_peers_when_the_repository_holds_the_maximum_number_of_peers(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+) {
+    let repo = repo.with_limits(MemoryLimits {
+        max_peers: Some(2),
+        ..Default::default()
+    });
+
+    repo.upsert_peer(&an_info_hash(1), &a_started_peer(1)).await;
+    repo.upsert_peer(&an_info_hash(1), &a_started_peer(2)).await;
+    repo.upsert_peer(&an_info_hash(1), &a_started_peer(3)).await;
+    repo.upsert_peer(&an_info_hash(2), &a_started_peer(4)).await;
+
+    assert_eq!(
+        repo.get(&an_info_hash(1)).await.map(|torrent| torrent.get_peers_len()),
+        Some(2)
+    );
+    assert!(repo.get(&an_info_hash(2)).await.is_none());
+
+    let metrics = repo.get_eviction_metrics();
+
+    assert_eq!(metrics.peers_refused, 1);
+    assert_eq!(metrics.torrents_refused, 1);
+}
+
+#[rstest]
+#[tokio::test]
+async fn it_should_remove_expired_peers_incrementally(
+    #[values(policy_none(), policy_persist(), policy_remove(), policy_remove_persist())] policy: TrackerPolicy,
+) {
+    let repo = TorrentsSkipMapMutexStd::default();
+
+    let mut expired = a_started_peer(1);
+    expired.updated = DurationSinceUnixEpoch::from_secs(100);
+    let mut active = a_started_peer(2);
+    active.updated = DurationSinceUnixEpoch::from_secs(300);
+
+    repo.upsert_peer(&an_info_hash(1), &expired);
+    repo.upsert_peer(&an_info_hash(2), &active);
+
+    let result = repo.remove_expired_peers(DurationSinceUnixEpoch::from_secs(200), &policy, EXPIRY_BATCH_SIZE);
+
+    assert!(result.finished);
+    assert_eq!(result.peers, 1);
+    assert_eq!(repo.get(&an_info_hash(2)).map(|torrent| torrent.get_peers_len()), Some(1));
+
+    match repo.get(&an_info_hash(1)) {
+        Some(torrent) => {
+            assert!(torrent.peers_is_empty());
+            assert!(torrent.meets_retaining_policy(&policy));
+        }
+        None => assert_eq!(result.torrents, vec![an_info_hash(1)]),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn it_should_not_remove_a_peer_that_has_announced_again_before_expiring() {
+    let repo = TorrentsSkipMapMutexStd::default();
+
+    let mut peer = a_started_peer(1);
+    peer.updated = DurationSinceUnixEpoch::from_secs(100);
+    repo.upsert_peer(&an_info_hash(1), &peer);
+
+    peer.updated = DurationSinceUnixEpoch::from_secs(300);
+    repo.upsert_peer(&an_info_hash(1), &peer);
+
+    let result = repo.remove_expired_peers(DurationSinceUnixEpoch::from_secs(200), &policy_remove(), EXPIRY_BATCH_SIZE);
+
+    assert_eq!(result.peers, 0);
+    assert_eq!(repo.get(&an_info_hash(1)).map(|torrent| torrent.get_peers_len()), Some(1));
+}
+
+#[rstest]
+#[case::reject(PeersPerIpPolicy::Reject)]
+#[case::drop_oldest(PeersPerIpPolicy::DropOldest)]
+#[tokio::test]
+async fn it_should_limit_the_number_of_peers_per_ip_address_in_a_torrent(
+    #[values(
+        standard(),
+        standard_mutex(),
+        standard_tokio(),
+        tokio_std(),
+        tokio_mutex(),
+        tokio_tokio(),
+        skip_list_mutex_std(),
+        skip_list_mutex_parking_lot(),
+        skip_list_rw_lock_parking_lot(),
+        dash_map_std()
+    )]
+    repo: Repo,
+    #[case] policy: PeersPerIpPolicy,
+) {
+    let repo = repo.with_limits(MemoryLimits {
+        max_peers_per_ip: Some(2),
+        peers_per_ip_policy: policy,
+        ..Default::default()
+    });
+
+    // The first three peers share the same IP address
+    let peers: Vec<_> = (1..=3)
+        .map(|n| {
+            let mut peer = a_started_peer(n);
+            peer.updated = DurationSinceUnixEpoch::from_secs(100 * u64::try_from(n).unwrap());
+            peer
+        })
+        .collect();
+    let mut other_host = a_started_peer(4);
+    other_host.peer_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 6881);
+
+    repo.upsert_peer(&an_info_hash(1), &peers[0]).await;
+    repo.upsert_peer(&an_info_hash(1), &peers[1]).await;
+    let changes = repo.upsert_peer(&an_info_hash(1), &peers[2]).await;
+    repo.upsert_peer(&an_info_hash(1), &other_host).await;
+
+    let torrent = repo.get(&an_info_hash(1)).await.expect("it should keep the torrent");
+    let metrics = repo.get_eviction_metrics();
+
+    assert_eq!(torrent.get_peers_len(), 3);
+    assert!(torrent.get_peers(None).contains(&other_host.into()));
+    assert!(changes.peers_per_ip_exceeded);
+    assert_eq!(metrics.peers_per_ip_exceeded, 1);
+
+    match policy {
+        PeersPerIpPolicy::Reject => {
+            assert!(changes.refused);
+            assert!(!torrent.get_peers(None).contains(&peers[2].into()));
+        }
+        PeersPerIpPolicy::DropOldest => {
+            assert!(!changes.refused);
+            assert!(!torrent.get_peers(None).contains(&peers[0].into()));
+        }
+    }
+
+    // The peers are only counted as exceeding the limit per IP address.
+    assert_eq!(metrics.peers_refused, 0);
+    assert_eq!(metrics.peers_evicted, 0);
+}
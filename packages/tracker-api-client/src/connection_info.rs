@@ -7,20 +7,65 @@ use url::Url;
 pub struct ConnectionInfo {
     pub origin: Origin,
     pub api_token: Option<String>,
+    pub token_location: TokenLocation,
+}
+
+/// Where the API token is sent in the requests.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TokenLocation {
+    /// In the `token` query param.
+    #[default]
+    QueryParam,
+    /// In the `Authorization: Bearer <token>` header.
+    BearerHeader,
 }
 
 impl ConnectionInfo {
+    /// The token is sent in the `token` query param.
     #[must_use]
     pub fn authenticated(origin: Origin, api_token: &str) -> Self {
         Self {
             origin,
             api_token: Some(api_token.to_string()),
+            token_location: TokenLocation::QueryParam,
+        }
+    }
+
+    /// The token is sent in the `Authorization: Bearer <token>` header.
+    #[must_use]
+    pub fn bearer(origin: Origin, api_token: &str) -> Self {
+        Self {
+            origin,
+            api_token: Some(api_token.to_string()),
+            token_location: TokenLocation::BearerHeader,
         }
     }
 
     #[must_use]
     pub fn anonymous(origin: Origin) -> Self {
-        Self { origin, api_token: None }
+        Self {
+            origin,
+            api_token: None,
+            token_location: TokenLocation::QueryParam,
+        }
+    }
+
+    /// The token to send in the `token` query param, if any.
+    #[must_use]
+    pub fn query_token(&self) -> Option<&str> {
+        match self.token_location {
+            TokenLocation::QueryParam => self.api_token.as_deref(),
+            TokenLocation::BearerHeader => None,
+        }
+    }
+
+    /// The token to send in the `Authorization` header, if any.
+    #[must_use]
+    pub fn bearer_token(&self) -> Option<&str> {
+        match self.token_location {
+            TokenLocation::QueryParam => None,
+            TokenLocation::BearerHeader => self.api_token.as_deref(),
+        }
     }
 }
 
@@ -1,5 +1,5 @@
 use hyper::HeaderMap;
-use reqwest::Response;
+use reqwest::{RequestBuilder, Response};
 use serde::Serialize;
 use url::Url;
 use uuid::Uuid;
@@ -31,7 +31,7 @@ impl Client {
     }
 
     pub async fn delete_auth_key(&self, key: &str, headers: Option<HeaderMap>) -> Response {
-        self.delete(&format!("key/{}", &key), headers).await
+        self.delete(&format!("key/{}", &key), Query::default(), headers).await
     }
 
     pub async fn reload_keys(&self, headers: Option<HeaderMap>) -> Response {
@@ -43,82 +43,218 @@ impl Client {
     }
 
     pub async fn remove_torrent_from_whitelist(&self, info_hash: &str, headers: Option<HeaderMap>) -> Response {
-        self.delete(&format!("whitelist/{}", &info_hash), headers).await
+        self.delete(&format!("whitelist/{}", &info_hash), Query::default(), headers)
+            .await
     }
 
     pub async fn reload_whitelist(&self, headers: Option<HeaderMap>) -> Response {
         self.get("whitelist/reload", Query::default(), headers).await
     }
 
+    pub async fn reload_config(&self, headers: Option<HeaderMap>) -> Response {
+        self.get("config/reload", Query::default(), headers).await
+    }
+
+    pub async fn get_listeners(&self, headers: Option<HeaderMap>) -> Response {
+        self.get("listeners", Query::default(), headers).await
+    }
+
+    pub async fn add_listener(&self, add_listener_form: AddListenerForm, headers: Option<HeaderMap>) -> Response {
+        self.post_form("listeners", &add_listener_form, headers).await
+    }
+
+    pub async fn stop_listener(&self, id: u64, headers: Option<HeaderMap>) -> Response {
+        self.post_empty(&format!("listener/{id}/stop"), headers).await
+    }
+
+    pub async fn restart_listener(
+        &self,
+        id: u64,
+        restart_listener_form: RestartListenerForm,
+        headers: Option<HeaderMap>,
+    ) -> Response {
+        self.post_form(&format!("listener/{id}/restart"), &restart_listener_form, headers)
+            .await
+    }
+
+    pub async fn get_client_rules(&self, headers: Option<HeaderMap>) -> Response {
+        self.get("client-policy/rules", Query::default(), headers).await
+    }
+
+    pub async fn add_client_rule(&self, add_client_rule_form: AddClientRuleForm, headers: Option<HeaderMap>) -> Response {
+        self.post_form("client-policy/rules", &add_client_rule_form, headers).await
+    }
+
+    pub async fn remove_client_rule(&self, id: u64, headers: Option<HeaderMap>) -> Response {
+        self.delete(&format!("client-policy/rules/{id}"), Query::default(), headers)
+            .await
+    }
+
     pub async fn get_torrent(&self, info_hash: &str, headers: Option<HeaderMap>) -> Response {
         self.get(&format!("torrent/{}", &info_hash), Query::default(), headers).await
     }
 
+    pub async fn delete_torrent(&self, info_hash: &str, delete_completed: bool, headers: Option<HeaderMap>) -> Response {
+        let params = if delete_completed {
+            Query::params([QueryParam::new("delete_completed", "true")].to_vec())
+        } else {
+            Query::default()
+        };
+
+        self.delete(&format!("torrent/{}", &info_hash), params, headers).await
+    }
+
+    pub async fn evict_peer(&self, info_hash: &str, peer_id: &str, headers: Option<HeaderMap>) -> Response {
+        self.delete(
+            &format!("torrent/{}/peers/{}", &info_hash, &peer_id),
+            Query::default(),
+            headers,
+        )
+        .await
+    }
+
     pub async fn get_torrents(&self, params: Query, headers: Option<HeaderMap>) -> Response {
         self.get("torrents", params, headers).await
     }
 
+    pub async fn search_torrents(&self, query: TorrentListQuery, headers: Option<HeaderMap>) -> Response {
+        self.get_torrents(query.into(), headers).await
+    }
+
+    pub async fn export_torrents(&self, params: Query, headers: Option<HeaderMap>) -> Response {
+        self.get("torrents/export", params, headers).await
+    }
+
     pub async fn get_tracker_statistics(&self, headers: Option<HeaderMap>) -> Response {
         self.get("stats", Query::default(), headers).await
     }
 
-    pub async fn get(&self, path: &str, params: Query, headers: Option<HeaderMap>) -> Response {
-        let mut query: Query = params;
+    pub async fn get_stats_history(&self, params: Query, headers: Option<HeaderMap>) -> Response {
+        self.get("stats/history", params, headers).await
+    }
 
-        if let Some(token) = &self.connection_info.api_token {
-            query.add_param(QueryParam::new("token", token));
-        }
+    pub async fn get_clients_stats(&self, params: Query, headers: Option<HeaderMap>) -> Response {
+        self.get("stats/clients", params, headers).await
+    }
+
+    pub async fn get_locations_stats(&self, params: Query, headers: Option<HeaderMap>) -> Response {
+        self.get("stats/locations", params, headers).await
+    }
+
+    pub async fn get_audit_records(&self, params: Query, headers: Option<HeaderMap>) -> Response {
+        self.get("audit", params, headers).await
+    }
+
+    /// It returns the response as soon as the event stream starts. The events
+    /// are read from the response body.
+    pub async fn get_events(&self, params: Query, headers: Option<HeaderMap>) -> Response {
+        self.get("events", params, headers).await
+    }
 
-        self.get_request_with_query(path, query, headers).await
+    pub async fn get_openapi_spec(&self, headers: Option<HeaderMap>) -> Response {
+        self.get_request_with_query("openapi.json", Query::default(), headers).await
     }
 
     /// # Panics
     ///
     /// Will panic if the request can't be sent
-    pub async fn post_empty(&self, path: &str, headers: Option<HeaderMap>) -> Response {
-        let builder = reqwest::Client::new()
-            .post(self.base_url(path).clone())
-            .query(&ReqwestQuery::from(self.query_with_token()));
-
-        let builder = match headers {
-            Some(headers) => builder.headers(headers),
-            None => builder,
-        };
+    pub async fn get(&self, path: &str, params: Query, headers: Option<HeaderMap>) -> Response {
+        self.try_get(path, params, headers).await.unwrap()
+    }
 
-        builder.send().await.unwrap()
+    /// # Panics
+    ///
+    /// Will panic if the request can't be sent
+    pub async fn post_empty(&self, path: &str, headers: Option<HeaderMap>) -> Response {
+        self.try_post_empty(path, headers).await.unwrap()
     }
 
     /// # Panics
     ///
     /// Will panic if the request can't be sent
     pub async fn post_form<T: Serialize + ?Sized>(&self, path: &str, form: &T, headers: Option<HeaderMap>) -> Response {
-        let builder = reqwest::Client::new()
-            .post(self.base_url(path).clone())
-            .query(&ReqwestQuery::from(self.query_with_token()))
-            .json(&form);
-
-        let builder = match headers {
-            Some(headers) => builder.headers(headers),
-            None => builder,
-        };
-
-        builder.send().await.unwrap()
+        self.try_post_form(path, form, headers).await.unwrap()
     }
 
     /// # Panics
     ///
     /// Will panic if the request can't be sent
-    async fn delete(&self, path: &str, headers: Option<HeaderMap>) -> Response {
-        let builder = reqwest::Client::new()
-            .delete(self.base_url(path).clone())
-            .query(&ReqwestQuery::from(self.query_with_token()));
+    async fn delete(&self, path: &str, params: Query, headers: Option<HeaderMap>) -> Response {
+        self.try_delete(path, params, headers).await.unwrap()
+    }
+
+    /// It sends an authenticated `GET` request.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request can't be sent.
+    pub async fn try_get(&self, path: &str, params: Query, headers: Option<HeaderMap>) -> Result<Response, reqwest::Error> {
+        self.send(reqwest::Client::new().get(self.base_url(path)), params, headers)
+            .await
+    }
+
+    /// It sends an authenticated `POST` request without body.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request can't be sent.
+    pub async fn try_post_empty(&self, path: &str, headers: Option<HeaderMap>) -> Result<Response, reqwest::Error> {
+        self.send(reqwest::Client::new().post(self.base_url(path)), Query::default(), headers)
+            .await
+    }
+
+    /// It sends an authenticated `POST` request with a json body.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request can't be sent.
+    pub async fn try_post_form<T: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        form: &T,
+        headers: Option<HeaderMap>,
+    ) -> Result<Response, reqwest::Error> {
+        self.send(
+            reqwest::Client::new().post(self.base_url(path)).json(&form),
+            Query::default(),
+            headers,
+        )
+        .await
+    }
+
+    /// It sends an authenticated `DELETE` request.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request can't be sent.
+    pub async fn try_delete(&self, path: &str, params: Query, headers: Option<HeaderMap>) -> Result<Response, reqwest::Error> {
+        self.send(reqwest::Client::new().delete(self.base_url(path)), params, headers)
+            .await
+    }
+
+    /// It adds the token, in the query or in the `Authorization` header, and
+    /// sends the request. The given headers take precedence over the bearer
+    /// token header.
+    async fn send(&self, builder: RequestBuilder, params: Query, headers: Option<HeaderMap>) -> Result<Response, reqwest::Error> {
+        let mut query: Query = params;
+
+        if let Some(token) = self.connection_info.query_token() {
+            query.add_param(QueryParam::new("token", token));
+        }
+
+        let builder = builder.query(&ReqwestQuery::from(query));
+
+        let builder = match self.connection_info.bearer_token() {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        };
 
         let builder = match headers {
             Some(headers) => builder.headers(headers),
             None => builder,
         };
 
-        builder.send().await.unwrap()
+        builder.send().await
     }
 
     pub async fn get_request_with_query(&self, path: &str, params: Query, headers: Option<HeaderMap>) -> Response {
@@ -129,13 +265,6 @@ impl Client {
         get(self.base_url(path), None, None).await
     }
 
-    fn query_with_token(&self) -> Query {
-        match &self.connection_info.api_token {
-            Some(token) => Query::params([QueryParam::new("token", token)].to_vec()),
-            None => Query::default(),
-        }
-    }
-
     fn base_url(&self, path: &str) -> Url {
         Url::parse(&format!("{}{}{path}", &self.connection_info.origin, &self.base_path)).unwrap()
     }
@@ -178,3 +307,71 @@ pub struct AddKeyForm {
     pub opt_key: Option<String>,
     pub seconds_valid: Option<u64>,
 }
+
+#[derive(Serialize, Debug)]
+pub struct AddListenerForm {
+    pub protocol: String,
+    pub bind_address: String,
+    pub ssl_cert_path: Option<String>,
+    pub ssl_key_path: Option<String>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct RestartListenerForm {
+    pub ssl_cert_path: Option<String>,
+    pub ssl_key_path: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AddClientRuleForm {
+    /// `allow` or `deny`.
+    pub action: String,
+    /// `prefix` or `regex`.
+    pub kind: String,
+    pub pattern: String,
+}
+
+/// Pagination, sorting and filters to list torrents.
+#[derive(Debug, Default)]
+pub struct TorrentListQuery {
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+    /// `seeders`, `leechers`, `completed` or `peers`.
+    pub sort: Option<String>,
+    /// `asc` or `desc`.
+    pub order: Option<String>,
+    pub min_seeders: Option<u64>,
+    pub max_seeders: Option<u64>,
+    pub min_leechers: Option<u64>,
+    pub max_leechers: Option<u64>,
+    pub min_completed: Option<u64>,
+    pub max_completed: Option<u64>,
+    pub min_peers: Option<u64>,
+    pub max_peers: Option<u64>,
+}
+
+impl From<TorrentListQuery> for Query {
+    fn from(query: TorrentListQuery) -> Self {
+        let params = [
+            ("offset", query.offset.map(|value| value.to_string())),
+            ("limit", query.limit.map(|value| value.to_string())),
+            ("sort", query.sort),
+            ("order", query.order),
+            ("min_seeders", query.min_seeders.map(|value| value.to_string())),
+            ("max_seeders", query.max_seeders.map(|value| value.to_string())),
+            ("min_leechers", query.min_leechers.map(|value| value.to_string())),
+            ("max_leechers", query.max_leechers.map(|value| value.to_string())),
+            ("min_completed", query.min_completed.map(|value| value.to_string())),
+            ("max_completed", query.max_completed.map(|value| value.to_string())),
+            ("min_peers", query.min_peers.map(|value| value.to_string())),
+            ("max_peers", query.max_peers.map(|value| value.to_string())),
+        ];
+
+        Query::params(
+            params
+                .into_iter()
+                .filter_map(|(name, value)| value.map(|value| QueryParam::new(name, &value)))
+                .collect(),
+        )
+    }
+}
@@ -1 +1,5 @@
 pub mod client;
+pub mod typed_client;
+
+/// The API resources, shared with the tracker API server.
+pub use torrust_tracker_api_resources::v1 as resources;
@@ -0,0 +1,395 @@
+//! API client that decodes the responses into the API
+//! [`resources`](crate::v1::resources).
+//!
+//! The [`Client`] returns the raw HTTP responses, which is what the contract
+//! tests need. The [`TypedClient`] wraps it and returns the resources, or an
+//! [`ApiError`] when the request can't be sent or the API rejects it:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), torrust_tracker_api_client::v1::typed_client::ApiError> {
+//! use torrust_tracker_api_client::connection_info::{ConnectionInfo, Origin};
+//! use torrust_tracker_api_client::v1::typed_client::TypedClient;
+//!
+//! let origin = Origin::new("http://127.0.0.1:1212").unwrap(); // DevSkim: ignore DS137138
+//! let client = TypedClient::new(ConnectionInfo::bearer(origin, "MyAccessToken"));
+//!
+//! let stats = client.get_stats().await?;
+//!
+//! println!("{} torrents", stats.torrents);
+//! # Ok(())
+//! # }
+//! ```
+use reqwest::{Response, StatusCode};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use super::client::{AddClientRuleForm, AddKeyForm, AddListenerForm, Client, RestartListenerForm, TorrentListQuery};
+use super::resources::action_status::ActionStatus;
+use super::resources::audit::AuditRecord;
+use super::resources::auth_key::AuthKey;
+use super::resources::client_policy::ClientRule;
+use super::resources::config::ReloadReport;
+use super::resources::listener::Listener;
+use super::resources::stats::{ClientStats, LocationStats, Stats, StatsHistory};
+use super::resources::torrent::{ListItem, Torrent};
+use crate::common::http::{Query, QueryParam};
+use crate::connection_info::ConnectionInfo;
+
+/// The body of the `200` response when the requested torrent is not tracked.
+const TORRENT_NOT_KNOWN: &str = "torrent not known";
+
+/// Errors returned by the [`TypedClient`].
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("the request could not be sent: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("unexpected response body: {source}. Body: {body}")]
+    Decode { source: serde_json::Error, body: String },
+
+    #[error("the request is not authenticated, the API token is missing")]
+    Unauthorized,
+
+    #[error("the API token is not valid")]
+    TokenNotValid,
+
+    #[error("the API token is not allowed to perform the action: {0}")]
+    Forbidden(String),
+
+    #[error("bad request: {0}")]
+    BadRequest(String),
+
+    #[error("not found: {0}")]
+    NotFound(String),
+
+    #[error("the tracker failed to perform the action: {0}")]
+    Server(String),
+
+    #[error("unexpected response status {status}: {body}")]
+    UnexpectedStatus { status: StatusCode, body: String },
+}
+
+/// API client that returns the API resources.
+pub struct TypedClient {
+    client: Client,
+}
+
+impl TypedClient {
+    #[must_use]
+    pub fn new(connection_info: ConnectionInfo) -> Self {
+        Self {
+            client: Client::new(connection_info),
+        }
+    }
+
+    /// The raw client used to send the requests.
+    #[must_use]
+    pub fn raw(&self) -> &Client {
+        &self.client
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_stats(&self) -> Result<Stats, ApiError> {
+        decode(&body(self.client.try_get("stats", Query::default(), None).await?).await?)
+    }
+
+    /// It returns the recorded statistics in the `[from, to)` range, in
+    /// seconds since the Unix epoch.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_stats_history(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+        step: Option<u64>,
+    ) -> Result<StatsHistory, ApiError> {
+        let params = [("from", from), ("to", to), ("step", step)]
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| QueryParam::new(name, &value.to_string())))
+            .collect();
+
+        decode(&body(self.client.try_get("stats/history", Query::params(params), None).await?).await?)
+    }
+
+    /// It returns the number of active peers by client software, for all the
+    /// torrents or only for the given one.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_clients_stats(&self, info_hash: Option<&str>) -> Result<Vec<ClientStats>, ApiError> {
+        let params = info_hash
+            .map(|info_hash| [QueryParam::new("info_hash", info_hash)].to_vec())
+            .unwrap_or_default();
+
+        decode(&body(self.client.try_get("stats/clients", Query::params(params), None).await?).await?)
+    }
+
+    /// It returns the number of active peers by country and ASN, for all the
+    /// torrents or only for the given one.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_locations_stats(&self, info_hash: Option<&str>) -> Result<LocationStats, ApiError> {
+        let params = info_hash
+            .map(|info_hash| [QueryParam::new("info_hash", info_hash)].to_vec())
+            .unwrap_or_default();
+
+        decode(&body(self.client.try_get("stats/locations", Query::params(params), None).await?).await?)
+    }
+
+    /// It returns `None` if the torrent is not tracked.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_torrent(&self, info_hash: &str) -> Result<Option<Torrent>, ApiError> {
+        let body = body(
+            self.client
+                .try_get(&format!("torrent/{info_hash}"), Query::default(), None)
+                .await?,
+        )
+        .await?;
+
+        match not_known(&body) {
+            Some(message) if message == TORRENT_NOT_KNOWN => Ok(None),
+            _ => decode(&body).map(Some),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_torrents(&self, query: TorrentListQuery) -> Result<Vec<ListItem>, ApiError> {
+        decode(&body(self.client.try_get("torrents", query.into(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return [`ApiError::NotFound`] if the torrent is not tracked, or
+    /// another error if the request fails or the API rejects it.
+    pub async fn delete_torrent(&self, info_hash: &str, delete_completed: bool) -> Result<(), ApiError> {
+        let params = if delete_completed {
+            Query::params([QueryParam::new("delete_completed", "true")].to_vec())
+        } else {
+            Query::default()
+        };
+
+        action(&body(self.client.try_delete(&format!("torrent/{info_hash}"), params, None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return [`ApiError::NotFound`] if the torrent or the peer are not
+    /// tracked, or another error if the request fails or the API rejects it.
+    pub async fn evict_peer(&self, info_hash: &str, peer_id: &str) -> Result<(), ApiError> {
+        let path = format!("torrent/{info_hash}/peers/{peer_id}");
+
+        action(&body(self.client.try_delete(&path, Query::default(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn generate_auth_key(&self, seconds_valid: u64) -> Result<AuthKey, ApiError> {
+        decode(&body(self.client.try_post_empty(&format!("key/{seconds_valid}"), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn add_auth_key(&self, add_key_form: AddKeyForm) -> Result<AuthKey, ApiError> {
+        decode(&body(self.client.try_post_form("keys", &add_key_form, None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn delete_auth_key(&self, key: &str) -> Result<(), ApiError> {
+        action(&body(self.client.try_delete(&format!("key/{key}"), Query::default(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn reload_keys(&self) -> Result<(), ApiError> {
+        action(&body(self.client.try_get("keys/reload", Query::default(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn whitelist_a_torrent(&self, info_hash: &str) -> Result<(), ApiError> {
+        action(&body(self.client.try_post_empty(&format!("whitelist/{info_hash}"), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn remove_torrent_from_whitelist(&self, info_hash: &str) -> Result<(), ApiError> {
+        let path = format!("whitelist/{info_hash}");
+
+        action(&body(self.client.try_delete(&path, Query::default(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn reload_whitelist(&self) -> Result<(), ApiError> {
+        action(&body(self.client.try_get("whitelist/reload", Query::default(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn reload_config(&self) -> Result<ReloadReport, ApiError> {
+        decode(&body(self.client.try_get("config/reload", Query::default(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_listeners(&self) -> Result<Vec<Listener>, ApiError> {
+        decode(&body(self.client.try_get("listeners", Query::default(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn add_listener(&self, add_listener_form: AddListenerForm) -> Result<Listener, ApiError> {
+        decode(&body(self.client.try_post_form("listeners", &add_listener_form, None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn stop_listener(&self, id: u64) -> Result<Listener, ApiError> {
+        decode(&body(self.client.try_post_empty(&format!("listener/{id}/stop"), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn restart_listener(&self, id: u64, restart_listener_form: RestartListenerForm) -> Result<Listener, ApiError> {
+        let path = format!("listener/{id}/restart");
+
+        decode(&body(self.client.try_post_form(&path, &restart_listener_form, None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_client_rules(&self) -> Result<Vec<ClientRule>, ApiError> {
+        decode(&body(self.client.try_get("client-policy/rules", Query::default(), None).await?).await?)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn add_client_rule(&self, add_client_rule_form: AddClientRuleForm) -> Result<ClientRule, ApiError> {
+        decode(
+            &body(
+                self.client
+                    .try_post_form("client-policy/rules", &add_client_rule_form, None)
+                    .await?,
+            )
+            .await?,
+        )
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn remove_client_rule(&self, id: u64) -> Result<(), ApiError> {
+        let path = format!("client-policy/rules/{id}");
+
+        action(&body(self.client.try_delete(&path, Query::default(), None).await?).await?)
+    }
+
+    /// It returns a page of the audit records, the newest first.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the request fails or the API rejects it.
+    pub async fn get_audit_records(&self, offset: Option<u32>, limit: Option<u32>) -> Result<Vec<AuditRecord>, ApiError> {
+        let params = [("offset", offset), ("limit", limit)]
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| QueryParam::new(name, &value.to_string())))
+            .collect();
+
+        decode(&body(self.client.try_get("audit", Query::params(params), None).await?).await?)
+    }
+}
+
+/// It returns the body of a successful response, or the error for the
+/// rejected requests.
+async fn body(response: Response) -> Result<String, ApiError> {
+    let status = response.status();
+    let body = response.text().await?;
+
+    match status {
+        StatusCode::OK => Ok(body),
+        StatusCode::BAD_REQUEST => Err(ApiError::BadRequest(body)),
+        StatusCode::FORBIDDEN => Err(ApiError::Forbidden(body)),
+        StatusCode::INTERNAL_SERVER_ERROR if body.contains("reason: \"unauthorized\"") => Err(ApiError::Unauthorized),
+        StatusCode::INTERNAL_SERVER_ERROR if body.contains("reason: \"token not valid\"") => Err(ApiError::TokenNotValid),
+        StatusCode::INTERNAL_SERVER_ERROR => Err(ApiError::Server(body)),
+        status => Err(ApiError::UnexpectedStatus { status, body }),
+    }
+}
+
+fn decode<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|source| ApiError::Decode {
+        source,
+        body: body.to_string(),
+    })
+}
+
+/// It decodes the result of the actions that don't return data.
+fn action(body: &str) -> Result<(), ApiError> {
+    if let Some(message) = not_known(body) {
+        return Err(ApiError::NotFound(message));
+    }
+
+    match decode::<ActionStatus<'_>>(body)? {
+        ActionStatus::Ok => Ok(()),
+        ActionStatus::Err { reason } => Err(ApiError::Server(reason.into_owned())),
+    }
+}
+
+/// Some endpoints return a `200` response with a json string like
+/// `"torrent not known"` when the resource is not tracked.
+fn not_known(body: &str) -> Option<String> {
+    serde_json::from_str::<String>(body)
+        .ok()
+        .filter(|message| message.ends_with("not known"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{action, not_known, ApiError};
+
+    #[test]
+    fn it_should_decode_the_ok_action_status() {
+        assert!(action(r#"{"status":"ok"}"#).is_ok());
+    }
+
+    #[test]
+    fn it_should_return_a_not_found_error_when_the_resource_is_not_known() {
+        assert!(matches!(action(r#""peer not known""#), Err(ApiError::NotFound(message)) if message == "peer not known"));
+        assert_eq!(not_known(r#"{"status":"ok"}"#), None);
+    }
+
+    #[test]
+    fn it_should_return_a_server_error_when_the_action_fails() {
+        assert!(matches!(
+            action(r#"{"status":"err","reason":"failed"}"#),
+            Err(ApiError::Server(reason)) if reason == "failed"
+        ));
+    }
+}
@@ -0,0 +1,14 @@
+//! Response status of the API actions that don't return data.
+use std::borrow::Cow;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Response status used when requests have only two possible results
+/// `Ok` or `Error` and no data is returned.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ActionStatus<'a> {
+    Ok,
+    Err { reason: Cow<'a, str> },
+}
@@ -0,0 +1,48 @@
+//! API resources for the `audit` API context.
+use std::net::IpAddr;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A record of an API action that changes the tracker state.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// When the action finished, in RFC 3339 format.
+    pub timestamp: String,
+    /// The label of the token used to authenticate the request.
+    pub token_label: String,
+    /// The IP address of the API client, if known.
+    pub client_ip: Option<IpAddr>,
+    /// The request method and the route.
+    pub action: String,
+    /// The requested path.
+    pub target: String,
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// `success`, `forbidden` or `failure`.
+    pub result: Outcome,
+}
+
+/// The result of an API action.
+#[derive(Serialize, Deserialize, JsonSchema, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// The action was performed.
+    Success,
+    /// The token is not allowed to perform the action.
+    Forbidden,
+    /// The action was not performed because of an invalid request or an
+    /// internal error.
+    Failure,
+}
+
+impl Outcome {
+    #[must_use]
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            200..=299 => Self::Success,
+            401 | 403 => Self::Forbidden,
+            _ => Self::Failure,
+        }
+    }
+}
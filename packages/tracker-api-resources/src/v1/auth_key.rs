@@ -0,0 +1,15 @@
+//! API resources for the `auth_key` API context.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A resource that represents an authentication key.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct AuthKey {
+    /// The authentication key.
+    pub key: String,
+    /// The timestamp when the key will expire.
+    #[deprecated(since = "3.0.0", note = "please use `expiry_time` instead")]
+    pub valid_until: Option<u64>, // todo: remove when the torrust-index-backend starts using the `expiry_time` attribute.
+    /// The ISO 8601 timestamp when the key will expire.
+    pub expiry_time: Option<String>,
+}
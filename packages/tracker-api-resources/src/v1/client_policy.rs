@@ -0,0 +1,16 @@
+//! API resources for the `client_policy` API context.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A rule that allows or denies peers by their peer ID.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct ClientRule {
+    /// The rule ID. It's used to remove the rule.
+    pub id: u64,
+    /// What to do with the matching peers: `allow` or `deny`.
+    pub action: String,
+    /// How the pattern matches the peer IDs: `prefix` or `regex`.
+    pub kind: String,
+    /// The peer ID prefix or regular expression.
+    pub pattern: String,
+}
@@ -0,0 +1,13 @@
+//! API resources for the `config` API context.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The changed settings after reloading the configuration.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// Changed settings that have been applied to the running tracker.
+    pub applied: Vec<String>,
+    /// Changed settings that will only be applied after restarting the
+    /// tracker.
+    pub requires_restart: Vec<String>,
+}
@@ -0,0 +1,22 @@
+//! API resources for the `listener` API context.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A UDP or HTTP tracker listener.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct Listener {
+    /// The listener ID. It's used to stop or restart the listener.
+    pub id: u64,
+    /// The tracker protocol: `udp` or `http`.
+    pub protocol: String,
+    /// The address in the listener configuration.
+    pub bind_address: String,
+    /// The address the listener is bound to. It's different from the
+    /// `bind_address` when the configured port is `0`. It's `None` when the
+    /// listener is not running.
+    pub local_address: Option<String>,
+    /// Whether the HTTP listener uses TLS.
+    pub tls: bool,
+    /// The listener state: `running` or `stopped`.
+    pub state: String,
+}
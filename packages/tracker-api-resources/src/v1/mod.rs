@@ -0,0 +1,15 @@
+//! Version 1 of the API resources, shared by the tracker API server and the
+//! API client.
+//!
+//! They are the types of the JSON request and response bodies of the API
+//! endpoints.
+pub mod action_status;
+pub mod audit;
+pub mod auth_key;
+pub mod client_policy;
+pub mod config;
+pub mod health_check;
+pub mod listener;
+pub mod peer;
+pub mod stats;
+pub mod torrent;
@@ -0,0 +1,184 @@
+//! API resources for the `stats` API context.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// It contains all the statistics generated by the tracker.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct Stats {
+    // Torrent metrics
+    /// Total number of torrents.
+    pub torrents: u64,
+    /// Total number of seeders for all torrents.
+    pub seeders: u64,
+    /// Total number of peers that have ever completed downloading for all torrents.
+    pub completed: u64,
+    /// Total number of leechers for all torrents.
+    pub leechers: u64,
+
+    // Eviction metrics
+    /// Total number of torrents evicted to make room for new torrents.
+    pub torrents_evicted: u64,
+    /// Total number of new torrents refused because the tracker was full.
+    pub torrents_refused: u64,
+    /// Total number of peers evicted to make room for new peers.
+    pub peers_evicted: u64,
+    /// Total number of new peers refused because the swarm or the tracker was full.
+    pub peers_refused: u64,
+    /// Total number of new peers whose IP address already had the maximum
+    /// number of peers in the swarm.
+    pub peers_per_ip_exceeded: u64,
+
+    // Announce interval metrics
+    /// Total number of announces received before the minimum announce
+    /// interval had elapsed.
+    pub early_announces: u64,
+    /// Tracker load used to lengthen the announce interval, as a percentage
+    /// rounded down. It goes above `100` when the announce request rate is
+    /// higher than the configured high rate. It's `0` when the adaptive
+    /// announce interval is disabled.
+    pub announce_load_percent: u64,
+    /// Average interval in seconds sent in the announce responses, rounded
+    /// down.
+    pub announce_interval_avg: u64,
+
+    // Protocol metrics
+    /// Total number of TCP (HTTP tracker) connections from IPv4 peers.
+    /// Since the HTTP tracker spec does not require a handshake, this metric
+    /// increases for every HTTP request.
+    pub tcp4_connections_handled: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests from IPv4 peers.
+    pub tcp4_announces_handled: u64,
+    /// Total number of TCP (HTTP tracker) `scrape` requests from IPv4 peers.
+    pub tcp4_scrapes_handled: u64,
+
+    /// Total number of TCP (HTTP tracker) connections from IPv6 peers.
+    pub tcp6_connections_handled: u64,
+    /// Total number of TCP (HTTP tracker) `announce` requests from IPv6 peers.
+    pub tcp6_announces_handled: u64,
+    /// Total number of TCP (HTTP tracker) `scrape` requests from IPv6 peers.
+    pub tcp6_scrapes_handled: u64,
+
+    // UDP
+    /// Total number of UDP (UDP tracker) requests aborted.
+    pub udp_requests_aborted: u64,
+    /// Total number of UDP (UDP tracker) requests banned.
+    pub udp_requests_banned: u64,
+    /// Total number of IPs banned for UDP (UDP tracker) requests.
+    pub udp_banned_ips_total: u64,
+    /// Average rounded time spent processing UDP connect requests.
+    pub udp_avg_connect_processing_time_ns: u64,
+    /// Average rounded time spent processing UDP announce requests.
+    pub udp_avg_announce_processing_time_ns: u64,
+    /// Average rounded time spent processing UDP scrape requests.
+    pub udp_avg_scrape_processing_time_ns: u64,
+
+    // UDPv4
+    /// Total number of UDP (UDP tracker) requests from IPv4 peers.
+    pub udp4_requests: u64,
+    /// Total number of UDP (UDP tracker) connections from IPv4 peers.
+    pub udp4_connections_handled: u64,
+    /// Total number of UDP (UDP tracker) `announce` requests from IPv4 peers.
+    pub udp4_announces_handled: u64,
+    /// Total number of UDP (UDP tracker) `scrape` requests from IPv4 peers.
+    pub udp4_scrapes_handled: u64,
+    /// Total number of UDP (UDP tracker) responses from IPv4 peers.
+    pub udp4_responses: u64,
+    /// Total number of UDP (UDP tracker) `scrape` requests from IPv4 peers.
+    pub udp4_errors_handled: u64,
+
+    // UDPv6
+    /// Total number of UDP (UDP tracker) requests from IPv6 peers.
+    pub udp6_requests: u64,
+    /// Total number of UDP (UDP tracker) `connection` requests from IPv6 peers.
+    pub udp6_connections_handled: u64,
+    /// Total number of UDP (UDP tracker) `announce` requests from IPv6 peers.
+    pub udp6_announces_handled: u64,
+    /// Total number of UDP (UDP tracker) `scrape` requests from IPv6 peers.
+    pub udp6_scrapes_handled: u64,
+    /// Total number of UDP (UDP tracker) responses from IPv6 peers.
+    pub udp6_responses: u64,
+    /// Total number of UDP (UDP tracker) `scrape` requests from IPv6 peers.
+    pub udp6_errors_handled: u64,
+}
+
+/// The historical metrics in a time range.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct StatsHistory {
+    /// The number of seconds covered by each sample.
+    pub step: u64,
+    /// The samples, from the oldest to the newest.
+    pub samples: Vec<StatsSample>,
+}
+
+/// The metrics in a period of time.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct StatsSample {
+    /// The start of the period, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The length of the period, in seconds. It can be shorter than the step
+    /// when some samples are missing, for example, when the tracker was
+    /// stopped.
+    pub duration: u64,
+
+    // Changes in the period
+    /// Number of UDP and HTTP requests.
+    pub requests: u64,
+    /// Number of UDP and HTTP `announce` requests.
+    pub announces: u64,
+    /// Number of UDP and HTTP `scrape` requests.
+    pub scrapes: u64,
+    /// Number of UDP error responses.
+    pub errors: u64,
+
+    // Values at the end of the period
+    /// Number of torrents.
+    pub torrents: u64,
+    /// Number of seeders for all torrents.
+    pub seeders: u64,
+    /// Number of leechers for all torrents.
+    pub leechers: u64,
+}
+
+/// The number of active peers using a client software.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct ClientStats {
+    /// The client family parsed from the peer IDs, for example,
+    /// `Transmission`. It's `Unknown` for the peer IDs that do not follow a
+    /// known convention.
+    pub client: String,
+    /// The client version, if the client encodes it in the peer ID.
+    pub version: Option<String>,
+    /// Number of active peers using this client and version.
+    pub peers: u64,
+}
+
+/// The number of active peers by country and autonomous system (ASN).
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct LocationStats {
+    /// The countries, the most common first.
+    pub countries: Vec<CountryStats>,
+    /// The autonomous systems, the most common first.
+    pub asns: Vec<AsnStats>,
+}
+
+/// The number of active peers in a country.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct CountryStats {
+    /// The ISO 3166-1 alpha-2 code of the country, for example, `SE`. It's
+    /// `null` for the peers whose address is not in the `GeoIP` database.
+    pub country: Option<String>,
+    /// Number of active peers in this country.
+    pub peers: u64,
+}
+
+/// The number of active peers in an autonomous system.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct AsnStats {
+    /// The autonomous system number, for example, `29518`. It's `null` for the
+    /// peers whose address is not in the `GeoIP` database.
+    pub asn: Option<u32>,
+    /// The organization that owns the autonomous system.
+    pub organization: Option<String>,
+    /// Number of active peers in this autonomous system.
+    pub peers: u64,
+}
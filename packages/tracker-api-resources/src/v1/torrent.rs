@@ -0,0 +1,47 @@
+//! `Torrent` and `ListItem` API resources.
+//!
+//! - `Torrent` is the full torrent resource.
+//! - `ListItem` is a list item resource on a torrent list. `ListItem` does
+//!   include a `peers` field but it is always `None` in the struct and `null` in
+//!   the JSON response.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::peer::Peer;
+
+/// `Torrent` API resource.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct Torrent {
+    /// The torrent's info hash v1.
+    pub info_hash: String,
+    /// The torrent's seeders counter. Active peers with a full copy of the
+    /// torrent.
+    pub seeders: u64,
+    /// The torrent's completed counter. Peers that have ever completed the
+    /// download.
+    pub completed: u64,
+    /// The torrent's leechers counter. Active peers that are downloading the
+    /// torrent.
+    pub leechers: u64,
+    /// The torrent's peers. See [`Peer`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peers: Option<Vec<Peer>>,
+}
+
+/// `ListItem` API resource. A list item on a torrent list.
+/// `ListItem` does include a `peers` field but it is always `None` in the
+///  struct and `null` in the JSON response.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq)]
+pub struct ListItem {
+    /// The torrent's info hash v1.
+    pub info_hash: String,
+    /// The torrent's seeders counter. Active peers with a full copy of the
+    /// torrent.
+    pub seeders: u64,
+    /// The torrent's completed counter. Peers that have ever completed the
+    /// download.
+    pub completed: u64,
+    /// The torrent's leechers counter. Active peers that are downloading the
+    /// torrent.
+    pub leechers: u64,
+}
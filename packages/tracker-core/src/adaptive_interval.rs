@@ -0,0 +1,383 @@
+//! Adaptive announce interval.
+//!
+//! The [`AnnouncePolicy`] is a fixed `interval`/`interval_min` pair for all
+//! the torrents. When the [`AdaptiveInterval`] configuration is set, the
+//! [`AnnounceHandler`](crate::announce_handler::AnnounceHandler) computes the
+//! policy of each announce response instead:
+//!
+//! - The interval is shortened for the swarms with fewer than
+//!   `small_swarm_peers` peers, down to `min_interval` for an empty swarm.
+//!   Fresh peer lists matter most in tiny swarms.
+//! - The interval is lengthened towards `max_interval` with the tracker load.
+//!   The load is the highest of the announce request rate, relative to
+//!   `high_announce_rate`, and the fraction of the memory limits in use.
+//!
+//! The interval is always between `min_interval` and `max_interval`, and the
+//! `interval_min` sent to the clients is never longer than the interval.
+//!
+//! The request rate is measured by the [`AnnounceRate`] in fixed windows of
+//! [`RATE_WINDOW_SECS`] seconds. The rate used is the one of the last complete
+//! window.
+//!
+//! The intervals sent in the announce responses are counted in an
+//! [`IntervalHistogram`], whether the adaptive interval is enabled or not.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use torrust_tracker_configuration::{AdaptiveInterval, AnnouncePolicy};
+use torrust_tracker_primitives::DurationSinceUnixEpoch;
+
+/// Length in seconds of the windows the announce requests are counted in.
+pub const RATE_WINDOW_SECS: u64 = 10;
+
+/// Upper bounds, in seconds, of the buckets of the [`IntervalHistogram`].
+/// The intervals longer than the last bound are counted in an extra bucket.
+pub const INTERVAL_BUCKETS: [u32; 8] = [60, 120, 300, 600, 900, 1200, 1800, 3600];
+
+/// Computes the announce policy of each announce response from the tracker
+/// load and the size of the swarm.
+#[derive(Debug, Default)]
+pub struct AdaptiveIntervalPolicy {
+    /// The announce requests received by the tracker.
+    rate: AnnounceRate,
+}
+
+impl AdaptiveIntervalPolicy {
+    /// It records an announce request received at `now`.
+    pub fn record_announce(&self, now: DurationSinceUnixEpoch) {
+        self.rate.record(now);
+    }
+
+    /// Returns the announce policy for a swarm with `swarm_peers` peers.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The bounds and thresholds of the adaptive interval.
+    /// * `base` - The configured announce policy.
+    /// * `swarm_peers` - The number of peers in the swarm.
+    /// * `memory_usage` - The fraction, from `0.0` to `1.0`, of the memory
+    ///   limits in use.
+    /// * `now` - The current time, to get the announce request rate.
+    #[must_use]
+    pub fn announce_policy(
+        &self,
+        config: &AdaptiveInterval,
+        base: &AnnouncePolicy,
+        swarm_peers: u32,
+        memory_usage: f64,
+        now: DurationSinceUnixEpoch,
+    ) -> AnnouncePolicy {
+        announce_policy_for(config, base, swarm_peers, self.load(config, memory_usage, now))
+    }
+
+    /// Returns the tracker load used to lengthen the interval: the highest of
+    /// the announce request rate, relative to `high_announce_rate`, and the
+    /// `memory_usage`. It's above `1.0` when the rate is higher than
+    /// `high_announce_rate`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn load(&self, config: &AdaptiveInterval, memory_usage: f64, now: DurationSinceUnixEpoch) -> f64 {
+        let rate_load = self.rate.per_second(now) / config.high_announce_rate.max(1) as f64;
+
+        rate_load.max(memory_usage)
+    }
+}
+
+/// Returns the announce policy for a swarm with `swarm_peers` peers when the
+/// tracker `load` is between `0.0` (idle) and `1.0` (fully loaded).
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+pub fn announce_policy_for(config: &AdaptiveInterval, base: &AnnouncePolicy, swarm_peers: u32, load: f64) -> AnnouncePolicy {
+    let min_interval = f64::from(config.min_interval);
+    let max_interval = f64::from(config.max_interval);
+    let mut interval = f64::from(base.interval).clamp(min_interval, max_interval);
+
+    if swarm_peers < config.small_swarm_peers {
+        interval = min_interval + (interval - min_interval) * f64::from(swarm_peers) / f64::from(config.small_swarm_peers);
+    }
+
+    interval += (max_interval - interval) * load.clamp(0.0, 1.0);
+
+    // The interval is between `min_interval` and `max_interval`, so it fits in a `u32`.
+    let interval = interval.round().clamp(min_interval, max_interval) as u32;
+
+    AnnouncePolicy {
+        interval,
+        interval_min: base.interval_min.min(interval),
+    }
+}
+
+/// Number of announce requests received by the tracker, counted in fixed
+/// windows of [`RATE_WINDOW_SECS`] seconds.
+///
+/// The counters are updated without locks, so the rate is approximate when
+/// many requests arrive at the boundary between two windows.
+#[derive(Debug, Default)]
+pub struct AnnounceRate {
+    /// The current window: the seconds since the Unix epoch divided by
+    /// [`RATE_WINDOW_SECS`].
+    window: AtomicU64,
+
+    /// The announce requests received in the current window.
+    current: AtomicU64,
+
+    /// The announce requests received in the window before the current one.
+    previous: AtomicU64,
+}
+
+impl AnnounceRate {
+    /// It records an announce request received at `now`.
+    pub fn record(&self, now: DurationSinceUnixEpoch) {
+        let window = now.as_secs() / RATE_WINDOW_SECS;
+        let current_window = self.window.load(Ordering::Relaxed);
+
+        if window > current_window
+            && self
+                .window
+                .compare_exchange(current_window, window, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            let announces = self.current.swap(0, Ordering::Relaxed);
+
+            // The previous window is empty if no announces were received in it.
+            let previous = if window == current_window + 1 { announces } else { 0 };
+
+            self.previous.store(previous, Ordering::Relaxed);
+        }
+
+        self.current.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The announce requests per second in the last complete window before
+    /// `now`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn per_second(&self, now: DurationSinceUnixEpoch) -> f64 {
+        let window = now.as_secs() / RATE_WINDOW_SECS;
+        let current_window = self.window.load(Ordering::Relaxed);
+
+        let announces = if window <= current_window {
+            self.previous.load(Ordering::Relaxed)
+        } else if window == current_window + 1 {
+            self.current.load(Ordering::Relaxed)
+        } else {
+            0
+        };
+
+        announces as f64 / RATE_WINDOW_SECS as f64
+    }
+}
+
+/// The current tracker load and the intervals sent in the announce responses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnnounceIntervalMetrics {
+    /// The tracker load used to lengthen the interval. See
+    /// [`AdaptiveIntervalPolicy::load`]. It's `None` when the adaptive
+    /// interval is disabled.
+    pub load: Option<f64>,
+
+    /// The intervals sent in the announce responses.
+    pub intervals: IntervalMetrics,
+}
+
+/// Number of announce responses by the interval sent in them.
+#[derive(Debug, Default)]
+pub struct IntervalHistogram {
+    /// The responses in each of the [`INTERVAL_BUCKETS`], and in the extra
+    /// bucket for the longer intervals.
+    buckets: [AtomicU64; INTERVAL_BUCKETS.len() + 1],
+
+    /// The sum of all the intervals sent, in seconds.
+    sum: AtomicU64,
+}
+
+impl IntervalHistogram {
+    /// It records an announce response with the given `interval`.
+    pub fn record(&self, interval: u32) {
+        let bucket = INTERVAL_BUCKETS.partition_point(|bound| *bound < interval);
+
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(u64::from(interval), Ordering::Relaxed);
+    }
+
+    /// Returns the intervals sent so far.
+    #[must_use]
+    pub fn metrics(&self) -> IntervalMetrics {
+        let buckets: Vec<u64> = self.buckets.iter().map(|bucket| bucket.load(Ordering::Relaxed)).collect();
+
+        IntervalMetrics {
+            count: buckets.iter().sum(),
+            buckets,
+            sum: self.sum.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The intervals sent in the announce responses.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalMetrics {
+    /// The number of responses in each of the [`INTERVAL_BUCKETS`], followed
+    /// by the number of responses with a longer interval. The counts are not
+    /// cumulative.
+    pub buckets: Vec<u64>,
+
+    /// The number of responses.
+    pub count: u64,
+
+    /// The sum of all the intervals sent, in seconds.
+    pub sum: u64,
+}
+
+impl IntervalMetrics {
+    /// The average interval sent, in seconds, rounded down. It's `0` before
+    /// the first response.
+    #[must_use]
+    pub fn average(&self) -> u64 {
+        self.sum.checked_div(self.count).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod the_announce_policy {
+        use torrust_tracker_configuration::{AdaptiveInterval, AnnouncePolicy};
+
+        use crate::adaptive_interval::announce_policy_for;
+
+        fn config() -> AdaptiveInterval {
+            AdaptiveInterval {
+                min_interval: 60,
+                max_interval: 1800,
+                small_swarm_peers: 10,
+                high_announce_rate: 1000,
+            }
+        }
+
+        fn base() -> AnnouncePolicy {
+            AnnouncePolicy {
+                interval: 600,
+                interval_min: 300,
+            }
+        }
+
+        #[test]
+        fn it_should_use_the_configured_policy_for_an_idle_tracker_and_a_big_enough_swarm() {
+            assert_eq!(announce_policy_for(&config(), &base(), 10, 0.0), base());
+        }
+
+        #[test]
+        fn it_should_shorten_the_interval_for_small_swarms() {
+            assert_eq!(
+                announce_policy_for(&config(), &base(), 0, 0.0),
+                AnnouncePolicy {
+                    interval: 60,
+                    interval_min: 60
+                }
+            );
+            assert_eq!(
+                announce_policy_for(&config(), &base(), 5, 0.0),
+                AnnouncePolicy {
+                    interval: 330,
+                    interval_min: 300
+                }
+            );
+        }
+
+        #[test]
+        fn it_should_lengthen_the_interval_with_the_tracker_load() {
+            assert_eq!(announce_policy_for(&config(), &base(), 10, 0.5).interval, 1200);
+            assert_eq!(announce_policy_for(&config(), &base(), 10, 1.0).interval, 1800);
+        }
+
+        #[test]
+        fn it_should_keep_the_interval_within_the_configured_bounds() {
+            let base = AnnouncePolicy {
+                interval: 3600,
+                interval_min: 3600,
+            };
+
+            assert_eq!(
+                announce_policy_for(&config(), &base, 10, 2.0),
+                AnnouncePolicy {
+                    interval: 1800,
+                    interval_min: 1800
+                }
+            );
+        }
+    }
+
+    mod the_announce_rate {
+        use std::time::Duration;
+
+        use crate::adaptive_interval::{AnnounceRate, RATE_WINDOW_SECS};
+
+        fn at(seconds: u64) -> Duration {
+            Duration::from_secs(seconds)
+        }
+
+        fn record(rate: &AnnounceRate, announces: u64, now: Duration) {
+            for _ in 0..announces {
+                rate.record(now);
+            }
+        }
+
+        #[test]
+        fn it_should_be_zero_without_announces() {
+            assert!(AnnounceRate::default().per_second(at(1000)).abs() < f64::EPSILON);
+        }
+
+        #[test]
+        fn it_should_be_the_rate_of_the_last_complete_window() {
+            let rate = AnnounceRate::default();
+
+            record(&rate, 50, at(1000));
+            assert!(rate.per_second(at(1000)).abs() < f64::EPSILON);
+
+            record(&rate, 20, at(1000 + RATE_WINDOW_SECS));
+            assert!((rate.per_second(at(1000 + RATE_WINDOW_SECS)) - 5.0).abs() < f64::EPSILON);
+            assert!((rate.per_second(at(1000 + 2 * RATE_WINDOW_SECS)) - 2.0).abs() < f64::EPSILON);
+        }
+
+        #[test]
+        fn it_should_drop_to_zero_after_a_window_without_announces() {
+            let rate = AnnounceRate::default();
+
+            record(&rate, 50, at(1000));
+            record(&rate, 1, at(1000 + 2 * RATE_WINDOW_SECS));
+
+            assert!(rate.per_second(at(1000 + 2 * RATE_WINDOW_SECS)).abs() < f64::EPSILON);
+            assert!(rate.per_second(at(1000 + 3 * RATE_WINDOW_SECS)) > 0.0);
+            assert!(rate.per_second(at(1000 + 4 * RATE_WINDOW_SECS)).abs() < f64::EPSILON);
+        }
+    }
+
+    mod the_interval_histogram {
+        use crate::adaptive_interval::{IntervalHistogram, INTERVAL_BUCKETS};
+
+        #[test]
+        fn it_should_count_the_intervals_in_the_bucket_of_the_smallest_bound_not_lower_than_them() {
+            let histogram = IntervalHistogram::default();
+
+            histogram.record(30);
+            histogram.record(60);
+            histogram.record(61);
+            histogram.record(7200);
+
+            let metrics = histogram.metrics();
+
+            assert_eq!(metrics.buckets.len(), INTERVAL_BUCKETS.len() + 1);
+            assert_eq!(metrics.buckets[0], 2);
+            assert_eq!(metrics.buckets[1], 1);
+            assert_eq!(metrics.buckets[INTERVAL_BUCKETS.len()], 1);
+            assert_eq!(metrics.count, 4);
+            assert_eq!(metrics.sum, 7351);
+            assert_eq!(metrics.average(), 1837);
+        }
+
+        #[test]
+        fn it_should_have_a_zero_average_before_the_first_interval() {
+            assert_eq!(IntervalHistogram::default().metrics().average(), 0);
+        }
+    }
+}
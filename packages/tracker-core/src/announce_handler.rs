@@ -39,7 +39,7 @@
 //! ```
 //!
 //! ```text
-//! let announce_data = announce_handler.announce(&info_hash, &mut peer, &peer_ip).await;
+//! let announce_data = announce_handler.announce(&info_hash, &mut peer, &peer_ip, &peers_wanted).unwrap()?;
 //! ```
 //!
 //! The handler returns the list of peers for the torrent with the infohash
@@ -65,7 +65,7 @@
 //! pub struct AnnounceData {
 //!     pub peers: Vec<peer::Peer>,
 //!     pub swarm_stats: SwarmMetadata,
-//!     pub policy: AnnouncePolicy, // the tracker announce policy.
+//!     pub policy: AnnouncePolicy, // the tracker announce policy, or the adaptive one.
 //! }
 //!
 //! pub struct SwarmMetadata {
@@ -83,6 +83,10 @@
 //! }
 //! ```
 //!
+//! When the [`adaptive_interval`](crate::adaptive_interval) is enabled, the
+//! `policy` of each response is computed from the tracker load and the swarm
+//! size, instead of being the configured announce policy.
+//!
 //! ## Related BEPs:
 //!
 //! Refer to `BitTorrent` BEPs and other sites for more information about the `announce` request:
@@ -91,27 +95,60 @@
 //! - [BEP 23. Tracker Returns Compact Peer Lists](https://www.bittorrent.org/beps/bep_0023.html)
 //! - [Vuze docs](https://wiki.vuze.com/w/Announce)
 use std::net::IpAddr;
-use std::sync::Arc;
+use std::panic::Location;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
+use aquatic_udp_protocol::AnnounceEvent;
 use bittorrent_primitives::info_hash::InfoHash;
-use torrust_tracker_configuration::{Core, TORRENT_PEERS_LIMIT};
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::{AdaptiveInterval, AnnouncePolicy, Core, EarlyAnnouncePolicy, TORRENT_PEERS_LIMIT};
 use torrust_tracker_primitives::core::AnnounceData;
 use torrust_tracker_primitives::peer;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 
 use super::torrent::repository::in_memory::InMemoryTorrentRepository;
 use super::torrent::repository::persisted::DatabasePersistentTorrentRepository;
+use crate::adaptive_interval::{AdaptiveIntervalPolicy, AnnounceIntervalMetrics, IntervalHistogram};
+use crate::error::AnnounceError;
+use crate::event::{Bus, Event};
+use crate::geoip::GeoIp;
+use crate::peer_address_policy;
+use crate::torrent::selection::PeerSelection;
+use crate::CurrentClock;
 
 /// Handles `announce` requests from `BitTorrent` clients.
 pub struct AnnounceHandler {
     /// The tracker configuration.
-    config: Core,
+    ///
+    /// Only the settings applied by [`reload`](Self::reload) can change while
+    /// the tracker is running.
+    config: RwLock<Core>,
 
     /// Repository for in-memory torrent data.
     in_memory_torrent_repository: Arc<InMemoryTorrentRepository>,
 
     /// Repository for persistent torrent data (database).
     db_torrent_repository: Arc<DatabasePersistentTorrentRepository>,
+
+    /// Bus where the new torrents are published.
+    events: Arc<Bus>,
+
+    /// Locates the peers to restrict the announce requests by country, and to
+    /// find the nearby peers with the locality peer selection.
+    geoip: Arc<GeoIp>,
+
+    /// Computes the announce interval of each response when the adaptive
+    /// interval is enabled.
+    adaptive_interval: AdaptiveIntervalPolicy,
+
+    /// The number of announces rejected because they were received before
+    /// the peer's `interval_min`.
+    early_announces: AtomicU64,
+
+    /// The intervals sent in the announce responses.
+    intervals: IntervalHistogram,
 }
 
 impl AnnounceHandler {
@@ -121,14 +158,44 @@ impl AnnounceHandler {
         config: &Core,
         in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>,
         db_torrent_repository: &Arc<DatabasePersistentTorrentRepository>,
+        events: &Arc<Bus>,
+        geoip: &Arc<GeoIp>,
     ) -> Self {
         Self {
-            config: config.clone(),
+            config: RwLock::new(config.clone()),
             in_memory_torrent_repository: in_memory_torrent_repository.clone(),
             db_torrent_repository: db_torrent_repository.clone(),
+            events: events.clone(),
+            geoip: geoip.clone(),
+            adaptive_interval: AdaptiveIntervalPolicy::default(),
+            early_announces: AtomicU64::default(),
+            intervals: IntervalHistogram::default(),
         }
     }
 
+    /// Applies the settings that can be changed without restarting the
+    /// tracker: the announce policy, the adaptive interval, the tracker
+    /// policy, the external IP, the peer address policy and the peer
+    /// selection.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the configuration lock is poisoned.
+    pub fn reload(&self, config: &Core) {
+        let mut current = self
+            .config
+            .write()
+            .expect("it should lock the announce handler configuration");
+
+        current.announce_policy = config.announce_policy;
+        current.adaptive_interval = config.adaptive_interval;
+        current.early_announce_policy = config.early_announce_policy;
+        current.tracker_policy = config.tracker_policy.clone();
+        current.net.external_ip = config.net.external_ip;
+        current.peer_address_policy = config.peer_address_policy;
+        current.peer_selection = config.peer_selection;
+    }
+
     /// Processes an announce request from a peer.
     ///
     /// BEP 03: [The `BitTorrent` Protocol Specification](https://www.bittorrent.org/beps/bep_0003.html).
@@ -143,13 +210,25 @@ impl AnnounceHandler {
     /// # Returns
     ///
     /// An `AnnounceData` struct containing the list of peers, swarm statistics, and tracker policy.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`AnnounceError`] if the peer is rejected, for example,
+    /// because its country or its address is not allowed, because its IP
+    /// address already has too many peers in the swarm, or because it
+    /// announced again too early. The peer is not added to the swarm in that
+    /// case.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the configuration lock is poisoned.
     pub fn announce(
         &self,
         info_hash: &InfoHash,
         peer: &mut peer::Peer,
         remote_client_ip: &IpAddr,
         peers_wanted: &PeersWanted,
-    ) -> AnnounceData {
+    ) -> Result<AnnounceData, AnnounceError> {
         // code-review: maybe instead of mutating the peer we could just return
         // a tuple with the new peer and the announce data: (Peer, AnnounceData).
         // It could even be a different struct: `StoredPeer` or `PublicPeer`.
@@ -160,48 +239,198 @@ impl AnnounceHandler {
         // The `Tracker` has delegated that responsibility to the handlers
         // (because we want to return a friendly error response) but that does not mean we should
         // double-check authorization at this domain level too.
-        // I would propose to return a `Result<AnnounceData, Error>` here.
         // Besides, regarding authentication the `Tracker` is also responsible for authentication but
         // we are actually handling authentication at the handlers level. So I would extract that
         // responsibility into another authentication service.
 
+        let (external_ip, announce_policy, adaptive_interval, early_announce_policy, peer_address_policy, peer_selection) = {
+            let config = self.config.read().expect("it should lock the announce handler configuration");
+            (
+                config.net.external_ip,
+                config.announce_policy,
+                config.adaptive_interval,
+                config.early_announce_policy,
+                config.peer_address_policy,
+                config.peer_selection,
+            )
+        };
+
+        if adaptive_interval.is_some() {
+            self.adaptive_interval.record_announce(peer.updated);
+        }
+
         tracing::debug!("Before: {peer:?}");
-        peer.change_ip(&assign_ip_address_to_peer(remote_client_ip, self.config.net.external_ip));
+        peer.change_ip(&assign_ip_address_to_peer(remote_client_ip, external_ip));
         tracing::debug!("After: {peer:?}");
 
-        let stats = self.upsert_peer_and_get_stats(info_hash, peer);
+        self.geoip.authorize(&peer.peer_addr.ip())?;
+        peer_address_policy::authorize(&peer_address_policy, &peer.peer_addr)?;
+
+        let announce_policy = match adaptive_interval {
+            Some(adaptive_interval) => self.get_adaptive_announce_policy(&adaptive_interval, &announce_policy, info_hash, peer),
+            None => announce_policy,
+        };
+
+        if early_announce_policy == EarlyAnnouncePolicy::Reject {
+            if let Some(wait) = self.get_early_announce_wait(info_hash, peer, announce_policy.interval_min) {
+                self.early_announces.fetch_add(1, Ordering::Relaxed);
+
+                return Err(AnnounceError::AnnouncedTooEarly {
+                    retry_in: wait.as_secs() + u64::from(wait.subsec_nanos() > 0),
+                    location: Location::caller(),
+                });
+            }
+        }
+
+        let stats = self.upsert_peer_and_get_stats(info_hash, peer)?;
 
-        let peers = self
-            .in_memory_torrent_repository
-            .get_peers_for(info_hash, peer, peers_wanted.limit());
+        let peers = self.in_memory_torrent_repository.get_peers_for(
+            info_hash,
+            peer,
+            peers_wanted.limit(),
+            &|other| peer_address_policy::is_shared_with(&peer_address_policy, &other.peer_addr, remote_client_ip),
+            &PeerSelection::new(&peer_selection, &peer.peer_addr.ip(), &self.geoip),
+        );
 
-        AnnounceData {
+        self.intervals.record(announce_policy.interval);
+
+        Ok(AnnounceData {
             peers,
             stats,
-            policy: self.config.announce_policy,
+            policy: announce_policy,
+        })
+    }
+
+    /// Returns the number of announces rejected because they were received
+    /// before the peer's `interval_min`, when the tracker enforces it.
+    #[must_use]
+    pub fn get_early_announces(&self) -> u64 {
+        self.early_announces.load(Ordering::Relaxed)
+    }
+
+    /// Returns the current tracker load, when the adaptive interval is
+    /// enabled, and the intervals sent in the announce responses.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the configuration lock is poisoned.
+    #[must_use]
+    pub fn get_announce_interval_metrics(&self) -> AnnounceIntervalMetrics {
+        let adaptive_interval = self
+            .config
+            .read()
+            .expect("it should lock the announce handler configuration")
+            .adaptive_interval;
+
+        AnnounceIntervalMetrics {
+            load: adaptive_interval.map(|adaptive_interval| {
+                self.adaptive_interval.load(
+                    &adaptive_interval,
+                    self.in_memory_torrent_repository.get_memory_usage(),
+                    CurrentClock::now(),
+                )
+            }),
+            intervals: self.intervals.metrics(),
+        }
+    }
+
+    /// Returns the announce policy for the swarm the peer announces to, from
+    /// the current tracker load and the number of peers in the swarm before
+    /// the announce.
+    fn get_adaptive_announce_policy(
+        &self,
+        adaptive_interval: &AdaptiveInterval,
+        announce_policy: &AnnouncePolicy,
+        info_hash: &InfoHash,
+        peer: &peer::Peer,
+    ) -> AnnouncePolicy {
+        let swarm_metadata = self.in_memory_torrent_repository.get_swarm_metadata(info_hash);
+
+        self.adaptive_interval.announce_policy(
+            adaptive_interval,
+            announce_policy,
+            swarm_metadata.complete + swarm_metadata.incomplete,
+            self.in_memory_torrent_repository.get_memory_usage(),
+            peer.updated,
+        )
+    }
+
+    /// Returns how long the peer still has to wait before announcing again,
+    /// if its previous announce was less than `interval_min` seconds ago.
+    ///
+    /// The `stopped` and `completed` events are never too early.
+    fn get_early_announce_wait(&self, info_hash: &InfoHash, peer: &peer::Peer, interval_min: u32) -> Option<Duration> {
+        if matches!(peer.event, AnnounceEvent::Stopped | AnnounceEvent::Completed) {
+            return None;
         }
+
+        let previous = self.in_memory_torrent_repository.get_peer(info_hash, &peer.peer_id)?;
+
+        let elapsed = peer.updated.saturating_sub(previous.updated);
+
+        Duration::from_secs(u64::from(interval_min))
+            .checked_sub(elapsed)
+            .filter(|wait| !wait.is_zero())
     }
 
     /// Updates the torrent data in memory, persists statistics if needed, and
     /// returns the updated swarm stats.
-    #[must_use]
-    fn upsert_peer_and_get_stats(&self, info_hash: &InfoHash, peer: &peer::Peer) -> SwarmMetadata {
+    ///
+    /// It fails when the peer is refused because its IP address already has
+    /// the maximum number of peers in the swarm.
+    fn upsert_peer_and_get_stats(&self, info_hash: &InfoHash, peer: &peer::Peer) -> Result<SwarmMetadata, AnnounceError> {
         let swarm_metadata_before = self.in_memory_torrent_repository.get_swarm_metadata(info_hash);
 
-        self.in_memory_torrent_repository.upsert_peer(info_hash, peer);
+        // The extra lookups are only done when someone is listening.
+        let is_new_torrent = self.events.has_subscribers() && self.in_memory_torrent_repository.get(info_hash).is_none();
+
+        let changes = self.in_memory_torrent_repository.upsert_peer_and_get_changes(info_hash, peer);
+
+        if let Some(evicted_info_hash) = changes.evicted_torrent {
+            self.events.publish(Event::TorrentRemoved {
+                info_hash: evicted_info_hash,
+            });
+        }
+
+        if changes.refused && changes.peers_per_ip_exceeded {
+            return Err(AnnounceError::TooManyPeersForAddress {
+                address: peer.peer_addr.ip(),
+                location: Location::caller(),
+            });
+        }
+
+        // The torrent is not created if the tracker is full.
+        if is_new_torrent && self.in_memory_torrent_repository.get(info_hash).is_some() {
+            self.events.publish(Event::TorrentCreated { info_hash: *info_hash });
+        }
 
         let swarm_metadata_after = self.in_memory_torrent_repository.get_swarm_metadata(info_hash);
 
+        if swarm_metadata_after.downloaded > swarm_metadata_before.downloaded {
+            self.events.publish(Event::TorrentCompleted { info_hash: *info_hash });
+        }
+
+        if swarm_metadata_before.complete == 0 && swarm_metadata_after.complete > 0 {
+            self.events.publish(Event::TorrentSeedersReturned { info_hash: *info_hash });
+        }
+
         if swarm_metadata_before != swarm_metadata_after {
             self.persist_stats(info_hash, &swarm_metadata_after);
         }
 
-        swarm_metadata_after
+        Ok(swarm_metadata_after)
     }
 
     /// Persists torrent statistics to the database if persistence is enabled.
     fn persist_stats(&self, info_hash: &InfoHash, swarm_metadata: &SwarmMetadata) {
-        if self.config.tracker_policy.persistent_torrent_completed_stat {
+        let persistent_torrent_completed_stat = self
+            .config
+            .read()
+            .expect("it should lock the announce handler configuration")
+            .tracker_policy
+            .persistent_torrent_completed_stat;
+
+        if persistent_torrent_completed_stat {
             let completed = swarm_metadata.downloaded;
             let info_hash = *info_hash;
 
@@ -351,6 +580,9 @@ mod tests {
 
                 use std::sync::Arc;
 
+                use torrust_tracker_configuration::AnnouncePolicy;
+                use torrust_tracker_test_helpers::configuration;
+
                 use crate::announce_handler::tests::the_announce_handler::{
                     peer_ip, public_tracker, sample_peer_1, sample_peer_2, sample_peer_3,
                 };
@@ -461,8 +693,9 @@ mod tests {
 
                     let mut peer = sample_peer();
 
-                    let announce_data =
-                        announce_handler.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+                    let announce_data = announce_handler
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                        .unwrap();
 
                     assert_eq!(announce_data.peers, vec![]);
                 }
@@ -472,16 +705,19 @@ mod tests {
                     let (announce_handler, _scrape_handler) = public_tracker();
 
                     let mut previously_announced_peer = sample_peer_1();
-                    announce_handler.announce(
-                        &sample_info_hash(),
-                        &mut previously_announced_peer,
-                        &peer_ip(),
-                        &PeersWanted::AsManyAsPossible,
-                    );
+                    announce_handler
+                        .announce(
+                            &sample_info_hash(),
+                            &mut previously_announced_peer,
+                            &peer_ip(),
+                            &PeersWanted::AsManyAsPossible,
+                        )
+                        .unwrap();
 
                     let mut peer = sample_peer_2();
-                    let announce_data =
-                        announce_handler.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+                    let announce_data = announce_handler
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                        .unwrap();
 
                     assert_eq!(announce_data.peers, vec![Arc::new(previously_announced_peer)]);
                 }
@@ -491,24 +727,29 @@ mod tests {
                     let (announce_handler, _scrape_handler) = public_tracker();
 
                     let mut previously_announced_peer_1 = sample_peer_1();
-                    announce_handler.announce(
-                        &sample_info_hash(),
-                        &mut previously_announced_peer_1,
-                        &peer_ip(),
-                        &PeersWanted::AsManyAsPossible,
-                    );
+                    announce_handler
+                        .announce(
+                            &sample_info_hash(),
+                            &mut previously_announced_peer_1,
+                            &peer_ip(),
+                            &PeersWanted::AsManyAsPossible,
+                        )
+                        .unwrap();
 
                     let mut previously_announced_peer_2 = sample_peer_2();
-                    announce_handler.announce(
-                        &sample_info_hash(),
-                        &mut previously_announced_peer_2,
-                        &peer_ip(),
-                        &PeersWanted::AsManyAsPossible,
-                    );
+                    announce_handler
+                        .announce(
+                            &sample_info_hash(),
+                            &mut previously_announced_peer_2,
+                            &peer_ip(),
+                            &PeersWanted::AsManyAsPossible,
+                        )
+                        .unwrap();
 
                     let mut peer = sample_peer_3();
-                    let announce_data =
-                        announce_handler.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::only(1));
+                    let announce_data = announce_handler
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::only(1))
+                        .unwrap();
 
                     // It should return only one peer. There is no guarantee on
                     // which peer will be returned.
@@ -518,6 +759,23 @@ mod tests {
                     );
                 }
 
+                #[tokio::test]
+                async fn it_should_return_the_reloaded_announce_policy() {
+                    let (announce_handler, _scrape_handler) = public_tracker();
+
+                    let mut config = configuration::ephemeral_public();
+                    config.core.announce_policy = AnnouncePolicy::new(60, 30);
+                    announce_handler.reload(&config.core);
+
+                    let mut peer = sample_peer();
+
+                    let announce_data = announce_handler
+                        .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                        .unwrap();
+
+                    assert_eq!(announce_data.policy, AnnouncePolicy::new(60, 30));
+                }
+
                 mod it_should_update_the_swarm_stats_for_the_torrent {
 
                     use crate::announce_handler::tests::the_announce_handler::{peer_ip, public_tracker};
@@ -530,8 +788,9 @@ mod tests {
 
                         let mut peer = seeder();
 
-                        let announce_data =
-                            announce_handler.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+                        let announce_data = announce_handler
+                            .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                            .unwrap();
 
                         assert_eq!(announce_data.stats.complete, 1);
                     }
@@ -542,8 +801,9 @@ mod tests {
 
                         let mut peer = leecher();
 
-                        let announce_data =
-                            announce_handler.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+                        let announce_data = announce_handler
+                            .announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                            .unwrap();
 
                         assert_eq!(announce_data.stats.incomplete, 1);
                     }
@@ -554,20 +814,24 @@ mod tests {
 
                         // We have to announce with "started" event because peer does not count if peer was not previously known
                         let mut started_peer = started_peer();
-                        announce_handler.announce(
-                            &sample_info_hash(),
-                            &mut started_peer,
-                            &peer_ip(),
-                            &PeersWanted::AsManyAsPossible,
-                        );
+                        announce_handler
+                            .announce(
+                                &sample_info_hash(),
+                                &mut started_peer,
+                                &peer_ip(),
+                                &PeersWanted::AsManyAsPossible,
+                            )
+                            .unwrap();
 
                         let mut completed_peer = completed_peer();
-                        let announce_data = announce_handler.announce(
-                            &sample_info_hash(),
-                            &mut completed_peer,
-                            &peer_ip(),
-                            &PeersWanted::AsManyAsPossible,
-                        );
+                        let announce_data = announce_handler
+                            .announce(
+                                &sample_info_hash(),
+                                &mut completed_peer,
+                                &peer_ip(),
+                                &PeersWanted::AsManyAsPossible,
+                            )
+                            .unwrap();
 
                         assert_eq!(announce_data.stats.downloaded, 1);
                     }
@@ -604,11 +868,14 @@ mod tests {
                     &config.core,
                     &in_memory_torrent_repository,
                     &db_torrent_repository,
+                    &Arc::default(),
                 ));
                 let announce_handler = Arc::new(AnnounceHandler::new(
                     &config.core,
                     &in_memory_torrent_repository,
                     &db_torrent_repository,
+                    &Arc::default(),
+                    &Arc::default(),
                 ));
 
                 let info_hash = sample_info_hash();
@@ -616,11 +883,15 @@ mod tests {
                 let mut peer = sample_peer();
 
                 peer.event = AnnounceEvent::Started;
-                let announce_data = announce_handler.announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+                let announce_data = announce_handler
+                    .announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                    .unwrap();
                 assert_eq!(announce_data.stats.downloaded, 0);
 
                 peer.event = AnnounceEvent::Completed;
-                let announce_data = announce_handler.announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+                let announce_data = announce_handler
+                    .announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                    .unwrap();
                 assert_eq!(announce_data.stats.downloaded, 1);
 
                 // Remove the newly updated torrent from memory
@@ -640,6 +911,668 @@ mod tests {
             }
         }
 
+        mod publishing_events {
+
+            use std::sync::Arc;
+
+            use torrust_tracker_configuration::{MemoryLimits, TorrentEvictionPolicy};
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::announce_handler::tests::the_announce_handler::{peer_ip, sample_peer_1, sample_peer_2};
+            use crate::announce_handler::{AnnounceHandler, PeersWanted};
+            use crate::databases::setup::initialize_database;
+            use crate::event::{Bus, Event};
+            use crate::test_helpers::tests::{complete_peer, incomplete_peer, sample_info_hash, sample_info_hash_two};
+            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+            use crate::torrent::repository::persisted::DatabasePersistentTorrentRepository;
+
+            #[tokio::test]
+            async fn it_should_publish_the_torrent_created_event_only_for_the_first_peer() {
+                let config = configuration::ephemeral_public();
+
+                let database = initialize_database(&config.core);
+                let events = Arc::new(Bus::default());
+                let announce_handler = AnnounceHandler::new(
+                    &config.core,
+                    &Arc::new(InMemoryTorrentRepository::default()),
+                    &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+                    &events,
+                    &Arc::default(),
+                );
+
+                let mut receiver = events.subscribe();
+
+                let info_hash = sample_info_hash();
+
+                for mut peer in [sample_peer_1(), sample_peer_2()] {
+                    announce_handler
+                        .announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                        .unwrap();
+                }
+
+                let created = std::iter::from_fn(|| receiver.try_recv().ok())
+                    .filter(|event| matches!(event, Event::TorrentCreated { .. }))
+                    .collect::<Vec<_>>();
+
+                assert_eq!(created, vec![Event::TorrentCreated { info_hash }]);
+            }
+
+            #[tokio::test]
+            async fn it_should_publish_the_completed_and_seeders_returned_events_when_a_leecher_completes_the_download() {
+                let config = configuration::ephemeral_public();
+
+                let database = initialize_database(&config.core);
+                let events = Arc::new(Bus::default());
+                let announce_handler = AnnounceHandler::new(
+                    &config.core,
+                    &Arc::new(InMemoryTorrentRepository::default()),
+                    &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+                    &events,
+                    &Arc::default(),
+                );
+
+                let info_hash = sample_info_hash();
+
+                let mut leecher = incomplete_peer();
+                announce_handler
+                    .announce(&info_hash, &mut leecher, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                    .unwrap();
+
+                let mut receiver = events.subscribe();
+
+                let mut completed_peer = complete_peer();
+                announce_handler
+                    .announce(&info_hash, &mut completed_peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                    .unwrap();
+
+                assert_eq!(receiver.try_recv().unwrap(), Event::TorrentCompleted { info_hash });
+                assert_eq!(receiver.try_recv().unwrap(), Event::TorrentSeedersReturned { info_hash });
+                assert!(receiver.try_recv().is_err());
+            }
+
+            #[tokio::test]
+            async fn it_should_publish_the_torrent_removed_event_when_a_torrent_is_evicted_to_make_room_for_a_new_one() {
+                let config = configuration::ephemeral_public();
+
+                let limits = MemoryLimits {
+                    max_torrents: Some(1),
+                    torrent_eviction_policy: TorrentEvictionPolicy::EvictLeastRecentlyActive,
+                    ..Default::default()
+                };
+
+                let database = initialize_database(&config.core);
+                let events = Arc::new(Bus::default());
+                let announce_handler = AnnounceHandler::new(
+                    &config.core,
+                    &Arc::new(InMemoryTorrentRepository::new(&limits, &Arc::default())),
+                    &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+                    &events,
+                    &Arc::default(),
+                );
+
+                let evicted_info_hash = sample_info_hash();
+                let new_info_hash = sample_info_hash_two();
+
+                let mut peer = sample_peer_1();
+                announce_handler
+                    .announce(&evicted_info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                    .unwrap();
+
+                let mut receiver = events.subscribe();
+
+                let mut peer = sample_peer_2();
+                announce_handler
+                    .announce(&new_info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                    .unwrap();
+
+                assert_eq!(
+                    receiver.try_recv().unwrap(),
+                    Event::TorrentRemoved {
+                        info_hash: evicted_info_hash
+                    }
+                );
+                assert_eq!(
+                    receiver.try_recv().unwrap(),
+                    Event::TorrentCreated {
+                        info_hash: new_info_hash
+                    }
+                );
+            }
+        }
+
+        mod with_a_geoip_country_policy {
+
+            use std::sync::Arc;
+
+            use torrust_tracker_configuration::GeoIp as GeoIpConfig;
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::announce_handler::tests::the_announce_handler::{peer_ip, sample_peer_1};
+            use crate::announce_handler::{AnnounceHandler, PeersWanted};
+            use crate::databases::setup::initialize_database;
+            use crate::error::AnnounceError;
+            use crate::geoip::GeoIp;
+            use crate::test_helpers::tests::{geoip_configuration, sample_info_hash};
+            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+            use crate::torrent::repository::persisted::DatabasePersistentTorrentRepository;
+
+            fn initialize_announce_handler_with(geoip_config: &GeoIpConfig) -> (AnnounceHandler, Arc<InMemoryTorrentRepository>) {
+                let config = configuration::ephemeral_public();
+
+                let database = initialize_database(&config.core);
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+                let announce_handler = AnnounceHandler::new(
+                    &config.core,
+                    &in_memory_torrent_repository,
+                    &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+                    &Arc::default(),
+                    &Arc::new(GeoIp::load(geoip_config).unwrap()),
+                );
+
+                (announce_handler, in_memory_torrent_repository)
+            }
+
+            #[tokio::test]
+            async fn it_should_reject_a_peer_in_a_denied_country_without_adding_it_to_the_swarm() {
+                // The peer IP `126.0.0.1` is in JP in the test database.
+                let (announce_handler, in_memory_torrent_repository) = initialize_announce_handler_with(&GeoIpConfig {
+                    denied_countries: vec!["JP".to_string()],
+                    ..geoip_configuration()
+                });
+
+                let mut peer = sample_peer_1();
+
+                let result =
+                    announce_handler.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+
+                assert!(matches!(
+                    result,
+                    Err(AnnounceError::CountryNotAllowed { country, .. }) if country == "JP"
+                ));
+                assert!(in_memory_torrent_repository.get(&sample_info_hash()).is_none());
+            }
+
+            #[tokio::test]
+            async fn it_should_accept_a_peer_in_an_allowed_country() {
+                let (announce_handler, _in_memory_torrent_repository) = initialize_announce_handler_with(&GeoIpConfig {
+                    allowed_countries: vec!["JP".to_string()],
+                    ..geoip_configuration()
+                });
+
+                let mut peer = sample_peer_1();
+
+                let result =
+                    announce_handler.announce(&sample_info_hash(), &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+
+                assert!(result.is_ok());
+            }
+        }
+
+        mod with_a_peer_address_policy {
+
+            use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+            use std::sync::Arc;
+
+            use torrust_tracker_configuration::{PeerAddressAction, PeerAddressPolicy};
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::announce_handler::tests::the_announce_handler::{sample_peer_1, sample_peer_2};
+            use crate::announce_handler::{AnnounceHandler, PeersWanted};
+            use crate::databases::setup::initialize_database;
+            use crate::error::AnnounceError;
+            use crate::test_helpers::tests::sample_info_hash;
+            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+            use crate::torrent::repository::persisted::DatabasePersistentTorrentRepository;
+
+            fn initialize_announce_handler_with(policy: PeerAddressPolicy) -> (AnnounceHandler, Arc<InMemoryTorrentRepository>) {
+                let mut config = configuration::ephemeral_public();
+
+                config.core.peer_address_policy = policy;
+
+                let database = initialize_database(&config.core);
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+                let announce_handler = AnnounceHandler::new(
+                    &config.core,
+                    &in_memory_torrent_repository,
+                    &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+                    &Arc::default(),
+                    &Arc::default(),
+                );
+
+                (announce_handler, in_memory_torrent_repository)
+            }
+
+            /// An address in the `TEST-NET-1` documentation range.
+            fn bogon_ip() -> IpAddr {
+                IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))
+            }
+
+            #[tokio::test]
+            async fn it_should_not_return_a_hidden_peer_to_the_other_peers_but_it_should_count_it() {
+                let (announce_handler, _in_memory_torrent_repository) = initialize_announce_handler_with(PeerAddressPolicy {
+                    bogon_peers: PeerAddressAction::Hide,
+                    ..PeerAddressPolicy::default()
+                });
+
+                let mut bogon_peer = sample_peer_1();
+                announce_handler
+                    .announce(
+                        &sample_info_hash(),
+                        &mut bogon_peer,
+                        &bogon_ip(),
+                        &PeersWanted::AsManyAsPossible,
+                    )
+                    .unwrap();
+
+                let mut public_peer = sample_peer_2();
+                let announce_data = announce_handler
+                    .announce(
+                        &sample_info_hash(),
+                        &mut public_peer,
+                        &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 2)),
+                        &PeersWanted::AsManyAsPossible,
+                    )
+                    .unwrap();
+
+                assert_eq!(announce_data.peers, vec![]);
+                assert_eq!(announce_data.stats.complete + announce_data.stats.incomplete, 2);
+            }
+
+            #[tokio::test]
+            async fn it_should_reject_a_peer_matching_a_reject_rule_without_adding_it_to_the_swarm() {
+                let (announce_handler, in_memory_torrent_repository) = initialize_announce_handler_with(PeerAddressPolicy {
+                    bogon_peers: PeerAddressAction::Reject,
+                    ..PeerAddressPolicy::default()
+                });
+
+                let mut peer = sample_peer_1();
+
+                let result =
+                    announce_handler.announce(&sample_info_hash(), &mut peer, &bogon_ip(), &PeersWanted::AsManyAsPossible);
+
+                assert!(matches!(
+                    result,
+                    Err(AnnounceError::PeerAddressNotAllowed { address, .. }) if address == SocketAddr::new(bogon_ip(), 8081)
+                ));
+                assert!(in_memory_torrent_repository.get(&sample_info_hash()).is_none());
+            }
+        }
+
+        mod with_a_limit_of_peers_per_ip {
+
+            use std::net::{IpAddr, Ipv4Addr};
+            use std::sync::Arc;
+
+            use torrust_tracker_configuration::{MemoryLimits, PeersPerIpPolicy};
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::announce_handler::tests::the_announce_handler::{sample_peer_1, sample_peer_2};
+            use crate::announce_handler::{AnnounceHandler, PeersWanted};
+            use crate::databases::setup::initialize_database;
+            use crate::error::AnnounceError;
+            use crate::test_helpers::tests::sample_info_hash;
+            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+            use crate::torrent::repository::persisted::DatabasePersistentTorrentRepository;
+
+            fn initialize_announce_handler_with(policy: PeersPerIpPolicy) -> (AnnounceHandler, Arc<InMemoryTorrentRepository>) {
+                let mut config = configuration::ephemeral_public();
+
+                config.core.memory_limits = MemoryLimits {
+                    max_peers_per_ip: Some(1),
+                    peers_per_ip_policy: policy,
+                    ..MemoryLimits::default()
+                };
+
+                let database = initialize_database(&config.core);
+                let in_memory_torrent_repository =
+                    Arc::new(InMemoryTorrentRepository::new(&config.core.memory_limits, &Arc::default()));
+                let announce_handler = AnnounceHandler::new(
+                    &config.core,
+                    &in_memory_torrent_repository,
+                    &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+                    &Arc::default(),
+                    &Arc::default(),
+                );
+
+                (announce_handler, in_memory_torrent_repository)
+            }
+
+            fn client_ip() -> IpAddr {
+                IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1))
+            }
+
+            #[tokio::test]
+            async fn it_should_reject_a_new_peer_when_its_ip_address_already_has_the_maximum_number_of_peers() {
+                let (announce_handler, in_memory_torrent_repository) = initialize_announce_handler_with(PeersPerIpPolicy::Reject);
+
+                let mut first_peer = sample_peer_1();
+                announce_handler
+                    .announce(
+                        &sample_info_hash(),
+                        &mut first_peer,
+                        &client_ip(),
+                        &PeersWanted::AsManyAsPossible,
+                    )
+                    .unwrap();
+
+                let mut second_peer = sample_peer_2();
+                let result = announce_handler.announce(
+                    &sample_info_hash(),
+                    &mut second_peer,
+                    &client_ip(),
+                    &PeersWanted::AsManyAsPossible,
+                );
+
+                assert!(matches!(
+                    result,
+                    Err(AnnounceError::TooManyPeersForAddress { address, .. }) if address == client_ip()
+                ));
+                assert_eq!(in_memory_torrent_repository.get_torrent_peers(&sample_info_hash()).len(), 1);
+                assert_eq!(in_memory_torrent_repository.get_eviction_metrics().peers_per_ip_exceeded, 1);
+            }
+
+            #[tokio::test]
+            async fn it_should_replace_the_oldest_peer_with_the_same_ip_address_when_the_policy_drops_the_oldest_peer() {
+                let (announce_handler, in_memory_torrent_repository) =
+                    initialize_announce_handler_with(PeersPerIpPolicy::DropOldest);
+
+                let mut first_peer = sample_peer_1();
+                announce_handler
+                    .announce(
+                        &sample_info_hash(),
+                        &mut first_peer,
+                        &client_ip(),
+                        &PeersWanted::AsManyAsPossible,
+                    )
+                    .unwrap();
+
+                let mut second_peer = sample_peer_2();
+                announce_handler
+                    .announce(
+                        &sample_info_hash(),
+                        &mut second_peer,
+                        &client_ip(),
+                        &PeersWanted::AsManyAsPossible,
+                    )
+                    .unwrap();
+
+                let peers = in_memory_torrent_repository.get_torrent_peers(&sample_info_hash());
+
+                assert_eq!(peers.len(), 1);
+                assert_eq!(peers[0].peer_id, second_peer.peer_id);
+                assert_eq!(in_memory_torrent_repository.get_eviction_metrics().peers_per_ip_exceeded, 1);
+            }
+        }
+
+        mod with_an_adaptive_interval {
+
+            use std::net::{IpAddr, Ipv4Addr};
+            use std::sync::Arc;
+
+            use torrust_tracker_configuration::{AdaptiveInterval, AnnouncePolicy, Configuration, MemoryLimits};
+            use torrust_tracker_primitives::core::AnnounceData;
+            use torrust_tracker_primitives::peer::Peer;
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::announce_handler::tests::the_announce_handler::{sample_peer_1, sample_peer_2, sample_peer_3};
+            use crate::announce_handler::{AnnounceHandler, PeersWanted};
+            use crate::databases::setup::initialize_database;
+            use crate::test_helpers::tests::sample_info_hash;
+            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+            use crate::torrent::repository::persisted::DatabasePersistentTorrentRepository;
+
+            fn announce_policy() -> AnnouncePolicy {
+                AnnouncePolicy {
+                    interval: 600,
+                    interval_min: 300,
+                }
+            }
+
+            fn adaptive_interval() -> AdaptiveInterval {
+                AdaptiveInterval {
+                    min_interval: 60,
+                    max_interval: 1800,
+                    small_swarm_peers: 2,
+                    high_announce_rate: 1000,
+                }
+            }
+
+            fn configuration_with(adaptive_interval: Option<AdaptiveInterval>) -> Configuration {
+                let mut config = configuration::ephemeral_public();
+
+                config.core.announce_policy = announce_policy();
+                config.core.adaptive_interval = adaptive_interval;
+
+                config
+            }
+
+            fn initialize_announce_handler(config: &Configuration) -> (AnnounceHandler, Arc<InMemoryTorrentRepository>) {
+                let database = initialize_database(&config.core);
+                let in_memory_torrent_repository =
+                    Arc::new(InMemoryTorrentRepository::new(&config.core.memory_limits, &Arc::default()));
+                let announce_handler = AnnounceHandler::new(
+                    &config.core,
+                    &in_memory_torrent_repository,
+                    &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+                    &Arc::default(),
+                    &Arc::default(),
+                );
+
+                (announce_handler, in_memory_torrent_repository)
+            }
+
+            fn announce(announce_handler: &AnnounceHandler, peer: &Peer) -> AnnounceData {
+                let mut peer = *peer;
+
+                announce_handler
+                    .announce(
+                        &sample_info_hash(),
+                        &mut peer,
+                        &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)),
+                        &PeersWanted::AsManyAsPossible,
+                    )
+                    .unwrap()
+            }
+
+            #[tokio::test]
+            async fn it_should_return_the_configured_announce_policy_when_it_is_disabled() {
+                let (announce_handler, _in_memory_torrent_repository) = initialize_announce_handler(&configuration_with(None));
+
+                let announce_data = announce(&announce_handler, &sample_peer_1());
+
+                assert_eq!(announce_data.policy, announce_policy());
+                assert_eq!(announce_handler.get_announce_interval_metrics().load, None);
+            }
+
+            #[tokio::test]
+            async fn it_should_shorten_the_interval_for_the_peers_announcing_to_small_swarms() {
+                let (announce_handler, _in_memory_torrent_repository) =
+                    initialize_announce_handler(&configuration_with(Some(adaptive_interval())));
+
+                let announce_data = announce(&announce_handler, &sample_peer_1());
+
+                assert_eq!(
+                    announce_data.policy,
+                    AnnouncePolicy {
+                        interval: 60,
+                        interval_min: 60
+                    }
+                );
+
+                announce(&announce_handler, &sample_peer_2());
+
+                let announce_data = announce(&announce_handler, &sample_peer_3());
+
+                assert_eq!(announce_data.policy, announce_policy());
+
+                let intervals = announce_handler.get_announce_interval_metrics().intervals;
+
+                assert_eq!(intervals.count, 3);
+                assert_eq!(intervals.sum, 60 + 330 + 600);
+            }
+
+            #[tokio::test]
+            async fn it_should_lengthen_the_interval_when_the_memory_limits_are_almost_reached() {
+                let mut config = configuration_with(Some(AdaptiveInterval {
+                    small_swarm_peers: 0,
+                    ..adaptive_interval()
+                }));
+
+                config.core.memory_limits = MemoryLimits {
+                    max_peers: Some(4),
+                    ..MemoryLimits::default()
+                };
+
+                let (announce_handler, _in_memory_torrent_repository) = initialize_announce_handler(&config);
+
+                assert_eq!(announce(&announce_handler, &sample_peer_1()).policy, announce_policy());
+
+                announce(&announce_handler, &sample_peer_2());
+
+                // Two of the four peers allowed are in the repository.
+                assert_eq!(announce(&announce_handler, &sample_peer_3()).policy.interval, 1200);
+
+                // Three of the four peers allowed are in the repository.
+                let load = announce_handler.get_announce_interval_metrics().load.unwrap();
+
+                assert!((load - 0.75).abs() < f64::EPSILON);
+            }
+        }
+
+        mod with_an_early_announce_policy {
+
+            use std::net::{IpAddr, Ipv4Addr};
+            use std::sync::Arc;
+            use std::time::Duration;
+
+            use aquatic_udp_protocol::AnnounceEvent;
+            use torrust_tracker_configuration::EarlyAnnouncePolicy;
+            use torrust_tracker_primitives::peer::Peer;
+            use torrust_tracker_test_helpers::configuration;
+
+            use crate::announce_handler::tests::the_announce_handler::sample_peer_1;
+            use crate::announce_handler::{AnnounceHandler, PeersWanted};
+            use crate::databases::setup::initialize_database;
+            use crate::error::AnnounceError;
+            use crate::test_helpers::tests::sample_info_hash;
+            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+            use crate::torrent::repository::persisted::DatabasePersistentTorrentRepository;
+
+            /// The `interval_min` of the announce policy, in seconds.
+            const INTERVAL_MIN: u32 = 120;
+
+            fn initialize_announce_handler_with(
+                policy: EarlyAnnouncePolicy,
+            ) -> (AnnounceHandler, Arc<InMemoryTorrentRepository>) {
+                let mut config = configuration::ephemeral_public();
+
+                config.core.announce_policy.interval_min = INTERVAL_MIN;
+                config.core.early_announce_policy = policy;
+
+                let database = initialize_database(&config.core);
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+                let announce_handler = AnnounceHandler::new(
+                    &config.core,
+                    &in_memory_torrent_repository,
+                    &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+                    &Arc::default(),
+                    &Arc::default(),
+                );
+
+                (announce_handler, in_memory_torrent_repository)
+            }
+
+            fn started_peer() -> Peer {
+                Peer {
+                    event: AnnounceEvent::Started,
+                    ..sample_peer_1()
+                }
+            }
+
+            /// The same peer announcing again after the given number of seconds.
+            fn announcing_again_after(peer: &Peer, seconds: u64) -> Peer {
+                Peer {
+                    event: AnnounceEvent::None,
+                    updated: peer.updated + Duration::from_secs(seconds),
+                    ..*peer
+                }
+            }
+
+            fn announce(announce_handler: &AnnounceHandler, peer: &Peer) -> Result<Vec<Arc<Peer>>, AnnounceError> {
+                let mut peer = *peer;
+
+                announce_handler
+                    .announce(
+                        &sample_info_hash(),
+                        &mut peer,
+                        &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)),
+                        &PeersWanted::AsManyAsPossible,
+                    )
+                    .map(|announce_data| announce_data.peers)
+            }
+
+            #[tokio::test]
+            async fn it_should_process_the_early_announces_by_default() {
+                let (announce_handler, _) = initialize_announce_handler_with(EarlyAnnouncePolicy::default());
+
+                let peer = started_peer();
+                announce(&announce_handler, &peer).unwrap();
+
+                assert!(announce(&announce_handler, &announcing_again_after(&peer, 30)).is_ok());
+                assert_eq!(announce_handler.get_early_announces(), 0);
+            }
+
+            #[tokio::test]
+            async fn it_should_reject_an_announce_sent_before_the_min_interval_without_updating_the_peer() {
+                let (announce_handler, in_memory_torrent_repository) =
+                    initialize_announce_handler_with(EarlyAnnouncePolicy::Reject);
+
+                let peer = started_peer();
+                announce(&announce_handler, &peer).unwrap();
+
+                let result = announce(&announce_handler, &announcing_again_after(&peer, 30));
+
+                assert!(matches!(result, Err(AnnounceError::AnnouncedTooEarly { retry_in: 90, .. })));
+                assert_eq!(
+                    in_memory_torrent_repository
+                        .get_peer(&sample_info_hash(), &peer.peer_id)
+                        .map(|stored| stored.updated),
+                    Some(peer.updated)
+                );
+                assert_eq!(announce_handler.get_early_announces(), 1);
+            }
+
+            #[tokio::test]
+            async fn it_should_process_an_announce_sent_after_the_min_interval() {
+                let (announce_handler, _) = initialize_announce_handler_with(EarlyAnnouncePolicy::Reject);
+
+                let peer = started_peer();
+                announce(&announce_handler, &peer).unwrap();
+
+                assert!(announce(&announce_handler, &announcing_again_after(&peer, u64::from(INTERVAL_MIN))).is_ok());
+                assert_eq!(announce_handler.get_early_announces(), 0);
+            }
+
+            #[tokio::test]
+            async fn it_should_always_process_the_completed_and_stopped_events() {
+                let (announce_handler, _) = initialize_announce_handler_with(EarlyAnnouncePolicy::Reject);
+
+                let peer = started_peer();
+                announce(&announce_handler, &peer).unwrap();
+
+                for event in [AnnounceEvent::Completed, AnnounceEvent::Stopped] {
+                    let early_announce = Peer {
+                        event,
+                        ..announcing_again_after(&peer, 30)
+                    };
+
+                    assert!(announce(&announce_handler, &early_announce).is_ok());
+                }
+
+                assert_eq!(announce_handler.get_early_announces(), 0);
+            }
+        }
+
         mod should_allow_the_client_peers_to_specified_the_number_of_peers_wanted {
 
             use torrust_tracker_configuration::TORRENT_PEERS_LIMIT;
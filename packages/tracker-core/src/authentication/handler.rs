@@ -18,6 +18,7 @@ use super::key::repository::persisted::DatabaseKeyRepository;
 use super::{key, CurrentClock, Key, PeerKey};
 use crate::databases;
 use crate::error::PeerKeyError;
+use crate::event::{Bus, Event};
 
 /// Contains the information needed to add a new tracker key.
 ///
@@ -47,6 +48,9 @@ pub struct KeysHandler {
 
     /// The in-memory repository for caching authentication keys.
     in_memory_key_repository: Arc<InMemoryKeyRepository>,
+
+    /// Bus where the key changes are published.
+    events: Arc<Bus>,
 }
 
 impl KeysHandler {
@@ -57,11 +61,17 @@ impl KeysHandler {
     /// - `db_key_repository`: A shared reference to the database key repository.
     /// - `in_memory_key_repository`: A shared reference to the in-memory key
     ///   repository.
+    /// - `events`: The bus where the key changes are published.
     #[must_use]
-    pub fn new(db_key_repository: &Arc<DatabaseKeyRepository>, in_memory_key_repository: &Arc<InMemoryKeyRepository>) -> Self {
+    pub fn new(
+        db_key_repository: &Arc<DatabaseKeyRepository>,
+        in_memory_key_repository: &Arc<InMemoryKeyRepository>,
+        events: &Arc<Bus>,
+    ) -> Self {
         Self {
             db_key_repository: db_key_repository.clone(),
             in_memory_key_repository: in_memory_key_repository.clone(),
+            events: events.clone(),
         }
     }
 
@@ -186,6 +196,8 @@ impl KeysHandler {
 
         self.in_memory_key_repository.insert(&peer_key).await;
 
+        self.events.publish(Event::KeyAdded);
+
         Ok(peer_key)
     }
 
@@ -233,6 +245,8 @@ impl KeysHandler {
 
         self.in_memory_key_repository.insert(&peer_key).await;
 
+        self.events.publish(Event::KeyAdded);
+
         Ok(peer_key)
     }
 
@@ -253,6 +267,8 @@ impl KeysHandler {
 
         self.remove_in_memory_auth_key(key).await;
 
+        self.events.publish(Event::KeyRemoved);
+
         Ok(())
     }
 
@@ -281,6 +297,8 @@ impl KeysHandler {
 
         self.in_memory_key_repository.reset_with(keys_from_database).await;
 
+        self.events.publish(Event::KeysReloaded);
+
         Ok(())
     }
 }
@@ -311,7 +329,7 @@ mod tests {
             let db_key_repository = Arc::new(DatabaseKeyRepository::new(database));
             let in_memory_key_repository = Arc::new(InMemoryKeyRepository::default());
 
-            KeysHandler::new(&db_key_repository, &in_memory_key_repository)
+            KeysHandler::new(&db_key_repository, &in_memory_key_repository, &Arc::default())
         }
 
         fn instantiate_keys_handler_with_configuration(config: &Configuration) -> KeysHandler {
@@ -321,7 +339,7 @@ mod tests {
             let db_key_repository = Arc::new(DatabaseKeyRepository::new(&database));
             let in_memory_key_repository = Arc::new(InMemoryKeyRepository::default());
 
-            KeysHandler::new(&db_key_repository, &in_memory_key_repository)
+            KeysHandler::new(&db_key_repository, &in_memory_key_repository, &Arc::default())
         }
 
         mod handling_expiring_peer_keys {
@@ -71,6 +71,7 @@ mod tests {
             let keys_handler = Arc::new(KeysHandler::new(
                 &db_key_repository.clone(),
                 &in_memory_key_repository.clone(),
+                &Arc::default(),
             ));
 
             (keys_handler, authentication_service)
@@ -0,0 +1,106 @@
+//! Client authorization.
+use std::panic::Location;
+use std::sync::Arc;
+
+use aquatic_udp_protocol::PeerId;
+use torrust_tracker_primitives::client_metrics::Client;
+use tracing::instrument;
+
+use super::repository::in_memory::InMemoryClientRules;
+use crate::error::ClientPolicyError;
+
+/// Manages the authorization of peers based on the client rules.
+///
+/// Used to determine whether a peer (`peer_id`) is allowed to announce to the
+/// tracker.
+pub struct ClientAuthorization {
+    /// The in-memory list of client rules.
+    in_memory_client_rules: Arc<InMemoryClientRules>,
+}
+
+impl ClientAuthorization {
+    /// Creates a new `ClientAuthorization` instance.
+    ///
+    /// # Arguments
+    /// - `in_memory_client_rules`: The in-memory client rules instance.
+    ///
+    /// # Returns
+    /// A new `ClientAuthorization` instance.
+    #[must_use]
+    pub fn new(in_memory_client_rules: &Arc<InMemoryClientRules>) -> Self {
+        Self {
+            in_memory_client_rules: in_memory_client_rules.clone(),
+        }
+    }
+
+    /// Checks whether a peer is authorized.
+    ///
+    /// Refer to the [`client_policy`](crate::client_policy) module for the
+    /// rules evaluation.
+    ///
+    /// # Errors
+    /// Returns `ClientPolicyError::ClientNotAllowed` if the rules do not allow
+    /// the `peer_id`.
+    #[instrument(skip(self, peer_id), err)]
+    pub async fn authorize(&self, peer_id: &PeerId) -> Result<(), ClientPolicyError> {
+        if self.in_memory_client_rules.allows(peer_id).await {
+            return Ok(());
+        }
+
+        let client = Client::from_peer_id(peer_id);
+
+        Err(ClientPolicyError::ClientNotAllowed {
+            client: match client.version {
+                Some(version) => format!("{} {version}", client.name),
+                None => client.name,
+            },
+            location: Location::caller(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use aquatic_udp_protocol::PeerId;
+
+    use crate::client_policy::authorization::ClientAuthorization;
+    use crate::client_policy::repository::in_memory::InMemoryClientRules;
+    use crate::client_policy::{Action, ClientRule, Matcher, PatternKind};
+    use crate::error::ClientPolicyError;
+
+    #[tokio::test]
+    async fn it_should_authorize_all_the_peers_when_there_are_no_rules() {
+        let client_authorization = ClientAuthorization::new(&Arc::new(InMemoryClientRules::default()));
+
+        let result = client_authorization.authorize(&PeerId(*b"-TR2940-xxxxxxxxxxxx")).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_authorize_a_denied_client_and_name_it_in_the_error() {
+        let in_memory_client_rules = Arc::new(InMemoryClientRules::default());
+
+        in_memory_client_rules
+            .add(
+                ClientRule {
+                    id: 1,
+                    action: Action::Deny,
+                    kind: PatternKind::Prefix,
+                    pattern: "-TR2".to_string(),
+                },
+                Matcher::new(PatternKind::Prefix, "-TR2").unwrap(),
+            )
+            .await;
+
+        let client_authorization = ClientAuthorization::new(&in_memory_client_rules);
+
+        let result = client_authorization.authorize(&PeerId(*b"-TR2940-xxxxxxxxxxxx")).await;
+
+        assert!(
+            matches!(result.unwrap_err(), ClientPolicyError::ClientNotAllowed { client, .. } if client == "Transmission 2.94")
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Client policy manager.
+//!
+//! This module provides the `ClientPolicyManager` struct, which is responsible
+//! for managing the client rules.
+use std::sync::Arc;
+
+use torrust_tracker_located_error::Located;
+
+use super::repository::in_memory::InMemoryClientRules;
+use super::repository::persisted::DatabaseClientRules;
+use super::{ClientRule, InvalidPattern, Matcher, NewClientRule, MAX_PATTERN_LEN};
+use crate::databases;
+use crate::error::ClientPolicyError;
+
+/// Manages the rules that allow or deny clients.
+///
+/// This structure handles both the in-memory and persistent representations of
+/// the rules.
+pub struct ClientPolicyManager {
+    /// The in-memory list of client rules.
+    in_memory_client_rules: Arc<InMemoryClientRules>,
+
+    /// The persisted list of client rules.
+    database_client_rules: Arc<DatabaseClientRules>,
+}
+
+impl ClientPolicyManager {
+    /// Creates a new `ClientPolicyManager` instance.
+    ///
+    /// # Arguments
+    ///
+    /// - `database_client_rules`: Persistent database-backed rules repository.
+    /// - `in_memory_client_rules`: In-memory rules repository for fast runtime
+    ///   access.
+    #[must_use]
+    pub fn new(database_client_rules: Arc<DatabaseClientRules>, in_memory_client_rules: Arc<InMemoryClientRules>) -> Self {
+        Self {
+            in_memory_client_rules,
+            database_client_rules,
+        }
+    }
+
+    /// Adds a rule.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ClientPolicyError` if the pattern is not valid, it's longer
+    /// than [`MAX_PATTERN_LEN`] or the rule can't be persisted.
+    pub async fn add_rule(&self, rule: NewClientRule) -> Result<ClientRule, ClientPolicyError> {
+        if rule.pattern.len() > MAX_PATTERN_LEN {
+            return Err(ClientPolicyError::InvalidPattern {
+                pattern: rule.pattern,
+                source: InvalidPattern::TooLongToStore,
+            });
+        }
+
+        let matcher = Matcher::new(rule.kind, &rule.pattern).map_err(|source| ClientPolicyError::InvalidPattern {
+            pattern: rule.pattern.clone(),
+            source,
+        })?;
+
+        let rule = self
+            .database_client_rules
+            .add(rule)
+            .map_err(|err| ClientPolicyError::DatabaseError {
+                source: Located(err).into(),
+            })?;
+
+        self.in_memory_client_rules.add(rule.clone(), matcher).await;
+
+        Ok(rule)
+    }
+
+    /// Removes a rule.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ClientPolicyError` if the rule does not exist or it can't be
+    /// removed from the database.
+    pub async fn remove_rule(&self, id: u64) -> Result<(), ClientPolicyError> {
+        if !self.in_memory_client_rules.contains(id).await {
+            return Err(ClientPolicyError::RuleNotFound { id });
+        }
+
+        self.database_client_rules
+            .remove(id)
+            .map_err(|err| ClientPolicyError::DatabaseError {
+                source: Located(err).into(),
+            })?;
+
+        self.in_memory_client_rules.remove(id).await;
+
+        Ok(())
+    }
+
+    /// Returns all the rules.
+    pub async fn get_rules(&self) -> Vec<ClientRule> {
+        self.in_memory_client_rules.get_all().await
+    }
+
+    /// Loads the rules from the database into memory.
+    ///
+    /// The rules with a pattern that is not valid anymore are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `database::Error` if the operation fails to load from the
+    /// database.
+    pub async fn load_rules_from_database(&self) -> Result<(), databases::error::Error> {
+        let rules = self.database_client_rules.load_from_database()?;
+
+        self.in_memory_client_rules.clear().await;
+
+        for rule in rules {
+            match Matcher::new(rule.kind, &rule.pattern) {
+                Ok(matcher) => self.in_memory_client_rules.add(rule, matcher).await,
+                Err(err) => tracing::warn!("Skipping the client rule {} with an invalid pattern: {err}", rule.id),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::Arc;
+
+    use aquatic_udp_protocol::PeerId;
+
+    use crate::client_policy::manager::ClientPolicyManager;
+    use crate::client_policy::repository::in_memory::InMemoryClientRules;
+    use crate::client_policy::repository::persisted::DatabaseClientRules;
+    use crate::client_policy::{Action, InvalidPattern, NewClientRule, PatternKind};
+    use crate::databases::setup::initialize_database;
+    use crate::error::ClientPolicyError;
+    use crate::test_helpers::tests::ephemeral_configuration;
+
+    fn initialize_client_policy_manager() -> (ClientPolicyManager, Arc<InMemoryClientRules>, Arc<DatabaseClientRules>) {
+        let database = initialize_database(&ephemeral_configuration());
+        let database_client_rules = Arc::new(DatabaseClientRules::new(database));
+        let in_memory_client_rules = Arc::new(InMemoryClientRules::default());
+
+        let manager = ClientPolicyManager::new(database_client_rules.clone(), in_memory_client_rules.clone());
+
+        (manager, in_memory_client_rules, database_client_rules)
+    }
+
+    fn deny(pattern: &str) -> NewClientRule {
+        NewClientRule {
+            action: Action::Deny,
+            kind: PatternKind::Prefix,
+            pattern: pattern.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_add_a_rule_to_the_database_and_to_memory() {
+        let (manager, in_memory_client_rules, database_client_rules) = initialize_client_policy_manager();
+
+        let rule = manager.add_rule(deny("-TR2")).await.unwrap();
+
+        assert_eq!(database_client_rules.load_from_database().unwrap(), vec![rule.clone()]);
+        assert_eq!(manager.get_rules().await, vec![rule]);
+        assert!(!in_memory_client_rules.allows(&PeerId(*b"-TR2940-xxxxxxxxxxxx")).await);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_add_a_rule_with_an_invalid_pattern() {
+        let (manager, _in_memory_client_rules, database_client_rules) = initialize_client_policy_manager();
+
+        let result = manager.add_rule(deny("")).await;
+
+        assert!(matches!(result.unwrap_err(), ClientPolicyError::InvalidPattern { .. }));
+        assert!(database_client_rules.load_from_database().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_add_a_rule_with_a_pattern_longer_than_the_database_column() {
+        let (manager, _in_memory_client_rules, database_client_rules) = initialize_client_policy_manager();
+
+        let result = manager
+            .add_rule(NewClientRule {
+                action: Action::Deny,
+                kind: PatternKind::Regex,
+                pattern: format!("^-TR{}", "x?".repeat(126)),
+            })
+            .await;
+
+        assert!(matches!(
+            result.unwrap_err(),
+            ClientPolicyError::InvalidPattern {
+                source: InvalidPattern::TooLongToStore,
+                ..
+            }
+        ));
+        assert!(database_client_rules.load_from_database().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_should_remove_a_rule_from_the_database_and_from_memory() {
+        let (manager, _in_memory_client_rules, database_client_rules) = initialize_client_policy_manager();
+
+        let rule = manager.add_rule(deny("-TR2")).await.unwrap();
+
+        manager.remove_rule(rule.id).await.unwrap();
+
+        assert!(database_client_rules.load_from_database().unwrap().is_empty());
+        assert!(manager.get_rules().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_should_fail_removing_a_rule_that_does_not_exist() {
+        let (manager, _in_memory_client_rules, _database_client_rules) = initialize_client_policy_manager();
+
+        let result = manager.remove_rule(1).await;
+
+        assert!(matches!(result.unwrap_err(), ClientPolicyError::RuleNotFound { id: 1 }));
+    }
+
+    #[tokio::test]
+    async fn it_should_load_the_rules_from_the_database() {
+        let (manager, _in_memory_client_rules, database_client_rules) = initialize_client_policy_manager();
+
+        let rule = database_client_rules.add(deny("-TR2")).unwrap();
+
+        manager.load_rules_from_database().await.unwrap();
+
+        assert_eq!(manager.get_rules().await, vec![rule]);
+    }
+}
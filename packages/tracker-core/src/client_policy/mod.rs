@@ -0,0 +1,250 @@
+//! This module contains the logic to allow or deny the client software of the
+//! peers.
+//!
+//! Some clients are broken or cheat, for example, clients that report fake
+//! upload ratios, or ancient versions with known bugs. The client policy keeps
+//! them off the tracker: their announce requests are rejected with a failure
+//! reason that says which client is not allowed.
+//!
+//! The clients are identified by their peer ID. Each [`ClientRule`] allows or
+//! denies the peer IDs that:
+//!
+//! - Start with a prefix, for example, `-TR2` for Transmission 2.x in the
+//!   Azureus-style convention.
+//! - Match a regular expression, for example, `^-qB4[0-2]` for qBittorrent
+//!   4.0 to 4.2. It's matched against the peer ID bytes, so it can also match
+//!   non UTF-8 peer IDs (use `(?-u:\xFF)` to match the byte `0xFF`).
+//!
+//! A peer is denied when:
+//!
+//! - It matches a `deny` rule, or
+//! - There are `allow` rules and it does not match any of them.
+//!
+//! So `deny` rules can be used to block some clients, and `allow` rules to
+//! only accept some clients. With no rules, all the clients are allowed.
+//!
+//! The rules are stored in the database and loaded into memory when the
+//! tracker starts.
+//!
+//! The module is organized into the following submodules:
+//!
+//! - **`authorization`**: Contains the logic to authorize peers based on the
+//!   rules.
+//! - **`manager`**: Provides functions to add and remove rules.
+//! - **`repository`**: Implements persistence for the rules.
+//! - **`setup`**: Initializes the manager.
+pub mod authorization;
+pub mod manager;
+pub mod repository;
+pub mod setup;
+
+use std::fmt;
+use std::str::FromStr;
+
+use aquatic_udp_protocol::PeerId;
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use torrust_tracker_primitives::peer::PEER_ID_BYTES_LEN;
+
+/// Maximum length in bytes of a rule pattern. It's the size of the `pattern`
+/// column in the `MySQL` database.
+pub const MAX_PATTERN_LEN: usize = 255;
+
+/// What to do with the peers that match a rule.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Allow,
+    Deny,
+}
+
+/// How a rule matches the peer IDs.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternKind {
+    /// The peer ID starts with the pattern.
+    Prefix,
+    /// The peer ID matches the pattern regular expression.
+    Regex,
+}
+
+impl Action {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Allow => "allow",
+            Action::Deny => "deny",
+        }
+    }
+}
+
+impl PatternKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PatternKind::Prefix => "prefix",
+            PatternKind::Regex => "regex",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for PatternKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("unknown value: {0}")]
+pub struct ParseRuleError(pub String);
+
+impl FromStr for Action {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Action::Allow),
+            "deny" => Ok(Action::Deny),
+            _ => Err(ParseRuleError(s.to_string())),
+        }
+    }
+}
+
+impl FromStr for PatternKind {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prefix" => Ok(PatternKind::Prefix),
+            "regex" => Ok(PatternKind::Regex),
+            _ => Err(ParseRuleError(s.to_string())),
+        }
+    }
+}
+
+/// A rule that has not been stored yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewClientRule {
+    pub action: Action,
+    pub kind: PatternKind,
+    pub pattern: String,
+}
+
+/// A stored rule.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ClientRule {
+    /// The rule ID assigned by the database.
+    pub id: u64,
+    pub action: Action,
+    pub kind: PatternKind,
+    pub pattern: String,
+}
+
+impl ClientRule {
+    #[must_use]
+    pub fn new(id: u64, rule: NewClientRule) -> Self {
+        Self {
+            id,
+            action: rule.action,
+            kind: rule.kind,
+            pattern: rule.pattern,
+        }
+    }
+}
+
+/// The reason a rule pattern is not valid.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidPattern {
+    #[error("the pattern is empty")]
+    Empty,
+
+    #[error("the prefix is longer than a peer ID")]
+    TooLong,
+
+    #[error("the pattern is longer than {MAX_PATTERN_LEN} bytes")]
+    TooLongToStore,
+
+    #[error("{0}")]
+    Regex(String),
+}
+
+/// The compiled pattern of a rule.
+#[derive(Clone, Debug)]
+pub(crate) enum Matcher {
+    Prefix(Vec<u8>),
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// # Errors
+    ///
+    /// Will return an error if the pattern is empty, the prefix does not fit
+    /// in a peer ID or the regular expression is not valid.
+    pub(crate) fn new(kind: PatternKind, pattern: &str) -> Result<Self, InvalidPattern> {
+        if pattern.is_empty() {
+            return Err(InvalidPattern::Empty);
+        }
+
+        match kind {
+            PatternKind::Prefix if pattern.len() > PEER_ID_BYTES_LEN => Err(InvalidPattern::TooLong),
+            PatternKind::Prefix => Ok(Matcher::Prefix(pattern.as_bytes().to_vec())),
+            PatternKind::Regex => Regex::new(pattern)
+                .map(Matcher::Regex)
+                .map_err(|e| InvalidPattern::Regex(e.to_string())),
+        }
+    }
+
+    pub(crate) fn matches(&self, peer_id: &PeerId) -> bool {
+        match self {
+            Matcher::Prefix(prefix) => peer_id.0.starts_with(prefix),
+            Matcher::Regex(regex) => regex.is_match(&peer_id.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aquatic_udp_protocol::PeerId;
+
+    use super::{InvalidPattern, Matcher, PatternKind};
+
+    #[test]
+    fn a_prefix_should_match_the_peer_ids_starting_with_it() {
+        let matcher = Matcher::new(PatternKind::Prefix, "-TR2").unwrap();
+
+        assert!(matcher.matches(&PeerId(*b"-TR2940-xxxxxxxxxxxx")));
+        assert!(!matcher.matches(&PeerId(*b"-TR4040-xxxxxxxxxxxx")));
+    }
+
+    #[test]
+    fn a_regex_should_match_the_peer_id_bytes() {
+        let matcher = Matcher::new(PatternKind::Regex, r"^-qB4[0-2]").unwrap();
+
+        assert!(matcher.matches(&PeerId(*b"-qB4200-xxxxxxxxxxxx")));
+        assert!(!matcher.matches(&PeerId(*b"-qB4630-xxxxxxxxxxxx")));
+
+        let matcher = Matcher::new(PatternKind::Regex, r"(?-u:\xFF)$").unwrap();
+
+        assert!(matcher.matches(&PeerId(*b"-qB4200-xxxxxxxxxxx\xFF")));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_patterns() {
+        assert_eq!(Matcher::new(PatternKind::Prefix, "").unwrap_err(), InvalidPattern::Empty);
+        assert_eq!(
+            Matcher::new(PatternKind::Prefix, "-TR4040-xxxxxxxxxxxxx").unwrap_err(),
+            InvalidPattern::TooLong
+        );
+        assert!(matches!(
+            Matcher::new(PatternKind::Regex, "^-qB4[").unwrap_err(),
+            InvalidPattern::Regex(_)
+        ));
+    }
+}
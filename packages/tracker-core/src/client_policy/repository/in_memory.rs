@@ -0,0 +1,159 @@
+//! The in-memory list of client rules.
+use aquatic_udp_protocol::PeerId;
+
+use crate::client_policy::{Action, ClientRule, Matcher};
+
+/// A rule with its compiled pattern.
+#[derive(Debug)]
+struct CompiledRule {
+    rule: ClientRule,
+    matcher: Matcher,
+}
+
+/// In-memory list of client rules.
+///
+/// Stores the rules with their compiled patterns, so they can be matched on
+/// every announce request.
+#[derive(Debug, Default)]
+pub struct InMemoryClientRules {
+    /// A thread-safe list of rules sorted by ID.
+    rules: tokio::sync::RwLock<Vec<CompiledRule>>,
+}
+
+impl InMemoryClientRules {
+    /// Adds a rule to the list.
+    pub(crate) async fn add(&self, rule: ClientRule, matcher: Matcher) {
+        let mut rules = self.rules.write().await;
+
+        let position = rules.partition_point(|compiled| compiled.rule.id < rule.id);
+
+        rules.insert(position, CompiledRule { rule, matcher });
+    }
+
+    /// Removes a rule from the list.
+    ///
+    /// # Returns
+    ///
+    /// - `true` if the rule was present and removed.
+    /// - `false` if the rule was not found.
+    pub(crate) async fn remove(&self, id: u64) -> bool {
+        let mut rules = self.rules.write().await;
+
+        let len = rules.len();
+
+        rules.retain(|compiled| compiled.rule.id != id);
+
+        rules.len() < len
+    }
+
+    /// Checks if a rule is in the list.
+    pub async fn contains(&self, id: u64) -> bool {
+        self.rules.read().await.iter().any(|compiled| compiled.rule.id == id)
+    }
+
+    /// Returns all the rules sorted by ID.
+    pub async fn get_all(&self) -> Vec<ClientRule> {
+        self.rules.read().await.iter().map(|compiled| compiled.rule.clone()).collect()
+    }
+
+    /// Checks if the rules allow a peer ID.
+    ///
+    /// It's denied when it matches a `deny` rule, or when there are `allow`
+    /// rules and it does not match any of them.
+    pub async fn allows(&self, peer_id: &PeerId) -> bool {
+        let rules = self.rules.read().await;
+
+        let mut has_allow_rules = false;
+        let mut is_allowed = false;
+
+        for compiled in rules.iter() {
+            match compiled.rule.action {
+                Action::Deny if compiled.matcher.matches(peer_id) => return false,
+                Action::Deny => {}
+                Action::Allow => {
+                    has_allow_rules = true;
+                    is_allowed = is_allowed || compiled.matcher.matches(peer_id);
+                }
+            }
+        }
+
+        !has_allow_rules || is_allowed
+    }
+
+    /// Clears all the rules.
+    pub(crate) async fn clear(&self) {
+        self.rules.write().await.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aquatic_udp_protocol::PeerId;
+
+    use crate::client_policy::repository::in_memory::InMemoryClientRules;
+    use crate::client_policy::{Action, ClientRule, Matcher, PatternKind};
+
+    async fn add_rule(rules: &InMemoryClientRules, id: u64, action: Action, kind: PatternKind, pattern: &str) {
+        let rule = ClientRule {
+            id,
+            action,
+            kind,
+            pattern: pattern.to_string(),
+        };
+
+        rules.add(rule, Matcher::new(kind, pattern).unwrap()).await;
+    }
+
+    const TRANSMISSION_2: PeerId = PeerId(*b"-TR2940-xxxxxxxxxxxx");
+    const TRANSMISSION_4: PeerId = PeerId(*b"-TR4040-xxxxxxxxxxxx");
+    const QBITTORRENT: PeerId = PeerId(*b"-qB4630-xxxxxxxxxxxx");
+
+    #[tokio::test]
+    async fn it_should_allow_all_the_clients_when_there_are_no_rules() {
+        let rules = InMemoryClientRules::default();
+
+        assert!(rules.allows(&TRANSMISSION_2).await);
+    }
+
+    #[tokio::test]
+    async fn it_should_deny_the_clients_matching_a_deny_rule() {
+        let rules = InMemoryClientRules::default();
+
+        add_rule(&rules, 1, Action::Deny, PatternKind::Prefix, "-TR2").await;
+
+        assert!(!rules.allows(&TRANSMISSION_2).await);
+        assert!(rules.allows(&TRANSMISSION_4).await);
+    }
+
+    #[tokio::test]
+    async fn it_should_only_allow_the_clients_matching_an_allow_rule_when_there_are_allow_rules() {
+        let rules = InMemoryClientRules::default();
+
+        add_rule(&rules, 1, Action::Allow, PatternKind::Regex, "^-TR").await;
+
+        assert!(rules.allows(&TRANSMISSION_4).await);
+        assert!(!rules.allows(&QBITTORRENT).await);
+    }
+
+    #[tokio::test]
+    async fn deny_rules_should_take_precedence_over_allow_rules() {
+        let rules = InMemoryClientRules::default();
+
+        add_rule(&rules, 1, Action::Allow, PatternKind::Regex, "^-TR").await;
+        add_rule(&rules, 2, Action::Deny, PatternKind::Prefix, "-TR2").await;
+
+        assert!(!rules.allows(&TRANSMISSION_2).await);
+        assert!(rules.allows(&TRANSMISSION_4).await);
+    }
+
+    #[tokio::test]
+    async fn it_should_remove_a_rule() {
+        let rules = InMemoryClientRules::default();
+
+        add_rule(&rules, 1, Action::Deny, PatternKind::Prefix, "-TR2").await;
+
+        assert!(rules.remove(1).await);
+        assert!(!rules.remove(1).await);
+        assert!(rules.allows(&TRANSMISSION_2).await);
+    }
+}
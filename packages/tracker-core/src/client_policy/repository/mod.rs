@@ -0,0 +1,3 @@
+//! Repository implementations for the client rules.
+pub mod in_memory;
+pub mod persisted;
@@ -0,0 +1,106 @@
+//! The repository that persists the client rules.
+use std::sync::Arc;
+
+use crate::client_policy::{ClientRule, NewClientRule};
+use crate::databases::{self, Database};
+
+/// The persisted list of client rules.
+///
+/// This repository handles adding, removing, and loading the rules from a
+/// persistent database like `SQLite` or `MySQL`.
+pub struct DatabaseClientRules {
+    /// A database driver implementation: [`Sqlite3`](crate::core::databases::sqlite)
+    /// or [`MySQL`](crate::core::databases::mysql)
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DatabaseClientRules {
+    /// Creates a new `DatabaseClientRules`.
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// Adds a rule and returns it with its new ID.
+    ///
+    /// # Errors
+    /// Returns a `database::Error` if unable to add the rule.
+    pub(crate) fn add(&self, rule: NewClientRule) -> Result<ClientRule, databases::error::Error> {
+        let id = self.database.add_client_rule(&rule)?;
+
+        Ok(ClientRule::new(id, rule))
+    }
+
+    /// Removes a rule.
+    ///
+    /// # Errors
+    /// Returns a `database::Error` if unable to remove the rule.
+    pub(crate) fn remove(&self, id: u64) -> Result<(), databases::error::Error> {
+        self.database.remove_client_rule(id)?;
+
+        Ok(())
+    }
+
+    /// Loads all the rules from the database.
+    ///
+    /// # Errors
+    /// Returns a `database::Error` if unable to load the rules.
+    pub(crate) fn load_from_database(&self) -> Result<Vec<ClientRule>, databases::error::Error> {
+        self.database.load_client_rules()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod the_persisted_client_rules_repository {
+
+        use crate::client_policy::repository::persisted::DatabaseClientRules;
+        use crate::client_policy::{Action, NewClientRule, PatternKind};
+        use crate::databases::setup::initialize_database;
+        use crate::test_helpers::tests::ephemeral_configuration;
+
+        fn initialize_database_client_rules() -> DatabaseClientRules {
+            let configuration = ephemeral_configuration();
+            let database = initialize_database(&configuration);
+            DatabaseClientRules::new(database)
+        }
+
+        fn sample_rule() -> NewClientRule {
+            NewClientRule {
+                action: Action::Deny,
+                kind: PatternKind::Prefix,
+                pattern: "-TR2".to_string(),
+            }
+        }
+
+        #[test]
+        fn should_add_a_new_rule() {
+            let rules = initialize_database_client_rules();
+
+            let rule = rules.add(sample_rule()).unwrap();
+
+            assert_eq!(rules.load_from_database().unwrap(), vec![rule]);
+        }
+
+        #[test]
+        fn should_assign_a_different_id_to_each_rule() {
+            let rules = initialize_database_client_rules();
+
+            let first = rules.add(sample_rule()).unwrap();
+            let second = rules.add(sample_rule()).unwrap();
+
+            assert_ne!(first.id, second.id);
+        }
+
+        #[test]
+        fn should_remove_a_rule() {
+            let rules = initialize_database_client_rules();
+
+            let rule = rules.add(sample_rule()).unwrap();
+
+            rules.remove(rule.id).unwrap();
+
+            assert_eq!(rules.load_from_database().unwrap(), vec![]);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Initializes the client policy manager.
+//!
+//! This module provides functions to set up the `ClientPolicyManager`, which is
+//! responsible for managing the client rules in both the in-memory and
+//! persistent database repositories.
+use std::sync::Arc;
+
+use super::manager::ClientPolicyManager;
+use super::repository::in_memory::InMemoryClientRules;
+use super::repository::persisted::DatabaseClientRules;
+use crate::databases::Database;
+
+/// Initializes the `ClientPolicyManager` by combining in-memory and database
+/// repositories.
+///
+/// # Arguments
+///
+/// * `database` - An `Arc<Box<dyn Database>>` representing the database
+///   connection, used for persistent rules storage.
+/// * `in_memory_client_rules` - An `Arc<InMemoryClientRules>` representing the
+///   in-memory rules repository for fast access.
+///
+/// # Returns
+///
+/// An `Arc<ClientPolicyManager>` instance that manages both the in-memory and
+/// database rules repositories.
+#[must_use]
+pub fn initialize_client_policy_manager(
+    database: Arc<Box<dyn Database>>,
+    in_memory_client_rules: Arc<InMemoryClientRules>,
+) -> Arc<ClientPolicyManager> {
+    let database_client_rules = Arc::new(DatabaseClientRules::new(database));
+    Arc::new(ClientPolicyManager::new(database_client_rules, in_memory_client_rules))
+}
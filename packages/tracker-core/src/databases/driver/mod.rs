@@ -98,6 +98,7 @@ pub(crate) mod tests {
         // Persistent torrents (stats)
 
         handling_torrent_persistence::it_should_save_and_load_persistent_torrents(driver);
+        handling_torrent_persistence::it_should_remove_a_persistent_torrent(driver);
 
         // Authentication keys (for private trackers)
 
@@ -117,6 +118,11 @@ pub(crate) mod tests {
         handling_the_whitelist::it_should_add_and_get_infohashes(driver);
         handling_the_whitelist::it_should_remove_an_infohash_from_the_whitelist(driver);
         handling_the_whitelist::it_should_fail_trying_to_add_the_same_infohash_twice(driver);
+
+        // Client rules
+
+        handling_client_rules::it_should_add_and_load_client_rules(driver);
+        handling_client_rules::it_should_remove_a_client_rule(driver);
     }
 
     /// It initializes the database schema.
@@ -164,6 +170,18 @@ pub(crate) mod tests {
             assert_eq!(torrents.len(), 1);
             assert_eq!(torrents.get(&infohash), Some(number_of_downloads).as_ref());
         }
+
+        pub fn it_should_remove_a_persistent_torrent(driver: &Arc<Box<dyn Database>>) {
+            let infohash = sample_info_hash();
+
+            driver.save_persistent_torrent(&infohash, 1).unwrap();
+
+            driver.remove_persistent_torrent(&infohash).unwrap();
+
+            let torrents = driver.load_persistent_torrents().unwrap();
+
+            assert_eq!(torrents.get(&infohash), None);
+        }
     }
 
     mod handling_authentication_keys {
@@ -269,4 +287,40 @@ pub(crate) mod tests {
             assert!(result.is_err());
         }
     }
+
+    mod handling_client_rules {
+
+        use std::sync::Arc;
+
+        use crate::client_policy::{Action, NewClientRule, PatternKind};
+        use crate::databases::Database;
+
+        fn sample_rule() -> NewClientRule {
+            NewClientRule {
+                action: Action::Deny,
+                kind: PatternKind::Prefix,
+                pattern: "-TR2".to_string(),
+            }
+        }
+
+        pub fn it_should_add_and_load_client_rules(driver: &Arc<Box<dyn Database>>) {
+            let id = driver.add_client_rule(&sample_rule()).unwrap();
+
+            let rules = driver.load_client_rules().unwrap();
+
+            let rule = rules.iter().find(|rule| rule.id == id).unwrap();
+
+            assert_eq!(rule.action, Action::Deny);
+            assert_eq!(rule.kind, PatternKind::Prefix);
+            assert_eq!(rule.pattern, "-TR2");
+        }
+
+        pub fn it_should_remove_a_client_rule(driver: &Arc<Box<dyn Database>>) {
+            let id = driver.add_client_rule(&sample_rule()).unwrap();
+
+            driver.remove_client_rule(id).unwrap();
+
+            assert!(!driver.load_client_rules().unwrap().iter().any(|rule| rule.id == id));
+        }
+    }
 }
@@ -3,7 +3,7 @@
 //! This module provides an implementation of the [`Database`] trait for `MySQL`
 //! using the `r2d2_mysql` connection pool. It configures the MySQL connection
 //! based on a URL, creates the necessary tables (for torrent metrics, torrent
-//! whitelist, and authentication keys), and implements all CRUD operations
+//! whitelist, authentication keys and client rules), and implements all CRUD operations
 //! required by the persistence layer.
 use std::str::FromStr;
 use std::time::Duration;
@@ -18,6 +18,7 @@ use torrust_tracker_primitives::PersistentTorrents;
 use super::{Database, Driver, Error};
 use crate::authentication::key::AUTH_KEY_LENGTH;
 use crate::authentication::{self, Key};
+use crate::client_policy::{ClientRule, NewClientRule};
 
 const DRIVER: Driver = Driver::MySQL;
 
@@ -78,6 +79,15 @@ impl Database for Mysql {
             i8::try_from(AUTH_KEY_LENGTH).expect("authentication key length should fit within a i8!")
         );
 
+        let create_client_rules_table = "
+        CREATE TABLE IF NOT EXISTS client_rules (
+            id integer PRIMARY KEY AUTO_INCREMENT,
+            action VARCHAR(5) NOT NULL,
+            kind VARCHAR(6) NOT NULL,
+            pattern VARCHAR(255) NOT NULL
+        );"
+        .to_string();
+
         let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
 
         conn.query_drop(&create_torrents_table)
@@ -85,6 +95,8 @@ impl Database for Mysql {
         conn.query_drop(&create_keys_table).expect("Could not create keys table.");
         conn.query_drop(&create_whitelist_table)
             .expect("Could not create whitelist table.");
+        conn.query_drop(&create_client_rules_table)
+            .expect("Could not create client_rules table.");
 
         Ok(())
     }
@@ -103,6 +115,10 @@ impl Database for Mysql {
             DROP TABLE `keys`;"
             .to_string();
 
+        let drop_client_rules_table = "
+        DROP TABLE `client_rules`;"
+            .to_string();
+
         let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
 
         conn.query_drop(&drop_whitelist_table)
@@ -110,6 +126,8 @@ impl Database for Mysql {
         conn.query_drop(&drop_torrents_table)
             .expect("Could not drop `torrents` table.");
         conn.query_drop(&drop_keys_table).expect("Could not drop `keys` table.");
+        conn.query_drop(&drop_client_rules_table)
+            .expect("Could not drop `client_rules` table.");
 
         Ok(())
     }
@@ -174,6 +192,20 @@ impl Database for Mysql {
         Ok(conn.exec_drop(COMMAND, params! { info_hash_str, completed })?)
     }
 
+    /// Refer to [`databases::Database::remove_persistent_torrent`](crate::core::databases::Database::remove_persistent_torrent).
+    fn remove_persistent_torrent(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        let info_hash_str = info_hash.to_string();
+
+        conn.exec_drop(
+            "DELETE FROM torrents WHERE info_hash = :info_hash_str",
+            params! { info_hash_str },
+        )?;
+
+        Ok(())
+    }
+
     /// Refer to [`databases::Database::get_info_hash_from_whitelist`](crate::core::databases::Database::get_info_hash_from_whitelist).
     fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error> {
         let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
@@ -213,6 +245,48 @@ impl Database for Mysql {
         Ok(1)
     }
 
+    /// Refer to [`databases::Database::load_client_rules`](crate::core::databases::Database::load_client_rules).
+    fn load_client_rules(&self) -> Result<Vec<ClientRule>, Error> {
+        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        let rows = conn.query_map(
+            "SELECT id, action, kind, pattern FROM client_rules ORDER BY id",
+            |(id, action, kind, pattern): (u64, String, String, String)| (id, action, kind, pattern),
+        )?;
+
+        rows.into_iter()
+            .map(|(id, action, kind, pattern)| {
+                Ok(ClientRule {
+                    id,
+                    action: action.parse().map_err(|e| (e, DRIVER))?,
+                    kind: kind.parse().map_err(|e| (e, DRIVER))?,
+                    pattern,
+                })
+            })
+            .collect()
+    }
+
+    /// Refer to [`databases::Database::add_client_rule`](crate::core::databases::Database::add_client_rule).
+    fn add_client_rule(&self, rule: &NewClientRule) -> Result<u64, Error> {
+        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        conn.exec_drop(
+            "INSERT INTO client_rules (action, kind, pattern) VALUES (:action, :kind, :pattern)",
+            params! { "action" => rule.action.as_str(), "kind" => rule.kind.as_str(), "pattern" => &rule.pattern },
+        )?;
+
+        Ok(conn.last_insert_id())
+    }
+
+    /// Refer to [`databases::Database::remove_client_rule`](crate::core::databases::Database::remove_client_rule).
+    fn remove_client_rule(&self, id: u64) -> Result<usize, Error> {
+        let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        conn.exec_drop("DELETE FROM client_rules WHERE id = :id", params! { id })?;
+
+        Ok(1)
+    }
+
     /// Refer to [`databases::Database::get_key_from_keys`](crate::core::databases::Database::get_key_from_keys).
     fn get_key_from_keys(&self, key: &Key) -> Result<Option<authentication::PeerKey>, Error> {
         let mut conn = self.pool.get().map_err(|e| (e, DRIVER))?;
@@ -2,7 +2,7 @@
 //!
 //! This module provides an implementation of the [`Database`] trait for
 //! `SQLite3` using the `r2d2_sqlite` connection pool. It defines the schema for
-//!  whitelist, torrent metrics, and authentication keys, and provides methods
+//!  whitelist, torrent metrics, authentication keys and client rules, and provides methods
 //! to create and drop tables as well as perform CRUD operations on these
 //! persistent objects.
 use std::panic::Location;
@@ -17,6 +17,7 @@ use torrust_tracker_primitives::{DurationSinceUnixEpoch, PersistentTorrents};
 
 use super::{Database, Driver, Error};
 use crate::authentication::{self, Key};
+use crate::client_policy::{ClientRule, NewClientRule};
 
 const DRIVER: Driver = Driver::Sqlite3;
 
@@ -77,11 +78,21 @@ impl Database for Sqlite {
          );"
         .to_string();
 
+        let create_client_rules_table = "
+        CREATE TABLE IF NOT EXISTS client_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            pattern TEXT NOT NULL
+        );"
+        .to_string();
+
         let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
 
         conn.execute(&create_whitelist_table, [])?;
         conn.execute(&create_keys_table, [])?;
         conn.execute(&create_torrents_table, [])?;
+        conn.execute(&create_client_rules_table, [])?;
 
         Ok(())
     }
@@ -100,11 +111,16 @@ impl Database for Sqlite {
         DROP TABLE keys;"
             .to_string();
 
+        let drop_client_rules_table = "
+        DROP TABLE client_rules;"
+            .to_string();
+
         let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
 
         conn.execute(&drop_whitelist_table, [])
             .and_then(|_| conn.execute(&drop_torrents_table, []))
-            .and_then(|_| conn.execute(&drop_keys_table, []))?;
+            .and_then(|_| conn.execute(&drop_keys_table, []))
+            .and_then(|_| conn.execute(&drop_client_rules_table, []))?;
 
         Ok(())
     }
@@ -188,6 +204,15 @@ impl Database for Sqlite {
         }
     }
 
+    /// Refer to [`databases::Database::remove_persistent_torrent`](crate::core::databases::Database::remove_persistent_torrent).
+    fn remove_persistent_torrent(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        conn.execute("DELETE FROM torrents WHERE info_hash = ?", [info_hash.to_string()])?;
+
+        Ok(())
+    }
+
     /// Refer to [`databases::Database::get_info_hash_from_whitelist`](crate::core::databases::Database::get_info_hash_from_whitelist).
     fn get_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<Option<InfoHash>, Error> {
         let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
@@ -235,6 +260,71 @@ impl Database for Sqlite {
         }
     }
 
+    /// Refer to [`databases::Database::load_client_rules`](crate::core::databases::Database::load_client_rules).
+    fn load_client_rules(&self) -> Result<Vec<ClientRule>, Error> {
+        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        let mut stmt = conn.prepare("SELECT id, action, kind, pattern FROM client_rules ORDER BY id")?;
+
+        let rows_iter = stmt.query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let action: String = row.get(1)?;
+            let kind: String = row.get(2)?;
+            let pattern: String = row.get(3)?;
+
+            Ok((id, action, kind, pattern))
+        })?;
+
+        rows_iter
+            .filter_map(std::result::Result::ok)
+            .map(|(id, action, kind, pattern)| {
+                Ok(ClientRule {
+                    id: id.unsigned_abs(),
+                    action: action.parse().map_err(|e| (e, DRIVER))?,
+                    kind: kind.parse().map_err(|e| (e, DRIVER))?,
+                    pattern,
+                })
+            })
+            .collect()
+    }
+
+    /// Refer to [`databases::Database::add_client_rule`](crate::core::databases::Database::add_client_rule).
+    fn add_client_rule(&self, rule: &NewClientRule) -> Result<u64, Error> {
+        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        let insert = conn.execute(
+            "INSERT INTO client_rules (action, kind, pattern) VALUES (?1, ?2, ?3)",
+            [rule.action.as_str(), rule.kind.as_str(), &rule.pattern],
+        )?;
+
+        if insert == 0 {
+            Err(Error::InsertFailed {
+                location: Location::caller(),
+                driver: DRIVER,
+            })
+        } else {
+            Ok(conn.last_insert_rowid().unsigned_abs())
+        }
+    }
+
+    /// Refer to [`databases::Database::remove_client_rule`](crate::core::databases::Database::remove_client_rule).
+    fn remove_client_rule(&self, id: u64) -> Result<usize, Error> {
+        let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
+
+        let deleted = conn.execute("DELETE FROM client_rules WHERE id = ?", [id.to_string()])?;
+
+        if deleted == 1 {
+            // should only remove a single record.
+            Ok(deleted)
+        } else {
+            Err(Error::DeleteFailed {
+                location: Location::caller(),
+                error_code: deleted,
+                driver: DRIVER,
+            })
+        }
+    }
+
     /// Refer to [`databases::Database::get_key_from_keys`](crate::core::databases::Database::get_key_from_keys).
     fn get_key_from_keys(&self, key: &Key) -> Result<Option<authentication::PeerKey>, Error> {
         let conn = self.pool.get().map_err(|e| (e, DRIVER))?;
@@ -316,6 +406,7 @@ mod tests {
 
     use crate::databases::driver::sqlite::Sqlite;
     use crate::databases::driver::tests::run_tests;
+    use crate::databases::error::Error;
     use crate::databases::Database;
 
     fn ephemeral_configuration() -> Core {
@@ -340,4 +431,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn it_should_fail_loading_a_client_rule_with_an_unknown_action() {
+        let config = ephemeral_configuration();
+
+        let sqlite = Sqlite::new(&config.database.path).unwrap();
+        sqlite.create_database_tables().unwrap();
+
+        sqlite
+            .pool
+            .get()
+            .unwrap()
+            .execute(
+                "INSERT INTO client_rules (action, kind, pattern) VALUES ('block', 'prefix', '-TR2')",
+                [],
+            )
+            .unwrap();
+
+        assert!(matches!(sqlite.load_client_rules(), Err(Error::InvalidRecord { .. })));
+    }
 }
@@ -15,6 +15,7 @@ use r2d2_mysql::mysql::UrlError;
 use torrust_tracker_located_error::{DynError, Located, LocatedError};
 
 use super::driver::Driver;
+use crate::client_policy::ParseRuleError;
 
 /// Database error type that encapsulates various failures encountered during
 /// database operations.
@@ -40,6 +41,16 @@ pub enum Error {
         driver: Driver,
     },
 
+    /// Indicates that a stored record holds an unexpected value.
+    ///
+    /// This error variant is used when a column can't be converted into the
+    /// domain type, for example, an unknown client rule action.
+    #[error("The {driver} database holds an invalid record: {source}")]
+    InvalidRecord {
+        source: LocatedError<'static, dyn std::error::Error + Send + Sync>,
+        driver: Driver,
+    },
+
     /// Indicates a failure to insert a record into the database.
     ///
     /// This error is raised when an insertion operation fails.
@@ -116,6 +127,17 @@ impl From<UrlError> for Error {
     }
 }
 
+impl From<(ParseRuleError, Driver)> for Error {
+    #[track_caller]
+    fn from(e: (ParseRuleError, Driver)) -> Self {
+        let (err, driver) = e;
+        Self::InvalidRecord {
+            source: (Arc::new(err) as DynError).into(),
+            driver,
+        }
+    }
+}
+
 impl From<(r2d2::Error, Driver)> for Error {
     #[track_caller]
     fn from(e: (r2d2::Error, Driver)) -> Self {
@@ -131,6 +153,8 @@ impl From<(r2d2::Error, Driver)> for Error {
 mod tests {
     use r2d2_mysql::mysql;
 
+    use crate::client_policy::ParseRuleError;
+    use crate::databases::driver::Driver;
     use crate::databases::error::Error;
 
     #[test]
@@ -155,6 +179,13 @@ mod tests {
         assert!(matches!(err, Error::InvalidQuery { .. }));
     }
 
+    #[test]
+    fn it_should_build_a_database_error_from_a_client_rule_parse_error() {
+        let err: Error = (ParseRuleError("block".to_string()), Driver::Sqlite3).into();
+
+        assert!(matches!(err, Error::InvalidRecord { .. }));
+    }
+
     #[test]
     fn it_should_build_a_database_error_from_a_mysql_url_error() {
         let err: Error = mysql::error::UrlError::BadUrl.into();
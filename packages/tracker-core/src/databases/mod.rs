@@ -18,6 +18,7 @@
 //! - **Torrent whitelist**: A list of torrents (by infohash) that are allowed.
 //! - **Authentication keys**: Expiring authentication keys used to secure
 //!   access to private trackers.
+//! - **Client rules**: Rules that allow or deny peers by their peer ID.
 //!
 //! # Torrent Metrics
 //!
@@ -46,6 +47,15 @@
 //! | `valid_until` | 1672419840                         | Timestamp indicating expiration time |
 //!
 //! > **NOTICE**: All authentication keys must have an expiration date.
+//!
+//! # Client Rules
+//!
+//! | Field     | Sample data | Description                                  |
+//! |-----------|-------------|----------------------------------------------|
+//! | `id`      | 1           | Auto-increment id                            |
+//! | `action`  | `deny`      | `allow` or `deny` the matching peers         |
+//! | `kind`    | `prefix`    | `prefix` or `regex`                          |
+//! | `pattern` | `-TR2`      | The peer ID prefix or regular expression     |
 pub mod driver;
 pub mod error;
 pub mod setup;
@@ -56,12 +66,13 @@ use torrust_tracker_primitives::PersistentTorrents;
 
 use self::error::Error;
 use crate::authentication::{self, Key};
+use crate::client_policy::{ClientRule, NewClientRule};
 
 /// The persistence trait.
 ///
 /// This trait defines all the methods required to interact with the database,
 /// including creating and dropping schema tables, and CRUD operations for
-/// torrent metrics, whitelists, and authentication keys. Implementations of
+/// torrent metrics, whitelists, authentication keys and client rules. Implementations of
 /// this trait must ensure that operations are safe, consistent, and report
 /// errors using the [`Error`] type.
 #[automock]
@@ -117,6 +128,21 @@ pub trait Database: Sync + Send {
     /// Returns an [`Error`] if the metrics cannot be saved.
     fn save_persistent_torrent(&self, info_hash: &InfoHash, downloaded: u32) -> Result<(), Error>;
 
+    /// Removes the torrent metrics data from the database.
+    ///
+    /// It does nothing if there is no data for the torrent.
+    ///
+    /// # Arguments
+    ///
+    /// * `info_hash` - A reference to the torrent's info hash.
+    ///
+    /// # Context: Torrent Metrics
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the metrics cannot be removed.
+    fn remove_persistent_torrent(&self, info_hash: &InfoHash) -> Result<(), Error>;
+
     // Whitelist
 
     /// Loads the whitelisted torrents from the database.
@@ -172,6 +198,37 @@ pub trait Database: Sync + Send {
     /// Returns an [`Error`] if the torrent cannot be removed from the whitelist.
     fn remove_info_hash_from_whitelist(&self, info_hash: InfoHash) -> Result<usize, Error>;
 
+    // Client rules
+
+    /// Loads all the client rules from the database.
+    ///
+    /// # Context: Client Rules
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the rules cannot be loaded.
+    fn load_client_rules(&self) -> Result<Vec<ClientRule>, Error>;
+
+    /// Adds a client rule to the database.
+    ///
+    /// It returns the ID assigned to the new rule.
+    ///
+    /// # Context: Client Rules
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the rule cannot be saved.
+    fn add_client_rule(&self, rule: &NewClientRule) -> Result<u64, Error>;
+
+    /// Removes a client rule from the database.
+    ///
+    /// # Context: Client Rules
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the rule cannot be removed.
+    fn remove_client_rule(&self, id: u64) -> Result<usize, Error>;
+
     // Authentication keys
 
     /// Loads all authentication keys from the database.
@@ -7,12 +7,14 @@
 //! peer key data, and database persistence failures. Each error variant
 //! includes contextual information (such as source code location) to facilitate
 //!  debugging.
+use std::net::{IpAddr, SocketAddr};
 use std::panic::Location;
 
 use bittorrent_primitives::info_hash::InfoHash;
 use torrust_tracker_located_error::LocatedError;
 
 use super::authentication::key::ParseKeyError;
+use super::client_policy::InvalidPattern;
 use super::databases;
 
 /// Errors related to torrent whitelisting.
@@ -29,6 +31,45 @@ pub enum WhitelistError {
     },
 }
 
+/// Errors returned when an announce request is rejected.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum AnnounceError {
+    /// Indicates that the country of the peer is not allowed by the `GeoIP`
+    /// configuration. The country is `unknown` if the peer address is not in
+    /// the `GeoIP` database.
+    #[error("The country: {country}, is not allowed, {location}")]
+    CountryNotAllowed {
+        country: String,
+        location: &'static Location<'static>,
+    },
+
+    /// Indicates that the peer address is rejected by the peer address
+    /// policy, for example, because it's a bogon address.
+    #[error("The peer address: {address}, is not allowed ({reason}), {location}")]
+    PeerAddressNotAllowed {
+        address: SocketAddr,
+        reason: &'static str,
+        location: &'static Location<'static>,
+    },
+
+    /// Indicates that the IP address of the peer already has the maximum
+    /// number of peers allowed in the torrent swarm.
+    #[error("The IP address: {address}, has too many peers in the torrent, {location}")]
+    TooManyPeersForAddress {
+        address: IpAddr,
+        location: &'static Location<'static>,
+    },
+
+    /// Indicates that the peer announced again before the `interval_min` of
+    /// the announce policy. `retry_in` is the number of seconds the peer
+    /// has to wait before announcing again.
+    #[error("The peer announced too early, retry in {retry_in} seconds, {location}")]
+    AnnouncedTooEarly {
+        retry_in: u64,
+        location: &'static Location<'static>,
+    },
+}
+
 /// Errors related to peer key operations.
 ///
 /// This error type covers issues encountered during the handling of peer keys,
@@ -56,6 +97,35 @@ pub enum PeerKeyError {
     },
 }
 
+/// Errors related to the client policy.
+///
+/// This error type covers the peers whose client is not allowed, and the
+/// issues encountered when managing the client rules.
+#[allow(clippy::module_name_repetitions)]
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum ClientPolicyError {
+    /// Indicates that the client of the peer is not allowed by the rules.
+    #[error("The client: {client}, is not allowed, {location}")]
+    ClientNotAllowed {
+        client: String,
+        location: &'static Location<'static>,
+    },
+
+    /// Returned when the pattern of a new rule is not valid.
+    #[error("Invalid pattern: {pattern}, {source}")]
+    InvalidPattern { pattern: String, source: InvalidPattern },
+
+    /// Returned when removing a rule that does not exist.
+    #[error("Client rule not found: {id}")]
+    RuleNotFound { id: u64 },
+
+    /// Returned when persisting the rule to the database fails.
+    #[error("Can't persist client rule: {source}")]
+    DatabaseError {
+        source: LocatedError<'static, databases::error::Error>,
+    },
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -80,6 +150,72 @@ mod tests {
         }
     }
 
+    mod announce_error {
+
+        use crate::error::AnnounceError;
+
+        #[test]
+        fn country_not_allowed() {
+            let err = AnnounceError::CountryNotAllowed {
+                country: "SE".to_string(),
+                location: std::panic::Location::caller(),
+            };
+
+            let err_msg = format!("{err}");
+
+            assert!(
+                err_msg.contains("The country: SE, is not allowed"),
+                "Error message did not contain expected text: {err_msg}"
+            );
+        }
+
+        #[test]
+        fn peer_address_not_allowed() {
+            let err = AnnounceError::PeerAddressNotAllowed {
+                address: "10.0.0.1:6881".parse().unwrap(),
+                reason: "private address",
+                location: std::panic::Location::caller(),
+            };
+
+            let err_msg = format!("{err}");
+
+            assert!(
+                err_msg.contains("The peer address: 10.0.0.1:6881, is not allowed (private address)"),
+                "Error message did not contain expected text: {err_msg}"
+            );
+        }
+
+        #[test]
+        fn too_many_peers_for_address() {
+            let err = AnnounceError::TooManyPeersForAddress {
+                address: "126.0.0.1".parse().unwrap(),
+                location: std::panic::Location::caller(),
+            };
+
+            let err_msg = format!("{err}");
+
+            assert!(
+                err_msg.contains("The IP address: 126.0.0.1, has too many peers in the torrent"),
+                "Error message did not contain expected text: {err_msg}"
+            );
+        }
+
+        #[test]
+        fn announced_too_early() {
+            let err = AnnounceError::AnnouncedTooEarly {
+                retry_in: 60,
+                location: std::panic::Location::caller(),
+            };
+
+            let err_msg = format!("{err}");
+
+            assert!(
+                err_msg.contains("The peer announced too early, retry in 60 seconds"),
+                "Error message did not contain expected text: {err_msg}"
+            );
+        }
+    }
+
     mod peer_key_error {
         use torrust_tracker_located_error::Located;
 
@@ -137,4 +273,62 @@ mod tests {
             );
         }
     }
+
+    mod client_policy_error {
+        use torrust_tracker_located_error::Located;
+
+        use crate::client_policy::InvalidPattern;
+        use crate::databases;
+        use crate::databases::driver::Driver;
+        use crate::error::ClientPolicyError;
+
+        #[test]
+        fn client_not_allowed() {
+            let err = ClientPolicyError::ClientNotAllowed {
+                client: "Transmission 2.94".to_string(),
+                location: std::panic::Location::caller(),
+            };
+
+            let err_msg = format!("{err}");
+
+            assert!(
+                err_msg.contains("The client: Transmission 2.94, is not allowed"),
+                "Error message did not contain expected text: {err_msg}"
+            );
+        }
+
+        #[test]
+        fn invalid_pattern() {
+            let err = ClientPolicyError::InvalidPattern {
+                pattern: String::new(),
+                source: InvalidPattern::Empty,
+            };
+
+            let err_msg = format!("{err}");
+
+            assert!(
+                err_msg.contains("Invalid pattern: , the pattern is empty"),
+                "Error message did not contain expected text: {err_msg}"
+            );
+        }
+
+        #[test]
+        fn persisting_into_database() {
+            let err = databases::error::Error::InsertFailed {
+                location: std::panic::Location::caller(),
+                driver: Driver::Sqlite3,
+            };
+
+            let err = ClientPolicyError::DatabaseError {
+                source: Located(err).into(),
+            };
+
+            let err_msg = format!("{err}");
+
+            assert!(
+                err_msg.contains("Can't persist client rule"),
+                "Error message did not contain expected text: {err_msg}"
+            );
+        }
+    }
 }
@@ -0,0 +1,257 @@
+//! Live events of the tracker activity.
+//!
+//! The tracker components publish an [`Event`] to the [`Bus`] when they
+//! change the tracker state, for example, when a torrent is removed from the
+//! whitelist. Any number of subscribers, like the API event stream, can
+//! receive them.
+//!
+//! The bus is a broadcast channel with a bounded capacity. Publishing never
+//! blocks: subscribers that fall behind miss the oldest events. When there are
+//! no subscribers the events are discarded.
+//!
+//! Authentication keys are secrets, so the key events do not include the
+//! keys.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::broadcast;
+
+/// Default number of events a subscriber can fall behind before it starts
+/// missing events.
+pub const DEFAULT_BUS_CAPACITY: usize = 1024;
+
+/// A change in the tracker state.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// The changes of the tracker metrics since the previous `metrics` event.
+    /// Only the metrics that have changed are included.
+    Metrics {
+        deltas: BTreeMap<String, i64>,
+    },
+    /// A peer announced a torrent the tracker was not tracking.
+    TorrentCreated {
+        info_hash: InfoHash,
+    },
+    /// A peer announced the `completed` event: it finished downloading the
+    /// torrent.
+    TorrentCompleted {
+        info_hash: InfoHash,
+    },
+    /// The torrent has a seeder after having none. It's published every time
+    /// the seeders return, not only when the first seeder appears: a swarm
+    /// that loses all its seeders and regains them is notified again.
+    TorrentSeedersReturned {
+        info_hash: InfoHash,
+    },
+    /// The torrent is not tracked anymore. It was left without peers, it was
+    /// deleted or it was evicted to make room for a new torrent.
+    TorrentRemoved {
+        info_hash: InfoHash,
+    },
+    TorrentWhitelisted {
+        info_hash: InfoHash,
+    },
+    TorrentRemovedFromWhitelist {
+        info_hash: InfoHash,
+    },
+    WhitelistReloaded,
+    KeyAdded,
+    KeyRemoved,
+    KeysReloaded,
+    /// The IP has been banned for sending too many requests with invalid
+    /// connection IDs.
+    IpBanned {
+        ip: IpAddr,
+    },
+}
+
+impl Event {
+    #[must_use]
+    pub fn event_type(&self) -> EventType {
+        match self {
+            Event::Metrics { .. } => EventType::Metrics,
+            Event::TorrentCreated { .. } => EventType::TorrentCreated,
+            Event::TorrentCompleted { .. } => EventType::TorrentCompleted,
+            Event::TorrentSeedersReturned { .. } => EventType::TorrentSeedersReturned,
+            Event::TorrentRemoved { .. } => EventType::TorrentRemoved,
+            Event::TorrentWhitelisted { .. } => EventType::TorrentWhitelisted,
+            Event::TorrentRemovedFromWhitelist { .. } => EventType::TorrentRemovedFromWhitelist,
+            Event::WhitelistReloaded => EventType::WhitelistReloaded,
+            Event::KeyAdded => EventType::KeyAdded,
+            Event::KeyRemoved => EventType::KeyRemoved,
+            Event::KeysReloaded => EventType::KeysReloaded,
+            Event::IpBanned { .. } => EventType::IpBanned,
+        }
+    }
+}
+
+/// The type of an [`Event`]. It's used to filter the events.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EventType {
+    Metrics,
+    TorrentCreated,
+    TorrentCompleted,
+    TorrentSeedersReturned,
+    TorrentRemoved,
+    TorrentWhitelisted,
+    TorrentRemovedFromWhitelist,
+    WhitelistReloaded,
+    KeyAdded,
+    KeyRemoved,
+    KeysReloaded,
+    IpBanned,
+}
+
+impl EventType {
+    pub const ALL: [EventType; 12] = [
+        EventType::Metrics,
+        EventType::TorrentCreated,
+        EventType::TorrentCompleted,
+        EventType::TorrentSeedersReturned,
+        EventType::TorrentRemoved,
+        EventType::TorrentWhitelisted,
+        EventType::TorrentRemovedFromWhitelist,
+        EventType::WhitelistReloaded,
+        EventType::KeyAdded,
+        EventType::KeyRemoved,
+        EventType::KeysReloaded,
+        EventType::IpBanned,
+    ];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventType::Metrics => "metrics",
+            EventType::TorrentCreated => "torrent_created",
+            EventType::TorrentCompleted => "torrent_completed",
+            EventType::TorrentSeedersReturned => "torrent_seeders_returned",
+            EventType::TorrentRemoved => "torrent_removed",
+            EventType::TorrentWhitelisted => "torrent_whitelisted",
+            EventType::TorrentRemovedFromWhitelist => "torrent_removed_from_whitelist",
+            EventType::WhitelistReloaded => "whitelist_reloaded",
+            EventType::KeyAdded => "key_added",
+            EventType::KeyRemoved => "key_removed",
+            EventType::KeysReloaded => "keys_reloaded",
+            EventType::IpBanned => "ip_banned",
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("unknown event type: {0}")]
+pub struct UnknownEventType(pub String);
+
+impl FromStr for EventType {
+    type Err = UnknownEventType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EventType::ALL
+            .into_iter()
+            .find(|event_type| event_type.as_str() == s)
+            .ok_or_else(|| UnknownEventType(s.to_string()))
+    }
+}
+
+/// Broadcast channel for the tracker [`Event`]s.
+#[derive(Debug)]
+pub struct Bus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUS_CAPACITY)
+    }
+}
+
+impl Bus {
+    /// # Panics
+    ///
+    /// Will panic if the `capacity` is zero.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _receiver) = broadcast::channel(capacity);
+
+        Self { sender }
+    }
+
+    /// It sends the event to all the current subscribers.
+    pub fn publish(&self, event: Event) {
+        // It only fails when there are no subscribers.
+        if self.sender.send(event).is_err() {
+            tracing::trace!("no subscribers for the tracker event");
+        }
+    }
+
+    /// It returns a receiver for the events published from now on.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+
+    /// Whether someone is listening. Publishers can use it to avoid building
+    /// events nobody will receive.
+    #[must_use]
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bittorrent_primitives::info_hash::InfoHash;
+
+    use super::{Bus, Event, EventType};
+
+    #[test]
+    fn it_should_parse_all_the_event_types() {
+        for event_type in EventType::ALL {
+            assert_eq!(EventType::from_str(event_type.as_str()), Ok(event_type));
+        }
+
+        assert!(EventType::from_str("unknown").is_err());
+    }
+
+    #[test]
+    fn it_should_serialize_the_event_type_in_the_event() {
+        let event = Event::TorrentCreated {
+            info_hash: InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(), // DevSkim: ignore DS173237
+        };
+
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"type":"torrent_created","info_hash":"9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_send_the_published_events_to_the_subscribers() {
+        let bus = Bus::default();
+
+        assert!(!bus.has_subscribers());
+
+        bus.publish(Event::KeyAdded);
+
+        let mut receiver = bus.subscribe();
+
+        assert!(bus.has_subscribers());
+
+        bus.publish(Event::KeysReloaded);
+
+        assert_eq!(receiver.recv().await.unwrap(), Event::KeysReloaded);
+    }
+}
@@ -0,0 +1,398 @@
+//! `GeoIP` enrichment of the peers.
+//!
+//! The peers are located with a local `MaxMind` DB (`MMDB`) file: the
+//! `GeoLite2` and `GeoIP2` databases, or the ones from other providers using
+//! the same format. A [`Location`] contains the country and the autonomous
+//! system (ASN) of the peer address, when they are in the database.
+//!
+//! The locations are used to:
+//!
+//! - Restrict the announce requests by country. The [`AnnounceHandler`](crate::announce_handler::AnnounceHandler)
+//!   locates the peer address of each announce request and rejects the peers
+//!   in a denied country, or not in an allowed country if there is an allow
+//!   list.
+//! - Count the active peers by country and ASN. The peers are located when
+//!   they join or leave a swarm, and when they announce from a new address,
+//!   so the [`LocationsMetrics`] of all the torrents are kept up to date
+//!   without going through all the peers.
+//!
+//! The lookups are done in memory, the database file is only read when the
+//! tracker starts.
+//!
+//! The [`GeoIp`] service is disabled when the `[core.geoip]` section is not
+//! in the configuration. In that case, the peers are not located and all the
+//! countries are allowed.
+use std::collections::{BTreeMap, HashSet};
+use std::net::IpAddr;
+use std::ops::AddAssign;
+use std::panic::Location as CodeLocation;
+
+use dashmap::DashMap;
+use maxminddb::{MaxMindDBError, Reader};
+use serde::Deserialize;
+use thiserror::Error;
+use torrust_tracker_configuration::GeoIp as GeoIpConfig;
+
+use crate::error::AnnounceError;
+
+/// The maximum number of addresses whose ASN is cached. The cache is cleared
+/// when it's full.
+const MAX_CACHED_ASNS: usize = 65_536;
+
+/// Error returned when a `GeoIP` database can't be loaded.
+#[derive(Error, Debug)]
+#[error("Can't load the GeoIP database: {path}, {source}")]
+pub struct LoadError {
+    pub path: String,
+    pub source: MaxMindDBError,
+}
+
+/// The location of a peer address.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Location {
+    /// The ISO 3166-1 alpha-2 code of the country, for example, `SE`.
+    pub country: Option<String>,
+    /// The autonomous system.
+    pub asn: Option<Asn>,
+}
+
+/// An autonomous system.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Asn {
+    /// The autonomous system number, for example, `29518`.
+    pub number: u32,
+    /// The organization that owns the autonomous system, for example,
+    /// `Bredband2 AB`.
+    pub organization: Option<String>,
+}
+
+/// The fields of a database record used to locate the peers. The `GeoIP2`
+/// country and ASN databases use the same names.
+#[derive(Deserialize)]
+struct Record<'a> {
+    #[serde(borrow)]
+    country: Option<CountryRecord<'a>>,
+    autonomous_system_number: Option<u32>,
+    autonomous_system_organization: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct CountryRecord<'a> {
+    iso_code: Option<&'a str>,
+}
+
+/// Locates the peers and applies the country allow and deny lists.
+#[derive(Default)]
+pub struct GeoIp {
+    /// The databases, in the order they are looked up.
+    databases: Vec<Reader<Vec<u8>>>,
+
+    /// The countries whose peers are accepted. All if it's empty.
+    allowed_countries: HashSet<String>,
+
+    /// The countries whose peers are rejected.
+    denied_countries: HashSet<String>,
+
+    /// The ASNs of the recently looked up addresses.
+    asns: DashMap<IpAddr, Option<u32>>,
+}
+
+impl GeoIp {
+    /// Loads the databases in the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a database file can't be read or it's not a
+    /// valid `MMDB` file.
+    pub fn load(config: &GeoIpConfig) -> Result<Self, LoadError> {
+        let mut databases = vec![];
+
+        for path in std::iter::once(&config.database_path).chain(&config.asn_database_path) {
+            let database = Reader::open_readfile(path).map_err(|source| LoadError {
+                path: path.to_string(),
+                source,
+            })?;
+
+            databases.push(database);
+        }
+
+        Ok(Self {
+            databases,
+            allowed_countries: config.allowed_countries.iter().cloned().collect(),
+            denied_countries: config.denied_countries.iter().cloned().collect(),
+            asns: DashMap::default(),
+        })
+    }
+
+    /// Returns `true` if there is a database to locate the peers.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        !self.databases.is_empty()
+    }
+
+    /// Looks up the country and the ASN of an address.
+    ///
+    /// The fields that are not in the first database are looked up in the
+    /// next one.
+    #[must_use]
+    pub fn locate(&self, ip: &IpAddr) -> Location {
+        let mut location = Location::default();
+
+        for database in &self.databases {
+            // The addresses that are not in the database are not located.
+            let Ok(record) = database.lookup::<Record<'_>>(*ip) else {
+                continue;
+            };
+
+            if location.country.is_none() {
+                location.country = record.country.and_then(|country| country.iso_code).map(ToString::to_string);
+            }
+
+            if location.asn.is_none() {
+                location.asn = record.autonomous_system_number.map(|number| Asn {
+                    number,
+                    organization: record.autonomous_system_organization.map(ToString::to_string),
+                });
+            }
+        }
+
+        location
+    }
+
+    /// Looks up the autonomous system number of an address.
+    ///
+    /// The numbers are cached, because the peer selection looks up the same
+    /// peer addresses on many announce requests.
+    #[must_use]
+    pub fn asn(&self, ip: &IpAddr) -> Option<u32> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        if let Some(asn) = self.asns.get(ip) {
+            return *asn;
+        }
+
+        let asn = self.locate(ip).asn.map(|asn| asn.number);
+
+        if self.asns.len() >= MAX_CACHED_ASNS {
+            self.asns.clear();
+        }
+
+        self.asns.insert(*ip, asn);
+
+        asn
+    }
+
+    /// Checks that the country of a peer address is allowed.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`AnnounceError::CountryNotAllowed`] error if the country
+    /// is denied, or there is an allow list and it does not contain the
+    /// country. The addresses with an unknown country are only rejected when
+    /// there is an allow list.
+    #[track_caller]
+    pub fn authorize(&self, ip: &IpAddr) -> Result<(), AnnounceError> {
+        if self.allowed_countries.is_empty() && self.denied_countries.is_empty() {
+            return Ok(());
+        }
+
+        let country = self.locate(ip).country;
+
+        let is_allowed = match &country {
+            Some(country) => {
+                !self.denied_countries.contains(country)
+                    && (self.allowed_countries.is_empty() || self.allowed_countries.contains(country))
+            }
+            None => self.allowed_countries.is_empty(),
+        };
+
+        if is_allowed {
+            Ok(())
+        } else {
+            Err(AnnounceError::CountryNotAllowed {
+                country: country.unwrap_or_else(|| "unknown".to_string()),
+                location: CodeLocation::caller(),
+            })
+        }
+    }
+}
+
+/// Number of active peers by country and ASN.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct LocationsMetrics {
+    /// Peers by country. The key is `None` for the peers whose country is
+    /// unknown.
+    pub countries: BTreeMap<Option<String>, u64>,
+    /// Peers by ASN. The key is `None` for the peers whose ASN is unknown.
+    pub asns: BTreeMap<Option<Asn>, u64>,
+}
+
+impl LocationsMetrics {
+    /// It counts a peer in the given location.
+    pub fn count(&mut self, location: Location) {
+        *self.countries.entry(location.country).or_default() += 1;
+        *self.asns.entry(location.asn).or_default() += 1;
+    }
+
+    /// It returns the countries sorted by the number of peers, the most
+    /// common first.
+    #[must_use]
+    pub fn top_countries(&self) -> Vec<(Option<&String>, u64)> {
+        top(&self.countries)
+            .into_iter()
+            .map(|(country, peers)| (country.as_ref(), peers))
+            .collect()
+    }
+
+    /// It returns the ASNs sorted by the number of peers, the most common
+    /// first.
+    #[must_use]
+    pub fn top_asns(&self) -> Vec<(Option<&Asn>, u64)> {
+        top(&self.asns)
+            .into_iter()
+            .map(|(asn, peers)| (asn.as_ref(), peers))
+            .collect()
+    }
+}
+
+fn top<K: Ord>(peers_by_key: &BTreeMap<K, u64>) -> Vec<(&K, u64)> {
+    let mut keys: Vec<(&K, u64)> = peers_by_key.iter().map(|(key, peers)| (key, *peers)).collect();
+
+    keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    keys
+}
+
+impl AddAssign for LocationsMetrics {
+    fn add_assign(&mut self, rhs: Self) {
+        for (country, peers) in rhs.countries {
+            *self.countries.entry(country).or_default() += peers;
+        }
+
+        for (asn, peers) in rhs.asns {
+            *self.asns.entry(asn).or_default() += peers;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use torrust_tracker_configuration::GeoIp as GeoIpConfig;
+
+    use super::{Asn, GeoIp, Location, LocationsMetrics};
+    use crate::error::AnnounceError;
+    use crate::test_helpers::tests::geoip_configuration;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    fn bredband2() -> Asn {
+        Asn {
+            number: 29518,
+            organization: Some("Bredband2 AB".to_string()),
+        }
+    }
+
+    #[test]
+    fn it_should_locate_the_country_and_the_asn_of_an_address() {
+        let geoip = GeoIp::load(&geoip_configuration()).unwrap();
+
+        assert_eq!(
+            geoip.locate(&ip("89.160.20.112")),
+            Location {
+                country: Some("SE".to_string()),
+                asn: Some(bredband2())
+            }
+        );
+
+        assert_eq!(geoip.locate(&ip("2a02:c7c::1")).country, Some("GB".to_string()));
+    }
+
+    #[test]
+    fn it_should_not_locate_the_addresses_that_are_not_in_the_database() {
+        let geoip = GeoIp::load(&geoip_configuration()).unwrap();
+
+        assert_eq!(geoip.locate(&ip("10.0.0.1")), Location::default());
+    }
+
+    #[test]
+    fn it_should_cache_the_asn_of_an_address() {
+        let geoip = GeoIp::load(&geoip_configuration()).unwrap();
+
+        assert_eq!(geoip.asn(&ip("89.160.20.112")), Some(29518));
+        assert_eq!(geoip.asn(&ip("10.0.0.1")), None);
+
+        assert_eq!(geoip.asns.len(), 2);
+        assert_eq!(geoip.asn(&ip("89.160.20.112")), Some(29518));
+    }
+
+    #[test]
+    fn it_should_fail_loading_a_database_that_does_not_exist() {
+        let config = GeoIpConfig {
+            database_path: "missing.mmdb".into(),
+            ..GeoIpConfig::default()
+        };
+
+        assert!(GeoIp::load(&config).is_err());
+    }
+
+    #[test]
+    fn it_should_allow_all_the_countries_when_it_is_disabled() {
+        let geoip = GeoIp::default();
+
+        assert!(!geoip.is_enabled());
+        assert_eq!(geoip.locate(&ip("89.160.20.112")), Location::default());
+        assert!(geoip.authorize(&ip("89.160.20.112")).is_ok());
+    }
+
+    #[test]
+    fn it_should_reject_the_peers_in_a_denied_country() {
+        let geoip = GeoIp::load(&GeoIpConfig {
+            denied_countries: vec!["SE".to_string()],
+            ..geoip_configuration()
+        })
+        .unwrap();
+
+        assert!(matches!(
+            geoip.authorize(&ip("89.160.20.112")),
+            Err(AnnounceError::CountryNotAllowed { country, .. }) if country == "SE"
+        ));
+        assert!(geoip.authorize(&ip("81.2.69.142")).is_ok());
+        assert!(geoip.authorize(&ip("10.0.0.1")).is_ok());
+    }
+
+    #[test]
+    fn it_should_only_accept_the_peers_in_an_allowed_country() {
+        let geoip = GeoIp::load(&GeoIpConfig {
+            allowed_countries: vec!["GB".to_string()],
+            ..geoip_configuration()
+        })
+        .unwrap();
+
+        assert!(geoip.authorize(&ip("81.2.69.142")).is_ok());
+        assert!(geoip.authorize(&ip("89.160.20.112")).is_err());
+        assert!(matches!(
+            geoip.authorize(&ip("10.0.0.1")),
+            Err(AnnounceError::CountryNotAllowed { country, .. }) if country == "unknown"
+        ));
+    }
+
+    #[test]
+    fn it_should_count_the_peers_by_country_and_asn_the_most_common_first() {
+        let geoip = GeoIp::load(&geoip_configuration()).unwrap();
+
+        let mut metrics = LocationsMetrics::default();
+
+        metrics.count(geoip.locate(&ip("89.160.20.112")));
+        metrics.count(geoip.locate(&ip("89.160.20.113")));
+        metrics.count(geoip.locate(&ip("10.0.0.1")));
+
+        assert_eq!(metrics.top_countries(), vec![(Some(&"SE".to_string()), 2), (None, 1)]);
+        assert_eq!(metrics.top_asns(), vec![(Some(&bredband2()), 2), (None, 1)]);
+    }
+}
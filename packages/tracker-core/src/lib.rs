@@ -23,6 +23,7 @@
 //! - [Introduction](#introduction)
 //! - [Configuration](#configuration)
 //! - [Announce handler](#announce-handler)
+//! - [Adaptive interval](#adaptive-interval)
 //! - [Scrape handler](#scrape-handler)
 //! - [Authentication](#authentication)
 //! - [Databases](#databases)
@@ -89,6 +90,13 @@
 //!
 //! Please refer to the [`announce_handler`] documentation.
 //!
+//! # Adaptive interval
+//!
+//! The `Adaptive interval` module is responsible for computing the announce
+//! interval of each response from the tracker load and the swarm size.
+//!
+//! Please refer to the [`adaptive_interval`] documentation.
+//!
 //! # Scrape handler
 //!
 //! The `ScrapeHandler` is responsible for handling scrape requests.
@@ -101,12 +109,33 @@
 //!
 //! Please refer to the [`authentication`] documentation.
 //!
+//! # Client policy
+//!
+//! The `Client policy` module is responsible for allowing or denying the
+//! client software of the peers, based on their peer IDs.
+//!
+//! Please refer to the [`client_policy`] documentation.
+//!
 //! # Databases
 //!
 //! The `Databases` module is responsible for handling persistence of data into a database.
 //!
 //! Please refer to the [`databases`] documentation.
 //!
+//! # `GeoIP`
+//!
+//! The `GeoIP` module is responsible for locating the peers, and restricting
+//! the announce requests by country.
+//!
+//! Please refer to the [`geoip`] documentation.
+//!
+//! # Peer address policy
+//!
+//! The `Peer address policy` module is responsible for hiding, or rejecting,
+//! the peers with bogon or private addresses, or with unusual ports.
+//!
+//! Please refer to the [`peer_address_policy`] documentation.
+//!
 //! # Torrent
 //!
 //! The `Torrent` module is responsible for handling the torrent data.
@@ -118,10 +147,15 @@
 //! The `Whitelist` module is responsible for handling the whitelist.
 //!
 //! Please refer to the [`whitelist`] documentation.
+pub mod adaptive_interval;
 pub mod announce_handler;
 pub mod authentication;
+pub mod client_policy;
 pub mod databases;
 pub mod error;
+pub mod event;
+pub mod geoip;
+pub mod peer_address_policy;
 pub mod scrape_handler;
 pub mod torrent;
 pub mod whitelist;
@@ -191,21 +225,25 @@ mod tests {
 
                     // Announce a "complete" peer for the torrent
                     let mut complete_peer = complete_peer();
-                    announce_handler.announce(
-                        &info_hash,
-                        &mut complete_peer,
-                        &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 10)),
-                        &PeersWanted::AsManyAsPossible,
-                    );
+                    announce_handler
+                        .announce(
+                            &info_hash,
+                            &mut complete_peer,
+                            &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 10)),
+                            &PeersWanted::AsManyAsPossible,
+                        )
+                        .unwrap();
 
                     // Announce an "incomplete" peer for the torrent
                     let mut incomplete_peer = incomplete_peer();
-                    announce_handler.announce(
-                        &info_hash,
-                        &mut incomplete_peer,
-                        &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 11)),
-                        &PeersWanted::AsManyAsPossible,
-                    );
+                    announce_handler
+                        .announce(
+                            &info_hash,
+                            &mut incomplete_peer,
+                            &IpAddr::V4(Ipv4Addr::new(126, 0, 0, 11)),
+                            &PeersWanted::AsManyAsPossible,
+                        )
+                        .unwrap();
 
                     // Scrape
                     let scrape_data = scrape_handler.scrape(&vec![info_hash]).await;
@@ -245,11 +283,15 @@ mod tests {
                     let info_hash = "3b245504cf5f11bbdbe1201cea6a6bf45aee1bc0".parse::<InfoHash>().unwrap(); // DevSkim: ignore DS173237
 
                     let mut peer = incomplete_peer();
-                    announce_handler.announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+                    announce_handler
+                        .announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                        .unwrap();
 
                     // Announce twice to force non zeroed swarm metadata
                     let mut peer = complete_peer();
-                    announce_handler.announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible);
+                    announce_handler
+                        .announce(&info_hash, &mut peer, &peer_ip(), &PeersWanted::AsManyAsPossible)
+                        .unwrap();
 
                     let scrape_data = scrape_handler.scrape(&vec![info_hash]).await;
 
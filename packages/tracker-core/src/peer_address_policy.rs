@@ -0,0 +1,328 @@
+//! Sanity policy for the peer addresses.
+//!
+//! The tracker stores the address the announce request comes from, or the
+//! `external_ip` when the client is on the loopback interface. Clients behind
+//! broken proxies, or on private networks, can still end up with addresses
+//! that are useless, or even harmful, to share with the rest of the swarm.
+//!
+//! The [`PeerAddressPolicy`] configuration decides what to do with those
+//! peers:
+//!
+//! - [`Keep`](PeerAddressAction::Keep): share them like any other peer.
+//! - [`Hide`](PeerAddressAction::Hide): keep them in the swarm, so they count
+//!   in the swarm statistics and get the list of peers, but never return them
+//!   to the other peers.
+//! - [`Reject`](PeerAddressAction::Reject): reject the announce request.
+//!
+//! The private addresses can also be shared only with the peers in the same
+//! private range ([`SameNetwork`](PrivatePeerAddressAction::SameNetwork)). The
+//! loopback range counts as a private range, so the peers running on the
+//! tracker host still see each other.
+//!
+//! The [`AnnounceHandler`](crate::announce_handler::AnnounceHandler) applies
+//! the policy with [`authorize`] before adding the peer to the swarm, and with
+//! [`is_shared_with`] when it builds the list of peers for the response.
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::panic::Location;
+
+use torrust_tracker_configuration::{PeerAddressAction, PeerAddressPolicy, PrivatePeerAddressAction};
+
+use crate::error::AnnounceError;
+
+/// The ports below this one are privileged ports.
+const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+
+/// The kind of network an address belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressScope {
+    /// A public address, reachable from the Internet.
+    Public,
+    /// An address in one of the private ranges.
+    Private(PrivateNetwork),
+    /// An address that is not routable on the Internet.
+    Bogon,
+}
+
+/// The private ranges. Two peers are in the same network when their addresses
+/// are in the same range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivateNetwork {
+    /// `127.0.0.0/8` and `::1`.
+    Loopback,
+    /// `10.0.0.0/8`.
+    Rfc1918A,
+    /// `172.16.0.0/12`.
+    Rfc1918B,
+    /// `192.168.0.0/16`.
+    Rfc1918C,
+    /// `100.64.0.0/10`, the carrier-grade NAT range.
+    SharedAddressSpace,
+    /// `fc00::/7`.
+    UniqueLocal,
+}
+
+impl AddressScope {
+    /// Returns the scope of an address. The IPv4-mapped IPv6 addresses have
+    /// the scope of the IPv4 address.
+    #[must_use]
+    pub fn of(ip: &IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => Self::of_ipv4(ip),
+            IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+                Some(ip) => Self::of_ipv4(&ip),
+                None => Self::of_ipv6(ip),
+            },
+        }
+    }
+
+    fn of_ipv4(ip: &Ipv4Addr) -> Self {
+        let [a, b, c, _] = ip.octets();
+
+        if ip.is_loopback() {
+            return Self::Private(PrivateNetwork::Loopback);
+        }
+
+        match (a, b) {
+            (10, _) => return Self::Private(PrivateNetwork::Rfc1918A),
+            (172, 16..=31) => return Self::Private(PrivateNetwork::Rfc1918B),
+            (192, 168) => return Self::Private(PrivateNetwork::Rfc1918C),
+            (100, 64..=127) => return Self::Private(PrivateNetwork::SharedAddressSpace),
+            _ => {}
+        }
+
+        let is_bogon = a == 0 // "This network"
+            || ip.is_link_local()
+            || ip.is_documentation()
+            || (a, b, c) == (192, 0, 0) // IETF protocol assignments
+            || (a == 198 && (b == 18 || b == 19)) // Benchmarking
+            || ip.is_multicast()
+            || a >= 240; // Reserved, including the broadcast address
+
+        if is_bogon {
+            Self::Bogon
+        } else {
+            Self::Public
+        }
+    }
+
+    fn of_ipv6(ip: &Ipv6Addr) -> Self {
+        let segments = ip.segments();
+
+        if ip.is_loopback() {
+            return Self::Private(PrivateNetwork::Loopback);
+        }
+
+        if segments[0] & 0xfe00 == 0xfc00 {
+            return Self::Private(PrivateNetwork::UniqueLocal);
+        }
+
+        let is_bogon = ip.is_unspecified()
+            || segments[0] & 0xffc0 == 0xfe80 // Link-local
+            || (segments[0], segments[1]) == (0x2001, 0x0db8) // Documentation
+            || segments[..4] == [0x0100, 0, 0, 0] // Discard-only
+            || ip.is_multicast();
+
+        if is_bogon {
+            Self::Bogon
+        } else {
+            Self::Public
+        }
+    }
+}
+
+/// Checks that the peer address is not rejected by the policy.
+///
+/// # Errors
+///
+/// Will return an [`AnnounceError::PeerAddressNotAllowed`] error if a rule
+/// with the [`Reject`](PeerAddressAction::Reject) action matches the address.
+#[track_caller]
+pub fn authorize(policy: &PeerAddressPolicy, peer_addr: &SocketAddr) -> Result<(), AnnounceError> {
+    let reason = match AddressScope::of(&peer_addr.ip()) {
+        AddressScope::Bogon if policy.bogon_peers == PeerAddressAction::Reject => Some("bogon address"),
+        AddressScope::Private(_) if policy.private_peers == PrivatePeerAddressAction::Reject => Some("private address"),
+        _ => None,
+    }
+    .or_else(|| match port_action(policy, peer_addr.port()) {
+        Some((PeerAddressAction::Reject, reason)) => Some(reason),
+        _ => None,
+    });
+
+    match reason {
+        Some(reason) => Err(AnnounceError::PeerAddressNotAllowed {
+            address: *peer_addr,
+            reason,
+            location: Location::caller(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Returns `true` if a peer with the `peer_addr` can be returned to the
+/// client making the request from the `client_ip`.
+///
+/// The `client_ip` is the address the request comes from, not the one stored
+/// for the client, so a client on the tracker host still gets the loopback
+/// peers when its address is replaced with the `external_ip`.
+#[must_use]
+pub fn is_shared_with(policy: &PeerAddressPolicy, peer_addr: &SocketAddr, client_ip: &IpAddr) -> bool {
+    let is_address_shared = match AddressScope::of(&peer_addr.ip()) {
+        AddressScope::Public => true,
+        AddressScope::Bogon => policy.bogon_peers == PeerAddressAction::Keep,
+        AddressScope::Private(network) => match policy.private_peers {
+            PrivatePeerAddressAction::Keep => true,
+            PrivatePeerAddressAction::SameNetwork => AddressScope::of(client_ip) == AddressScope::Private(network),
+            PrivatePeerAddressAction::Hide | PrivatePeerAddressAction::Reject => false,
+        },
+    };
+
+    let is_port_shared = match port_action(policy, peer_addr.port()) {
+        Some((action, _)) => action == PeerAddressAction::Keep,
+        None => true,
+    };
+
+    is_address_shared && is_port_shared
+}
+
+/// Returns the action for the port, and the reason to reject it, if a port
+/// rule matches it.
+fn port_action(policy: &PeerAddressPolicy, port: u16) -> Option<(PeerAddressAction, &'static str)> {
+    if port == 0 {
+        Some((policy.port_zero_peers, "port 0"))
+    } else if port < FIRST_UNPRIVILEGED_PORT {
+        Some((policy.privileged_port_peers, "privileged port"))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, SocketAddr};
+
+    use torrust_tracker_configuration::{PeerAddressAction, PeerAddressPolicy, PrivatePeerAddressAction};
+
+    use super::{authorize, is_shared_with, AddressScope, PrivateNetwork};
+    use crate::error::AnnounceError;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    fn addr(addr: &str) -> SocketAddr {
+        addr.parse().unwrap()
+    }
+
+    #[test]
+    fn it_should_classify_the_public_addresses() {
+        for public in ["126.0.0.1", "89.160.20.112", "2a02:c7c::1", "::ffff:126.0.0.1"] {
+            assert_eq!(AddressScope::of(&ip(public)), AddressScope::Public, "{public}");
+        }
+    }
+
+    #[test]
+    fn it_should_classify_the_private_addresses_by_network() {
+        let cases = [
+            ("127.0.0.1", PrivateNetwork::Loopback),
+            ("::1", PrivateNetwork::Loopback),
+            ("10.1.2.3", PrivateNetwork::Rfc1918A),
+            ("172.31.0.1", PrivateNetwork::Rfc1918B),
+            ("192.168.1.10", PrivateNetwork::Rfc1918C),
+            ("::ffff:192.168.1.10", PrivateNetwork::Rfc1918C),
+            ("100.64.0.1", PrivateNetwork::SharedAddressSpace),
+            ("fd00::1", PrivateNetwork::UniqueLocal),
+        ];
+
+        for (private, network) in cases {
+            assert_eq!(AddressScope::of(&ip(private)), AddressScope::Private(network), "{private}");
+        }
+    }
+
+    #[test]
+    fn it_should_classify_the_bogon_addresses() {
+        for bogon in [
+            "0.0.0.0",
+            "169.254.0.1",
+            "192.0.2.1",
+            "198.18.0.1",
+            "203.0.113.7",
+            "224.0.0.1",
+            "255.255.255.255",
+            "::",
+            "fe80::1",
+            "2001:db8::1",
+            "ff02::1",
+        ] {
+            assert_eq!(AddressScope::of(&ip(bogon)), AddressScope::Bogon, "{bogon}");
+        }
+    }
+
+    #[test]
+    fn it_should_hide_the_bogon_peers_and_the_peers_announcing_the_port_0_by_default() {
+        let policy = PeerAddressPolicy::default();
+        let client = ip("126.0.0.1");
+
+        assert!(authorize(&policy, &addr("192.0.2.1:6881")).is_ok());
+        assert!(!is_shared_with(&policy, &addr("192.0.2.1:6881"), &client));
+        assert!(!is_shared_with(&policy, &addr("126.0.0.2:0"), &client));
+        assert!(is_shared_with(&policy, &addr("126.0.0.2:80"), &client));
+        assert!(is_shared_with(&policy, &addr("126.0.0.2:6881"), &client));
+    }
+
+    #[test]
+    fn it_should_only_share_the_private_peers_with_the_clients_in_the_same_network_by_default() {
+        let policy = PeerAddressPolicy::default();
+        let peer = addr("192.168.1.10:6881");
+
+        assert!(is_shared_with(&policy, &peer, &ip("192.168.2.20")));
+        assert!(!is_shared_with(&policy, &peer, &ip("10.0.0.1")));
+        assert!(!is_shared_with(&policy, &peer, &ip("126.0.0.1")));
+
+        // The private clients still get the public peers
+        assert!(is_shared_with(&policy, &addr("126.0.0.1:6881"), &ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn it_should_reject_the_peers_matching_a_reject_rule() {
+        let policy = PeerAddressPolicy {
+            bogon_peers: PeerAddressAction::Reject,
+            private_peers: PrivatePeerAddressAction::Reject,
+            port_zero_peers: PeerAddressAction::Reject,
+            privileged_port_peers: PeerAddressAction::Reject,
+        };
+
+        let cases = [
+            ("169.254.0.1:6881", "bogon address"),
+            ("10.0.0.1:6881", "private address"),
+            ("126.0.0.1:0", "port 0"),
+            ("126.0.0.1:443", "privileged port"),
+        ];
+
+        for (peer, expected_reason) in cases {
+            assert!(
+                matches!(
+                    authorize(&policy, &addr(peer)),
+                    Err(AnnounceError::PeerAddressNotAllowed { reason, .. }) if reason == expected_reason
+                ),
+                "{peer}"
+            );
+        }
+
+        assert!(authorize(&policy, &addr("126.0.0.1:6881")).is_ok());
+    }
+
+    #[test]
+    fn it_should_share_all_the_peers_when_every_rule_keeps_them() {
+        let policy = PeerAddressPolicy {
+            bogon_peers: PeerAddressAction::Keep,
+            private_peers: PrivatePeerAddressAction::Keep,
+            port_zero_peers: PeerAddressAction::Keep,
+            privileged_port_peers: PeerAddressAction::Keep,
+        };
+        let client = ip("126.0.0.1");
+
+        for peer in ["0.0.0.0:6881", "10.0.0.1:6881", "126.0.0.2:0", "126.0.0.2:80"] {
+            assert!(is_shared_with(&policy, &addr(peer), &client), "{peer}");
+        }
+    }
+}
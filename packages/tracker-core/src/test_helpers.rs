@@ -10,7 +10,7 @@ pub(crate) mod tests {
     use rand::Rng;
     use torrust_tracker_configuration::Configuration;
     #[cfg(test)]
-    use torrust_tracker_configuration::Core;
+    use torrust_tracker_configuration::{Core, GeoIp};
     use torrust_tracker_primitives::peer::Peer;
     use torrust_tracker_primitives::DurationSinceUnixEpoch;
     #[cfg(test)]
@@ -179,6 +179,8 @@ pub(crate) mod tests {
             &config.core,
             &in_memory_torrent_repository,
             &db_torrent_repository,
+            &Arc::default(),
+            &Arc::default(),
         ));
 
         let scrape_handler = Arc::new(ScrapeHandler::new(&whitelist_authorization, &in_memory_torrent_repository));
@@ -216,4 +218,22 @@ pub(crate) mod tests {
 
         config
     }
+
+    /// The configuration for the test `GeoIP` database. It contains:
+    ///
+    /// - `81.2.69.0/24`: GB, ASN `20712`.
+    /// - `89.160.20.0/24`: SE, ASN `29518`.
+    /// - `126.0.0.0/8`: JP, ASN `17676`.
+    /// - `2001:218::/32`: JP, ASN `2914`.
+    /// - `2a02:c7c::/32`: GB, ASN `5607`.
+    ///
+    /// The database is generated by the `tests/geoip_fixture.rs` test.
+    #[cfg(test)]
+    #[must_use]
+    pub fn geoip_configuration() -> GeoIp {
+        GeoIp {
+            database_path: concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/geoip-test.mmdb").into(),
+            ..GeoIp::default()
+        }
+    }
 }
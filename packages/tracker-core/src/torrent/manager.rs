@@ -1,12 +1,17 @@
 //! Torrents manager.
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
+use aquatic_udp_protocol::PeerId;
+use bittorrent_primitives::info_hash::InfoHash;
 use torrust_tracker_clock::clock::Time;
-use torrust_tracker_configuration::Core;
+use torrust_tracker_configuration::{Core, TrackerPolicy};
+use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch};
+use torrust_tracker_torrent_repository::expiry::{ExpiredPeers, EXPIRY_BATCH_SIZE};
 
 use super::repository::in_memory::InMemoryTorrentRepository;
 use super::repository::persisted::DatabasePersistentTorrentRepository;
+use crate::event::{Bus, Event};
 use crate::{databases, CurrentClock};
 
 /// The `TorrentsManager` is responsible for managing torrent entries by
@@ -23,14 +28,19 @@ use crate::{databases, CurrentClock};
 ///   seeders count) across tracker restarts.
 pub struct TorrentsManager {
     /// The tracker configuration.
-    config: Core,
+    ///
+    /// Only the tracker policy can change while the tracker is running (see
+    /// [`reload`](Self::reload)).
+    config: RwLock<Core>,
 
     /// The in-memory torrents repository.
     in_memory_torrent_repository: Arc<InMemoryTorrentRepository>,
 
     /// The persistent torrents repository.
-    #[allow(dead_code)]
     db_torrent_repository: Arc<DatabasePersistentTorrentRepository>,
+
+    /// Bus where the removed torrents are published.
+    events: Arc<Bus>,
 }
 
 impl TorrentsManager {
@@ -43,6 +53,7 @@ impl TorrentsManager {
     ///   repository of torrents.
     /// * `db_torrent_repository` - A shared reference to the persistent
     ///   repository for torrent metrics.
+    /// * `events` - The bus where the removed torrents are published.
     ///
     /// # Returns
     ///
@@ -52,14 +63,31 @@ impl TorrentsManager {
         config: &Core,
         in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>,
         db_torrent_repository: &Arc<DatabasePersistentTorrentRepository>,
+        events: &Arc<Bus>,
     ) -> Self {
         Self {
-            config: config.clone(),
+            config: RwLock::new(config.clone()),
             in_memory_torrent_repository: in_memory_torrent_repository.clone(),
             db_torrent_repository: db_torrent_repository.clone(),
+            events: events.clone(),
         }
     }
 
+    /// Applies the settings that can be changed without restarting the
+    /// tracker: the tracker policy.
+    ///
+    /// The new `max_peer_timeout` is used from the next cleanup on.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the configuration lock is poisoned.
+    pub fn reload(&self, config: &Core) {
+        self.config
+            .write()
+            .expect("it should lock the torrents manager configuration")
+            .tracker_policy = config.tracker_policy.clone();
+    }
+
     /// Loads torrents from the persistent database into the in-memory repository.
     ///
     /// This function retrieves the list of persistent torrent entries (which
@@ -92,15 +120,123 @@ impl TorrentsManager {
     ///    (`remove_peerless_torrents` is set), it removes entire torrent
     ///    entries that have no active peers.
     pub fn cleanup_torrents(&self) {
-        let current_cutoff = CurrentClock::now_sub(&Duration::from_secs(u64::from(self.config.tracker_policy.max_peer_timeout)))
-            .unwrap_or_default();
+        let tracker_policy = self.tracker_policy();
+
+        self.in_memory_torrent_repository
+            .remove_inactive_peers(Self::cutoff(&tracker_policy));
+
+        if tracker_policy.remove_peerless_torrents {
+            let removed = self.in_memory_torrent_repository.remove_peerless_torrents(&tracker_policy);
+            self.publish_removed(&removed);
+        }
+    }
+
+    /// Removes the next batch of inactive peers without walking all the
+    /// torrents.
+    ///
+    /// It's the incremental alternative to [`cleanup_torrents`](Self::cleanup_torrents).
+    /// It only inspects the peers that were last updated before the cutoff
+    /// time, up to a small batch size, so it can be called frequently without
+    /// producing latency spikes on announce requests. Torrents left without
+    /// peers are removed in the same step when the tracker is configured to
+    /// remove peerless torrents.
+    ///
+    /// The caller should call it again while the returned
+    /// [`ExpiredPeers::finished`] is `false`.
+    pub fn expire_inactive_peers(&self) -> ExpiredPeers {
+        let tracker_policy = self.tracker_policy();
+
+        let expired = self.in_memory_torrent_repository.remove_expired_peers(
+            Self::cutoff(&tracker_policy),
+            &tracker_policy,
+            EXPIRY_BATCH_SIZE,
+        );
+
+        self.publish_removed(&expired.torrents);
+
+        expired
+    }
+
+    /// Removes torrents with no active peers, if the tracker is configured
+    /// to do so.
+    ///
+    /// Torrents can be left without peers without their peers expiring, for
+    /// example when all of them announce the `stopped` event.
+    pub fn remove_peerless_torrents(&self) {
+        let tracker_policy = self.tracker_policy();
+
+        if tracker_policy.remove_peerless_torrents {
+            let removed = self.in_memory_torrent_repository.remove_peerless_torrents(&tracker_policy);
+            self.publish_removed(&removed);
+        }
+    }
+
+    /// Removes a torrent and all its peers from the tracker.
+    ///
+    /// The persisted `completed` counter is kept unless `delete_completed`
+    /// is set. Otherwise, it's loaded again on the next restart if the
+    /// tracker is configured to persist the torrent metrics.
+    ///
+    /// It returns `true` if the torrent was being tracked.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `databases::error::Error` if unable to delete the persisted
+    /// `completed` counter. The torrent is removed from memory anyway.
+    pub fn purge_torrent(&self, info_hash: &InfoHash, delete_completed: bool) -> Result<bool, databases::error::Error> {
+        let removed = self.in_memory_torrent_repository.remove(info_hash).is_some();
 
-        self.in_memory_torrent_repository.remove_inactive_peers(current_cutoff);
+        if removed {
+            self.events.publish(Event::TorrentRemoved { info_hash: *info_hash });
+        }
 
-        if self.config.tracker_policy.remove_peerless_torrents {
-            self.in_memory_torrent_repository
-                .remove_peerless_torrents(&self.config.tracker_policy);
+        if delete_completed {
+            self.db_torrent_repository.remove(info_hash)?;
         }
+
+        Ok(removed)
+    }
+
+    /// Removes a single peer from a torrent's swarm, regardless of its
+    /// activity.
+    ///
+    /// The torrent is removed too if it's left without peers and the tracker
+    /// is configured to remove peerless torrents.
+    ///
+    /// It returns the removed peer, or `None` if the torrent or the peer do
+    /// not exist.
+    #[must_use]
+    pub fn evict_peer(&self, info_hash: &InfoHash, peer_id: &PeerId) -> Option<peer::Peer> {
+        let tracker_policy = self.tracker_policy();
+
+        let removed = self
+            .in_memory_torrent_repository
+            .remove_peer(info_hash, peer_id, &tracker_policy);
+
+        if removed.is_some() && self.in_memory_torrent_repository.get(info_hash).is_none() {
+            self.events.publish(Event::TorrentRemoved { info_hash: *info_hash });
+        }
+
+        removed
+    }
+
+    fn publish_removed(&self, info_hashes: &[InfoHash]) {
+        for info_hash in info_hashes {
+            self.events.publish(Event::TorrentRemoved { info_hash: *info_hash });
+        }
+    }
+
+    fn tracker_policy(&self) -> TrackerPolicy {
+        self.config
+            .read()
+            .expect("it should lock the torrents manager configuration")
+            .tracker_policy
+            .clone()
+    }
+
+    /// Peers not updated after this time are inactive.
+    fn cutoff(tracker_policy: &TrackerPolicy) -> DurationSinceUnixEpoch {
+        CurrentClock::now_sub(&Duration::from_secs(u64::from(tracker_policy.max_peer_timeout))).unwrap_or_default()
     }
 }
 
@@ -114,6 +250,7 @@ mod tests {
 
     use super::{DatabasePersistentTorrentRepository, TorrentsManager};
     use crate::databases::setup::initialize_database;
+    use crate::event::Bus;
     use crate::test_helpers::tests::{ephemeral_configuration, sample_info_hash};
     use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
 
@@ -121,6 +258,7 @@ mod tests {
         config: Arc<Core>,
         in_memory_torrent_repository: Arc<InMemoryTorrentRepository>,
         database_persistent_torrent_repository: Arc<DatabasePersistentTorrentRepository>,
+        events: Arc<Bus>,
     }
 
     fn initialize_torrents_manager() -> (Arc<TorrentsManager>, Arc<TorrentsManagerDeps>) {
@@ -132,11 +270,13 @@ mod tests {
         let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
         let database = initialize_database(&config);
         let database_persistent_torrent_repository = Arc::new(DatabasePersistentTorrentRepository::new(&database));
+        let events = Arc::new(Bus::default());
 
         let torrents_manager = Arc::new(TorrentsManager::new(
             &config,
             &in_memory_torrent_repository,
             &database_persistent_torrent_repository,
+            &events,
         ));
 
         (
@@ -145,6 +285,7 @@ mod tests {
                 config: Arc::new(config),
                 in_memory_torrent_repository,
                 database_persistent_torrent_repository,
+                events,
             }),
         )
     }
@@ -170,6 +311,158 @@ mod tests {
         );
     }
 
+    mod purging_torrents {
+        use torrust_tracker_torrent_repository::entry::EntrySync;
+
+        use crate::event::Event;
+        use crate::test_helpers::tests::{sample_info_hash, sample_peer};
+        use crate::torrent::manager::tests::initialize_torrents_manager;
+
+        #[test]
+        fn it_should_publish_the_removed_torrent() {
+            let (torrents_manager, services) = initialize_torrents_manager();
+
+            let mut events = services.events.subscribe();
+
+            let infohash = sample_info_hash();
+
+            let () = services.in_memory_torrent_repository.upsert_peer(&infohash, &sample_peer());
+
+            torrents_manager.purge_torrent(&infohash, false).unwrap();
+
+            assert_eq!(events.try_recv().unwrap(), Event::TorrentRemoved { info_hash: infohash });
+        }
+
+        #[test]
+        fn it_should_remove_the_torrent_with_all_its_peers() {
+            let (torrents_manager, services) = initialize_torrents_manager();
+
+            let infohash = sample_info_hash();
+
+            let () = services.in_memory_torrent_repository.upsert_peer(&infohash, &sample_peer());
+
+            assert!(torrents_manager.purge_torrent(&infohash, false).unwrap());
+
+            assert!(services.in_memory_torrent_repository.get(&infohash).is_none());
+        }
+
+        #[test]
+        fn it_should_return_false_when_the_torrent_is_not_being_tracked() {
+            let (torrents_manager, _services) = initialize_torrents_manager();
+
+            assert!(!torrents_manager.purge_torrent(&sample_info_hash(), false).unwrap());
+        }
+
+        #[test]
+        fn it_should_keep_the_persisted_completed_counter_by_default() {
+            let (torrents_manager, services) = initialize_torrents_manager();
+
+            let infohash = sample_info_hash();
+
+            services.database_persistent_torrent_repository.save(&infohash, 1).unwrap();
+
+            torrents_manager.purge_torrent(&infohash, false).unwrap();
+
+            torrents_manager.load_torrents_from_database().unwrap();
+
+            assert_eq!(
+                services
+                    .in_memory_torrent_repository
+                    .get(&infohash)
+                    .unwrap()
+                    .get_swarm_metadata()
+                    .downloaded,
+                1
+            );
+        }
+
+        #[test]
+        fn it_should_delete_the_persisted_completed_counter_when_requested() {
+            let (torrents_manager, services) = initialize_torrents_manager();
+
+            let infohash = sample_info_hash();
+
+            services.database_persistent_torrent_repository.save(&infohash, 1).unwrap();
+
+            torrents_manager.purge_torrent(&infohash, true).unwrap();
+
+            torrents_manager.load_torrents_from_database().unwrap();
+
+            assert!(services.in_memory_torrent_repository.get(&infohash).is_none());
+        }
+    }
+
+    mod evicting_peers {
+        use torrust_tracker_torrent_repository::entry::EntrySync;
+
+        use crate::event::Event;
+        use crate::test_helpers::tests::{ephemeral_configuration, sample_info_hash, sample_peer};
+        use crate::torrent::manager::tests::{initialize_torrents_manager, initialize_torrents_manager_with};
+
+        #[test]
+        fn it_should_remove_a_single_peer_from_the_swarm() {
+            let mut config = ephemeral_configuration();
+            config.tracker_policy.remove_peerless_torrents = false;
+
+            let (torrents_manager, services) = initialize_torrents_manager_with(config);
+
+            let infohash = sample_info_hash();
+            let peer = sample_peer();
+
+            let () = services.in_memory_torrent_repository.upsert_peer(&infohash, &peer);
+
+            assert_eq!(torrents_manager.evict_peer(&infohash, &peer.peer_id), Some(peer));
+
+            assert_eq!(
+                services.in_memory_torrent_repository.get(&infohash).unwrap().get_peers_len(),
+                0
+            );
+        }
+
+        #[test]
+        fn it_should_remove_the_torrent_when_it_is_left_without_peers_and_it_is_configured_to_do_so() {
+            let mut config = ephemeral_configuration();
+            config.tracker_policy.remove_peerless_torrents = true;
+
+            let (torrents_manager, services) = initialize_torrents_manager_with(config);
+
+            let infohash = sample_info_hash();
+            let peer = sample_peer();
+
+            let () = services.in_memory_torrent_repository.upsert_peer(&infohash, &peer);
+
+            let _ = torrents_manager.evict_peer(&infohash, &peer.peer_id);
+
+            assert!(services.in_memory_torrent_repository.get(&infohash).is_none());
+        }
+
+        #[test]
+        fn it_should_publish_the_torrent_removed_when_it_is_left_without_peers() {
+            let mut config = ephemeral_configuration();
+            config.tracker_policy.remove_peerless_torrents = true;
+
+            let (torrents_manager, services) = initialize_torrents_manager_with(config);
+
+            let infohash = sample_info_hash();
+            let peer = sample_peer();
+
+            let () = services.in_memory_torrent_repository.upsert_peer(&infohash, &peer);
+
+            let mut events = services.events.subscribe();
+
+            let _ = torrents_manager.evict_peer(&infohash, &peer.peer_id);
+
+            assert_eq!(events.try_recv().unwrap(), Event::TorrentRemoved { info_hash: infohash });
+        }
+
+        #[test]
+        fn it_should_return_none_when_the_peer_does_not_exist() {
+            let (torrents_manager, _services) = initialize_torrents_manager();
+
+            assert_eq!(torrents_manager.evict_peer(&sample_info_hash(), &sample_peer().peer_id), None);
+        }
+    }
+
     mod cleaning_torrents {
         use std::ops::Add;
         use std::sync::Arc;
@@ -179,7 +472,9 @@ mod tests {
         use torrust_tracker_clock::clock::stopped::Stopped;
         use torrust_tracker_clock::clock::{self};
         use torrust_tracker_primitives::DurationSinceUnixEpoch;
+        use torrust_tracker_torrent_repository::entry::EntrySync;
 
+        use crate::event::Event;
         use crate::test_helpers::tests::{ephemeral_configuration, sample_info_hash, sample_peer};
         use crate::torrent::manager::tests::{initialize_torrents_manager, initialize_torrents_manager_with};
         use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
@@ -208,6 +503,38 @@ mod tests {
             assert!(services.in_memory_torrent_repository.get(&infohash).is_none());
         }
 
+        #[test]
+        fn it_should_expire_peers_that_have_not_been_updated_after_a_cutoff_time_incrementally() {
+            let (torrents_manager, services) = initialize_torrents_manager();
+
+            let infohash = sample_info_hash();
+
+            clock::Stopped::local_set(&Duration::from_secs(0));
+
+            // Add a peer to the torrent
+            let mut peer = sample_peer();
+            peer.updated = DurationSinceUnixEpoch::new(0, 0);
+            let () = services.in_memory_torrent_repository.upsert_peer(&infohash, &peer);
+
+            // Simulate the time has passed 1 second more than the max peer timeout.
+            clock::Stopped::local_add(&Duration::from_secs(
+                (services.config.tracker_policy.max_peer_timeout + 1).into(),
+            ))
+            .unwrap();
+
+            let expired = torrents_manager.expire_inactive_peers();
+
+            assert!(expired.finished);
+            assert_eq!(expired.peers, 1);
+            assert_eq!(
+                services
+                    .in_memory_torrent_repository
+                    .get(&infohash)
+                    .map_or(0, |torrent| torrent.get_peers_len()),
+                0
+            );
+        }
+
         fn add_a_peerless_torrent(infohash: &InfoHash, in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>) {
             // Add a peer to the torrent
             let mut peer = sample_peer();
@@ -234,6 +561,24 @@ mod tests {
             assert!(services.in_memory_torrent_repository.get(&infohash).is_none());
         }
 
+        #[test]
+        fn it_should_publish_the_torrents_removed_because_they_have_no_peers() {
+            let mut config = ephemeral_configuration();
+            config.tracker_policy.remove_peerless_torrents = true;
+
+            let (torrents_manager, services) = initialize_torrents_manager_with(config);
+
+            let mut events = services.events.subscribe();
+
+            let infohash = sample_info_hash();
+
+            add_a_peerless_torrent(&infohash, &services.in_memory_torrent_repository);
+
+            torrents_manager.cleanup_torrents();
+
+            assert_eq!(events.try_recv().unwrap(), Event::TorrentRemoved { info_hash: infohash });
+        }
+
         #[test]
         fn it_should_retain_peerless_torrents_when_it_is_configured_to_do_so() {
             let mut config = ephemeral_configuration();
@@ -249,5 +594,28 @@ mod tests {
 
             assert!(services.in_memory_torrent_repository.get(&infohash).is_some());
         }
+
+        #[test]
+        fn it_should_use_the_reloaded_max_peer_timeout() {
+            let (torrents_manager, services) = initialize_torrents_manager();
+
+            let infohash = sample_info_hash();
+
+            clock::Stopped::local_set(&Duration::from_secs(0));
+
+            let mut peer = sample_peer();
+            peer.updated = DurationSinceUnixEpoch::new(0, 0);
+            let () = services.in_memory_torrent_repository.upsert_peer(&infohash, &peer);
+
+            let mut config = ephemeral_configuration();
+            config.tracker_policy.max_peer_timeout = 10;
+            torrents_manager.reload(&config);
+
+            clock::Stopped::local_add(&Duration::from_secs(11)).unwrap();
+
+            torrents_manager.cleanup_torrents();
+
+            assert!(services.in_memory_torrent_repository.get(&infohash).is_none());
+        }
     }
 }
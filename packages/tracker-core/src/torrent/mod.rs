@@ -165,7 +165,9 @@
 //! Refer to [`peer`](torrust_tracker_primitives::peer) for more information about peers.
 pub mod manager;
 pub mod repository;
+pub mod selection;
 pub mod services;
+pub mod tally;
 
 #[cfg(test)]
 use torrust_tracker_torrent_repository::EntryMutexStd;
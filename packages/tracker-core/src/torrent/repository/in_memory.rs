@@ -2,18 +2,29 @@
 use std::cmp::max;
 use std::sync::Arc;
 
+use aquatic_udp_protocol::PeerId;
 use bittorrent_primitives::info_hash::InfoHash;
-use torrust_tracker_configuration::{TrackerPolicy, TORRENT_PEERS_LIMIT};
+use torrust_tracker_configuration::{MemoryLimits, TrackerPolicy, TORRENT_PEERS_LIMIT};
+use torrust_tracker_primitives::client_metrics::ClientsMetrics;
 use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
 use torrust_tracker_primitives::{peer, DurationSinceUnixEpoch, PersistentTorrents};
-use torrust_tracker_torrent_repository::entry::EntrySync;
+use torrust_tracker_torrent_repository::entry::{EntrySync, PeerPredicate};
+use torrust_tracker_torrent_repository::expiry::ExpiredPeers;
+use torrust_tracker_torrent_repository::limits::{EvictionMetrics, PeerChanges};
 use torrust_tracker_torrent_repository::repository::Repository;
 use torrust_tracker_torrent_repository::EntryMutexStd;
 
+use crate::geoip::{GeoIp, LocationsMetrics};
+use crate::torrent::selection::{PeerSelection, LOCALITY_CANDIDATES_PER_PEER};
+use crate::torrent::tally::PeersTally;
 use crate::torrent::Torrents;
 
+/// The number of torrents read from the repository at a time when iterating
+/// over all the torrents.
+const ITERATION_BATCH_SIZE: usize = 1000;
+
 /// In-memory repository for torrent entries.
 ///
 /// This repository manages the torrent entries and their associated peer lists
@@ -23,13 +34,40 @@ use crate::torrent::Torrents;
 ///
 /// Multiple implementations were considered, and the chosen implementation is
 /// used in production. Other implementations are kept for reference.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct InMemoryTorrentRepository {
     /// The underlying in-memory data structure that stores torrent entries.
     torrents: Arc<Torrents>,
+
+    /// The number of peers by client and location, kept up to date by the
+    /// `torrents`.
+    tally: Arc<PeersTally>,
+}
+
+impl Default for InMemoryTorrentRepository {
+    fn default() -> Self {
+        Self::new(&MemoryLimits::default(), &Arc::default())
+    }
 }
 
 impl InMemoryTorrentRepository {
+    /// Creates an empty repository that enforces the given memory limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `limits` - The maximum number of torrents and peers to keep in
+    ///   memory and what to do when they are reached.
+    /// * `geoip` - The service used to count the peers by location.
+    #[must_use]
+    pub fn new(limits: &MemoryLimits, geoip: &Arc<GeoIp>) -> Self {
+        let tally = Arc::new(PeersTally::new(geoip));
+
+        Self {
+            torrents: Arc::new(Torrents::new(*limits).with_tally(tally.clone())),
+            tally,
+        }
+    }
+
     /// Inserts or updates a peer in the torrent entry corresponding to the
     /// given infohash.
     ///
@@ -44,11 +82,18 @@ impl InMemoryTorrentRepository {
         self.torrents.upsert_peer(info_hash, peer);
     }
 
+    /// Like [`upsert_peer`](Self::upsert_peer), but it returns the changes in
+    /// the torrent swarm, for example, to know whether the peer was refused
+    /// because of the memory limits.
+    #[must_use]
+    pub(crate) fn upsert_peer_and_get_changes(&self, info_hash: &InfoHash, peer: &peer::Peer) -> PeerChanges {
+        self.torrents.upsert_peer(info_hash, peer)
+    }
+
     /// Removes a torrent entry from the repository.
     ///
-    /// This method is only available in tests. It removes the torrent entry
-    /// associated with the given info hash and returns the removed entry if it
-    /// existed.
+    /// It removes the torrent entry associated with the given info hash,
+    /// including its peers, and returns the removed entry if it existed.
     ///
     /// # Arguments
     ///
@@ -57,12 +102,29 @@ impl InMemoryTorrentRepository {
     /// # Returns
     ///
     /// An `Option` containing the removed torrent entry if it existed.
-    #[cfg(test)]
     #[must_use]
     pub(crate) fn remove(&self, key: &InfoHash) -> Option<EntryMutexStd> {
         self.torrents.remove(key)
     }
 
+    /// Removes a single peer from a torrent entry.
+    ///
+    /// The torrent entry is removed too if it's left without peers and the
+    /// `policy` does not retain it.
+    ///
+    /// # Arguments
+    ///
+    /// * `info_hash` - The info hash of the torrent.
+    /// * `peer_id` - The ID of the peer to remove.
+    /// * `policy` - The tracker policy.
+    ///
+    /// # Returns
+    ///
+    /// The removed peer, or `None` if the torrent or the peer did not exist.
+    pub(crate) fn remove_peer(&self, info_hash: &InfoHash, peer_id: &PeerId, policy: &TrackerPolicy) -> Option<peer::Peer> {
+        self.torrents.remove_peer(info_hash, peer_id, policy)
+    }
+
     /// Removes inactive peers from all torrent entries.
     ///
     /// A peer is considered inactive if its last update timestamp is older than
@@ -76,6 +138,35 @@ impl InMemoryTorrentRepository {
         self.torrents.remove_inactive_peers(current_cutoff);
     }
 
+    /// Removes inactive peers incrementally, inspecting at most `max_peers`
+    /// peers.
+    ///
+    /// Unlike [`remove_inactive_peers`](Self::remove_inactive_peers), it only
+    /// visits the peers that were last updated before the cutoff time, so it
+    /// does not lock all the torrent entries at once. Torrents left without
+    /// peers are also removed if the policy does not retain them.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_cutoff` - The cutoff timestamp; peers not updated since this
+    ///   time will be removed.
+    /// * `policy` - The tracker policy used to decide whether to remove
+    ///   torrents left without peers.
+    /// * `max_peers` - The maximum number of peers to inspect.
+    ///
+    /// # Returns
+    ///
+    /// An [`ExpiredPeers`] struct with the number of peers removed, the
+    /// removed torrents and whether there could be more expired peers pending.
+    pub(crate) fn remove_expired_peers(
+        &self,
+        current_cutoff: DurationSinceUnixEpoch,
+        policy: &TrackerPolicy,
+        max_peers: usize,
+    ) -> ExpiredPeers {
+        self.torrents.remove_expired_peers(current_cutoff, policy, max_peers)
+    }
+
     /// Removes torrent entries that have no active peers.
     ///
     /// Depending on the tracker policy, torrents without any peers may be
@@ -85,8 +176,12 @@ impl InMemoryTorrentRepository {
     ///
     /// * `policy` - The tracker policy containing the configuration for
     ///   removing peerless torrents.
-    pub(crate) fn remove_peerless_torrents(&self, policy: &TrackerPolicy) {
-        self.torrents.remove_peerless_torrents(policy);
+    ///
+    /// # Returns
+    ///
+    /// The info hashes of the removed torrents.
+    pub(crate) fn remove_peerless_torrents(&self, policy: &TrackerPolicy) -> Vec<InfoHash> {
+        self.torrents.take_peerless_torrents(policy)
     }
 
     /// Retrieves a torrent entry by its infohash.
@@ -121,6 +216,62 @@ impl InMemoryTorrentRepository {
         self.torrents.get_paginated(pagination)
     }
 
+    /// Retrieves up to `limit` torrent entries whose infohash comes after
+    /// `after`, in infohash order.
+    ///
+    /// It can be used to walk through all the torrents in batches, using the
+    /// last infohash of each batch as the cursor for the next one. Unlike
+    /// offset pagination, torrents added or removed between batches do not
+    /// shift the remaining ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - The infohash of the last entry of the previous batch, or
+    ///   `None` to start from the first torrent.
+    /// * `limit` - The maximum number of entries to return.
+    #[must_use]
+    pub(crate) fn get_after(&self, after: Option<&InfoHash>, limit: usize) -> Vec<(InfoHash, EntryMutexStd)> {
+        self.torrents.get_after(after, limit)
+    }
+
+    /// Returns an iterator over the swarm metadata of all the torrents, in
+    /// infohash order.
+    ///
+    /// Unlike [`get_paginated`](Self::get_paginated), it does not collect the
+    /// torrent entries, so it can be used to scan all the torrents without
+    /// allocating memory for each one. The swarm metadata is read in batches
+    /// of [`ITERATION_BATCH_SIZE`] torrents.
+    ///
+    /// The iteration is not atomic. The torrents keep being updated while they
+    /// are read, so the changes made during the iteration may or may not be
+    /// included.
+    pub(crate) fn swarm_metadata_iter(&self) -> impl Iterator<Item = (InfoHash, SwarmMetadata)> + '_ {
+        let first = self.torrents.get_swarm_metadata_after(None, ITERATION_BATCH_SIZE);
+
+        std::iter::successors(Some(first), move |batch| {
+            if batch.len() < ITERATION_BATCH_SIZE {
+                return None;
+            }
+
+            batch
+                .last()
+                .map(|(last, _)| self.torrents.get_swarm_metadata_after(Some(last), ITERATION_BATCH_SIZE))
+        })
+        .flatten()
+    }
+
+    /// Returns the swarm metadata of all the torrents, in infohash order,
+    /// read in a single pass over the repository.
+    ///
+    /// Unlike [`swarm_metadata_iter`](Self::swarm_metadata_iter), the torrents
+    /// are not read in batches, so the result is a copy of the swarm metadata
+    /// at the time of the call. Only the swarm metadata is copied, not the
+    /// peers.
+    #[must_use]
+    pub(crate) fn get_swarm_metadata_snapshot(&self) -> Vec<(InfoHash, SwarmMetadata)> {
+        self.torrents.get_swarm_metadata_after(None, usize::MAX)
+    }
+
     /// Retrieves swarm metadata for a given torrent.
     ///
     /// This method returns the swarm metadata (aggregate information such as
@@ -148,22 +299,42 @@ impl InMemoryTorrentRepository {
     /// This method filters out the client making the request (based on its
     /// network address) and returns up to a maximum number of peers, defined by
     /// the greater of the provided limit or the global `TORRENT_PEERS_LIMIT`.
+    /// The peers are chosen with the given [`PeerSelection`] strategy.
     ///
     /// # Arguments
     ///
     /// * `info_hash` - The info hash of the torrent.
     /// * `peer` - The client peer that should be excluded from the returned list.
     /// * `limit` - The maximum number of peers to return.
+    /// * `predicate` - Only the peers matching it are returned.
+    /// * `selection` - How the peers are chosen when there are more than the
+    ///   limit.
     ///
     /// # Returns
     ///
     /// A vector of peers (wrapped in `Arc`) representing the active peers for
     /// the torrent, excluding the requesting client.
     #[must_use]
-    pub(crate) fn get_peers_for(&self, info_hash: &InfoHash, peer: &peer::Peer, limit: usize) -> Vec<Arc<peer::Peer>> {
-        match self.torrents.get(info_hash) {
-            None => vec![],
-            Some(entry) => entry.get_peers_for_client(&peer.peer_addr, Some(max(limit, TORRENT_PEERS_LIMIT))),
+    pub(crate) fn get_peers_for(
+        &self,
+        info_hash: &InfoHash,
+        peer: &peer::Peer,
+        limit: usize,
+        predicate: &PeerPredicate<'_>,
+        selection: &PeerSelection<'_>,
+    ) -> Vec<Arc<peer::Peer>> {
+        let Some(entry) = self.torrents.get(info_hash) else {
+            return vec![];
+        };
+
+        let limit = max(limit, TORRENT_PEERS_LIMIT);
+
+        match selection {
+            PeerSelection::Ordered => entry.get_peers_for_client_matching(&peer.peer_addr, Some(limit), predicate),
+            PeerSelection::Locality { .. } => selection.select(
+                entry.sample_peers_for_client_matching(&peer.peer_addr, limit * LOCALITY_CANDIDATES_PER_PEER, predicate),
+                limit,
+            ),
         }
     }
 
@@ -188,6 +359,24 @@ impl InMemoryTorrentRepository {
         }
     }
 
+    /// Retrieves a peer of a torrent by its peer ID.
+    ///
+    /// # Returns
+    ///
+    /// The peer if the torrent exists and the peer is in its swarm, or `None`
+    /// otherwise.
+    #[must_use]
+    pub(crate) fn get_peer(&self, info_hash: &InfoHash, peer_id: &PeerId) -> Option<peer::Peer> {
+        self.torrents.get(info_hash).and_then(|entry| entry.get_peer(peer_id))
+    }
+
+    /// Returns the fraction, from `0.0` to `1.0`, of the most used memory
+    /// limit that is in use. It's `0.0` when there are no memory limits.
+    #[must_use]
+    pub(crate) fn get_memory_usage(&self) -> f64 {
+        self.torrents.get_memory_usage()
+    }
+
     /// Calculates and returns overall torrent metrics.
     ///
     /// The returned [`TorrentsMetrics`] contains aggregate data such as the
@@ -202,6 +391,94 @@ impl InMemoryTorrentRepository {
         self.torrents.get_metrics()
     }
 
+    /// Counts the active peers of all the torrents by client software.
+    ///
+    /// The counts are kept up to date when the peers are added and removed,
+    /// so it does not go through the peers.
+    ///
+    /// # Returns
+    ///
+    /// A [`ClientsMetrics`] struct with the number of peers by client.
+    #[must_use]
+    pub fn get_clients_metrics(&self) -> ClientsMetrics {
+        self.tally.clients_metrics()
+    }
+
+    /// Counts the active peers of a torrent by client software.
+    ///
+    /// # Arguments
+    ///
+    /// * `info_hash` - The info hash of the torrent.
+    ///
+    /// # Returns
+    ///
+    /// A [`ClientsMetrics`] struct with the number of peers by client. It's
+    /// empty if the torrent is not tracked.
+    #[must_use]
+    pub fn get_torrent_clients_metrics(&self, info_hash: &InfoHash) -> ClientsMetrics {
+        let mut metrics = ClientsMetrics::default();
+
+        if let Some(entry) = self.torrents.get(info_hash) {
+            for peer in entry.get_peers(None) {
+                metrics.count(&peer.peer_id);
+            }
+        }
+
+        metrics
+    }
+
+    /// Counts the active peers of all the torrents by country and ASN.
+    ///
+    /// The peers are located when they join or leave a swarm, so it does not
+    /// have to go through all the peers.
+    ///
+    /// # Returns
+    ///
+    /// A [`LocationsMetrics`] struct with the number of peers by country and
+    /// ASN. It's empty if the repository was created without a `GeoIP`
+    /// database.
+    #[must_use]
+    pub fn get_locations_metrics(&self) -> LocationsMetrics {
+        self.tally.locations_metrics()
+    }
+
+    /// Counts the active peers of a torrent by country and ASN.
+    ///
+    /// Only the peers of the torrent are looked up in the `GeoIP` databases.
+    ///
+    /// # Arguments
+    ///
+    /// * `info_hash` - The info hash of the torrent.
+    /// * `geoip` - The service used to locate the peers.
+    ///
+    /// # Returns
+    ///
+    /// A [`LocationsMetrics`] struct with the number of peers by country and
+    /// ASN. It's empty if the torrent is not tracked.
+    #[must_use]
+    pub fn get_torrent_locations_metrics(&self, info_hash: &InfoHash, geoip: &GeoIp) -> LocationsMetrics {
+        let mut metrics = LocationsMetrics::default();
+
+        if let Some(entry) = self.torrents.get(info_hash) {
+            for peer in entry.get_peers(None) {
+                metrics.count(geoip.locate(&peer.peer_addr.ip()));
+            }
+        }
+
+        metrics
+    }
+
+    /// Returns the counters for the torrents and peers that did not fit in
+    /// the repository because of the configured memory limits.
+    ///
+    /// # Returns
+    ///
+    /// An [`EvictionMetrics`] struct with the eviction counters.
+    #[must_use]
+    pub fn get_eviction_metrics(&self) -> EvictionMetrics {
+        self.torrents.get_eviction_metrics()
+    }
+
     /// Imports persistent torrent data into the in-memory repository.
     ///
     /// This method takes a set of persisted torrent entries (e.g., from a database)
@@ -352,16 +629,23 @@ mod tests {
                 use torrust_tracker_primitives::peer::Peer;
                 use torrust_tracker_primitives::DurationSinceUnixEpoch;
 
-                use crate::test_helpers::tests::{sample_info_hash, sample_peer};
+                use crate::geoip::GeoIp;
+                use crate::test_helpers::tests::{sample_info_hash, sample_peer, sample_peer_one, sample_peer_two};
                 use crate::torrent::repository::in_memory::tests::the_in_memory_torrent_repository::numeric_peer_id;
                 use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+                use crate::torrent::selection::{Neighbourhood, PeerSelection};
 
                 #[tokio::test]
                 async fn it_should_return_an_empty_peer_list_for_a_non_existing_torrent() {
                     let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
 
-                    let peers =
-                        in_memory_torrent_repository.get_peers_for(&sample_info_hash(), &sample_peer(), TORRENT_PEERS_LIMIT);
+                    let peers = in_memory_torrent_repository.get_peers_for(
+                        &sample_info_hash(),
+                        &sample_peer(),
+                        TORRENT_PEERS_LIMIT,
+                        &|_| true,
+                        &PeerSelection::Ordered,
+                    );
 
                     assert_eq!(peers, vec![]);
                 }
@@ -375,7 +659,13 @@ mod tests {
 
                     let () = in_memory_torrent_repository.upsert_peer(&info_hash, &peer);
 
-                    let peers = in_memory_torrent_repository.get_peers_for(&info_hash, &peer, TORRENT_PEERS_LIMIT);
+                    let peers = in_memory_torrent_repository.get_peers_for(
+                        &info_hash,
+                        &peer,
+                        TORRENT_PEERS_LIMIT,
+                        &|_| true,
+                        &PeerSelection::Ordered,
+                    );
 
                     assert_eq!(peers, vec![]);
                 }
@@ -405,10 +695,82 @@ mod tests {
                         let () = in_memory_torrent_repository.upsert_peer(&info_hash, &peer);
                     }
 
-                    let peers = in_memory_torrent_repository.get_peers_for(&info_hash, &excluded_peer, TORRENT_PEERS_LIMIT);
+                    let peers = in_memory_torrent_repository.get_peers_for(
+                        &info_hash,
+                        &excluded_peer,
+                        TORRENT_PEERS_LIMIT,
+                        &|_| true,
+                        &PeerSelection::Ordered,
+                    );
 
                     assert_eq!(peers.len(), 74);
                 }
+
+                #[tokio::test]
+                async fn it_should_only_return_the_peers_matching_the_predicate() {
+                    let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+                    let info_hash = sample_info_hash();
+
+                    let () = in_memory_torrent_repository.upsert_peer(&info_hash, &sample_peer_one());
+                    let () = in_memory_torrent_repository.upsert_peer(&info_hash, &sample_peer_two());
+
+                    let peers = in_memory_torrent_repository.get_peers_for(
+                        &info_hash,
+                        &sample_peer(),
+                        TORRENT_PEERS_LIMIT,
+                        &|peer| peer.peer_addr.port() == 8082,
+                        &PeerSelection::Ordered,
+                    );
+
+                    assert_eq!(peers, vec![Arc::new(sample_peer_two())]);
+                }
+
+                #[tokio::test]
+                async fn it_should_prefer_the_nearby_peers_with_the_locality_selection() {
+                    let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+                    let info_hash = sample_info_hash();
+
+                    // 80 peers in other networks, ordered before 10 peers in the client `/24` network
+                    for idx in 2..=91 {
+                        let ip = if idx <= 81 {
+                            Ipv4Addr::new(5, 6, idx.try_into().unwrap(), 1)
+                        } else {
+                            Ipv4Addr::new(126, 0, 0, (idx - 80).try_into().unwrap())
+                        };
+
+                        let peer = Peer {
+                            peer_id: numeric_peer_id(idx),
+                            peer_addr: SocketAddr::new(IpAddr::V4(ip), 8080),
+                            ..sample_peer()
+                        };
+
+                        let () = in_memory_torrent_repository.upsert_peer(&info_hash, &peer);
+                    }
+
+                    let client = sample_peer();
+                    let geoip = GeoIp::default();
+                    let is_nearby = |peer: &Arc<Peer>| peer.peer_addr.ip().to_string().starts_with("126.0.0.");
+
+                    let ordered =
+                        in_memory_torrent_repository.get_peers_for(&info_hash, &client, 74, &|_| true, &PeerSelection::Ordered);
+
+                    let locality = in_memory_torrent_repository.get_peers_for(
+                        &info_hash,
+                        &client,
+                        74,
+                        &|_| true,
+                        &PeerSelection::Locality {
+                            neighbourhood: Neighbourhood::new(&client.peer_addr.ip(), &geoip),
+                            local_peers_percentage: 50,
+                        },
+                    );
+
+                    assert_eq!(ordered.iter().filter(|peer| is_nearby(peer)).count(), 0);
+                    assert_eq!(locality.len(), 74);
+                    assert_eq!(locality.iter().filter(|peer| is_nearby(peer)).count(), 10);
+                }
             }
         }
 
@@ -802,14 +1164,203 @@ mod tests {
             }
         }
 
+        mod returning_client_metrics {
+
+            use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+            use std::sync::Arc;
+
+            use aquatic_udp_protocol::{AnnounceEvent, PeerId};
+            use bittorrent_primitives::info_hash::fixture::gen_seeded_infohash;
+            use torrust_tracker_primitives::client_metrics::{Client, ClientsMetrics};
+            use torrust_tracker_primitives::peer::Peer;
+
+            use crate::test_helpers::tests::sample_peer;
+            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+
+            fn peer_with_id(peer_id: &[u8; 20], port: u16) -> Peer {
+                Peer {
+                    peer_id: PeerId(*peer_id),
+                    peer_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), port),
+                    ..sample_peer()
+                }
+            }
+
+            fn transmission() -> Client {
+                Client {
+                    name: "Transmission".to_string(),
+                    version: Some("4.0.4".to_string()),
+                }
+            }
+
+            fn qbittorrent() -> Client {
+                Client {
+                    name: "qBittorrent".to_string(),
+                    version: Some("4.6.3".to_string()),
+                }
+            }
+
+            #[tokio::test]
+            async fn it_should_count_the_peers_of_all_the_torrents_by_client() {
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+                let () =
+                    in_memory_torrent_repository.upsert_peer(&gen_seeded_infohash(&1), &peer_with_id(b"-TR4040-xxxxxxxxxxxx", 1));
+                let () =
+                    in_memory_torrent_repository.upsert_peer(&gen_seeded_infohash(&1), &peer_with_id(b"-qB4630-xxxxxxxxxxxx", 2));
+                let () =
+                    in_memory_torrent_repository.upsert_peer(&gen_seeded_infohash(&2), &peer_with_id(b"-qB4630-yyyyyyyyyyyy", 3));
+
+                assert_eq!(
+                    in_memory_torrent_repository.get_clients_metrics(),
+                    ClientsMetrics {
+                        peers: [(transmission(), 1), (qbittorrent(), 2)].into_iter().collect()
+                    }
+                );
+            }
+
+            #[tokio::test]
+            async fn it_should_stop_counting_the_peers_that_leave_the_swarm() {
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+                let mut peer = peer_with_id(b"-TR4040-xxxxxxxxxxxx", 1);
+
+                let () = in_memory_torrent_repository.upsert_peer(&gen_seeded_infohash(&1), &peer);
+
+                peer.event = AnnounceEvent::Stopped;
+
+                let () = in_memory_torrent_repository.upsert_peer(&gen_seeded_infohash(&1), &peer);
+
+                assert_eq!(in_memory_torrent_repository.get_clients_metrics(), ClientsMetrics::default());
+            }
+
+            #[tokio::test]
+            async fn it_should_count_the_peers_of_a_torrent_by_client() {
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+                let () =
+                    in_memory_torrent_repository.upsert_peer(&gen_seeded_infohash(&1), &peer_with_id(b"-TR4040-xxxxxxxxxxxx", 1));
+                let () =
+                    in_memory_torrent_repository.upsert_peer(&gen_seeded_infohash(&2), &peer_with_id(b"-qB4630-yyyyyyyyyyyy", 2));
+
+                assert_eq!(
+                    in_memory_torrent_repository.get_torrent_clients_metrics(&gen_seeded_infohash(&1)),
+                    ClientsMetrics {
+                        peers: [(transmission(), 1)].into_iter().collect()
+                    }
+                );
+
+                assert_eq!(
+                    in_memory_torrent_repository.get_torrent_clients_metrics(&gen_seeded_infohash(&3)),
+                    ClientsMetrics::default()
+                );
+            }
+        }
+
+        mod returning_locations_metrics {
+
+            use std::net::{IpAddr, SocketAddr};
+            use std::sync::Arc;
+
+            use aquatic_udp_protocol::PeerId;
+            use bittorrent_primitives::info_hash::fixture::gen_seeded_infohash;
+            use torrust_tracker_configuration::MemoryLimits;
+            use torrust_tracker_primitives::peer::Peer;
+
+            use crate::geoip::GeoIp;
+            use crate::test_helpers::tests::{geoip_configuration, sample_peer};
+            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+
+            fn peer_with_ip(peer_id: &[u8; 20], ip: &str) -> Peer {
+                Peer {
+                    peer_id: PeerId(*peer_id),
+                    peer_addr: SocketAddr::new(ip.parse::<IpAddr>().unwrap(), 8080),
+                    ..sample_peer()
+                }
+            }
+
+            #[tokio::test]
+            async fn it_should_count_the_peers_of_all_the_torrents_by_country_and_asn() {
+                let geoip = Arc::new(GeoIp::load(&geoip_configuration()).unwrap());
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::new(&MemoryLimits::default(), &geoip));
+
+                let () = in_memory_torrent_repository.upsert_peer(
+                    &gen_seeded_infohash(&1),
+                    &peer_with_ip(b"-qB00000000000000001", "89.160.20.112"),
+                );
+                let () = in_memory_torrent_repository.upsert_peer(
+                    &gen_seeded_infohash(&2),
+                    &peer_with_ip(b"-qB00000000000000002", "81.2.69.142"),
+                );
+                let () = in_memory_torrent_repository.upsert_peer(
+                    &gen_seeded_infohash(&2),
+                    &peer_with_ip(b"-qB00000000000000003", "81.2.69.143"),
+                );
+
+                let metrics = in_memory_torrent_repository.get_locations_metrics();
+
+                assert_eq!(
+                    metrics.top_countries(),
+                    vec![(Some(&"GB".to_string()), 2), (Some(&"SE".to_string()), 1)]
+                );
+                assert_eq!(metrics.asns.len(), 2);
+            }
+
+            #[tokio::test]
+            async fn it_should_count_the_peers_of_a_torrent_by_country_and_asn() {
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+                let geoip = GeoIp::load(&geoip_configuration()).unwrap();
+
+                let () = in_memory_torrent_repository.upsert_peer(
+                    &gen_seeded_infohash(&1),
+                    &peer_with_ip(b"-qB00000000000000004", "89.160.20.112"),
+                );
+                let () = in_memory_torrent_repository.upsert_peer(
+                    &gen_seeded_infohash(&2),
+                    &peer_with_ip(b"-qB00000000000000005", "81.2.69.142"),
+                );
+
+                let metrics = in_memory_torrent_repository.get_torrent_locations_metrics(&gen_seeded_infohash(&1), &geoip);
+
+                assert_eq!(metrics.top_countries(), vec![(Some(&"SE".to_string()), 1)]);
+
+                assert!(in_memory_torrent_repository
+                    .get_torrent_locations_metrics(&gen_seeded_infohash(&3), &geoip)
+                    .countries
+                    .is_empty());
+            }
+        }
+
         mod returning_swarm_metadata {
 
             use std::sync::Arc;
 
+            use bittorrent_primitives::info_hash::InfoHash;
             use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 
             use crate::test_helpers::tests::{leecher, sample_info_hash};
-            use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+            use crate::torrent::repository::in_memory::{InMemoryTorrentRepository, ITERATION_BATCH_SIZE};
+
+            #[tokio::test]
+            async fn it_should_iterate_over_the_swarm_metadata_of_all_the_torrents_in_infohash_order() {
+                let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+                let torrents = u32::try_from(ITERATION_BATCH_SIZE).unwrap() + 1;
+
+                for i in (0..torrents).rev() {
+                    let mut info_hash = [0u8; 20];
+                    info_hash[..4].copy_from_slice(&i.to_be_bytes());
+
+                    let () = in_memory_torrent_repository.upsert_peer(&InfoHash::from_bytes(&info_hash), &leecher());
+                }
+
+                let info_hashes: Vec<InfoHash> = in_memory_torrent_repository
+                    .swarm_metadata_iter()
+                    .map(|(info_hash, _)| info_hash)
+                    .collect();
+
+                assert_eq!(info_hashes.len(), ITERATION_BATCH_SIZE + 1);
+                assert!(info_hashes.windows(2).all(|pair| pair[0] < pair[1]));
+            }
 
             #[tokio::test]
             async fn it_should_get_swarm_metadata_for_an_existing_torrent() {
@@ -75,6 +75,21 @@ impl DatabasePersistentTorrentRepository {
     pub(crate) fn save(&self, info_hash: &InfoHash, downloaded: u32) -> Result<(), Error> {
         self.database.save_persistent_torrent(info_hash, downloaded)
     }
+
+    /// Removes the persistent torrent metric from the database.
+    ///
+    /// It does nothing if the torrent has no persisted metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `info_hash` - The info hash of the torrent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the database operation fails.
+    pub(crate) fn remove(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        self.database.remove_persistent_torrent(info_hash)
+    }
 }
 
 #[cfg(test)]
@@ -123,4 +138,19 @@ mod tests {
 
         assert_eq!(torrents, expected_torrents);
     }
+
+    #[test]
+    fn it_removes_the_numbers_of_downloads_for_a_torrent_from_the_database() {
+        let repository = initialize_db_persistent_torrent_repository();
+
+        let infohash = sample_info_hash();
+
+        repository.save(&infohash, 1).unwrap();
+
+        repository.remove(&infohash).unwrap();
+
+        let torrents = repository.load_all().unwrap();
+
+        assert_eq!(torrents.get(&infohash), None);
+    }
 }
@@ -0,0 +1,259 @@
+//! Selection of the peers returned in the announce responses.
+//!
+//! When a swarm has more peers than an announce response can hold, the
+//! tracker has to choose which ones to return. By default, it returns the
+//! first peers ordered by peer ID ([`PeerSelection::Ordered`]).
+//!
+//! The [`PeerSelection::Locality`] strategy prefers the peers near the client,
+//! so the traffic stays inside the same network when the tracker is hosted by
+//! an ISP or a campus network. A configurable percentage of the response is
+//! filled with random nearby peers, and the rest with random peers from the
+//! whole swarm. A peer is near the client when:
+//!
+//! - Both addresses are in the same `/24` IPv4 network or `/48` IPv6 network.
+//! - Or both addresses are in the same autonomous system, if the `GeoIP`
+//!   database has the ASNs.
+//!
+//! If there are not enough nearby peers, the response is completed with other
+//! peers, and the other way around, so the response is never shorter than
+//! with the default strategy.
+//!
+//! To bound the cost of an announce request in a large swarm, the peers are
+//! chosen from a random sample of [`LOCALITY_CANDIDATES_PER_PEER`] times the
+//! number of peers in the response, so a few nearby peers in a very large
+//! swarm may be missed.
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use rand::seq::SliceRandom;
+use torrust_tracker_configuration::{PeerSelection as PeerSelectionConfig, PeerSelectionStrategy};
+use torrust_tracker_primitives::peer;
+
+use crate::geoip::GeoIp;
+
+/// How many candidates are sampled from the swarm per peer in the response
+/// with the [`PeerSelection::Locality`] strategy.
+pub const LOCALITY_CANDIDATES_PER_PEER: usize = 4;
+
+/// How the peers returned to a client are chosen.
+pub enum PeerSelection<'a> {
+    /// The first peers ordered by peer ID.
+    Ordered,
+
+    /// A percentage of the response is filled with peers near the client.
+    Locality {
+        neighbourhood: Neighbourhood<'a>,
+        local_peers_percentage: u8,
+    },
+}
+
+impl<'a> PeerSelection<'a> {
+    /// Builds the selection for a client from the configuration.
+    #[must_use]
+    pub fn new(config: &PeerSelectionConfig, client_ip: &IpAddr, geoip: &'a GeoIp) -> Self {
+        match config.strategy {
+            PeerSelectionStrategy::Ordered => Self::Ordered,
+            PeerSelectionStrategy::Locality => Self::Locality {
+                neighbourhood: Neighbourhood::new(client_ip, geoip),
+                local_peers_percentage: config.local_peers_percentage,
+            },
+        }
+    }
+
+    /// Chooses up to `limit` peers from the candidates.
+    ///
+    /// With the [`PeerSelection::Ordered`] strategy, the candidates must be
+    /// all the peers that can be returned to the client, ordered by peer ID.
+    /// With the [`PeerSelection::Locality`] strategy, they can be a random
+    /// sample of them.
+    #[must_use]
+    pub fn select(&self, mut candidates: Vec<Arc<peer::Peer>>, limit: usize) -> Vec<Arc<peer::Peer>> {
+        match self {
+            Self::Ordered => {
+                candidates.truncate(limit);
+                candidates
+            }
+            Self::Locality {
+                neighbourhood,
+                local_peers_percentage,
+            } => {
+                let (mut nearby, mut others): (Vec<_>, Vec<_>) = candidates
+                    .into_iter()
+                    .partition(|candidate| neighbourhood.contains(&candidate.peer_addr.ip()));
+
+                let mut rng = rand::rng();
+                nearby.shuffle(&mut rng);
+                others.shuffle(&mut rng);
+
+                let local_peers = limit * usize::from(*local_peers_percentage) / 100;
+
+                // The nearby peers also fill the places the other peers can't.
+                let nearby_peers = nearby.len().min(local_peers.max(limit.saturating_sub(others.len())));
+
+                nearby.truncate(nearby_peers);
+                others.truncate(limit - nearby_peers);
+
+                nearby.append(&mut others);
+                nearby
+            }
+        }
+    }
+}
+
+/// The network area around a client.
+pub struct Neighbourhood<'a> {
+    client_ip: IpAddr,
+
+    /// The autonomous system number of the client, if it's known.
+    client_asn: Option<u32>,
+
+    geoip: &'a GeoIp,
+}
+
+impl<'a> Neighbourhood<'a> {
+    #[must_use]
+    pub fn new(client_ip: &IpAddr, geoip: &'a GeoIp) -> Self {
+        Self {
+            client_ip: *client_ip,
+            client_asn: geoip.asn(client_ip),
+            geoip,
+        }
+    }
+
+    /// Returns `true` if the address is near the client.
+    #[must_use]
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        let is_same_network = match (self.client_ip, ip) {
+            (IpAddr::V4(client_ip), IpAddr::V4(ip)) => client_ip.octets()[..3] == ip.octets()[..3],
+            (IpAddr::V6(client_ip), IpAddr::V6(ip)) => client_ip.segments()[..3] == ip.segments()[..3],
+            _ => false,
+        };
+
+        is_same_network || (self.client_asn.is_some() && self.client_asn == self.geoip.asn(ip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, SocketAddr};
+    use std::sync::Arc;
+
+    use aquatic_udp_protocol::PeerId;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+    use torrust_tracker_primitives::peer::Peer;
+
+    use super::{Neighbourhood, PeerSelection};
+    use crate::geoip::GeoIp;
+    use crate::test_helpers::tests::geoip_configuration;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    /// A synthetic swarm with `nearby` peers in `1.2.3.0/24` and `others`
+    /// peers in other networks, ordered by peer ID.
+    fn swarm(nearby: u8, others: u8) -> Vec<Arc<Peer>> {
+        let nearby = (0..nearby).map(|n| ip(&format!("1.2.3.{n}")));
+        let others = (0..others).map(|n| ip(&format!("5.6.{n}.1")));
+
+        nearby
+            .chain(others)
+            .enumerate()
+            .map(|(n, ip)| {
+                let mut peer_id = *b"-qB00000000000000000";
+                peer_id[12..].copy_from_slice(format!("{n:08}").as_bytes());
+
+                Arc::new(
+                    PeerBuilder::default()
+                        .with_peer_id(&PeerId(peer_id))
+                        .with_peer_addr(&SocketAddr::new(ip, 6881))
+                        .build(),
+                )
+            })
+            .collect()
+    }
+
+    fn locality<'a>(client_ip: &str, geoip: &'a GeoIp, local_peers_percentage: u8) -> PeerSelection<'a> {
+        PeerSelection::Locality {
+            neighbourhood: Neighbourhood::new(&ip(client_ip), geoip),
+            local_peers_percentage,
+        }
+    }
+
+    fn count_nearby(peers: &[Arc<Peer>]) -> usize {
+        peers
+            .iter()
+            .filter(|peer| peer.peer_addr.ip().to_string().starts_with("1.2.3."))
+            .count()
+    }
+
+    #[test]
+    fn it_should_return_the_first_peers_by_peer_id_with_the_ordered_strategy() {
+        let candidates = swarm(5, 5);
+
+        let peers = PeerSelection::Ordered.select(candidates.clone(), 3);
+
+        assert_eq!(peers, candidates[..3].to_vec());
+    }
+
+    #[test]
+    fn it_should_fill_the_configured_percentage_of_the_response_with_nearby_peers() {
+        let geoip = GeoIp::default();
+
+        let peers = locality("1.2.3.200", &geoip, 30).select(swarm(50, 50), 10);
+
+        assert_eq!(peers.len(), 10);
+        assert_eq!(count_nearby(&peers), 3);
+    }
+
+    #[test]
+    fn it_should_fill_the_response_with_nearby_peers_when_there_are_not_enough_other_peers() {
+        let geoip = GeoIp::default();
+
+        let peers = locality("1.2.3.200", &geoip, 30).select(swarm(50, 2), 10);
+
+        assert_eq!(peers.len(), 10);
+        assert_eq!(count_nearby(&peers), 8);
+    }
+
+    #[test]
+    fn it_should_fill_the_response_with_other_peers_when_there_are_not_enough_nearby_peers() {
+        let geoip = GeoIp::default();
+
+        let peers = locality("1.2.3.200", &geoip, 50).select(swarm(1, 50), 10);
+
+        assert_eq!(peers.len(), 10);
+        assert_eq!(count_nearby(&peers), 1);
+    }
+
+    #[test]
+    fn it_should_not_return_more_peers_than_the_candidates() {
+        let geoip = GeoIp::default();
+
+        let peers = locality("1.2.3.200", &geoip, 50).select(swarm(3, 4), 74);
+
+        assert_eq!(peers.len(), 7);
+    }
+
+    #[test]
+    fn it_should_consider_the_peers_in_the_same_ipv6_48_network_as_nearby() {
+        let geoip = GeoIp::default();
+        let neighbourhood = Neighbourhood::new(&ip("2001:db8:1::1"), &geoip);
+
+        assert!(neighbourhood.contains(&ip("2001:db8:1:ffff::1")));
+        assert!(!neighbourhood.contains(&ip("2001:db8:2::1")));
+        assert!(!neighbourhood.contains(&ip("1.2.3.4")));
+    }
+
+    #[test]
+    fn it_should_consider_the_peers_in_the_same_autonomous_system_as_nearby() {
+        let geoip = GeoIp::load(&geoip_configuration()).unwrap();
+
+        // `126.0.0.0/8` is in the AS `17676` in the test database
+        let neighbourhood = Neighbourhood::new(&ip("126.0.0.1"), &geoip);
+
+        assert!(neighbourhood.contains(&ip("126.1.2.3")));
+        assert!(!neighbourhood.contains(&ip("89.160.20.112")));
+        assert!(!neighbourhood.contains(&ip("10.0.0.1")));
+    }
+}
@@ -7,16 +7,22 @@
 //!   peers) about a single torrent.
 //! - [`get_torrents_page`] and [`get_torrents`]: Return summarized data about
 //!   multiple torrents, excluding the peer list.
+//! - [`search_torrents`]: Returns summarized data about the torrents matching
+//!   some [`Filters`], optionally sorted by one of the swarm metrics.
+//! - [`get_torrents_after`]: Returns the full data about a batch of torrents,
+//!   so that all of them can be exported without collecting them at once.
 //!
 //! The full torrent info is represented by the [`Info`] struct, which includes
 //! swarm data (peer list) and aggregate metrics. The [`BasicInfo`] struct
 //! provides similar data but without the list of peers, making it suitable for
 //! bulk queries.
+use std::collections::BinaryHeap;
 use std::sync::Arc;
 
 use bittorrent_primitives::info_hash::InfoHash;
 use torrust_tracker_primitives::pagination::Pagination;
 use torrust_tracker_primitives::peer;
+use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
 use torrust_tracker_torrent_repository::entry::EntrySync;
 
 use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
@@ -71,6 +77,128 @@ pub struct BasicInfo {
     pub leechers: u64,
 }
 
+impl BasicInfo {
+    fn new(info_hash: InfoHash, stats: &SwarmMetadata) -> Self {
+        Self {
+            info_hash,
+            seeders: u64::from(stats.complete),
+            completed: u64::from(stats.downloaded),
+            leechers: u64::from(stats.incomplete),
+        }
+    }
+
+    /// The number of peers in the swarm: seeders and leechers.
+    #[must_use]
+    pub fn peers(&self) -> u64 {
+        self.seeders + self.leechers
+    }
+}
+
+/// The swarm metric used to sort torrents.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SortBy {
+    Seeders,
+    Leechers,
+    Completed,
+    /// Seeders and leechers.
+    Peers,
+}
+
+/// The sorting direction.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Order {
+    Ascending,
+    #[default]
+    Descending,
+}
+
+/// How to sort a list of torrents.
+///
+/// Torrents with the same value for the metric are sorted by infohash.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sorting {
+    pub sort_by: SortBy,
+    pub order: Order,
+}
+
+/// Inclusive ranges for the swarm metrics of the torrents to return.
+///
+/// A `None` bound does not filter torrents.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filters {
+    pub min_seeders: Option<u64>,
+    pub max_seeders: Option<u64>,
+    pub min_leechers: Option<u64>,
+    pub max_leechers: Option<u64>,
+    pub min_completed: Option<u64>,
+    pub max_completed: Option<u64>,
+    pub min_peers: Option<u64>,
+    pub max_peers: Option<u64>,
+}
+
+impl Filters {
+    fn matches(&self, info: &BasicInfo) -> bool {
+        in_range(info.seeders, self.min_seeders, self.max_seeders)
+            && in_range(info.leechers, self.min_leechers, self.max_leechers)
+            && in_range(info.completed, self.min_completed, self.max_completed)
+            && in_range(info.peers(), self.min_peers, self.max_peers)
+    }
+}
+
+fn in_range(value: u64, min: Option<u64>, max: Option<u64>) -> bool {
+    min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max)
+}
+
+/// A torrent with the key used to sort it.
+///
+/// The key is built so that the expected order is always the ascending order
+/// of the key.
+struct Ranked {
+    key: (u64, InfoHash),
+    info: BasicInfo,
+}
+
+impl Ranked {
+    fn new(info: BasicInfo, sorting: Sorting) -> Self {
+        let value = match sorting.sort_by {
+            SortBy::Seeders => info.seeders,
+            SortBy::Leechers => info.leechers,
+            SortBy::Completed => info.completed,
+            SortBy::Peers => info.peers(),
+        };
+
+        let value = match sorting.order {
+            Order::Ascending => value,
+            Order::Descending => u64::MAX - value,
+        };
+
+        Self {
+            key: (value, info.info_hash),
+            info,
+        }
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Ranked {}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
 /// Retrieves complete torrent information for a given torrent.
 ///
 /// This function queries the in-memory torrent repository for a torrent entry
@@ -99,7 +227,7 @@ pub fn get_torrent_info(in_memory_torrent_repository: &Arc<InMemoryTorrentReposi
 
     let peers = torrent_entry.get_peers(None);
 
-    let peers = Some(peers.iter().map(|peer| (**peer)).collect());
+    let peers = Some(peers.iter().map(|peer| **peer).collect());
 
     Some(Info {
         info_hash: *info_hash,
@@ -183,6 +311,148 @@ pub fn get_torrents(in_memory_torrent_repository: &Arc<InMemoryTorrentRepository
     basic_infos
 }
 
+/// Retrieves summarized torrent information for the torrents matching the
+/// `filters`.
+///
+/// Torrents are returned in infohash order, unless a `sorting` is provided.
+/// The `pagination` is applied after filtering and sorting.
+///
+/// All the torrents are scanned, but only the swarm metadata of each one is
+/// read. When sorting, only the torrents up to the end of the requested page
+/// are kept in memory.
+///
+/// # Arguments
+///
+/// * `in_memory_torrent_repository` - A shared reference to the in-memory
+///   torrent repository.
+/// * `filters` - The ranges for the swarm metrics of the returned torrents.
+/// * `sorting` - An optional sorting. Infohash order is used if it's `None`.
+/// * `pagination` - An optional reference to a [`Pagination`] object specifying
+///   offset and limit.
+///
+/// # Returns
+///
+/// A vector of [`BasicInfo`] structs for the matching torrents.
+#[must_use]
+pub fn search_torrents(
+    in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>,
+    filters: &Filters,
+    sorting: Option<Sorting>,
+    pagination: Option<&Pagination>,
+) -> Vec<BasicInfo> {
+    let matching = in_memory_torrent_repository
+        .swarm_metadata_iter()
+        .map(|(info_hash, stats)| BasicInfo::new(info_hash, &stats))
+        .filter(|info| filters.matches(info));
+
+    let (offset, limit) = match pagination {
+        Some(pagination) => (pagination.offset as usize, pagination.limit as usize),
+        None => (0, usize::MAX),
+    };
+
+    let Some(sorting) = sorting else {
+        return matching.skip(offset).take(limit).collect();
+    };
+
+    // Keep only the first `offset + limit` torrents in a max-heap, so that the
+    // last one in the page is always on the top.
+    let capacity = offset.saturating_add(limit);
+
+    let mut top: BinaryHeap<Ranked> = BinaryHeap::new();
+
+    for info in matching {
+        let ranked = Ranked::new(info, sorting);
+
+        if top.len() < capacity {
+            top.push(ranked);
+        } else if top.peek().is_some_and(|last| ranked < *last) {
+            top.pop();
+            top.push(ranked);
+        }
+    }
+
+    top.into_sorted_vec()
+        .into_iter()
+        .skip(offset)
+        .map(|ranked| ranked.info)
+        .collect()
+}
+
+/// Retrieves the torrent information for up to `limit` torrents whose
+/// infohash comes after `after`, in infohash order.
+///
+/// It's meant to walk through all the torrents in batches: the infohash of the
+/// last torrent in a batch is the cursor for the next one. An empty vector
+/// means there are no more torrents.
+///
+/// # Arguments
+///
+/// * `in_memory_torrent_repository` - A shared reference to the in-memory
+///   torrent repository.
+/// * `after` - The cursor, or `None` to start from the first torrent.
+/// * `limit` - The maximum number of torrents to return.
+/// * `with_peers` - Whether to include the list of peers of each torrent.
+///
+/// # Returns
+///
+/// A vector of [`Info`] structs. The `peers` field is `None` when `with_peers`
+/// is `false`.
+#[must_use]
+pub fn get_torrents_after(
+    in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>,
+    after: Option<&InfoHash>,
+    limit: usize,
+    with_peers: bool,
+) -> Vec<Info> {
+    in_memory_torrent_repository
+        .get_after(after, limit)
+        .into_iter()
+        .map(|(info_hash, torrent_entry)| {
+            let stats = torrent_entry.get_swarm_metadata();
+
+            let peers = with_peers.then(|| torrent_entry.get_peers(None).iter().map(|peer| **peer).collect());
+
+            Info {
+                info_hash,
+                seeders: u64::from(stats.complete),
+                completed: u64::from(stats.downloaded),
+                leechers: u64::from(stats.incomplete),
+                peers,
+            }
+        })
+        .collect()
+}
+
+/// Retrieves the torrent information of all the torrents, in infohash order,
+/// as they are at the time of the call.
+///
+/// The swarm metadata of all the torrents is copied in a single pass over the
+/// repository, so the result is not affected by the changes made after the
+/// call. The peers are not included.
+///
+/// # Arguments
+///
+/// * `in_memory_torrent_repository` - A shared reference to the in-memory
+///   torrent repository.
+///
+/// # Returns
+///
+/// A vector of [`Info`] structs with the `peers` field set to `None`.
+#[must_use]
+pub fn get_torrents_snapshot(in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>) -> Vec<Info> {
+    in_memory_torrent_repository
+        .get_swarm_metadata_snapshot()
+        .into_iter()
+        .map(|(info_hash, stats)| Info {
+            info_hash,
+            seeders: u64::from(stats.complete),
+            completed: u64::from(stats.downloaded),
+            leechers: u64::from(stats.incomplete),
+            peers: None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
@@ -375,6 +645,173 @@ mod tests {
         }
     }
 
+    mod searching_for_torrents_with_filters_and_sorting {
+
+        use std::str::FromStr;
+        use std::sync::Arc;
+
+        use aquatic_udp_protocol::{NumberOfBytes, PeerId};
+        use bittorrent_primitives::info_hash::InfoHash;
+
+        use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+        use crate::torrent::services::tests::sample_peer;
+        use crate::torrent::services::{search_torrents, BasicInfo, Filters, Order, Pagination, SortBy, Sorting};
+
+        /// It adds a torrent with the given number of seeders and leechers.
+        fn add_torrent(
+            in_memory_torrent_repository: &InMemoryTorrentRepository,
+            hash: &str,
+            seeders: u8,
+            leechers: u8,
+        ) -> InfoHash {
+            let info_hash = InfoHash::from_str(hash).unwrap();
+
+            for i in 0..seeders + leechers {
+                let mut peer = sample_peer();
+                peer.peer_id = PeerId([i; 20]);
+                if i >= seeders {
+                    peer.left = NumberOfBytes::new(1000);
+                }
+
+                let () = in_memory_torrent_repository.upsert_peer(&info_hash, &peer);
+            }
+
+            info_hash
+        }
+
+        fn sample_repository() -> (Arc<InMemoryTorrentRepository>, Vec<InfoHash>) {
+            let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+            let info_hashes = vec![
+                add_torrent(
+                    &in_memory_torrent_repository,
+                    "0000000000000000000000000000000000000001",
+                    3,
+                    0,
+                ), // DevSkim: ignore DS173237
+                add_torrent(
+                    &in_memory_torrent_repository,
+                    "0000000000000000000000000000000000000002",
+                    0,
+                    2,
+                ), // DevSkim: ignore DS173237
+                add_torrent(
+                    &in_memory_torrent_repository,
+                    "0000000000000000000000000000000000000003",
+                    1,
+                    4,
+                ), // DevSkim: ignore DS173237
+            ];
+
+            (in_memory_torrent_repository, info_hashes)
+        }
+
+        fn info_hashes_of(torrents: &[BasicInfo]) -> Vec<InfoHash> {
+            torrents.iter().map(|torrent| torrent.info_hash).collect()
+        }
+
+        #[tokio::test]
+        async fn it_should_return_all_torrents_in_info_hash_order_without_filters_nor_sorting() {
+            let (in_memory_torrent_repository, info_hashes) = sample_repository();
+
+            let torrents = search_torrents(&in_memory_torrent_repository, &Filters::default(), None, None);
+
+            assert_eq!(info_hashes_of(&torrents), info_hashes);
+        }
+
+        #[tokio::test]
+        async fn it_should_sort_the_torrents_by_seeders_in_descending_order() {
+            let (in_memory_torrent_repository, info_hashes) = sample_repository();
+
+            let torrents = search_torrents(
+                &in_memory_torrent_repository,
+                &Filters::default(),
+                Some(Sorting {
+                    sort_by: SortBy::Seeders,
+                    order: Order::Descending,
+                }),
+                None,
+            );
+
+            assert_eq!(
+                info_hashes_of(&torrents),
+                vec![info_hashes[0], info_hashes[2], info_hashes[1]]
+            );
+        }
+
+        #[tokio::test]
+        async fn it_should_sort_the_torrents_by_peers_in_ascending_order() {
+            let (in_memory_torrent_repository, info_hashes) = sample_repository();
+
+            let torrents = search_torrents(
+                &in_memory_torrent_repository,
+                &Filters::default(),
+                Some(Sorting {
+                    sort_by: SortBy::Peers,
+                    order: Order::Ascending,
+                }),
+                None,
+            );
+
+            assert_eq!(
+                info_hashes_of(&torrents),
+                vec![info_hashes[1], info_hashes[0], info_hashes[2]]
+            );
+        }
+
+        #[tokio::test]
+        async fn it_should_paginate_the_sorted_torrents() {
+            let (in_memory_torrent_repository, info_hashes) = sample_repository();
+
+            let torrents = search_torrents(
+                &in_memory_torrent_repository,
+                &Filters::default(),
+                Some(Sorting {
+                    sort_by: SortBy::Leechers,
+                    order: Order::Descending,
+                }),
+                Some(&Pagination::new(1, 1)),
+            );
+
+            assert_eq!(info_hashes_of(&torrents), vec![info_hashes[1]]);
+        }
+
+        #[tokio::test]
+        async fn it_should_return_the_torrents_without_seeders_but_with_active_leechers() {
+            let (in_memory_torrent_repository, info_hashes) = sample_repository();
+
+            let torrents = search_torrents(
+                &in_memory_torrent_repository,
+                &Filters {
+                    max_seeders: Some(0),
+                    min_leechers: Some(1),
+                    ..Default::default()
+                },
+                None,
+                None,
+            );
+
+            assert_eq!(info_hashes_of(&torrents), vec![info_hashes[1]]);
+        }
+
+        #[tokio::test]
+        async fn it_should_paginate_the_filtered_torrents() {
+            let (in_memory_torrent_repository, info_hashes) = sample_repository();
+
+            let torrents = search_torrents(
+                &in_memory_torrent_repository,
+                &Filters {
+                    min_seeders: Some(1),
+                    ..Default::default()
+                },
+                None,
+                Some(&Pagination::new(1, 10)),
+            );
+
+            assert_eq!(info_hashes_of(&torrents), vec![info_hashes[2]]);
+        }
+    }
+
     mod getting_basic_torrent_info_for_multiple_torrents_at_once {
 
         use std::sync::Arc;
@@ -414,4 +851,77 @@ mod tests {
             );
         }
     }
+
+    mod getting_torrents_in_batches {
+
+        use std::str::FromStr;
+        use std::sync::Arc;
+
+        use bittorrent_primitives::info_hash::InfoHash;
+
+        use crate::torrent::repository::in_memory::InMemoryTorrentRepository;
+        use crate::torrent::services::tests::sample_peer;
+        use crate::torrent::services::{get_torrents_after, get_torrents_snapshot};
+
+        fn sample_repository() -> (Arc<InMemoryTorrentRepository>, Vec<InfoHash>) {
+            let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+            let info_hashes: Vec<InfoHash> = [
+                "0000000000000000000000000000000000000001",
+                "0000000000000000000000000000000000000002",
+                "0000000000000000000000000000000000000003",
+            ]
+            .iter()
+            .map(|hash| InfoHash::from_str(hash).unwrap())
+            .collect();
+
+            for info_hash in &info_hashes {
+                let () = in_memory_torrent_repository.upsert_peer(info_hash, &sample_peer());
+            }
+
+            (in_memory_torrent_repository, info_hashes)
+        }
+
+        #[tokio::test]
+        async fn it_should_return_the_torrents_after_the_cursor_in_infohash_order() {
+            let (in_memory_torrent_repository, info_hashes) = sample_repository();
+
+            let first_batch = get_torrents_after(&in_memory_torrent_repository, None, 2, false);
+
+            assert_eq!(
+                first_batch.iter().map(|info| info.info_hash).collect::<Vec<_>>(),
+                info_hashes[..2].to_vec()
+            );
+
+            let second_batch = get_torrents_after(&in_memory_torrent_repository, Some(&info_hashes[1]), 2, false);
+
+            assert_eq!(
+                second_batch.iter().map(|info| info.info_hash).collect::<Vec<_>>(),
+                info_hashes[2..].to_vec()
+            );
+
+            assert!(get_torrents_after(&in_memory_torrent_repository, Some(&info_hashes[2]), 2, false).is_empty());
+        }
+
+        #[tokio::test]
+        async fn it_should_include_the_peers_only_when_requested() {
+            let (in_memory_torrent_repository, _info_hashes) = sample_repository();
+
+            let without_peers = get_torrents_after(&in_memory_torrent_repository, None, 1, false);
+            let with_peers = get_torrents_after(&in_memory_torrent_repository, None, 1, true);
+
+            assert_eq!(without_peers[0].peers, None);
+            assert_eq!(with_peers[0].peers, Some(vec![sample_peer()]));
+        }
+
+        #[tokio::test]
+        async fn it_should_return_a_snapshot_of_all_the_torrents_in_infohash_order() {
+            let (in_memory_torrent_repository, info_hashes) = sample_repository();
+
+            let snapshot = get_torrents_snapshot(&in_memory_torrent_repository);
+
+            assert_eq!(snapshot.iter().map(|info| info.info_hash).collect::<Vec<_>>(), info_hashes);
+            assert!(snapshot.iter().all(|info| info.seeders == 1 && info.peers.is_none()));
+        }
+    }
 }
@@ -0,0 +1,193 @@
+//! Running counts of the active peers by client and location.
+//!
+//! The [`PeersTally`] is notified by the torrent repository of every peer
+//! added to or removed from a swarm. The client of a peer is parsed from its
+//! peer ID, and its address is located in the `GeoIP` databases, only when
+//! the peer is added or removed, and when it announces from a new address.
+//! That way, the [`ClientsMetrics`] and [`LocationsMetrics`] of all the
+//! torrents can be read without walking all the peers.
+//!
+//! The lookups use the databases loaded when the tracker starts, which do not
+//! change while it's running, so a removed peer is always found in the same
+//! location it was counted in.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use torrust_tracker_primitives::client_metrics::{Client, ClientsMetrics};
+use torrust_tracker_primitives::peer;
+use torrust_tracker_torrent_repository::tally::PeerTally;
+
+use crate::geoip::{Asn, GeoIp, LocationsMetrics};
+
+/// The number of active peers by client and location.
+#[derive(Default)]
+pub struct PeersTally {
+    /// The service used to locate the peers. The peers are not counted by
+    /// location when it's disabled.
+    geoip: Arc<GeoIp>,
+
+    clients: DashMap<Client, u64>,
+
+    countries: DashMap<Option<String>, u64>,
+
+    asns: DashMap<Option<Asn>, u64>,
+}
+
+impl PeersTally {
+    #[must_use]
+    pub fn new(geoip: &Arc<GeoIp>) -> Self {
+        Self {
+            geoip: geoip.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// The number of active peers by client software.
+    #[must_use]
+    pub fn clients_metrics(&self) -> ClientsMetrics {
+        ClientsMetrics {
+            peers: collect(&self.clients),
+        }
+    }
+
+    /// The number of active peers by country and ASN. It's empty when the
+    /// `GeoIP` service is disabled.
+    #[must_use]
+    pub fn locations_metrics(&self) -> LocationsMetrics {
+        LocationsMetrics {
+            countries: collect(&self.countries),
+            asns: collect(&self.asns),
+        }
+    }
+
+    fn add_location(&self, peer: &peer::Peer) {
+        if self.geoip.is_enabled() {
+            let location = self.geoip.locate(&peer.peer_addr.ip());
+
+            increment(&self.countries, location.country);
+            increment(&self.asns, location.asn);
+        }
+    }
+
+    fn remove_location(&self, peer: &peer::Peer) {
+        if self.geoip.is_enabled() {
+            let location = self.geoip.locate(&peer.peer_addr.ip());
+
+            decrement(&self.countries, &location.country);
+            decrement(&self.asns, &location.asn);
+        }
+    }
+}
+
+impl PeerTally for PeersTally {
+    fn add(&self, peer: &peer::Peer) {
+        increment(&self.clients, Client::from_peer_id(&peer.peer_id));
+
+        self.add_location(peer);
+    }
+
+    fn remove(&self, peer: &peer::Peer) {
+        decrement(&self.clients, &Client::from_peer_id(&peer.peer_id));
+
+        self.remove_location(peer);
+    }
+
+    fn update(&self, previous: &peer::Peer, current: &peer::Peer) {
+        // The peer ID, and so the client, of a peer does not change.
+        if previous.peer_addr.ip() != current.peer_addr.ip() {
+            self.remove_location(previous);
+            self.add_location(current);
+        }
+    }
+}
+
+impl fmt::Debug for PeersTally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeersTally")
+            .field("clients", &self.clients)
+            .field("countries", &self.countries)
+            .field("asns", &self.asns)
+            .finish_non_exhaustive()
+    }
+}
+
+fn increment<K: Eq + Hash>(counts: &DashMap<K, u64>, key: K) {
+    *counts.entry(key).or_default() += 1;
+}
+
+fn decrement<K: Eq + Hash>(counts: &DashMap<K, u64>, key: &K) {
+    if let Some(mut count) = counts.get_mut(key) {
+        *count = count.saturating_sub(1);
+    }
+
+    // The keys without peers are removed so they are not reported.
+    counts.remove_if(key, |_key, count| *count == 0);
+}
+
+fn collect<K: Eq + Hash + Ord + Clone>(counts: &DashMap<K, u64>) -> BTreeMap<K, u64> {
+    counts.iter().map(|count| (count.key().clone(), *count.value())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, SocketAddr};
+    use std::sync::Arc;
+
+    use aquatic_udp_protocol::PeerId;
+    use torrust_tracker_primitives::peer::Peer;
+    use torrust_tracker_torrent_repository::tally::PeerTally;
+
+    use super::PeersTally;
+    use crate::geoip::GeoIp;
+    use crate::test_helpers::tests::{geoip_configuration, sample_peer};
+
+    fn peer(peer_id: &[u8; 20], ip: &str) -> Peer {
+        Peer {
+            peer_id: PeerId(*peer_id),
+            peer_addr: SocketAddr::new(ip.parse::<IpAddr>().unwrap(), 8080),
+            ..sample_peer()
+        }
+    }
+
+    #[test]
+    fn it_should_stop_counting_the_clients_and_locations_of_the_removed_peers() {
+        let tally = PeersTally::new(&Arc::new(GeoIp::load(&geoip_configuration()).unwrap()));
+
+        let sweden = peer(b"-TR4040-xxxxxxxxxxxx", "89.160.20.112");
+        let britain = peer(b"-TR4040-yyyyyyyyyyyy", "81.2.69.142");
+
+        tally.add(&sweden);
+        tally.add(&britain);
+        tally.remove(&sweden);
+
+        assert_eq!(tally.clients_metrics().peers.values().copied().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(tally.locations_metrics().top_countries(), vec![(Some(&"GB".to_string()), 1)]);
+    }
+
+    #[test]
+    fn it_should_move_a_peer_that_announces_from_a_new_address_to_its_new_location() {
+        let tally = PeersTally::new(&Arc::new(GeoIp::load(&geoip_configuration()).unwrap()));
+
+        let previous = peer(b"-TR4040-xxxxxxxxxxxx", "89.160.20.112");
+        let current = peer(b"-TR4040-xxxxxxxxxxxx", "81.2.69.142");
+
+        tally.add(&previous);
+        tally.update(&previous, &current);
+
+        assert_eq!(tally.clients_metrics().peers.values().copied().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(tally.locations_metrics().top_countries(), vec![(Some(&"GB".to_string()), 1)]);
+    }
+
+    #[test]
+    fn it_should_not_count_the_locations_when_the_geoip_service_is_disabled() {
+        let tally = PeersTally::default();
+
+        tally.add(&peer(b"-TR4040-xxxxxxxxxxxx", "89.160.20.112"));
+
+        assert!(tally.locations_metrics().countries.is_empty());
+        assert_eq!(tally.clients_metrics().peers.len(), 1);
+    }
+}
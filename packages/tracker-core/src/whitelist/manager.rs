@@ -9,6 +9,8 @@ use bittorrent_primitives::info_hash::InfoHash;
 use super::repository::in_memory::InMemoryWhitelist;
 use super::repository::persisted::DatabaseWhitelist;
 use crate::databases;
+use crate::event::{Bus, Event};
+
 /// Manages the whitelist of allowed torrents.
 ///
 /// This structure handles both the in-memory and persistent representations of
@@ -20,6 +22,9 @@ pub struct WhitelistManager {
 
     /// The persisted list of allowed torrents.
     database_whitelist: Arc<DatabaseWhitelist>,
+
+    /// Bus where the whitelist changes are published.
+    events: Arc<Bus>,
 }
 
 impl WhitelistManager {
@@ -30,15 +35,21 @@ impl WhitelistManager {
     /// - `database_whitelist`: Persistent database-backed whitelist repository.
     /// - `in_memory_whitelist`: In-memory whitelist repository for fast runtime
     ///   access.
+    /// - `events`: The bus where the whitelist changes are published.
     ///
     /// # Returns
     ///
     /// A new `WhitelistManager` instance.
     #[must_use]
-    pub fn new(database_whitelist: Arc<DatabaseWhitelist>, in_memory_whitelist: Arc<InMemoryWhitelist>) -> Self {
+    pub fn new(
+        database_whitelist: Arc<DatabaseWhitelist>,
+        in_memory_whitelist: Arc<InMemoryWhitelist>,
+        events: &Arc<Bus>,
+    ) -> Self {
         Self {
             in_memory_whitelist,
             database_whitelist,
+            events: events.clone(),
         }
     }
 
@@ -52,6 +63,7 @@ impl WhitelistManager {
     pub async fn add_torrent_to_whitelist(&self, info_hash: &InfoHash) -> Result<(), databases::error::Error> {
         self.database_whitelist.add(info_hash)?;
         self.in_memory_whitelist.add(info_hash).await;
+        self.events.publish(Event::TorrentWhitelisted { info_hash: *info_hash });
         Ok(())
     }
 
@@ -65,6 +77,8 @@ impl WhitelistManager {
     pub async fn remove_torrent_from_whitelist(&self, info_hash: &InfoHash) -> Result<(), databases::error::Error> {
         self.database_whitelist.remove(info_hash)?;
         self.in_memory_whitelist.remove(info_hash).await;
+        self.events
+            .publish(Event::TorrentRemovedFromWhitelist { info_hash: *info_hash });
         Ok(())
     }
 
@@ -84,6 +98,8 @@ impl WhitelistManager {
             let _: bool = self.in_memory_whitelist.add(&info_hash).await;
         }
 
+        self.events.publish(Event::WhitelistReloaded);
+
         Ok(())
     }
 }
@@ -118,7 +134,11 @@ mod tests {
         let database_whitelist = Arc::new(DatabaseWhitelist::new(database.clone()));
         let in_memory_whitelist = Arc::new(InMemoryWhitelist::default());
 
-        let whitelist_manager = Arc::new(WhitelistManager::new(database_whitelist.clone(), in_memory_whitelist.clone()));
+        let whitelist_manager = Arc::new(WhitelistManager::new(
+            database_whitelist.clone(),
+            in_memory_whitelist.clone(),
+            &Arc::default(),
+        ));
 
         (
             whitelist_manager,
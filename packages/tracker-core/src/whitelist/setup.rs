@@ -8,6 +8,7 @@ use super::manager::WhitelistManager;
 use super::repository::in_memory::InMemoryWhitelist;
 use super::repository::persisted::DatabaseWhitelist;
 use crate::databases::Database;
+use crate::event::Bus;
 
 /// Initializes the `WhitelistManager` by combining in-memory and database
 /// repositories.
@@ -26,6 +27,7 @@ use crate::databases::Database;
 ///   sed for persistent whitelist storage.
 /// * `in_memory_whitelist` - An `Arc<InMemoryWhitelist>` representing the in-memory
 ///   whitelist repository for fast access.
+/// * `events` - The bus where the whitelist changes are published.
 ///
 /// # Returns
 ///
@@ -35,7 +37,8 @@ use crate::databases::Database;
 pub fn initialize_whitelist_manager(
     database: Arc<Box<dyn Database>>,
     in_memory_whitelist: Arc<InMemoryWhitelist>,
+    events: &Arc<Bus>,
 ) -> Arc<WhitelistManager> {
     let database_whitelist = Arc::new(DatabaseWhitelist::new(database));
-    Arc::new(WhitelistManager::new(database_whitelist, in_memory_whitelist))
+    Arc::new(WhitelistManager::new(database_whitelist, in_memory_whitelist, events))
 }
@@ -0,0 +1,249 @@
+//! Generator of `tests/fixtures/geoip-test.mmdb`, the GeoIP database used by
+//! the tests.
+//!
+//! The database follows the [MaxMind DB format](https://maxmind.github.io/MaxMind-DB/)
+//! with the `country.iso_code`, `autonomous_system_number` and
+//! `autonomous_system_organization` fields the tracker reads. The networks are
+//! a few of the ones used by the `MaxMind` test databases.
+//!
+//! The test checks that the checked-in database is the one generated here. To
+//! change the database, edit the [`NETWORKS`] and run:
+//!
+//! ```text
+//! UPDATE_GEOIP_FIXTURE=1 cargo test -p bittorrent-tracker-core --test geoip_fixture
+//! ```
+use std::net::IpAddr;
+
+/// The networks in the database: network, prefix length, country, ASN and
+/// ASN organization.
+const NETWORKS: [(&str, u8, &str, u64, &str); 5] = [
+    ("81.2.69.0", 24, "GB", 20712, "Andrews & Arnold Ltd"),
+    ("89.160.20.0", 24, "SE", 29518, "Bredband2 AB"),
+    ("126.0.0.0", 8, "JP", 17676, "SoftBank Corp."),
+    ("2001:218::", 32, "JP", 2914, "NTT America, Inc."),
+    ("2a02:c7c::", 32, "GB", 5607, "Sky UK Limited"),
+];
+
+const FIXTURE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/geoip-test.mmdb");
+
+const RECORD_SIZE: u64 = 24;
+
+const DATA_SECTION_SEPARATOR: [u8; 16] = [0; 16];
+
+const METADATA_MARKER: &[u8] = b"\xab\xcd\xefMaxMind.com";
+
+/// A value of the data section.
+enum Value {
+    String(String),
+    Uint16(u64),
+    Uint32(u64),
+    Uint64(u64),
+    Map(Vec<(&'static str, Value)>),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn type_number(&self) -> u8 {
+        match self {
+            Value::String(_) => 2,
+            Value::Uint16(_) => 5,
+            Value::Uint32(_) => 6,
+            Value::Map(_) => 7,
+            Value::Uint64(_) => 9,
+            Value::Array(_) => 11,
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let (size, payload) = match self {
+            Value::String(string) => (string.len(), string.as_bytes().to_vec()),
+            Value::Uint16(number) | Value::Uint32(number) | Value::Uint64(number) => {
+                let bytes = number.to_be_bytes();
+                let payload = bytes[(number.leading_zeros() / 8) as usize..].to_vec();
+                (payload.len(), payload)
+            }
+            Value::Map(fields) => (
+                fields.len(),
+                fields
+                    .iter()
+                    .flat_map(|(key, field)| [Value::String((*key).to_string()).encode(), field.encode()].concat())
+                    .collect(),
+            ),
+            Value::Array(items) => (items.len(), items.iter().flat_map(Value::encode).collect()),
+        };
+
+        [control(self.type_number(), size), payload].concat()
+    }
+}
+
+/// The control byte(s) of a value with the given type and size.
+fn control(type_number: u8, size: usize) -> Vec<u8> {
+    let (prefix, extra) = if size < 29 {
+        (size, vec![])
+    } else if size < 285 {
+        (29, vec![u8::try_from(size - 29).unwrap()])
+    } else if size < 65_821 {
+        (30, u16::try_from(size - 285).unwrap().to_be_bytes().to_vec())
+    } else {
+        (31, u32::try_from(size - 65_821).unwrap().to_be_bytes()[1..].to_vec())
+    };
+
+    let prefix = u8::try_from(prefix).unwrap();
+
+    if type_number <= 7 {
+        [vec![type_number << 5 | prefix], extra].concat()
+    } else {
+        [vec![prefix, type_number - 7], extra].concat()
+    }
+}
+
+fn record(country: &str, asn: u64, organization: &str) -> Value {
+    Value::Map(vec![
+        ("country", Value::Map(vec![("iso_code", Value::String(country.to_string()))])),
+        ("autonomous_system_number", Value::Uint32(asn)),
+        ("autonomous_system_organization", Value::String(organization.to_string())),
+    ])
+}
+
+fn metadata(node_count: u64) -> Value {
+    Value::Map(vec![
+        ("binary_format_major_version", Value::Uint16(2)),
+        ("binary_format_minor_version", Value::Uint16(0)),
+        ("build_epoch", Value::Uint64(1_735_689_600)),
+        ("database_type", Value::String("Torrust-Tracker-GeoIP-Test".to_string())),
+        (
+            "description",
+            Value::Map(vec![(
+                "en",
+                Value::String("Test database for the Torrust Tracker GeoIP enrichment".to_string()),
+            )]),
+        ),
+        ("ip_version", Value::Uint16(6)),
+        ("languages", Value::Array(vec![Value::String("en".to_string())])),
+        ("node_count", Value::Uint32(node_count)),
+        ("record_size", Value::Uint16(RECORD_SIZE)),
+    ])
+}
+
+/// The bits of the network prefix in the IPv6 search tree.
+fn prefix_bits(network: &str, prefix_length: u8) -> Vec<usize> {
+    let (address, length) = match network.parse::<IpAddr>().unwrap() {
+        // The IPv4 networks live in the `::/96` subtree of the IPv6 tree.
+        IpAddr::V4(address) => (u128::from(u32::from(address)), prefix_length + 96),
+        IpAddr::V6(address) => (u128::from(address), prefix_length),
+    };
+
+    (0..length)
+        .map(|bit| usize::from((address >> (127 - bit)) & 1 == 1))
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum Child {
+    Empty,
+    Node(usize),
+    /// The index of the network in the [`NETWORKS`].
+    Network(usize),
+}
+
+/// It returns the search tree nodes numbered depth-first, left before right.
+fn build_tree() -> Vec<[Child; 2]> {
+    let mut nodes = vec![[Child::Empty; 2]];
+
+    for (index, (network, prefix_length, ..)) in NETWORKS.iter().enumerate() {
+        let bits = prefix_bits(network, *prefix_length);
+        let (last_bit, bits) = bits.split_last().unwrap();
+
+        let mut node = 0;
+
+        for bit in bits {
+            node = match nodes[node][*bit] {
+                Child::Node(next) => next,
+                _ => {
+                    nodes.push([Child::Empty; 2]);
+                    nodes[node][*bit] = Child::Node(nodes.len() - 1);
+                    nodes.len() - 1
+                }
+            };
+        }
+
+        nodes[node][*last_bit] = Child::Network(index);
+    }
+
+    let mut order = vec![];
+    let mut pending = vec![0];
+
+    while let Some(node) = pending.pop() {
+        order.push(node);
+
+        for child in nodes[node].iter().rev() {
+            if let Child::Node(next) = child {
+                pending.push(*next);
+            }
+        }
+    }
+
+    let mut numbers = vec![0; nodes.len()];
+
+    for (number, node) in order.iter().enumerate() {
+        numbers[*node] = number;
+    }
+
+    order
+        .iter()
+        .map(|node| {
+            nodes[*node].map(|child| match child {
+                Child::Node(next) => Child::Node(numbers[next]),
+                other => other,
+            })
+        })
+        .collect()
+}
+
+fn build_database() -> Vec<u8> {
+    let nodes = build_tree();
+    let node_count = nodes.len() as u64;
+
+    let mut tree = vec![];
+    let mut data = vec![];
+
+    for node in &nodes {
+        for child in node {
+            let pointer = match child {
+                Child::Empty => node_count,
+                Child::Node(number) => *number as u64,
+                Child::Network(index) => {
+                    let pointer = node_count + DATA_SECTION_SEPARATOR.len() as u64 + data.len() as u64;
+                    let (_, _, country, asn, organization) = NETWORKS[*index];
+                    data.extend(record(country, asn, organization).encode());
+                    pointer
+                }
+            };
+
+            tree.extend(&pointer.to_be_bytes()[8 - (RECORD_SIZE / 8) as usize..]);
+        }
+    }
+
+    [
+        tree,
+        DATA_SECTION_SEPARATOR.to_vec(),
+        data,
+        METADATA_MARKER.to_vec(),
+        metadata(node_count).encode(),
+    ]
+    .concat()
+}
+
+#[test]
+fn the_geoip_test_database_should_be_the_generated_one() {
+    let database = build_database();
+
+    if std::env::var_os("UPDATE_GEOIP_FIXTURE").is_some() {
+        std::fs::write(FIXTURE_PATH, &database).unwrap();
+    }
+
+    assert!(
+        std::fs::read(FIXTURE_PATH).unwrap() == database,
+        "the GeoIP test database is outdated, run `UPDATE_GEOIP_FIXTURE=1 cargo test -p bittorrent-tracker-core --test geoip_fixture`"
+    );
+}
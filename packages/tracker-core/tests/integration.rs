@@ -78,6 +78,8 @@ impl Container {
             config,
             &in_memory_torrent_repository,
             &db_torrent_repository,
+            &Arc::default(),
+            &Arc::default(),
         ));
         let scrape_handler = Arc::new(ScrapeHandler::new(&whitelist_authorization, &in_memory_torrent_repository));
 
@@ -102,10 +104,10 @@ async fn test_announce_and_scrape_requests() {
 
     // First announce: download started
     peer.event = AnnounceEvent::Started;
-    let announce_data =
-        container
-            .announce_handler
-            .announce(&info_hash, &mut peer, &remote_client_ip(), &PeersWanted::AsManyAsPossible);
+    let announce_data = container
+        .announce_handler
+        .announce(&info_hash, &mut peer, &remote_client_ip(), &PeersWanted::AsManyAsPossible)
+        .unwrap();
 
     // NOTICE: you don't get back the peer making the request.
     assert_eq!(announce_data.peers.len(), 0);
@@ -113,10 +115,10 @@ async fn test_announce_and_scrape_requests() {
 
     // Second announce: download completed
     peer.event = AnnounceEvent::Completed;
-    let announce_data =
-        container
-            .announce_handler
-            .announce(&info_hash, &mut peer, &remote_client_ip(), &PeersWanted::AsManyAsPossible);
+    let announce_data = container
+        .announce_handler
+        .announce(&info_hash, &mut peer, &remote_client_ip(), &PeersWanted::AsManyAsPossible)
+        .unwrap();
 
     assert_eq!(announce_data.peers.len(), 0);
     assert_eq!(announce_data.stats.downloaded, 1);
@@ -20,17 +20,29 @@
 //! - Torrent cleaner: it removes inactive peers and (optionally) peerless torrents.
 //! - UDP trackers: the user can enable multiple UDP tracker on several ports.
 //! - HTTP trackers: the user can enable multiple HTTP tracker on several ports.
+//!
+//! UDP and HTTP trackers are started as [`Listeners`], so they can also be
+//! started, stopped and restarted while the application is running.
 //! - Tracker REST API: the tracker API can be enabled/disabled.
+//!
+//! On Unix systems, a job also reloads the configuration when the process
+//! receives a `SIGHUP` signal.
 use std::sync::Arc;
 
 use tokio::task::JoinHandle;
 use torrust_tracker_configuration::Configuration;
 use tracing::instrument;
 
-use crate::bootstrap::jobs::{health_check_api, http_tracker, torrent_cleanup, tracker_apis, udp_tracker};
-use crate::container::{AppContainer, HttpApiContainer, HttpTrackerContainer, UdpTrackerContainer};
+#[cfg(unix)]
+use crate::bootstrap::jobs::config_reload;
+use crate::bootstrap::jobs::{
+    full_scrape, health_check_api, metrics_events, metrics_history, torrent_cleanup, tracker_apis, webhooks,
+};
+use crate::container::{AppContainer, HttpApiContainer};
 use crate::servers;
+use crate::servers::listeners::Listeners;
 use crate::servers::registar::Registar;
+use crate::servers::signals::global_shutdown_signal;
 
 /// # Panics
 ///
@@ -38,6 +50,8 @@ use crate::servers::registar::Registar;
 ///
 /// - Can't retrieve tracker keys from database.
 /// - Can't load whitelist from database.
+/// - Can't load the client rules from database.
+/// - The webhooks configuration is not valid.
 #[instrument(skip(config, app_container))]
 pub async fn start(config: &Configuration, app_container: &Arc<AppContainer>) -> Vec<JoinHandle<()>> {
     if config.http_api.is_none()
@@ -69,6 +83,23 @@ pub async fn start(config: &Configuration, app_container: &Arc<AppContainer>) ->
             .expect("Could not load whitelist from database.");
     }
 
+    // Load client rules
+    app_container
+        .client_policy_manager
+        .load_rules_from_database()
+        .await
+        .expect("Could not load client rules from database.");
+
+    // Start the webhooks before the trackers, so they get the first events
+    if let Some(webhooks_config) = &config.webhooks {
+        jobs.push(
+            webhooks::start_job(webhooks_config, &app_container.events)
+                .expect("Could not start the webhooks: invalid configuration."),
+        );
+    }
+
+    let listeners = Arc::new(Listeners::new(app_container, &registar));
+
     // Start the UDP blocks
     if let Some(udp_trackers) = &config.udp_trackers {
         for udp_tracker_config in udp_trackers {
@@ -78,10 +109,10 @@ pub async fn start(config: &Configuration, app_container: &Arc<AppContainer>) ->
                     udp_tracker_config.bind_address
                 );
             } else {
-                let udp_tracker_config = Arc::new(udp_tracker_config.clone());
-                let udp_tracker_container = Arc::new(UdpTrackerContainer::from_app_container(&udp_tracker_config, app_container));
-
-                jobs.push(udp_tracker::start_job(udp_tracker_container, registar.give_form()).await);
+                listeners
+                    .add_udp(udp_tracker_config.clone())
+                    .await
+                    .expect("it should be able to start the udp tracker");
             }
         }
     } else {
@@ -91,27 +122,39 @@ pub async fn start(config: &Configuration, app_container: &Arc<AppContainer>) ->
     // Start the HTTP blocks
     if let Some(http_trackers) = &config.http_trackers {
         for http_tracker_config in http_trackers {
-            let http_tracker_config = Arc::new(http_tracker_config.clone());
-            let http_tracker_container = Arc::new(HttpTrackerContainer::from_app_container(&http_tracker_config, app_container));
-
-            if let Some(job) =
-                http_tracker::start_job(http_tracker_container, registar.give_form(), servers::http::Version::V1).await
-            {
-                jobs.push(job);
-            }
+            listeners
+                .add_http(http_tracker_config.clone())
+                .await
+                .expect("it should be able to start to the http tracker");
         }
     } else {
         tracing::info!("No HTTP blocks in configuration");
     }
 
+    // Wait for the UDP and HTTP trackers to shutdown
+    jobs.push(tokio::spawn({
+        let listeners = listeners.clone();
+        async move {
+            global_shutdown_signal().await;
+            listeners.wait_for_shutdown().await;
+        }
+    }));
+
     // Start HTTP API
     if let Some(http_api_config) = &config.http_api {
         let http_api_config = Arc::new(http_api_config.clone());
-        let http_api_container = Arc::new(HttpApiContainer::from_app_container(&http_api_config, app_container));
+        let http_api_container = Arc::new(HttpApiContainer::from_app_container(
+            &http_api_config,
+            app_container,
+            &listeners,
+        ));
 
         if let Some(job) = tracker_apis::start_job(http_api_container, registar.give_form(), servers::apis::Version::V1).await {
             jobs.push(job);
         }
+
+        // Start runner to publish the metric changes to the API event stream
+        jobs.push(metrics_events::start_job(app_container));
     } else {
         tracing::info!("No API block in configuration");
     }
@@ -121,6 +164,24 @@ pub async fn start(config: &Configuration, app_container: &Arc<AppContainer>) ->
         jobs.push(torrent_cleanup::start_job(&config.core, &app_container.torrents_manager));
     }
 
+    // Start runner to dump the full scrape, every interval
+    if let Some(full_scrape_config) = &config.full_scrape {
+        jobs.push(full_scrape::start_job(
+            full_scrape_config,
+            &app_container.in_memory_torrent_repository,
+            &app_container.full_scrape_repository,
+        ));
+    }
+
+    // Start runner to record the metrics history, every interval
+    if config.metrics_history.is_some() {
+        jobs.push(metrics_history::start_job(app_container));
+    }
+
+    // Start runner to reload the configuration on SIGHUP
+    #[cfg(unix)]
+    jobs.push(config_reload::start_job(&app_container.config_reloader));
+
     // Start Health Check API
     jobs.push(health_check_api::start_job(&config.health_check_api, registar.entries()).await);
 
@@ -18,7 +18,12 @@ use bittorrent_tracker_core::authentication::handler::KeysHandler;
 use bittorrent_tracker_core::authentication::key::repository::in_memory::InMemoryKeyRepository;
 use bittorrent_tracker_core::authentication::key::repository::persisted::DatabaseKeyRepository;
 use bittorrent_tracker_core::authentication::service;
+use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
+use bittorrent_tracker_core::client_policy::repository::in_memory::InMemoryClientRules;
+use bittorrent_tracker_core::client_policy::setup::initialize_client_policy_manager;
 use bittorrent_tracker_core::databases::setup::initialize_database;
+use bittorrent_tracker_core::event::Bus;
+use bittorrent_tracker_core::geoip::GeoIp;
 use bittorrent_tracker_core::scrape_handler::ScrapeHandler;
 use bittorrent_tracker_core::torrent::manager::TorrentsManager;
 use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
@@ -32,10 +37,11 @@ use torrust_tracker_configuration::validator::Validator;
 use torrust_tracker_configuration::Configuration;
 use tracing::instrument;
 
-use super::config::initialize_configuration;
+use super::config::{initialize_configuration, Reloader};
 use crate::bootstrap;
 use crate::container::AppContainer;
-use crate::packages::{http_tracker_core, udp_tracker_core};
+use crate::packages::tracker_api_core::statistics::history::History;
+use crate::packages::{full_scrape, http_tracker_core, udp_tracker_core};
 use crate::servers::udp::server::banning::BanService;
 use crate::servers::udp::server::launcher::MAX_CONNECTION_ID_ERRORS_PER_IP;
 use crate::shared::crypto::ephemeral_instance_keys;
@@ -87,6 +93,11 @@ pub fn initialize_global_services(configuration: &Configuration) {
 }
 
 /// It initializes the IoC Container.
+///
+/// # Panics
+///
+/// Will panic if the saved metrics history or the `GeoIP` databases can't be
+/// loaded.
 #[instrument(skip())]
 pub fn initialize_app_container(configuration: &Configuration) -> AppContainer {
     let core_config = Arc::new(configuration.core.clone());
@@ -103,11 +114,16 @@ pub fn initialize_app_container(configuration: &Configuration) -> AppContainer {
     let udp_stats_event_sender = Arc::new(udp_stats_event_sender);
     let udp_stats_repository = Arc::new(udp_stats_repository);
 
-    let ban_service = Arc::new(RwLock::new(BanService::new(MAX_CONNECTION_ID_ERRORS_PER_IP)));
+    let events = Arc::new(Bus::default());
+
+    let ban_service = Arc::new(RwLock::new(BanService::new(MAX_CONNECTION_ID_ERRORS_PER_IP, &events)));
     let database = initialize_database(&configuration.core);
     let in_memory_whitelist = Arc::new(InMemoryWhitelist::default());
     let whitelist_authorization = Arc::new(WhitelistAuthorization::new(&configuration.core, &in_memory_whitelist.clone()));
-    let whitelist_manager = initialize_whitelist_manager(database.clone(), in_memory_whitelist.clone());
+    let whitelist_manager = initialize_whitelist_manager(database.clone(), in_memory_whitelist.clone(), &events);
+    let in_memory_client_rules = Arc::new(InMemoryClientRules::default());
+    let client_authorization = Arc::new(ClientAuthorization::new(&in_memory_client_rules));
+    let client_policy_manager = initialize_client_policy_manager(database.clone(), in_memory_client_rules);
     let db_key_repository = Arc::new(DatabaseKeyRepository::new(&database));
     let in_memory_key_repository = Arc::new(InMemoryKeyRepository::default());
     let authentication_service = Arc::new(service::AuthenticationService::new(
@@ -117,24 +133,52 @@ pub fn initialize_app_container(configuration: &Configuration) -> AppContainer {
     let keys_handler = Arc::new(KeysHandler::new(
         &db_key_repository.clone(),
         &in_memory_key_repository.clone(),
+        &events,
     ));
-    let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+    let geoip = Arc::new(match &configuration.core.geoip {
+        Some(geoip_config) => GeoIp::load(geoip_config).expect("Could not load the GeoIP database."),
+        None => GeoIp::default(),
+    });
+    let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::new(&configuration.core.memory_limits, &geoip));
     let db_torrent_repository = Arc::new(DatabasePersistentTorrentRepository::new(&database));
 
     let torrents_manager = Arc::new(TorrentsManager::new(
         &configuration.core,
         &in_memory_torrent_repository,
         &db_torrent_repository,
+        &events,
     ));
 
     let announce_handler = Arc::new(AnnounceHandler::new(
         &configuration.core,
         &in_memory_torrent_repository,
         &db_torrent_repository,
+        &events,
+        &geoip,
     ));
 
     let scrape_handler = Arc::new(ScrapeHandler::new(&whitelist_authorization, &in_memory_torrent_repository));
 
+    let metrics_history = Arc::new(match &configuration.metrics_history {
+        Some(metrics_history_config) => History::load(metrics_history_config).expect("Could not load the metrics history."),
+        None => History::default(),
+    });
+
+    let http_api_access_tokens = Arc::new(RwLock::new(
+        configuration
+            .http_api
+            .as_ref()
+            .map(|http_api| http_api.access_tokens.clone())
+            .unwrap_or_default(),
+    ));
+
+    let config_reloader = Arc::new(Reloader::new(
+        configuration,
+        &announce_handler,
+        &torrents_manager,
+        &http_api_access_tokens,
+    ));
+
     AppContainer {
         core_config,
         database,
@@ -144,6 +188,8 @@ pub fn initialize_app_container(configuration: &Configuration) -> AppContainer {
         authentication_service,
         in_memory_whitelist,
         whitelist_authorization,
+        client_authorization,
+        client_policy_manager,
         ban_service,
         http_stats_event_sender,
         udp_stats_event_sender,
@@ -153,6 +199,13 @@ pub fn initialize_app_container(configuration: &Configuration) -> AppContainer {
         in_memory_torrent_repository,
         db_torrent_repository,
         torrents_manager,
+        http_api_access_tokens,
+        config_reloader,
+        full_scrape_config: configuration.full_scrape.clone().map(Arc::new),
+        full_scrape_repository: Arc::new(full_scrape::Repository::default()),
+        events,
+        metrics_history,
+        geoip,
     }
 }
 
@@ -1,11 +1,54 @@
 //! Initialize configuration from file or env var.
 //!
 //! All environment variables are prefixed with `TORRUST_TRACKER_`.
+//!
+//! The configuration can also be reloaded while the tracker is running with
+//! the [`Reloader`]. Only some settings can be changed without restarting the
+//! tracker:
+//!
+//! - The announce policy: `core.announce_policy`.
+//! - The adaptive announce interval: `core.adaptive_interval`.
+//! - The early announce policy: `core.early_announce_policy`.
+//! - The peer address policy: `core.peer_address_policy`.
+//! - The peer selection strategy: `core.peer_selection`.
+//! - The tracker policy: `core.tracker_policy`.
+//! - The external IP: `core.net.external_ip`.
+//! - The API access tokens: `http_api.access_tokens`.
+//! - The logging threshold: `logging.threshold`.
+//!
+//! Changes in any other setting, for example whether the tracker is behind a
+//! reverse proxy (`core.net.on_reverse_proxy`), are reported as requiring a
+//! restart, and they are ignored until the tracker is restarted. The
+//! hot-swappable settings in the same configuration are still applied.
+use std::sync::Arc;
 
-use torrust_tracker_configuration::{Configuration, Info};
+use bittorrent_tracker_core::announce_handler::AnnounceHandler;
+use bittorrent_tracker_core::torrent::manager::TorrentsManager;
+use serde_json::Value;
+use thiserror::Error;
+use tokio::sync::{Mutex, RwLock};
+use torrust_tracker_configuration::validator::{SemanticValidationError, Validator};
+use torrust_tracker_configuration::{AccessTokens, Configuration, Info};
+
+use crate::bootstrap::logging;
 
 pub const DEFAULT_PATH_CONFIG: &str = "./share/default/config/tracker.development.sqlite3.toml";
 
+/// Settings that can be changed without restarting the tracker.
+///
+/// A changed setting is hot-swappable if its path starts with one of these.
+const HOT_SWAPPABLE_SETTINGS: [&str; 9] = [
+    "core.announce_policy",
+    "core.adaptive_interval",
+    "core.early_announce_policy",
+    "core.peer_address_policy",
+    "core.peer_selection",
+    "core.tracker_policy",
+    "core.net.external_ip",
+    "http_api.access_tokens",
+    "logging.threshold",
+];
+
 /// It loads the application configuration from the environment.
 ///
 /// There are two methods to inject the configuration:
@@ -23,8 +66,195 @@ pub const DEFAULT_PATH_CONFIG: &str = "./share/default/config/tracker.developmen
 /// `./tracker.toml` file or the env var `TORRUST_TRACKER_CONFIG_TOML`.
 #[must_use]
 pub fn initialize_configuration() -> Configuration {
-    let info = Info::new(DEFAULT_PATH_CONFIG.to_string()).expect("info to load configuration is not valid");
-    Configuration::load(&info).expect("error loading configuration from sources")
+    load_configuration().expect("error loading configuration from sources")
+}
+
+/// It loads the application configuration from the same sources used by
+/// [`initialize_configuration`].
+///
+/// # Errors
+///
+/// Will return an error if it can't load the configuration.
+pub fn load_configuration() -> Result<Configuration, torrust_tracker_configuration::Error> {
+    let info = Info::new(DEFAULT_PATH_CONFIG.to_string())?;
+    Configuration::load(&info)
+}
+
+/// Errors that can occur reloading the configuration.
+#[derive(Error, Debug)]
+pub enum ReloadError {
+    #[error("Unable to load the new configuration: {source}")]
+    UnableToLoad { source: torrust_tracker_configuration::Error },
+
+    #[error("Invalid new configuration: {source}")]
+    InvalidConfiguration { source: SemanticValidationError },
+}
+
+/// The settings that changed after reloading the configuration.
+///
+/// Settings are identified by their path in the configuration, for example:
+/// `core.announce_policy.interval`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// Changed settings that have been applied to the running tracker.
+    pub applied: Vec<String>,
+    /// Changed settings that will only be applied after restarting the
+    /// tracker.
+    pub requires_restart: Vec<String>,
+}
+
+/// It applies a new configuration to the running tracker.
+///
+/// It keeps the configuration the tracker is running with, so that it can
+/// tell which settings have changed.
+pub struct Reloader {
+    configuration: Mutex<Configuration>,
+    announce_handler: Arc<AnnounceHandler>,
+    torrents_manager: Arc<TorrentsManager>,
+    http_api_access_tokens: Arc<RwLock<AccessTokens>>,
+}
+
+impl Reloader {
+    #[must_use]
+    pub fn new(
+        configuration: &Configuration,
+        announce_handler: &Arc<AnnounceHandler>,
+        torrents_manager: &Arc<TorrentsManager>,
+        http_api_access_tokens: &Arc<RwLock<AccessTokens>>,
+    ) -> Self {
+        Self {
+            configuration: Mutex::new(configuration.clone()),
+            announce_handler: announce_handler.clone(),
+            torrents_manager: torrents_manager.clone(),
+            http_api_access_tokens: http_api_access_tokens.clone(),
+        }
+    }
+
+    /// It loads the configuration again from its sources and applies it.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the new configuration can't be loaded or it's
+    /// not valid. In that case, the running tracker is not changed.
+    pub async fn reload_from_sources(&self) -> Result<ReloadReport, ReloadError> {
+        let new_configuration = load_configuration().map_err(|source| ReloadError::UnableToLoad { source })?;
+
+        self.reload(new_configuration).await
+    }
+
+    /// It applies the hot-swappable settings of a new configuration.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the new configuration is not valid. In that
+    /// case, the running tracker is not changed.
+    pub async fn reload(&self, new_configuration: Configuration) -> Result<ReloadReport, ReloadError> {
+        new_configuration
+            .validate()
+            .map_err(|source| ReloadError::InvalidConfiguration { source })?;
+
+        let mut configuration = self.configuration.lock().await;
+
+        let changed = changed_settings(&configuration, &new_configuration);
+
+        let mut report = ReloadReport::default();
+
+        for setting in changed {
+            if !is_hot_swappable(&setting) {
+                report.requires_restart.push(setting);
+                continue;
+            }
+
+            if setting.starts_with("logging.") && !logging::reload_threshold(&new_configuration.logging.threshold) {
+                report.requires_restart.push(setting);
+                continue;
+            }
+
+            report.applied.push(setting);
+        }
+
+        if report.applied.iter().any(|setting| setting.starts_with("core.")) {
+            self.announce_handler.reload(&new_configuration.core);
+            self.torrents_manager.reload(&new_configuration.core);
+
+            configuration.core.announce_policy = new_configuration.core.announce_policy;
+            configuration.core.adaptive_interval = new_configuration.core.adaptive_interval;
+            configuration.core.early_announce_policy = new_configuration.core.early_announce_policy;
+            configuration.core.peer_address_policy = new_configuration.core.peer_address_policy;
+            configuration.core.peer_selection = new_configuration.core.peer_selection;
+            configuration.core.tracker_policy = new_configuration.core.tracker_policy.clone();
+            configuration.core.net.external_ip = new_configuration.core.net.external_ip;
+        }
+
+        if report.applied.iter().any(|setting| setting.starts_with("http_api.")) {
+            if let (Some(http_api), Some(new_http_api)) = (&mut configuration.http_api, &new_configuration.http_api) {
+                http_api.access_tokens.clone_from(&new_http_api.access_tokens);
+
+                *self.http_api_access_tokens.write().await = new_http_api.access_tokens.clone();
+            }
+        }
+
+        if report.applied.iter().any(|setting| setting.starts_with("logging.")) {
+            configuration.logging.threshold = new_configuration.logging.threshold.clone();
+        }
+
+        tracing::info!(
+            "Configuration reloaded. Applied: {:?}. Requires restart: {:?}",
+            report.applied,
+            report.requires_restart
+        );
+
+        Ok(report)
+    }
+}
+
+fn is_hot_swappable(setting: &str) -> bool {
+    HOT_SWAPPABLE_SETTINGS
+        .iter()
+        .any(|hot_swappable| setting == *hot_swappable || setting.starts_with(&format!("{hot_swappable}.")))
+}
+
+/// It returns the paths of the settings that are different in both
+/// configurations.
+fn changed_settings(current: &Configuration, new: &Configuration) -> Vec<String> {
+    let current = serde_json::to_value(current).expect("the configuration should be serializable");
+    let new = serde_json::to_value(new).expect("the configuration should be serializable");
+
+    let mut changed = vec![];
+
+    collect_changed_settings(&current, &new, "", &mut changed);
+
+    changed
+}
+
+fn collect_changed_settings(current: &Value, new: &Value, path: &str, changed: &mut Vec<String>) {
+    match (current, new) {
+        (Value::Object(current_fields), Value::Object(new_fields)) => {
+            let keys = current_fields
+                .keys()
+                .chain(new_fields.keys().filter(|key| !current_fields.contains_key(*key)));
+
+            for key in keys {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+
+                collect_changed_settings(
+                    current_fields.get(key).unwrap_or(&Value::Null),
+                    new_fields.get(key).unwrap_or(&Value::Null),
+                    &path,
+                    changed,
+                );
+            }
+        }
+        (current, new) => {
+            if current != new {
+                changed.push(path.to_string());
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -36,4 +266,135 @@ mod tests {
 
         drop(initialize_configuration());
     }
+
+    mod the_configuration_reloader {
+        use torrust_tracker_configuration::{AccessToken, AnnouncePolicy, EarlyAnnouncePolicy, PeerSelectionStrategy};
+        use torrust_tracker_test_helpers::configuration::ephemeral_public;
+
+        use crate::bootstrap::app::initialize_app_container;
+
+        #[tokio::test]
+        async fn it_should_apply_the_hot_swappable_settings() {
+            let configuration = ephemeral_public();
+            let app_container = initialize_app_container(&configuration);
+
+            let mut new_configuration = configuration.clone();
+            new_configuration.core.announce_policy = AnnouncePolicy::new(60, 30);
+            new_configuration.core.tracker_policy.max_peer_timeout = 60;
+
+            let report = app_container.config_reloader.reload(new_configuration).await.unwrap();
+
+            assert_eq!(
+                report.applied,
+                vec![
+                    "core.announce_policy.interval".to_string(),
+                    "core.announce_policy.interval_min".to_string(),
+                    "core.tracker_policy.max_peer_timeout".to_string(),
+                ]
+            );
+            assert!(report.requires_restart.is_empty());
+        }
+
+        #[tokio::test]
+        async fn it_should_replace_the_api_access_tokens() {
+            let configuration = ephemeral_public();
+            let app_container = initialize_app_container(&configuration);
+
+            let mut new_configuration = configuration.clone();
+            new_configuration
+                .http_api
+                .as_mut()
+                .unwrap()
+                .add_token("admin", "NewAccessToken");
+
+            app_container.config_reloader.reload(new_configuration).await.unwrap();
+
+            assert_eq!(
+                app_container
+                    .http_api_access_tokens
+                    .read()
+                    .await
+                    .get("admin")
+                    .map(AccessToken::token),
+                Some("NewAccessToken")
+            );
+        }
+
+        #[tokio::test]
+        async fn it_should_report_the_changed_settings_that_require_a_restart() {
+            let configuration = ephemeral_public();
+            let app_container = initialize_app_container(&configuration);
+
+            let mut new_configuration = configuration.clone();
+            new_configuration.core.inactive_peer_cleanup_interval += 1;
+            new_configuration.core.listed = !configuration.core.listed;
+
+            let report = app_container.config_reloader.reload(new_configuration.clone()).await.unwrap();
+
+            assert!(report.applied.is_empty());
+            assert_eq!(
+                report.requires_restart,
+                vec!["core.inactive_peer_cleanup_interval".to_string(), "core.listed".to_string(),]
+            );
+
+            // They are still reported until the tracker is restarted
+            let report = app_container.config_reloader.reload(new_configuration).await.unwrap();
+
+            assert_eq!(report.requires_restart.len(), 2);
+        }
+
+        #[tokio::test]
+        async fn it_should_apply_the_announce_policy_settings() {
+            let configuration = ephemeral_public();
+            let app_container = initialize_app_container(&configuration);
+
+            let mut new_configuration = configuration.clone();
+            new_configuration.core.early_announce_policy = EarlyAnnouncePolicy::Reject;
+            new_configuration.core.peer_selection.strategy = PeerSelectionStrategy::Locality;
+
+            let report = app_container.config_reloader.reload(new_configuration).await.unwrap();
+
+            assert_eq!(
+                report.applied,
+                vec![
+                    "core.early_announce_policy".to_string(),
+                    "core.peer_selection.strategy".to_string()
+                ]
+            );
+            assert!(report.requires_restart.is_empty());
+        }
+
+        #[tokio::test]
+        async fn it_should_report_that_changing_whether_the_tracker_is_on_a_reverse_proxy_requires_a_restart() {
+            let configuration = ephemeral_public();
+            let app_container = initialize_app_container(&configuration);
+
+            let mut new_configuration = configuration.clone();
+            new_configuration.core.announce_policy = AnnouncePolicy::new(60, 30);
+            new_configuration.core.net.on_reverse_proxy = !configuration.core.net.on_reverse_proxy;
+
+            let report = app_container.config_reloader.reload(new_configuration).await.unwrap();
+
+            assert_eq!(
+                report.applied,
+                vec![
+                    "core.announce_policy.interval".to_string(),
+                    "core.announce_policy.interval_min".to_string(),
+                ]
+            );
+            assert_eq!(report.requires_restart, vec!["core.net.on_reverse_proxy".to_string()]);
+        }
+
+        #[tokio::test]
+        async fn it_should_reject_an_invalid_configuration() {
+            let configuration = ephemeral_public();
+            let app_container = initialize_app_container(&configuration);
+
+            let mut new_configuration = configuration.clone();
+            new_configuration.core.private = false;
+            new_configuration.core.private_mode = Some(torrust_tracker_configuration::v2_0_0::core::PrivateMode::default());
+
+            assert!(app_container.config_reloader.reload(new_configuration).await.is_err());
+        }
+    }
 }
@@ -0,0 +1,53 @@
+//! Job that reloads the configuration when the process receives a `SIGHUP`
+//! signal.
+//!
+//! Only the hot-swappable settings are applied to the running tracker. Refer
+//! to the [`Reloader`] for more info about which settings can be changed
+//! without restarting the tracker.
+use std::sync::Arc;
+
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::task::JoinHandle;
+use tracing::instrument;
+
+use crate::bootstrap::config::Reloader;
+
+/// It starts a job that reloads the configuration from its sources on every
+/// `SIGHUP` signal.
+///
+/// # Panics
+///
+/// It would panic if the `SIGHUP` signal handler can't be registered.
+#[must_use]
+#[instrument(skip(config_reloader))]
+pub fn start_job(config_reloader: &Arc<Reloader>) -> JoinHandle<()> {
+    let config_reloader = config_reloader.clone();
+
+    let mut hangup = signal(SignalKind::hangup()).expect("it should register the SIGHUP signal handler");
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("Stopping configuration reload job..");
+                    break;
+                }
+                _ = hangup.recv() => {
+                    tracing::info!("SIGHUP received. Reloading configuration..");
+
+                    match config_reloader.reload_from_sources().await {
+                        Ok(report) => {
+                            if !report.requires_restart.is_empty() {
+                                tracing::warn!(
+                                    "These configuration changes require a restart: {:?}",
+                                    report.requires_restart
+                                );
+                            }
+                        }
+                        Err(err) => tracing::error!("Failed to reload the configuration: {err}"),
+                    }
+                }
+            }
+        }
+    })
+}
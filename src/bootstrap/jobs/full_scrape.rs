@@ -0,0 +1,81 @@
+//! Job that periodically dumps the full scrape of all the torrents.
+//!
+//! It runs when the configuration includes a `[full_scrape]` section. The
+//! first dump is generated as soon as the job starts, and then every
+//! `interval` seconds.
+//!
+//! Each dump is written to the configured directory and stored in the
+//! [`Repository`], where the HTTP trackers get it from.
+//!
+//! Refer to the [`full_scrape`](crate::packages::full_scrape) module for more
+//! information about the dump formats.
+use std::sync::Arc;
+use std::time::Duration;
+
+use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use torrust_tracker_configuration::FullScrape;
+use tracing::instrument;
+
+use crate::packages::full_scrape::{Dump, Repository};
+
+/// It starts the job that generates the full scrape dump every `interval`
+/// seconds.
+#[must_use]
+#[instrument(skip(config, in_memory_torrent_repository, repository))]
+pub fn start_job(
+    config: &FullScrape,
+    in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>,
+    repository: &Arc<Repository>,
+) -> JoinHandle<()> {
+    let weak_in_memory_torrent_repository = Arc::downgrade(in_memory_torrent_repository);
+    let repository = repository.clone();
+    let interval = config.interval;
+    let path = config.path.clone();
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval));
+
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("Stopping full scrape job..");
+                    break;
+                }
+                _ = interval.tick() => {
+                    let Some(in_memory_torrent_repository) = weak_in_memory_torrent_repository.upgrade() else {
+                        break;
+                    };
+
+                    let start_time = Utc::now().time();
+
+                    let result = tokio::task::spawn_blocking({
+                        let repository = repository.clone();
+                        let path = path.clone();
+
+                        move || -> std::io::Result<Option<usize>> {
+                            let dump = Dump::generate(&in_memory_torrent_repository)?;
+                            let torrents = dump.torrents;
+
+                            dump.write_to(&path)?;
+
+                            Ok(repository.update(dump).then_some(torrents))
+                        }
+                    })
+                    .await;
+
+                    match result {
+                        Ok(Ok(Some(torrents))) => tracing::info!(
+                            "Dumped the full scrape of {torrents} torrents in: {}ms",
+                            (Utc::now().time() - start_time).num_milliseconds()
+                        ),
+                        Ok(Ok(None)) => tracing::debug!("The full scrape did not change"),
+                        Ok(Err(err)) => tracing::error!("Unable to dump the full scrape to {path}: {err}"),
+                        Err(err) => tracing::error!("The full scrape dump task failed: {err}"),
+                    }
+                }
+            }
+        }
+    })
+}
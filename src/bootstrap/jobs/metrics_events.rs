@@ -0,0 +1,136 @@
+//! Job that publishes the changes of the tracker metrics to the event bus.
+//!
+//! Every second, while someone is subscribed to the
+//! [event bus](bittorrent_tracker_core::event), it compares the tracker
+//! metrics with the previous ones and publishes an
+//! [`Event::Metrics`] with the metrics that have changed.
+//!
+//! The metrics have the same names as in the
+//! [`stats`](crate::servers::apis::v1::context::stats) API endpoint.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bittorrent_tracker_core::event::{Bus, Event};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tracing::instrument;
+
+use crate::container::AppContainer;
+use crate::packages::tracker_api_core::statistics::services::get_metrics;
+use crate::servers::apis::v1::context::stats::resources::Stats;
+
+/// How often the metrics are compared.
+pub const METRICS_EVENTS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// It starts the job that publishes the metric deltas.
+#[must_use]
+#[instrument(skip(app_container))]
+pub fn start_job(app_container: &Arc<AppContainer>) -> JoinHandle<()> {
+    let events = Arc::downgrade(&app_container.events);
+    let in_memory_torrent_repository = app_container.in_memory_torrent_repository.clone();
+    let announce_handler = app_container.announce_handler.clone();
+    let ban_service = app_container.ban_service.clone();
+    let http_stats_repository = app_container.http_stats_repository.clone();
+    let udp_stats_repository = app_container.udp_stats_repository.clone();
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(METRICS_EVENTS_INTERVAL);
+        let mut previous: Option<BTreeMap<String, u64>> = None;
+
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("Stopping metrics events job..");
+                    break;
+                }
+                _ = interval.tick() => {
+                    let Some(events) = events.upgrade() else {
+                        break;
+                    };
+
+                    // Deltas are only meaningful for the current subscribers.
+                    if !events.has_subscribers() {
+                        previous = None;
+                        continue;
+                    }
+
+                    let metrics = get_metrics(
+                        in_memory_torrent_repository.clone(),
+                        announce_handler.clone(),
+                        ban_service.clone(),
+                        http_stats_repository.clone(),
+                        udp_stats_repository.clone(),
+                    )
+                    .await;
+
+                    let current = flatten(&Stats::from(metrics));
+
+                    if let Some(previous) = &previous {
+                        publish_deltas(&events, previous, &current);
+                    }
+
+                    previous = Some(current);
+                }
+            }
+        }
+    })
+}
+
+fn publish_deltas(events: &Bus, previous: &BTreeMap<String, u64>, current: &BTreeMap<String, u64>) {
+    let deltas = deltas(previous, current);
+
+    if !deltas.is_empty() {
+        events.publish(Event::Metrics { deltas });
+    }
+}
+
+/// It returns the metrics of the stats resource by name.
+fn flatten(stats: &Stats) -> BTreeMap<String, u64> {
+    match serde_json::to_value(stats) {
+        Ok(Value::Object(fields)) => fields
+            .into_iter()
+            .filter_map(|(name, value)| value.as_u64().map(|value| (name, value)))
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// It returns the difference of the metrics that have changed.
+#[allow(clippy::cast_possible_wrap)]
+fn deltas(previous: &BTreeMap<String, u64>, current: &BTreeMap<String, u64>) -> BTreeMap<String, i64> {
+    current
+        .iter()
+        .filter_map(|(name, value)| {
+            let previous = previous.get(name).copied().unwrap_or_default();
+
+            (*value != previous).then(|| (name.clone(), *value as i64 - previous as i64))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::deltas;
+
+    #[test]
+    fn it_should_only_include_the_metrics_that_have_changed() {
+        let previous = BTreeMap::from([
+            ("torrents".to_string(), 2),
+            ("seeders".to_string(), 5),
+            ("leechers".to_string(), 1),
+        ]);
+        let current = BTreeMap::from([
+            ("torrents".to_string(), 3),
+            ("seeders".to_string(), 4),
+            ("leechers".to_string(), 1),
+        ]);
+
+        assert_eq!(
+            deltas(&previous, &current),
+            BTreeMap::from([("torrents".to_string(), 1), ("seeders".to_string(), -1)])
+        );
+    }
+}
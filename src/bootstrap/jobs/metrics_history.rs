@@ -0,0 +1,83 @@
+//! Job that records the historical metrics.
+//!
+//! It runs when the configuration includes a `[metrics_history]` section.
+//! Every `interval` seconds it adds a [`Sample`] of the tracker metrics to the
+//! [`History`] and saves the history to its file.
+//!
+//! Refer to the [`history`](crate::packages::tracker_api_core::statistics::history)
+//! module for more information about the samples.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use torrust_tracker_clock::clock::Time;
+use tracing::instrument;
+
+use crate::container::AppContainer;
+use crate::packages::tracker_api_core::statistics::history::{Counters, History, Sample};
+use crate::packages::tracker_api_core::statistics::services::get_metrics;
+use crate::CurrentClock;
+
+/// It starts the job that takes a sample of the metrics every `interval`
+/// seconds.
+#[must_use]
+#[instrument(skip(app_container))]
+pub fn start_job(app_container: &Arc<AppContainer>) -> JoinHandle<()> {
+    let weak_history = Arc::downgrade(&app_container.metrics_history);
+    let in_memory_torrent_repository = app_container.in_memory_torrent_repository.clone();
+    let announce_handler = app_container.announce_handler.clone();
+    let ban_service = app_container.ban_service.clone();
+    let http_stats_repository = app_container.http_stats_repository.clone();
+    let udp_stats_repository = app_container.udp_stats_repository.clone();
+    let period = Duration::from_secs(app_container.metrics_history.interval());
+
+    tokio::spawn(async move {
+        // The first sample is taken after a whole interval.
+        let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+
+        // The counters start at zero when the tracker starts.
+        let mut previous = Counters::default();
+        let mut start = CurrentClock::now().as_secs();
+
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("Stopping metrics history job..");
+                    break;
+                }
+                _ = interval.tick() => {
+                    let Some(history) = weak_history.upgrade() else {
+                        break;
+                    };
+
+                    let metrics = get_metrics(
+                        in_memory_torrent_repository.clone(),
+                        announce_handler.clone(),
+                        ban_service.clone(),
+                        http_stats_repository.clone(),
+                        udp_stats_repository.clone(),
+                    )
+                    .await;
+
+                    let now = CurrentClock::now().as_secs();
+
+                    history.push(Sample::new(start, now, &previous, &metrics));
+
+                    previous = Counters::from(&metrics);
+                    start = now;
+
+                    save(history).await;
+                }
+            }
+        }
+    })
+}
+
+async fn save(history: Arc<History>) {
+    match tokio::task::spawn_blocking(move || history.save()).await {
+        Ok(Ok(())) => tracing::debug!("Saved the metrics history"),
+        Ok(Err(err)) => tracing::error!("Unable to save the metrics history: {err}"),
+        Err(err) => tracing::error!("The metrics history task failed: {err}"),
+    }
+}
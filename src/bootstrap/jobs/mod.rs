@@ -6,11 +6,15 @@
 //! 2. Launch all the application services as concurrent jobs.
 //!
 //! This modules contains all the functions needed to start those jobs.
+#[cfg(unix)]
+pub mod config_reload;
+pub mod full_scrape;
 pub mod health_check_api;
-pub mod http_tracker;
+pub mod metrics_events;
+pub mod metrics_history;
 pub mod torrent_cleanup;
 pub mod tracker_apis;
-pub mod udp_tracker;
+pub mod webhooks;
 
 /// This is the message that the "launcher" spawned task sends to the main
 /// application process to notify the service was successfully started.
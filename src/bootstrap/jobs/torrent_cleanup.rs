@@ -5,12 +5,19 @@
 //! **Inactive peers** are peers that have not been updated for more than `max_peer_timeout` seconds.
 //! `max_peer_timeout` is a customizable core tracker option.
 //!
+//! Inactive peers are removed incrementally every second, in small batches,
+//! so the cleanup never locks all the torrents at once. Only the peers that
+//! have actually expired are visited.
+//!
 //! If the core tracker configuration option `remove_peerless_torrents` is true, the cleanup job will also
-//! remove **peerless torrents** which are torrents with an empty peer list.
+//! remove **peerless torrents** which are torrents with an empty peer list. Torrents left without peers
+//! because their peers expired are removed right away. The rest (for example, torrents whose peers
+//! all announced the `stopped` event) are removed every `inactive_peer_cleanup_interval` seconds.
 //!
 //! Refer to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration) for more info about those options.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use bittorrent_tracker_core::torrent::manager::TorrentsManager;
 use chrono::Utc;
@@ -18,9 +25,13 @@ use tokio::task::JoinHandle;
 use torrust_tracker_configuration::Core;
 use tracing::instrument;
 
+/// How often the job looks for expired peers.
+const PEER_EXPIRY_INTERVAL: Duration = Duration::from_secs(1);
+
 /// It starts a jobs for cleaning up the torrent data in the tracker.
 ///
-/// The cleaning task is executed on an `inactive_peer_cleanup_interval`.
+/// Inactive peers are expired every second. Peerless torrents are removed on
+/// an `inactive_peer_cleanup_interval`.
 ///
 /// Refer to [`torrust-tracker-configuration documentation`](https://docs.rs/torrust-tracker-configuration) for more info about that option.
 #[must_use]
@@ -34,18 +45,28 @@ pub fn start_job(config: &Core, torrents_manager: &Arc<TorrentsManager>) -> Join
         let mut interval = tokio::time::interval(interval);
         interval.tick().await;
 
+        let mut expiry_interval = tokio::time::interval(PEER_EXPIRY_INTERVAL);
+        expiry_interval.tick().await;
+
         loop {
             tokio::select! {
                 _ = tokio::signal::ctrl_c() => {
                     tracing::info!("Stopping torrent cleanup job..");
                     break;
                 }
+                _ = expiry_interval.tick() => {
+                    if let Some(torrents_manager) = weak_torrents_manager.upgrade() {
+                        expire_inactive_peers(&torrents_manager).await;
+                    } else {
+                        break;
+                    }
+                }
                 _ = interval.tick() => {
                     if let Some(torrents_manager) = weak_torrents_manager.upgrade() {
                         let start_time = Utc::now().time();
-                        tracing::info!("Cleaning up torrents..");
-                        torrents_manager.cleanup_torrents();
-                        tracing::info!("Cleaned up torrents in: {}ms", (Utc::now().time() - start_time).num_milliseconds());
+                        tracing::info!("Cleaning up peerless torrents..");
+                        torrents_manager.remove_peerless_torrents();
+                        tracing::info!("Cleaned up peerless torrents in: {}ms", (Utc::now().time() - start_time).num_milliseconds());
                     } else {
                         break;
                     }
@@ -54,3 +75,27 @@ pub fn start_job(config: &Core, torrents_manager: &Arc<TorrentsManager>) -> Join
         }
     })
 }
+
+/// It removes expired peers batch by batch, yielding to other tasks between
+/// batches.
+async fn expire_inactive_peers(torrents_manager: &TorrentsManager) {
+    let mut peers = 0;
+    let mut torrents = 0;
+
+    loop {
+        let expired = torrents_manager.expire_inactive_peers();
+
+        peers += expired.peers;
+        torrents += expired.torrents.len();
+
+        if expired.finished {
+            break;
+        }
+
+        tokio::task::yield_now().await;
+    }
+
+    if peers > 0 {
+        tracing::debug!("Expired {peers} inactive peers and removed {torrents} peerless torrents");
+    }
+}
@@ -24,6 +24,7 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 
 use axum_server::tls_rustls::RustlsConfig;
+use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use torrust_tracker_configuration::AccessTokens;
 use tracing::instrument;
@@ -66,7 +67,7 @@ pub async fn start_job(
         .await
         .map(|tls| tls.expect("it should have a valid tracker api tls configuration"));
 
-    let access_tokens = Arc::new(http_api_container.http_api_config.access_tokens.clone());
+    let access_tokens = http_api_container.access_tokens.clone();
 
     match version {
         Version::V1 => Some(start_v1(bind_to, tls, http_api_container, form, access_tokens).await),
@@ -80,7 +81,7 @@ async fn start_v1(
     tls: Option<RustlsConfig>,
     http_api_container: Arc<HttpApiContainer>,
     form: ServiceRegistrationForm,
-    access_tokens: Arc<AccessTokens>,
+    access_tokens: Arc<RwLock<AccessTokens>>,
 ) -> JoinHandle<()> {
     let server = ApiServer::new(Launcher::new(socket, tls))
         .start(http_api_container, form, access_tokens)
@@ -103,6 +104,7 @@ mod tests {
     use crate::bootstrap::jobs::tracker_apis::start_job;
     use crate::container::HttpApiContainer;
     use crate::servers::apis::Version;
+    use crate::servers::listeners::Listeners;
     use crate::servers::registar::Registar;
 
     #[tokio::test]
@@ -114,11 +116,19 @@ mod tests {
 
         let app_container = Arc::new(initialize_app_container(&cfg));
 
-        let http_api_container = Arc::new(HttpApiContainer::from_app_container(&http_api_config, &app_container));
+        let registar = Registar::default();
+
+        let listeners = Arc::new(Listeners::new(&app_container, &registar));
+
+        let http_api_container = Arc::new(HttpApiContainer::from_app_container(
+            &http_api_config,
+            &app_container,
+            &listeners,
+        ));
 
         let version = Version::V1;
 
-        start_job(http_api_container, Registar::default().give_form(), version)
+        start_job(http_api_container, registar.give_form(), version)
             .await
             .expect("it should be able to join to the tracker api start-job");
     }
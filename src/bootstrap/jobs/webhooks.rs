@@ -0,0 +1,51 @@
+//! Job that sends the webhook notifications.
+//!
+//! It runs when the configuration includes a `[webhooks]` section. It queues
+//! the events published to the [event bus](bittorrent_tracker_core::event)
+//! for the interested targets and sends them in the background.
+//!
+//! Refer to the [`webhooks`](crate::packages::webhooks) module for more
+//! information about the requests.
+use std::sync::Arc;
+
+use bittorrent_tracker_core::event::Bus;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use torrust_tracker_configuration::Webhooks as Config;
+use tracing::instrument;
+
+use crate::packages::webhooks::{Error, Webhooks};
+
+/// It starts the job that sends the webhook notifications.
+///
+/// It only receives the events published after it starts.
+///
+/// # Errors
+///
+/// Will return an error if a target URL or event type is not valid.
+#[instrument(skip(config, events))]
+pub fn start_job(config: &Config, events: &Arc<Bus>) -> Result<JoinHandle<()>, Error> {
+    let (webhooks, worker) = Webhooks::new(config)?;
+
+    let mut receiver = events.subscribe();
+
+    Ok(tokio::spawn(async move {
+        let worker = tokio::spawn(worker.run());
+
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("Stopping webhooks job..");
+                    break;
+                }
+                event = receiver.recv() => match event {
+                    Ok(event) => webhooks.notify(&event),
+                    Err(RecvError::Lagged(missed)) => tracing::warn!("webhooks missed {missed} events"),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+
+        worker.abort();
+    }))
+}
@@ -10,14 +10,24 @@
 //! - `Debug`
 //! - `Trace`
 //!
+//! The threshold can be changed while the tracker is running with
+//! [`reload_threshold`], unless logging was disabled (`Off`) when the tracker
+//! started.
+//!
 //! Refer to the [configuration crate documentation](https://docs.rs/torrust-tracker-configuration) to know how to change log settings.
-use std::sync::Once;
+use std::sync::{Once, OnceLock};
 
 use torrust_tracker_configuration::{Configuration, Threshold};
 use tracing::level_filters::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt as _;
+use tracing_subscriber::util::SubscriberInitExt as _;
+use tracing_subscriber::{fmt, reload, Registry};
 
 static INIT: Once = Once::new();
 
+/// Handle to change the log threshold of the global subscriber.
+static THRESHOLD_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
 /// It redirects the log info to the standard output with the log threshold
 /// defined in the configuration.
 pub fn setup(cfg: &Configuration) {
@@ -32,6 +42,28 @@ pub fn setup(cfg: &Configuration) {
     });
 }
 
+/// It changes the log threshold of the running application.
+///
+/// It returns `false` if the threshold can't be changed because logging was
+/// not initialized (for example, because the threshold was `Off` when the
+/// application started).
+pub fn reload_threshold(threshold: &Threshold) -> bool {
+    let Some(handle) = THRESHOLD_HANDLE.get() else {
+        return false;
+    };
+
+    match handle.reload(map_to_tracing_level_filter(threshold)) {
+        Ok(()) => {
+            tracing::info!("Logging threshold changed to: {threshold:?}");
+            true
+        }
+        Err(err) => {
+            tracing::error!("Failed to change the logging threshold: {err}");
+            false
+        }
+    }
+}
+
 fn map_to_tracing_level_filter(threshold: &Threshold) -> LevelFilter {
     match threshold {
         Threshold::Off => LevelFilter::OFF,
@@ -44,18 +76,21 @@ fn map_to_tracing_level_filter(threshold: &Threshold) -> LevelFilter {
 }
 
 fn tracing_init(filter: LevelFilter, style: &TraceStyle) {
-    let builder = tracing_subscriber::fmt()
-        .with_max_level(filter)
-        .with_ansi(true)
-        .with_test_writer();
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let registry = tracing_subscriber::registry().with(filter);
+    let layer = fmt::layer().with_ansi(true).with_test_writer();
 
     let () = match style {
-        TraceStyle::Default => builder.init(),
-        TraceStyle::Pretty(display_filename) => builder.pretty().with_file(*display_filename).init(),
-        TraceStyle::Compact => builder.compact().init(),
-        TraceStyle::Json => builder.json().init(),
+        TraceStyle::Default => registry.with(layer).init(),
+        TraceStyle::Pretty(display_filename) => registry.with(layer.pretty().with_file(*display_filename)).init(),
+        TraceStyle::Compact => registry.with(layer.compact()).init(),
+        TraceStyle::Json => registry.with(layer.json()).init(),
     };
 
+    // It's only initialized once.
+    drop(THRESHOLD_HANDLE.set(handle));
+
     tracing::info!("Logging initialized");
 }
 
@@ -3,7 +3,11 @@ use std::sync::Arc;
 use bittorrent_tracker_core::announce_handler::AnnounceHandler;
 use bittorrent_tracker_core::authentication::handler::KeysHandler;
 use bittorrent_tracker_core::authentication::service::AuthenticationService;
+use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
+use bittorrent_tracker_core::client_policy::manager::ClientPolicyManager;
 use bittorrent_tracker_core::databases::Database;
+use bittorrent_tracker_core::event::Bus;
+use bittorrent_tracker_core::geoip::GeoIp;
 use bittorrent_tracker_core::scrape_handler::ScrapeHandler;
 use bittorrent_tracker_core::torrent::manager::TorrentsManager;
 use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
@@ -12,9 +16,13 @@ use bittorrent_tracker_core::whitelist;
 use bittorrent_tracker_core::whitelist::manager::WhitelistManager;
 use bittorrent_tracker_core::whitelist::repository::in_memory::InMemoryWhitelist;
 use tokio::sync::RwLock;
-use torrust_tracker_configuration::{Core, HttpApi, HttpTracker, UdpTracker};
+use torrust_tracker_configuration::{AccessTokens, Core, FullScrape, HttpApi, HttpTracker, UdpTracker};
 
-use crate::packages::{http_tracker_core, udp_tracker_core};
+use crate::bootstrap::config::Reloader;
+use crate::packages::tracker_api_core::statistics::history::History;
+use crate::packages::{full_scrape, http_tracker_core, udp_tracker_core};
+use crate::servers::apis::audit::AuditLog;
+use crate::servers::listeners::Listeners;
 use crate::servers::udp::server::banning::BanService;
 
 pub struct AppContainer {
@@ -26,6 +34,8 @@ pub struct AppContainer {
     pub authentication_service: Arc<AuthenticationService>,
     pub in_memory_whitelist: Arc<InMemoryWhitelist>,
     pub whitelist_authorization: Arc<whitelist::authorization::WhitelistAuthorization>,
+    pub client_authorization: Arc<ClientAuthorization>,
+    pub client_policy_manager: Arc<ClientPolicyManager>,
     pub ban_service: Arc<RwLock<BanService>>,
     pub http_stats_event_sender: Arc<Option<Box<dyn http_tracker_core::statistics::event::sender::Sender>>>,
     pub udp_stats_event_sender: Arc<Option<Box<dyn udp_tracker_core::statistics::event::sender::Sender>>>,
@@ -35,6 +45,13 @@ pub struct AppContainer {
     pub in_memory_torrent_repository: Arc<InMemoryTorrentRepository>,
     pub db_torrent_repository: Arc<DatabasePersistentTorrentRepository>,
     pub torrents_manager: Arc<TorrentsManager>,
+    pub http_api_access_tokens: Arc<RwLock<AccessTokens>>,
+    pub config_reloader: Arc<Reloader>,
+    pub full_scrape_config: Option<Arc<FullScrape>>,
+    pub full_scrape_repository: Arc<full_scrape::Repository>,
+    pub events: Arc<Bus>,
+    pub metrics_history: Arc<History>,
+    pub geoip: Arc<GeoIp>,
 }
 
 pub struct UdpTrackerContainer {
@@ -43,6 +60,7 @@ pub struct UdpTrackerContainer {
     pub announce_handler: Arc<AnnounceHandler>,
     pub scrape_handler: Arc<ScrapeHandler>,
     pub whitelist_authorization: Arc<whitelist::authorization::WhitelistAuthorization>,
+    pub client_authorization: Arc<ClientAuthorization>,
     pub udp_stats_event_sender: Arc<Option<Box<dyn udp_tracker_core::statistics::event::sender::Sender>>>,
     pub ban_service: Arc<RwLock<BanService>>,
 }
@@ -56,6 +74,7 @@ impl UdpTrackerContainer {
             announce_handler: app_container.announce_handler.clone(),
             scrape_handler: app_container.scrape_handler.clone(),
             whitelist_authorization: app_container.whitelist_authorization.clone(),
+            client_authorization: app_container.client_authorization.clone(),
             udp_stats_event_sender: app_container.udp_stats_event_sender.clone(),
             ban_service: app_container.ban_service.clone(),
         }
@@ -68,8 +87,11 @@ pub struct HttpTrackerContainer {
     pub announce_handler: Arc<AnnounceHandler>,
     pub scrape_handler: Arc<ScrapeHandler>,
     pub whitelist_authorization: Arc<whitelist::authorization::WhitelistAuthorization>,
+    pub client_authorization: Arc<ClientAuthorization>,
     pub http_stats_event_sender: Arc<Option<Box<dyn http_tracker_core::statistics::event::sender::Sender>>>,
     pub authentication_service: Arc<AuthenticationService>,
+    pub full_scrape_config: Option<Arc<FullScrape>>,
+    pub full_scrape_repository: Arc<full_scrape::Repository>,
 }
 
 impl HttpTrackerContainer {
@@ -81,8 +103,11 @@ impl HttpTrackerContainer {
             announce_handler: app_container.announce_handler.clone(),
             scrape_handler: app_container.scrape_handler.clone(),
             whitelist_authorization: app_container.whitelist_authorization.clone(),
+            client_authorization: app_container.client_authorization.clone(),
             http_stats_event_sender: app_container.http_stats_event_sender.clone(),
             authentication_service: app_container.authentication_service.clone(),
+            full_scrape_config: app_container.full_scrape_config.clone(),
+            full_scrape_repository: app_container.full_scrape_repository.clone(),
         }
     }
 }
@@ -91,25 +116,49 @@ pub struct HttpApiContainer {
     pub core_config: Arc<Core>,
     pub http_api_config: Arc<HttpApi>,
     pub in_memory_torrent_repository: Arc<InMemoryTorrentRepository>,
+    pub announce_handler: Arc<AnnounceHandler>,
+    pub torrents_manager: Arc<TorrentsManager>,
     pub keys_handler: Arc<KeysHandler>,
     pub whitelist_manager: Arc<WhitelistManager>,
+    pub client_policy_manager: Arc<ClientPolicyManager>,
     pub ban_service: Arc<RwLock<BanService>>,
     pub http_stats_repository: Arc<http_tracker_core::statistics::repository::Repository>,
     pub udp_stats_repository: Arc<udp_tracker_core::statistics::repository::Repository>,
+    pub access_tokens: Arc<RwLock<AccessTokens>>,
+    pub config_reloader: Arc<Reloader>,
+    pub listeners: Arc<Listeners>,
+    pub audit_log: Arc<AuditLog>,
+    pub events: Arc<Bus>,
+    pub metrics_history: Arc<History>,
+    pub geoip: Arc<GeoIp>,
 }
 
 impl HttpApiContainer {
     #[must_use]
-    pub fn from_app_container(http_api_config: &Arc<HttpApi>, app_container: &Arc<AppContainer>) -> Self {
+    pub fn from_app_container(
+        http_api_config: &Arc<HttpApi>,
+        app_container: &Arc<AppContainer>,
+        listeners: &Arc<Listeners>,
+    ) -> Self {
         Self {
             http_api_config: http_api_config.clone(),
             core_config: app_container.core_config.clone(),
             in_memory_torrent_repository: app_container.in_memory_torrent_repository.clone(),
+            announce_handler: app_container.announce_handler.clone(),
+            torrents_manager: app_container.torrents_manager.clone(),
             keys_handler: app_container.keys_handler.clone(),
             whitelist_manager: app_container.whitelist_manager.clone(),
+            client_policy_manager: app_container.client_policy_manager.clone(),
             ban_service: app_container.ban_service.clone(),
             http_stats_repository: app_container.http_stats_repository.clone(),
             udp_stats_repository: app_container.udp_stats_repository.clone(),
+            access_tokens: app_container.http_api_access_tokens.clone(),
+            config_reloader: app_container.config_reloader.clone(),
+            listeners: listeners.clone(),
+            audit_log: Arc::new(AuditLog::new(&http_api_config.audit_log_path)),
+            events: app_container.events.clone(),
+            metrics_history: app_container.metrics_history.clone(),
+            geoip: app_container.geoip.clone(),
         }
     }
 }
@@ -221,8 +221,16 @@
 //! By default, if you don’t specify any `tracker.toml` file, the application
 //! will use `./share/default/config/tracker.development.sqlite3.toml`.
 //!
-//! > IMPORTANT: Every time you change the configuration you need to restart the
-//! > service.
+//! Some settings can be changed without restarting the service: the announce
+//! policy, the tracker policy, the external IP, the API access tokens and the
+//! logging threshold. You can reload them by sending a `SIGHUP` signal to the
+//! tracker process or with the [API](crate::servers::apis::v1::context::config).
+//! Changes in other settings are reported, but they are not applied until the
+//! service is restarted.
+//!
+//! ```text
+//! kill -HUP $(pidof torrust-tracker)
+//! ```
 //!
 //! # Usage
 //!
@@ -0,0 +1,349 @@
+//! Periodic full scrape dump.
+//!
+//! Torrent indexes usually need the swarm metadata (seeders, completed and
+//! leechers) of every torrent. Instead of sending scrape requests in batches of
+//! 74 torrents, they can download a single "full scrape" dump, like the one
+//! provided by `opentracker`.
+//!
+//! The dump is generated in two gzipped formats:
+//!
+//! - Bencoded, with the same structure as a scrape response:
+//!   `d5:filesd20:<info_hash>d8:completei1e10:downloadedi2e10:incompletei3eeee`.
+//! - JSON, with the infohashes in hex:
+//!   `{"files":{"<info_hash>":{"complete":1,"downloaded":2,"incomplete":3}}}`.
+//!
+//! The [`full_scrape`](crate::bootstrap::jobs::full_scrape) job regenerates the
+//! dump periodically, writes it to disk and keeps the latest one in the
+//! [`Repository`], so that the HTTP trackers can serve it.
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bittorrent_primitives::info_hash::InfoHash;
+use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
+use bittorrent_tracker_core::torrent::services::{get_torrents_after, Info};
+use camino::Utf8Path;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sha2::{Digest, Sha256};
+
+/// The file name of the gzipped bencoded dump.
+pub const BENCODE_FILE_NAME: &str = "full_scrape.bencode.gz";
+
+/// The file name of the gzipped JSON dump.
+pub const JSON_FILE_NAME: &str = "full_scrape.json.gz";
+
+/// The number of torrents read from the torrent repository at once.
+const BATCH_SIZE: usize = 1000;
+
+/// The format of a full scrape dump.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    Bencode,
+    Json,
+}
+
+impl Format {
+    /// The name of the dump file in this format.
+    #[must_use]
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Format::Bencode => BENCODE_FILE_NAME,
+            Format::Json => JSON_FILE_NAME,
+        }
+    }
+}
+
+/// A gzipped dump in one format.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Compressed {
+    /// The gzipped content.
+    pub bytes: Vec<u8>,
+    /// A strong entity tag with the SHA-256 hash of the uncompressed content,
+    /// including the double quotes.
+    pub etag: String,
+}
+
+impl Compressed {
+    fn new(content: &[u8]) -> io::Result<Self> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content)?;
+
+        Ok(Self {
+            bytes: encoder.finish()?,
+            etag: format!("\"{:x}\"", Sha256::digest(content)),
+        })
+    }
+}
+
+/// A full scrape dump in all the formats.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Dump {
+    /// The number of torrents in the dump.
+    pub torrents: usize,
+    /// When the content of the dump last changed, truncated to seconds.
+    pub last_modified: SystemTime,
+    pub bencoded: Compressed,
+    pub json: Compressed,
+}
+
+impl Dump {
+    /// It generates the dump for all the torrents in the repository.
+    ///
+    /// Torrents are read in batches, in infohash order, so the torrent
+    /// repository is never locked while the whole dump is built.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the dump can't be compressed.
+    pub fn generate(in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>) -> io::Result<Self> {
+        let mut bencoded = b"d5:filesd".to_vec();
+        let mut json = String::from("{\"files\":{");
+        let mut torrents = 0;
+        let mut cursor: Option<InfoHash> = None;
+
+        loop {
+            let batch = get_torrents_after(in_memory_torrent_repository, cursor.as_ref(), BATCH_SIZE, false);
+
+            let Some(last) = batch.last() else {
+                break;
+            };
+
+            cursor = Some(last.info_hash);
+
+            for info in &batch {
+                push_bencoded(&mut bencoded, info);
+                push_json(&mut json, info, torrents == 0);
+                torrents += 1;
+            }
+        }
+
+        bencoded.extend_from_slice(b"ee");
+        json.push_str("}}");
+
+        Ok(Self {
+            torrents,
+            last_modified: now_in_seconds(),
+            bencoded: Compressed::new(&bencoded)?,
+            json: Compressed::new(json.as_bytes())?,
+        })
+    }
+
+    /// The dump in the given format.
+    #[must_use]
+    pub fn get(&self, format: Format) -> &Compressed {
+        match format {
+            Format::Bencode => &self.bencoded,
+            Format::Json => &self.json,
+        }
+    }
+
+    /// It writes the dump files in the directory, creating it if it does not
+    /// exist.
+    ///
+    /// Each file is written to a temporary file first and then renamed, so
+    /// readers never see a partially written dump.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the directory or the files can't be written.
+    pub fn write_to(&self, dir: &Utf8Path) -> io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+
+        for format in [Format::Bencode, Format::Json] {
+            let path = dir.join(format.file_name());
+            let tmp_path = dir.join(format!("{}.tmp", format.file_name()));
+
+            std::fs::write(&tmp_path, &self.get(format).bytes)?;
+            std::fs::rename(&tmp_path, &path)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn push_bencoded(bencoded: &mut Vec<u8>, info: &Info) {
+    bencoded.extend_from_slice(b"20:");
+    bencoded.extend_from_slice(&info.info_hash.bytes());
+    bencoded.extend_from_slice(
+        format!(
+            "d8:completei{}e10:downloadedi{}e10:incompletei{}ee",
+            info.seeders, info.completed, info.leechers
+        )
+        .as_bytes(),
+    );
+}
+
+fn push_json(json: &mut String, info: &Info, first: bool) {
+    if !first {
+        json.push(',');
+    }
+
+    write!(
+        json,
+        "\"{}\":{{\"complete\":{},\"downloaded\":{},\"incomplete\":{}}}",
+        info.info_hash, info.seeders, info.completed, info.leechers
+    )
+    .expect("writing to a string should not fail");
+}
+
+/// HTTP dates have a resolution of one second.
+fn now_in_seconds() -> SystemTime {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs())
+}
+
+/// It keeps the latest full scrape dump.
+#[derive(Debug, Default)]
+pub struct Repository {
+    latest: RwLock<Option<Arc<Dump>>>,
+}
+
+impl Repository {
+    /// The latest dump, or `None` if it has not been generated yet.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock is poisoned.
+    #[must_use]
+    pub fn latest(&self) -> Option<Arc<Dump>> {
+        self.latest.read().expect("it should get the read lock").clone()
+    }
+
+    /// It replaces the latest dump.
+    ///
+    /// If the content did not change, the previous dump is kept, so that its
+    /// `Last-Modified` date is preserved. It returns `false` in that case.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock is poisoned.
+    pub fn update(&self, dump: Dump) -> bool {
+        let mut latest = self.latest.write().expect("it should get the write lock");
+
+        let unchanged = latest
+            .as_ref()
+            .is_some_and(|previous| previous.bencoded.etag == dump.bencoded.etag && previous.json.etag == dump.json.etag);
+
+        if unchanged {
+            return false;
+        }
+
+        *latest = Some(Arc::new(dump));
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use bittorrent_primitives::info_hash::InfoHash;
+    use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
+    use flate2::read::GzDecoder;
+    use torrust_tracker_primitives::core::ScrapeData;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+    use torrust_tracker_primitives::swarm_metadata::SwarmMetadata;
+
+    use super::{Compressed, Dump, Repository};
+
+    fn decompress(compressed: &Compressed) -> Vec<u8> {
+        let mut content = Vec::new();
+        GzDecoder::new(compressed.bytes.as_slice()).read_to_end(&mut content).unwrap();
+        content
+    }
+
+    fn sample_repository() -> (Arc<InMemoryTorrentRepository>, InfoHash, InfoHash) {
+        let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
+
+        let info_hash_1 = InfoHash::from_str("0000000000000000000000000000000000000001").unwrap();
+        let info_hash_2 = InfoHash::from_str("0000000000000000000000000000000000000002").unwrap();
+
+        let () = in_memory_torrent_repository.upsert_peer(&info_hash_1, &PeerBuilder::seeder().build());
+        let () = in_memory_torrent_repository.upsert_peer(&info_hash_2, &PeerBuilder::leecher().build());
+
+        (in_memory_torrent_repository, info_hash_1, info_hash_2)
+    }
+
+    #[test]
+    fn it_should_bencode_the_dump_like_a_scrape_response() {
+        let (in_memory_torrent_repository, info_hash_1, info_hash_2) = sample_repository();
+
+        let dump = Dump::generate(&in_memory_torrent_repository).unwrap();
+
+        let mut scrape_data = ScrapeData::empty();
+        scrape_data.add_file(
+            &info_hash_1,
+            SwarmMetadata {
+                complete: 1,
+                downloaded: 0,
+                incomplete: 0,
+            },
+        );
+        scrape_data.add_file(
+            &info_hash_2,
+            SwarmMetadata {
+                complete: 0,
+                downloaded: 0,
+                incomplete: 1,
+            },
+        );
+
+        assert_eq!(dump.torrents, 2);
+        assert_eq!(
+            decompress(&dump.bencoded),
+            bittorrent_http_protocol::v1::responses::scrape::Bencoded::from(scrape_data).body()
+        );
+    }
+
+    #[test]
+    fn it_should_generate_the_dump_in_json() {
+        let (in_memory_torrent_repository, _, _) = sample_repository();
+
+        let dump = Dump::generate(&in_memory_torrent_repository).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&decompress(&dump.json)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "files": {
+                    "0000000000000000000000000000000000000001": { "complete": 1, "downloaded": 0, "incomplete": 0 },
+                    "0000000000000000000000000000000000000002": { "complete": 0, "downloaded": 0, "incomplete": 1 }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_generate_an_empty_dump_when_there_are_no_torrents() {
+        let dump = Dump::generate(&Arc::new(InMemoryTorrentRepository::default())).unwrap();
+
+        assert_eq!(dump.torrents, 0);
+        assert_eq!(decompress(&dump.bencoded), b"d5:filesdee");
+        assert_eq!(decompress(&dump.json), b"{\"files\":{}}");
+    }
+
+    #[test]
+    fn it_should_keep_the_previous_dump_when_the_content_did_not_change() {
+        let (in_memory_torrent_repository, _, _) = sample_repository();
+
+        let repository = Repository::default();
+
+        assert!(repository.update(Dump::generate(&in_memory_torrent_repository).unwrap()));
+        assert!(!repository.update(Dump::generate(&in_memory_torrent_repository).unwrap()));
+
+        let () = in_memory_torrent_repository.upsert_peer(
+            &InfoHash::from_str("0000000000000000000000000000000000000003").unwrap(),
+            &PeerBuilder::seeder().build(),
+        );
+
+        assert!(repository.update(Dump::generate(&in_memory_torrent_repository).unwrap()));
+        assert_eq!(repository.latest().unwrap().torrents, 3);
+    }
+}
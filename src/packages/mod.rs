@@ -1,6 +1,8 @@
 //! This module contains logic pending to be extracted into workspace packages.
 //!
 //! It will be moved to the directory `packages`.
+pub mod full_scrape;
 pub mod http_tracker_core;
 pub mod tracker_api_core;
 pub mod udp_tracker_core;
+pub mod webhooks;
@@ -0,0 +1,468 @@
+//! Historical metrics.
+//!
+//! The tracker metrics are counters since the tracker started, so they can't
+//! show trends and they are lost on restart. The
+//! [`metrics_history`](crate::bootstrap::jobs::metrics_history) job takes a
+//! [`Sample`] of them periodically and keeps them in the [`History`]:
+//!
+//! - The number of requests, announces, scrapes and errors since the
+//!   previous sample (deltas).
+//! - The number of torrents, seeders and leechers at the time of the sample
+//!   (gauges).
+//!
+//! The history has two ring buffers: one with the latest samples at full
+//! resolution, and one with the samples downsampled to a longer interval.
+//! When samples are aggregated, the deltas are added up and the gauges take
+//! the value of the latest sample.
+//!
+//! The history is saved to a JSON file after each sample, and loaded again
+//! when the tracker starts.
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::sync::RwLock;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use torrust_tracker_configuration::MetricsHistory;
+
+use super::services::TrackerMetrics;
+
+/// The metrics in a period of time.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sample {
+    /// The start of the period, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The length of the period, in seconds.
+    pub duration: u64,
+
+    // Deltas
+    /// UDP requests and HTTP requests.
+    pub requests: u64,
+    pub announces: u64,
+    pub scrapes: u64,
+    /// UDP error responses. The HTTP tracker does not count its errors.
+    pub errors: u64,
+
+    // Gauges
+    pub torrents: u64,
+    pub seeders: u64,
+    pub leechers: u64,
+}
+
+impl Sample {
+    /// A sample for the period starting at `timestamp` with the metrics of
+    /// the `first` sample in the period.
+    fn starting_with(timestamp: u64, first: &Sample) -> Self {
+        let mut sample = Self {
+            timestamp,
+            ..Self::default()
+        };
+        sample.merge(first);
+        sample
+    }
+
+    /// It merges a later sample into this one.
+    fn merge(&mut self, later: &Sample) {
+        self.duration = later.timestamp + later.duration - self.timestamp;
+        self.requests += later.requests;
+        self.announces += later.announces;
+        self.scrapes += later.scrapes;
+        self.errors += later.errors;
+        self.torrents = later.torrents;
+        self.seeders = later.seeders;
+        self.leechers = later.leechers;
+    }
+}
+
+/// The counters the sample deltas are calculated from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counters {
+    requests: u64,
+    announces: u64,
+    scrapes: u64,
+    errors: u64,
+}
+
+impl From<&TrackerMetrics> for Counters {
+    fn from(metrics: &TrackerMetrics) -> Self {
+        let protocol = &metrics.protocol_metrics;
+
+        Self {
+            requests: protocol.udp4_requests
+                + protocol.udp6_requests
+                + protocol.tcp4_connections_handled
+                + protocol.tcp6_connections_handled,
+            announces: protocol.udp4_announces_handled
+                + protocol.udp6_announces_handled
+                + protocol.tcp4_announces_handled
+                + protocol.tcp6_announces_handled,
+            scrapes: protocol.udp4_scrapes_handled
+                + protocol.udp6_scrapes_handled
+                + protocol.tcp4_scrapes_handled
+                + protocol.tcp6_scrapes_handled,
+            errors: protocol.udp4_errors_handled + protocol.udp6_errors_handled,
+        }
+    }
+}
+
+impl Sample {
+    /// The sample of the period that started at `timestamp` and ends now.
+    #[must_use]
+    pub fn new(timestamp: u64, now: u64, previous: &Counters, metrics: &TrackerMetrics) -> Self {
+        let current = Counters::from(metrics);
+
+        Self {
+            timestamp,
+            duration: now.saturating_sub(timestamp),
+            requests: current.requests.saturating_sub(previous.requests),
+            announces: current.announces.saturating_sub(previous.announces),
+            scrapes: current.scrapes.saturating_sub(previous.scrapes),
+            errors: current.errors.saturating_sub(previous.errors),
+            torrents: metrics.torrents_metrics.torrents,
+            seeders: metrics.torrents_metrics.complete,
+            leechers: metrics.torrents_metrics.incomplete,
+        }
+    }
+}
+
+/// A page of the history.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Series {
+    /// The length of the period of each sample, in seconds.
+    pub step: u64,
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Samples {
+    samples: VecDeque<Sample>,
+    downsampled: VecDeque<Sample>,
+}
+
+/// The recorded samples.
+#[derive(Debug)]
+pub struct History {
+    interval: u64,
+    capacity: usize,
+    downsampled_interval: u64,
+    downsampled_capacity: usize,
+    path: Option<Utf8PathBuf>,
+    samples: RwLock<(VecDeque<Sample>, VecDeque<Sample>)>,
+}
+
+impl Default for History {
+    /// An empty in-memory history with the default settings. It's used when
+    /// the history is not enabled.
+    fn default() -> Self {
+        let config = MetricsHistory::default();
+
+        Self {
+            interval: config.interval,
+            capacity: config.samples,
+            downsampled_interval: config.downsampled_interval,
+            downsampled_capacity: config.downsampled_samples,
+            path: None,
+            samples: RwLock::default(),
+        }
+    }
+}
+
+impl History {
+    /// It loads the history saved in the configured file, if any.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file exists but can't be read or parsed.
+    pub fn load(config: &MetricsHistory) -> io::Result<Self> {
+        let saved = match fs::read(&config.path) {
+            Ok(content) => serde_json::from_slice::<Samples>(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Samples::default(),
+            Err(e) => return Err(e),
+        };
+
+        let history = Self {
+            interval: config.interval,
+            capacity: config.samples,
+            downsampled_interval: config.downsampled_interval,
+            downsampled_capacity: config.downsampled_samples,
+            path: Some(config.path.clone()),
+            samples: RwLock::new((saved.samples, saved.downsampled)),
+        };
+
+        // The capacities could be smaller than when the file was saved.
+        history.truncate();
+
+        Ok(history)
+    }
+
+    /// The number of seconds between two samples.
+    #[must_use]
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// The number of seconds covered by the full resolution samples.
+    #[must_use]
+    pub fn full_resolution_range(&self) -> u64 {
+        self.interval.saturating_mul(self.capacity as u64)
+    }
+
+    /// It adds a new sample and downsamples it.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock is poisoned.
+    pub fn push(&self, sample: Sample) {
+        {
+            let mut samples = self.samples.write().expect("it should get the write lock");
+            let (full, downsampled) = &mut *samples;
+
+            full.push_back(sample);
+
+            let bucket = sample.timestamp - sample.timestamp % self.downsampled_interval;
+
+            match downsampled.back_mut() {
+                Some(last) if last.timestamp == bucket => last.merge(&sample),
+                _ => downsampled.push_back(Sample::starting_with(bucket, &sample)),
+            }
+        }
+
+        self.truncate();
+    }
+
+    fn truncate(&self) {
+        let mut samples = self.samples.write().expect("it should get the write lock");
+        let (full, downsampled) = &mut *samples;
+
+        while full.len() > self.capacity {
+            full.pop_front();
+        }
+
+        while downsampled.len() > self.downsampled_capacity {
+            downsampled.pop_front();
+        }
+    }
+
+    /// It saves the history to the configured file, if any. The file is
+    /// replaced atomically.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file can't be written.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock is poisoned.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let content = {
+            let samples = self.samples.read().expect("it should get the read lock");
+
+            serde_json::to_vec(&Samples {
+                samples: samples.0.clone(),
+                downsampled: samples.1.clone(),
+            })?
+        };
+
+        write_atomically(path, &content)
+    }
+
+    /// It returns the samples that start in the `[from, to)` range, with one
+    /// sample every `step` seconds at the most.
+    ///
+    /// The full resolution samples are used when the `step` is shorter than
+    /// the downsampled interval and they cover the whole range. Otherwise,
+    /// the downsampled samples are used. The `step` is
+    /// rounded up to a multiple of the interval of the samples used.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock is poisoned.
+    #[must_use]
+    pub fn query(&self, from: u64, to: u64, step: Option<u64>) -> Series {
+        let samples = self.samples.read().expect("it should get the read lock");
+        let (full, downsampled) = &*samples;
+
+        let requested_step = step.unwrap_or(self.interval);
+
+        // The full resolution samples cover the range if they start before it,
+        // or if no older samples have been discarded.
+        let covers_the_range = match (full.front(), downsampled.front()) {
+            (Some(oldest), Some(oldest_downsampled)) => {
+                oldest.timestamp <= from || oldest_downsampled.timestamp + self.downsampled_interval > oldest.timestamp
+            }
+            _ => false,
+        };
+
+        let (source, interval) = if requested_step < self.downsampled_interval && covers_the_range {
+            (full, self.interval)
+        } else {
+            (downsampled, self.downsampled_interval)
+        };
+
+        let step = requested_step.max(1).saturating_add(interval - 1) / interval * interval;
+
+        let mut series: Vec<Sample> = vec![];
+
+        for sample in source
+            .iter()
+            .filter(|sample| sample.timestamp >= from && sample.timestamp < to)
+        {
+            if step == interval {
+                series.push(*sample);
+                continue;
+            }
+
+            let bucket = sample.timestamp - sample.timestamp % step;
+
+            match series.last_mut() {
+                Some(last) if last.timestamp == bucket => last.merge(sample),
+                _ => series.push(Sample::starting_with(bucket, sample)),
+            }
+        }
+
+        Series { step, samples: series }
+    }
+}
+
+fn write_atomically(path: &Utf8Path, content: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temporary = path.with_extension("json.tmp");
+
+    fs::write(&temporary, content)?;
+    fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use torrust_tracker_configuration::MetricsHistory;
+
+    use super::{History, Sample};
+
+    fn config(samples: usize, downsampled_samples: usize) -> MetricsHistory {
+        MetricsHistory {
+            interval: 60,
+            samples,
+            downsampled_interval: 3600,
+            downsampled_samples,
+            path: std::env::temp_dir()
+                .join(format!("metrics_history_{}.json", uuid::Uuid::new_v4()))
+                .try_into()
+                .unwrap(),
+        }
+    }
+
+    fn sample(timestamp: u64, announces: u64, torrents: u64) -> Sample {
+        Sample {
+            timestamp,
+            duration: 60,
+            announces,
+            torrents,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_should_return_the_samples_in_the_requested_range() {
+        let history = History::load(&config(10, 10)).unwrap();
+
+        for minute in 0..5 {
+            history.push(sample(minute * 60, 1, minute));
+        }
+
+        let series = history.query(60, 180, None);
+
+        assert_eq!(series.step, 60);
+        assert_eq!(series.samples, vec![sample(60, 1, 1), sample(120, 1, 2)]);
+    }
+
+    #[test]
+    fn it_should_add_up_the_deltas_and_keep_the_latest_gauges_when_the_step_is_longer() {
+        let history = History::load(&config(10, 10)).unwrap();
+
+        for minute in 0..4 {
+            history.push(sample(minute * 60, 1, minute));
+        }
+
+        let series = history.query(0, 240, Some(120));
+
+        assert_eq!(series.step, 120);
+        assert_eq!(
+            series.samples,
+            vec![
+                Sample {
+                    duration: 120,
+                    ..sample(0, 2, 1)
+                },
+                Sample {
+                    duration: 120,
+                    ..sample(120, 2, 3)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_use_the_downsampled_samples_when_the_range_is_not_covered_at_full_resolution() {
+        // Only the last 2 samples are kept at full resolution
+        let history = History::load(&config(2, 10)).unwrap();
+
+        for minute in 0..120 {
+            history.push(sample(minute * 60, 1, minute));
+        }
+
+        let series = history.query(0, 7200, None);
+
+        assert_eq!(series.step, 3600);
+        assert_eq!(
+            series.samples,
+            vec![
+                Sample {
+                    duration: 3600,
+                    ..sample(0, 60, 59)
+                },
+                Sample {
+                    duration: 3600,
+                    ..sample(3600, 60, 119)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_only_keep_the_latest_samples() {
+        let history = History::load(&config(2, 1)).unwrap();
+
+        for hour in 0..3 {
+            history.push(sample(hour * 3600, 1, hour));
+        }
+
+        assert_eq!(
+            history.query(0, u64::MAX, Some(60)).samples,
+            vec![sample(3600, 1, 1), sample(7200, 1, 2)]
+        );
+        assert_eq!(history.query(0, u64::MAX, Some(3600)).samples.len(), 1);
+    }
+
+    #[test]
+    fn it_should_be_saved_and_loaded_again() {
+        let config = config(10, 10);
+
+        let history = History::load(&config).unwrap();
+        history.push(sample(0, 1, 1));
+        history.save().unwrap();
+
+        let loaded = History::load(&config).unwrap();
+
+        assert_eq!(loaded.query(0, u64::MAX, None).samples, vec![sample(0, 1, 1)]);
+
+        std::fs::remove_file(&config.path).unwrap();
+    }
+}
@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
+use bittorrent_tracker_core::adaptive_interval::AnnounceIntervalMetrics;
+use bittorrent_tracker_core::announce_handler::AnnounceHandler;
 use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
 use packages::tracker_api_core::statistics::metrics::Metrics;
 use tokio::sync::RwLock;
 use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
+use torrust_tracker_torrent_repository::limits::EvictionMetrics;
 
 use crate::packages::{self, http_tracker_core, udp_tracker_core};
 use crate::servers::udp::server::banning::BanService;
@@ -16,6 +19,23 @@ pub struct TrackerMetrics {
     /// General metrics for all torrents (number of seeders, leechers, etcetera)
     pub torrents_metrics: TorrentsMetrics,
 
+    /// Domain level metrics.
+    ///
+    /// Torrents and peers evicted or refused because of the memory limits.
+    pub eviction_metrics: EvictionMetrics,
+
+    /// Domain level metrics.
+    ///
+    /// Number of announces rejected because they were received before the
+    /// minimum announce interval.
+    pub early_announces: u64,
+
+    /// Domain level metrics.
+    ///
+    /// The tracker load used by the adaptive announce interval and the
+    /// intervals sent in the announce responses.
+    pub announce_interval_metrics: AnnounceIntervalMetrics,
+
     /// Application level metrics. Usage statistics/metrics.
     ///
     /// Metrics about how the tracker is been used (number of udp announce requests, number of http scrape requests, etcetera)
@@ -25,17 +45,24 @@ pub struct TrackerMetrics {
 /// It returns all the [`TrackerMetrics`]
 pub async fn get_metrics(
     in_memory_torrent_repository: Arc<InMemoryTorrentRepository>,
+    announce_handler: Arc<AnnounceHandler>,
     ban_service: Arc<RwLock<BanService>>,
     http_stats_repository: Arc<http_tracker_core::statistics::repository::Repository>,
     udp_stats_repository: Arc<udp_tracker_core::statistics::repository::Repository>,
 ) -> TrackerMetrics {
     let torrents_metrics = in_memory_torrent_repository.get_torrents_metrics();
+    let eviction_metrics = in_memory_torrent_repository.get_eviction_metrics();
+    let early_announces = announce_handler.get_early_announces();
+    let announce_interval_metrics = announce_handler.get_announce_interval_metrics();
     let udp_banned_ips_total = ban_service.read().await.get_banned_ips_total();
     let http_stats = http_stats_repository.get_stats().await;
     let udp_stats = udp_stats_repository.get_stats().await;
 
     TrackerMetrics {
         torrents_metrics,
+        eviction_metrics,
+        early_announces,
+        announce_interval_metrics,
         protocol_metrics: Metrics {
             // TCPv4
             tcp4_connections_handled: http_stats.tcp4_connections_handled,
@@ -74,12 +101,17 @@ pub async fn get_metrics(
 mod tests {
     use std::sync::Arc;
 
+    use bittorrent_tracker_core::adaptive_interval::{AnnounceIntervalMetrics, IntervalHistogram};
+    use bittorrent_tracker_core::announce_handler::AnnounceHandler;
+    use bittorrent_tracker_core::databases::setup::initialize_database;
     use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
+    use bittorrent_tracker_core::torrent::repository::persisted::DatabasePersistentTorrentRepository;
     use bittorrent_tracker_core::{self};
     use tokio::sync::RwLock;
     use torrust_tracker_configuration::Configuration;
     use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
     use torrust_tracker_test_helpers::configuration;
+    use torrust_tracker_torrent_repository::limits::EvictionMetrics;
 
     use crate::packages::tracker_api_core::statistics::metrics::Metrics;
     use crate::packages::tracker_api_core::statistics::services::{get_metrics, TrackerMetrics};
@@ -96,7 +128,15 @@ mod tests {
         let config = tracker_configuration();
 
         let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
-        let ban_service = Arc::new(RwLock::new(BanService::new(MAX_CONNECTION_ID_ERRORS_PER_IP)));
+        let database = initialize_database(&config.core);
+        let announce_handler = Arc::new(AnnounceHandler::new(
+            &config.core,
+            &in_memory_torrent_repository,
+            &Arc::new(DatabasePersistentTorrentRepository::new(&database)),
+            &Arc::default(),
+            &Arc::default(),
+        ));
+        let ban_service = Arc::new(RwLock::new(BanService::new(MAX_CONNECTION_ID_ERRORS_PER_IP, &Arc::default())));
 
         // HTTP stats
         let (_http_stats_event_sender, http_stats_repository) =
@@ -110,6 +150,7 @@ mod tests {
 
         let tracker_metrics = get_metrics(
             in_memory_torrent_repository.clone(),
+            announce_handler.clone(),
             ban_service.clone(),
             http_stats_repository.clone(),
             udp_stats_repository.clone(),
@@ -120,6 +161,12 @@ mod tests {
             tracker_metrics,
             TrackerMetrics {
                 torrents_metrics: TorrentsMetrics::default(),
+                eviction_metrics: EvictionMetrics::default(),
+                early_announces: 0,
+                announce_interval_metrics: AnnounceIntervalMetrics {
+                    load: None,
+                    intervals: IntervalHistogram::default().metrics(),
+                },
                 protocol_metrics: Metrics::default(),
             }
         );
@@ -125,7 +125,7 @@ mod tests {
         let config = tracker_configuration();
 
         let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
-        let ban_service = Arc::new(RwLock::new(BanService::new(MAX_CONNECTION_ID_ERRORS_PER_IP)));
+        let ban_service = Arc::new(RwLock::new(BanService::new(MAX_CONNECTION_ID_ERRORS_PER_IP, &Arc::default())));
 
         let (_udp_stats_event_sender, udp_stats_repository) =
             udp_tracker_core::statistics::setup::factory(config.core.tracker_usage_statistics);
@@ -0,0 +1,590 @@
+//! Webhook notifications.
+//!
+//! External services, like torrent indexers, can be notified of the tracker
+//! [events](bittorrent_tracker_core::event) without polling the API. Each
+//! configured target receives an HTTP `POST` request with the event as JSON
+//! for the event types it's interested in:
+//!
+//! ```text
+//! POST /tracker-events HTTP/1.1
+//! Content-Type: application/json
+//! X-Torrust-Event: torrent_created
+//! X-Torrust-Delivery: 4f1c1ab6-4d6b-4f4e-8a59-3e2b6f0b9a1e
+//! X-Torrust-Timestamp: 1717171200
+//! X-Torrust-Signature: sha256=5d3c...
+//!
+//! {"type":"torrent_created","info_hash":"9c38422213e30bff212b30c360d26f9a02136422"}
+//! ```
+//!
+//! The timestamp is the time of the attempt, in seconds since the Unix epoch.
+//! The signature is the hex-encoded HMAC-SHA256, with the configured secret,
+//! of the timestamp, a dot and the body (`1717171200.{"type":...}`).
+//! Receivers should reject the requests with an old timestamp, so a captured
+//! request can't be replayed later. The delivery ID is the same for all the
+//! attempts of a delivery, so receivers can discard duplicates.
+//!
+//! The [`Webhooks`] only put the deliveries in a bounded queue per target, so
+//! notifying never blocks the announce requests. When a queue is full, the
+//! deliveries are dropped. The [`Worker`] sends them in the background, with
+//! a task per target so that a slow target does not delay the others. It
+//! retries the failed ones (network errors and non-2xx responses) with an
+//! exponential backoff, up to the configured number of attempts. The retries
+//! wait in their own queue, so they are not dropped when the queue of new
+//! deliveries is full, and they go before the new deliveries.
+//!
+//! The [`webhooks`](crate::bootstrap::jobs::webhooks) job feeds the
+//! [`Webhooks`] with the events from the event bus.
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bittorrent_tracker_core::event::{Event, EventType, UnknownEventType};
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use sha2::Sha256;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_configuration::{WebhookTarget, Webhooks as Config};
+use url::Url;
+use uuid::Uuid;
+
+use crate::CurrentClock;
+
+/// Header with the HMAC-SHA256 signature of the timestamp and the body.
+pub const SIGNATURE_HEADER: &str = "X-Torrust-Signature";
+
+/// Header with the time of the attempt, in seconds since the Unix epoch.
+pub const TIMESTAMP_HEADER: &str = "X-Torrust-Timestamp";
+
+/// Header with the event type.
+pub const EVENT_HEADER: &str = "X-Torrust-Event";
+
+/// Header with the delivery ID.
+pub const DELIVERY_HEADER: &str = "X-Torrust-Delivery";
+
+/// Maximum time to wait for the response of a target.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time to wait before the first retry. It doubles on each retry.
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Maximum time to wait before a retry.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// Errors in the webhooks configuration.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid webhook URL {url}: {source}")]
+    InvalidUrl { url: String, source: url::ParseError },
+
+    #[error("invalid event filter for the webhook {url}: {source}")]
+    InvalidEventFilter { url: String, source: UnknownEventType },
+}
+
+/// A URL the events are sent to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub url: Url,
+    /// The event types sent to the target. All of them if it's empty.
+    pub types: Vec<EventType>,
+}
+
+impl Target {
+    fn wants(&self, event_type: EventType) -> bool {
+        self.types.is_empty() || self.types.contains(&event_type)
+    }
+}
+
+impl TryFrom<&WebhookTarget> for Target {
+    type Error = Error;
+
+    fn try_from(config: &WebhookTarget) -> Result<Self, Self::Error> {
+        let url = Url::parse(&config.url).map_err(|source| Error::InvalidUrl {
+            url: config.url.clone(),
+            source,
+        })?;
+
+        let types = config
+            .events
+            .iter()
+            .map(|name| EventType::from_str(name))
+            .collect::<Result<_, _>>()
+            .map_err(|source| Error::InvalidEventFilter {
+                url: config.url.clone(),
+                source,
+            })?;
+
+        Ok(Self { url, types })
+    }
+}
+
+/// An event to be sent to a target.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub id: Uuid,
+    pub url: Url,
+    pub event_type: EventType,
+    /// The event as JSON.
+    pub body: Arc<str>,
+    /// The number of failed attempts.
+    pub failures: u32,
+}
+
+/// It queues the deliveries of the events to the targets.
+pub struct Webhooks {
+    /// The targets and the queues of their deliveries.
+    targets: Vec<(Target, mpsc::Sender<Delivery>)>,
+}
+
+impl Webhooks {
+    /// It returns the webhooks and the worker that sends the queued
+    /// deliveries. The worker must be run for the deliveries to be sent.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a target URL or event type is not valid.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the queue capacity is zero.
+    pub fn new(config: &Config) -> Result<(Self, Worker), Error> {
+        let targets: Vec<Target> = config.targets.iter().map(Target::try_from).collect::<Result<_, _>>()?;
+
+        let (senders, queues): (Vec<_>, Vec<_>) = targets.iter().map(|_| mpsc::channel(config.queue_capacity)).unzip();
+
+        let worker = Worker {
+            queues,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("it should build the webhooks HTTP client"),
+            secret: config.secret.clone(),
+            queue_capacity: config.queue_capacity,
+            max_attempts: config.max_attempts,
+            first_retry_delay: FIRST_RETRY_DELAY,
+        };
+
+        Ok((
+            Self {
+                targets: targets.into_iter().zip(senders).collect(),
+            },
+            worker,
+        ))
+    }
+
+    /// It queues the event for the targets interested in it. It never waits:
+    /// the deliveries are dropped if the queue of the target is full.
+    pub fn notify(&self, event: &Event) {
+        let event_type = event.event_type();
+
+        let mut targets = self.targets.iter().filter(|(target, _)| target.wants(event_type)).peekable();
+
+        if targets.peek().is_none() {
+            return;
+        }
+
+        let body: Arc<str> = match serde_json::to_string(event) {
+            Ok(body) => body.into(),
+            Err(e) => {
+                tracing::error!("failed to serialize the webhook event {event:?}: {e}");
+                return;
+            }
+        };
+
+        for (target, queue) in targets {
+            let delivery = Delivery {
+                id: Uuid::new_v4(),
+                url: target.url.clone(),
+                event_type,
+                body: body.clone(),
+                failures: 0,
+            };
+
+            if let Err(e) = queue.try_send(delivery) {
+                tracing::warn!("webhook delivery of {event_type} to {} dropped: {e}", target.url);
+            }
+        }
+    }
+}
+
+/// It sends the queued deliveries.
+pub struct Worker {
+    /// The queues of the deliveries of each target.
+    queues: Vec<mpsc::Receiver<Delivery>>,
+    client: reqwest::Client,
+    secret: Option<String>,
+    queue_capacity: usize,
+    max_attempts: u32,
+    first_retry_delay: Duration,
+}
+
+impl Worker {
+    /// It runs a [`Sender`] for each target until all the queues are closed.
+    /// The senders are aborted when the returned future is dropped.
+    pub async fn run(self) {
+        let sender = Arc::new(Sender {
+            client: self.client,
+            secret: self.secret,
+            queue_capacity: self.queue_capacity,
+            max_attempts: self.max_attempts,
+            first_retry_delay: self.first_retry_delay,
+        });
+
+        let mut senders = JoinSet::new();
+
+        for queue in self.queues {
+            senders.spawn(sender.clone().run(queue));
+        }
+
+        while senders.join_next().await.is_some() {}
+    }
+}
+
+/// It sends the deliveries of a target.
+struct Sender {
+    client: reqwest::Client,
+    secret: Option<String>,
+    queue_capacity: usize,
+    max_attempts: u32,
+    first_retry_delay: Duration,
+}
+
+impl Sender {
+    /// It sends the deliveries of the target one by one, the retries first.
+    /// The failed ones are put in the retry queue after the retry delay,
+    /// without delaying the rest.
+    async fn run(self: Arc<Self>, mut queue: mpsc::Receiver<Delivery>) {
+        let (retries, mut retry_queue) = mpsc::channel(self.queue_capacity);
+
+        loop {
+            let mut delivery = tokio::select! {
+                biased;
+                Some(delivery) = retry_queue.recv() => delivery,
+                delivery = queue.recv() => match delivery {
+                    Some(delivery) => delivery,
+                    None => break,
+                },
+            };
+
+            let Err(e) = self.send(&delivery).await else {
+                continue;
+            };
+
+            delivery.failures += 1;
+
+            if delivery.failures >= self.max_attempts {
+                tracing::warn!(
+                    "webhook delivery {} of {} to {} failed {} times, giving up: {e}",
+                    delivery.id,
+                    delivery.event_type,
+                    delivery.url,
+                    delivery.failures
+                );
+                continue;
+            }
+
+            let delay = self.retry_delay(delivery.failures);
+
+            tracing::debug!(
+                "webhook delivery {} to {} failed, retrying in {delay:?}: {e}",
+                delivery.id,
+                delivery.url
+            );
+
+            let retries = retries.clone();
+
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+
+                if let Err(e) = retries.try_send(delivery) {
+                    tracing::warn!("webhook delivery retry dropped: {e}");
+                }
+            });
+        }
+    }
+
+    async fn send(&self, delivery: &Delivery) -> Result<(), reqwest::Error> {
+        let timestamp = CurrentClock::now().as_secs();
+
+        let mut request = self
+            .client
+            .post(delivery.url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, delivery.event_type.as_str())
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp);
+
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, signature(secret, timestamp, delivery.body.as_bytes()));
+        }
+
+        request
+            .body(delivery.body.to_string())
+            .send()
+            .await?
+            .error_for_status()
+            .map(|_response| ())
+    }
+
+    fn retry_delay(&self, failures: u32) -> Duration {
+        self.first_retry_delay
+            .saturating_mul(2_u32.saturating_pow(failures - 1))
+            .min(MAX_RETRY_DELAY)
+    }
+}
+
+/// It returns the value of the [`SIGNATURE_HEADER`]: `sha256=` followed by
+/// the hex-encoded HMAC-SHA256 of the `timestamp`, a dot and the body.
+///
+/// # Panics
+///
+/// Will not panic: HMAC accepts keys of any size.
+#[must_use]
+pub fn signature(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC should accept keys of any size");
+
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use bittorrent_primitives::info_hash::InfoHash;
+    use bittorrent_tracker_core::event::Event;
+    use torrust_tracker_configuration::{WebhookTarget, Webhooks as Config};
+
+    use super::{signature, Webhooks, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+    /// A request received by the [`StandIn`].
+    #[derive(Debug, Clone)]
+    struct Received {
+        headers: HeaderMap,
+        body: String,
+    }
+
+    #[derive(Default)]
+    struct Recorded {
+        received: Vec<Received>,
+        /// The statuses of the next responses. `200` when there are no more.
+        statuses: Vec<StatusCode>,
+    }
+
+    /// A local HTTP server that records the webhook requests.
+    struct StandIn {
+        url: String,
+        recorded: Arc<Mutex<Recorded>>,
+    }
+
+    impl StandIn {
+        async fn start(statuses: Vec<StatusCode>) -> Self {
+            let recorded = Arc::new(Mutex::new(Recorded {
+                received: vec![],
+                statuses,
+            }));
+
+            let router = Router::new()
+                .route(
+                    "/tracker-events",
+                    post(
+                        |State(recorded): State<Arc<Mutex<Recorded>>>, headers: HeaderMap, body: Bytes| async move {
+                            let mut recorded = recorded.lock().unwrap();
+
+                            recorded.received.push(Received {
+                                headers,
+                                body: String::from_utf8(body.to_vec()).unwrap(),
+                            });
+
+                            if recorded.statuses.is_empty() {
+                                StatusCode::OK
+                            } else {
+                                recorded.statuses.remove(0)
+                            }
+                        },
+                    ),
+                )
+                .with_state(recorded.clone());
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/tracker-events", listener.local_addr().unwrap());
+
+            tokio::spawn(async move { axum::serve(listener, router).await });
+
+            Self { url, recorded }
+        }
+
+        /// It waits until the stand-in has received `count` requests.
+        async fn received(&self, count: usize) -> Vec<Received> {
+            tokio::time::timeout(Duration::from_secs(5), async {
+                loop {
+                    let received = self.recorded.lock().unwrap().received.clone();
+
+                    if received.len() >= count {
+                        return received;
+                    }
+
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("it should receive the webhook requests")
+        }
+    }
+
+    fn config(url: &str, events: &[&str]) -> Config {
+        Config {
+            secret: Some("MyWebhookSecret".to_string()),
+            targets: vec![WebhookTarget {
+                url: url.to_string(),
+                events: events.iter().map(ToString::to_string).collect(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn torrent_created() -> Event {
+        Event::TorrentCreated {
+            info_hash: InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(), // DevSkim: ignore DS173237
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_send_the_signed_event_to_the_target() {
+        let stand_in = StandIn::start(vec![]).await;
+
+        let (webhooks, worker) = Webhooks::new(&config(&stand_in.url, &[])).unwrap();
+        tokio::spawn(worker.run());
+
+        webhooks.notify(&torrent_created());
+
+        let received = stand_in.received(1).await;
+        let body = r#"{"type":"torrent_created","info_hash":"9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"}"#;
+
+        assert_eq!(received[0].body, body);
+        assert_eq!(received[0].headers[EVENT_HEADER], "torrent_created");
+
+        let timestamp = received[0].headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+
+        assert_eq!(
+            received[0].headers[SIGNATURE_HEADER],
+            signature("MyWebhookSecret", timestamp, body.as_bytes()).as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_only_send_the_events_of_the_target_types() {
+        let stand_in = StandIn::start(vec![]).await;
+
+        let (webhooks, worker) = Webhooks::new(&config(&stand_in.url, &["torrent_removed"])).unwrap();
+        tokio::spawn(worker.run());
+
+        webhooks.notify(&torrent_created());
+        webhooks.notify(&Event::TorrentRemoved {
+            info_hash: InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(), // DevSkim: ignore DS173237
+        });
+
+        let received = stand_in.received(1).await;
+
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].headers[EVENT_HEADER], "torrent_removed");
+    }
+
+    #[tokio::test]
+    async fn it_should_retry_the_failed_deliveries_with_the_same_delivery_id() {
+        let stand_in = StandIn::start(vec![StatusCode::INTERNAL_SERVER_ERROR, StatusCode::SERVICE_UNAVAILABLE]).await;
+
+        let (webhooks, mut worker) = Webhooks::new(&config(&stand_in.url, &[])).unwrap();
+        worker.first_retry_delay = Duration::from_millis(10);
+        tokio::spawn(worker.run());
+
+        webhooks.notify(&torrent_created());
+
+        let received = stand_in.received(3).await;
+
+        assert_eq!(received.len(), 3);
+        assert!(received
+            .iter()
+            .all(|request| request.headers[DELIVERY_HEADER] == received[0].headers[DELIVERY_HEADER]));
+    }
+
+    #[tokio::test]
+    async fn it_should_give_up_after_the_maximum_number_of_attempts() {
+        let stand_in = StandIn::start(vec![StatusCode::INTERNAL_SERVER_ERROR; 5]).await;
+
+        let mut config = config(&stand_in.url, &[]);
+        config.max_attempts = 2;
+
+        let (webhooks, mut worker) = Webhooks::new(&config).unwrap();
+        worker.first_retry_delay = Duration::from_millis(10);
+        tokio::spawn(worker.run());
+
+        webhooks.notify(&torrent_created());
+
+        stand_in.received(2).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(stand_in.recorded.lock().unwrap().received.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_delay_the_deliveries_to_a_target_while_another_one_does_not_respond() {
+        // It accepts the connections but never responds.
+        let unresponsive = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stand_in = StandIn::start(vec![]).await;
+
+        let mut config = config(&format!("http://{}/tracker-events", unresponsive.local_addr().unwrap()), &[]);
+        config.targets.push(WebhookTarget {
+            url: stand_in.url.clone(),
+            events: vec![],
+        });
+
+        let (webhooks, worker) = Webhooks::new(&config).unwrap();
+        tokio::spawn(worker.run());
+
+        webhooks.notify(&torrent_created());
+        webhooks.notify(&torrent_created());
+
+        assert_eq!(stand_in.received(2).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_drop_the_deliveries_when_the_queue_is_full() {
+        let mut config = config("http://127.0.0.1:1/tracker-events", &[]);
+        config.queue_capacity = 1;
+
+        let (webhooks, mut worker) = Webhooks::new(&config).unwrap();
+
+        // The worker is not running, so the deliveries stay in the queue.
+        webhooks.notify(&torrent_created());
+        webhooks.notify(&torrent_created());
+
+        assert!(worker.queues[0].try_recv().is_ok());
+        assert!(worker.queues[0].try_recv().is_err());
+    }
+
+    #[test]
+    fn it_should_not_allow_unknown_event_types() {
+        assert!(Webhooks::new(&config("http://127.0.0.1/tracker-events", &["torrent_updated"])).is_err());
+    }
+
+    #[test]
+    fn it_should_sign_the_timestamp_and_the_body_with_hmac_sha256() {
+        // echo -n '1700000000.{}' | openssl dgst -sha256 -hmac "MyWebhookSecret"
+        assert_eq!(
+            signature("MyWebhookSecret", 1_700_000_000, b"{}"),
+            "sha256=d1837aaa198ac568ae6c5ea2db0e17013c0f68d86530dad3464bc3d2333b4009"
+        );
+    }
+}
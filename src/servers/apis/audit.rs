@@ -0,0 +1,318 @@
+//! Audit log of the API actions that change the tracker state.
+//!
+//! Every API call to an endpoint that requires a write scope (see
+//! [`Scope::is_write`](torrust_tracker_configuration::Scope::is_write)) is
+//! recorded, including the calls rejected for missing scopes. The records are
+//! appended as JSON lines to the file configured in the `audit_log_path`
+//! option of the [HTTP API configuration](torrust_tracker_configuration::HttpApi):
+//!
+//! ```json
+//! {"timestamp":"2024-05-06T10:04:05+00:00","token_label":"admin","client_ip":"127.0.0.1","action":"DELETE /api/v1/torrent/{info_hash}","target":"/api/v1/torrent/9c38422213e30bff212b30c360d26f9a02136422","status":200,"result":"success"}
+//! ```
+//!
+//! The file is never truncated by the tracker. Operators can query the
+//! records with the [`audit`](crate::servers::apis::v1::context::audit) API
+//! context. Pages are read backwards from the end of the file, so getting the
+//! newest records does not read the whole file.
+//!
+//! The file is accessed with blocking I/O, so the [`AuditLog`] methods must
+//! not be called from the async executor.
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::net::IpAddr;
+use std::sync::Mutex;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+pub use torrust_tracker_api_resources::v1::audit::Outcome;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_clock::conv::convert_from_timestamp_to_datetime_utc;
+use torrust_tracker_primitives::pagination::Pagination;
+
+use crate::CurrentClock;
+
+/// A structured record of an API action.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// When the action finished, in RFC 3339 format.
+    pub timestamp: String,
+    /// The label of the token used to authenticate the request.
+    pub token_label: String,
+    /// The IP address of the API client, if known.
+    pub client_ip: Option<IpAddr>,
+    /// The request method and the route, for example
+    /// `POST /api/v1/whitelist/{info_hash}`.
+    pub action: String,
+    /// The requested path, for example
+    /// `/api/v1/whitelist/9c38422213e30bff212b30c360d26f9a02136422`.
+    pub target: String,
+    /// The HTTP status code of the response.
+    pub status: u16,
+    pub result: Outcome,
+}
+
+impl Record {
+    /// It creates a record timestamped with the current time.
+    #[must_use]
+    pub fn now(token_label: &str, client_ip: Option<IpAddr>, action: &str, target: &str, status: u16) -> Self {
+        Self {
+            timestamp: convert_from_timestamp_to_datetime_utc(CurrentClock::now()).to_rfc3339(),
+            token_label: token_label.to_string(),
+            client_ip,
+            action: action.to_string(),
+            target: target.to_string(),
+            status,
+            result: Outcome::from_status(status),
+        }
+    }
+}
+
+/// The number of bytes read at a time when reading the file backwards.
+const READ_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Append-only JSONL file with the [`Record`]s.
+#[derive(Debug)]
+pub struct AuditLog {
+    path: Utf8PathBuf,
+    /// Serializes the writes, and the reads of the file length, so that
+    /// readers never get a partially written line.
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    #[must_use]
+    pub fn new(path: &Utf8Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// It appends a record to the end of the file. The file and its parent
+    /// directory are created if they don't exist.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file cannot be written.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock is poisoned.
+    pub fn append(&self, record: &Record) -> io::Result<()> {
+        let mut line = serde_json::to_string(record).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().expect("it should get the audit log lock");
+
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+
+        file.write_all(line.as_bytes())
+    }
+
+    /// It returns a page of records, the newest first. Lines that are not
+    /// valid records are skipped.
+    ///
+    /// The file is read backwards from the end, and only until the page is
+    /// complete. The lock is only held to get the length of the file, so
+    /// reading does not block the writers.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file exists but cannot be read.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the lock is poisoned.
+    pub fn list(&self, pagination: &Pagination) -> io::Result<Vec<Record>> {
+        let (file, len) = {
+            let _guard = self.lock.lock().expect("it should get the audit log lock");
+
+            let file = match File::open(&self.path) {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+                Err(err) => return Err(err),
+            };
+
+            let len = file.metadata()?.len();
+
+            (file, len)
+        };
+
+        let mut records = vec![];
+        let mut skipped = 0;
+
+        for line in ReverseLines::new(file, len) {
+            let line = line?;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match serde_json::from_slice::<Record>(&line) {
+                Ok(_) if skipped < pagination.offset => skipped += 1,
+                Ok(record) => records.push(record),
+                Err(err) => tracing::warn!(path = %self.path, %err, "skipping invalid audit log line"),
+            }
+
+            if records.len() >= pagination.limit as usize {
+                break;
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+/// It iterates over the lines of the first `len` bytes of a file, from the
+/// last one to the first one, without the line breaks.
+struct ReverseLines {
+    file: File,
+    /// The position of the first byte of the file that has been read.
+    position: u64,
+    /// The bytes read that have not been returned yet.
+    pending: Vec<u8>,
+}
+
+impl ReverseLines {
+    fn new(file: File, len: u64) -> Self {
+        Self {
+            file,
+            position: len,
+            pending: vec![],
+        }
+    }
+
+    /// It reads the chunk of the file before the bytes already read.
+    #[allow(clippy::cast_possible_truncation)]
+    fn read_previous_chunk(&mut self) -> io::Result<()> {
+        let chunk_len = self.position.min(READ_CHUNK_SIZE);
+
+        self.position -= chunk_len;
+
+        let mut chunk = vec![0; chunk_len as usize];
+
+        self.file.seek(SeekFrom::Start(self.position))?;
+        self.file.read_exact(&mut chunk)?;
+
+        chunk.extend_from_slice(&self.pending);
+        self.pending = chunk;
+
+        Ok(())
+    }
+}
+
+impl Iterator for ReverseLines {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(line_break) = self.pending.iter().rposition(|byte| *byte == b'\n') {
+                let line = self.pending.split_off(line_break + 1);
+                self.pending.truncate(line_break);
+                return Some(Ok(line));
+            }
+
+            if self.position == 0 {
+                if self.pending.is_empty() {
+                    return None;
+                }
+                return Some(Ok(std::mem::take(&mut self.pending)));
+            }
+
+            if let Err(err) = self.read_previous_chunk() {
+                self.position = 0;
+                self.pending.clear();
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use camino::Utf8Path;
+    use torrust_tracker_primitives::pagination::Pagination;
+    use torrust_tracker_test_helpers::random;
+
+    use super::{AuditLog, Outcome, Record};
+
+    fn ephemeral_audit_log() -> AuditLog {
+        let path = env::temp_dir().join(format!("api_audit_{}.jsonl", random::string(16)));
+        AuditLog::new(Utf8Path::new(path.to_str().unwrap()))
+    }
+
+    fn sample_record(target: &str, status: u16) -> Record {
+        Record::now("admin", None, "DELETE /api/v1/torrent/{info_hash}", target, status)
+    }
+
+    #[test]
+    fn it_should_return_no_records_when_the_file_does_not_exist() {
+        let audit_log = ephemeral_audit_log();
+
+        assert!(audit_log.list(&Pagination::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_should_return_the_appended_records_newest_first() {
+        let audit_log = ephemeral_audit_log();
+
+        let first = sample_record("/api/v1/torrent/1", 200);
+        let second = sample_record("/api/v1/torrent/2", 200);
+
+        audit_log.append(&first).unwrap();
+        audit_log.append(&second).unwrap();
+
+        assert_eq!(audit_log.list(&Pagination::default()).unwrap(), vec![second, first]);
+    }
+
+    #[test]
+    fn it_should_paginate_the_records() {
+        let audit_log = ephemeral_audit_log();
+
+        let records: Vec<Record> = (0..5).map(|i| sample_record(&format!("/api/v1/torrent/{i}"), 200)).collect();
+
+        for record in &records {
+            audit_log.append(record).unwrap();
+        }
+
+        assert_eq!(
+            audit_log.list(&Pagination::new(1, 2)).unwrap(),
+            vec![records[3].clone(), records[2].clone()]
+        );
+    }
+
+    #[test]
+    fn it_should_read_records_longer_than_the_read_chunk() {
+        let audit_log = ephemeral_audit_log();
+
+        let target = format!(
+            "/api/v1/torrent/{}",
+            "a".repeat(usize::try_from(super::READ_CHUNK_SIZE).unwrap())
+        );
+        let records = vec![sample_record("/api/v1/torrent/1", 200), sample_record(&target, 200)];
+
+        for record in &records {
+            audit_log.append(record).unwrap();
+        }
+
+        assert_eq!(
+            audit_log.list(&Pagination::default()).unwrap(),
+            vec![records[1].clone(), records[0].clone()]
+        );
+    }
+
+    #[test]
+    fn it_should_derive_the_result_from_the_response_status() {
+        assert_eq!(sample_record("/", 200).result, Outcome::Success);
+        assert_eq!(sample_record("/", 403).result, Outcome::Forbidden);
+        assert_eq!(sample_record("/", 500).result, Outcome::Failure);
+    }
+}
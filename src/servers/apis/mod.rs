@@ -14,6 +14,7 @@
 //!
 //! - [Configuration](#configuration)
 //! - [Authentication](#authentication)
+//! - [Audit log](#audit-log)
 //! - [Versioning](#versioning)
 //! - [Endpoints](#endpoints)
 //! - [Documentation](#documentation)
@@ -85,10 +86,21 @@
 //!
 //! # Authentication
 //!
-//! The API supports authentication using a GET parameter token.
+//! The API supports authentication using a `Bearer` token in the
+//! `Authorization` header:
+//!
+//! ```text
+//! curl -H "Authorization: Bearer MyAccessToken" http://0.0.0.0:1212/api/v1/stats
+//! ```
+//!
+//! Or, as a legacy mode, using a GET parameter token:
 //!
 //! <http://0.0.0.0:1212/api/v1/stats?token=MyAccessToken>
 //!
+//! The GET parameter ends up in the logs of reverse proxies, so you should
+//! disable it with `token_query_param = false` in the `[http_api]` section
+//! once your clients use the header.
+//!
 //! You can set as many tokens as you want in the configuration file:
 //!
 //! ```toml
@@ -96,14 +108,55 @@
 //! admin = "MyAccessToken"
 //! ```
 //!
-//! The token label is used to identify the token. All tokens have full access
-//! to the API.
+//! The token label is used to identify the token. Plain tokens have full
+//! access to the API. Tokens can also be restricted to some scopes:
+//!
+//! ```toml
+//! [http_api.access_tokens]
+//! monitoring = { token = "MyMonitoringToken", scopes = ["stats:read", "torrents:read"] }
+//! ```
+//!
+//! The available scopes are `stats:read`, `torrents:read`, `torrents:write`,
+//! `whitelist:write`, `keys:write`, `config:write`, `listeners:read`,
+//! `listeners:write`, `audit:read`, `events:read`, `client-policy:read` and
+//! `client-policy:write`. Requests to endpoints out of the token scopes get a
+//! `403` response with the missing scope.
+//!
+//! The configuration can contain a hash of the token instead of the token
+//! itself. For example, a SHA-256 hash of the `token_pepper` followed by the
+//! token:
+//!
+//! ```toml
+//! [http_api]
+//! token_pepper = "MyPepper"
+//!
+//! [http_api.access_tokens]
+//! admin = "sha256:bf861ed58fa98054d5cf77018e8fce13fb8e6d7e8f0628b1283ff675535ee5fa"
+//! ```
+//!
+//! You can generate it with `echo -n "MyPepperMyAccessToken" | sha256sum`.
+//! Argon2 hashes in the PHC string format are supported too.
 //!
 //! Refer to [`torrust-tracker-configuration`](torrust_tracker_configuration)
 //! for more information about the API configuration and to the
 //! [`auth`](crate::servers::apis::v1::middlewares::auth) middleware for more
 //! information about the authentication process.
 //!
+//! # Audit log
+//!
+//! Every API call to an endpoint that can change the tracker state is
+//! recorded with the token label, the client IP, the action and its result.
+//! The records are appended to a JSONL file:
+//!
+//! ```toml
+//! [http_api]
+//! audit_log_path = "./storage/tracker/lib/api_audit.jsonl"
+//! ```
+//!
+//! Tokens with the `audit:read` scope can query the records with the
+//! [`audit`](crate::servers::apis::v1::context::audit) endpoint. Refer to the
+//! [`audit`] module for more information about the records.
+//!
 //! # Setup SSL (optional)
 //!
 //! The API server supports SSL. You can enable it by adding the `tsl_config`
@@ -148,11 +201,16 @@
 //!
 //! # Documentation
 //!
+//! The [`OpenAPI` specification](crate::servers::apis::v1::context::openapi)
+//! of the API is served at `/api/v1/openapi.json`. You can use it to
+//! generate API clients.
+//!
 //! If you want to contribute to this documentation you can [open a new pull request](https://github.com/torrust/torrust-tracker/pulls).
 //!
 //! > **NOTICE**: we are using [curl](https://curl.se/) in the API examples.
 //! > And you have to use quotes around the URL in order to avoid unexpected
 //! > errors. For example: `curl "http://127.0.0.1:1212/api/v1/stats?token=MyAccessToken"`.
+pub mod audit;
 pub mod routes;
 pub mod server;
 pub mod v1;
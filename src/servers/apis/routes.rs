@@ -1,7 +1,8 @@
 //! API routes.
 //!
 //! It loads all the API routes for all API versions and adds the authentication
-//! middleware to them.
+//! middleware to them. Each API version enforces the scopes required by its
+//! routes.
 //!
 //! All the API routes have the `/api` prefix and the version number as the
 //! first path segment. For example: `/api/v1/torrents`.
@@ -15,6 +16,7 @@ use axum::response::Response;
 use axum::routing::get;
 use axum::{middleware, BoxError, Router};
 use hyper::{Request, StatusCode};
+use tokio::sync::RwLock;
 use torrust_tracker_configuration::{AccessTokens, DEFAULT_TIMEOUT};
 use tower::timeout::TimeoutLayer;
 use tower::ServiceBuilder;
@@ -22,13 +24,13 @@ use tower_http::classify::ServerErrorsFailureClass;
 use tower_http::compression::CompressionLayer;
 use tower_http::propagate_header::PropagateHeaderLayer;
 use tower_http::request_id::{MakeRequestUuid, SetRequestIdLayer};
-use tower_http::trace::{DefaultMakeSpan, TraceLayer};
+use tower_http::trace::TraceLayer;
 use tower_http::LatencyUnit;
 use tracing::{instrument, Level, Span};
 
 use super::v1;
 use super::v1::context::health_check::handlers::health_check_handler;
-use super::v1::middlewares::auth::State;
+use super::v1::middlewares::auth::{redacted_uri, State};
 use crate::container::HttpApiContainer;
 use crate::servers::apis::API_LOG_TARGET;
 use crate::servers::logging::Latency;
@@ -37,7 +39,7 @@ use crate::servers::logging::Latency;
 #[instrument(skip(http_api_container, access_tokens))]
 pub fn router(
     http_api_container: Arc<HttpApiContainer>,
-    access_tokens: Arc<AccessTokens>,
+    access_tokens: Arc<RwLock<AccessTokens>>,
     server_socket_addr: SocketAddr,
 ) -> Router {
     let router = Router::new();
@@ -46,20 +48,38 @@ pub fn router(
 
     let router = v1::routes::add(api_url_prefix, router, &http_api_container);
 
-    let state = State { access_tokens };
+    let state = State {
+        access_tokens,
+        token_pepper: http_api_container.http_api_config.token_pepper.clone(),
+        token_query_param: http_api_container.http_api_config.token_query_param,
+        argon2_verifier: Arc::default(),
+    };
 
     router
         .layer(middleware::from_fn_with_state(state, v1::middlewares::auth::auth))
         .route(&format!("{api_url_prefix}/health_check"), get(health_check_handler))
+        .merge(v1::context::openapi::routes::add(
+            &format!("{api_url_prefix}/v1"),
+            Router::new(),
+        ))
         .layer(CompressionLayer::new())
         .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(PropagateHeaderLayer::new(HeaderName::from_static("x-request-id")))
         .layer(
             TraceLayer::new_for_http()
-                .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+                // The default span includes the URI, which can contain the token.
+                .make_span_with(|request: &Request<axum::body::Body>| {
+                    tracing::span!(
+                        Level::INFO,
+                        "request",
+                        method = %request.method(),
+                        uri = %redacted_uri(request.uri()),
+                        version = ?request.version(),
+                    )
+                })
                 .on_request(|request: &Request<axum::body::Body>, span: &Span| {
                     let method = request.method().to_string();
-                    let uri = request.uri().to_string();
+                    let uri = redacted_uri(request.uri());
                     let request_id = request
                         .headers()
                         .get("x-request-id")
@@ -33,6 +33,7 @@ use derive_more::Constructor;
 use futures::future::BoxFuture;
 use thiserror::Error;
 use tokio::sync::oneshot::{Receiver, Sender};
+use tokio::sync::RwLock;
 use torrust_tracker_configuration::AccessTokens;
 use tracing::{instrument, Level};
 
@@ -127,7 +128,7 @@ impl ApiServer<Stopped> {
         self,
         http_api_container: Arc<HttpApiContainer>,
         form: ServiceRegistrationForm,
-        access_tokens: Arc<AccessTokens>,
+        access_tokens: Arc<RwLock<AccessTokens>>,
     ) -> Result<ApiServer<Running>, Error> {
         let (tx_start, rx_start) = tokio::sync::oneshot::channel::<Started>();
         let (tx_halt, rx_halt) = tokio::sync::oneshot::channel::<Halted>();
@@ -239,7 +240,7 @@ impl Launcher {
     pub fn start(
         &self,
         http_api_container: Arc<HttpApiContainer>,
-        access_tokens: Arc<AccessTokens>,
+        access_tokens: Arc<RwLock<AccessTokens>>,
         tx_start: Sender<Started>,
         rx_halt: Receiver<Halted>,
     ) -> BoxFuture<'static, ()> {
@@ -300,6 +301,7 @@ mod tests {
     use crate::bootstrap::jobs::make_rust_tls;
     use crate::container::HttpApiContainer;
     use crate::servers::apis::server::{ApiServer, Launcher};
+    use crate::servers::listeners::Listeners;
     use crate::servers::registar::Registar;
 
     #[tokio::test]
@@ -317,13 +319,19 @@ mod tests {
             .await
             .map(|tls| tls.expect("tls config failed"));
 
-        let access_tokens = Arc::new(http_api_config.access_tokens.clone());
-
         let stopped = ApiServer::new(Launcher::new(bind_to, tls));
 
         let register = &Registar::default();
 
-        let http_api_container = Arc::new(HttpApiContainer::from_app_container(&http_api_config, &app_container));
+        let listeners = Arc::new(Listeners::new(&app_container, register));
+
+        let http_api_container = Arc::new(HttpApiContainer::from_app_container(
+            &http_api_config,
+            &app_container,
+            &listeners,
+        ));
+
+        let access_tokens = http_api_container.access_tokens.clone();
 
         let started = stopped
             .start(http_api_container, register.give_form(), access_tokens)
@@ -0,0 +1,45 @@
+//! API handlers for the [`audit`](crate::servers::apis::v1::context::audit)
+//! API context.
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Response;
+use axum_extra::extract::Query;
+use serde::Deserialize;
+use torrust_tracker_primitives::pagination::Pagination;
+
+use super::responses::{audit_records_response, failed_to_read_audit_log_response};
+use crate::servers::apis::audit::AuditLog;
+
+/// A container for the pagination URL query parameters: `offset` and
+/// `limit`.
+#[derive(Deserialize, Debug, Default)]
+pub struct QueryParams {
+    /// The offset of the first page to return. Starts at 0.
+    #[serde(default)]
+    pub offset: Option<u32>,
+    /// The maximum number of items to return per page.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// It handles the request to get the audit records.
+///
+/// It returns:
+///
+/// - `200` response with a json array of [`AuditRecord`](crate::servers::apis::v1::context::audit::resources::AuditRecord)s,
+///   the newest first.
+/// - `500` with serialized error in debug format if the audit log cannot be
+///   read.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::audit#get-the-audit-records)
+/// for more information about this endpoint.
+pub async fn get_audit_records_handler(State(audit_log): State<Arc<AuditLog>>, pagination: Query<QueryParams>) -> Response {
+    let pagination = Pagination::new_with_options(pagination.0.offset, pagination.0.limit);
+
+    match tokio::task::spawn_blocking(move || audit_log.list(&pagination)).await {
+        Ok(Ok(records)) => audit_records_response(records),
+        Ok(Err(e)) => failed_to_read_audit_log_response(e),
+        Err(e) => failed_to_read_audit_log_response(e),
+    }
+}
@@ -0,0 +1,57 @@
+//! Audit API context.
+//!
+//! This API context is responsible for handling the requests related to the
+//! [audit log](crate::servers::apis::audit) of the API actions that change
+//! the tracker state.
+//!
+//! # Endpoints
+//!
+//! - [Get the audit records](#get-the-audit-records)
+//!
+//! # Get the audit records
+//!
+//! `GET /audit`
+//!
+//! It returns the audit records, the newest first. It requires a token with
+//! the `audit:read` scope.
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Default | Example
+//! ---|---|---|---|---|---
+//! `offset` | positive integer | The page number, starting at 0 | No | 0 | `0`
+//! `limit` | positive integer | Page size. The number of results per page | No | 4000 | `10`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/audit?token=MyAccessToken&limit=1"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!         "timestamp": "2024-05-06T10:04:05+00:00",
+//!         "token_label": "admin",
+//!         "client_ip": "127.0.0.1",
+//!         "action": "DELETE /api/v1/torrent/{info_hash}",
+//!         "target": "/api/v1/torrent/9c38422213e30bff212b30c360d26f9a02136422",
+//!         "status": 200,
+//!         "result": "success"
+//!     }
+//! ]
+//! ```
+//!
+//! The `result` is `success`, `forbidden` (the token does not have the
+//! required scope) or `failure` (invalid request or internal error).
+//!
+//! **Resource**
+//!
+//! Refer to the API [`AuditRecord`](crate::servers::apis::v1::context::audit::resources::AuditRecord)
+//! resource for more information about the response attributes.
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,19 @@
+//! API resources for the [`audit`](crate::servers::apis::v1::context::audit)
+//! API context.
+pub use torrust_tracker_api_resources::v1::audit::AuditRecord;
+
+use crate::servers::apis::audit;
+
+impl From<audit::Record> for AuditRecord {
+    fn from(record: audit::Record) -> Self {
+        Self {
+            timestamp: record.timestamp,
+            token_label: record.token_label,
+            client_ip: record.client_ip,
+            action: record.action,
+            target: record.target,
+            status: record.status,
+            result: record.result,
+        }
+    }
+}
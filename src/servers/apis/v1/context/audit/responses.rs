@@ -0,0 +1,21 @@
+//! API responses for the [`audit`](crate::servers::apis::v1::context::audit)
+//! API context.
+use std::error::Error;
+
+use axum::response::{IntoResponse, Json, Response};
+
+use super::resources::AuditRecord;
+use crate::servers::apis::audit;
+use crate::servers::apis::v1::responses::unhandled_rejection_response;
+
+/// `200` response that contains an array of [`AuditRecord`] resources as json.
+#[must_use]
+pub fn audit_records_response(records: Vec<audit::Record>) -> Response {
+    Json(records.into_iter().map(AuditRecord::from).collect::<Vec<_>>()).into_response()
+}
+
+/// `500` error response when the audit log cannot be read.
+#[must_use]
+pub fn failed_to_read_audit_log_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to read audit log: {e}"))
+}
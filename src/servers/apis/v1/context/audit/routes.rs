@@ -0,0 +1,20 @@
+//! API routes for the [`audit`](crate::servers::apis::v1::context::audit) API context.
+//!
+//! - `GET /audit`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::audit).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+
+use super::handlers::get_audit_records_handler;
+use crate::servers::apis::audit::AuditLog;
+
+/// It adds the routes to the router for the [`audit`](crate::servers::apis::v1::context::audit) API context.
+pub fn add(prefix: &str, router: Router, audit_log: &Arc<AuditLog>) -> Router {
+    router.route(
+        &format!("{prefix}/audit"),
+        get(get_audit_records_handler).with_state(audit_log.clone()),
+    )
+}
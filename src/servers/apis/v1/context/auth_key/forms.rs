@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DefaultOnNull};
 
@@ -7,7 +8,7 @@ use serde_with::{serde_as, DefaultOnNull};
 /// You can also set an expiration date or leave it empty (`None`) if you want
 /// to create permanent key that does not expire.
 #[serde_as]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub struct AddKeyForm {
     /// The pre-generated key. Use `None` (null in json) to generate a random key.
     #[serde_as(deserialize_as = "DefaultOnNull")]
@@ -14,14 +14,14 @@ use super::responses::{
     auth_key_response, failed_to_delete_key_response, failed_to_generate_key_response, failed_to_reload_keys_response,
     invalid_auth_key_duration_response, invalid_auth_key_response,
 };
-use crate::servers::apis::v1::context::auth_key::resources::AuthKey;
+use crate::servers::apis::v1::context::auth_key::resources::auth_key_resource;
 use crate::servers::apis::v1::responses::{invalid_auth_key_param_response, ok_response};
 
 /// It handles the request to add a new authentication key.
 ///
 /// It returns these types of responses:
 ///
-/// - `200` with a json [`AuthKey`]
+/// - `200` with a json [`AuthKey`](crate::servers::apis::v1::context::auth_key::resources::AuthKey)
 ///   resource. If the key was generated successfully.
 /// - `400` with an error if the key couldn't been added because of an invalid
 ///   request.
@@ -41,7 +41,7 @@ pub async fn add_auth_key_handler(
         })
         .await
     {
-        Ok(auth_key) => auth_key_response(&AuthKey::from(auth_key)),
+        Ok(auth_key) => auth_key_response(&auth_key_resource(&auth_key)),
         Err(err) => match err {
             bittorrent_tracker_core::error::PeerKeyError::DurationOverflow { seconds_valid } => {
                 invalid_auth_key_duration_response(seconds_valid)
@@ -56,7 +56,7 @@ pub async fn add_auth_key_handler(
 ///
 /// It returns two types of responses:
 ///
-/// - `200` with an json [`AuthKey`]
+/// - `200` with an json [`AuthKey`](crate::servers::apis::v1::context::auth_key::resources::AuthKey)
 ///   resource. If the key was generated successfully.
 /// - `500` with serialized error in debug format. If the key couldn't be
 ///   generated.
@@ -74,7 +74,7 @@ pub async fn generate_auth_key_handler(
         .generate_expiring_peer_key(Some(Duration::from_secs(seconds_valid)))
         .await
     {
-        Ok(auth_key) => auth_key_response(&AuthKey::from(auth_key)),
+        Ok(auth_key) => auth_key_response(&auth_key_resource(&auth_key)),
         Err(e) => failed_to_generate_key_response(e),
     }
 }
@@ -1,47 +1,41 @@
 //! API resources for the [`auth_key`](crate::servers::apis::v1::context::auth_key) API context.
 
 use bittorrent_tracker_core::authentication::{self, Key};
-use serde::{Deserialize, Serialize};
+pub use torrust_tracker_api_resources::v1::auth_key::AuthKey;
 use torrust_tracker_clock::conv::convert_from_iso_8601_to_timestamp;
 
-/// A resource that represents an authentication key.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
-pub struct AuthKey {
-    /// The authentication key.
-    pub key: String,
-    /// The timestamp when the key will expire.
-    #[deprecated(since = "3.0.0", note = "please use `expiry_time` instead")]
-    pub valid_until: Option<u64>, // todo: remove when the torrust-index-backend starts using the `expiry_time` attribute.
-    /// The ISO 8601 timestamp when the key will expire.
-    pub expiry_time: Option<String>,
-}
-
-impl From<AuthKey> for authentication::PeerKey {
-    fn from(auth_key_resource: AuthKey) -> Self {
-        authentication::PeerKey {
-            key: auth_key_resource.key.parse::<Key>().unwrap(),
-            valid_until: auth_key_resource
-                .expiry_time
-                .map(|expiry_time| convert_from_iso_8601_to_timestamp(&expiry_time)),
-        }
+/// Maps the API resource type [`AuthKey`] to the domain type
+/// [`PeerKey`](authentication::PeerKey).
+///
+/// # Panics
+///
+/// Will panic if the key in the resource is not a valid key.
+#[must_use]
+pub fn peer_key(auth_key_resource: AuthKey) -> authentication::PeerKey {
+    authentication::PeerKey {
+        key: auth_key_resource.key.parse::<Key>().unwrap(),
+        valid_until: auth_key_resource
+            .expiry_time
+            .map(|expiry_time| convert_from_iso_8601_to_timestamp(&expiry_time)),
     }
 }
 
+/// Maps the domain type [`PeerKey`](authentication::PeerKey) to the API
+/// resource type [`AuthKey`].
+#[must_use]
 #[allow(deprecated)]
-impl From<authentication::PeerKey> for AuthKey {
-    fn from(auth_key: authentication::PeerKey) -> Self {
-        match (auth_key.valid_until, auth_key.expiry_time()) {
-            (Some(valid_until), Some(expiry_time)) => AuthKey {
-                key: auth_key.key.to_string(),
-                valid_until: Some(valid_until.as_secs()),
-                expiry_time: Some(expiry_time.to_string()),
-            },
-            _ => AuthKey {
-                key: auth_key.key.to_string(),
-                valid_until: None,
-                expiry_time: None,
-            },
-        }
+pub fn auth_key_resource(auth_key: &authentication::PeerKey) -> AuthKey {
+    match (auth_key.valid_until, auth_key.expiry_time()) {
+        (Some(valid_until), Some(expiry_time)) => AuthKey {
+            key: auth_key.key.to_string(),
+            valid_until: Some(valid_until.as_secs()),
+            expiry_time: Some(expiry_time.to_string()),
+        },
+        _ => AuthKey {
+            key: auth_key.key.to_string(),
+            valid_until: None,
+            expiry_time: None,
+        },
     }
 }
 
@@ -53,7 +47,7 @@ mod tests {
     use torrust_tracker_clock::clock::stopped::Stopped as _;
     use torrust_tracker_clock::clock::{self, Time};
 
-    use super::AuthKey;
+    use super::{auth_key_resource, peer_key, AuthKey};
     use crate::CurrentClock;
 
     struct TestTime {
@@ -85,7 +79,7 @@ mod tests {
         };
 
         assert_eq!(
-            authentication::PeerKey::from(auth_key_resource),
+            peer_key(auth_key_resource),
             authentication::PeerKey {
                 key: "IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM".parse::<Key>().unwrap(), // cspell:disable-line
                 valid_until: Some(CurrentClock::now_add(&Duration::new(one_hour_after_unix_epoch().timestamp, 0)).unwrap())
@@ -104,7 +98,7 @@ mod tests {
         };
 
         assert_eq!(
-            AuthKey::from(auth_key),
+            auth_key_resource(&auth_key),
             AuthKey {
                 key: "IaWDneuFNZi8IB4MPA3qW1CD0M30EZSM".to_string(), // cspell:disable-line
                 valid_until: Some(one_hour_after_unix_epoch().timestamp),
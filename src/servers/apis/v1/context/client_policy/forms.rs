@@ -0,0 +1,15 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This type contains the info needed to add a new client rule.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct AddClientRuleForm {
+    /// What to do with the matching peers: `allow` or `deny`.
+    pub action: String,
+
+    /// How the pattern matches the peer IDs: `prefix` or `regex`.
+    pub kind: String,
+
+    /// The peer ID prefix or regular expression.
+    pub pattern: String,
+}
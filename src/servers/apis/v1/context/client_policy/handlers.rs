@@ -0,0 +1,85 @@
+//! API handlers for the [`client_policy`](crate::servers::apis::v1::context::client_policy)
+//! API context.
+use std::sync::Arc;
+
+use axum::extract::{self, Path, State};
+use axum::response::Response;
+use bittorrent_tracker_core::client_policy::manager::ClientPolicyManager;
+use bittorrent_tracker_core::client_policy::{Action, NewClientRule, PatternKind};
+
+use super::forms::AddClientRuleForm;
+use super::responses::{
+    client_policy_error_response, client_rule_list_response, client_rule_response, invalid_client_rule_action_response,
+    invalid_client_rule_kind_response,
+};
+use crate::servers::apis::v1::responses::ok_response;
+
+/// It handles the request to list the client rules.
+///
+/// It returns a `200` response with a json array of
+/// [`ClientRule`](crate::servers::apis::v1::context::client_policy::resources::ClientRule)
+/// resources.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::client_policy#list-the-client-rules)
+/// for more information about this endpoint.
+pub async fn get_client_rules_handler(State(client_policy_manager): State<Arc<ClientPolicyManager>>) -> Response {
+    client_rule_list_response(&client_policy_manager.get_rules().await)
+}
+
+/// It handles the request to add a new client rule.
+///
+/// It returns:
+///
+/// - `200` with a json [`ClientRule`](crate::servers::apis::v1::context::client_policy::resources::ClientRule)
+///   resource if the rule was added.
+/// - `400` with an error if the request is not valid.
+/// - `500` with serialized error in debug format if the rule couldn't be
+///   persisted.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::client_policy#add-a-client-rule)
+/// for more information about this endpoint.
+pub async fn add_client_rule_handler(
+    State(client_policy_manager): State<Arc<ClientPolicyManager>>,
+    extract::Json(add_client_rule_form): extract::Json<AddClientRuleForm>,
+) -> Response {
+    let Ok(action) = add_client_rule_form.action.parse::<Action>() else {
+        return invalid_client_rule_action_response(&add_client_rule_form.action);
+    };
+
+    let Ok(kind) = add_client_rule_form.kind.parse::<PatternKind>() else {
+        return invalid_client_rule_kind_response(&add_client_rule_form.kind);
+    };
+
+    match client_policy_manager
+        .add_rule(NewClientRule {
+            action,
+            kind,
+            pattern: add_client_rule_form.pattern,
+        })
+        .await
+    {
+        Ok(rule) => client_rule_response(&rule),
+        Err(e) => client_policy_error_response(&e),
+    }
+}
+
+/// It handles the request to remove a client rule.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json.
+/// - `400` with an error if the rule does not exist.
+/// - `500` with serialized error in debug format if the rule couldn't be
+///   removed from the database.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::client_policy#remove-a-client-rule)
+/// for more information about this endpoint.
+pub async fn remove_client_rule_handler(
+    State(client_policy_manager): State<Arc<ClientPolicyManager>>,
+    Path(id): Path<u64>,
+) -> Response {
+    match client_policy_manager.remove_rule(id).await {
+        Ok(()) => ok_response(),
+        Err(e) => client_policy_error_response(&e),
+    }
+}
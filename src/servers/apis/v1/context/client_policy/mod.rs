@@ -0,0 +1,127 @@
+//! Client policy API context.
+//!
+//! This API context is responsible for handling the requests related to the
+//! rules that allow or deny clients by their peer ID.
+//!
+//! The `announce` requests from peers whose client is not allowed are
+//! rejected with a failure reason, for example:
+//!
+//! ```text
+//! Tracker error: The client: Transmission 2.94, is not allowed
+//! ```
+//!
+//! Refer to the [`client_policy`](bittorrent_tracker_core::client_policy)
+//! module for more information about how the rules are evaluated.
+//!
+//! The rules are stored in the database, so they are kept when the tracker
+//! restarts.
+//!
+//! # Endpoints
+//!
+//! - [List the client rules](#list-the-client-rules)
+//! - [Add a client rule](#add-a-client-rule)
+//! - [Remove a client rule](#remove-a-client-rule)
+//!
+//! # List the client rules
+//!
+//! `GET /client-policy/rules`
+//!
+//! It returns all the rules ordered by ID.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/client-policy/rules?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!         "id": 1,
+//!         "action": "deny",
+//!         "kind": "prefix",
+//!         "pattern": "-TR2"
+//!     }
+//! ]
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`ClientRule`](crate::servers::apis::v1::context::client_policy::resources::ClientRule)
+//! resource for more information about the response attributes.
+//!
+//! # Add a client rule
+//!
+//! `POST /client-policy/rules`
+//!
+//! It adds a rule and returns it with its ID.
+//!
+//! **POST params (json)**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `action` | string | `allow` or `deny` the matching peers | Yes | `deny`
+//! `kind` | string | `prefix` or `regex` | Yes | `prefix`
+//! `pattern` | string | The peer ID prefix or regular expression, up to 255 bytes | Yes | `-TR2`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST http://localhost:1212/api/v1/client-policy/rules?token=MyAccessToken \
+//!   -H "Content-Type: application/json" \
+//!   -d '{
+//!     "action": "deny",
+//!     "kind": "regex",
+//!     "pattern": "^-qB4[0-2]"
+//!   }'
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "id": 2,
+//!     "action": "deny",
+//!     "kind": "regex",
+//!     "pattern": "^-qB4[0-2]"
+//! }
+//! ```
+//!
+//! It returns a `400` response if the action or the kind are not valid, or the
+//! pattern is empty, longer than a peer ID (prefixes) or not a valid regular
+//! expression.
+//!
+//! # Remove a client rule
+//!
+//! `DELETE /client-policy/rules/:id`
+//!
+//! It removes a rule.
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `id` | positive integer | The rule ID | Yes | `1`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X DELETE "http://127.0.0.1:1212/api/v1/client-policy/rules/1?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! It returns a `400` response if the rule does not exist.
+pub mod forms;
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,41 @@
+//! API resources for the [`client_policy`](crate::servers::apis::v1::context::client_policy)
+//! API context.
+use bittorrent_tracker_core::client_policy;
+pub use torrust_tracker_api_resources::v1::client_policy::ClientRule;
+
+/// Maps the domain type [`ClientRule`](client_policy::ClientRule) to the API
+/// resource type [`ClientRule`].
+#[must_use]
+pub fn client_rule_resource(rule: &client_policy::ClientRule) -> ClientRule {
+    ClientRule {
+        id: rule.id,
+        action: rule.action.to_string(),
+        kind: rule.kind.to_string(),
+        pattern: rule.pattern.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bittorrent_tracker_core::client_policy::{self, Action, PatternKind};
+
+    use super::{client_rule_resource, ClientRule};
+
+    #[test]
+    fn it_should_be_converted_from_the_domain_rule() {
+        assert_eq!(
+            client_rule_resource(&client_policy::ClientRule {
+                id: 1,
+                action: Action::Deny,
+                kind: PatternKind::Prefix,
+                pattern: "-TR2".to_string(),
+            }),
+            ClientRule {
+                id: 1,
+                action: "deny".to_string(),
+                kind: "prefix".to_string(),
+                pattern: "-TR2".to_string(),
+            }
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! API responses for the [`client_policy`](crate::servers::apis::v1::context::client_policy)
+//! API context.
+use axum::response::{IntoResponse, Json, Response};
+use bittorrent_tracker_core::client_policy;
+use bittorrent_tracker_core::error::ClientPolicyError;
+
+use super::resources::{client_rule_resource, ClientRule};
+use crate::servers::apis::v1::responses::{bad_request_response, unhandled_rejection_response};
+
+/// `200` response that contains the [`ClientRule`] resource as json.
+#[must_use]
+pub fn client_rule_response(rule: &client_policy::ClientRule) -> Response {
+    Json(client_rule_resource(rule)).into_response()
+}
+
+/// `200` response that contains an array of [`ClientRule`] resources as json.
+#[must_use]
+pub fn client_rule_list_response(rules: &[client_policy::ClientRule]) -> Response {
+    Json(rules.iter().map(client_rule_resource).collect::<Vec<ClientRule>>()).into_response()
+}
+
+#[must_use]
+pub fn invalid_client_rule_action_response(action: &str) -> Response {
+    bad_request_response(&format!(
+        "Invalid client rule action: \"{action}\", expected \"allow\" or \"deny\""
+    ))
+}
+
+#[must_use]
+pub fn invalid_client_rule_kind_response(kind: &str) -> Response {
+    bad_request_response(&format!(
+        "Invalid client rule kind: \"{kind}\", expected \"prefix\" or \"regex\""
+    ))
+}
+
+/// Error response when an operation on a client rule fails.
+///
+/// It's a `400` response if the pattern is not valid or the rule does not
+/// exist. Otherwise, it's a `500` response.
+#[must_use]
+pub fn client_policy_error_response(e: &ClientPolicyError) -> Response {
+    match e {
+        ClientPolicyError::InvalidPattern { .. } | ClientPolicyError::RuleNotFound { .. } => bad_request_response(&e.to_string()),
+        ClientPolicyError::ClientNotAllowed { .. } | ClientPolicyError::DatabaseError { .. } => {
+            unhandled_rejection_response(format!("failed to update the client rules: {e}"))
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! API routes for the [`client_policy`](crate::servers::apis::v1::context::client_policy)
+//! API context.
+//!
+//! - `GET /client-policy/rules`
+//! - `POST /client-policy/rules`
+//! - `DELETE /client-policy/rules/:id`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::client_policy).
+use std::sync::Arc;
+
+use axum::routing::{delete, get};
+use axum::Router;
+use bittorrent_tracker_core::client_policy::manager::ClientPolicyManager;
+
+use super::handlers::{add_client_rule_handler, get_client_rules_handler, remove_client_rule_handler};
+
+/// It adds the routes to the router for the [`client_policy`](crate::servers::apis::v1::context::client_policy) API context.
+pub fn add(prefix: &str, router: Router, client_policy_manager: &Arc<ClientPolicyManager>) -> Router {
+    let prefix = format!("{prefix}/client-policy");
+
+    router
+        .route(
+            &format!("{prefix}/rules"),
+            get(get_client_rules_handler)
+                .with_state(client_policy_manager.clone())
+                .post(add_client_rule_handler)
+                .with_state(client_policy_manager.clone()),
+        )
+        .route(
+            &format!("{prefix}/rules/{{id}}"),
+            delete(remove_client_rule_handler).with_state(client_policy_manager.clone()),
+        )
+}
@@ -0,0 +1,26 @@
+//! API handlers for the [`config`](crate::servers::apis::v1::context::config)
+//! API context.
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Response;
+
+use super::responses::{failed_to_reload_config_response, reload_report_response};
+use crate::bootstrap::config::Reloader;
+
+/// It handles the request to reload the configuration.
+///
+/// It returns:
+///
+/// - `200` response with a json [`ReloadReport`](crate::servers::apis::v1::context::config::resources::ReloadReport).
+/// - `500` with serialized error in debug format if the new configuration
+///   couldn't be loaded or it's not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::config#reload-the-configuration)
+/// for more information about this endpoint.
+pub async fn reload_config_handler(State(config_reloader): State<Arc<Reloader>>) -> Response {
+    match config_reloader.reload_from_sources().await {
+        Ok(report) => reload_report_response(report),
+        Err(e) => failed_to_reload_config_response(e),
+    }
+}
@@ -0,0 +1,50 @@
+//! Configuration API context.
+//!
+//! This API context is responsible for handling the requests related to the
+//! tracker configuration.
+//!
+//! # Endpoints
+//!
+//! - [Reload the configuration](#reload-the-configuration)
+//!
+//! # Reload the configuration
+//!
+//! `GET /config/reload`
+//!
+//! It loads the configuration again from its sources (the configuration file
+//! or the `TORRUST_TRACKER_CONFIG_TOML` environment variable) and applies the
+//! settings that can be changed without restarting the tracker. It's the
+//! same as sending a `SIGHUP` signal to the tracker process.
+//!
+//! The response contains the changed settings that have been applied and the
+//! ones that require a restart. Refer to the
+//! [`Reloader`](crate::bootstrap::config::Reloader) for the list of settings
+//! that can be changed without restarting the tracker.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/config/reload?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "applied": [
+//!         "core.announce_policy.interval"
+//!     ],
+//!     "requires_restart": [
+//!         "core.inactive_peer_cleanup_interval"
+//!     ]
+//! }
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`ReloadReport`](crate::servers::apis::v1::context::config::resources::ReloadReport)
+//! resource for more information about the response attributes.
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,34 @@
+//! API resources for the [`config`](crate::servers::apis::v1::context::config)
+//! API context.
+pub use torrust_tracker_api_resources::v1::config::ReloadReport;
+
+use crate::bootstrap::config;
+
+impl From<config::ReloadReport> for ReloadReport {
+    fn from(report: config::ReloadReport) -> Self {
+        Self {
+            applied: report.applied,
+            requires_restart: report.requires_restart,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReloadReport;
+    use crate::bootstrap::config;
+
+    #[test]
+    fn it_should_be_converted_from_the_reload_report_of_the_configuration_reloader() {
+        assert_eq!(
+            ReloadReport::from(config::ReloadReport {
+                applied: vec!["core.announce_policy.interval".to_string()],
+                requires_restart: vec!["core.private".to_string()],
+            }),
+            ReloadReport {
+                applied: vec!["core.announce_policy.interval".to_string()],
+                requires_restart: vec!["core.private".to_string()],
+            }
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! API responses for the [`config`](crate::servers::apis::v1::context::config)
+//! API context.
+use std::error::Error;
+
+use axum::response::{IntoResponse, Json, Response};
+
+use super::resources::ReloadReport;
+use crate::bootstrap::config;
+use crate::servers::apis::v1::responses::unhandled_rejection_response;
+
+/// `200` response that contains the [`ReloadReport`] resource as json.
+#[must_use]
+pub fn reload_report_response(report: config::ReloadReport) -> Response {
+    Json(ReloadReport::from(report)).into_response()
+}
+
+/// `500` error response when the configuration cannot be reloaded.
+#[must_use]
+pub fn failed_to_reload_config_response<E: Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to reload configuration: {e}"))
+}
@@ -0,0 +1,20 @@
+//! API routes for the [`config`](crate::servers::apis::v1::context::config) API context.
+//!
+//! - `GET /config/reload`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::config).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+
+use super::handlers::reload_config_handler;
+use crate::bootstrap::config::Reloader;
+
+/// It adds the routes to the router for the [`config`](crate::servers::apis::v1::context::config) API context.
+pub fn add(prefix: &str, router: Router, config_reloader: &Arc<Reloader>) -> Router {
+    router.route(
+        &format!("{prefix}/config/reload"),
+        get(reload_config_handler).with_state(config_reloader.clone()),
+    )
+}
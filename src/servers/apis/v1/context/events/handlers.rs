@@ -0,0 +1,38 @@
+//! API handlers for the [`events`](crate::servers::apis::v1::context::events)
+//! API context.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Response;
+use axum_extra::extract::Query;
+use bittorrent_tracker_core::event::{Bus, EventType};
+use serde::Deserialize;
+
+use super::responses::{events_response, invalid_event_type_response};
+
+/// The URL query parameters: the event `type`s to receive.
+#[derive(Deserialize, Debug, Default)]
+pub struct QueryParams {
+    /// The event types. All of them if none is given.
+    #[serde(default, rename = "type")]
+    pub types: Vec<String>,
+}
+
+/// It handles the request to subscribe to the tracker events.
+///
+/// It returns:
+///
+/// - `200` response with a `text/event-stream` of the events.
+/// - `400` response if one of the event types is not known.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::events#subscribe-to-the-events)
+/// for more information about this endpoint.
+pub async fn get_events_handler(State(events): State<Arc<Bus>>, params: Query<QueryParams>) -> Response {
+    let types = match params.0.types.iter().map(|name| EventType::from_str(name)).collect() {
+        Ok(types) => types,
+        Err(e) => return invalid_event_type_response(&e),
+    };
+
+    events_response(events.subscribe(), types)
+}
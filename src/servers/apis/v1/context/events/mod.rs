@@ -0,0 +1,72 @@
+//! Events API context.
+//!
+//! This API context streams the tracker [events](bittorrent_tracker_core::event)
+//! as they happen, using [Server-Sent Events](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+//!
+//! # Endpoints
+//!
+//! - [Subscribe to the events](#subscribe-to-the-events)
+//!
+//! # Subscribe to the events
+//!
+//! `GET /events`
+//!
+//! It keeps the connection open and sends the events published from the
+//! moment the client connects. It requires a token with the `events:read`
+//! scope.
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Default | Example
+//! ---|---|---|---|---|---
+//! `type` | string | The types of the events to receive. It can be repeated | No | All the types | `torrent_created`
+//!
+//! The event types are:
+//!
+//! Type | Description | Attributes
+//! ---|---|---
+//! `metrics` | The changes of the [stats](crate::servers::apis::v1::context::stats) metrics, every second | `deltas`
+//! `torrent_created` | A peer announced a torrent the tracker was not tracking | `info_hash`
+//! `torrent_completed` | A peer announced that it finished downloading the torrent | `info_hash`
+//! `torrent_seeders_returned` | The torrent has a seeder after having none, every time it happens | `info_hash`
+//! `torrent_removed` | The torrent is not tracked anymore | `info_hash`
+//! `torrent_whitelisted` | The torrent has been added to the whitelist | `info_hash`
+//! `torrent_removed_from_whitelist` | The torrent has been removed from the whitelist | `info_hash`
+//! `whitelist_reloaded` | The whitelist has been reloaded from the database |
+//! `key_added` | An authentication key has been generated or added |
+//! `key_removed` | An authentication key has been deleted |
+//! `keys_reloaded` | The authentication keys have been reloaded from the database |
+//! `ip_banned` | The UDP tracker banned an IP for sending invalid connection IDs | `ip`
+//!
+//! The key events do not include the keys.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -N "http://127.0.0.1:1212/api/v1/events?token=MyAccessToken&type=torrent_created&type=torrent_removed"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```text
+//! event: torrent_created
+//! data: {"type":"torrent_created","info_hash":"9c38422213e30bff212b30c360d26f9a02136422"}
+//!
+//! event: torrent_removed
+//! data: {"type":"torrent_removed","info_hash":"9c38422213e30bff212b30c360d26f9a02136422"}
+//! ```
+//!
+//! Clients that do not read the events fast enough miss the oldest ones.
+//! The stream then contains a `lagged` event with the number of missed
+//! events, for example `{"missed":12}`.
+//!
+//! **Example response** `400`
+//!
+//! If the event type is not known.
+//!
+//! ```text
+//! Invalid URL: unknown event type: torrent_updated
+//! ```
+pub mod handlers;
+pub mod responses;
+pub mod routes;
@@ -0,0 +1,58 @@
+//! API responses for the [`events`](crate::servers::apis::v1::context::events)
+//! API context.
+use std::convert::Infallible;
+
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use bittorrent_tracker_core::event::{Event, EventType, UnknownEventType};
+use futures::stream::{self, Stream};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+
+use crate::servers::apis::v1::responses::bad_request_response;
+
+/// `200` response with the stream of the events of the given types. All the
+/// events are sent if there are no types.
+#[must_use]
+pub fn events_response(receiver: Receiver<Event>, types: Vec<EventType>) -> Response {
+    Sse::new(event_stream(receiver, types))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// `400` response when the event type is not known.
+#[must_use]
+pub fn invalid_event_type_response(e: &UnknownEventType) -> Response {
+    bad_request_response(&format!("Invalid URL: {e}"))
+}
+
+/// It ends when the bus is dropped.
+fn event_stream(receiver: Receiver<Event>, types: Vec<EventType>) -> impl Stream<Item = Result<SseEvent, Infallible>> {
+    stream::unfold((receiver, types), |(mut receiver, types)| async move {
+        loop {
+            let sse_event = match receiver.recv().await {
+                Ok(event) if types.is_empty() || types.contains(&event.event_type()) => sse_event(&event),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(missed)) => SseEvent::default()
+                    .event("lagged")
+                    .data(json!({ "missed": missed }).to_string()),
+                Err(RecvError::Closed) => return None,
+            };
+
+            return Some((Ok(sse_event), (receiver, types)));
+        }
+    })
+}
+
+fn sse_event(event: &Event) -> SseEvent {
+    let sse_event = SseEvent::default().event(event.event_type().as_str());
+
+    match sse_event.json_data(event) {
+        Ok(sse_event) => sse_event,
+        Err(e) => {
+            tracing::error!("failed to serialize the event {event:?}: {e}");
+            SseEvent::default().event(event.event_type().as_str())
+        }
+    }
+}
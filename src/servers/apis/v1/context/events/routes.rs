@@ -0,0 +1,20 @@
+//! API routes for the [`events`](crate::servers::apis::v1::context::events) API context.
+//!
+//! - `GET /events`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::events).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+use bittorrent_tracker_core::event::Bus;
+
+use super::handlers::get_events_handler;
+
+/// It adds the routes to the router for the [`events`](crate::servers::apis::v1::context::events) API context.
+pub fn add(prefix: &str, router: Router, events: &Arc<Bus>) -> Router {
+    router.route(
+        &format!("{prefix}/events"),
+        get(get_events_handler).with_state(events.clone()),
+    )
+}
@@ -1,14 +1,3 @@
 //! API resources for the [`stats`](crate::servers::apis::v1::context::health_check)
 //! API context.
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
-pub enum Status {
-    Ok,
-    Error,
-}
-
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
-pub struct Report {
-    pub status: Status,
-}
+pub use torrust_tracker_api_resources::v1::health_check::{Report, Status};
@@ -0,0 +1,35 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This type contains the info needed to add a new tracker listener.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct AddListenerForm {
+    /// The tracker protocol: `udp` or `http`.
+    pub protocol: String,
+
+    /// The address the listener will bind to, for example `0.0.0.0:7070`.
+    pub bind_address: String,
+
+    /// The TLS certificate path. Only for HTTP listeners.
+    #[serde(default)]
+    pub ssl_cert_path: Option<String>,
+
+    /// The TLS key path. Only for HTTP listeners.
+    #[serde(default)]
+    pub ssl_key_path: Option<String>,
+}
+
+/// This type contains the info needed to restart a tracker listener.
+///
+/// Both TLS paths must be provided to change the TLS certificate. Use an
+/// empty object to restart the listener with the current one.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Default)]
+pub struct RestartListenerForm {
+    /// The new TLS certificate path.
+    #[serde(default)]
+    pub ssl_cert_path: Option<String>,
+
+    /// The new TLS key path.
+    #[serde(default)]
+    pub ssl_key_path: Option<String>,
+}
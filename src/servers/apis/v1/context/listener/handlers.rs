@@ -0,0 +1,141 @@
+//! API handlers for the [`listener`](crate::servers::apis::v1::context::listener)
+//! API context.
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{self, Path, State};
+use axum::response::Response;
+use torrust_tracker_configuration::{HttpTracker, TslConfig, UdpTracker};
+
+use super::forms::{AddListenerForm, RestartListenerForm};
+use super::responses::{
+    incomplete_tls_config_response, invalid_listener_bind_address_response, invalid_listener_protocol_response,
+    listener_error_response, listener_list_response, listener_response,
+};
+use crate::servers::listeners::{Error, Listeners};
+
+/// It handles the request to list the tracker listeners.
+///
+/// It returns a `200` response with a json array of
+/// [`Listener`](crate::servers::apis::v1::context::listener::resources::Listener)
+/// resources.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::listener#list-the-listeners)
+/// for more information about this endpoint.
+pub async fn get_listeners_handler(State(listeners): State<Arc<Listeners>>) -> Response {
+    listener_list_response(listeners.list().await)
+}
+
+/// It handles the request to add a new tracker listener.
+///
+/// It returns:
+///
+/// - `200` with a json [`Listener`](crate::servers::apis::v1::context::listener::resources::Listener)
+///   resource if the listener was started.
+/// - `400` with an error if the request is not valid.
+/// - `500` with serialized error in debug format if the listener couldn't be
+///   started.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::listener#add-a-new-listener)
+/// for more information about this endpoint.
+pub async fn add_listener_handler(
+    State(listeners): State<Arc<Listeners>>,
+    extract::Json(add_listener_form): extract::Json<AddListenerForm>,
+) -> Response {
+    let Ok(bind_address) = add_listener_form.bind_address.parse::<SocketAddr>() else {
+        return invalid_listener_bind_address_response(&add_listener_form.bind_address);
+    };
+
+    let Ok(tsl_config) = tsl_config(add_listener_form.ssl_cert_path, add_listener_form.ssl_key_path) else {
+        return incomplete_tls_config_response();
+    };
+
+    let result = match add_listener_form.protocol.as_str() {
+        "udp" => {
+            if tsl_config.is_some() {
+                return listener_error_response(&Error::TlsNotSupported);
+            }
+
+            listeners
+                .add_udp(UdpTracker {
+                    bind_address,
+                    ..Default::default()
+                })
+                .await
+        }
+        "http" => {
+            listeners
+                .add_http(HttpTracker {
+                    bind_address,
+                    tsl_config,
+                })
+                .await
+        }
+        protocol => return invalid_listener_protocol_response(protocol),
+    };
+
+    match result {
+        Ok(info) => listener_response(info),
+        Err(e) => listener_error_response(&e),
+    }
+}
+
+/// It handles the request to stop a tracker listener.
+///
+/// It returns:
+///
+/// - `200` with a json [`Listener`](crate::servers::apis::v1::context::listener::resources::Listener)
+///   resource if the listener was stopped.
+/// - `400` with an error if the listener does not exist or it's not running.
+/// - `500` with serialized error in debug format if the listener couldn't be
+///   stopped.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::listener#stop-a-listener)
+/// for more information about this endpoint.
+pub async fn stop_listener_handler(State(listeners): State<Arc<Listeners>>, Path(id): Path<u64>) -> Response {
+    match listeners.stop(id).await {
+        Ok(info) => listener_response(info),
+        Err(e) => listener_error_response(&e),
+    }
+}
+
+/// It handles the request to restart a tracker listener.
+///
+/// It returns:
+///
+/// - `200` with a json [`Listener`](crate::servers::apis::v1::context::listener::resources::Listener)
+///   resource if the listener was restarted.
+/// - `400` with an error if the listener does not exist or the new TLS
+///   configuration is not valid.
+/// - `500` with serialized error in debug format if the listener couldn't be
+///   restarted.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::listener#restart-a-listener)
+/// for more information about this endpoint.
+pub async fn restart_listener_handler(
+    State(listeners): State<Arc<Listeners>>,
+    Path(id): Path<u64>,
+    extract::Json(restart_listener_form): extract::Json<RestartListenerForm>,
+) -> Response {
+    let Ok(tsl_config) = tsl_config(restart_listener_form.ssl_cert_path, restart_listener_form.ssl_key_path) else {
+        return incomplete_tls_config_response();
+    };
+
+    match listeners.restart(id, tsl_config).await {
+        Ok(info) => listener_response(info),
+        Err(e) => listener_error_response(&e),
+    }
+}
+
+/// It builds the TLS configuration from the form fields. Both paths must be
+/// provided, or none of them.
+fn tsl_config(ssl_cert_path: Option<String>, ssl_key_path: Option<String>) -> Result<Option<TslConfig>, ()> {
+    match (ssl_cert_path, ssl_key_path) {
+        (Some(ssl_cert_path), Some(ssl_key_path)) => Ok(Some(TslConfig {
+            ssl_cert_path: ssl_cert_path.into(),
+            ssl_key_path: ssl_key_path.into(),
+        })),
+        (None, None) => Ok(None),
+        _ => Err(()),
+    }
+}
@@ -0,0 +1,156 @@
+//! Listeners API context.
+//!
+//! This API context is responsible for handling the requests related to the
+//! UDP and HTTP tracker listeners. Listeners can be added, stopped and
+//! restarted without restarting the tracker.
+//!
+//! Refer to the [`Listeners`](crate::servers::listeners::Listeners) registry
+//! for more information about how listeners are managed.
+//!
+//! # Endpoints
+//!
+//! - [List the listeners](#list-the-listeners)
+//! - [Add a new listener](#add-a-new-listener)
+//! - [Stop a listener](#stop-a-listener)
+//! - [Restart a listener](#restart-a-listener)
+//!
+//! # List the listeners
+//!
+//! `GET /listeners`
+//!
+//! It returns all the listeners, running or stopped, ordered by ID.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/listeners?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!         "id": 1,
+//!         "protocol": "udp",
+//!         "bind_address": "0.0.0.0:6969",
+//!         "local_address": "0.0.0.0:6969",
+//!         "tls": false,
+//!         "state": "running"
+//!     },
+//!     {
+//!         "id": 2,
+//!         "protocol": "http",
+//!         "bind_address": "0.0.0.0:7070",
+//!         "local_address": null,
+//!         "tls": false,
+//!         "state": "stopped"
+//!     }
+//! ]
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`Listener`](crate::servers::apis::v1::context::listener::resources::Listener)
+//! resource for more information about the response attributes.
+//!
+//! # Add a new listener
+//!
+//! `POST /listeners`
+//!
+//! It starts a new UDP or HTTP tracker listener.
+//!
+//! **POST parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `protocol` | `udp` or `http` | The tracker protocol. | Yes | `http`
+//! `bind_address` | socket address | The address the listener will bind to. | Yes | `0.0.0.0:7071`
+//! `ssl_cert_path` | string or `null` | The TLS certificate. Only for HTTP listeners. | No | `./storage/tracker/lib/tls/localhost.crt`
+//! `ssl_key_path` | string or `null` | The TLS key. Only for HTTP listeners. | No | `./storage/tracker/lib/tls/localhost.key`
+//!
+//! > **NOTICE**: UDP listeners can't be added when the tracker is running in
+//! > `private` mode.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST "http://127.0.0.1:1212/api/v1/listeners?token=MyAccessToken" \
+//!      -H "Content-Type: application/json" \
+//!      -d '{
+//!            "protocol": "http",
+//!            "bind_address": "0.0.0.0:7071"
+//!          }'
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "id": 3,
+//!     "protocol": "http",
+//!     "bind_address": "0.0.0.0:7071",
+//!     "local_address": "0.0.0.0:7071",
+//!     "tls": false,
+//!     "state": "running"
+//! }
+//! ```
+//!
+//! # Stop a listener
+//!
+//! `POST /listener/:id/stop`
+//!
+//! It stops a running listener gracefully. The listener is not removed, so it
+//! can be restarted later.
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `id` | positive integer | The listener ID. | Yes | `2`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST "http://127.0.0.1:1212/api/v1/listener/2/stop?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! The stopped [`Listener`](crate::servers::apis::v1::context::listener::resources::Listener).
+//!
+//! # Restart a listener
+//!
+//! `POST /listener/:id/restart`
+//!
+//! It restarts a listener on the same address it was bound to. Stopped
+//! listeners are just started. HTTP listeners can be restarted with a new TLS
+//! certificate. If the new certificate is not valid, the listener keeps
+//! running with the current one.
+//!
+//! **POST parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `ssl_cert_path` | string or `null` | The new TLS certificate. | No | `./storage/tracker/lib/tls/localhost.crt`
+//! `ssl_key_path` | string or `null` | The new TLS key. | No | `./storage/tracker/lib/tls/localhost.key`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X POST "http://127.0.0.1:1212/api/v1/listener/2/restart?token=MyAccessToken" \
+//!      -H "Content-Type: application/json" \
+//!      -d '{
+//!            "ssl_cert_path": "./storage/tracker/lib/tls/localhost.crt",
+//!            "ssl_key_path": "./storage/tracker/lib/tls/localhost.key"
+//!          }'
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! The restarted [`Listener`](crate::servers::apis::v1::context::listener::resources::Listener).
+pub mod forms;
+pub mod handlers;
+pub mod resources;
+pub mod responses;
+pub mod routes;
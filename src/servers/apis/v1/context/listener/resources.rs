@@ -0,0 +1,50 @@
+//! API resources for the [`listener`](crate::servers::apis::v1::context::listener)
+//! API context.
+pub use torrust_tracker_api_resources::v1::listener::Listener;
+
+use crate::servers::listeners::ListenerInfo;
+
+impl From<ListenerInfo> for Listener {
+    fn from(info: ListenerInfo) -> Self {
+        Self {
+            id: info.id,
+            protocol: info.protocol.to_string(),
+            bind_address: info.bind_address.to_string(),
+            local_address: info.local_address.map(|address| address.to_string()),
+            tls: info.tls,
+            state: info.state.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    use super::Listener;
+    use crate::servers::listeners::{ListenerInfo, Protocol, State};
+
+    #[test]
+    fn it_should_be_converted_from_the_listener_info() {
+        let bind_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 7070);
+
+        assert_eq!(
+            Listener::from(ListenerInfo {
+                id: 1,
+                protocol: Protocol::Http,
+                bind_address,
+                local_address: Some(bind_address),
+                tls: false,
+                state: State::Running,
+            }),
+            Listener {
+                id: 1,
+                protocol: "http".to_string(),
+                bind_address: "0.0.0.0:7070".to_string(),
+                local_address: Some("0.0.0.0:7070".to_string()),
+                tls: false,
+                state: "running".to_string(),
+            }
+        );
+    }
+}
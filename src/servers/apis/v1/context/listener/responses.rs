@@ -0,0 +1,53 @@
+//! API responses for the [`listener`](crate::servers::apis::v1::context::listener)
+//! API context.
+use axum::response::{IntoResponse, Json, Response};
+
+use super::resources::Listener;
+use crate::servers::apis::v1::responses::{bad_request_response, unhandled_rejection_response};
+use crate::servers::listeners::{Error, ListenerInfo};
+
+/// `200` response that contains the [`Listener`] resource as json.
+#[must_use]
+pub fn listener_response(info: ListenerInfo) -> Response {
+    Json(Listener::from(info)).into_response()
+}
+
+/// `200` response that contains an array of [`Listener`] resources as json.
+#[must_use]
+pub fn listener_list_response(infos: Vec<ListenerInfo>) -> Response {
+    Json(infos.into_iter().map(Listener::from).collect::<Vec<_>>()).into_response()
+}
+
+#[must_use]
+pub fn invalid_listener_protocol_response(protocol: &str) -> Response {
+    bad_request_response(&format!(
+        "Invalid listener protocol: \"{protocol}\", expected \"udp\" or \"http\""
+    ))
+}
+
+#[must_use]
+pub fn invalid_listener_bind_address_response(bind_address: &str) -> Response {
+    bad_request_response(&format!("Invalid listener bind address: \"{bind_address}\""))
+}
+
+#[must_use]
+pub fn incomplete_tls_config_response() -> Response {
+    bad_request_response("Invalid TLS configuration: both `ssl_cert_path` and `ssl_key_path` are required")
+}
+
+/// Error response when an operation on a listener fails.
+///
+/// It's a `400` response if the request can't be fulfilled, for example,
+/// because the listener does not exist or the TLS certificate is not valid.
+/// Otherwise, it's a `500` response.
+#[must_use]
+pub fn listener_error_response(e: &Error) -> Response {
+    match e {
+        Error::UnableToStart { .. } | Error::UnableToStop { .. } => unhandled_rejection_response(e.to_string()),
+        Error::NotFound { .. }
+        | Error::AlreadyStopped { .. }
+        | Error::UdpInPrivateMode
+        | Error::TlsNotSupported
+        | Error::InvalidTls { .. } => bad_request_response(&e.to_string()),
+    }
+}
@@ -0,0 +1,35 @@
+//! API routes for the [`listener`](crate::servers::apis::v1::context::listener) API context.
+//!
+//! - `GET /listeners`
+//! - `POST /listeners`
+//! - `POST /listener/:id/stop`
+//! - `POST /listener/:id/restart`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::listener).
+use std::sync::Arc;
+
+use axum::routing::{get, post};
+use axum::Router;
+
+use super::handlers::{add_listener_handler, get_listeners_handler, restart_listener_handler, stop_listener_handler};
+use crate::servers::listeners::Listeners;
+
+/// It adds the routes to the router for the [`listener`](crate::servers::apis::v1::context::listener) API context.
+pub fn add(prefix: &str, router: Router, listeners: &Arc<Listeners>) -> Router {
+    router
+        .route(
+            &format!("{prefix}/listeners"),
+            get(get_listeners_handler)
+                .with_state(listeners.clone())
+                .post(add_listener_handler)
+                .with_state(listeners.clone()),
+        )
+        .route(
+            &format!("{prefix}/listener/{{id}}/stop"),
+            post(stop_listener_handler).with_state(listeners.clone()),
+        )
+        .route(
+            &format!("{prefix}/listener/{{id}}/restart"),
+            post(restart_listener_handler).with_state(listeners.clone()),
+        )
+}
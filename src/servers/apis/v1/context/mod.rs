@@ -2,8 +2,14 @@
 //!
 //! Each context is a module that contains the API endpoints related to a
 //! specific resource group.
+pub mod audit;
 pub mod auth_key;
+pub mod client_policy;
+pub mod config;
+pub mod events;
 pub mod health_check;
+pub mod listener;
+pub mod openapi;
 pub mod stats;
 pub mod torrent;
 pub mod whitelist;
@@ -0,0 +1,16 @@
+//! API handlers for the [`openapi`](crate::servers::apis::v1::context::openapi)
+//! API context.
+use axum::response::Json;
+use serde_json::Value;
+
+use super::spec;
+
+/// It handles the request to get the `OpenAPI` specification.
+///
+/// It returns a `200` response with the specification as json.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::openapi#get-the-openapi-specification)
+/// for more information about this endpoint.
+pub async fn get_openapi_spec_handler() -> Json<Value> {
+    Json(spec::openapi())
+}
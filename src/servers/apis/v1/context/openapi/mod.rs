@@ -0,0 +1,56 @@
+//! `OpenAPI` API context.
+//!
+//! This API context serves the [`OpenAPI` 3](https://spec.openapis.org/oas/v3.0.3)
+//! specification of the v1 API, so that API clients can be generated instead
+//! of written by hand.
+//!
+//! # Endpoints
+//!
+//! - [Get the `OpenAPI` specification](#get-the-openapi-specification)
+//!
+//! # Get the `OpenAPI` specification
+//!
+//! `GET /openapi.json`
+//!
+//! It returns the `OpenAPI` document of the v1 API. It does not require a
+//! token.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/openapi.json"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "openapi": "3.0.3",
+//!     "info": {
+//!         "title": "Torrust Tracker API",
+//!         "version": "1"
+//!     },
+//!     "paths": {
+//!         "/api/v1/stats": {
+//!             "get": {
+//!                 "operationId": "getStats",
+//!                 "...": "..."
+//!             }
+//!         }
+//!     },
+//!     "components": {
+//!         "schemas": {
+//!             "Stats": {
+//!                 "...": "..."
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The schemas of the resources are generated from the API resource types
+//! (see [`spec`]). The paths must be kept in sync with the routes of each
+//! context; the contract tests check every documented operation is served.
+pub mod handlers;
+pub mod routes;
+pub mod spec;
@@ -0,0 +1,14 @@
+//! API routes for the [`openapi`](crate::servers::apis::v1::context::openapi) API context.
+//!
+//! - `GET /openapi.json`
+//!
+//! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::openapi).
+use axum::routing::get;
+use axum::Router;
+
+use super::handlers::get_openapi_spec_handler;
+
+/// It adds the routes to the router for the [`openapi`](crate::servers::apis::v1::context::openapi) API context.
+pub fn add(prefix: &str, router: Router) -> Router {
+    router.route(&format!("{prefix}/openapi.json"), get(get_openapi_spec_handler))
+}
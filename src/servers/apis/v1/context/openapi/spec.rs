@@ -0,0 +1,725 @@
+//! `OpenAPI` specification of the v1 API.
+//!
+//! The operations are listed by hand, one per route registered by the API
+//! contexts. The schemas of the request and response bodies are generated
+//! from the API resource and form types, which implement
+//! [`JsonSchema`](schemars::JsonSchema).
+//!
+//! Every operation requires a token with the scope named in the
+//! `x-required-scope` extension, except the ones with an empty `security`
+//! list.
+use bittorrent_tracker_core::event::EventType;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+use torrust_tracker_configuration::Scope;
+
+use crate::servers::apis::v1::context::audit::resources::AuditRecord;
+use crate::servers::apis::v1::context::auth_key::forms::AddKeyForm;
+use crate::servers::apis::v1::context::auth_key::resources::AuthKey;
+use crate::servers::apis::v1::context::client_policy::forms::AddClientRuleForm;
+use crate::servers::apis::v1::context::client_policy::resources::ClientRule;
+use crate::servers::apis::v1::context::config::resources::ReloadReport;
+use crate::servers::apis::v1::context::health_check::resources::Report;
+use crate::servers::apis::v1::context::listener::forms::{AddListenerForm, RestartListenerForm};
+use crate::servers::apis::v1::context::listener::resources::Listener;
+use crate::servers::apis::v1::context::stats::resources::{ClientStats, LocationStats, Stats, StatsHistory};
+use crate::servers::apis::v1::context::torrent::resources::torrent::{ListItem, Torrent};
+use crate::servers::apis::v1::responses::ActionStatus;
+
+/// The version of the `OpenAPI` specification the document conforms to.
+pub const OPENAPI_VERSION: &str = "3.0.3";
+
+/// It builds the `OpenAPI` document of the v1 API.
+#[must_use]
+pub fn openapi() -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+
+    let mut paths = Map::new();
+
+    for operation in operations(&mut generator) {
+        if let Value::Object(path_item) = paths.entry(operation.path).or_insert_with(|| json!({})) {
+            path_item.insert(operation.method.to_string(), operation.body);
+        }
+    }
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "Torrust Tracker API",
+            "description": "REST API to administrate the Torrust Tracker.",
+            "version": "1",
+        },
+        "paths": paths,
+        "components": {
+            "schemas": generator.take_definitions(),
+            "responses": {
+                "BadRequest": text_response("The request is not valid. The body describes the problem."),
+                "Forbidden": text_response("The token does not have the scope required by the endpoint."),
+                "UnhandledRejection": text_response(
+                    "The token is missing or not valid, or the request could not be fulfilled because of an internal error."
+                ),
+            },
+            "securitySchemes": {
+                "bearerToken": {
+                    "type": "http",
+                    "scheme": "bearer",
+                },
+                "tokenQueryParam": {
+                    "type": "apiKey",
+                    "in": "query",
+                    "name": "token",
+                },
+            },
+        },
+        "security": [
+            { "bearerToken": [] },
+            { "tokenQueryParam": [] },
+        ],
+    })
+}
+
+/// An operation of the API: an HTTP method on a path.
+struct Operation {
+    path: String,
+    method: &'static str,
+    body: Value,
+}
+
+impl Operation {
+    fn new(method: &'static str, path: &str, operation_id: &str, summary: &str, tag: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            method,
+            body: json!({
+                "operationId": operation_id,
+                "summary": summary,
+                "tags": [tag],
+                "parameters": [],
+                "responses": {},
+            }),
+        }
+    }
+
+    /// The operation requires a token with the given scope.
+    fn scope(mut self, scope: Scope) -> Self {
+        self.body["x-required-scope"] = json!(scope.to_string());
+        self.body["responses"]["403"] = json!({ "$ref": "#/components/responses/Forbidden" });
+        self.body["responses"]["500"] = json!({ "$ref": "#/components/responses/UnhandledRejection" });
+        self
+    }
+
+    /// The operation does not require a token.
+    fn public(mut self) -> Self {
+        self.body["security"] = json!([]);
+        self
+    }
+
+    fn param(mut self, param: Value) -> Self {
+        self.body["parameters"]
+            .as_array_mut()
+            .expect("parameters should be a json array")
+            .push(param);
+        self
+    }
+
+    fn params(self, params: impl IntoIterator<Item = Value>) -> Self {
+        params.into_iter().fold(self, Operation::param)
+    }
+
+    fn json_body(mut self, schema: Value) -> Self {
+        self.body["requestBody"] = json!({ "required": true });
+        self.body["requestBody"]["content"]["application/json"]["schema"] = schema;
+        self
+    }
+
+    fn response(mut self, status: u16, response: Value) -> Self {
+        self.body["responses"][status.to_string()] = response;
+        self
+    }
+
+    fn bad_request(self) -> Self {
+        self.response(400, json!({ "$ref": "#/components/responses/BadRequest" }))
+    }
+}
+
+fn schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Value {
+    json!(generator.subschema_for::<T>())
+}
+
+fn path_param(name: &str, description: &str) -> Value {
+    json!({
+        "name": name,
+        "in": "path",
+        "required": true,
+        "description": description,
+        "schema": { "type": "string" },
+    })
+}
+
+fn query_param(name: &str, description: &str, schema: Value) -> Value {
+    let mut param = json!({
+        "name": name,
+        "in": "query",
+        "required": false,
+        "description": description,
+    });
+    param["schema"] = schema;
+    param
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    let mut response = json!({ "description": description });
+    response["content"]["application/json"]["schema"] = schema;
+    response
+}
+
+fn text_response(description: &str) -> Value {
+    json!({
+        "description": description,
+        "content": { "text/plain": { "schema": { "type": "string" } } },
+    })
+}
+
+fn info_hash_param() -> Value {
+    path_param("info_hash", "The torrent infohash: 40 hex characters.")
+}
+
+fn pagination_params() -> [Value; 2] {
+    [
+        query_param(
+            "offset",
+            "The page number, starting at 0.",
+            json!({ "type": "integer", "minimum": 0 }),
+        ),
+        query_param(
+            "limit",
+            "Page size. The number of results per page. Default: 4000.",
+            json!({ "type": "integer", "minimum": 0 }),
+        ),
+    ]
+}
+
+/// The `min_*` and `max_*` filters of the torrent list.
+fn swarm_filter_params() -> Vec<Value> {
+    ["seeders", "leechers", "completed", "peers"]
+        .iter()
+        .flat_map(|metric| {
+            ["min", "max"].iter().map(move |bound| {
+                query_param(
+                    &format!("{bound}_{metric}"),
+                    &format!("Only the torrents with this {bound}imum number of {metric}."),
+                    json!({ "type": "integer", "minimum": 0 }),
+                )
+            })
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_lines)]
+fn operations(generator: &mut SchemaGenerator) -> Vec<Operation> {
+    let ok = json_response("The action has been performed.", schema::<ActionStatus<'_>>(generator));
+
+    let [offset, limit] = pagination_params();
+
+    let mut operations = vec![
+        // Health check
+        Operation::new(
+            "get",
+            "/api/health_check",
+            "healthCheck",
+            "Get the API status",
+            "health_check",
+        )
+        .public()
+        .response(200, json_response("The API is running.", schema::<Report>(generator))),
+        // OpenAPI
+        Operation::new(
+            "get",
+            "/api/v1/openapi.json",
+            "getOpenApiSpec",
+            "Get the OpenAPI specification",
+            "openapi",
+        )
+        .public()
+        .response(200, json_response("The OpenAPI document.", json!({ "type": "object" }))),
+        // Stats
+        Operation::new("get", "/api/v1/stats", "getStats", "Get the tracker statistics", "stats")
+            .scope(Scope::StatsRead)
+            .param(query_param(
+                "format",
+                "The format of the statistics. Default: `json`.",
+                json!({ "type": "string", "enum": ["json", "prometheus"] }),
+            ))
+            .response(
+                200,
+                json!({
+                    "description": "The tracker statistics.",
+                    "content": {
+                        "application/json": { "schema": schema::<Stats>(generator) },
+                        "text/plain": { "schema": { "type": "string" } },
+                    },
+                }),
+            ),
+        Operation::new(
+            "get",
+            "/api/v1/stats/history",
+            "getStatsHistory",
+            "Get the recorded statistics history",
+            "stats",
+        )
+        .scope(Scope::StatsRead)
+        .params([
+            query_param(
+                "from",
+                "The start of the range, in seconds since the Unix epoch. Default: the start of the full resolution samples.",
+                json!({ "type": "integer", "minimum": 0 }),
+            ),
+            query_param(
+                "to",
+                "The end of the range (excluded), in seconds since the Unix epoch. Default: now.",
+                json!({ "type": "integer", "minimum": 0 }),
+            ),
+            query_param(
+                "step",
+                "The number of seconds covered by each sample. Default: the sample interval.",
+                json!({ "type": "integer", "minimum": 1 }),
+            ),
+        ])
+        .bad_request()
+        .response(
+            200,
+            json_response("The samples in the range.", schema::<StatsHistory>(generator)),
+        ),
+        Operation::new(
+            "get",
+            "/api/v1/stats/clients",
+            "getClientsStats",
+            "Get the number of peers by client software",
+            "stats",
+        )
+        .scope(Scope::StatsRead)
+        .params([
+            query_param(
+                "info_hash",
+                "Only the peers of this torrent: 40 hex characters.",
+                json!({ "type": "string" }),
+            ),
+            query_param(
+                "format",
+                "The format of the statistics. Default: `json`.",
+                json!({ "type": "string", "enum": ["json", "prometheus"] }),
+            ),
+        ])
+        .bad_request()
+        .response(
+            200,
+            json!({
+                "description": "The number of peers by client, the most used first.",
+                "content": {
+                    "application/json": { "schema": { "type": "array", "items": schema::<ClientStats>(generator) } },
+                    "text/plain": { "schema": { "type": "string" } },
+                },
+            }),
+        ),
+        Operation::new(
+            "get",
+            "/api/v1/stats/locations",
+            "getLocationsStats",
+            "Get the number of peers by country and ASN",
+            "stats",
+        )
+        .scope(Scope::StatsRead)
+        .params([
+            query_param(
+                "info_hash",
+                "Only the peers of this torrent: 40 hex characters.",
+                json!({ "type": "string" }),
+            ),
+            query_param(
+                "format",
+                "The format of the statistics. Default: `json`.",
+                json!({ "type": "string", "enum": ["json", "prometheus"] }),
+            ),
+        ])
+        .bad_request()
+        .response(
+            200,
+            json!({
+                "description": "The number of peers by country and ASN, the most common first.",
+                "content": {
+                    "application/json": { "schema": schema::<LocationStats>(generator) },
+                    "text/plain": { "schema": { "type": "string" } },
+                },
+            }),
+        ),
+    ];
+
+    // Torrents
+    operations.extend([
+        Operation::new("get", "/api/v1/torrent/{info_hash}", "getTorrent", "Get a torrent", "torrent")
+            .scope(Scope::TorrentsRead)
+            .param(info_hash_param())
+            .bad_request()
+            .response(
+                200,
+                json_response(
+                    "The torrent, or the string `torrent not known`.",
+                    json!({ "oneOf": [schema::<Torrent>(generator), { "type": "string" }] }),
+                ),
+            ),
+        Operation::new(
+            "delete",
+            "/api/v1/torrent/{info_hash}",
+            "deleteTorrent",
+            "Delete a torrent and its peers",
+            "torrent",
+        )
+        .scope(Scope::TorrentsWrite)
+        .param(info_hash_param())
+        .param(query_param(
+            "delete_completed",
+            "Whether to delete the persisted number of downloads too. Default: `false`.",
+            json!({ "type": "boolean" }),
+        ))
+        .bad_request()
+        .response(200, ok.clone()),
+        Operation::new(
+            "delete",
+            "/api/v1/torrent/{info_hash}/peers/{peer_id}",
+            "evictPeer",
+            "Remove a peer from a torrent swarm",
+            "torrent",
+        )
+        .scope(Scope::TorrentsWrite)
+        .param(info_hash_param())
+        .param(path_param(
+            "peer_id",
+            "The peer ID: 40 hex characters, optionally prefixed with `0x`.",
+        ))
+        .bad_request()
+        .response(200, ok.clone()),
+        Operation::new("get", "/api/v1/torrents", "getTorrents", "Get a page of torrents", "torrent")
+            .scope(Scope::TorrentsRead)
+            .param(offset.clone())
+            .param(limit.clone())
+            .param(json!({
+                "name": "info_hash",
+                "in": "query",
+                "required": false,
+                "description": "Infohashes of the torrents to get. Pagination, sorting and filters are ignored when provided.",
+                "schema": { "type": "array", "items": { "type": "string" } },
+                "style": "form",
+                "explode": true,
+            }))
+            .param(query_param(
+                "sort",
+                "The swarm metric to sort by.",
+                json!({ "type": "string", "enum": ["seeders", "leechers", "completed", "peers"] }),
+            ))
+            .param(query_param(
+                "order",
+                "The sorting direction. Default: `desc`.",
+                json!({ "type": "string", "enum": ["asc", "desc"] }),
+            ))
+            .params(swarm_filter_params())
+            .bad_request()
+            .response(
+                200,
+                json_response(
+                    "The torrents.",
+                    json!({ "type": "array", "items": schema::<ListItem>(generator) }),
+                ),
+            ),
+        Operation::new(
+            "get",
+            "/api/v1/torrents/export",
+            "exportTorrents",
+            "Export all the torrents",
+            "torrent",
+        )
+        .scope(Scope::TorrentsRead)
+        .param(query_param(
+            "format",
+            "The export format. Default: `ndjson`.",
+            json!({ "type": "string", "enum": ["ndjson", "csv"] }),
+        ))
+        .param(query_param(
+            "peers",
+            "Whether to include the peers of each torrent. Default: `false`.",
+            json!({ "type": "boolean" }),
+        ))
+        .param(query_param(
+            "snapshot",
+            "Whether to export a copy of the torrents taken when the request is received, without the peers. It cannot be combined with `peers`. Default: `false`.",
+            json!({ "type": "boolean" }),
+        ))
+        .bad_request()
+        .response(
+            200,
+            json!({
+                "description": "The torrents, one per line.",
+                "headers": {
+                    "X-Torrust-Snapshot-Time": {
+                        "description": "The time the snapshot was taken, in RFC 3339 format. Only for snapshot exports.",
+                        "schema": { "type": "string", "format": "date-time" },
+                    },
+                },
+                "content": {
+                    "application/x-ndjson": { "schema": { "type": "string" } },
+                    "text/csv": { "schema": { "type": "string" } },
+                },
+            }),
+        ),
+    ]);
+
+    // Whitelist
+    operations.extend([
+        Operation::new(
+            "post",
+            "/api/v1/whitelist/{info_hash}",
+            "whitelistTorrent",
+            "Add a torrent to the whitelist",
+            "whitelist",
+        )
+        .scope(Scope::WhitelistWrite)
+        .param(info_hash_param())
+        .bad_request()
+        .response(200, ok.clone()),
+        Operation::new(
+            "delete",
+            "/api/v1/whitelist/{info_hash}",
+            "removeTorrentFromWhitelist",
+            "Remove a torrent from the whitelist",
+            "whitelist",
+        )
+        .scope(Scope::WhitelistWrite)
+        .param(info_hash_param())
+        .bad_request()
+        .response(200, ok.clone()),
+        Operation::new(
+            "get",
+            "/api/v1/whitelist/reload",
+            "reloadWhitelist",
+            "Reload the whitelist from the database",
+            "whitelist",
+        )
+        .scope(Scope::WhitelistWrite)
+        .response(200, ok.clone()),
+    ]);
+
+    // Authentication keys
+    operations.extend([
+        Operation::new(
+            "post",
+            "/api/v1/key/{seconds_valid_or_key}",
+            "generateAuthKey",
+            "Generate a new authentication key (deprecated, use `POST /api/v1/keys`)",
+            "auth_key",
+        )
+        .scope(Scope::KeysWrite)
+        .param(path_param("seconds_valid_or_key", "The number of seconds the key is valid."))
+        .bad_request()
+        .response(200, json_response("The new key.", schema::<AuthKey>(generator))),
+        Operation::new(
+            "delete",
+            "/api/v1/key/{seconds_valid_or_key}",
+            "deleteAuthKey",
+            "Delete an authentication key",
+            "auth_key",
+        )
+        .scope(Scope::KeysWrite)
+        .param(path_param("seconds_valid_or_key", "The key to delete."))
+        .bad_request()
+        .response(200, ok.clone()),
+        Operation::new(
+            "get",
+            "/api/v1/keys/reload",
+            "reloadAuthKeys",
+            "Reload the authentication keys from the database",
+            "auth_key",
+        )
+        .scope(Scope::KeysWrite)
+        .response(200, ok.clone()),
+        Operation::new(
+            "post",
+            "/api/v1/keys",
+            "addAuthKey",
+            "Add a pre-generated or a random authentication key",
+            "auth_key",
+        )
+        .scope(Scope::KeysWrite)
+        .json_body(schema::<AddKeyForm>(generator))
+        .bad_request()
+        .response(200, json_response("The new key.", schema::<AuthKey>(generator))),
+    ]);
+
+    // Configuration
+    operations.push(
+        Operation::new(
+            "get",
+            "/api/v1/config/reload",
+            "reloadConfig",
+            "Reload the configuration",
+            "config",
+        )
+        .scope(Scope::ConfigWrite)
+        .response(200, json_response("The changed settings.", schema::<ReloadReport>(generator))),
+    );
+
+    // Listeners
+    operations.extend([
+        Operation::new("get", "/api/v1/listeners", "getListeners", "Get the listeners", "listener")
+            .scope(Scope::ListenersRead)
+            .response(
+                200,
+                json_response(
+                    "The listeners.",
+                    json!({ "type": "array", "items": schema::<Listener>(generator) }),
+                ),
+            ),
+        Operation::new("post", "/api/v1/listeners", "addListener", "Start a new listener", "listener")
+            .scope(Scope::ListenersWrite)
+            .json_body(schema::<AddListenerForm>(generator))
+            .bad_request()
+            .response(200, json_response("The new listener.", schema::<Listener>(generator))),
+        Operation::new(
+            "post",
+            "/api/v1/listener/{id}/stop",
+            "stopListener",
+            "Stop a listener",
+            "listener",
+        )
+        .scope(Scope::ListenersWrite)
+        .param(path_param("id", "The listener ID."))
+        .bad_request()
+        .response(200, json_response("The stopped listener.", schema::<Listener>(generator))),
+        Operation::new(
+            "post",
+            "/api/v1/listener/{id}/restart",
+            "restartListener",
+            "Restart a listener, optionally with a new TLS certificate",
+            "listener",
+        )
+        .scope(Scope::ListenersWrite)
+        .param(path_param("id", "The listener ID."))
+        .json_body(schema::<RestartListenerForm>(generator))
+        .bad_request()
+        .response(200, json_response("The restarted listener.", schema::<Listener>(generator))),
+    ]);
+
+    // Audit
+    operations.push(
+        Operation::new("get", "/api/v1/audit", "getAuditRecords", "Get the audit records", "audit")
+            .scope(Scope::AuditRead)
+            .param(offset)
+            .param(limit)
+            .response(
+                200,
+                json_response(
+                    "The audit records, the newest first.",
+                    json!({ "type": "array", "items": schema::<AuditRecord>(generator) }),
+                ),
+            ),
+    );
+
+    // Events
+    operations.push(
+        Operation::new(
+            "get",
+            "/api/v1/events",
+            "getEvents",
+            "Subscribe to the tracker events",
+            "events",
+        )
+        .scope(Scope::EventsRead)
+        .param(query_param(
+            "type",
+            "The types of the events to receive. Default: all of them.",
+            json!({
+                "type": "array",
+                "items": { "type": "string", "enum": EventType::ALL.map(EventType::as_str) },
+            }),
+        ))
+        .bad_request()
+        .response(
+            200,
+            json!({
+                "description": "Server-Sent Events stream. Each event data is a JSON object with the event `type`.",
+                "content": { "text/event-stream": { "schema": { "type": "string" } } },
+            }),
+        ),
+    );
+
+    // Client policy
+    operations.extend([
+        Operation::new(
+            "get",
+            "/api/v1/client-policy/rules",
+            "getClientRules",
+            "Get the client policy rules",
+            "client-policy",
+        )
+        .scope(Scope::ClientPolicyRead)
+        .response(
+            200,
+            json_response(
+                "The rules ordered by ID.",
+                json!({ "type": "array", "items": schema::<ClientRule>(generator) }),
+            ),
+        ),
+        Operation::new(
+            "post",
+            "/api/v1/client-policy/rules",
+            "addClientRule",
+            "Allow or deny the clients with a peer ID prefix or pattern",
+            "client-policy",
+        )
+        .scope(Scope::ClientPolicyWrite)
+        .json_body(schema::<AddClientRuleForm>(generator))
+        .bad_request()
+        .response(200, json_response("The new rule.", schema::<ClientRule>(generator))),
+        Operation::new(
+            "delete",
+            "/api/v1/client-policy/rules/{id}",
+            "removeClientRule",
+            "Remove a client policy rule",
+            "client-policy",
+        )
+        .scope(Scope::ClientPolicyWrite)
+        .param(path_param("id", "The rule ID."))
+        .bad_request()
+        .response(200, ok),
+    ]);
+
+    operations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::openapi;
+
+    #[test]
+    fn it_should_include_the_schemas_of_the_api_resources() {
+        let spec = openapi();
+
+        for schema in ["Torrent", "ListItem", "Peer", "Stats", "AuthKey", "ActionStatus"] {
+            assert!(
+                spec["components"]["schemas"][schema].is_object(),
+                "missing schema for the {schema} resource"
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_only_reference_defined_schemas() {
+        let spec = openapi();
+        let json = spec.to_string();
+
+        for reference in json.split("\"$ref\":\"#/components/schemas/").skip(1) {
+            let name = reference.split('"').next().unwrap();
+
+            assert!(spec["components"]["schemas"][name].is_object(), "undefined schema {name}");
+        }
+    }
+}
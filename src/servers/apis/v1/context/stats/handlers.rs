@@ -1,18 +1,29 @@
 //! API handlers for the [`stats`](crate::servers::apis::v1::context::stats)
 //! API context.
+use std::str::FromStr;
 use std::sync::Arc;
 
 use axum::extract::State;
 use axum::response::Response;
 use axum_extra::extract::Query;
+use bittorrent_primitives::info_hash::InfoHash;
+use bittorrent_tracker_core::announce_handler::AnnounceHandler;
+use bittorrent_tracker_core::geoip::{GeoIp, LocationsMetrics};
 use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
 use serde::Deserialize;
 use tokio::sync::RwLock;
+use torrust_tracker_clock::clock::Time;
 
-use super::responses::{metrics_response, stats_response};
+use super::responses::{
+    clients_metrics_response, clients_stats_response, invalid_history_range_response, locations_metrics_response,
+    locations_stats_response, metrics_response, stats_history_response, stats_response,
+};
+use crate::packages::tracker_api_core::statistics::history::History;
 use crate::packages::tracker_api_core::statistics::services::get_metrics;
 use crate::packages::{http_tracker_core, udp_tracker_core};
+use crate::servers::apis::v1::responses::invalid_info_hash_param_response;
 use crate::servers::udp::server::banning::BanService;
+use crate::CurrentClock;
 
 #[derive(Deserialize, Debug, Default)]
 #[serde(rename_all = "lowercase")]
@@ -42,19 +53,146 @@ pub struct QueryParams {
 pub async fn get_stats_handler(
     State(state): State<(
         Arc<InMemoryTorrentRepository>,
+        Arc<AnnounceHandler>,
         Arc<RwLock<BanService>>,
         Arc<http_tracker_core::statistics::repository::Repository>,
         Arc<udp_tracker_core::statistics::repository::Repository>,
     )>,
     params: Query<QueryParams>,
 ) -> Response {
-    let metrics = get_metrics(state.0.clone(), state.1.clone(), state.2.clone(), state.3.clone()).await;
+    let metrics = get_metrics(
+        state.0.clone(),
+        state.1.clone(),
+        state.2.clone(),
+        state.3.clone(),
+        state.4.clone(),
+    )
+    .await;
 
     match params.0.format {
         Some(format) => match format {
             Format::Json => stats_response(metrics),
-            Format::Prometheus => metrics_response(&metrics),
+            Format::Prometheus => metrics_response(&metrics, &state.0.get_clients_metrics()),
         },
         None => stats_response(metrics),
     }
 }
+
+/// The URL query parameters of the stats history: the time range and the
+/// resolution.
+#[derive(Deserialize, Debug, Default)]
+pub struct HistoryQueryParams {
+    /// The start of the range, in seconds since the Unix epoch. The default is
+    /// the start of the full resolution samples.
+    pub from: Option<u64>,
+    /// The end of the range (excluded), in seconds since the Unix epoch. The
+    /// default is now.
+    pub to: Option<u64>,
+    /// The number of seconds covered by each sample. The default is the
+    /// interval of the recorded samples.
+    pub step: Option<u64>,
+}
+
+/// It handles the request to get the historical tracker statistics.
+///
+/// It returns:
+///
+/// - `200` response with the samples in the range as json.
+/// - `400` response if the range is empty or the step is zero.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-the-statistics-history)
+/// for more information about this endpoint.
+pub async fn get_stats_history_handler(State(history): State<Arc<History>>, params: Query<HistoryQueryParams>) -> Response {
+    let now = CurrentClock::now().as_secs();
+
+    let to = params.to.unwrap_or(now.saturating_add(1));
+    let from = params.from.unwrap_or(to.saturating_sub(history.full_resolution_range()));
+
+    if from >= to || params.step == Some(0) {
+        return invalid_history_range_response();
+    }
+
+    stats_history_response(history.query(from, to, params.step))
+}
+
+/// The URL query parameters of the stats about the active peers.
+#[derive(Deserialize, Debug, Default)]
+pub struct PeersQueryParams {
+    /// Only the peers of this torrent. All the peers if it's not set.
+    pub info_hash: Option<String>,
+    /// The [`Format`] of the stats.
+    #[serde(default)]
+    pub format: Option<Format>,
+}
+
+/// It handles the request to get the number of active peers by client
+/// software.
+///
+/// It returns:
+///
+/// - `200` response with the clients in JSON format, or in Prometheus Text
+///   Exposition Format with the `format=prometheus` GET parameter.
+/// - `400` response if the `info_hash` is not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-the-client-software-statistics)
+/// for more information about this endpoint.
+pub async fn get_clients_stats_handler(
+    State(in_memory_torrent_repository): State<Arc<InMemoryTorrentRepository>>,
+    params: Query<PeersQueryParams>,
+) -> Response {
+    let info_hash = match &params.info_hash {
+        Some(info_hash) => match InfoHash::from_str(info_hash) {
+            Ok(info_hash) => Some(info_hash),
+            Err(_) => return invalid_info_hash_param_response(info_hash),
+        },
+        None => None,
+    };
+
+    let clients_metrics = match &info_hash {
+        Some(info_hash) => in_memory_torrent_repository.get_torrent_clients_metrics(info_hash),
+        None => in_memory_torrent_repository.get_clients_metrics(),
+    };
+
+    match params.format {
+        Some(Format::Prometheus) => clients_metrics_response(&clients_metrics, info_hash.as_ref()),
+        Some(Format::Json) | None => clients_stats_response(&clients_metrics),
+    }
+}
+
+/// It handles the request to get the number of active peers by country and
+/// ASN.
+///
+/// It returns:
+///
+/// - `200` response with the countries and ASNs in JSON format, or in
+///   Prometheus Text Exposition Format with the `format=prometheus` GET
+///   parameter. They are empty if `GeoIP` is not enabled.
+/// - `400` response if the `info_hash` is not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::stats#get-the-location-statistics)
+/// for more information about this endpoint.
+pub async fn get_locations_stats_handler(
+    State(state): State<(Arc<InMemoryTorrentRepository>, Arc<GeoIp>)>,
+    params: Query<PeersQueryParams>,
+) -> Response {
+    let (in_memory_torrent_repository, geoip) = state;
+
+    let info_hash = match &params.info_hash {
+        Some(info_hash) => match InfoHash::from_str(info_hash) {
+            Ok(info_hash) => Some(info_hash),
+            Err(_) => return invalid_info_hash_param_response(info_hash),
+        },
+        None => None,
+    };
+
+    let locations_metrics = match (&info_hash, geoip.is_enabled()) {
+        (_, false) => LocationsMetrics::default(),
+        (Some(info_hash), true) => in_memory_torrent_repository.get_torrent_locations_metrics(info_hash, &geoip),
+        (None, true) => in_memory_torrent_repository.get_locations_metrics(),
+    };
+
+    match params.format {
+        Some(Format::Prometheus) => locations_metrics_response(&locations_metrics, info_hash.as_ref()),
+        Some(Format::Json) | None => locations_stats_response(&locations_metrics),
+    }
+}
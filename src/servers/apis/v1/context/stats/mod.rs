@@ -6,6 +6,9 @@
 //! # Endpoints
 //!
 //! - [Get tracker statistics](#get-tracker-statistics)
+//! - [Get the statistics history](#get-the-statistics-history)
+//! - [Get the client software statistics](#get-the-client-software-statistics)
+//! - [Get the location statistics](#get-the-location-statistics)
 //!
 //! # Get tracker statistics
 //!
@@ -46,6 +49,172 @@
 //!
 //! Refer to the API [`Stats`](crate::servers::apis::v1::context::stats::resources::Stats)
 //! resource for more information about the response attributes.
+//!
+//! # Get the statistics history
+//!
+//! `GET /stats/history`
+//!
+//! Returns the samples of the metrics recorded by the tracker when the
+//! [`metrics_history`](torrust_tracker_configuration::MetricsHistory) section
+//! is in the configuration. Each sample contains the number of requests,
+//! announces, scrapes and errors in its period, and the number of torrents,
+//! seeders and leechers at the end of it.
+//!
+//! **Query parameters**
+//!
+//! Parameter | Format | Description | Required | Default | Example
+//! ---|---|---|---|---|---
+//! `from` | positive integer | The start of the range, in seconds since the Unix epoch | No | The start of the full resolution samples | `1717171200`
+//! `to` | positive integer | The end of the range (excluded), in seconds since the Unix epoch | No | Now | `1717257600`
+//! `step` | positive integer | The number of seconds covered by each sample | No | The sample interval | `3600`
+//!
+//! The `step` is rounded up to a multiple of the interval of the samples. The
+//! downsampled samples are used for ranges older than the full resolution
+//! samples and for steps longer than the downsampled interval.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/history?from=1717171200&step=3600&token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "step": 3600,
+//!     "samples": [
+//!         {
+//!             "timestamp": 1717171200,
+//!             "duration": 3600,
+//!             "requests": 5120,
+//!             "announces": 4830,
+//!             "scrapes": 150,
+//!             "errors": 3,
+//!             "torrents": 210,
+//!             "seeders": 190,
+//!             "leechers": 45
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the API [`StatsHistory`](crate::servers::apis::v1::context::stats::resources::StatsHistory)
+//! resource for more information about the response attributes.
+//!
+//! # Get the client software statistics
+//!
+//! `GET /stats/clients`
+//!
+//! Returns the number of active peers by client software and version, the
+//! most used first. The client is parsed from the peer ID, using the
+//! Azureus-style (`-TR4040-...`) and Shadow-style (`S58B-----...`)
+//! conventions. The peers with other peer IDs are counted as `Unknown`.
+//!
+//! It can be used to spot buggy client versions and fake clients.
+//!
+//! **Query parameters**
+//!
+//! Parameter | Format | Description | Required | Default | Example
+//! ---|---|---|---|---|---
+//! `info_hash` | 40-char string | Only the peers of this torrent | No | All the torrents | `9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d`
+//! `format` | `json` or `prometheus` | The format of the response | No | `json` | `prometheus`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/clients?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! [
+//!     {
+//!         "client": "qBittorrent",
+//!         "version": "4.6.3",
+//!         "peers": 120
+//!     },
+//!     {
+//!         "client": "Transmission",
+//!         "version": "4.0.4",
+//!         "peers": 35
+//!     }
+//! ]
+//! ```
+//!
+//! **Example response** `200` with `format=prometheus`
+//!
+//! ```text
+//! peer_clients{client="qBittorrent",version="4.6.3"} 120
+//! peer_clients{client="Transmission",version="4.0.4"} 35
+//! ```
+//!
+//! The lines also have the `info_hash` label when the `info_hash` param is
+//! given. The global lines are included in the `GET /stats` response in
+//! Prometheus format too.
+//!
+//! **Resource**
+//!
+//! Refer to the API [`ClientStats`](crate::servers::apis::v1::context::stats::resources::ClientStats)
+//! resource for more information about the response attributes.
+//!
+//! # Get the location statistics
+//!
+//! `GET /stats/locations`
+//!
+//! Returns the number of active peers by country and autonomous system
+//! (ASN), the most common first. The peer addresses are looked up in the
+//! `GeoIP` database configured in the `[core.geoip]` section. The peers whose
+//! address is not in the database have a `null` country or ASN.
+//!
+//! Both lists are empty if `GeoIP` is not enabled.
+//!
+//! **Query parameters**
+//!
+//! Parameter | Format | Description | Required | Default | Example
+//! ---|---|---|---|---|---
+//! `info_hash` | 40-char string | Only the peers of this torrent | No | All the torrents | `9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d`
+//! `format` | `json` or `prometheus` | The format of the response | No | `json` | `prometheus`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/stats/locations?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "countries": [
+//!         { "country": "SE", "peers": 120 },
+//!         { "country": null, "peers": 3 }
+//!     ],
+//!     "asns": [
+//!         { "asn": 29518, "organization": "Bredband2 AB", "peers": 120 },
+//!         { "asn": null, "organization": null, "peers": 3 }
+//!     ]
+//! }
+//! ```
+//!
+//! **Example response** `200` with `format=prometheus`
+//!
+//! ```text
+//! peer_countries{country="SE"} 120
+//! peer_asns{asn="29518",organization="Bredband2 AB"} 120
+//! ```
+//!
+//! The peers that could not be located are not included in the Prometheus
+//! format. The lines also have the `info_hash` label when the `info_hash`
+//! param is given.
+//!
+//! **Resource**
+//!
+//! Refer to the API [`LocationStats`](crate::servers::apis::v1::context::stats::resources::LocationStats)
+//! resource for more information about the response attributes.
 pub mod handlers;
 pub mod resources;
 pub mod responses;
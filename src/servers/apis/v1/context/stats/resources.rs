@@ -1,80 +1,83 @@
 //! API resources for the [`stats`](crate::servers::apis::v1::context::stats)
 //! API context.
-use serde::{Deserialize, Serialize};
+use bittorrent_tracker_core::geoip::LocationsMetrics;
+pub use torrust_tracker_api_resources::v1::stats::{
+    AsnStats, ClientStats, CountryStats, LocationStats, Stats, StatsHistory, StatsSample,
+};
+use torrust_tracker_primitives::client_metrics::ClientsMetrics;
 
+use crate::packages::tracker_api_core::statistics::history::{Sample, Series};
 use crate::packages::tracker_api_core::statistics::services::TrackerMetrics;
 
-/// It contains all the statistics generated by the tracker.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
-pub struct Stats {
-    // Torrent metrics
-    /// Total number of torrents.
-    pub torrents: u64,
-    /// Total number of seeders for all torrents.
-    pub seeders: u64,
-    /// Total number of peers that have ever completed downloading for all torrents.
-    pub completed: u64,
-    /// Total number of leechers for all torrents.
-    pub leechers: u64,
-
-    // Protocol metrics
-    /// Total number of TCP (HTTP tracker) connections from IPv4 peers.
-    /// Since the HTTP tracker spec does not require a handshake, this metric
-    /// increases for every HTTP request.
-    pub tcp4_connections_handled: u64,
-    /// Total number of TCP (HTTP tracker) `announce` requests from IPv4 peers.
-    pub tcp4_announces_handled: u64,
-    /// Total number of TCP (HTTP tracker) `scrape` requests from IPv4 peers.
-    pub tcp4_scrapes_handled: u64,
+/// It returns the [`ClientStats`] of each client, the most used first.
+#[must_use]
+pub fn client_stats(metrics: &ClientsMetrics) -> Vec<ClientStats> {
+    metrics
+        .most_used()
+        .into_iter()
+        .map(|(client, peers)| ClientStats {
+            client: client.name.clone(),
+            version: client.version.clone(),
+            peers,
+        })
+        .collect()
+}
 
-    /// Total number of TCP (HTTP tracker) connections from IPv6 peers.
-    pub tcp6_connections_handled: u64,
-    /// Total number of TCP (HTTP tracker) `announce` requests from IPv6 peers.
-    pub tcp6_announces_handled: u64,
-    /// Total number of TCP (HTTP tracker) `scrape` requests from IPv6 peers.
-    pub tcp6_scrapes_handled: u64,
+/// It returns the [`LocationStats`], the most common countries and ASNs first.
+#[must_use]
+pub fn location_stats(metrics: &LocationsMetrics) -> LocationStats {
+    LocationStats {
+        countries: metrics
+            .top_countries()
+            .into_iter()
+            .map(|(country, peers)| CountryStats {
+                country: country.cloned(),
+                peers,
+            })
+            .collect(),
+        asns: metrics
+            .top_asns()
+            .into_iter()
+            .map(|(asn, peers)| AsnStats {
+                asn: asn.map(|asn| asn.number),
+                organization: asn.and_then(|asn| asn.organization.clone()),
+                peers,
+            })
+            .collect(),
+    }
+}
 
-    // UDP
-    /// Total number of UDP (UDP tracker) requests aborted.
-    pub udp_requests_aborted: u64,
-    /// Total number of UDP (UDP tracker) requests banned.
-    pub udp_requests_banned: u64,
-    /// Total number of IPs banned for UDP (UDP tracker) requests.
-    pub udp_banned_ips_total: u64,
-    /// Average rounded time spent processing UDP connect requests.
-    pub udp_avg_connect_processing_time_ns: u64,
-    /// Average rounded time spent processing UDP announce requests.
-    pub udp_avg_announce_processing_time_ns: u64,
-    /// Average rounded time spent processing UDP scrape requests.
-    pub udp_avg_scrape_processing_time_ns: u64,
+/// It returns the tracker `load` as a percentage rounded down, `0` when the
+/// adaptive announce interval is disabled.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn load_percent(load: Option<f64>) -> u64 {
+    load.map_or(0, |load| (load * 100.0).floor() as u64)
+}
 
-    // UDPv4
-    /// Total number of UDP (UDP tracker) requests from IPv4 peers.
-    pub udp4_requests: u64,
-    /// Total number of UDP (UDP tracker) connections from IPv4 peers.
-    pub udp4_connections_handled: u64,
-    /// Total number of UDP (UDP tracker) `announce` requests from IPv4 peers.
-    pub udp4_announces_handled: u64,
-    /// Total number of UDP (UDP tracker) `scrape` requests from IPv4 peers.
-    pub udp4_scrapes_handled: u64,
-    /// Total number of UDP (UDP tracker) responses from IPv4 peers.
-    pub udp4_responses: u64,
-    /// Total number of UDP (UDP tracker) `scrape` requests from IPv4 peers.
-    pub udp4_errors_handled: u64,
+impl From<Series> for StatsHistory {
+    fn from(series: Series) -> Self {
+        Self {
+            step: series.step,
+            samples: series.samples.into_iter().map(StatsSample::from).collect(),
+        }
+    }
+}
 
-    // UDPv6
-    /// Total number of UDP (UDP tracker) requests from IPv6 peers.
-    pub udp6_requests: u64,
-    /// Total number of UDP (UDP tracker) `connection` requests from IPv6 peers.
-    pub udp6_connections_handled: u64,
-    /// Total number of UDP (UDP tracker) `announce` requests from IPv6 peers.
-    pub udp6_announces_handled: u64,
-    /// Total number of UDP (UDP tracker) `scrape` requests from IPv6 peers.
-    pub udp6_scrapes_handled: u64,
-    /// Total number of UDP (UDP tracker) responses from IPv6 peers.
-    pub udp6_responses: u64,
-    /// Total number of UDP (UDP tracker) `scrape` requests from IPv6 peers.
-    pub udp6_errors_handled: u64,
+impl From<Sample> for StatsSample {
+    fn from(sample: Sample) -> Self {
+        Self {
+            timestamp: sample.timestamp,
+            duration: sample.duration,
+            requests: sample.requests,
+            announces: sample.announces,
+            scrapes: sample.scrapes,
+            errors: sample.errors,
+            torrents: sample.torrents,
+            seeders: sample.seeders,
+            leechers: sample.leechers,
+        }
+    }
 }
 
 impl From<TrackerMetrics> for Stats {
@@ -84,6 +87,16 @@ impl From<TrackerMetrics> for Stats {
             seeders: metrics.torrents_metrics.complete,
             completed: metrics.torrents_metrics.downloaded,
             leechers: metrics.torrents_metrics.incomplete,
+            // Eviction
+            torrents_evicted: metrics.eviction_metrics.torrents_evicted,
+            torrents_refused: metrics.eviction_metrics.torrents_refused,
+            peers_evicted: metrics.eviction_metrics.peers_evicted,
+            peers_refused: metrics.eviction_metrics.peers_refused,
+            peers_per_ip_exceeded: metrics.eviction_metrics.peers_per_ip_exceeded,
+            // Announce interval
+            early_announces: metrics.early_announces,
+            announce_load_percent: load_percent(metrics.announce_interval_metrics.load),
+            announce_interval_avg: metrics.announce_interval_metrics.intervals.average(),
             // TCP
             tcp4_connections_handled: metrics.protocol_metrics.tcp4_connections_handled,
             tcp4_announces_handled: metrics.protocol_metrics.tcp4_announces_handled,
@@ -118,8 +131,10 @@ impl From<TrackerMetrics> for Stats {
 
 #[cfg(test)]
 mod tests {
+    use bittorrent_tracker_core::adaptive_interval::{AnnounceIntervalMetrics, IntervalMetrics};
     use packages::tracker_api_core::statistics::metrics::Metrics;
     use torrust_tracker_primitives::torrent_metrics::TorrentsMetrics;
+    use torrust_tracker_torrent_repository::limits::EvictionMetrics;
 
     use super::Stats;
     use crate::packages::tracker_api_core::statistics::services::TrackerMetrics;
@@ -135,6 +150,22 @@ mod tests {
                     incomplete: 3,
                     torrents: 4
                 },
+                eviction_metrics: EvictionMetrics {
+                    torrents_evicted: 29,
+                    torrents_refused: 30,
+                    peers_evicted: 31,
+                    peers_refused: 32,
+                    peers_per_ip_exceeded: 33,
+                },
+                early_announces: 34,
+                announce_interval_metrics: AnnounceIntervalMetrics {
+                    load: Some(0.75),
+                    intervals: IntervalMetrics {
+                        buckets: vec![0, 0, 0, 1, 0, 1, 0, 0, 0],
+                        count: 2,
+                        sum: 1800,
+                    },
+                },
                 protocol_metrics: Metrics {
                     // TCP
                     tcp4_connections_handled: 5,
@@ -171,6 +202,16 @@ mod tests {
                 seeders: 1,
                 completed: 2,
                 leechers: 3,
+                // Eviction
+                torrents_evicted: 29,
+                torrents_refused: 30,
+                peers_evicted: 31,
+                peers_refused: 32,
+                peers_per_ip_exceeded: 33,
+                // Announce interval
+                early_announces: 34,
+                announce_load_percent: 75,
+                announce_interval_avg: 900,
                 // TCPv4
                 tcp4_connections_handled: 5,
                 tcp4_announces_handled: 6,
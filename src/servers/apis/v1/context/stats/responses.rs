@@ -2,8 +2,15 @@
 //! API context.
 use axum::response::{IntoResponse, Json, Response};
 
-use super::resources::Stats;
+use bittorrent_primitives::info_hash::InfoHash;
+use bittorrent_tracker_core::adaptive_interval::INTERVAL_BUCKETS;
+use bittorrent_tracker_core::geoip::LocationsMetrics;
+use torrust_tracker_primitives::client_metrics::ClientsMetrics;
+
+use super::resources::{client_stats, location_stats, Stats, StatsHistory};
+use crate::packages::tracker_api_core::statistics::history::Series;
 use crate::packages::tracker_api_core::statistics::services::TrackerMetrics;
+use crate::servers::apis::v1::responses::bad_request_response;
 
 /// `200` response that contains the [`Stats`] resource as json.
 #[must_use]
@@ -11,9 +18,108 @@ pub fn stats_response(tracker_metrics: TrackerMetrics) -> Response {
     Json(Stats::from(tracker_metrics)).into_response()
 }
 
+/// `200` response that contains the [`StatsHistory`] resource as json.
+#[must_use]
+pub fn stats_history_response(series: Series) -> Response {
+    Json(StatsHistory::from(series)).into_response()
+}
+
+/// `400` response when the history range or step are not valid.
+#[must_use]
+pub fn invalid_history_range_response() -> Response {
+    bad_request_response("Invalid URL: the `from` param must be before the `to` param and the `step` must be positive")
+}
+
+/// `200` response that contains the [`ClientStats`](super::resources::ClientStats)
+/// of each client as json.
+#[must_use]
+pub fn clients_stats_response(clients_metrics: &ClientsMetrics) -> Response {
+    Json(client_stats(clients_metrics)).into_response()
+}
+
+/// `200` response that contains the number of peers by client in Prometheus
+/// Text Exposition Format. The lines have the `info_hash` label when they are
+/// for a single torrent.
+#[must_use]
+pub fn clients_metrics_response(clients_metrics: &ClientsMetrics, info_hash: Option<&InfoHash>) -> Response {
+    clients_metrics_lines(clients_metrics, info_hash).join("\n").into_response()
+}
+
+fn clients_metrics_lines(clients_metrics: &ClientsMetrics, info_hash: Option<&InfoHash>) -> Vec<String> {
+    clients_metrics
+        .most_used()
+        .into_iter()
+        .map(|(client, peers)| {
+            let mut labels = vec![format!("client=\"{}\"", escape_label_value(&client.name))];
+
+            if let Some(version) = &client.version {
+                labels.push(format!("version=\"{}\"", escape_label_value(version)));
+            }
+
+            if let Some(info_hash) = info_hash {
+                labels.push(format!("info_hash=\"{info_hash}\""));
+            }
+
+            format!("peer_clients{{{}}} {peers}", labels.join(","))
+        })
+        .collect()
+}
+
+/// `200` response that contains the [`LocationStats`](super::resources::LocationStats)
+/// resource as json.
+#[must_use]
+pub fn locations_stats_response(locations_metrics: &LocationsMetrics) -> Response {
+    Json(location_stats(locations_metrics)).into_response()
+}
+
+/// `200` response that contains the number of peers by country and ASN in
+/// Prometheus Text Exposition Format. The lines have the `info_hash` label
+/// when they are for a single torrent. The peers that could not be located
+/// are not included.
+#[must_use]
+pub fn locations_metrics_response(locations_metrics: &LocationsMetrics, info_hash: Option<&InfoHash>) -> Response {
+    let info_hash_label = info_hash
+        .map(|info_hash| format!(",info_hash=\"{info_hash}\""))
+        .unwrap_or_default();
+
+    let countries = locations_metrics
+        .top_countries()
+        .into_iter()
+        .filter_map(|(country, peers)| country.map(|country| (country, peers)))
+        .map(|(country, peers)| {
+            format!(
+                "peer_countries{{country=\"{}\"{info_hash_label}}} {peers}",
+                escape_label_value(country)
+            )
+        });
+
+    let asns = locations_metrics
+        .top_asns()
+        .into_iter()
+        .filter_map(|(asn, peers)| asn.map(|asn| (asn, peers)))
+        .map(|(asn, peers)| {
+            let organization = asn
+                .organization
+                .as_ref()
+                .map(|organization| format!(",organization=\"{}\"", escape_label_value(organization)))
+                .unwrap_or_default();
+
+            format!("peer_asns{{asn=\"{}\"{organization}{info_hash_label}}} {peers}", asn.number)
+        });
+
+    countries.chain(asns).collect::<Vec<String>>().join("\n").into_response()
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
 /// `200` response that contains the [`Stats`] resource in Prometheus Text Exposition Format .
+///
+/// It also includes the number of peers by client.
 #[must_use]
-pub fn metrics_response(tracker_metrics: &TrackerMetrics) -> Response {
+#[allow(clippy::too_many_lines)]
+pub fn metrics_response(tracker_metrics: &TrackerMetrics, clients_metrics: &ClientsMetrics) -> Response {
     let mut lines = vec![];
 
     lines.push(format!("torrents {}", tracker_metrics.torrents_metrics.torrents));
@@ -21,6 +127,42 @@ pub fn metrics_response(tracker_metrics: &TrackerMetrics) -> Response {
     lines.push(format!("completed {}", tracker_metrics.torrents_metrics.downloaded));
     lines.push(format!("leechers {}", tracker_metrics.torrents_metrics.incomplete));
 
+    // Eviction
+
+    lines.push(format!(
+        "torrents_evicted {}",
+        tracker_metrics.eviction_metrics.torrents_evicted
+    ));
+    lines.push(format!(
+        "torrents_refused {}",
+        tracker_metrics.eviction_metrics.torrents_refused
+    ));
+    lines.push(format!("peers_evicted {}", tracker_metrics.eviction_metrics.peers_evicted));
+    lines.push(format!("peers_refused {}", tracker_metrics.eviction_metrics.peers_refused));
+    lines.push(format!(
+        "peers_per_ip_exceeded {}",
+        tracker_metrics.eviction_metrics.peers_per_ip_exceeded
+    ));
+
+    // Announce interval
+
+    lines.push(format!("early_announces {}", tracker_metrics.early_announces));
+    lines.push(format!(
+        "announce_load {}",
+        tracker_metrics.announce_interval_metrics.load.unwrap_or_default()
+    ));
+
+    // The buckets of a Prometheus histogram are cumulative.
+    let intervals = &tracker_metrics.announce_interval_metrics.intervals;
+    let mut responses = 0;
+    for (bound, count) in INTERVAL_BUCKETS.iter().zip(&intervals.buckets) {
+        responses += count;
+        lines.push(format!("announce_interval_seconds_bucket{{le=\"{bound}\"}} {responses}"));
+    }
+    lines.push(format!("announce_interval_seconds_bucket{{le=\"+Inf\"}} {}", intervals.count));
+    lines.push(format!("announce_interval_seconds_sum {}", intervals.sum));
+    lines.push(format!("announce_interval_seconds_count {}", intervals.count));
+
     // TCP
 
     // TCPv4
@@ -122,6 +264,10 @@ pub fn metrics_response(tracker_metrics: &TrackerMetrics) -> Response {
         tracker_metrics.protocol_metrics.udp6_errors_handled
     ));
 
+    // Clients
+
+    lines.extend(clients_metrics_lines(clients_metrics, None));
+
     // Return the plain text response
     lines.join("\n").into_response()
 }
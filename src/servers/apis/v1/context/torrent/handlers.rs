@@ -1,22 +1,35 @@
 //! API handlers for the [`torrent`](crate::servers::apis::v1::context::torrent)
 //! API context.
+use std::convert::Infallible;
 use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use aquatic_udp_protocol::PeerId;
 use axum::extract::{Path, State};
 use axum::response::{IntoResponse, Response};
 use axum_extra::extract::Query;
 use bittorrent_primitives::info_hash::InfoHash;
+use bittorrent_tracker_core::torrent::manager::TorrentsManager;
 use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
-use bittorrent_tracker_core::torrent::services::{get_torrent_info, get_torrents, get_torrents_page};
+use bittorrent_tracker_core::torrent::services::{
+    get_torrent_info, get_torrents, get_torrents_after, get_torrents_snapshot, search_torrents, Filters, Order, SortBy, Sorting,
+};
+use futures::stream::{self, StreamExt};
 use serde::{de, Deserialize, Deserializer};
 use thiserror::Error;
+use tokio::task::JoinError;
+use torrust_tracker_clock::clock::Time;
+use torrust_tracker_clock::conv::convert_from_timestamp_to_datetime_utc;
 use torrust_tracker_primitives::pagination::Pagination;
 
-use super::responses::{torrent_info_response, torrent_list_response, torrent_not_known_response};
-use crate::servers::apis::v1::responses::invalid_info_hash_param_response;
+use super::responses::{
+    failed_to_export_torrents_response, failed_to_purge_torrent_response, peer_not_known_response, torrent_export_response,
+    torrent_info_response, torrent_list_response, torrent_not_known_response, torrent_snapshot_export_response, ExportFormat,
+};
+use crate::servers::apis::v1::responses::{bad_request_response, invalid_info_hash_param_response, ok_response};
 use crate::servers::apis::InfoHashParam;
+use crate::CurrentClock;
 
 /// It handles the request to get the torrent data.
 ///
@@ -40,6 +53,80 @@ pub async fn get_torrent_handler(
     }
 }
 
+/// A container for the URL query parameters of the torrent deletion.
+///
+/// For example, to also delete the persisted number of downloads:
+///
+/// <http://127.0.0.1:1212/api/v1/torrent/9c38422213e30bff212b30c360d26f9a02136422?token=MyAccessToken&delete_completed=true>
+#[derive(Deserialize, Debug)]
+pub struct DeleteTorrentQueryParams {
+    /// Whether to delete the persisted `completed` counter too. Default:
+    /// `false`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub delete_completed: Option<bool>,
+}
+
+/// It handles the request to delete a torrent and all its peers.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json.
+/// - `200` response with a "torrent not known" json string if the torrent
+///   was not being tracked and there was nothing to delete.
+/// - `500` with serialized error in debug format if the persisted `completed`
+///   counter couldn't be deleted.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#delete-a-torrent)
+/// for more information about this endpoint.
+pub async fn delete_torrent_handler(
+    State(torrents_manager): State<Arc<TorrentsManager>>,
+    Path(info_hash): Path<InfoHashParam>,
+    params: Query<DeleteTorrentQueryParams>,
+) -> Response {
+    let delete_completed = params.0.delete_completed.unwrap_or_default();
+
+    match InfoHash::from_str(&info_hash.0) {
+        Err(_) => invalid_info_hash_param_response(&info_hash.0),
+        Ok(info_hash) => match torrents_manager.purge_torrent(&info_hash, delete_completed) {
+            Ok(true) => ok_response(),
+            // The persisted counter, if any, has been deleted anyway.
+            Ok(false) if delete_completed => ok_response(),
+            Ok(false) => torrent_not_known_response(),
+            Err(e) => failed_to_purge_torrent_response(e),
+        },
+    }
+}
+
+/// It handles the request to evict a single peer from a torrent's swarm.
+///
+/// It returns:
+///
+/// - `200` response with a [`ActionStatus::Ok`](crate::servers::apis::v1::responses::ActionStatus::Ok) in json.
+/// - `200` response with a "peer not known" json string if the torrent or
+///   the peer do not exist.
+/// - `400` response if the peer ID is not valid.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#evict-a-peer)
+/// for more information about this endpoint.
+pub async fn evict_peer_handler(
+    State(torrents_manager): State<Arc<TorrentsManager>>,
+    Path((info_hash, peer_id)): Path<(String, String)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash) else {
+        return invalid_info_hash_param_response(&info_hash);
+    };
+
+    let peer_id = match parse_peer_id(&peer_id) {
+        Ok(peer_id) => peer_id,
+        Err(err) => return query_param_error_response(err),
+    };
+
+    match torrents_manager.evict_peer(&info_hash, &peer_id) {
+        Some(_peer) => ok_response(),
+        None => peer_not_known_response(),
+    }
+}
+
 /// A container for the URL query parameters.
 ///
 /// Pagination: `offset` and `limit`.
@@ -58,7 +145,16 @@ pub async fn get_torrent_handler(
 /// <http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&info_hash=9c38422213e30bff212b30c360d26f9a02136422&info_hash=2b66980093bc11806fab50cb3cb41835b95a0362>
 ///
 ///
-/// NOTICE: Pagination is ignored if array of infohashes is provided.
+/// The top 100 torrents by seeders:
+///
+/// <http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&sort=seeders&order=desc&limit=100>
+///
+/// Sorting: `sort` and `order`.
+/// Filters: `min_seeders`, `max_seeders`, `min_leechers`, `max_leechers`,
+/// `min_completed`, `max_completed`, `min_peers` and `max_peers`.
+///
+/// NOTICE: Pagination, sorting and filters are ignored if array of infohashes
+/// is provided.
 #[derive(Deserialize, Debug)]
 pub struct QueryParams {
     /// The offset of the first page to return. Starts at 0.
@@ -70,11 +166,50 @@ pub struct QueryParams {
     /// A list of infohashes to retrieve.
     #[serde(default, rename = "info_hash")]
     pub info_hashes: Vec<String>,
+    /// The swarm metric to sort by: `seeders`, `leechers`, `completed` or
+    /// `peers`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub sort: Option<String>,
+    /// The sorting direction: `asc` or `desc`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub order: Option<String>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub min_seeders: Option<u64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub max_seeders: Option<u64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub min_leechers: Option<u64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub max_leechers: Option<u64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub min_completed: Option<u64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub max_completed: Option<u64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub min_peers: Option<u64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub max_peers: Option<u64>,
+}
+
+impl QueryParams {
+    fn filters(&self) -> Filters {
+        Filters {
+            min_seeders: self.min_seeders,
+            max_seeders: self.max_seeders,
+            min_leechers: self.min_leechers,
+            max_leechers: self.max_leechers,
+            min_completed: self.min_completed,
+            max_completed: self.max_completed,
+            min_peers: self.min_peers,
+            max_peers: self.max_peers,
+        }
+    }
 }
 
 /// It handles the request to get a list of torrents.
 ///
-/// It returns a `200` response with a json array with [`crate::servers::apis::v1::context::torrent::resources::torrent::ListItem`] resources.
+/// It returns a `200` response with a json array with [`crate::servers::apis::v1::context::torrent::resources::torrent::ListItem`] resources,
+/// or a `400` response if the sorting params are not valid.
 ///
 /// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#list-torrents)
 /// for more information about this endpoint.
@@ -85,25 +220,201 @@ pub async fn get_torrents_handler(
     tracing::debug!("pagination: {:?}", pagination);
 
     if pagination.0.info_hashes.is_empty() {
-        torrent_list_response(&get_torrents_page(
+        let sorting = match parse_sorting(pagination.0.sort.as_deref(), pagination.0.order.as_deref()) {
+            Ok(sorting) => sorting,
+            Err(err) => return query_param_error_response(err),
+        };
+
+        torrent_list_response(&search_torrents(
             &in_memory_torrent_repository,
+            &pagination.0.filters(),
+            sorting,
             Some(&Pagination::new_with_options(pagination.0.offset, pagination.0.limit)),
         ))
         .into_response()
     } else {
         match parse_info_hashes(pagination.0.info_hashes) {
             Ok(info_hashes) => torrent_list_response(&get_torrents(&in_memory_torrent_repository, &info_hashes)).into_response(),
-            Err(err) => match err {
-                QueryParamError::InvalidInfoHash { info_hash } => invalid_info_hash_param_response(&info_hash),
-            },
+            Err(err) => query_param_error_response(err),
+        }
+    }
+}
+
+/// The number of torrents read from the repository and sent in each chunk of
+/// the export.
+const EXPORT_BATCH_SIZE: usize = 1000;
+
+/// A container for the URL query parameters of the torrents export.
+///
+/// For example, all the torrents with their peers in CSV format:
+///
+/// <http://127.0.0.1:1212/api/v1/torrents/export?token=MyAccessToken&format=csv&peers=true>
+#[derive(Deserialize, Debug)]
+pub struct ExportQueryParams {
+    /// The export format: `ndjson` (default) or `csv`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub format: Option<String>,
+    /// Whether to include the peers of each torrent. Default: `false`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub peers: Option<bool>,
+    /// Whether to export a copy of the torrents taken when the request is
+    /// received. It cannot be combined with `peers`. Default: `false`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub snapshot: Option<bool>,
+}
+
+/// It handles the request to export all the torrents.
+///
+/// It returns a `200` response that streams the torrents in NDJSON or CSV
+/// format, or a `400` response if the params are not valid.
+///
+/// By default, the torrents are read from the repository in batches while the
+/// response is being sent, so the export reflects the changes made during the
+/// download. The torrents are read one at a time while they keep changing, so
+/// the export is not atomic.
+///
+/// In snapshot mode, the swarm metadata of all the torrents is copied in a
+/// single pass over the repository before the response is sent, and the
+/// response is stamped with the time of the copy. The snapshot does not
+/// include the peers.
+///
+/// Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent#export-torrents)
+/// for more information about this endpoint.
+pub async fn export_torrents_handler(
+    State(in_memory_torrent_repository): State<Arc<InMemoryTorrentRepository>>,
+    params: Query<ExportQueryParams>,
+) -> Response {
+    let format = match parse_export_format(params.0.format.as_deref()) {
+        Ok(format) => format,
+        Err(err) => return query_param_error_response(err),
+    };
+
+    let with_peers = params.0.peers.unwrap_or_default();
+
+    if params.0.snapshot.unwrap_or_default() {
+        if with_peers {
+            return query_param_error_response(QueryParamError::SnapshotWithPeers);
         }
+
+        let snapshot_time = convert_from_timestamp_to_datetime_utc(CurrentClock::now()).to_rfc3339();
+
+        return match tokio::task::spawn_blocking(move || get_torrents_snapshot(&in_memory_torrent_repository)).await {
+            Ok(torrents) => torrent_snapshot_export_response(
+                format,
+                &snapshot_time,
+                stream::iter(torrents).chunks(EXPORT_BATCH_SIZE).map(Ok::<_, Infallible>),
+            ),
+            Err(e) => failed_to_export_torrents_response(e),
+        };
     }
+
+    let batches = stream::try_unfold(None, move |cursor: Option<InfoHash>| {
+        let in_memory_torrent_repository = in_memory_torrent_repository.clone();
+
+        async move {
+            let batch = tokio::task::spawn_blocking(move || {
+                get_torrents_after(&in_memory_torrent_repository, cursor.as_ref(), EXPORT_BATCH_SIZE, with_peers)
+            })
+            .await?;
+
+            let Some(last) = batch.last().map(|info| info.info_hash) else {
+                return Ok(None);
+            };
+
+            Ok::<_, JoinError>(Some((batch, Some(last))))
+        }
+    });
+
+    torrent_export_response(format, with_peers, batches)
 }
 
 #[derive(Error, Debug)]
 pub enum QueryParamError {
     #[error("invalid infohash {info_hash}")]
     InvalidInfoHash { info_hash: String },
+
+    #[error("invalid sort param \"{sort}\", expected one of: seeders, leechers, completed, peers")]
+    InvalidSort { sort: String },
+
+    #[error("invalid order param \"{order}\", expected one of: asc, desc")]
+    InvalidOrder { order: String },
+
+    #[error("invalid format param \"{format}\", expected one of: ndjson, csv")]
+    InvalidFormat { format: String },
+
+    #[error("invalid peer id \"{peer_id}\", expected 40 hex characters")]
+    InvalidPeerId { peer_id: String },
+
+    #[error("the snapshot export does not include the peers, remove the peers param")]
+    SnapshotWithPeers,
+}
+
+fn query_param_error_response(err: QueryParamError) -> Response {
+    match err {
+        QueryParamError::InvalidInfoHash { info_hash } => invalid_info_hash_param_response(&info_hash),
+        QueryParamError::InvalidSort { .. }
+        | QueryParamError::InvalidOrder { .. }
+        | QueryParamError::InvalidFormat { .. }
+        | QueryParamError::InvalidPeerId { .. }
+        | QueryParamError::SnapshotWithPeers => bad_request_response(&format!("Invalid URL: {err}")),
+    }
+}
+
+fn parse_sorting(sort: Option<&str>, order: Option<&str>) -> Result<Option<Sorting>, QueryParamError> {
+    let order = match order {
+        None | Some("desc") => Order::Descending,
+        Some("asc") => Order::Ascending,
+        Some(order) => {
+            return Err(QueryParamError::InvalidOrder {
+                order: order.to_string(),
+            })
+        }
+    };
+
+    let sort_by = match sort {
+        None => return Ok(None),
+        Some("seeders") => SortBy::Seeders,
+        Some("leechers") => SortBy::Leechers,
+        Some("completed") => SortBy::Completed,
+        Some("peers") => SortBy::Peers,
+        Some(sort) => return Err(QueryParamError::InvalidSort { sort: sort.to_string() }),
+    };
+
+    Ok(Some(Sorting { sort_by, order }))
+}
+
+fn parse_export_format(format: Option<&str>) -> Result<ExportFormat, QueryParamError> {
+    match format {
+        None | Some("ndjson") => Ok(ExportFormat::Ndjson),
+        Some("csv") => Ok(ExportFormat::Csv),
+        Some(format) => Err(QueryParamError::InvalidFormat {
+            format: format.to_string(),
+        }),
+    }
+}
+
+/// It parses a peer ID in the same hex format the API uses to show them, with
+/// or without the `0x` prefix. For example:
+/// `0x2d7142343431302d2a64465a3844484944704579`.
+fn parse_peer_id(peer_id_str: &str) -> Result<PeerId, QueryParamError> {
+    let invalid = || QueryParamError::InvalidPeerId {
+        peer_id: peer_id_str.to_string(),
+    };
+
+    let hex = peer_id_str.strip_prefix("0x").unwrap_or(peer_id_str);
+
+    if hex.len() != 40 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+
+    let mut bytes = [0u8; 20];
+
+    for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+    }
+
+    Ok(PeerId(bytes))
 }
 
 fn parse_info_hashes(info_hashes_str: Vec<String>) -> Result<Vec<InfoHash>, QueryParamError> {
@@ -6,7 +6,10 @@
 //! # Endpoints
 //!
 //! - [Get a torrent](#get-a-torrent)
+//! - [Delete a torrent](#delete-a-torrent)
+//! - [Evict a peer](#evict-a-peer)
 //! - [List torrents](#list-torrents)
+//! - [Export torrents](#export-torrents)
 //!
 //! # Get a torrent
 //!
@@ -65,6 +68,90 @@
 //! Refer to the API [`Torrent`](crate::servers::apis::v1::context::torrent::resources::torrent::Torrent)
 //! resource for more information about the response attributes.
 //!
+//! # Delete a torrent
+//!
+//! `DELETE /torrent/:info_hash`
+//!
+//! It removes a torrent and all its peers from the tracker. Peers that keep
+//! announcing the torrent will add it again.
+//!
+//! The number of downloads (`completed`) persisted in the database is kept by
+//! default, so it's loaded again when the tracker restarts.
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `info_hash` | 40-char string | The Info Hash v1 | Yes | `5452869be36f9f3350ccee6b4544e7e76caaadab`
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `delete_completed` | bool | Delete the persisted `completed` counter too. Default: `false` | No | `true`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X DELETE "http://127.0.0.1:1212/api/v1/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab?token=MyAccessToken&delete_completed=true"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! **Not Found response** `200`
+//!
+//! This response is returned when the tracker does not have the torrent and
+//! `delete_completed` is not set.
+//!
+//! ```json
+//! "torrent not known"
+//! ```
+//!
+//! # Evict a peer
+//!
+//! `DELETE /torrent/:info_hash/peers/:peer_id`
+//!
+//! It removes a single peer from the torrent's swarm, regardless of when it
+//! announced for the last time. The peer is added again if it announces
+//! again. The torrent is removed too if it's left without peers and the
+//! tracker is configured to remove peerless torrents.
+//!
+//! **Path parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `info_hash` | 40-char string | The Info Hash v1 | Yes | `5452869be36f9f3350ccee6b4544e7e76caaadab`
+//! `peer_id` | 40-char hex string | The peer ID, with or without the `0x` prefix | Yes | `0x2d7142343431302d2a64465a3844484944704579`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl -X DELETE "http://127.0.0.1:1212/api/v1/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab/peers/0x2d7142343431302d2a64465a3844484944704579?token=MyAccessToken"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!     "status": "ok"
+//! }
+//! ```
+//!
+//! **Not Found response** `200`
+//!
+//! This response is returned when the tracker does not have the torrent or
+//! the peer.
+//!
+//! ```json
+//! "peer not known"
+//! ```
+//!
 //! # List torrents
 //!
 //! `GET /torrents`
@@ -80,12 +167,49 @@
 //! `offset` | positive integer | The page number, starting at 0 | No | `1`
 //! `limit` | positive integer | Page size. The number of results per page | No | `10`
 //!
+//! Torrents can be sorted by one of the swarm metrics. Torrents with the same
+//! value are sorted by infohash. Without the `sort` parameter, torrents are
+//! returned in infohash order.
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `sort` | `seeders`, `leechers`, `completed` or `peers` | The metric to sort by. `peers` are seeders plus leechers | No | `seeders`
+//! `order` | `asc` or `desc` | The sorting direction. Default: `desc` | No | `asc`
+//!
+//! And they can be filtered by ranges of the swarm metrics. All the bounds are
+//! inclusive.
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `min_seeders` | positive integer | Minimum number of seeders | No | `1`
+//! `max_seeders` | positive integer | Maximum number of seeders | No | `0`
+//! `min_leechers` | positive integer | Minimum number of leechers | No | `1`
+//! `max_leechers` | positive integer | Maximum number of leechers | No | `10`
+//! `min_completed` | positive integer | Minimum number of completed downloads | No | `100`
+//! `max_completed` | positive integer | Maximum number of completed downloads | No | `1000`
+//! `min_peers` | positive integer | Minimum number of peers | No | `1`
+//! `max_peers` | positive integer | Maximum number of peers | No | `50`
+//!
+//! Filters and sorting are applied before pagination.
+//!
 //! **Example request**
 //!
 //! ```bash
 //! curl "http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&offset=1&limit=1"
 //! ```
 //!
+//! The top 100 torrents by seeders:
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&sort=seeders&order=desc&limit=100"
+//! ```
+//!
+//! Torrents with no seeders but active leechers:
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&max_seeders=0&min_leechers=1"
+//! ```
+//!
 //! **Example response** `200`
 //!
 //! ```json
@@ -107,6 +231,70 @@
 //! response.
 //!
 //! > **NOTICE**: this endpoint does not include the `peers` list.
+//!
+//! # Export torrents
+//!
+//! `GET /torrents/export`
+//!
+//! Returns all the torrents in a single streamed response, using chunked
+//! transfer encoding. Torrents are sent in infohash order, in batches, so the
+//! tracker never builds the whole list in memory.
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `format` | `ndjson` or `csv` | The export format. Default: `ndjson` | No | `csv`
+//! `peers` | `true` or `false` | Include the peers of each torrent. Default: `false` | No | `true`
+//! `snapshot` | `true` or `false` | Export a copy of the torrents taken when the request is received. Default: `false` | No | `true`
+//!
+//! Without `snapshot`, each batch is read from the tracker right before it's
+//! sent. A torrent is never sent twice, but changes made while the export is
+//! being downloaded can be partially included. That export is best-effort:
+//! torrents are read one at a time while the tracker keeps updating them, so
+//! it's not a point-in-time copy of the tracker state.
+//!
+//! With `snapshot`, the seeders, completed and leechers of all the torrents
+//! are copied in a single pass over the tracker before the response is sent,
+//! so the export is not affected by the changes made while it's being
+//! downloaded. The response has an `X-Torrust-Snapshot-Time` header with the
+//! time of the copy, in RFC 3339 format. The snapshot does not include the
+//! peers, so `snapshot` cannot be combined with `peers`.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:1212/api/v1/torrents/export?token=MyAccessToken&format=csv&peers=true"
+//! ```
+//!
+//! ```bash
+//! curl -i "http://127.0.0.1:1212/api/v1/torrents/export?token=MyAccessToken&snapshot=true"
+//! ```
+//!
+//! **Example response** `200` (`ndjson`)
+//!
+//! ```text
+//! {"info_hash":"5452869be36f9f3350ccee6b4544e7e76caaadab","seeders":1,"completed":0,"leechers":0}
+//! {"info_hash":"9c38422213e30bff212b30c360d26f9a02136422","seeders":0,"completed":0,"leechers":1}
+//! ```
+//!
+//! **Example response** `200` (`csv`)
+//!
+//! ```text
+//! info_hash,seeders,completed,leechers
+//! 5452869be36f9f3350ccee6b4544e7e76caaadab,1,0,0
+//! 9c38422213e30bff212b30c360d26f9a02136422,0,0,1
+//! ```
+//!
+//! With `peers=true`, the CSV export has one line per peer, with the
+//! `peer_id`, `peer_addr`, `updated_milliseconds_ago`, `uploaded`,
+//! `downloaded`, `left` and `event` columns. Torrents without peers have one
+//! line with those columns empty.
+//!
+//! **Resource**
+//!
+//! Refer to the API [`Torrent`](crate::servers::apis::v1::context::torrent::resources::torrent::Torrent)
+//! resource for more information about the attributes of each line.
 pub mod handlers;
 pub mod resources;
 pub mod responses;
@@ -1,23 +1,48 @@
 //! API responses for the [`torrent`](crate::servers::apis::v1::context::torrent)
 //! API context.
+use axum::body::Body;
+use axum::http::header;
 use axum::response::{IntoResponse, Json, Response};
+use axum::BoxError;
 use bittorrent_tracker_core::torrent::services::{BasicInfo, Info};
+use futures::{stream, Stream, StreamExt};
 use serde_json::json;
 
-use super::resources::torrent::{ListItem, Torrent};
+use super::resources::peer::Peer;
+use super::resources::torrent::{to_resource, torrent_resource, ListItem, Torrent};
+use crate::servers::apis::v1::responses::unhandled_rejection_response;
+
+/// The format of the torrents export.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One [`Torrent`] json object per line.
+    Ndjson,
+    /// Comma-separated values with a header line. When peers are included,
+    /// there is one line per peer.
+    Csv,
+}
+
+impl ExportFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Csv => "text/csv",
+        }
+    }
+}
 
 /// `200` response that contains an array of
 /// [`ListItem`]
 /// resources as json.
 pub fn torrent_list_response(basic_infos: &[BasicInfo]) -> Json<Vec<ListItem>> {
-    Json(ListItem::new_vec(basic_infos))
+    Json(to_resource(basic_infos))
 }
 
 /// `200` response that contains a
 /// [`Torrent`]
 /// resources as json.
 pub fn torrent_info_response(info: Info) -> Json<Torrent> {
-    Json(Torrent::from(info))
+    Json(torrent_resource(info))
 }
 
 /// `500` error response in plain text returned when a torrent is not found.
@@ -25,3 +50,132 @@ pub fn torrent_info_response(info: Info) -> Json<Torrent> {
 pub fn torrent_not_known_response() -> Response {
     Json(json!("torrent not known")).into_response()
 }
+
+/// `200` response returned when the peer to evict is not in the torrent's
+/// swarm.
+#[must_use]
+pub fn peer_not_known_response() -> Response {
+    Json(json!("peer not known")).into_response()
+}
+
+/// `500` error response when the persisted data of a torrent cannot be
+/// deleted.
+#[must_use]
+pub fn failed_to_purge_torrent_response<E: std::error::Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to delete torrent: {e}"))
+}
+
+/// `500` error response when the torrents cannot be read for the export.
+#[must_use]
+pub fn failed_to_export_torrents_response<E: std::error::Error>(e: E) -> Response {
+    unhandled_rejection_response(format!("failed to export torrents: {e}"))
+}
+
+/// The response header with the time a snapshot export was taken, in RFC 3339
+/// format.
+pub const SNAPSHOT_TIME_HEADER: &str = "X-Torrust-Snapshot-Time";
+
+/// `200` response that streams the exported torrents with chunked transfer
+/// encoding.
+///
+/// Each batch of torrents is encoded and sent as soon as it's produced, so the
+/// whole export is never kept in memory. If a batch cannot be read, the
+/// response is aborted, so the client does not get a truncated export that
+/// looks complete.
+pub fn torrent_export_response<S, E>(format: ExportFormat, with_peers: bool, batches: S) -> Response
+where
+    S: Stream<Item = Result<Vec<Info>, E>> + Send + 'static,
+    E: Into<BoxError> + Send + 'static,
+{
+    let header_line = match format {
+        ExportFormat::Ndjson => None,
+        ExportFormat::Csv => Some(csv_header(with_peers)),
+    };
+
+    let chunks = stream::iter(header_line.map(Ok))
+        .chain(batches.map(move |batch| batch.map(|batch| encode_batch(format, with_peers, batch))));
+
+    ([(header::CONTENT_TYPE, format.content_type())], Body::from_stream(chunks)).into_response()
+}
+
+/// `200` response that streams a snapshot export of the torrents, stamped
+/// with the time the snapshot was taken in the [`SNAPSHOT_TIME_HEADER`].
+///
+/// The snapshot does not include the peers.
+pub fn torrent_snapshot_export_response<S, E>(format: ExportFormat, snapshot_time: &str, batches: S) -> Response
+where
+    S: Stream<Item = Result<Vec<Info>, E>> + Send + 'static,
+    E: Into<BoxError> + Send + 'static,
+{
+    (
+        [(SNAPSHOT_TIME_HEADER, snapshot_time.to_string())],
+        torrent_export_response(format, false, batches),
+    )
+        .into_response()
+}
+
+fn encode_batch(format: ExportFormat, with_peers: bool, batch: Vec<Info>) -> String {
+    let mut chunk = String::new();
+
+    for info in batch {
+        let torrent = torrent_resource(info);
+
+        match format {
+            ExportFormat::Ndjson => {
+                chunk.push_str(&serde_json::to_string(&torrent).expect("a torrent resource should be serializable"));
+                chunk.push('\n');
+            }
+            ExportFormat::Csv => push_csv_rows(&mut chunk, &torrent, with_peers),
+        }
+    }
+
+    chunk
+}
+
+fn csv_header(with_peers: bool) -> String {
+    if with_peers {
+        "info_hash,seeders,completed,leechers,peer_id,peer_addr,updated_milliseconds_ago,uploaded,downloaded,left,event\n"
+            .to_string()
+    } else {
+        "info_hash,seeders,completed,leechers\n".to_string()
+    }
+}
+
+/// It adds the CSV lines for a torrent. None of the fields can contain commas,
+/// so values are not quoted.
+fn push_csv_rows(chunk: &mut String, torrent: &Torrent, with_peers: bool) {
+    let swarm = format!(
+        "{},{},{},{}",
+        torrent.info_hash, torrent.seeders, torrent.completed, torrent.leechers
+    );
+
+    let peers = torrent.peers.as_deref().unwrap_or_default();
+
+    if !with_peers {
+        chunk.push_str(&swarm);
+        chunk.push('\n');
+    } else if peers.is_empty() {
+        chunk.push_str(&swarm);
+        chunk.push_str(",,,,,,,\n");
+    } else {
+        for peer in peers {
+            chunk.push_str(&swarm);
+            chunk.push(',');
+            chunk.push_str(&csv_peer_fields(peer));
+            chunk.push('\n');
+        }
+    }
+}
+
+fn csv_peer_fields(peer: &Peer) -> String {
+    format!(
+        "{},{},{},{},{},{},{}",
+        peer.peer_id.id.as_deref().unwrap_or_default(),
+        peer.peer_addr,
+        peer.updated_milliseconds_ago,
+        peer.uploaded,
+        peer.downloaded,
+        peer.left,
+        peer.event
+    )
+}
@@ -1,27 +1,50 @@
 //! API routes for the [`torrent`](crate::servers::apis::v1::context::torrent) API context.
 //!
 //! - `GET /torrent/:info_hash`
+//! - `DELETE /torrent/:info_hash`
+//! - `DELETE /torrent/:info_hash/peers/:peer_id`
 //! - `GET /torrents`
+//! - `GET /torrents/export`
 //!
 //! Refer to the [API endpoint documentation](crate::servers::apis::v1::context::torrent).
 use std::sync::Arc;
 
-use axum::routing::get;
+use axum::routing::{delete, get};
 use axum::Router;
+use bittorrent_tracker_core::torrent::manager::TorrentsManager;
 use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
 
-use super::handlers::{get_torrent_handler, get_torrents_handler};
+use super::handlers::{
+    delete_torrent_handler, evict_peer_handler, export_torrents_handler, get_torrent_handler, get_torrents_handler,
+};
 
 /// It adds the routes to the router for the [`torrent`](crate::servers::apis::v1::context::torrent) API context.
-pub fn add(prefix: &str, router: Router, in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>) -> Router {
+pub fn add(
+    prefix: &str,
+    router: Router,
+    in_memory_torrent_repository: &Arc<InMemoryTorrentRepository>,
+    torrents_manager: &Arc<TorrentsManager>,
+) -> Router {
     // Torrents
     router
         .route(
             &format!("{prefix}/torrent/{{info_hash}}"),
             get(get_torrent_handler).with_state(in_memory_torrent_repository.clone()),
         )
+        .route(
+            &format!("{prefix}/torrent/{{info_hash}}"),
+            delete(delete_torrent_handler).with_state(torrents_manager.clone()),
+        )
+        .route(
+            &format!("{prefix}/torrent/{{info_hash}}/peers/{{peer_id}}"),
+            delete(evict_peer_handler).with_state(torrents_manager.clone()),
+        )
         .route(
             &format!("{prefix}/torrents"),
             get(get_torrents_handler).with_state(in_memory_torrent_repository.clone()),
         )
+        .route(
+            &format!("{prefix}/torrents/export"),
+            get(export_torrents_handler).with_state(in_memory_torrent_repository.clone()),
+        )
 }
@@ -0,0 +1,65 @@
+//! Audit middleware for the API.
+//!
+//! It appends a [`Record`] to the [`AuditLog`] for every request to a route
+//! that requires a write scope. It must run after the
+//! [`auth`](super::auth::auth) middleware and before the
+//! [`authorize`](super::auth::authorize) middleware, so that requests
+//! rejected for missing scopes are recorded too.
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{self, ConnectInfo, MatchedPath};
+use axum::http::Request;
+use axum::middleware::Next;
+use axum::response::Response;
+
+use super::auth::{Authenticated, RequiredScopes};
+use crate::servers::apis::audit::{AuditLog, Record};
+
+#[derive(Clone, Debug)]
+pub struct State {
+    pub audit_log: Arc<AuditLog>,
+    pub required_scopes: RequiredScopes,
+}
+
+/// Middleware that records the requests that can change the tracker state.
+pub async fn audit(extract::State(state): extract::State<State>, request: Request<axum::body::Body>, next: Next) -> Response {
+    if !state.required_scopes.for_method(request.method()).is_write() {
+        return next.run(request).await;
+    }
+
+    let token_label = request
+        .extensions()
+        .get::<Authenticated>()
+        .map(|authenticated| authenticated.label.clone())
+        .unwrap_or_default();
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|connect_info| connect_info.0.ip());
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path().to_string(), |path| path.as_str().to_string());
+    let action = format!("{} {route}", request.method());
+    let target = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+
+    let record = Record::now(&token_label, client_ip, &action, &target, response.status().as_u16());
+
+    let audit_log = state.audit_log.clone();
+
+    let appended = tokio::task::spawn_blocking(move || {
+        if let Err(err) = audit_log.append(&record) {
+            tracing::error!(%err, ?record, "failed to append the API action to the audit log");
+        }
+    })
+    .await;
+
+    if let Err(err) = appended {
+        tracing::error!(%err, "failed to append the API action to the audit log");
+    }
+
+    response
+}
@@ -1,12 +1,18 @@
 //! Authentication middleware for the API.
 //!
-//! It uses a "token" GET param to authenticate the user. URLs must be of the
-//! form:
+//! It uses the `Authorization` header with the `Bearer` scheme to
+//! authenticate the user:
+//!
+//! `Authorization: Bearer <token>`
+//!
+//! For backward compatibility, the token can also be sent in the "token" GET
+//! param, unless the `token_query_param` option is disabled in the
+//! configuration. URLs must be of the form:
 //!
 //! `http://<host>:<port>/api/v1/<context>?token=<token>`.
 //!
 //! > **NOTICE**: the token can be at any position in the URL, not just at the
-//! > beginning or at the end.
+//! > beginning or at the end. The header takes precedence over the GET param.
 //!
 //! The token must be one of the `access_tokens` in the tracker
 //! [HTTP API configuration](torrust_tracker_configuration::HttpApi).
@@ -18,19 +24,49 @@
 //! admin = "MyAccessToken"
 //! ```
 //!
-//! All the tokes have the same permissions, so it is not possible to have
-//! different permissions for different tokens. The label is only used to
-//! identify the token.
-use std::sync::Arc;
+//! Plain tokens have all the permissions. Tokens can also be restricted to
+//! some [`Scope`]s:
+//!
+//! ```toml
+//! [http_api.access_tokens]
+//! admin = "MyAccessToken"
+//! monitoring = { token = "MyMonitoringToken", scopes = ["stats:read"] }
+//! ```
+//!
+//! The [`auth`] middleware only checks the token is valid. The scopes are
+//! checked per route with the [`authorize`] middleware, and a `403` response
+//! naming the missing scope is returned when the token is not allowed to use
+//! the endpoint. The label is only used to identify the token.
+//!
+//! The configuration can contain a SHA-256 or Argon2 hash of the tokens
+//! instead of the tokens themselves (see [`AccessToken`]). Tokens are always
+//! compared in constant time, and the provided token is compared with all the
+//! configured tokens, not only until the first match.
+//!
+//! Argon2 is slow on purpose, so the Argon2 hashes are only checked when no
+//! plain or SHA-256 token matches. The verifications run on the blocking
+//! thread pool, a few at a time, and the results are cached (see
+//! [`Argon2Verifier`]).
+//!
+//! The tokens can be changed without restarting the tracker by reloading the
+//! configuration (see [`Reloader`](crate::bootstrap::config::Reloader)).
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
+use argon2::password_hash::PasswordHash;
+use argon2::{Argon2, PasswordVerifier};
 use axum::extract::{self};
-use axum::http::Request;
+use axum::http::{header, HeaderMap, Method, Request, Uri};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use serde::Deserialize;
-use torrust_tracker_configuration::AccessTokens;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tokio::sync::{RwLock, Semaphore};
+use torrust_tracker_configuration::v2_0_0::tracker_api::{ARGON2_TOKEN_PREFIX, SHA256_TOKEN_PREFIX};
+use torrust_tracker_configuration::{AccessToken, AccessTokens, Scope};
 
-use crate::servers::apis::v1::responses::unhandled_rejection_response;
+use crate::servers::apis::v1::responses::{forbidden_response, unhandled_rejection_response};
 
 /// Container for the `token` extracted from the query params.
 #[derive(Deserialize, Debug)]
@@ -40,23 +76,189 @@ pub struct QueryParams {
 
 #[derive(Clone, Debug)]
 pub struct State {
-    pub access_tokens: Arc<AccessTokens>,
+    pub access_tokens: Arc<RwLock<AccessTokens>>,
+    /// The secret prepended to the tokens stored as a SHA-256 hash.
+    pub token_pepper: Option<String>,
+    /// Whether the token can be sent in the "token" GET param.
+    pub token_query_param: bool,
+    pub argon2_verifier: Arc<Argon2Verifier>,
+}
+
+/// The maximum number of Argon2 verifications running at the same time.
+const MAX_CONCURRENT_ARGON2_VERIFICATIONS: usize = 4;
+
+/// The maximum number of cached Argon2 verification results. The cache is
+/// cleared when it is full.
+const MAX_CACHED_ARGON2_VERIFICATIONS: usize = 1024;
+
+/// It verifies tokens against Argon2 hashes.
+///
+/// Each distinct token is only verified once against each hash. The results
+/// are cached with the SHA-256 digest of the hash and the token as the key, so
+/// the tokens are not kept in memory.
+#[derive(Debug)]
+pub struct Argon2Verifier {
+    results: Mutex<HashMap<[u8; 32], bool>>,
+    permits: Semaphore,
+}
+
+impl Default for Argon2Verifier {
+    fn default() -> Self {
+        Self {
+            results: Mutex::new(HashMap::new()),
+            permits: Semaphore::new(MAX_CONCURRENT_ARGON2_VERIFICATIONS),
+        }
+    }
+}
+
+impl Argon2Verifier {
+    /// It returns whether the token matches each of the hashes.
+    ///
+    /// The hashes that have not been checked with this token yet are verified
+    /// on the blocking thread pool.
+    async fn verify(&self, provided: &str, hashes: &[String]) -> Vec<bool> {
+        let keys: Vec<[u8; 32]> = hashes.iter().map(|hash| cache_key(provided, hash)).collect();
+
+        let mut verified: Vec<Option<bool>> = {
+            let results = self.results.lock().expect("it should lock the Argon2 verification results");
+            keys.iter().map(|key| results.get(key).copied()).collect()
+        };
+
+        let pending: Vec<(usize, String)> = verified
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.is_none())
+            .map(|(index, _)| (index, hashes[index].clone()))
+            .collect();
+
+        if !pending.is_empty() {
+            let Ok(_permit) = self.permits.acquire().await else {
+                return vec![false; hashes.len()];
+            };
+
+            let token = provided.to_string();
+
+            let results = tokio::task::spawn_blocking(move || {
+                pending
+                    .into_iter()
+                    .map(|(index, hash)| (index, verify_argon2(&token, &hash)))
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap_or_default();
+
+            let mut cache = self.results.lock().expect("it should lock the Argon2 verification results");
+
+            for (index, result) in results {
+                if cache.len() >= MAX_CACHED_ARGON2_VERIFICATIONS {
+                    cache.clear();
+                }
+                cache.insert(keys[index], result);
+                verified[index] = Some(result);
+            }
+        }
+
+        verified.into_iter().map(|result| result.unwrap_or(false)).collect()
+    }
+}
+
+fn cache_key(provided: &str, hash: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(hash);
+    hasher.update([0]);
+    hasher.update(provided);
+    hasher.finalize().into()
+}
+
+/// The token that authenticated the request.
+///
+/// The [`auth`] middleware adds it to the request extensions.
+#[derive(Clone, Debug)]
+pub struct Authenticated {
+    /// The label of the token in the configuration.
+    pub label: String,
+    pub token: AccessToken,
+}
+
+/// The scopes required to use the routes of an API context.
+#[derive(Copy, Clone, Debug)]
+pub struct RequiredScopes {
+    /// The scope required by `GET` requests.
+    pub read: Scope,
+    /// The scope required by any other request.
+    pub write: Scope,
+}
+
+impl RequiredScopes {
+    /// The routes can be used with a single scope, regardless of the method.
+    #[must_use]
+    pub fn only(scope: Scope) -> Self {
+        Self {
+            read: scope,
+            write: scope,
+        }
+    }
+
+    #[must_use]
+    pub fn read_write(read: Scope, write: Scope) -> Self {
+        Self { read, write }
+    }
+
+    /// The scope required by requests with the given method.
+    #[must_use]
+    pub fn for_method(self, method: &Method) -> Scope {
+        if method == Method::GET || method == Method::HEAD {
+            self.read
+        } else {
+            self.write
+        }
+    }
 }
 
-/// Middleware for authentication using a "token" GET param.
+/// Middleware for authentication using the `Authorization` header or the
+/// "token" GET param.
 /// The token must be one of the tokens in the tracker [HTTP API configuration](torrust_tracker_configuration::HttpApi).
 pub async fn auth(
     extract::State(state): extract::State<State>,
     extract::Query(params): extract::Query<QueryParams>,
+    mut request: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    let token = match bearer_token(request.headers()) {
+        Ok(Some(token)) => token,
+        Ok(None) if state.token_query_param => match params.token {
+            Some(token) => token,
+            None => return AuthError::Unauthorized.into_response(),
+        },
+        Ok(None) | Err(()) => return AuthError::Unauthorized.into_response(),
+    };
+
+    let Some(authenticated) = authenticate(&token, &state).await else {
+        return AuthError::TokenNotValid.into_response();
+    };
+
+    request.extensions_mut().insert(authenticated);
+
+    next.run(request).await
+}
+
+/// Middleware that checks the token that authenticated the request has the
+/// scope required by the route.
+///
+/// It must run after the [`auth`] middleware.
+pub async fn authorize(
+    extract::State(required_scopes): extract::State<RequiredScopes>,
     request: Request<axum::body::Body>,
     next: Next,
 ) -> Response {
-    let Some(token) = params.token else {
+    let Some(authenticated) = request.extensions().get::<Authenticated>() else {
         return AuthError::Unauthorized.into_response();
     };
 
-    if !authenticate(&token, &state.access_tokens) {
-        return AuthError::TokenNotValid.into_response();
+    let scope = required_scopes.for_method(request.method());
+
+    if !authenticated.token.has_scope(scope) {
+        return AuthError::MissingScope(scope).into_response();
     }
 
     next.run(request).await
@@ -67,6 +269,8 @@ enum AuthError {
     Unauthorized,
     /// Token was provided but it is not valid.
     TokenNotValid,
+    /// Token is valid but it does not allow using the endpoint.
+    MissingScope(Scope),
 }
 
 impl IntoResponse for AuthError {
@@ -74,12 +278,109 @@ impl IntoResponse for AuthError {
         match self {
             AuthError::Unauthorized => unauthorized_response(),
             AuthError::TokenNotValid => token_not_valid_response(),
+            AuthError::MissingScope(scope) => missing_scope_response(scope),
         }
     }
 }
 
-fn authenticate(token: &str, tokens: &AccessTokens) -> bool {
-    tokens.values().any(|t| t == token)
+/// It returns the token in the `Authorization` header, if any.
+///
+/// # Errors
+///
+/// Will return an error if the header is not a valid `Bearer` token.
+fn bearer_token(headers: &HeaderMap) -> Result<Option<String>, ()> {
+    let Some(value) = headers.get(header::AUTHORIZATION) else {
+        return Ok(None);
+    };
+
+    let value = value.to_str().map_err(|_| ())?;
+
+    let (scheme, token) = value.split_once(' ').ok_or(())?;
+
+    if !scheme.eq_ignore_ascii_case("bearer") || token.trim().is_empty() {
+        return Err(());
+    }
+
+    Ok(Some(token.trim().to_string()))
+}
+
+async fn authenticate(token: &str, state: &State) -> Option<Authenticated> {
+    let mut authenticated = None;
+    let mut argon2_tokens = vec![];
+
+    for (label, access_token) in state.access_tokens.read().await.iter() {
+        if access_token.token().starts_with(ARGON2_TOKEN_PREFIX) {
+            argon2_tokens.push((label.clone(), access_token.clone()));
+            continue;
+        }
+
+        // All the tokens are compared, even after a match.
+        if verify(token, access_token.token(), state.token_pepper.as_deref()) && authenticated.is_none() {
+            authenticated = Some(Authenticated {
+                label: label.clone(),
+                token: access_token.clone(),
+            });
+        }
+    }
+
+    if authenticated.is_some() || argon2_tokens.is_empty() {
+        return authenticated;
+    }
+
+    let hashes: Vec<String> = argon2_tokens
+        .iter()
+        .map(|(_label, access_token)| access_token.token().to_string())
+        .collect();
+
+    let verified = state.argon2_verifier.verify(token, &hashes).await;
+
+    argon2_tokens
+        .into_iter()
+        .zip(verified)
+        .find(|(_, verified)| *verified)
+        .map(|((label, token), _)| Authenticated { label, token })
+}
+
+/// It checks the provided token against the configured one, which can be the
+/// token itself or a SHA-256 hash of the token. The comparison is
+/// constant-time.
+///
+/// Argon2 hashes are checked with [`verify_argon2`].
+fn verify(provided: &str, configured: &str, pepper: Option<&str>) -> bool {
+    if let Some(digest) = configured.strip_prefix(SHA256_TOKEN_PREFIX) {
+        let mut hasher = Sha256::new();
+        hasher.update(pepper.unwrap_or_default());
+        hasher.update(provided);
+        let computed = format!("{:x}", hasher.finalize());
+
+        return computed.as_bytes().ct_eq(digest.to_ascii_lowercase().as_bytes()).into();
+    }
+
+    provided.as_bytes().ct_eq(configured.as_bytes()).into()
+}
+
+/// It checks the provided token against an Argon2 hash. The verification is
+/// constant-time, but slow and memory hungry, so it must not run on the async
+/// executor.
+fn verify_argon2(provided: &str, configured: &str) -> bool {
+    PasswordHash::new(configured).is_ok_and(|hash| Argon2::default().verify_password(provided.as_bytes(), &hash).is_ok())
+}
+
+/// It returns the URI with the value of the "token" GET param replaced, so
+/// that it can be logged.
+#[must_use]
+pub fn redacted_uri(uri: &Uri) -> String {
+    let Some(query) = uri.query() else {
+        return uri.to_string();
+    };
+
+    let query = query
+        .split('&')
+        .map(|pair| if pair.starts_with("token=") { "token=***" } else { pair })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{}?{query}", uri.path())
 }
 
 /// `500` error response returned when the token is missing.
@@ -93,3 +394,84 @@ pub fn unauthorized_response() -> Response {
 pub fn token_not_valid_response() -> Response {
     unhandled_rejection_response("token not valid".to_string())
 }
+
+/// `403` error response when the provided token does not have the scope
+/// required by the endpoint.
+#[must_use]
+pub fn missing_scope_response(scope: Scope) -> Response {
+    forbidden_response(&format!("Forbidden: the token is missing the \"{scope}\" scope"))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, HeaderMap, Uri};
+
+    use super::{bearer_token, redacted_uri, verify, verify_argon2, Argon2Verifier};
+
+    #[test]
+    fn it_should_verify_plain_tokens() {
+        assert!(verify("MyAccessToken", "MyAccessToken", None));
+        assert!(!verify("MyAccessToken", "OtherToken", None));
+    }
+
+    #[test]
+    fn it_should_verify_tokens_stored_as_a_sha256_hash_with_a_pepper() {
+        // echo -n "MyPepperMyAccessToken" | sha256sum
+        let configured = "sha256:bf861ed58fa98054d5cf77018e8fce13fb8e6d7e8f0628b1283ff675535ee5fa";
+
+        assert!(verify("MyAccessToken", configured, Some("MyPepper")));
+        assert!(!verify("MyAccessToken", configured, None));
+        assert!(!verify("OtherToken", configured, Some("MyPepper")));
+    }
+
+    #[test]
+    fn it_should_verify_tokens_stored_as_an_argon2_hash() {
+        use argon2::password_hash::{PasswordHasher, SaltString};
+        use argon2::Argon2;
+
+        let salt = SaltString::from_b64("c29tZXNhbHRzb21lc2FsdA").unwrap();
+        let configured = Argon2::default().hash_password(b"MyAccessToken", &salt).unwrap().to_string();
+
+        assert!(verify_argon2("MyAccessToken", &configured));
+        assert!(!verify_argon2("OtherToken", &configured));
+    }
+
+    #[tokio::test]
+    async fn it_should_cache_the_argon2_verifications() {
+        use argon2::password_hash::{PasswordHasher, SaltString};
+        use argon2::Argon2;
+
+        let salt = SaltString::from_b64("c29tZXNhbHRzb21lc2FsdA").unwrap();
+        let configured = Argon2::default().hash_password(b"MyAccessToken", &salt).unwrap().to_string();
+        let hashes = vec![configured, "MyAccessToken".to_string()];
+
+        let verifier = Argon2Verifier::default();
+
+        assert_eq!(verifier.verify("MyAccessToken", &hashes).await, vec![true, false]);
+        assert_eq!(verifier.results.lock().unwrap().len(), 2);
+
+        assert_eq!(verifier.verify("MyAccessToken", &hashes).await, vec![true, false]);
+        assert_eq!(verifier.verify("OtherToken", &hashes).await, vec![false, false]);
+        assert_eq!(verifier.results.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn it_should_get_the_token_from_the_bearer_authorization_header() {
+        let mut headers = HeaderMap::new();
+
+        assert_eq!(bearer_token(&headers), Ok(None));
+
+        headers.insert(header::AUTHORIZATION, "Bearer MyAccessToken".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Ok(Some("MyAccessToken".to_string())));
+
+        headers.insert(header::AUTHORIZATION, "Basic MyAccessToken".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Err(()));
+    }
+
+    #[test]
+    fn it_should_redact_the_token_query_param() {
+        let uri: Uri = "/api/v1/stats?token=MyAccessToken&format=prometheus".parse().unwrap();
+
+        assert_eq!(redacted_uri(&uri), "/api/v1/stats?token=***&format=prometheus");
+    }
+}
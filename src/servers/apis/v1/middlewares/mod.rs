@@ -1,2 +1,3 @@
 //! API middlewares. See [Axum middlewares](axum::middleware).
+pub mod audit;
 pub mod auth;
@@ -8,6 +8,12 @@
 //! `Torrents` | Torrents | [`v1`](crate::servers::apis::v1::context::torrent)
 //! `Whitelist` | Torrents whitelist | [`v1`](crate::servers::apis::v1::context::whitelist)
 //! `Authentication keys` | Authentication keys | [`v1`](crate::servers::apis::v1::context::auth_key)
+//! `Configuration` | Tracker configuration | [`v1`](crate::servers::apis::v1::context::config)
+//! `Listeners` | UDP and HTTP tracker listeners | [`v1`](crate::servers::apis::v1::context::listener)
+//! `Audit` | Audit log of the administrative actions | [`v1`](crate::servers::apis::v1::context::audit)
+//! `Events` | Live stream of the tracker events | [`v1`](crate::servers::apis::v1::context::events)
+//! `Client policy` | Rules that allow or deny clients | [`v1`](crate::servers::apis::v1::context::client_policy)
+//! `OpenAPI` | OpenAPI specification of the API | [`v1`](crate::servers::apis::v1::context::openapi)
 //!
 //! > **NOTICE**:
 //! - The authentication keys are only used by the HTTP tracker.
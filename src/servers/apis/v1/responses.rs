@@ -1,7 +1,7 @@
 //! Common responses for the API v1 shared by all the contexts.
 use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
-use serde::Serialize;
+pub use torrust_tracker_api_resources::v1::action_status::ActionStatus;
 
 /* code-review:
     When Axum cannot parse a path or query param it shows a message like this:
@@ -23,15 +23,6 @@ use serde::Serialize;
     We can put the second level of validation in the application and domain services.
 */
 
-/// Response status used when requests have only two possible results
-/// `Ok` or `Error` and no data is returned.
-#[derive(Serialize, Debug)]
-#[serde(tag = "status", rename_all = "snake_case")]
-pub enum ActionStatus<'a> {
-    Ok,
-    Err { reason: std::borrow::Cow<'a, str> },
-}
-
 // OK response
 
 /// # Panics
@@ -71,6 +62,16 @@ pub fn bad_request_response(body: &str) -> Response {
         .into_response()
 }
 
+#[must_use]
+pub fn forbidden_response(body: &str) -> Response {
+    (
+        StatusCode::FORBIDDEN,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        body.to_owned(),
+    )
+        .into_response()
+}
+
 /// This error response is to keep backward compatibility with the old API.
 /// It should be a plain text or json.
 #[must_use]
@@ -1,18 +1,87 @@
 //! Route initialization for the v1 API.
+//!
+//! The routes of each API context can only be used with a token that has
+//! the scopes required by the context (see [`RequiredScopes`]). The requests
+//! that can change the tracker state are recorded in the
+//! [audit log](crate::servers::apis::audit).
 use std::sync::Arc;
 
-use axum::Router;
+use axum::{middleware, Router};
+use torrust_tracker_configuration::Scope;
 
-use super::context::{auth_key, stats, torrent, whitelist};
+use super::context::{audit, auth_key, client_policy, config, events, listener, stats, torrent, whitelist};
+use super::middlewares::audit as audit_middleware;
+use super::middlewares::auth::{authorize, RequiredScopes};
 use crate::container::HttpApiContainer;
+use crate::servers::apis::audit::AuditLog;
 
 /// Add the routes for the v1 API.
 pub fn add(prefix: &str, router: Router, http_api_container: &Arc<HttpApiContainer>) -> Router {
     let v1_prefix = format!("{prefix}/v1");
 
-    let router = auth_key::routes::add(&v1_prefix, router, &http_api_container.keys_handler.clone());
-    let router = stats::routes::add(&v1_prefix, router, http_api_container);
-    let router = whitelist::routes::add(&v1_prefix, router, &http_api_container.whitelist_manager);
+    router
+        .merge(scoped(
+            auth_key::routes::add(&v1_prefix, Router::new(), &http_api_container.keys_handler.clone()),
+            RequiredScopes::only(Scope::KeysWrite),
+            &http_api_container.audit_log,
+        ))
+        .merge(scoped(
+            stats::routes::add(&v1_prefix, Router::new(), http_api_container),
+            RequiredScopes::only(Scope::StatsRead),
+            &http_api_container.audit_log,
+        ))
+        .merge(scoped(
+            whitelist::routes::add(&v1_prefix, Router::new(), &http_api_container.whitelist_manager),
+            RequiredScopes::only(Scope::WhitelistWrite),
+            &http_api_container.audit_log,
+        ))
+        .merge(scoped(
+            config::routes::add(&v1_prefix, Router::new(), &http_api_container.config_reloader),
+            RequiredScopes::only(Scope::ConfigWrite),
+            &http_api_container.audit_log,
+        ))
+        .merge(scoped(
+            listener::routes::add(&v1_prefix, Router::new(), &http_api_container.listeners),
+            RequiredScopes::read_write(Scope::ListenersRead, Scope::ListenersWrite),
+            &http_api_container.audit_log,
+        ))
+        .merge(scoped(
+            torrent::routes::add(
+                &v1_prefix,
+                Router::new(),
+                &http_api_container.in_memory_torrent_repository.clone(),
+                &http_api_container.torrents_manager,
+            ),
+            RequiredScopes::read_write(Scope::TorrentsRead, Scope::TorrentsWrite),
+            &http_api_container.audit_log,
+        ))
+        .merge(scoped(
+            audit::routes::add(&v1_prefix, Router::new(), &http_api_container.audit_log),
+            RequiredScopes::only(Scope::AuditRead),
+            &http_api_container.audit_log,
+        ))
+        .merge(scoped(
+            events::routes::add(&v1_prefix, Router::new(), &http_api_container.events),
+            RequiredScopes::only(Scope::EventsRead),
+            &http_api_container.audit_log,
+        ))
+        .merge(scoped(
+            client_policy::routes::add(&v1_prefix, Router::new(), &http_api_container.client_policy_manager),
+            RequiredScopes::read_write(Scope::ClientPolicyRead, Scope::ClientPolicyWrite),
+            &http_api_container.audit_log,
+        ))
+}
 
-    torrent::routes::add(&v1_prefix, router, &http_api_container.in_memory_torrent_repository.clone())
+/// It only allows using the routes with tokens that have the required scopes,
+/// and records the requests that require a write scope.
+fn scoped(router: Router, required_scopes: RequiredScopes, audit_log: &Arc<AuditLog>) -> Router {
+    router
+        .route_layer(middleware::from_fn_with_state(required_scopes, authorize))
+        .route_layer(middleware::from_fn_with_state(
+            audit_middleware::State {
+                audit_log: audit_log.clone(),
+                required_scopes,
+            },
+            audit_middleware::audit,
+        ))
 }
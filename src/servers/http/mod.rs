@@ -15,6 +15,7 @@
 //! - [Requests](#requests)
 //!     - [Announce](#announce)
 //!     - [Scrape](#scrape)
+//!     - [Full scrape](#full-scrape)
 //! - [Versioning](#versioning)
 //! - [Links](#links)
 //!
@@ -287,6 +288,24 @@
 //! - [BEP 48. Tracker Protocol Extension: Scrape](https://www.bittorrent.org/beps/bep_0048.html)
 //! - [Vuze scrape docs](https://wiki.vuze.com/w/Scrape)
 //!
+//! ### Full scrape
+//!
+//! When the `[full_scrape]` configuration section sets an `http_path`, the
+//! HTTP tracker also serves a periodic dump with the scrape data of all the
+//! torrents:
+//!
+//! ```toml
+//! [full_scrape]
+//! interval = 300
+//! path = "./storage/tracker/lib/full_scrape"
+//! http_path = "/full_scrape"
+//! ```
+//!
+//! The dump is gzipped and has the same bencoded structure as a scrape
+//! response. Add `?format=json` to get it in JSON. Refer to the
+//! [`full_scrape`](crate::servers::http::v1::handlers::full_scrape) handler
+//! for more information about caching.
+//!
 //! ## Versioning
 //!
 //! Right not there is only version `v1`. The HTTP tracker implements BEPS:
@@ -152,12 +152,12 @@ impl HttpServer<Stopped> {
     ///
     /// # Errors
     ///
-    /// It would return an error if no `SocketAddr` is returned after launching the server.
+    /// It would return an error if no `SocketAddr` is returned after launching
+    /// the server, for example, when it can't bind to the address.
     ///
     /// # Panics
     ///
-    /// It would panic spawned HTTP server launcher cannot send the bound `SocketAddr`
-    /// back to the main thread.
+    /// It would panic if the service registration form can't be sent.
     pub async fn start(
         self,
         http_tracker_container: Arc<HttpTrackerContainer>,
@@ -176,7 +176,10 @@ impl HttpServer<Stopped> {
             launcher
         });
 
-        let binding = rx_start.await.expect("it should be able to start the service").address;
+        let binding = rx_start
+            .await
+            .map_err(|_| Error::Error("The HTTP tracker could not be started.".to_string()))?
+            .address;
 
         form.send(ServiceRegistration::new(binding, check_fn))
             .expect("it should be able to send service registration");
@@ -19,6 +19,7 @@ use bittorrent_http_protocol::v1::services::peer_ip_resolver::ClientIpSources;
 use bittorrent_tracker_core::announce_handler::{AnnounceHandler, PeersWanted};
 use bittorrent_tracker_core::authentication::service::AuthenticationService;
 use bittorrent_tracker_core::authentication::Key;
+use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
 use bittorrent_tracker_core::whitelist;
 use hyper::StatusCode;
 use torrust_tracker_clock::clock::Time;
@@ -45,6 +46,7 @@ pub async fn handle_without_key(
         Arc<AnnounceHandler>,
         Arc<AuthenticationService>,
         Arc<whitelist::authorization::WhitelistAuthorization>,
+        Arc<ClientAuthorization>,
         Arc<Option<Box<dyn http_tracker_core::statistics::event::sender::Sender>>>,
     )>,
     ExtractRequest(announce_request): ExtractRequest,
@@ -58,6 +60,7 @@ pub async fn handle_without_key(
         &state.2,
         &state.3,
         &state.4,
+        &state.5,
         &announce_request,
         &client_ip_sources,
         None,
@@ -75,6 +78,7 @@ pub async fn handle_with_key(
         Arc<AnnounceHandler>,
         Arc<AuthenticationService>,
         Arc<whitelist::authorization::WhitelistAuthorization>,
+        Arc<ClientAuthorization>,
         Arc<Option<Box<dyn http_tracker_core::statistics::event::sender::Sender>>>,
     )>,
     ExtractRequest(announce_request): ExtractRequest,
@@ -89,6 +93,7 @@ pub async fn handle_with_key(
         &state.2,
         &state.3,
         &state.4,
+        &state.5,
         &announce_request,
         &client_ip_sources,
         Some(key),
@@ -106,6 +111,7 @@ async fn handle(
     announce_handler: &Arc<AnnounceHandler>,
     authentication_service: &Arc<AuthenticationService>,
     whitelist_authorization: &Arc<whitelist::authorization::WhitelistAuthorization>,
+    client_authorization: &Arc<ClientAuthorization>,
     opt_http_stats_event_sender: &Arc<Option<Box<dyn http_tracker_core::statistics::event::sender::Sender>>>,
     announce_request: &Announce,
     client_ip_sources: &ClientIpSources,
@@ -116,6 +122,7 @@ async fn handle(
         announce_handler,
         authentication_service,
         whitelist_authorization,
+        client_authorization,
         opt_http_stats_event_sender,
         announce_request,
         client_ip_sources,
@@ -141,6 +148,7 @@ async fn handle_announce(
     announce_handler: &Arc<AnnounceHandler>,
     authentication_service: &Arc<AuthenticationService>,
     whitelist_authorization: &Arc<whitelist::authorization::WhitelistAuthorization>,
+    client_authorization: &Arc<ClientAuthorization>,
     opt_http_stats_event_sender: &Arc<Option<Box<dyn http_tracker_core::statistics::event::sender::Sender>>>,
     announce_request: &Announce,
     client_ip_sources: &ClientIpSources,
@@ -167,6 +175,11 @@ async fn handle_announce(
         Err(error) => return Err(responses::error::Error::from(error)),
     }
 
+    match client_authorization.authorize(&announce_request.peer_id).await {
+        Ok(()) => (),
+        Err(error) => return Err(responses::error::Error::from(error)),
+    }
+
     let peer_ip = match peer_ip_resolver::invoke(core_config.net.on_reverse_proxy, client_ip_sources) {
         Ok(peer_ip) => peer_ip,
         Err(error) => return Err(responses::error::Error::from(error)),
@@ -185,7 +198,8 @@ async fn handle_announce(
         &mut peer,
         &peers_wanted,
     )
-    .await;
+    .await
+    .map_err(responses::error::Error::from)?;
 
     Ok(announce_data)
 }
@@ -254,6 +268,10 @@ mod tests {
     use bittorrent_tracker_core::announce_handler::AnnounceHandler;
     use bittorrent_tracker_core::authentication::key::repository::in_memory::InMemoryKeyRepository;
     use bittorrent_tracker_core::authentication::service::AuthenticationService;
+    use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
+    use bittorrent_tracker_core::client_policy::manager::ClientPolicyManager;
+    use bittorrent_tracker_core::client_policy::repository::in_memory::InMemoryClientRules;
+    use bittorrent_tracker_core::client_policy::setup::initialize_client_policy_manager;
     use bittorrent_tracker_core::databases::setup::initialize_database;
     use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
     use bittorrent_tracker_core::torrent::repository::persisted::DatabasePersistentTorrentRepository;
@@ -269,6 +287,8 @@ mod tests {
         pub core_config: Arc<Core>,
         pub announce_handler: Arc<AnnounceHandler>,
         pub whitelist_authorization: Arc<WhitelistAuthorization>,
+        pub client_authorization: Arc<ClientAuthorization>,
+        pub client_policy_manager: Arc<ClientPolicyManager>,
         pub authentication_service: Arc<AuthenticationService>,
     }
 
@@ -280,6 +300,10 @@ mod tests {
         initialize_core_tracker_services(&configuration::ephemeral_private())
     }
 
+    fn initialize_public_tracker() -> (CoreTrackerServices, CoreHttpTrackerServices) {
+        initialize_core_tracker_services(&configuration::ephemeral_public())
+    }
+
     fn initialize_listed_tracker() -> (CoreTrackerServices, CoreHttpTrackerServices) {
         initialize_core_tracker_services(&configuration::ephemeral_listed())
     }
@@ -297,6 +321,9 @@ mod tests {
         let database = initialize_database(&config.core);
         let in_memory_whitelist = Arc::new(InMemoryWhitelist::default());
         let whitelist_authorization = Arc::new(WhitelistAuthorization::new(&config.core, &in_memory_whitelist.clone()));
+        let in_memory_client_rules = Arc::new(InMemoryClientRules::default());
+        let client_authorization = Arc::new(ClientAuthorization::new(&in_memory_client_rules));
+        let client_policy_manager = initialize_client_policy_manager(database.clone(), in_memory_client_rules);
         let in_memory_key_repository = Arc::new(InMemoryKeyRepository::default());
         let authentication_service = Arc::new(AuthenticationService::new(&config.core, &in_memory_key_repository));
         let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
@@ -305,6 +332,8 @@ mod tests {
             &config.core,
             &in_memory_torrent_repository,
             &db_torrent_repository,
+            &Arc::default(),
+            &Arc::default(),
         ));
 
         // HTTP stats
@@ -318,6 +347,8 @@ mod tests {
                 core_config,
                 announce_handler,
                 whitelist_authorization,
+                client_authorization,
+                client_policy_manager,
                 authentication_service,
             },
             CoreHttpTrackerServices { http_stats_event_sender },
@@ -373,6 +404,7 @@ mod tests {
                 &core_tracker_services.announce_handler,
                 &core_tracker_services.authentication_service,
                 &core_tracker_services.whitelist_authorization,
+                &core_tracker_services.client_authorization,
                 &http_core_tracker_services.http_stats_event_sender,
                 &sample_announce_request(),
                 &sample_client_ip_sources(),
@@ -400,6 +432,7 @@ mod tests {
                 &core_tracker_services.announce_handler,
                 &core_tracker_services.authentication_service,
                 &core_tracker_services.whitelist_authorization,
+                &core_tracker_services.client_authorization,
                 &http_core_tracker_services.http_stats_event_sender,
                 &sample_announce_request(),
                 &sample_client_ip_sources(),
@@ -429,6 +462,7 @@ mod tests {
                 &core_tracker_services.announce_handler,
                 &core_tracker_services.authentication_service,
                 &core_tracker_services.whitelist_authorization,
+                &core_tracker_services.client_authorization,
                 &http_core_tracker_services.http_stats_event_sender,
                 &announce_request,
                 &sample_client_ip_sources(),
@@ -447,6 +481,47 @@ mod tests {
         }
     }
 
+    mod with_a_client_policy {
+
+        use bittorrent_tracker_core::client_policy::{Action, NewClientRule, PatternKind};
+
+        use super::{initialize_public_tracker, sample_announce_request, sample_client_ip_sources};
+        use crate::servers::http::v1::handlers::announce::handle_announce;
+        use crate::servers::http::v1::handlers::announce::tests::assert_error_response;
+
+        #[tokio::test]
+        async fn it_should_fail_when_the_client_is_denied() {
+            let (core_tracker_services, http_core_tracker_services) = initialize_public_tracker();
+
+            core_tracker_services
+                .client_policy_manager
+                .add_rule(NewClientRule {
+                    action: Action::Deny,
+                    kind: PatternKind::Prefix,
+                    pattern: "-qB".to_string(),
+                })
+                .await
+                .unwrap();
+
+            let response = handle_announce(
+                &core_tracker_services.core_config,
+                &core_tracker_services.announce_handler,
+                &core_tracker_services.authentication_service,
+                &core_tracker_services.whitelist_authorization,
+                &core_tracker_services.client_authorization,
+                &http_core_tracker_services.http_stats_event_sender,
+                &sample_announce_request(),
+                &sample_client_ip_sources(),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+            assert_error_response(&response, "Tracker error: The client:");
+            assert_error_response(&response, "is not allowed");
+        }
+    }
+
     mod with_tracker_on_reverse_proxy {
 
         use bittorrent_http_protocol::v1::services::peer_ip_resolver::ClientIpSources;
@@ -469,6 +544,7 @@ mod tests {
                 &core_tracker_services.announce_handler,
                 &core_tracker_services.authentication_service,
                 &core_tracker_services.whitelist_authorization,
+                &core_tracker_services.client_authorization,
                 &http_core_tracker_services.http_stats_event_sender,
                 &sample_announce_request(),
                 &client_ip_sources,
@@ -506,6 +582,7 @@ mod tests {
                 &core_tracker_services.announce_handler,
                 &core_tracker_services.authentication_service,
                 &core_tracker_services.whitelist_authorization,
+                &core_tracker_services.client_authorization,
                 &http_core_tracker_services.http_stats_event_sender,
                 &sample_announce_request(),
                 &client_ip_sources,
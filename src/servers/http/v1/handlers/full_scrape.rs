@@ -0,0 +1,117 @@
+//! Axum [`handlers`](axum#handlers) for the full scrape dump.
+//!
+//! The HTTP tracker serves the latest [full scrape dump](crate::packages::full_scrape)
+//! when the `http_path` option of the `[full_scrape]` configuration section is
+//! set. For example, with `http_path = "/full_scrape"`:
+//!
+//! ```text
+//! http://0.0.0.0:7070/full_scrape
+//! http://0.0.0.0:7070/full_scrape?format=json
+//! ```
+//!
+//! The dump is sent as a gzipped file, with the `Content-Type: application/gzip`
+//! header and the file name in the `Content-Disposition` header. It's not sent
+//! with the `Content-Encoding: gzip` header, so HTTP clients don't decompress
+//! it transparently and the `ETag` always matches the bytes sent.
+//! Responses include the `ETag` and `Last-Modified` headers, and the handler
+//! replies with `304 Not Modified` to conditional requests (`If-None-Match`
+//! and `If-Modified-Since`) when the dump did not change.
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+
+use crate::packages::full_scrape::{Compressed, Format, Repository};
+
+/// The URL query parameters for the full scrape dump.
+#[derive(Deserialize, Debug)]
+pub struct QueryParams {
+    /// The dump format: `bencode` (default) or `json`.
+    pub format: Option<String>,
+}
+
+/// It handles the request to get the full scrape dump.
+///
+/// It returns:
+///
+/// - `200` with the gzipped dump.
+/// - `304` if the client already has the latest dump.
+/// - `400` if the format is not valid.
+/// - `503` if the first dump has not been generated yet.
+#[allow(clippy::unused_async)]
+pub async fn handler(
+    State(repository): State<Arc<Repository>>,
+    Query(params): Query<QueryParams>,
+    headers: HeaderMap,
+) -> Response {
+    let format = match params.format.as_deref() {
+        None | Some("bencode") => Format::Bencode,
+        Some("json") => Format::Json,
+        Some(format) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("invalid format param \"{format}\", expected one of: bencode, json"),
+            )
+                .into_response()
+        }
+    };
+
+    let Some(dump) = repository.latest() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "full scrape not generated yet").into_response();
+    };
+
+    let compressed = dump.get(format);
+
+    let validators = [
+        (header::ETAG, header_value(&compressed.etag)),
+        (
+            header::LAST_MODIFIED,
+            header_value(&httpdate::fmt_http_date(dump.last_modified)),
+        ),
+    ];
+
+    if is_not_modified(&headers, compressed, dump.last_modified) {
+        return (StatusCode::NOT_MODIFIED, validators).into_response();
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("application/gzip")),
+            (
+                header::CONTENT_DISPOSITION,
+                header_value(&format!("attachment; filename=\"{}\"", format.file_name())),
+            ),
+        ],
+        validators,
+        compressed.bytes.clone(),
+    )
+        .into_response()
+}
+
+fn header_value(value: &str) -> HeaderValue {
+    HeaderValue::from_str(value).expect("entity tags, HTTP dates and file names should be valid header values")
+}
+
+/// It evaluates the conditional request headers. As in RFC 9110,
+/// `If-Modified-Since` is ignored when `If-None-Match` is present.
+fn is_not_modified(headers: &HeaderMap, compressed: &Compressed, last_modified: SystemTime) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        let Ok(if_none_match) = if_none_match.to_str() else {
+            return false;
+        };
+
+        return if_none_match
+            .split(',')
+            .map(|etag| etag.trim().trim_start_matches("W/"))
+            .any(|etag| etag == "*" || etag == compressed.etag);
+    }
+
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|if_modified_since| if_modified_since.to_str().ok())
+        .and_then(|if_modified_since| httpdate::parse_http_date(if_modified_since).ok())
+        .is_some_and(|if_modified_since| last_modified <= if_modified_since)
+}
@@ -4,5 +4,6 @@
 //! more information about the HTTP tracker.
 pub mod announce;
 pub mod common;
+pub mod full_scrape;
 pub mod health_check;
 pub mod scrape;
@@ -21,7 +21,7 @@ use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use tower_http::LatencyUnit;
 use tracing::{instrument, Level, Span};
 
-use super::handlers::{announce, health_check, scrape};
+use super::handlers::{announce, full_scrape, health_check, scrape};
 use crate::container::HttpTrackerContainer;
 use crate::servers::http::HTTP_TRACKER_LOG_TARGET;
 use crate::servers::logging::Latency;
@@ -32,7 +32,7 @@ use crate::servers::logging::Latency;
 /// > info. The tracker could use the connection info to get the client IP.
 #[instrument(skip(http_tracker_container, server_socket_addr))]
 pub fn router(http_tracker_container: Arc<HttpTrackerContainer>, server_socket_addr: SocketAddr) -> Router {
-    Router::new()
+    let mut router = Router::new()
         // Health check
         .route("/health_check", get(health_check::handler))
         // Announce request
@@ -43,6 +43,7 @@ pub fn router(http_tracker_container: Arc<HttpTrackerContainer>, server_socket_a
                 http_tracker_container.announce_handler.clone(),
                 http_tracker_container.authentication_service.clone(),
                 http_tracker_container.whitelist_authorization.clone(),
+                http_tracker_container.client_authorization.clone(),
                 http_tracker_container.http_stats_event_sender.clone(),
             )),
         )
@@ -53,6 +54,7 @@ pub fn router(http_tracker_container: Arc<HttpTrackerContainer>, server_socket_a
                 http_tracker_container.announce_handler.clone(),
                 http_tracker_container.authentication_service.clone(),
                 http_tracker_container.whitelist_authorization.clone(),
+                http_tracker_container.client_authorization.clone(),
                 http_tracker_container.http_stats_event_sender.clone(),
             )),
         )
@@ -74,7 +76,21 @@ pub fn router(http_tracker_container: Arc<HttpTrackerContainer>, server_socket_a
                 http_tracker_container.authentication_service.clone(),
                 http_tracker_container.http_stats_event_sender.clone(),
             )),
-        )
+        );
+
+    // Full scrape dump
+    if let Some(http_path) = http_tracker_container
+        .full_scrape_config
+        .as_ref()
+        .and_then(|config| config.http_path.as_ref())
+    {
+        router = router.route(
+            http_path,
+            get(full_scrape::handler).with_state(http_tracker_container.full_scrape_repository.clone()),
+        );
+    }
+
+    router
         // Add extension to get the client IP from the connection info
         .layer(SecureClientIpSource::ConnectInfo.into_extension())
         .layer(CompressionLayer::new())
@@ -3,7 +3,7 @@
 //! The service is responsible for handling the `announce` requests.
 //!
 //! It delegates the `announce` logic to the [`AnnounceHandler`] and it returns
-//! the [`AnnounceData`].
+//! the [`AnnounceData`], or the [`AnnounceError`] if the peer is rejected.
 //!
 //! It also sends an [`http_tracker_core::statistics::event::Event`]
 //! because events are specific for the HTTP tracker.
@@ -12,6 +12,7 @@ use std::sync::Arc;
 
 use bittorrent_primitives::info_hash::InfoHash;
 use bittorrent_tracker_core::announce_handler::{AnnounceHandler, PeersWanted};
+use bittorrent_tracker_core::error::AnnounceError;
 use torrust_tracker_primitives::core::AnnounceData;
 use torrust_tracker_primitives::peer;
 
@@ -27,17 +28,22 @@ use crate::packages::http_tracker_core;
 /// > **NOTICE**: as the HTTP tracker does not requires a connection request
 /// > like the UDP tracker, the number of TCP connections is incremented for
 /// > each `announce` request.
+///
+/// # Errors
+///
+/// Will return an [`AnnounceError`] if the [`AnnounceHandler`] rejects the
+/// peer. No statistics event is sent in that case.
 pub async fn invoke(
     announce_handler: Arc<AnnounceHandler>,
     opt_http_stats_event_sender: Arc<Option<Box<dyn http_tracker_core::statistics::event::sender::Sender>>>,
     info_hash: InfoHash,
     peer: &mut peer::Peer,
     peers_wanted: &PeersWanted,
-) -> AnnounceData {
+) -> Result<AnnounceData, AnnounceError> {
     let original_peer_ip = peer.peer_addr.ip();
 
     // The tracker could change the original peer ip
-    let announce_data = announce_handler.announce(&info_hash, peer, &original_peer_ip, peers_wanted);
+    let announce_data = announce_handler.announce(&info_hash, peer, &original_peer_ip, peers_wanted)?;
 
     if let Some(http_stats_event_sender) = opt_http_stats_event_sender.as_deref() {
         match original_peer_ip {
@@ -54,7 +60,7 @@ pub async fn invoke(
         }
     }
 
-    announce_data
+    Ok(announce_data)
 }
 
 #[cfg(test)]
@@ -92,6 +98,8 @@ mod tests {
             &config.core,
             &in_memory_torrent_repository,
             &db_torrent_repository,
+            &Arc::default(),
+            &Arc::default(),
         ));
 
         // HTTP stats
@@ -181,6 +189,8 @@ mod tests {
                 &config.core,
                 &in_memory_torrent_repository,
                 &db_torrent_repository,
+                &Arc::default(),
+                &Arc::default(),
             ))
         }
 
@@ -197,7 +207,8 @@ mod tests {
                 &mut peer,
                 &PeersWanted::AsManyAsPossible,
             )
-            .await;
+            .await
+            .unwrap();
 
             let expected_announce_data = AnnounceData {
                 peers: vec![],
@@ -234,7 +245,8 @@ mod tests {
                 &mut peer,
                 &PeersWanted::AsManyAsPossible,
             )
-            .await;
+            .await
+            .unwrap();
         }
 
         fn tracker_with_an_ipv6_external_ip() -> Arc<AnnounceHandler> {
@@ -279,7 +291,8 @@ mod tests {
                 &mut peer,
                 &PeersWanted::AsManyAsPossible,
             )
-            .await;
+            .await
+            .unwrap();
         }
 
         #[tokio::test]
@@ -305,7 +318,8 @@ mod tests {
                 &mut peer,
                 &PeersWanted::AsManyAsPossible,
             )
-            .await;
+            .await
+            .unwrap();
         }
     }
 }
@@ -111,6 +111,8 @@ mod tests {
             &config.core,
             &in_memory_torrent_repository,
             &db_torrent_repository,
+            &Arc::default(),
+            &Arc::default(),
         ));
         let scrape_handler = Arc::new(ScrapeHandler::new(&whitelist_authorization, &in_memory_torrent_repository));
 
@@ -183,7 +185,9 @@ mod tests {
             // Announce a new peer to force scrape data to contain not zeroed data
             let mut peer = sample_peer();
             let original_peer_ip = peer.ip();
-            announce_handler.announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::AsManyAsPossible);
+            announce_handler
+                .announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::AsManyAsPossible)
+                .unwrap();
 
             let scrape_data = invoke(&scrape_handler, &http_stats_event_sender, &info_hashes, &original_peer_ip).await;
 
@@ -268,7 +272,9 @@ mod tests {
             // Announce a new peer to force scrape data to contain not zeroed data
             let mut peer = sample_peer();
             let original_peer_ip = peer.ip();
-            announce_handler.announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::AsManyAsPossible);
+            announce_handler
+                .announce(&info_hash, &mut peer, &original_peer_ip, &PeersWanted::AsManyAsPossible)
+                .unwrap();
 
             let scrape_data = fake(&http_stats_event_sender, &info_hashes, &original_peer_ip).await;
 
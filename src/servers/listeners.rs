@@ -0,0 +1,564 @@
+//! Listeners. The UDP and HTTP tracker servers running in the application.
+//!
+//! The [`Listeners`] registry owns the running tracker servers. The
+//! listeners configured in the `[[udp_trackers]]` and `[[http_trackers]]`
+//! sections are added to it when the application starts, and more can be
+//! added later, for example, through the tracker API.
+//!
+//! Listeners are never removed from the registry. A stopped listener is kept
+//! so that it can be restarted later, but it's listed without a local
+//! address: its socket is closed. That includes the listeners whose server
+//! has stopped by itself, for example, on shutdown. It's restarted on the
+//! same address it was bound to, even when the original bind address uses
+//! port `0`.
+//!
+//! HTTP listeners can be restarted with a new TLS certificate.
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use derive_more::Display;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use torrust_tracker_configuration::{HttpTracker, TslConfig, UdpTracker};
+
+use crate::bootstrap::jobs::make_rust_tls;
+use crate::container::{AppContainer, HttpTrackerContainer, UdpTrackerContainer};
+use crate::servers::http::server::{HttpServer, Launcher, RunningHttpServer};
+use crate::servers::registar::Registar;
+use crate::servers::udp::server::spawner::Spawner;
+use crate::servers::udp::server::states::RunningUdpServer;
+use crate::servers::udp::server::Server;
+
+pub type ListenerId = u64;
+
+/// The protocol of the tracker listener.
+#[derive(Copy, Clone, Debug, Display, PartialEq, Eq)]
+pub enum Protocol {
+    #[display("udp")]
+    Udp,
+    #[display("http")]
+    Http,
+}
+
+/// The state of the tracker listener.
+#[derive(Copy, Clone, Debug, Display, PartialEq, Eq)]
+pub enum State {
+    #[display("running")]
+    Running,
+    #[display("stopped")]
+    Stopped,
+}
+
+/// The public information about a tracker listener.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListenerInfo {
+    pub id: ListenerId,
+    pub protocol: Protocol,
+    /// The address in the listener configuration.
+    pub bind_address: SocketAddr,
+    /// The address the listener is bound to. It's `None` when it's not
+    /// running.
+    pub local_address: Option<SocketAddr>,
+    pub tls: bool,
+    pub state: State,
+}
+
+/// Errors that can occur managing the tracker listeners.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Listener not found: {id}")]
+    NotFound { id: ListenerId },
+
+    #[error("Listener {id} is already stopped")]
+    AlreadyStopped { id: ListenerId },
+
+    #[error("UDP listeners cannot be used for private trackers")]
+    UdpInPrivateMode,
+
+    #[error("UDP listeners do not support TLS")]
+    TlsNotSupported,
+
+    #[error("Invalid TLS configuration: {source}")]
+    InvalidTls { source: crate::bootstrap::jobs::Error },
+
+    #[error("Unable to start the listener on {bind_address}: {reason}")]
+    UnableToStart { bind_address: SocketAddr, reason: String },
+
+    #[error("Unable to stop listener {id}: {reason}")]
+    UnableToStop { id: ListenerId, reason: String },
+}
+
+enum ListenerConfig {
+    Udp(Arc<UdpTracker>),
+    Http(Arc<HttpTracker>),
+}
+
+enum RunningServer {
+    Udp(RunningUdpServer),
+    Http(RunningHttpServer),
+}
+
+impl RunningServer {
+    fn local_address(&self) -> SocketAddr {
+        match self {
+            RunningServer::Udp(server) => server.state.local_addr,
+            RunningServer::Http(server) => server.state.binding,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        match self {
+            RunningServer::Udp(server) => server.state.task.is_finished(),
+            RunningServer::Http(server) => server.state.task.is_finished(),
+        }
+    }
+}
+
+struct Listener {
+    config: ListenerConfig,
+    /// The address the listener was last bound to. The listener is restarted
+    /// on it.
+    local_address: Option<SocketAddr>,
+    server: Option<RunningServer>,
+}
+
+impl Listener {
+    fn info(&self, id: ListenerId) -> ListenerInfo {
+        let (protocol, bind_address, tls) = match &self.config {
+            ListenerConfig::Udp(config) => (Protocol::Udp, config.bind_address, false),
+            ListenerConfig::Http(config) => (Protocol::Http, config.bind_address, config.tsl_config.is_some()),
+        };
+
+        let (state, local_address) = match &self.server {
+            Some(server) if !server.is_finished() => (State::Running, self.local_address),
+            _ => (State::Stopped, None),
+        };
+
+        ListenerInfo {
+            id,
+            protocol,
+            bind_address,
+            local_address,
+            tls,
+            state,
+        }
+    }
+}
+
+/// The registry of tracker listeners.
+///
+/// Operations on listeners are serialized: starting or stopping a listener
+/// waits for the previous operation to finish.
+pub struct Listeners {
+    app_container: Arc<AppContainer>,
+    registar: Registar,
+    entries: Mutex<BTreeMap<ListenerId, Listener>>,
+}
+
+impl Listeners {
+    /// The started listeners are registered for the health check in the
+    /// `registar`.
+    #[must_use]
+    pub fn new(app_container: &Arc<AppContainer>, registar: &Registar) -> Self {
+        Self {
+            app_container: app_container.clone(),
+            registar: registar.clone(),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// It returns the information of all the listeners ordered by ID.
+    pub async fn list(&self) -> Vec<ListenerInfo> {
+        self.entries
+            .lock()
+            .await
+            .iter()
+            .map(|(id, listener)| listener.info(*id))
+            .collect()
+    }
+
+    /// It starts a new UDP tracker listener.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the tracker is private or the listener can't be
+    /// started.
+    pub async fn add_udp(&self, config: UdpTracker) -> Result<ListenerInfo, Error> {
+        self.add(ListenerConfig::Udp(Arc::new(config))).await
+    }
+
+    /// It starts a new HTTP tracker listener.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the TLS configuration is not valid or the
+    /// listener can't be started.
+    pub async fn add_http(&self, config: HttpTracker) -> Result<ListenerInfo, Error> {
+        self.add(ListenerConfig::Http(Arc::new(config))).await
+    }
+
+    /// It stops a running listener gracefully.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the listener does not exist, it's not running
+    /// or it can't be stopped.
+    pub async fn stop(&self, id: ListenerId) -> Result<ListenerInfo, Error> {
+        let mut listeners = self.entries.lock().await;
+
+        let listener = listeners.get_mut(&id).ok_or(Error::NotFound { id })?;
+
+        let server = match listener.server.take() {
+            Some(server) if !server.is_finished() => server,
+            finished => {
+                // A listener whose task has finished is already reported as
+                // stopped, so it's kept as it is.
+                listener.server = finished;
+                return Err(Error::AlreadyStopped { id });
+            }
+        };
+
+        self.stop_server(id, server).await?;
+
+        Ok(listener.info(id))
+    }
+
+    /// It restarts a listener. If it's not running, it's just started.
+    ///
+    /// HTTP listeners can be restarted with a new TLS configuration. The
+    /// current one is kept when `tsl_config` is `None`. The new TLS
+    /// configuration is checked before stopping the listener, so the listener
+    /// keeps running if it's not valid. The new configuration is only kept if
+    /// the listener starts with it. Otherwise, the listener is started again
+    /// with its previous configuration.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the listener does not exist, the new TLS
+    /// configuration is not valid or the listener can't be restarted.
+    pub async fn restart(&self, id: ListenerId, tsl_config: Option<TslConfig>) -> Result<ListenerInfo, Error> {
+        let mut listeners = self.entries.lock().await;
+
+        let listener = listeners.get_mut(&id).ok_or(Error::NotFound { id })?;
+
+        let new_config = match tsl_config {
+            Some(tsl_config) => {
+                let ListenerConfig::Http(config) = &listener.config else {
+                    return Err(Error::TlsNotSupported);
+                };
+
+                let mut config = (**config).clone();
+                config.tsl_config = Some(tsl_config);
+
+                check_tls(&config).await?;
+
+                Some(ListenerConfig::Http(Arc::new(config)))
+            }
+            None => None,
+        };
+
+        let was_running = match listener.server.take() {
+            Some(server) if !server.is_finished() => {
+                self.stop_server(id, server).await?;
+                true
+            }
+            _ => false,
+        };
+
+        let bind_to = listener.local_address.unwrap_or_else(|| listener.config.bind_address());
+
+        let Some(new_config) = new_config else {
+            let server = self.start_server(&listener.config, bind_to).await?;
+
+            listener.local_address = Some(server.local_address());
+            listener.server = Some(server);
+
+            return Ok(listener.info(id));
+        };
+
+        match self.start_server(&new_config, bind_to).await {
+            Ok(server) => {
+                listener.config = new_config;
+                listener.local_address = Some(server.local_address());
+                listener.server = Some(server);
+
+                Ok(listener.info(id))
+            }
+            Err(err) => {
+                if was_running {
+                    match self.start_server(&listener.config, bind_to).await {
+                        Ok(server) => listener.server = Some(server),
+                        Err(rollback_err) => {
+                            tracing::error!("Unable to restart listener {id} with its previous configuration: {rollback_err}");
+                        }
+                    }
+                }
+
+                Err(err)
+            }
+        }
+    }
+
+    /// It waits until all the running listeners have finished and removes
+    /// them from the `registar`.
+    ///
+    /// Listeners stop by themselves when the process receives a shutdown
+    /// signal, so this should only be called after that.
+    pub async fn wait_for_shutdown(&self) {
+        let mut listeners = self.entries.lock().await;
+
+        for (id, listener) in listeners.iter_mut() {
+            let Some(server) = listener.server.take() else {
+                continue;
+            };
+
+            let local_address = server.local_address();
+
+            let joined = match server {
+                RunningServer::Udp(server) => server.state.task.await.map(drop),
+                RunningServer::Http(server) => server.state.task.await.map(drop),
+            };
+
+            self.registar.remove(&local_address).await;
+
+            if let Err(err) = joined {
+                tracing::error!("Listener {id} did not shutdown cleanly: {err}");
+            }
+        }
+    }
+
+    async fn add(&self, config: ListenerConfig) -> Result<ListenerInfo, Error> {
+        let mut listeners = self.entries.lock().await;
+
+        let server = self.start_server(&config, config.bind_address()).await?;
+
+        let id = listeners.last_key_value().map_or(1, |(id, _)| id + 1);
+
+        let listener = Listener {
+            config,
+            local_address: Some(server.local_address()),
+            server: Some(server),
+        };
+
+        let info = listener.info(id);
+
+        listeners.insert(id, listener);
+
+        Ok(info)
+    }
+
+    async fn start_server(&self, config: &ListenerConfig, bind_to: SocketAddr) -> Result<RunningServer, Error> {
+        match config {
+            ListenerConfig::Udp(config) => {
+                if self.app_container.core_config.private {
+                    return Err(Error::UdpInPrivateMode);
+                }
+
+                let udp_tracker_container = Arc::new(UdpTrackerContainer::from_app_container(config, &self.app_container));
+
+                let server = Server::new(Spawner::new(bind_to))
+                    .start(udp_tracker_container, self.registar.give_form(), config.cookie_lifetime)
+                    .await
+                    .map_err(|err| Error::UnableToStart {
+                        bind_address: bind_to,
+                        reason: err.to_string(),
+                    })?;
+
+                Ok(RunningServer::Udp(server))
+            }
+            ListenerConfig::Http(config) => {
+                let tls = check_tls(config).await?;
+
+                let http_tracker_container = Arc::new(HttpTrackerContainer::from_app_container(config, &self.app_container));
+
+                let server = HttpServer::new(Launcher::new(bind_to, tls))
+                    .start(http_tracker_container, self.registar.give_form())
+                    .await
+                    .map_err(|err| Error::UnableToStart {
+                        bind_address: bind_to,
+                        reason: format!("{err:?}"),
+                    })?;
+
+                Ok(RunningServer::Http(server))
+            }
+        }
+    }
+
+    /// Stopping a server consumes it. It only fails when the server task has
+    /// already finished, so the server is not running after this call either
+    /// way and it's always removed from the `registar`.
+    async fn stop_server(&self, id: ListenerId, server: RunningServer) -> Result<(), Error> {
+        let local_address = server.local_address();
+
+        let stopped = match server {
+            RunningServer::Udp(server) => server.stop().await.map(|_| ()).map_err(|err| err.to_string()),
+            RunningServer::Http(server) => server.stop().await.map(|_| ()).map_err(|err| format!("{err:?}")),
+        };
+
+        self.registar.remove(&local_address).await;
+
+        stopped.map_err(|reason| Error::UnableToStop { id, reason })
+    }
+}
+
+impl ListenerConfig {
+    fn bind_address(&self) -> SocketAddr {
+        match self {
+            ListenerConfig::Udp(config) => config.bind_address,
+            ListenerConfig::Http(config) => config.bind_address,
+        }
+    }
+}
+
+async fn check_tls(config: &HttpTracker) -> Result<Option<axum_server::tls_rustls::RustlsConfig>, Error> {
+    make_rust_tls(&config.tsl_config)
+        .await
+        .transpose()
+        .map_err(|source| Error::InvalidTls { source })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use torrust_tracker_configuration::TslConfig;
+    use torrust_tracker_test_helpers::configuration::ephemeral_public;
+
+    use super::{Error, ListenerInfo, Listeners, Protocol, RunningServer, State};
+    use crate::bootstrap::app::{initialize_app_container, initialize_global_services};
+    use crate::servers::registar::Registar;
+
+    fn listeners() -> (Listeners, Arc<torrust_tracker_configuration::Configuration>) {
+        let cfg = Arc::new(ephemeral_public());
+
+        initialize_global_services(&cfg);
+
+        let app_container = Arc::new(initialize_app_container(&cfg));
+
+        (Listeners::new(&app_container, &Registar::default()), cfg)
+    }
+
+    #[tokio::test]
+    async fn it_should_start_and_list_new_listeners() {
+        let (listeners, cfg) = listeners();
+
+        let udp = listeners.add_udp(cfg.udp_trackers.clone().unwrap()[0].clone()).await.unwrap();
+        let http = listeners
+            .add_http(cfg.http_trackers.clone().unwrap()[0].clone())
+            .await
+            .unwrap();
+
+        assert_eq!(udp.protocol, Protocol::Udp);
+        assert_eq!(http.protocol, Protocol::Http);
+        assert_eq!(listeners.list().await, vec![udp, http]);
+    }
+
+    #[tokio::test]
+    async fn it_should_stop_a_listener_and_restart_it_on_the_same_address() {
+        let (listeners, cfg) = listeners();
+
+        let started = listeners
+            .add_http(cfg.http_trackers.clone().unwrap()[0].clone())
+            .await
+            .unwrap();
+
+        let stopped = listeners.stop(started.id).await.unwrap();
+
+        assert_eq!(stopped.state, State::Stopped);
+        assert_eq!(stopped.local_address, None);
+
+        let restarted = listeners.restart(started.id, None).await.unwrap();
+
+        assert_eq!(restarted.state, State::Running);
+        assert_eq!(restarted.local_address, started.local_address);
+
+        listeners.stop(started.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_should_list_a_stopped_listener_without_its_closed_socket() {
+        let (listeners, cfg) = listeners();
+
+        let started = listeners.add_udp(cfg.udp_trackers.clone().unwrap()[0].clone()).await.unwrap();
+
+        listeners.stop(started.id).await.unwrap();
+
+        assert_eq!(
+            listeners.list().await,
+            vec![ListenerInfo {
+                local_address: None,
+                state: State::Stopped,
+                ..started
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_stop_a_stopped_listener() {
+        let (listeners, cfg) = listeners();
+
+        let started = listeners.add_udp(cfg.udp_trackers.clone().unwrap()[0].clone()).await.unwrap();
+
+        listeners.stop(started.id).await.unwrap();
+
+        assert!(matches!(
+            listeners.stop(started.id).await,
+            Err(Error::AlreadyStopped { id: _ })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_should_report_a_listener_whose_task_has_finished_as_already_stopped() {
+        let (listeners, cfg) = listeners();
+
+        let started = listeners.add_udp(cfg.udp_trackers.clone().unwrap()[0].clone()).await.unwrap();
+
+        if let Some(RunningServer::Udp(server)) = &listeners.entries.lock().await[&started.id].server {
+            server.state.task.abort();
+        }
+
+        while listeners.list().await[0].state == State::Running {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(listeners.list().await[0].local_address, None);
+
+        assert!(matches!(
+            listeners.stop(started.id).await,
+            Err(Error::AlreadyStopped { id: _ })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_the_listener_running_when_the_new_tls_configuration_is_not_valid() {
+        let (listeners, cfg) = listeners();
+
+        let started = listeners
+            .add_http(cfg.http_trackers.clone().unwrap()[0].clone())
+            .await
+            .unwrap();
+
+        let result = listeners
+            .restart(
+                started.id,
+                Some(TslConfig {
+                    ssl_cert_path: "missing.crt".into(),
+                    ssl_key_path: "missing.key".into(),
+                }),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::InvalidTls { source: _ })));
+        assert_eq!(listeners.list().await, vec![started.clone()]);
+
+        listeners.stop(started.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_should_fail_restarting_a_listener_that_does_not_exist() {
+        let (listeners, _cfg) = listeners();
+
+        assert!(matches!(listeners.restart(1, None).await, Err(Error::NotFound { id: 1 })));
+    }
+}
@@ -3,6 +3,7 @@ pub mod apis;
 pub mod custom_axum_server;
 pub mod health_check_api;
 pub mod http;
+pub mod listeners;
 pub mod logging;
 pub mod registar;
 pub mod signals;
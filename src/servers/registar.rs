@@ -83,15 +83,23 @@ impl Registar {
     async fn insert(&self, rx: tokio::sync::oneshot::Receiver<ServiceRegistration>) {
         tracing::debug!("Waiting for the started service to send registration data ...");
 
-        let service_registration = rx
-            .await
-            .expect("it should receive the service registration from the started service");
+        let Ok(service_registration) = rx.await else {
+            tracing::debug!("The service was not started, there is nothing to register");
+            return;
+        };
 
         let mut mutex = self.registry.lock().await;
 
         mutex.insert(service_registration.binding, service_registration);
     }
 
+    /// Removes the listing of a stopped service from the registry.
+    pub async fn remove(&self, binding: &SocketAddr) {
+        let mut mutex = self.registry.lock().await;
+
+        mutex.remove(binding);
+    }
+
     /// Returns the [`ServiceRegistry`] of services
     #[must_use]
     pub fn entries(&self) -> ServiceRegistry {
@@ -12,10 +12,10 @@ use aquatic_udp_protocol::{
 };
 use bittorrent_primitives::info_hash::InfoHash;
 use bittorrent_tracker_core::announce_handler::{AnnounceHandler, PeersWanted};
+use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
 use bittorrent_tracker_core::scrape_handler::ScrapeHandler;
 use bittorrent_tracker_core::whitelist;
 use torrust_tracker_clock::clock::Time as _;
-use torrust_tracker_configuration::Core;
 use tracing::{instrument, Level};
 use uuid::Uuid;
 use zerocopy::network_endian::I32;
@@ -151,9 +151,9 @@ pub async fn handle_request(
             handle_announce(
                 remote_addr,
                 &announce_request,
-                &udp_tracker_container.core_config,
                 &udp_tracker_container.announce_handler,
                 &udp_tracker_container.whitelist_authorization,
+                &udp_tracker_container.client_authorization,
                 &udp_tracker_container.udp_stats_event_sender,
                 cookie_time_values.valid_range,
             )
@@ -221,13 +221,13 @@ pub async fn handle_connect(
 ///
 /// If a error happens in the `handle_announce` function, it will just return the  `ServerError`.
 #[allow(clippy::too_many_arguments)]
-#[instrument(fields(transaction_id, connection_id, info_hash), skip(announce_handler, whitelist_authorization, opt_udp_stats_event_sender), ret(level = Level::TRACE))]
+#[instrument(fields(transaction_id, connection_id, info_hash), skip(announce_handler, whitelist_authorization, client_authorization, opt_udp_stats_event_sender), ret(level = Level::TRACE))]
 pub async fn handle_announce(
     remote_addr: SocketAddr,
     request: &AnnounceRequest,
-    core_config: &Arc<Core>,
     announce_handler: &Arc<AnnounceHandler>,
     whitelist_authorization: &Arc<whitelist::authorization::WhitelistAuthorization>,
+    client_authorization: &Arc<ClientAuthorization>,
     opt_udp_stats_event_sender: &Arc<Option<Box<dyn udp_tracker_core::statistics::event::sender::Sender>>>,
     cookie_valid_range: Range<f64>,
 ) -> Result<Response, (Error, TransactionId)> {
@@ -257,10 +257,23 @@ pub async fn handle_announce(
         })
         .map_err(|e| (e, request.transaction_id))?;
 
+    client_authorization
+        .authorize(&request.peer_id)
+        .await
+        .map_err(|e| Error::TrackerError {
+            source: (Arc::new(e) as Arc<dyn std::error::Error + Send + Sync>).into(),
+        })
+        .map_err(|e| (e, request.transaction_id))?;
+
     let mut peer = peer_builder::from_request(request, &remote_client_ip);
     let peers_wanted: PeersWanted = i32::from(request.peers_wanted.0).into();
 
-    let response = announce_handler.announce(&info_hash, &mut peer, &remote_client_ip, &peers_wanted);
+    let response = announce_handler
+        .announce(&info_hash, &mut peer, &remote_client_ip, &peers_wanted)
+        .map_err(|e| Error::TrackerError {
+            source: (Arc::new(e) as Arc<dyn std::error::Error + Send + Sync>).into(),
+        })
+        .map_err(|e| (e, request.transaction_id))?;
 
     if let Some(udp_stats_event_sender) = opt_udp_stats_event_sender.as_deref() {
         match remote_client_ip {
@@ -282,7 +295,7 @@ pub async fn handle_announce(
         let announce_response = AnnounceResponse {
             fixed: AnnounceResponseFixedData {
                 transaction_id: request.transaction_id,
-                announce_interval: AnnounceInterval(I32::new(i64::from(core_config.announce_policy.interval) as i32)),
+                announce_interval: AnnounceInterval(I32::new(i64::from(response.policy.interval) as i32)),
                 leechers: NumberOfPeers(I32::new(i64::from(response.stats.incomplete) as i32)),
                 seeders: NumberOfPeers(I32::new(i64::from(response.stats.complete) as i32)),
             },
@@ -307,7 +320,7 @@ pub async fn handle_announce(
         let announce_response = AnnounceResponse {
             fixed: AnnounceResponseFixedData {
                 transaction_id: request.transaction_id,
-                announce_interval: AnnounceInterval(I32::new(i64::from(core_config.announce_policy.interval) as i32)),
+                announce_interval: AnnounceInterval(I32::new(i64::from(response.policy.interval) as i32)),
                 leechers: NumberOfPeers(I32::new(i64::from(response.stats.incomplete) as i32)),
                 seeders: NumberOfPeers(I32::new(i64::from(response.stats.complete) as i32)),
             },
@@ -486,6 +499,10 @@ mod tests {
 
     use aquatic_udp_protocol::{NumberOfBytes, PeerId};
     use bittorrent_tracker_core::announce_handler::AnnounceHandler;
+    use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
+    use bittorrent_tracker_core::client_policy::manager::ClientPolicyManager;
+    use bittorrent_tracker_core::client_policy::repository::in_memory::InMemoryClientRules;
+    use bittorrent_tracker_core::client_policy::setup::initialize_client_policy_manager;
     use bittorrent_tracker_core::databases::setup::initialize_database;
     use bittorrent_tracker_core::scrape_handler::ScrapeHandler;
     use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
@@ -512,6 +529,8 @@ mod tests {
         pub in_memory_torrent_repository: Arc<InMemoryTorrentRepository>,
         pub in_memory_whitelist: Arc<InMemoryWhitelist>,
         pub whitelist_authorization: Arc<whitelist::authorization::WhitelistAuthorization>,
+        pub client_authorization: Arc<ClientAuthorization>,
+        pub client_policy_manager: Arc<ClientPolicyManager>,
     }
 
     struct CoreUdpTrackerServices {
@@ -539,12 +558,17 @@ mod tests {
         let database = initialize_database(&config.core);
         let in_memory_whitelist = Arc::new(InMemoryWhitelist::default());
         let whitelist_authorization = Arc::new(WhitelistAuthorization::new(&config.core, &in_memory_whitelist.clone()));
+        let in_memory_client_rules = Arc::new(InMemoryClientRules::default());
+        let client_authorization = Arc::new(ClientAuthorization::new(&in_memory_client_rules));
+        let client_policy_manager = initialize_client_policy_manager(database.clone(), in_memory_client_rules);
         let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
         let db_torrent_repository = Arc::new(DatabasePersistentTorrentRepository::new(&database));
         let announce_handler = Arc::new(AnnounceHandler::new(
             &config.core,
             &in_memory_torrent_repository,
             &db_torrent_repository,
+            &Arc::default(),
+            &Arc::default(),
         ));
         let scrape_handler = Arc::new(ScrapeHandler::new(&whitelist_authorization, &in_memory_torrent_repository));
 
@@ -559,6 +583,8 @@ mod tests {
                 in_memory_torrent_repository,
                 in_memory_whitelist,
                 whitelist_authorization,
+                client_authorization,
+                client_policy_manager,
             },
             CoreUdpTrackerServices { udp_stats_event_sender },
         )
@@ -893,16 +919,20 @@ mod tests {
                 PeerId as AquaticPeerId, Response, ResponsePeer,
             };
             use bittorrent_tracker_core::announce_handler::AnnounceHandler;
+            use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
+            use bittorrent_tracker_core::client_policy::{Action, NewClientRule, PatternKind};
             use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
             use bittorrent_tracker_core::whitelist;
             use mockall::predicate::eq;
-            use torrust_tracker_configuration::Core;
+            use torrust_tracker_configuration::AdaptiveInterval;
+            use torrust_tracker_test_helpers::configuration;
 
             use crate::packages::{self, udp_tracker_core};
             use crate::servers::udp::connection_cookie::make;
             use crate::servers::udp::handlers::tests::announce_request::AnnounceRequestBuilder;
             use crate::servers::udp::handlers::tests::{
-                gen_remote_fingerprint, initialize_core_tracker_services_for_default_tracker_configuration,
+                gen_remote_fingerprint, initialize_core_tracker_services,
+                initialize_core_tracker_services_for_default_tracker_configuration,
                 initialize_core_tracker_services_for_public_tracker, sample_cookie_valid_range, sample_ipv4_socket_address,
                 sample_issue_time, MockUdpStatsEventSender, TorrentPeerBuilder,
             };
@@ -930,9 +960,9 @@ mod tests {
                 handle_announce(
                     remote_addr,
                     &request,
-                    &core_tracker_services.core_config,
                     &core_tracker_services.announce_handler,
                     &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
                     &core_udp_tracker_services.udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -951,6 +981,48 @@ mod tests {
                 assert_eq!(peers[0], Arc::new(expected_peer));
             }
 
+            #[tokio::test]
+            async fn an_announce_from_a_denied_client_should_fail_and_not_add_the_peer() {
+                let (core_tracker_services, core_udp_tracker_services) = initialize_core_tracker_services_for_public_tracker();
+
+                core_tracker_services
+                    .client_policy_manager
+                    .add_rule(NewClientRule {
+                        action: Action::Deny,
+                        kind: PatternKind::Prefix,
+                        pattern: "-TR2".to_string(),
+                    })
+                    .await
+                    .unwrap();
+
+                let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080);
+                let info_hash = AquaticInfoHash([0u8; 20]);
+
+                let request = AnnounceRequestBuilder::default()
+                    .with_connection_id(make(gen_remote_fingerprint(&remote_addr), sample_issue_time()).unwrap())
+                    .with_info_hash(info_hash)
+                    .with_peer_id(AquaticPeerId(*b"-TR2940-xxxxxxxxxxxx"))
+                    .into();
+
+                let (error, _transaction_id) = handle_announce(
+                    remote_addr,
+                    &request,
+                    &core_tracker_services.announce_handler,
+                    &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
+                    &core_udp_tracker_services.udp_stats_event_sender,
+                    sample_cookie_valid_range(),
+                )
+                .await
+                .unwrap_err();
+
+                assert!(error.to_string().contains("The client: Transmission 2.94, is not allowed"));
+                assert!(core_tracker_services
+                    .in_memory_torrent_repository
+                    .get_torrent_peers(&info_hash.0.into())
+                    .is_empty());
+            }
+
             #[tokio::test]
             async fn the_announced_peer_should_not_be_included_in_the_response() {
                 let (core_tracker_services, core_udp_tracker_services) = initialize_core_tracker_services_for_public_tracker();
@@ -964,9 +1036,9 @@ mod tests {
                 let response = handle_announce(
                     remote_addr,
                     &request,
-                    &core_tracker_services.core_config,
                     &core_tracker_services.announce_handler,
                     &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
                     &core_udp_tracker_services.udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -988,6 +1060,43 @@ mod tests {
                 );
             }
 
+            #[tokio::test]
+            async fn the_announce_interval_should_be_the_adaptive_one_when_it_is_enabled() {
+                let mut config = configuration::ephemeral_public();
+
+                config.core.adaptive_interval = Some(AdaptiveInterval {
+                    min_interval: 30,
+                    ..AdaptiveInterval::default()
+                });
+
+                let (core_tracker_services, core_udp_tracker_services) = initialize_core_tracker_services(&config);
+
+                let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)), 8080);
+
+                let request = AnnounceRequestBuilder::default()
+                    .with_connection_id(make(gen_remote_fingerprint(&remote_addr), sample_issue_time()).unwrap())
+                    .into();
+
+                let response = handle_announce(
+                    remote_addr,
+                    &request,
+                    &core_tracker_services.announce_handler,
+                    &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
+                    &core_udp_tracker_services.udp_stats_event_sender,
+                    sample_cookie_valid_range(),
+                )
+                .await
+                .unwrap();
+
+                let Response::AnnounceIpv4(response) = response else {
+                    panic!("it should be an IPv4 announce response");
+                };
+
+                // The swarm was empty, so the peer gets the shortest interval.
+                assert_eq!(response.fixed.announce_interval, AnnounceInterval(30i32.into()));
+            }
+
             #[tokio::test]
             async fn the_tracker_should_always_use_the_remote_client_ip_but_not_the_port_in_the_udp_request_header_instead_of_the_peer_address_in_the_announce_request(
             ) {
@@ -1017,9 +1126,9 @@ mod tests {
                 handle_announce(
                     remote_addr,
                     &request,
-                    &core_tracker_services.core_config,
                     &core_tracker_services.announce_handler,
                     &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
                     &core_udp_tracker_services.udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -1050,9 +1159,9 @@ mod tests {
             }
 
             async fn announce_a_new_peer_using_ipv4(
-                core_config: Arc<Core>,
                 announce_handler: Arc<AnnounceHandler>,
                 whitelist_authorization: Arc<whitelist::authorization::WhitelistAuthorization>,
+                client_authorization: Arc<ClientAuthorization>,
             ) -> Response {
                 let (udp_stats_event_sender, _udp_stats_repository) =
                     packages::udp_tracker_core::statistics::setup::factory(false);
@@ -1066,9 +1175,9 @@ mod tests {
                 handle_announce(
                     remote_addr,
                     &request,
-                    &core_config,
                     &announce_handler,
                     &whitelist_authorization,
+                    &client_authorization,
                     &udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -1083,9 +1192,9 @@ mod tests {
                 add_a_torrent_peer_using_ipv6(&core_tracker_services.in_memory_torrent_repository);
 
                 let response = announce_a_new_peer_using_ipv4(
-                    core_tracker_services.core_config.clone(),
                     core_tracker_services.announce_handler.clone(),
                     core_tracker_services.whitelist_authorization,
+                    core_tracker_services.client_authorization,
                 )
                 .await;
 
@@ -1115,9 +1224,9 @@ mod tests {
                 handle_announce(
                     sample_ipv4_socket_address(),
                     &AnnounceRequestBuilder::default().into(),
-                    &core_tracker_services.core_config,
                     &core_tracker_services.announce_handler,
                     &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
                     &udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -1162,9 +1271,9 @@ mod tests {
                     handle_announce(
                         remote_addr,
                         &request,
-                        &core_tracker_services.core_config,
                         &core_tracker_services.announce_handler,
                         &core_tracker_services.whitelist_authorization,
+                        &core_tracker_services.client_authorization,
                         &core_udp_tracker_services.udp_stats_event_sender,
                         sample_cookie_valid_range(),
                     )
@@ -1198,10 +1307,10 @@ mod tests {
                 PeerId as AquaticPeerId, Response, ResponsePeer,
             };
             use bittorrent_tracker_core::announce_handler::AnnounceHandler;
+            use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
             use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
             use bittorrent_tracker_core::whitelist;
             use mockall::predicate::eq;
-            use torrust_tracker_configuration::Core;
 
             use crate::packages::{self, udp_tracker_core};
             use crate::servers::udp::connection_cookie::make;
@@ -1236,9 +1345,9 @@ mod tests {
                 handle_announce(
                     remote_addr,
                     &request,
-                    &core_tracker_services.core_config,
                     &core_tracker_services.announce_handler,
                     &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
                     &core_udp_tracker_services.udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -1273,9 +1382,9 @@ mod tests {
                 let response = handle_announce(
                     remote_addr,
                     &request,
-                    &core_tracker_services.core_config,
                     &core_tracker_services.announce_handler,
                     &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
                     &core_udp_tracker_services.udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -1326,9 +1435,9 @@ mod tests {
                 handle_announce(
                     remote_addr,
                     &request,
-                    &core_tracker_services.core_config,
                     &core_tracker_services.announce_handler,
                     &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
                     &core_udp_tracker_services.udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -1359,9 +1468,9 @@ mod tests {
             }
 
             async fn announce_a_new_peer_using_ipv6(
-                core_config: Arc<Core>,
                 announce_handler: Arc<AnnounceHandler>,
                 whitelist_authorization: Arc<whitelist::authorization::WhitelistAuthorization>,
+                client_authorization: Arc<ClientAuthorization>,
             ) -> Response {
                 let (udp_stats_event_sender, _udp_stats_repository) =
                     packages::udp_tracker_core::statistics::setup::factory(false);
@@ -1378,9 +1487,9 @@ mod tests {
                 handle_announce(
                     remote_addr,
                     &request,
-                    &core_config,
                     &announce_handler,
                     &whitelist_authorization,
+                    &client_authorization,
                     &udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -1395,9 +1504,9 @@ mod tests {
                 add_a_torrent_peer_using_ipv4(&core_tracker_services.in_memory_torrent_repository);
 
                 let response = announce_a_new_peer_using_ipv6(
-                    core_tracker_services.core_config.clone(),
                     core_tracker_services.announce_handler.clone(),
                     core_tracker_services.whitelist_authorization,
+                    core_tracker_services.client_authorization,
                 )
                 .await;
 
@@ -1433,9 +1542,9 @@ mod tests {
                 handle_announce(
                     remote_addr,
                     &announce_request,
-                    &core_tracker_services.core_config,
                     &core_tracker_services.announce_handler,
                     &core_tracker_services.whitelist_authorization,
+                    &core_tracker_services.client_authorization,
                     &udp_stats_event_sender,
                     sample_cookie_valid_range(),
                 )
@@ -1450,6 +1559,7 @@ mod tests {
 
                 use aquatic_udp_protocol::{InfoHash as AquaticInfoHash, PeerId as AquaticPeerId};
                 use bittorrent_tracker_core::announce_handler::AnnounceHandler;
+                use bittorrent_tracker_core::client_policy::authorization::ClientAuthorization;
                 use bittorrent_tracker_core::databases::setup::initialize_database;
                 use bittorrent_tracker_core::torrent::repository::in_memory::InMemoryTorrentRepository;
                 use bittorrent_tracker_core::torrent::repository::persisted::DatabasePersistentTorrentRepository;
@@ -1474,6 +1584,7 @@ mod tests {
                     let in_memory_whitelist = Arc::new(InMemoryWhitelist::default());
                     let whitelist_authorization =
                         Arc::new(WhitelistAuthorization::new(&config.core, &in_memory_whitelist.clone()));
+                    let client_authorization = Arc::new(ClientAuthorization::new(&Arc::default()));
                     let in_memory_torrent_repository = Arc::new(InMemoryTorrentRepository::default());
                     let db_torrent_repository = Arc::new(DatabasePersistentTorrentRepository::new(&database));
 
@@ -1490,6 +1601,8 @@ mod tests {
                         &config.core,
                         &in_memory_torrent_repository,
                         &db_torrent_repository,
+                        &Arc::default(),
+                        &Arc::default(),
                     ));
 
                     let loopback_ipv4 = Ipv4Addr::new(127, 0, 0, 1);
@@ -1517,9 +1630,9 @@ mod tests {
                     handle_announce(
                         remote_addr,
                         &request,
-                        &core_config,
                         &announce_handler,
                         &whitelist_authorization,
+                        &client_authorization,
                         &udp_stats_event_sender,
                         sample_cookie_valid_range(),
                     )
@@ -15,9 +15,14 @@
 //! This two level filtering is to avoid false positives. It has the advantage
 //! of being fast by using a Counting Bloom Filter and not having false
 //! negatives at the cost of increasing the memory usage.
+//!
+//! An [`Event::IpBanned`] is published the first time the errors of an ip
+//! exceed the limit.
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::sync::Arc;
 
+use bittorrent_tracker_core::event::{Bus, Event};
 use bloom::{CountingBloomFilter, ASMS};
 use tokio::time::Instant;
 
@@ -28,22 +33,29 @@ pub struct BanService {
     fuzzy_error_counter: CountingBloomFilter,
     accurate_error_counter: HashMap<IpAddr, u32>,
     last_connection_id_errors_reset: Instant,
+    events: Arc<Bus>,
 }
 
 impl BanService {
     #[must_use]
-    pub fn new(max_connection_id_errors_per_ip: u32) -> Self {
+    pub fn new(max_connection_id_errors_per_ip: u32, events: &Arc<Bus>) -> Self {
         Self {
             max_connection_id_errors_per_ip,
             fuzzy_error_counter: CountingBloomFilter::with_rate(4, 0.01, 100),
             accurate_error_counter: HashMap::new(),
             last_connection_id_errors_reset: tokio::time::Instant::now(),
+            events: events.clone(),
         }
     }
 
     pub fn increase_counter(&mut self, ip: &IpAddr) {
         self.fuzzy_error_counter.insert(&ip.to_string());
-        *self.accurate_error_counter.entry(*ip).or_insert(0) += 1;
+        let count = self.accurate_error_counter.entry(*ip).or_insert(0);
+        *count += 1;
+
+        if *count == self.max_connection_id_errors_per_ip + 1 {
+            self.events.publish(Event::IpBanned { ip: *ip });
+        }
     }
 
     #[must_use]
@@ -91,12 +103,15 @@ impl BanService {
 #[cfg(test)]
 mod tests {
     use std::net::IpAddr;
+    use std::sync::Arc;
+
+    use bittorrent_tracker_core::event::{Bus, Event};
 
     use super::BanService;
 
     /// Sample service with one day ban duration.
     fn ban_service(counter_limit: u32) -> BanService {
-        BanService::new(counter_limit)
+        BanService::new(counter_limit, &Arc::default())
     }
 
     #[test]
@@ -147,4 +162,24 @@ mod tests {
 
         assert_eq!(ban_service.get_estimate_count(&ip), 0);
     }
+
+    #[test]
+    fn it_should_publish_the_ban_only_when_the_counter_exceeds_the_limit_for_the_first_time() {
+        let events = Arc::new(Bus::default());
+        let mut receiver = events.subscribe();
+
+        let mut ban_service = BanService::new(1, &events);
+
+        let ip: IpAddr = "127.0.0.2".parse().unwrap();
+
+        ban_service.increase_counter(&ip); // Counter = 1
+
+        assert!(receiver.try_recv().is_err());
+
+        ban_service.increase_counter(&ip); // Counter = 2
+        ban_service.increase_counter(&ip); // Counter = 3
+
+        assert_eq!(receiver.try_recv().unwrap(), Event::IpBanned { ip });
+        assert!(receiver.try_recv().is_err());
+    }
 }
@@ -56,11 +56,12 @@ impl Server<Stopped> {
     ///
     /// # Errors
     ///
-    /// Will return `Err` if UDP can't bind to given bind address.
+    /// Will return `Err` if UDP can't bind to given bind address, that is,
+    /// when the bound socket address is not received from the service.
     ///
     /// # Panics
     ///
-    /// It panics if unable to receive the bound socket address from service.
+    /// It panics if unable to send the service registration.
     #[instrument(skip(self, udp_tracker_container, form), err, ret(Display, level = Level::INFO))]
     pub async fn start(
         self,
@@ -79,7 +80,10 @@ impl Server<Stopped> {
             .spawner
             .spawn_launcher(udp_tracker_container, cookie_lifetime, tx_start, rx_halt);
 
-        let local_addr = rx_start.await.expect("it should be able to start the service").address;
+        let local_addr = rx_start
+            .await
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "the UDP tracker could not be started"))?
+            .address;
 
         form.send(ServiceRegistration::new(local_addr, Launcher::check))
             .expect("it should be able to send service registration");
@@ -1,5 +1,6 @@
 use aquatic_udp_protocol::TransactionId;
 use bittorrent_primitives::info_hash::InfoHash;
+use torrust_tracker_configuration::GeoIp;
 
 #[allow(dead_code)]
 pub fn invalid_info_hashes() -> Vec<String> {
@@ -27,3 +28,20 @@ pub fn random_transaction_id() -> TransactionId {
     let random_value = rand::Rng::random::<i32>(&mut rand::rng());
     TransactionId::new(random_value)
 }
+
+/// Returns the `GeoIP` configuration for the test database. It contains:
+///
+/// - `81.2.69.0/24`: GB, ASN `20712`.
+/// - `89.160.20.0/24`: SE, ASN `29518`.
+/// - `126.0.0.0/8`: JP, ASN `17676`.
+#[allow(dead_code)]
+pub fn geoip_configuration() -> GeoIp {
+    GeoIp {
+        database_path: concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/packages/tracker-core/tests/fixtures/geoip-test.mmdb"
+        )
+        .into(),
+        ..GeoIp::default()
+    }
+}
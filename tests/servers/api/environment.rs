@@ -6,12 +6,14 @@ use bittorrent_tracker_core::authentication::service::AuthenticationService;
 use bittorrent_tracker_core::databases::Database;
 use bittorrent_tracker_core::whitelist::repository::in_memory::InMemoryWhitelist;
 use futures::executor::block_on;
-use torrust_tracker_api_client::connection_info::{ConnectionInfo, Origin};
+use torrust_tracker_api_client::connection_info::{ConnectionInfo, Origin, TokenLocation};
 use torrust_tracker_configuration::Configuration;
 use torrust_tracker_lib::bootstrap::app::{initialize_app_container, initialize_global_services};
 use torrust_tracker_lib::bootstrap::jobs::make_rust_tls;
 use torrust_tracker_lib::container::HttpApiContainer;
+use torrust_tracker_lib::servers::apis::audit::AuditLog;
 use torrust_tracker_lib::servers::apis::server::{ApiServer, Launcher, Running, Stopped};
+use torrust_tracker_lib::servers::listeners::Listeners;
 use torrust_tracker_lib::servers::registar::Registar;
 use torrust_tracker_primitives::peer;
 
@@ -46,7 +48,9 @@ impl Environment<Stopped> {
     pub fn new(configuration: &Arc<Configuration>) -> Self {
         initialize_global_services(configuration);
 
-        let app_container = initialize_app_container(configuration);
+        let app_container = Arc::new(initialize_app_container(configuration));
+
+        let registar = Registar::default();
 
         let http_api_config = Arc::new(configuration.http_api.clone().expect("missing API configuration"));
 
@@ -60,11 +64,21 @@ impl Environment<Stopped> {
             http_api_config: http_api_config.clone(),
             core_config: app_container.core_config.clone(),
             in_memory_torrent_repository: app_container.in_memory_torrent_repository.clone(),
+            announce_handler: app_container.announce_handler.clone(),
+            torrents_manager: app_container.torrents_manager.clone(),
             keys_handler: app_container.keys_handler.clone(),
             whitelist_manager: app_container.whitelist_manager.clone(),
+            client_policy_manager: app_container.client_policy_manager.clone(),
             ban_service: app_container.ban_service.clone(),
             http_stats_repository: app_container.http_stats_repository.clone(),
             udp_stats_repository: app_container.udp_stats_repository.clone(),
+            access_tokens: app_container.http_api_access_tokens.clone(),
+            config_reloader: app_container.config_reloader.clone(),
+            listeners: Arc::new(Listeners::new(&app_container, &registar)),
+            audit_log: Arc::new(AuditLog::new(&http_api_config.audit_log_path)),
+            events: app_container.events.clone(),
+            metrics_history: app_container.metrics_history.clone(),
+            geoip: app_container.geoip.clone(),
         });
 
         Self {
@@ -74,13 +88,13 @@ impl Environment<Stopped> {
             authentication_service: app_container.authentication_service.clone(),
             in_memory_whitelist: app_container.in_memory_whitelist.clone(),
 
-            registar: Registar::default(),
+            registar,
             server,
         }
     }
 
     pub async fn start(self) -> Environment<Running> {
-        let access_tokens = Arc::new(self.http_api_container.http_api_config.access_tokens.clone());
+        let access_tokens = self.http_api_container.access_tokens.clone();
 
         Environment {
             http_api_container: self.http_api_container.clone(),
@@ -122,7 +136,13 @@ impl Environment<Running> {
 
         ConnectionInfo {
             origin,
-            api_token: self.http_api_container.http_api_config.access_tokens.get("admin").cloned(),
+            api_token: self
+                .http_api_container
+                .http_api_config
+                .access_tokens
+                .get("admin")
+                .map(|token| token.token().to_string()),
+            token_location: TokenLocation::QueryParam,
         }
     }
 
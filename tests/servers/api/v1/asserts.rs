@@ -73,6 +73,12 @@ pub async fn assert_unprocessable_content(response: Response, text: &str) {
     assert!(response.text().await.unwrap().contains(text));
 }
 
+pub async fn assert_forbidden(response: Response, body: &str) {
+    assert_eq!(response.status(), 403);
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/plain; charset=utf-8");
+    assert_eq!(response.text().await.unwrap(), body);
+}
+
 pub async fn assert_not_found(response: Response) {
     assert_eq!(response.status(), 404);
     // todo: missing header in the response
@@ -86,6 +92,12 @@ pub async fn assert_torrent_not_known(response: Response) {
     assert_eq!(response.text().await.unwrap(), "\"torrent not known\"");
 }
 
+pub async fn assert_peer_not_known(response: Response) {
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/json");
+    assert_eq!(response.text().await.unwrap(), "\"peer not known\"");
+}
+
 pub async fn assert_invalid_infohash_param(response: Response, invalid_infohash: &str) {
     assert_bad_request(
         response,
@@ -1,12 +1,39 @@
+use reqwest::header::{HeaderMap, AUTHORIZATION};
 use torrust_tracker_api_client::common::http::{Query, QueryParam};
+use torrust_tracker_api_client::connection_info::ConnectionInfo;
 use torrust_tracker_api_client::v1::client::{headers_with_request_id, Client};
+use torrust_tracker_configuration::{Configuration, Scope};
 use torrust_tracker_test_helpers::configuration;
 use uuid::Uuid;
 
 use crate::common::logging::{self, logs_contains_a_line_with};
-use crate::servers::api::v1::asserts::{assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::v1::asserts::{assert_forbidden, assert_token_not_valid, assert_unauthorized};
 use crate::servers::api::Started;
 
+const MONITORING_TOKEN: &str = "MyMonitoringToken";
+
+fn configuration_with_a_monitoring_token() -> Configuration {
+    let mut configuration = configuration::ephemeral();
+
+    configuration.http_api.as_mut().unwrap().add_scoped_token(
+        "monitoring",
+        MONITORING_TOKEN,
+        &[Scope::StatsRead, Scope::TorrentsRead],
+    );
+
+    configuration
+}
+
+fn connection_with_monitoring_token(env: &Started) -> ConnectionInfo {
+    ConnectionInfo::authenticated(env.get_connection_info().origin, MONITORING_TOKEN)
+}
+
+fn headers_with_bearer_token(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, format!("Bearer {token}").parse().unwrap());
+    headers
+}
+
 #[tokio::test]
 async fn should_authenticate_requests_by_using_a_token_query_param() {
     logging::setup();
@@ -122,3 +149,143 @@ async fn should_allow_the_token_query_param_to_be_at_any_position_in_the_url_que
 
     env.stop().await;
 }
+
+#[tokio::test]
+async fn should_allow_scoped_tokens_to_use_the_endpoints_in_their_scopes() {
+    logging::setup();
+
+    let env = Started::new(&configuration_with_a_monitoring_token().into()).await;
+
+    let client = Client::new(connection_with_monitoring_token(&env));
+
+    assert_eq!(client.get_tracker_statistics(None).await.status(), 200);
+    assert_eq!(client.get_torrents(Query::empty(), None).await.status(), 200);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_scoped_tokens_to_use_the_endpoints_out_of_their_scopes() {
+    logging::setup();
+
+    let env = Started::new(&configuration_with_a_monitoring_token().into()).await;
+
+    let client = Client::new(connection_with_monitoring_token(&env));
+
+    assert_forbidden(
+        client.generate_auth_key(60, None).await,
+        "Forbidden: the token is missing the \"keys:write\" scope",
+    )
+    .await;
+
+    assert_forbidden(
+        client
+            .whitelist_a_torrent("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d", None) // DevSkim: ignore DS173237
+            .await,
+        "Forbidden: the token is missing the \"whitelist:write\" scope",
+    )
+    .await;
+
+    assert_forbidden(
+        client
+            .delete_torrent("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d", false, None) // DevSkim: ignore DS173237
+            .await,
+        "Forbidden: the token is missing the \"torrents:write\" scope",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_plain_tokens_to_use_all_the_endpoints() {
+    logging::setup();
+
+    let env = Started::new(&configuration_with_a_monitoring_token().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    assert_eq!(client.generate_auth_key(60, None).await.status(), 200);
+    assert_eq!(client.reload_whitelist(None).await.status(), 200);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_authenticate_requests_by_using_a_bearer_token_in_the_authorization_header() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let token = env.get_connection_info().api_token.unwrap();
+
+    let response = Client::new(ConnectionInfo::anonymous(env.get_connection_info().origin))
+        .get_request_with_query("stats", Query::default(), Some(headers_with_bearer_token(&token)))
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_authenticate_requests_by_using_a_token_query_param_when_it_is_disabled() {
+    logging::setup();
+
+    let mut configuration = configuration::ephemeral();
+    configuration.http_api.as_mut().unwrap().token_query_param = false;
+
+    let env = Started::new(&configuration.into()).await;
+
+    let token = env.get_connection_info().api_token.unwrap();
+
+    let response = Client::new(env.get_connection_info())
+        .get_request_with_query("stats", Query::params([QueryParam::new("token", &token)].to_vec()), None)
+        .await;
+
+    assert_unauthorized(response).await;
+
+    let response = Client::new(ConnectionInfo::anonymous(env.get_connection_info().origin))
+        .get_request_with_query("stats", Query::default(), Some(headers_with_bearer_token(&token)))
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_authenticate_requests_with_tokens_stored_as_a_sha256_hash() {
+    logging::setup();
+
+    let mut configuration = configuration::ephemeral();
+    let http_api = configuration.http_api.as_mut().unwrap();
+    http_api.token_pepper = Some("MyPepper".to_string());
+    // echo -n "MyPepperMyHashedToken" | sha256sum
+    http_api.add_token(
+        "hashed",
+        "sha256:84dfbde1826f938ae9f0c4cdeabf72deb2ecaf34ce9b83abc6326cc20749cca8",
+    );
+
+    let env = Started::new(&configuration.into()).await;
+
+    let response = Client::new(ConnectionInfo::anonymous(env.get_connection_info().origin))
+        .get_request_with_query("stats", Query::default(), Some(headers_with_bearer_token("MyHashedToken")))
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    let response = Client::new(ConnectionInfo::anonymous(env.get_connection_info().origin))
+        .get_request_with_query(
+            "stats",
+            Query::default(),
+            Some(headers_with_bearer_token(
+                "sha256:84dfbde1826f938ae9f0c4cdeabf72deb2ecaf34ce9b83abc6326cc20749cca8",
+            )),
+        )
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
@@ -0,0 +1,175 @@
+use torrust_tracker_api_client::common::http::{Query, QueryParam};
+use torrust_tracker_api_client::connection_info::ConnectionInfo;
+use torrust_tracker_api_client::v1::client::{headers_with_request_id, Client};
+use torrust_tracker_configuration::{Configuration, Scope};
+use torrust_tracker_lib::servers::apis::audit::Outcome;
+use torrust_tracker_lib::servers::apis::v1::context::audit::resources::AuditRecord;
+use torrust_tracker_test_helpers::configuration;
+use uuid::Uuid;
+
+use crate::common::logging::{self, logs_contains_a_line_with};
+use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
+use crate::servers::api::v1::asserts::{assert_ok, assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::Started;
+
+const MONITORING_TOKEN: &str = "MyMonitoringToken";
+
+fn configuration_with_a_monitoring_token() -> Configuration {
+    let mut configuration = configuration::ephemeral();
+
+    configuration
+        .http_api
+        .as_mut()
+        .unwrap()
+        .add_scoped_token("monitoring", MONITORING_TOKEN, &[Scope::StatsRead]);
+
+    configuration
+}
+
+async fn get_audit_records(env: &Started, params: Query) -> Vec<AuditRecord> {
+    let response = Client::new(env.get_connection_info())
+        .get_audit_records(params, Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/json");
+
+    response.json::<Vec<AuditRecord>>().await.unwrap()
+}
+
+#[tokio::test]
+async fn should_record_the_api_calls_that_change_the_tracker_state() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"; // DevSkim: ignore DS173237
+
+    let response = Client::new(env.get_connection_info())
+        .whitelist_a_torrent(info_hash, Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    assert_ok(response).await;
+
+    let records = get_audit_records(&env, Query::default()).await;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].token_label, "admin");
+    assert_eq!(records[0].client_ip, Some("127.0.0.1".parse().unwrap()));
+    assert_eq!(records[0].action, "POST /api/v1/whitelist/{info_hash}");
+    assert_eq!(records[0].target, format!("/api/v1/whitelist/{info_hash}"));
+    assert_eq!(records[0].status, 200);
+    assert_eq!(records[0].result, Outcome::Success);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_record_the_api_calls_that_only_read_the_tracker_state() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_tracker_statistics(Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    assert!(get_audit_records(&env, Query::default()).await.is_empty());
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_record_the_api_calls_rejected_for_missing_scopes() {
+    logging::setup();
+
+    let env = Started::new(&configuration_with_a_monitoring_token().into()).await;
+
+    let response = Client::new(ConnectionInfo::authenticated(
+        env.get_connection_info().origin,
+        MONITORING_TOKEN,
+    ))
+    .reload_whitelist(Some(headers_with_request_id(Uuid::new_v4())))
+    .await;
+
+    assert_eq!(response.status(), 403);
+
+    let records = get_audit_records(&env, Query::default()).await;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].token_label, "monitoring");
+    assert_eq!(records[0].action, "GET /api/v1/whitelist/reload");
+    assert_eq!(records[0].status, 403);
+    assert_eq!(records[0].result, Outcome::Forbidden);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_paginating_the_audit_records_newest_first() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hashes = [
+        "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d", // DevSkim: ignore DS173237
+        "0b3aea4adc213ce32295be85d3883a63bca25446", // DevSkim: ignore DS173237
+        "2b66980093bc11806fab50cb3cb41835b95a0362", // DevSkim: ignore DS173237
+    ];
+
+    for info_hash in info_hashes {
+        let response = Client::new(env.get_connection_info())
+            .whitelist_a_torrent(info_hash, Some(headers_with_request_id(Uuid::new_v4())))
+            .await;
+
+        assert_ok(response).await;
+    }
+
+    let records = get_audit_records(
+        &env,
+        Query::params([QueryParam::new("offset", "1"), QueryParam::new("limit", "1")].to_vec()),
+    )
+    .await;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].target, format!("/api/v1/whitelist/{}", info_hashes[1]));
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_getting_the_audit_records_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .get_audit_records(Query::default(), Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_no_token(env.get_connection_info().origin))
+        .get_audit_records(Query::default(), Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_unauthorized(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    env.stop().await;
+}
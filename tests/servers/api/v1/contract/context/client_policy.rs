@@ -0,0 +1,184 @@
+use torrust_tracker_api_client::v1::client::{headers_with_request_id, AddClientRuleForm, Client};
+use torrust_tracker_lib::servers::apis::v1::context::client_policy::resources::ClientRule;
+use torrust_tracker_test_helpers::configuration;
+use uuid::Uuid;
+
+use crate::common::logging::{self, logs_contains_a_line_with};
+use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
+use crate::servers::api::v1::asserts::{assert_bad_request, assert_ok, assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::Started;
+
+fn deny_prefix_form(pattern: &str) -> AddClientRuleForm {
+    AddClientRuleForm {
+        action: "deny".to_string(),
+        kind: "prefix".to_string(),
+        pattern: pattern.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn should_allow_adding_a_client_rule() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .add_client_rule(deny_prefix_form("-qB"), Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    let rule = response.json::<ClientRule>().await.unwrap();
+
+    assert_eq!(rule.action, "deny");
+    assert_eq!(rule.kind, "prefix");
+    assert_eq!(rule.pattern, "-qB");
+
+    let response = Client::new(env.get_connection_info())
+        .get_client_rules(Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<Vec<ClientRule>>().await.unwrap(), vec![rule]);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_removing_a_client_rule() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let rule = client
+        .add_client_rule(deny_prefix_form("-qB"), None)
+        .await
+        .json::<ClientRule>()
+        .await
+        .unwrap();
+
+    let response = client.remove_client_rule(rule.id, None).await;
+
+    assert_ok(response).await;
+
+    let response = client.get_client_rules(None).await;
+
+    assert_eq!(response.json::<Vec<ClientRule>>().await.unwrap(), vec![]);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_removing_a_client_rule_that_does_not_exist() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info()).remove_client_rule(1, None).await;
+
+    assert_bad_request(response, "Client rule not found: 1").await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_adding_a_client_rule_with_an_invalid_action() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let mut form = deny_prefix_form("-qB");
+    form.action = "block".to_string();
+
+    let response = Client::new(env.get_connection_info()).add_client_rule(form, None).await;
+
+    assert_bad_request(
+        response,
+        "Invalid client rule action: \"block\", expected \"allow\" or \"deny\"",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_adding_a_client_rule_with_an_invalid_kind() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let mut form = deny_prefix_form("-qB");
+    form.kind = "glob".to_string();
+
+    let response = Client::new(env.get_connection_info()).add_client_rule(form, None).await;
+
+    assert_bad_request(
+        response,
+        "Invalid client rule kind: \"glob\", expected \"prefix\" or \"regex\"",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_adding_a_client_rule_with_an_invalid_pattern() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .add_client_rule(deny_prefix_form("-TR4040-xxxxxxxxxxxxx"), None)
+        .await;
+
+    assert_bad_request(
+        response,
+        "Invalid pattern: -TR4040-xxxxxxxxxxxxx, the prefix is longer than a peer ID",
+    )
+    .await;
+
+    let response = Client::new(env.get_connection_info()).get_client_rules(None).await;
+
+    assert_eq!(response.json::<Vec<ClientRule>>().await.unwrap(), vec![]);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_adding_a_client_rule_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .add_client_rule(deny_prefix_form("-qB"), Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_no_token(env.get_connection_info().origin))
+        .add_client_rule(deny_prefix_form("-qB"), Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_unauthorized(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    env.stop().await;
+}
@@ -0,0 +1,63 @@
+use torrust_tracker_api_client::v1::client::{headers_with_request_id, Client};
+use torrust_tracker_lib::servers::apis::v1::context::config::resources::ReloadReport;
+use torrust_tracker_test_helpers::configuration;
+use uuid::Uuid;
+
+use crate::common::logging::{self, logs_contains_a_line_with};
+use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
+use crate::servers::api::v1::asserts::{assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::Started;
+
+#[tokio::test]
+async fn should_allow_reloading_the_configuration() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .reload_config(Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/json");
+    assert!(response.json::<ReloadReport>().await.is_ok());
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_reloading_the_configuration_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .reload_config(Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_no_token(env.get_connection_info().origin))
+        .reload_config(Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_unauthorized(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    env.stop().await;
+}
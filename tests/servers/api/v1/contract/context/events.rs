@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use reqwest::Response;
+use torrust_tracker_api_client::common::http::{Query, QueryParam};
+use torrust_tracker_api_client::connection_info::ConnectionInfo;
+use torrust_tracker_api_client::v1::client::{headers_with_request_id, Client};
+use torrust_tracker_configuration::Scope;
+use torrust_tracker_test_helpers::configuration;
+use uuid::Uuid;
+
+use crate::common::logging;
+use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
+use crate::servers::api::v1::asserts::{assert_bad_request, assert_forbidden, assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::Started;
+
+const INFO_HASH: &str = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"; // DevSkim: ignore DS173237
+
+fn event_types(types: &[&str]) -> Query {
+    Query::params(types.iter().map(|event_type| QueryParam::new("type", event_type)).collect())
+}
+
+async fn subscribe(env: &Started, params: Query) -> Response {
+    let response = Client::new(env.get_connection_info())
+        .get_events(params, Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/event-stream");
+
+    response
+}
+
+/// It returns the name and the data of the next event in the stream. The
+/// keep-alive comments are skipped.
+async fn next_event(response: &mut Response, buffer: &mut String) -> (String, String) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(end) = buffer.find("\n\n") {
+                let block: String = buffer.drain(..end + 2).collect();
+
+                let field = |name: &str| {
+                    block
+                        .lines()
+                        .find_map(|line| line.strip_prefix(name))
+                        .map(|value| value.trim_start().to_string())
+                };
+
+                if let (Some(event), Some(data)) = (field("event:"), field("data:")) {
+                    return (event, data);
+                }
+
+                continue;
+            }
+
+            let chunk = response.chunk().await.unwrap().expect("the event stream should not end");
+
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    })
+    .await
+    .expect("it should receive an event")
+}
+
+#[tokio::test]
+async fn should_stream_the_whitelist_events() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let mut events = subscribe(&env, Query::default()).await;
+    let mut buffer = String::new();
+
+    Client::new(env.get_connection_info())
+        .whitelist_a_torrent(INFO_HASH, Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    let (event, data) = next_event(&mut events, &mut buffer).await;
+
+    assert_eq!(event, "torrent_whitelisted");
+    assert_eq!(data, format!(r#"{{"type":"torrent_whitelisted","info_hash":"{INFO_HASH}"}}"#));
+
+    drop(events);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_only_stream_the_events_of_the_requested_types() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let mut events = subscribe(&env, event_types(&["key_added", "key_removed"])).await;
+    let mut buffer = String::new();
+
+    let client = Client::new(env.get_connection_info());
+
+    client
+        .whitelist_a_torrent(INFO_HASH, Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+    client
+        .generate_auth_key(60, Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    let (event, data) = next_event(&mut events, &mut buffer).await;
+
+    assert_eq!(event, "key_added");
+    assert_eq!(data, r#"{"type":"key_added"}"#);
+
+    drop(events);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_when_the_event_type_is_not_known() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_events(
+            event_types(&["torrent_updated"]),
+            Some(headers_with_request_id(Uuid::new_v4())),
+        )
+        .await;
+
+    assert_bad_request(response, "Invalid URL: unknown event type: torrent_updated").await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_subscribing_to_the_events_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(connection_with_no_token(env.get_connection_info().origin))
+        .get_events(Query::default(), Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    assert_unauthorized(response).await;
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .get_events(Query::default(), Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_subscribing_to_the_events_without_the_events_read_scope() {
+    logging::setup();
+
+    let mut configuration = configuration::ephemeral();
+
+    configuration
+        .http_api
+        .as_mut()
+        .unwrap()
+        .add_scoped_token("monitoring", "MyMonitoringToken", &[Scope::StatsRead]);
+
+    let env = Started::new(&configuration.into()).await;
+
+    let response = Client::new(ConnectionInfo::bearer(env.get_connection_info().origin, "MyMonitoringToken"))
+        .get_events(Query::default(), Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    assert_forbidden(response, "Forbidden: the token is missing the \"events:read\" scope").await;
+
+    env.stop().await;
+}
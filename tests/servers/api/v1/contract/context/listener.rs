@@ -0,0 +1,185 @@
+use torrust_tracker_api_client::v1::client::{headers_with_request_id, AddListenerForm, Client, RestartListenerForm};
+use torrust_tracker_lib::servers::apis::v1::context::listener::resources::Listener;
+use torrust_tracker_test_helpers::configuration;
+use uuid::Uuid;
+
+use crate::common::logging::{self, logs_contains_a_line_with};
+use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
+use crate::servers::api::v1::asserts::{assert_bad_request, assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::Started;
+
+fn add_http_listener_form() -> AddListenerForm {
+    AddListenerForm {
+        protocol: "http".to_string(),
+        bind_address: "127.0.0.1:0".to_string(),
+        ssl_cert_path: None,
+        ssl_key_path: None,
+    }
+}
+
+#[tokio::test]
+async fn should_allow_adding_a_new_listener() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .add_listener(add_http_listener_form(), Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    let listener = response.json::<Listener>().await.unwrap();
+
+    assert_eq!(listener.protocol, "http");
+    assert_eq!(listener.state, "running");
+    assert_ne!(listener.local_address, Some(listener.bind_address.clone()));
+
+    let response = Client::new(env.get_connection_info())
+        .get_listeners(Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.json::<Vec<Listener>>().await.unwrap(), vec![listener]);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_stopping_and_restarting_a_listener() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let listener = client
+        .add_listener(add_http_listener_form(), None)
+        .await
+        .json::<Listener>()
+        .await
+        .unwrap();
+
+    let response = client.stop_listener(listener.id, None).await;
+
+    assert_eq!(response.status(), 200);
+
+    let stopped = response.json::<Listener>().await.unwrap();
+
+    assert_eq!(stopped.state, "stopped");
+    assert_eq!(stopped.local_address, None);
+
+    let response = client
+        .restart_listener(listener.id, RestartListenerForm::default(), None)
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    let restarted = response.json::<Listener>().await.unwrap();
+
+    assert_eq!(restarted.state, "running");
+    assert_eq!(restarted.local_address, listener.local_address);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_stopping_a_listener_that_does_not_exist() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info()).stop_listener(1, None).await;
+
+    assert_bad_request(response, "Listener not found: 1").await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_adding_a_listener_with_an_invalid_protocol() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let mut form = add_http_listener_form();
+    form.protocol = "ftp".to_string();
+
+    let response = Client::new(env.get_connection_info()).add_listener(form, None).await;
+
+    assert_bad_request(response, "Invalid listener protocol: \"ftp\", expected \"udp\" or \"http\"").await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_restarting_a_listener_with_an_invalid_tls_certificate() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let client = Client::new(env.get_connection_info());
+
+    let listener = client
+        .add_listener(add_http_listener_form(), None)
+        .await
+        .json::<Listener>()
+        .await
+        .unwrap();
+
+    let response = client
+        .restart_listener(
+            listener.id,
+            RestartListenerForm {
+                ssl_cert_path: Some("missing.crt".to_string()),
+                ssl_key_path: Some("missing.key".to_string()),
+            },
+            None,
+        )
+        .await;
+
+    assert_bad_request(response, "Invalid TLS configuration: tls config missing").await;
+
+    let response = client.get_listeners(None).await;
+
+    assert_eq!(response.json::<Vec<Listener>>().await.unwrap(), vec![listener]);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_listing_the_listeners_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .get_listeners(Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_no_token(env.get_connection_info().origin))
+        .stop_listener(1, Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_unauthorized(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    env.stop().await;
+}
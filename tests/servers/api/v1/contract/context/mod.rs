@@ -1,5 +1,11 @@
+pub mod audit;
 pub mod auth_key;
+pub mod client_policy;
+pub mod config;
+pub mod events;
 pub mod health_check;
+pub mod listener;
+pub mod openapi;
 pub mod stats;
 pub mod torrent;
 pub mod whitelist;
@@ -0,0 +1,156 @@
+use reqwest::header::AUTHORIZATION;
+use reqwest::Method;
+use serde_json::Value;
+use torrust_tracker_api_client::connection_info::ConnectionInfo;
+use torrust_tracker_api_client::v1::client::{headers_with_request_id, Client};
+use torrust_tracker_configuration::{Configuration, Scope};
+use torrust_tracker_lib::servers::apis::v1::context::openapi::spec::OPENAPI_VERSION;
+use torrust_tracker_test_helpers::configuration;
+use uuid::Uuid;
+
+use crate::common::logging;
+use crate::servers::api::Started;
+
+async fn get_spec(env: &Started) -> Value {
+    let response = Client::new(ConnectionInfo::anonymous(env.get_connection_info().origin))
+        .get_openapi_spec(Some(headers_with_request_id(Uuid::new_v4())))
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/json");
+
+    response.json::<Value>().await.unwrap()
+}
+
+/// All the documented operations: method, path and required scope, if any.
+fn operations(spec: &Value) -> Vec<(Method, String, Option<Scope>)> {
+    let mut operations = vec![];
+
+    for (path, path_item) in spec["paths"].as_object().unwrap() {
+        for (method, operation) in path_item.as_object().unwrap() {
+            let scope = operation
+                .get("x-required-scope")
+                .map(|scope| serde_json::from_value::<Scope>(scope.clone()).unwrap());
+
+            operations.push((method.to_uppercase().parse().unwrap(), path.clone(), scope));
+        }
+    }
+
+    operations
+}
+
+/// It replaces the path params with valid sample values.
+fn sample_path(path: &str) -> String {
+    path.replace("{info_hash}", "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d") // DevSkim: ignore DS173237
+        .replace("{peer_id}", "2d7142343431302d2a64465a3844484944704579")
+        .replace("{seconds_valid_or_key}", "60")
+        .replace("{id}", "999999")
+}
+
+/// One token per scope, labeled with the scope name.
+fn configuration_with_a_token_per_scope(scopes: &[Scope]) -> Configuration {
+    let mut configuration = configuration::ephemeral();
+
+    for scope in scopes {
+        configuration
+            .http_api
+            .as_mut()
+            .unwrap()
+            .add_scoped_token(&scope.to_string(), &format!("TokenFor-{scope}"), &[*scope]);
+    }
+
+    configuration
+}
+
+#[tokio::test]
+async fn should_serve_the_openapi_specification_without_a_token() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let spec = get_spec(&env).await;
+
+    assert_eq!(spec["openapi"], OPENAPI_VERSION);
+
+    for schema in ["Torrent", "ListItem", "Stats", "AuthKey", "ActionStatus"] {
+        assert!(
+            spec["components"]["schemas"][schema].is_object(),
+            "Expected the spec to contain the {schema} schema"
+        );
+    }
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_document_operations_that_are_served_with_the_documented_scope() {
+    logging::setup();
+
+    let spec = {
+        let env = Started::new(&configuration::ephemeral().into()).await;
+        let spec = get_spec(&env).await;
+        env.stop().await;
+        spec
+    };
+
+    let operations = operations(&spec);
+
+    let scopes: Vec<Scope> = operations.iter().filter_map(|(_, _, scope)| *scope).collect();
+
+    // A new tracker for each operation, because some of them change the
+    // tracker state. For example, reloading the configuration resets the
+    // tokens.
+    for (method, path, scope) in operations {
+        let env = Started::new(&configuration_with_a_token_per_scope(&scopes).into()).await;
+
+        let mut headers = headers_with_request_id(Uuid::new_v4());
+
+        if let Some(scope) = scope {
+            headers.insert(AUTHORIZATION, format!("Bearer TokenFor-{scope}").parse().unwrap());
+        }
+
+        let response = reqwest::Client::new()
+            .request(
+                method.clone(),
+                format!(
+                    "{}{}",
+                    env.get_connection_info().origin,
+                    sample_path(&path).trim_start_matches('/')
+                ),
+            )
+            .headers(headers)
+            .send()
+            .await
+            .unwrap();
+
+        let status = response.status();
+
+        // The event stream doesn't end, so only the status is checked.
+        let is_event_stream = response
+            .headers()
+            .get("content-type")
+            .is_some_and(|content_type| content_type == "text/event-stream");
+
+        let body = if is_event_stream {
+            String::new()
+        } else {
+            response.text().await.unwrap()
+        };
+
+        assert_ne!(status, 405, "{method} {path} is documented but the method is not allowed");
+        assert!(
+            !(status == 404 && body.is_empty()),
+            "{method} {path} is documented but there is no route"
+        );
+        assert_ne!(
+            status, 403,
+            "{method} {path} should be allowed with the {scope:?} scope: {body}"
+        );
+        assert!(
+            !(status == 500 && (body.contains("unauthorized") || body.contains("token not valid"))),
+            "{method} {path} should be authenticated with the {scope:?} scope token: {body}"
+        );
+
+        env.stop().await;
+    }
+}
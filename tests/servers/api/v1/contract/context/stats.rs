@@ -1,15 +1,22 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 
+use aquatic_udp_protocol::PeerId;
 use bittorrent_primitives::info_hash::InfoHash;
+use torrust_tracker_api_client::common::http::{Query, QueryParam};
 use torrust_tracker_api_client::v1::client::{headers_with_request_id, Client};
-use torrust_tracker_lib::servers::apis::v1::context::stats::resources::Stats;
+use torrust_tracker_lib::packages::tracker_api_core::statistics::history::Sample;
+use torrust_tracker_lib::servers::apis::v1::context::stats::resources::{
+    AsnStats, ClientStats, CountryStats, LocationStats, Stats, StatsHistory, StatsSample,
+};
 use torrust_tracker_primitives::peer::fixture::PeerBuilder;
 use torrust_tracker_test_helpers::configuration;
 use uuid::Uuid;
 
+use crate::common::fixtures::geoip_configuration;
 use crate::common::logging::{self, logs_contains_a_line_with};
 use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
-use crate::servers::api::v1::asserts::{assert_stats, assert_token_not_valid, assert_unauthorized};
+use crate::servers::api::v1::asserts::{assert_bad_request, assert_stats, assert_token_not_valid, assert_unauthorized};
 use crate::servers::api::Started;
 
 #[tokio::test]
@@ -36,6 +43,16 @@ async fn should_allow_getting_tracker_statistics() {
             seeders: 1,
             completed: 0,
             leechers: 0,
+            // Eviction
+            torrents_evicted: 0,
+            torrents_refused: 0,
+            peers_evicted: 0,
+            peers_refused: 0,
+            peers_per_ip_exceeded: 0,
+            // Announce interval
+            early_announces: 0,
+            announce_load_percent: 0,
+            announce_interval_avg: 0,
             // TCP
             tcp4_connections_handled: 0,
             tcp4_announces_handled: 0,
@@ -105,3 +122,283 @@ async fn should_not_allow_getting_tracker_statistics_for_unauthenticated_users()
 
     env.stop().await;
 }
+
+fn sample(timestamp: u64, announces: u64, torrents: u64) -> Sample {
+    Sample {
+        timestamp,
+        duration: 60,
+        requests: announces,
+        announces,
+        torrents,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn should_allow_getting_the_tracker_statistics_history() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    for (minute, announces) in [(1, 10), (2, 20), (3, 30)] {
+        env.http_api_container
+            .metrics_history
+            .push(sample(minute * 60, announces, minute));
+    }
+
+    let response = Client::new(env.get_connection_info())
+        .get_stats_history(
+            Query::params(
+                [
+                    QueryParam::new("from", "0"),
+                    QueryParam::new("to", "240"),
+                    QueryParam::new("step", "120"),
+                ]
+                .to_vec(),
+            ),
+            None,
+        )
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<StatsHistory>().await.unwrap(),
+        StatsHistory {
+            step: 120,
+            samples: vec![
+                StatsSample::from(Sample {
+                    duration: 120,
+                    ..sample(0, 10, 1)
+                }),
+                StatsSample::from(Sample {
+                    duration: 120,
+                    ..sample(120, 50, 3)
+                }),
+            ],
+        }
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_getting_the_tracker_statistics_history_when_the_range_is_empty() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    for params in [
+        [QueryParam::new("from", "240"), QueryParam::new("to", "60")].to_vec(),
+        [QueryParam::new("step", "0")].to_vec(),
+    ] {
+        let response = Client::new(env.get_connection_info())
+            .get_stats_history(Query::params(params), None)
+            .await;
+
+        assert_bad_request(
+            response,
+            "Invalid URL: the `from` param must be before the `to` param and the `step` must be positive",
+        )
+        .await;
+    }
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_getting_the_tracker_statistics_history_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(connection_with_no_token(env.get_connection_info().origin))
+        .get_stats_history(Query::default(), None)
+        .await;
+
+    assert_unauthorized(response).await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_getting_the_number_of_peers_by_client_software() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+    let other_info_hash = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(
+        &info_hash,
+        &PeerBuilder::default().with_peer_id(&PeerId(*b"-TR4040-xxxxxxxxxxxx")).into(),
+    );
+    env.add_torrent_peer(
+        &other_info_hash,
+        &PeerBuilder::default().with_peer_id(&PeerId(*b"-qB4630-xxxxxxxxxxxx")).into(),
+    );
+    env.add_torrent_peer(
+        &other_info_hash,
+        &PeerBuilder::default()
+            .with_peer_id(&PeerId(*b"-qB4630-yyyyyyyyyyyy"))
+            .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 2)), 8080))
+            .into(),
+    );
+
+    let response = Client::new(env.get_connection_info())
+        .get_clients_stats(Query::default(), None)
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<Vec<ClientStats>>().await.unwrap(),
+        vec![
+            client_stats("qBittorrent", "4.6.3", 2),
+            client_stats("Transmission", "4.0.4", 1)
+        ]
+    );
+
+    let response = Client::new(env.get_connection_info())
+        .get_clients_stats(
+            Query::params(
+                [
+                    QueryParam::new("info_hash", &info_hash.to_string()),
+                    QueryParam::new("format", "prometheus"),
+                ]
+                .to_vec(),
+            ),
+            None,
+        )
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.text().await.unwrap(),
+        format!("peer_clients{{client=\"Transmission\",version=\"4.0.4\",info_hash=\"{info_hash}\"}} 1")
+    );
+
+    env.stop().await;
+}
+
+fn client_stats(client: &str, version: &str, peers: u64) -> ClientStats {
+    ClientStats {
+        client: client.to_string(),
+        version: Some(version.to_string()),
+        peers,
+    }
+}
+
+#[tokio::test]
+async fn should_fail_getting_the_number_of_peers_by_client_software_for_an_invalid_infohash() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_clients_stats(Query::params([QueryParam::new("info_hash", "invalid")].to_vec()), None)
+        .await;
+
+    assert_bad_request(
+        response,
+        "Invalid URL: invalid infohash param: string \"invalid\", expected a 40 character long string",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_getting_the_number_of_peers_by_country_and_asn() {
+    logging::setup();
+
+    let mut configuration = configuration::ephemeral();
+    configuration.core.geoip = Some(geoip_configuration());
+
+    let env = Started::new(&configuration.into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(
+        &info_hash,
+        &PeerBuilder::default()
+            .with_peer_id(&PeerId(*b"-qB00000000000000001"))
+            .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(89, 160, 20, 112)), 8080))
+            .into(),
+    );
+    env.add_torrent_peer(
+        &info_hash,
+        &PeerBuilder::default()
+            .with_peer_id(&PeerId(*b"-qB00000000000000002"))
+            .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 8080))
+            .into(),
+    );
+
+    let response = Client::new(env.get_connection_info())
+        .get_locations_stats(Query::default(), None)
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<LocationStats>().await.unwrap(),
+        LocationStats {
+            countries: vec![
+                CountryStats { country: None, peers: 1 },
+                CountryStats {
+                    country: Some("SE".to_string()),
+                    peers: 1
+                }
+            ],
+            asns: vec![
+                AsnStats {
+                    asn: None,
+                    organization: None,
+                    peers: 1
+                },
+                AsnStats {
+                    asn: Some(29518),
+                    organization: Some("Bredband2 AB".to_string()),
+                    peers: 1
+                }
+            ],
+        }
+    );
+
+    let response = Client::new(env.get_connection_info())
+        .get_locations_stats(Query::params([QueryParam::new("format", "prometheus")].to_vec()), None)
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.text().await.unwrap(),
+        "peer_countries{country=\"SE\"} 1\npeer_asns{asn=\"29518\",organization=\"Bredband2 AB\"} 1"
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_return_no_locations_when_geoip_is_not_enabled() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    env.add_torrent_peer(
+        &InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(), // DevSkim: ignore DS173237
+        &PeerBuilder::default().into(),
+    );
+
+    let response = Client::new(env.get_connection_info())
+        .get_locations_stats(Query::default(), None)
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.json::<LocationStats>().await.unwrap(),
+        LocationStats {
+            countries: vec![],
+            asns: vec![]
+        }
+    );
+
+    env.stop().await;
+}
@@ -1,9 +1,10 @@
 use std::str::FromStr;
 
+use aquatic_udp_protocol::PeerId;
 use bittorrent_primitives::info_hash::InfoHash;
 use torrust_tracker_api_client::common::http::{Query, QueryParam};
-use torrust_tracker_api_client::v1::client::{headers_with_request_id, Client};
-use torrust_tracker_lib::servers::apis::v1::context::torrent::resources::peer::Peer;
+use torrust_tracker_api_client::v1::client::{headers_with_request_id, Client, TorrentListQuery};
+use torrust_tracker_lib::servers::apis::v1::context::torrent::resources::peer::peer_resource;
 use torrust_tracker_lib::servers::apis::v1::context::torrent::resources::torrent::{self, Torrent};
 use torrust_tracker_primitives::peer::fixture::PeerBuilder;
 use torrust_tracker_test_helpers::configuration;
@@ -12,8 +13,8 @@ use uuid::Uuid;
 use crate::common::logging::{self, logs_contains_a_line_with};
 use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
 use crate::servers::api::v1::asserts::{
-    assert_bad_request, assert_invalid_infohash_param, assert_not_found, assert_token_not_valid, assert_torrent_info,
-    assert_torrent_list, assert_torrent_not_known, assert_unauthorized,
+    assert_bad_request, assert_invalid_infohash_param, assert_not_found, assert_ok, assert_peer_not_known,
+    assert_token_not_valid, assert_torrent_info, assert_torrent_list, assert_torrent_not_known, assert_unauthorized,
 };
 use crate::servers::api::v1::contract::fixtures::{
     invalid_infohashes_returning_bad_request, invalid_infohashes_returning_not_found,
@@ -255,6 +256,130 @@ async fn should_fail_getting_torrents_when_the_info_hash_parameter_is_invalid()
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_allow_sorting_the_torrents() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+    let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::seeder().into());
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::leecher().into());
+    env.add_torrent_peer(&info_hash_2, &PeerBuilder::seeder().into());
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .search_torrents(
+            TorrentListQuery {
+                sort: Some("peers".to_string()),
+                order: Some("desc".to_string()),
+                ..Default::default()
+            },
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_torrent_list(
+        response,
+        vec![
+            torrent::ListItem {
+                info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(), // DevSkim: ignore DS173237
+                seeders: 1,
+                completed: 0,
+                leechers: 1,
+            },
+            torrent::ListItem {
+                info_hash: "0b3aea4adc213ce32295be85d3883a63bca25446".to_string(), // DevSkim: ignore DS173237
+                seeders: 1,
+                completed: 0,
+                leechers: 0,
+            },
+        ],
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_filtering_the_torrents() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+    let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(&info_hash_1, &PeerBuilder::leecher().into());
+    env.add_torrent_peer(&info_hash_2, &PeerBuilder::seeder().into());
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .search_torrents(
+            TorrentListQuery {
+                max_seeders: Some(0),
+                min_leechers: Some(1),
+                ..Default::default()
+            },
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_torrent_list(
+        response,
+        vec![torrent::ListItem {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(), // DevSkim: ignore DS173237
+            seeders: 0,
+            completed: 0,
+            leechers: 1,
+        }],
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_getting_torrents_when_the_sort_parameter_is_invalid() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrents(
+            Query::params([QueryParam::new("sort", "size")].to_vec()),
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_bad_request(
+        response,
+        "Invalid URL: invalid sort param \"size\", expected one of: seeders, leechers, completed, peers",
+    )
+    .await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrents(
+            Query::params([QueryParam::new("sort", "seeders"), QueryParam::new("order", "up")].to_vec()),
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_bad_request(
+        response,
+        "Invalid URL: invalid order param \"up\", expected one of: asc, desc",
+    )
+    .await;
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_not_allow_getting_torrents_for_unauthenticated_users() {
     logging::setup();
@@ -290,6 +415,190 @@ async fn should_not_allow_getting_torrents_for_unauthenticated_users() {
     env.stop().await;
 }
 
+#[tokio::test]
+async fn should_allow_exporting_the_torrents_as_ndjson() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash_1 = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+    let info_hash_2 = InfoHash::from_str("0b3aea4adc213ce32295be85d3883a63bca25446").unwrap(); // DevSkim: ignore DS173237
+
+    let peer = PeerBuilder::default().into();
+
+    env.add_torrent_peer(&info_hash_1, &peer);
+    env.add_torrent_peer(&info_hash_2, &peer);
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .export_torrents(
+            Query::params([QueryParam::new("peers", "true")].to_vec()),
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "application/x-ndjson");
+
+    let body = response.text().await.unwrap();
+
+    let torrents: Vec<Torrent> = body.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+
+    assert_eq!(
+        torrents,
+        vec![
+            Torrent {
+                info_hash: "0b3aea4adc213ce32295be85d3883a63bca25446".to_string(), // DevSkim: ignore DS173237
+                seeders: 1,
+                completed: 0,
+                leechers: 0,
+                peers: Some(vec![peer_resource(peer)]),
+            },
+            Torrent {
+                info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(), // DevSkim: ignore DS173237
+                seeders: 1,
+                completed: 0,
+                leechers: 0,
+                peers: Some(vec![peer_resource(peer)]),
+            },
+        ]
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_exporting_the_torrents_as_csv() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::leecher().into());
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .export_torrents(
+            Query::params([QueryParam::new("format", "csv")].to_vec()),
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-type").unwrap(), "text/csv");
+    assert_eq!(
+        response.text().await.unwrap(),
+        "info_hash,seeders,completed,leechers\n9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d,0,0,1\n" // DevSkim: ignore DS173237
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_exporting_a_snapshot_of_the_torrents() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::leecher().into());
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .export_torrents(
+            Query::params([QueryParam::new("format", "csv"), QueryParam::new("snapshot", "true")].to_vec()),
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_eq!(response.status(), 200);
+
+    let snapshot_time = response.headers().get("x-torrust-snapshot-time").unwrap().to_str().unwrap();
+
+    assert!(chrono::DateTime::parse_from_rfc3339(snapshot_time).is_ok());
+    assert_eq!(
+        response.text().await.unwrap(),
+        "info_hash,seeders,completed,leechers\n9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d,0,0,1\n" // DevSkim: ignore DS173237
+    );
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_exporting_a_snapshot_of_the_torrents_with_their_peers() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .export_torrents(
+            Query::params([QueryParam::new("snapshot", "true"), QueryParam::new("peers", "true")].to_vec()),
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_bad_request(
+        response,
+        "Invalid URL: the snapshot export does not include the peers, remove the peers param",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_exporting_the_torrents_when_the_format_is_invalid() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .export_torrents(
+            Query::params([QueryParam::new("format", "xml")].to_vec()),
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_bad_request(
+        response,
+        "Invalid URL: invalid format param \"xml\", expected one of: ndjson, csv",
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_exporting_the_torrents_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .export_torrents(Query::empty(), Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    assert!(
+        logs_contains_a_line_with(&["ERROR", "API", &format!("{request_id}")]),
+        "Expected logs to contain: ERROR ... API ... request_id={request_id}"
+    );
+
+    env.stop().await;
+}
+
 #[tokio::test]
 async fn should_allow_getting_a_torrent_info() {
     logging::setup();
@@ -315,7 +624,7 @@ async fn should_allow_getting_a_torrent_info() {
             seeders: 1,
             completed: 0,
             leechers: 0,
-            peers: Some(vec![Peer::from(peer)]),
+            peers: Some(vec![peer_resource(peer)]),
         },
     )
     .await;
@@ -408,3 +717,249 @@ async fn should_not_allow_getting_a_torrent_info_for_unauthenticated_users() {
 
     env.stop().await;
 }
+
+#[tokio::test]
+async fn should_allow_deleting_a_torrent() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into());
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .delete_torrent(&info_hash.to_string(), false, Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_ok(response).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrent(&info_hash.to_string(), None)
+        .await;
+
+    assert_torrent_not_known(response).await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_deleting_a_torrent_with_its_persisted_completed_counter() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    env.database.save_persistent_torrent(&info_hash, 1).unwrap();
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into());
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .delete_torrent(&info_hash.to_string(), true, Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_ok(response).await;
+
+    assert!(!env.database.load_persistent_torrents().unwrap().contains_key(&info_hash));
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_deleting_a_torrent_when_the_torrent_does_not_exist() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let request_id = Uuid::new_v4();
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    let response = Client::new(env.get_connection_info())
+        .delete_torrent(&info_hash.to_string(), false, Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_torrent_not_known(response).await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_deleting_a_torrent_when_the_provided_infohash_is_invalid() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    for invalid_infohash in &invalid_infohashes_returning_bad_request() {
+        let request_id = Uuid::new_v4();
+
+        let response = Client::new(env.get_connection_info())
+            .delete_torrent(invalid_infohash, false, Some(headers_with_request_id(request_id)))
+            .await;
+
+        assert_invalid_infohash_param(response, invalid_infohash).await;
+    }
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_deleting_a_torrent_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into());
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .delete_torrent(&info_hash.to_string(), false, Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_no_token(env.get_connection_info().origin))
+        .delete_torrent(&info_hash.to_string(), false, Some(headers_with_request_id(request_id)))
+        .await;
+
+    assert_unauthorized(response).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrent(&info_hash.to_string(), None)
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_ne!(response.text().await.unwrap(), "\"torrent not known\"");
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_allow_evicting_a_peer_from_a_torrent() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    let peer_to_evict = PeerBuilder::default().with_peer_id(&PeerId(*b"-qB00000000000000001")).build();
+    let peer_to_keep = PeerBuilder::default().with_peer_id(&PeerId(*b"-qB00000000000000002")).build();
+
+    env.add_torrent_peer(&info_hash, &peer_to_evict);
+    env.add_torrent_peer(&info_hash, &peer_to_keep);
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .evict_peer(
+            &info_hash.to_string(),
+            "0x2d71423030303030303030303030303030303031", // DevSkim: ignore DS173237
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_ok(response).await;
+
+    let response = Client::new(env.get_connection_info())
+        .get_torrent(&info_hash.to_string(), None)
+        .await;
+
+    assert_torrent_info(
+        response,
+        Torrent {
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(), // DevSkim: ignore DS173237
+            seeders: 1,
+            completed: 0,
+            leechers: 0,
+            peers: Some(vec![peer_resource(peer_to_keep)]),
+        },
+    )
+    .await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_evicting_a_peer_when_the_peer_does_not_exist() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    env.add_torrent_peer(&info_hash, &PeerBuilder::default().into());
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(env.get_connection_info())
+        .evict_peer(
+            &info_hash.to_string(),
+            "2d71423030303030303030303030303030303039", // DevSkim: ignore DS173237
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_peer_not_known(response).await;
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_fail_evicting_a_peer_when_the_peer_id_is_invalid() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    for invalid_peer_id in ["0x2d71", "-qB00000000000000001", "0xzz71423030303030303030303030303030303031"] {
+        let request_id = Uuid::new_v4();
+
+        let response = Client::new(env.get_connection_info())
+            .evict_peer(
+                &info_hash.to_string(),
+                invalid_peer_id,
+                Some(headers_with_request_id(request_id)),
+            )
+            .await;
+
+        assert_bad_request(
+            response,
+            &format!("Invalid URL: invalid peer id \"{invalid_peer_id}\", expected 40 hex characters"),
+        )
+        .await;
+    }
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_not_allow_evicting_a_peer_for_unauthenticated_users() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let info_hash = InfoHash::from_str("9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d").unwrap(); // DevSkim: ignore DS173237
+
+    let request_id = Uuid::new_v4();
+
+    let response = Client::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .evict_peer(
+            &info_hash.to_string(),
+            "0x2d71423030303030303030303030303030303031", // DevSkim: ignore DS173237
+            Some(headers_with_request_id(request_id)),
+        )
+        .await;
+
+    assert_token_not_valid(response).await;
+
+    env.stop().await;
+}
@@ -2,3 +2,4 @@ pub mod authentication;
 pub mod configuration;
 pub mod context;
 pub mod fixtures;
+pub mod typed_client;
@@ -0,0 +1,167 @@
+use std::str::FromStr;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use torrust_tracker_api_client::connection_info::ConnectionInfo;
+use torrust_tracker_api_client::v1::client::TorrentListQuery;
+use torrust_tracker_api_client::v1::typed_client::{ApiError, TypedClient};
+use torrust_tracker_configuration::Scope;
+use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+use torrust_tracker_test_helpers::configuration;
+
+use crate::common::logging;
+use crate::servers::api::connection_info::{connection_with_invalid_token, connection_with_no_token};
+use crate::servers::api::Started;
+
+const INFO_HASH: &str = "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d"; // DevSkim: ignore DS173237
+
+fn bearer_connection(env: &Started) -> ConnectionInfo {
+    let connection_info = env.get_connection_info();
+
+    ConnectionInfo::bearer(connection_info.origin, &connection_info.api_token.unwrap())
+}
+
+#[tokio::test]
+async fn should_return_the_tracker_statistics_using_a_bearer_token() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    env.add_torrent_peer(&InfoHash::from_str(INFO_HASH).unwrap(), &PeerBuilder::default().into());
+
+    let stats = TypedClient::new(bearer_connection(&env)).get_stats().await.unwrap();
+
+    assert_eq!(stats.torrents, 1);
+    assert_eq!(stats.seeders, 1);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_return_the_torrents() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    env.add_torrent_peer(&InfoHash::from_str(INFO_HASH).unwrap(), &PeerBuilder::default().into());
+
+    let client = TypedClient::new(env.get_connection_info());
+
+    let torrents = client.get_torrents(TorrentListQuery::default()).await.unwrap();
+
+    assert_eq!(torrents.len(), 1);
+    assert_eq!(torrents[0].info_hash, INFO_HASH);
+
+    let torrent = client.get_torrent(INFO_HASH).await.unwrap().unwrap();
+
+    assert_eq!(torrent.seeders, 1);
+    assert_eq!(torrent.peers.unwrap().len(), 1);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_return_none_when_the_torrent_is_not_known() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let torrent = TypedClient::new(env.get_connection_info())
+        .get_torrent(INFO_HASH)
+        .await
+        .unwrap();
+
+    assert_eq!(torrent, None);
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_return_a_not_found_error_when_deleting_a_torrent_that_is_not_known() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let result = TypedClient::new(env.get_connection_info())
+        .delete_torrent(INFO_HASH, false)
+        .await;
+
+    assert!(matches!(result, Err(ApiError::NotFound(_))), "{result:?}");
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_generate_auth_keys() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let client = TypedClient::new(env.get_connection_info());
+
+    let auth_key = client.generate_auth_key(60).await.unwrap();
+
+    assert!(auth_key.expiry_time.is_some());
+
+    client.delete_auth_key(&auth_key.key).await.unwrap();
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_return_a_bad_request_error_when_the_request_is_not_valid() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let result = TypedClient::new(env.get_connection_info())
+        .whitelist_a_torrent("INVALID INFO HASH")
+        .await;
+
+    assert!(matches!(result, Err(ApiError::BadRequest(_))), "{result:?}");
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_return_authentication_errors() {
+    logging::setup();
+
+    let env = Started::new(&configuration::ephemeral().into()).await;
+
+    let result = TypedClient::new(connection_with_no_token(env.get_connection_info().origin))
+        .get_stats()
+        .await;
+
+    assert!(matches!(result, Err(ApiError::Unauthorized)), "{result:?}");
+
+    let result = TypedClient::new(connection_with_invalid_token(env.get_connection_info().origin))
+        .get_stats()
+        .await;
+
+    assert!(matches!(result, Err(ApiError::TokenNotValid)), "{result:?}");
+
+    env.stop().await;
+}
+
+#[tokio::test]
+async fn should_return_a_forbidden_error_when_the_token_does_not_have_the_required_scope() {
+    logging::setup();
+
+    let mut configuration = configuration::ephemeral();
+
+    configuration
+        .http_api
+        .as_mut()
+        .unwrap()
+        .add_scoped_token("monitoring", "MyMonitoringToken", &[Scope::StatsRead]);
+
+    let env = Started::new(&configuration.into()).await;
+
+    let result = TypedClient::new(ConnectionInfo::bearer(env.get_connection_info().origin, "MyMonitoringToken"))
+        .reload_whitelist()
+        .await;
+
+    assert!(matches!(result, Err(ApiError::Forbidden(_))), "{result:?}");
+
+    env.stop().await;
+}
@@ -114,6 +114,38 @@ pub async fn assert_torrent_not_in_whitelist_error_response(response: Response)
     assert_bencoded_error(&response.text().await.unwrap(), "is not whitelisted", Location::caller());
 }
 
+pub async fn assert_country_not_allowed_error_response(response: Response) {
+    assert_eq!(response.status(), 200);
+
+    assert_bencoded_error(&response.text().await.unwrap(), "is not allowed", Location::caller());
+}
+
+pub async fn assert_peer_address_not_allowed_error_response(response: Response) {
+    assert_eq!(response.status(), 200);
+
+    assert_bencoded_error(&response.text().await.unwrap(), "is not allowed", Location::caller());
+}
+
+pub async fn assert_too_many_peers_for_address_error_response(response: Response) {
+    assert_eq!(response.status(), 200);
+
+    assert_bencoded_error(
+        &response.text().await.unwrap(),
+        "has too many peers in the torrent",
+        Location::caller(),
+    );
+}
+
+pub async fn assert_announced_too_early_error_response(response: Response) {
+    assert_eq!(response.status(), 200);
+
+    assert_bencoded_error(
+        &response.text().await.unwrap(),
+        "The peer announced too early",
+        Location::caller(),
+    );
+}
+
 pub async fn assert_could_not_find_remote_address_on_x_forwarded_for_header_error_response(response: Response) {
     assert_eq!(response.status(), 200);
 
@@ -60,8 +60,11 @@ impl Environment<Stopped> {
             announce_handler: app_container.announce_handler.clone(),
             scrape_handler: app_container.scrape_handler.clone(),
             whitelist_authorization: app_container.whitelist_authorization.clone(),
+            client_authorization: app_container.client_authorization.clone(),
             http_stats_event_sender: app_container.http_stats_event_sender.clone(),
             authentication_service: app_container.authentication_service.clone(),
+            full_scrape_config: app_container.full_scrape_config.clone(),
+            full_scrape_repository: app_container.full_scrape_repository.clone(),
         });
 
         Self {
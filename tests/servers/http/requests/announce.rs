@@ -126,11 +126,21 @@ impl QueryBuilder {
         self
     }
 
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.announce_query.port = port;
+        self
+    }
+
     pub fn without_compact(mut self) -> Self {
         self.announce_query.compact = None;
         self
     }
 
+    pub fn without_event(mut self) -> Self {
+        self.announce_query.event = None;
+        self
+    }
+
     pub fn query(self) -> Query {
         self.announce_query
     }
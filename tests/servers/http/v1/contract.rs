@@ -1625,3 +1625,479 @@ mod configured_as_private_and_whitelisted {
 
     mod receiving_an_scrape_request {}
 }
+
+mod configured_with_a_peer_address_policy {
+
+    mod and_receiving_an_announce_request {
+        use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+        use aquatic_udp_protocol::PeerId;
+        use torrust_tracker_configuration::{Configuration, PeerAddressAction, PeerAddressPolicy};
+        use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+        use torrust_tracker_test_helpers::configuration;
+
+        use crate::common::fixtures::random_info_hash;
+        use crate::common::logging;
+        use crate::servers::http::asserts::{assert_empty_announce_response, assert_peer_address_not_allowed_error_response};
+        use crate::servers::http::client::Client;
+        use crate::servers::http::requests::announce::QueryBuilder;
+        use crate::servers::http::Started;
+
+        fn configuration_with_peer_address_policy(policy: PeerAddressPolicy) -> Configuration {
+            let mut configuration = configuration::ephemeral_public();
+
+            configuration.core.peer_address_policy = policy;
+
+            configuration
+        }
+
+        #[tokio::test]
+        async fn should_not_return_the_peers_in_a_private_network_to_a_client_outside_that_network() {
+            logging::setup();
+
+            let env = Started::new(&configuration_with_peer_address_policy(PeerAddressPolicy::default()).into()).await;
+
+            let info_hash = random_info_hash();
+
+            let private_peer = PeerBuilder::default()
+                .with_peer_id(&PeerId(*b"-qB00000000000000001"))
+                .with_peer_addr(&SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)), 6881))
+                .build();
+
+            env.add_torrent_peer(&info_hash, &private_peer);
+
+            // The client is on the loopback network
+            let response = Client::new(*env.bind_address())
+                .announce(
+                    &QueryBuilder::default()
+                        .with_info_hash(&info_hash)
+                        .with_peer_id(&PeerId(*b"-qB00000000000000002"))
+                        .query(),
+                )
+                .await;
+
+            assert_empty_announce_response(response).await;
+
+            env.stop().await;
+        }
+
+        #[tokio::test]
+        async fn should_fail_if_the_peer_announces_the_port_0_and_the_policy_rejects_it() {
+            logging::setup();
+
+            let env = Started::new(
+                &configuration_with_peer_address_policy(PeerAddressPolicy {
+                    port_zero_peers: PeerAddressAction::Reject,
+                    ..PeerAddressPolicy::default()
+                })
+                .into(),
+            )
+            .await;
+
+            let info_hash = random_info_hash();
+
+            let response = Client::new(*env.bind_address())
+                .announce(&QueryBuilder::default().with_info_hash(&info_hash).with_port(0).query())
+                .await;
+
+            assert_peer_address_not_allowed_error_response(response).await;
+
+            assert!(env.in_memory_torrent_repository.get_torrent_peers(&info_hash).is_empty());
+
+            env.stop().await;
+        }
+    }
+}
+
+mod configured_with_a_limit_of_peers_per_ip {
+
+    mod and_receiving_an_announce_request {
+        use aquatic_udp_protocol::PeerId;
+        use torrust_tracker_configuration::{MemoryLimits, PeersPerIpPolicy};
+        use torrust_tracker_test_helpers::configuration;
+
+        use crate::common::fixtures::random_info_hash;
+        use crate::common::logging;
+        use crate::servers::http::asserts::assert_too_many_peers_for_address_error_response;
+        use crate::servers::http::client::Client;
+        use crate::servers::http::requests::announce::QueryBuilder;
+        use crate::servers::http::Started;
+
+        #[tokio::test]
+        async fn should_fail_if_the_ip_address_already_has_the_maximum_number_of_peers_in_the_torrent() {
+            logging::setup();
+
+            let mut configuration = configuration::ephemeral_public();
+
+            configuration.core.memory_limits = MemoryLimits {
+                max_peers_per_ip: Some(1),
+                peers_per_ip_policy: PeersPerIpPolicy::Reject,
+                ..MemoryLimits::default()
+            };
+
+            let env = Started::new(&configuration.into()).await;
+
+            let info_hash = random_info_hash();
+
+            let client = Client::new(*env.bind_address());
+
+            client
+                .announce(
+                    &QueryBuilder::default()
+                        .with_info_hash(&info_hash)
+                        .with_peer_id(&PeerId(*b"-qB00000000000000001"))
+                        .query(),
+                )
+                .await;
+
+            let response = client
+                .announce(
+                    &QueryBuilder::default()
+                        .with_info_hash(&info_hash)
+                        .with_peer_id(&PeerId(*b"-qB00000000000000002"))
+                        .query(),
+                )
+                .await;
+
+            assert_too_many_peers_for_address_error_response(response).await;
+
+            assert_eq!(env.in_memory_torrent_repository.get_torrent_peers(&info_hash).len(), 1);
+
+            env.stop().await;
+        }
+    }
+}
+
+mod configured_with_an_adaptive_interval {
+
+    mod and_receiving_an_announce_request {
+        use torrust_tracker_configuration::{AdaptiveInterval, Configuration};
+        use torrust_tracker_test_helpers::configuration;
+
+        use crate::common::fixtures::random_info_hash;
+        use crate::common::logging;
+        use crate::servers::http::asserts::{assert_announce_response, assert_compact_announce_response};
+        use crate::servers::http::client::Client;
+        use crate::servers::http::requests::announce::{Compact, QueryBuilder};
+        use crate::servers::http::responses::announce::{Announce, CompactPeerList};
+        use crate::servers::http::{responses, Started};
+
+        fn configuration_with_an_adaptive_interval() -> Configuration {
+            let mut configuration = configuration::ephemeral_public();
+
+            configuration.core.adaptive_interval = Some(AdaptiveInterval {
+                min_interval: 30,
+                ..AdaptiveInterval::default()
+            });
+
+            configuration
+        }
+
+        #[tokio::test]
+        async fn should_return_the_shortest_interval_to_the_first_peer_of_a_swarm() {
+            logging::setup();
+
+            let env = Started::new(&configuration_with_an_adaptive_interval().into()).await;
+
+            let response = Client::new(*env.bind_address())
+                .announce(&QueryBuilder::default().with_info_hash(&random_info_hash()).query())
+                .await;
+
+            assert_announce_response(
+                response,
+                &Announce {
+                    complete: 1, // the peer for this test
+                    incomplete: 0,
+                    interval: 30,
+                    min_interval: 30,
+                    peers: vec![],
+                },
+            )
+            .await;
+
+            env.stop().await;
+        }
+
+        #[tokio::test]
+        async fn should_return_the_shortest_interval_in_the_compact_response_to_the_first_peer_of_a_swarm() {
+            logging::setup();
+
+            let env = Started::new(&configuration_with_an_adaptive_interval().into()).await;
+
+            let response = Client::new(*env.bind_address())
+                .announce(
+                    &QueryBuilder::default()
+                        .with_info_hash(&random_info_hash())
+                        .with_compact(Compact::Accepted)
+                        .query(),
+                )
+                .await;
+
+            let expected_response = responses::announce::Compact {
+                complete: 1, // the peer for this test
+                incomplete: 0,
+                interval: 30,
+                min_interval: 30,
+                peers: CompactPeerList::new(vec![]),
+            };
+
+            assert_compact_announce_response(response, &expected_response).await;
+
+            env.stop().await;
+        }
+    }
+}
+
+mod configured_with_an_early_announce_policy {
+
+    mod and_receiving_an_announce_request {
+        use torrust_tracker_configuration::EarlyAnnouncePolicy;
+        use torrust_tracker_test_helpers::configuration;
+
+        use crate::common::fixtures::random_info_hash;
+        use crate::common::logging;
+        use crate::servers::http::asserts::{assert_announced_too_early_error_response, assert_is_announce_response};
+        use crate::servers::http::client::Client;
+        use crate::servers::http::requests::announce::QueryBuilder;
+        use crate::servers::http::Started;
+
+        #[tokio::test]
+        async fn should_fail_if_the_peer_announces_again_before_the_min_interval() {
+            logging::setup();
+
+            let mut configuration = configuration::ephemeral_public();
+
+            configuration.core.early_announce_policy = EarlyAnnouncePolicy::Reject;
+
+            let env = Started::new(&configuration.into()).await;
+
+            let info_hash = random_info_hash();
+
+            let client = Client::new(*env.bind_address());
+
+            let response = client
+                .announce(&QueryBuilder::default().with_info_hash(&info_hash).without_event().query())
+                .await;
+
+            assert_is_announce_response(response).await;
+
+            let response = client
+                .announce(&QueryBuilder::default().with_info_hash(&info_hash).without_event().query())
+                .await;
+
+            assert_announced_too_early_error_response(response).await;
+
+            assert_eq!(env.http_tracker_container.announce_handler.get_early_announces(), 1);
+
+            env.stop().await;
+        }
+    }
+}
+
+mod configured_with_a_geoip_country_policy {
+
+    mod and_receiving_an_announce_request {
+        use torrust_tracker_configuration::{Configuration, GeoIp};
+        use torrust_tracker_test_helpers::configuration;
+
+        use crate::common::fixtures::{geoip_configuration, random_info_hash};
+        use crate::common::logging;
+        use crate::servers::http::asserts::{assert_country_not_allowed_error_response, assert_is_announce_response};
+        use crate::servers::http::client::Client;
+        use crate::servers::http::requests::announce::QueryBuilder;
+        use crate::servers::http::Started;
+
+        fn configuration_with_geoip(geoip: GeoIp) -> Configuration {
+            let mut configuration = configuration::ephemeral_public();
+
+            configuration.core.geoip = Some(geoip);
+
+            configuration
+        }
+
+        #[tokio::test]
+        async fn should_fail_if_the_peer_is_not_in_an_allowed_country() {
+            logging::setup();
+
+            // The loopback peer address is not in the `GeoIP` database.
+            let env = Started::new(
+                &configuration_with_geoip(GeoIp {
+                    allowed_countries: vec!["SE".to_string()],
+                    ..geoip_configuration()
+                })
+                .into(),
+            )
+            .await;
+
+            let info_hash = random_info_hash();
+
+            let response = Client::new(*env.bind_address())
+                .announce(&QueryBuilder::default().with_info_hash(&info_hash).query())
+                .await;
+
+            assert_country_not_allowed_error_response(response).await;
+
+            assert!(env.in_memory_torrent_repository.get_torrent_peers(&info_hash).is_empty());
+
+            env.stop().await;
+        }
+
+        #[tokio::test]
+        async fn should_allow_announcing_when_no_country_is_denied_for_the_peer() {
+            logging::setup();
+
+            let env = Started::new(
+                &configuration_with_geoip(GeoIp {
+                    denied_countries: vec!["SE".to_string()],
+                    ..geoip_configuration()
+                })
+                .into(),
+            )
+            .await;
+
+            let response = Client::new(*env.bind_address())
+                .announce(&QueryBuilder::default().with_info_hash(&random_info_hash()).query())
+                .await;
+
+            assert_is_announce_response(response).await;
+
+            env.stop().await;
+        }
+    }
+}
+
+mod configured_with_a_full_scrape_dump {
+
+    use std::io::Read;
+    use std::str::FromStr;
+
+    use bittorrent_primitives::info_hash::InfoHash;
+    use flate2::read::GzDecoder;
+    use torrust_tracker_configuration::{Configuration, FullScrape};
+    use torrust_tracker_lib::packages::full_scrape::Dump;
+    use torrust_tracker_primitives::peer::fixture::PeerBuilder;
+    use torrust_tracker_test_helpers::configuration;
+
+    use crate::common::logging;
+    use crate::servers::http::client::Client;
+    use crate::servers::http::Started;
+
+    fn configuration_with_full_scrape_http_path() -> Configuration {
+        let mut configuration = configuration::ephemeral_public();
+
+        configuration.full_scrape = Some(FullScrape {
+            http_path: Some("/full_scrape".to_string()),
+            ..Default::default()
+        });
+
+        configuration
+    }
+
+    fn generate_dump(env: &Started) {
+        let dump = Dump::generate(&env.in_memory_torrent_repository).unwrap();
+
+        env.http_tracker_container.full_scrape_repository.update(dump);
+    }
+
+    #[tokio::test]
+    async fn should_return_the_gzipped_bencoded_full_scrape() {
+        logging::setup();
+
+        let env = Started::new(&configuration_with_full_scrape_http_path().into()).await;
+
+        let info_hash = InfoHash::from_str("9c38422213e30bff212b30c360d26f9a02136422").unwrap(); // DevSkim: ignore DS173237
+
+        env.add_torrent_peer(&info_hash, &PeerBuilder::seeder().build());
+
+        generate_dump(&env);
+
+        let response = Client::new(*env.bind_address()).get("full_scrape").await;
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers().get("content-type").unwrap(), "application/gzip");
+        assert_eq!(
+            response.headers().get("content-disposition").unwrap(),
+            "attachment; filename=\"full_scrape.bencode.gz\""
+        );
+        assert!(response.headers().get("content-encoding").is_none());
+        assert!(response.headers().get("etag").is_some());
+        assert!(response.headers().get("last-modified").is_some());
+
+        let mut body = Vec::new();
+        GzDecoder::new(response.bytes().await.unwrap().as_ref())
+            .read_to_end(&mut body)
+            .unwrap();
+
+        let mut expected_body = b"d5:filesd20:".to_vec();
+        expected_body.extend_from_slice(&info_hash.bytes());
+        expected_body.extend_from_slice(b"d8:completei1e10:downloadedi0e10:incompletei0eeee");
+
+        assert_eq!(body, expected_body);
+
+        env.stop().await;
+    }
+
+    #[tokio::test]
+    async fn should_return_not_modified_when_the_client_already_has_the_latest_dump() {
+        logging::setup();
+
+        let env = Started::new(&configuration_with_full_scrape_http_path().into()).await;
+
+        generate_dump(&env);
+
+        let client = Client::new(*env.bind_address());
+
+        let response = client.get("full_scrape?format=json").await;
+
+        let etag = response.headers().get("etag").unwrap().to_str().unwrap().to_string();
+        let last_modified = response.headers().get("last-modified").unwrap().to_str().unwrap().to_string();
+
+        let response = client
+            .get_with_header("full_scrape?format=json", "If-None-Match", &etag)
+            .await;
+
+        assert_eq!(response.status(), 304);
+
+        let response = client
+            .get_with_header("full_scrape?format=json", "If-Modified-Since", &last_modified)
+            .await;
+
+        assert_eq!(response.status(), 304);
+
+        let response = client
+            .get_with_header("full_scrape?format=json", "If-None-Match", "\"another-etag\"")
+            .await;
+
+        assert_eq!(response.status(), 200);
+
+        env.stop().await;
+    }
+
+    #[tokio::test]
+    async fn should_fail_when_the_dump_has_not_been_generated_yet() {
+        logging::setup();
+
+        let env = Started::new(&configuration_with_full_scrape_http_path().into()).await;
+
+        let response = Client::new(*env.bind_address()).get("full_scrape").await;
+
+        assert_eq!(response.status(), 503);
+
+        env.stop().await;
+    }
+
+    #[tokio::test]
+    async fn should_not_serve_the_dump_when_the_http_path_is_not_configured() {
+        logging::setup();
+
+        let env = Started::new(&configuration::ephemeral_public().into()).await;
+
+        generate_dump(&env);
+
+        let response = Client::new(*env.bind_address()).get("full_scrape").await;
+
+        assert_eq!(response.status(), 404);
+
+        env.stop().await;
+    }
+}